@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 use fvm_ipld_encoding::ipld_block::IpldBlock;
-use fvm_shared::address::Protocol;
+use fvm_shared::address::{Address, Protocol};
 use fvm_shared::crypto::signature::SignatureType::{Secp256k1, BLS};
 use fvm_shared::crypto::signature::{Signature, SignatureType};
 use fvm_shared::error::ExitCode;
@@ -13,9 +13,11 @@ use fil_actors_runtime::builtin::singletons::SYSTEM_ACTOR_ADDR;
 use fil_actors_runtime::runtime::{ActorCode, Runtime};
 use fil_actors_runtime::{actor_dispatch, ActorDowncast, FIRST_EXPORTED_METHOD_NUMBER};
 use fil_actors_runtime::{actor_error, ActorError};
-use types::{AuthenticateMessageReturn, ConstructorParams, PubkeyAddressReturn};
+use types::{
+    AuthenticateMessageReturn, AuthenticateMessagesReturn, ConstructorParams, PubkeyAddressReturn,
+};
 
-use crate::types::AuthenticateMessageParams;
+use crate::types::{AuthenticateMessageParams, AuthenticateMessagesParams};
 
 pub use self::state::State;
 
@@ -35,6 +37,7 @@ pub enum Method {
     // Deprecated in v10
     // AuthenticateMessage = 3,
     AuthenticateMessageExported = frc42_dispatch::method_hash!("AuthenticateMessage"),
+    AuthenticateMessagesExported = frc42_dispatch::method_hash!("AuthenticateMessages"),
 }
 
 /// Account Actor
@@ -73,14 +76,7 @@ impl Actor {
         rt.validate_immediate_caller_accept_any()?;
         let st: State = rt.state()?;
         let address = st.address;
-        let sig_type: SignatureType = match address.protocol() {
-            Protocol::Secp256k1 => Secp256k1,
-            Protocol::BLS => BLS,
-            protocol => {
-                return Err(actor_error!(illegal_state;
-                    "account address must use BLS or SECP protocol, got {}", protocol));
-            }
-        };
+        let sig_type = signature_type(&address)?;
         let sig = Signature { sig_type, bytes: params.signature };
         rt.verify_signature(&sig, &address, &params.message).map_err(|e| {
             e.downcast_default(
@@ -92,6 +88,33 @@ impl Actor {
         Ok(AuthenticateMessageReturn { authenticated: true })
     }
 
+    /// Authenticates whether each of the provided signatures is valid for the corresponding message,
+    /// as for `AuthenticateMessage`, but in a single call.
+    /// Errors with USR_ILLEGAL_ARGUMENT if any authentication is invalid.
+    pub fn authenticate_messages(
+        rt: &impl Runtime,
+        params: AuthenticateMessagesParams,
+    ) -> Result<AuthenticateMessagesReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        if params.messages.is_empty() {
+            return Err(actor_error!(illegal_argument; "no messages to authenticate"));
+        }
+        let st: State = rt.state()?;
+        let address = st.address;
+        let sig_type = signature_type(&address)?;
+        for (i, params) in params.messages.into_iter().enumerate() {
+            let sig = Signature { sig_type, bytes: params.signature };
+            rt.verify_signature(&sig, &address, &params.message).map_err(|e| {
+                e.downcast_default(
+                    ExitCode::USR_ILLEGAL_ARGUMENT,
+                    format!("failed to authenticate message {}, signature invalid", i),
+                )
+            })?;
+        }
+
+        Ok(AuthenticateMessagesReturn { authenticated: true })
+    }
+
     /// Fallback method for unimplemented method numbers.
     pub fn fallback(
         rt: &impl Runtime,
@@ -107,6 +130,15 @@ impl Actor {
     }
 }
 
+fn signature_type(address: &Address) -> Result<SignatureType, ActorError> {
+    match address.protocol() {
+        Protocol::Secp256k1 => Ok(Secp256k1),
+        Protocol::BLS => Ok(BLS),
+        protocol => Err(actor_error!(illegal_state;
+            "account address must use BLS or SECP protocol, got {}", protocol)),
+    }
+}
+
 impl ActorCode for Actor {
     type Methods = Method;
 
@@ -114,10 +146,16 @@ impl ActorCode for Actor {
         "Account"
     }
 
+    fil_actors_runtime::actor_info! {
+        interface_revisions: [1],
+        features: [],
+    }
+
     actor_dispatch! {
         Constructor => constructor,
         PubkeyAddress => pubkey_address,
         AuthenticateMessageExported => authenticate_message,
+        AuthenticateMessagesExported => authenticate_messages,
         _ => fallback,
     }
 }
@@ -14,7 +14,7 @@ pub struct PubkeyAddressReturn {
     pub address: Address,
 }
 
-#[derive(Debug, Serialize_tuple, Deserialize_tuple)]
+#[derive(Clone, Debug, Serialize_tuple, Deserialize_tuple)]
 pub struct AuthenticateMessageParams {
     #[serde(with = "strict_bytes")]
     pub signature: Vec<u8>,
@@ -27,3 +27,15 @@ pub struct AuthenticateMessageParams {
 pub struct AuthenticateMessageReturn {
     pub authenticated: bool,
 }
+
+#[derive(Debug, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct AuthenticateMessagesParams {
+    pub messages: Vec<AuthenticateMessageParams>,
+}
+
+#[derive(Debug, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct AuthenticateMessagesReturn {
+    pub authenticated: bool,
+}
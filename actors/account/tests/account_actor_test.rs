@@ -10,9 +10,10 @@ use fvm_shared::crypto::signature::Signature;
 use fvm_shared::error::ExitCode;
 use fvm_shared::MethodNum;
 
-use fil_actor_account::types::AuthenticateMessageParams;
+use fil_actor_account::types::{AuthenticateMessageParams, AuthenticateMessagesParams};
 use fil_actor_account::{testing::check_state_invariants, Actor as AccountActor, Method, State};
 use fil_actors_runtime::builtin::SYSTEM_ACTOR_ADDR;
+use fil_actors_runtime::runtime::{runtime_features, ActorInfo, GET_ACTOR_INFO_METHOD};
 use fil_actors_runtime::test_utils::*;
 use fil_actors_runtime::FIRST_EXPORTED_METHOD_NUMBER;
 
@@ -159,6 +160,89 @@ fn authenticate_message() {
         .unwrap());
 }
 
+#[test]
+fn authenticate_messages_secp() {
+    let addr = Address::new_secp256k1(&[2; fvm_shared::address::SECP_PUB_LEN]).unwrap();
+    let rt = construct(addr);
+
+    let messages: Vec<_> = (0..2u8)
+        .map(|i| AuthenticateMessageParams { signature: vec![i], message: vec![i, i] })
+        .collect();
+    let expect_verify = |i: u8, result| {
+        rt.expect_verify_signature(ExpectedVerifySig {
+            sig: Signature::new_secp256k1(vec![i]),
+            signer: addr,
+            plaintext: vec![i, i],
+            result,
+        })
+    };
+    let params = IpldBlock::serialize_cbor(&AuthenticateMessagesParams { messages }).unwrap();
+
+    // Valid signatures
+    rt.expect_validate_caller_any();
+    expect_verify(0, Ok(()));
+    expect_verify(1, Ok(()));
+    assert!(rt
+        .call::<AccountActor>(Method::AuthenticateMessagesExported as MethodNum, params.clone())
+        .unwrap()
+        .unwrap()
+        .deserialize::<bool>()
+        .unwrap());
+    rt.verify();
+
+    // Any invalid signature fails the batch
+    rt.expect_validate_caller_any();
+    expect_verify(0, Ok(()));
+    expect_verify(1, Err(anyhow!("bad signature")));
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "failed to authenticate message 1",
+        rt.call::<AccountActor>(Method::AuthenticateMessagesExported as MethodNum, params),
+    );
+    rt.verify();
+
+    // Nothing to authenticate
+    rt.expect_validate_caller_any();
+    expect_abort(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        rt.call::<AccountActor>(
+            Method::AuthenticateMessagesExported as MethodNum,
+            IpldBlock::serialize_cbor(&AuthenticateMessagesParams { messages: vec![] }).unwrap(),
+        ),
+    );
+    rt.verify();
+}
+
+#[test]
+fn authenticate_messages_bls() {
+    let addr = Address::new_bls(&[1; fvm_shared::address::BLS_PUB_LEN]).unwrap();
+    let rt = construct(addr);
+
+    // Each signature is verified by the runtime as a BLS signature.
+    let messages: Vec<_> = (0..2u8)
+        .map(|i| AuthenticateMessageParams { signature: vec![i; 96], message: vec![i, i] })
+        .collect();
+    rt.expect_validate_caller_any();
+    for i in 0..2u8 {
+        rt.expect_verify_signature(ExpectedVerifySig {
+            sig: Signature::new_bls(vec![i; 96]),
+            signer: addr,
+            plaintext: vec![i, i],
+            result: Ok(()),
+        });
+    }
+    assert!(rt
+        .call::<AccountActor>(
+            Method::AuthenticateMessagesExported as MethodNum,
+            IpldBlock::serialize_cbor(&AuthenticateMessagesParams { messages }).unwrap(),
+        )
+        .unwrap()
+        .unwrap()
+        .deserialize::<bool>()
+        .unwrap());
+    rt.verify();
+}
+
 #[test]
 fn test_fallback() {
     let rt = MockRuntime { receiver: Address::new_id(100), ..Default::default() };
@@ -195,6 +279,44 @@ fn test_fallback() {
     rt.verify();
 }
 
+#[test]
+fn get_actor_info() {
+    let addr = Address::new_secp256k1(&[2; fvm_shared::address::SECP_PUB_LEN]).unwrap();
+    let rt = construct(addr);
+
+    rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(1000));
+    rt.expect_validate_caller_any();
+    let info: ActorInfo = rt
+        .call::<AccountActor>(GET_ACTOR_INFO_METHOD, None)
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    assert_eq!(vec![1], info.interface_revisions);
+    assert_eq!(runtime_features().into_iter().map(String::from).collect::<Vec<_>>(), info.features);
+
+    // The method takes no parameters.
+    rt.expect_validate_caller_any();
+    expect_abort(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        rt.call::<AccountActor>(GET_ACTOR_INFO_METHOD, IpldBlock::serialize_cbor(&1u8).unwrap()),
+    );
+}
+
+fn construct(addr: Address) -> MockRuntime {
+    let rt = MockRuntime { receiver: Address::new_id(100), ..Default::default() };
+    rt.set_caller(*SYSTEM_ACTOR_CODE_ID, SYSTEM_ACTOR_ADDR);
+    rt.expect_validate_caller_addr(vec![SYSTEM_ACTOR_ADDR]);
+    rt.call::<AccountActor>(
+        Method::Constructor as MethodNum,
+        IpldBlock::serialize_cbor(&addr).unwrap(),
+    )
+    .unwrap();
+    rt.verify();
+    rt
+}
+
 fn check_state(rt: &MockRuntime) {
     let test_address = Address::new_id(1000);
     let (_, acc) = check_state_invariants(&rt.get_state(), &test_address);
@@ -0,0 +1,31 @@
+use fil_actors_runtime::runtime::Runtime;
+use fil_actors_runtime::{ActorError, EventBuilder};
+
+use crate::Entry;
+
+/// Indicates a disabled entry was skipped during epoch tick.
+pub fn entry_skipped(rt: &impl Runtime, entry: &Entry) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("cron-entry-skipped")
+            .field_indexed("receiver", &entry.receiver)
+            .field_indexed("method", &entry.method_num)
+            .build()?,
+    )
+}
+
+/// Indicates an entry ran out of gas before completing.
+pub fn entry_out_of_gas(
+    rt: &impl Runtime,
+    entry: &Entry,
+    gas_limit: u64,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("cron-entry-out-of-gas")
+            .field_indexed("receiver", &entry.receiver)
+            .field_indexed("method", &entry.method_num)
+            .field("gas-limit", &gas_limit)
+            .build()?,
+    )
+}
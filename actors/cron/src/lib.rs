@@ -1,20 +1,26 @@
 // Copyright 2019-2022 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+#[cfg(feature = "storage-rent")]
+use fil_actors_runtime::runtime::builtins::Type;
 use fil_actors_runtime::runtime::{ActorCode, Runtime};
 use fil_actors_runtime::{
     actor_dispatch, actor_error, extract_send_result, ActorError, SYSTEM_ACTOR_ADDR,
 };
 
 use fvm_ipld_encoding::tuple::*;
+use fvm_shared::address::Address;
 use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
+use fvm_shared::sys::SendFlags;
 
-use fvm_shared::METHOD_CONSTRUCTOR;
+use fvm_shared::{MethodNum, METHOD_CONSTRUCTOR};
 use num_derive::FromPrimitive;
 use num_traits::Zero;
 
-pub use self::state::{Entry, State};
+pub use self::state::{Entry, RegisteredMap, State, REGISTERED_CONFIG};
 
+mod emit;
 mod state;
 pub mod testing;
 
@@ -29,8 +35,21 @@ fil_actors_runtime::wasm_trampoline!(Actor);
 pub enum Method {
     Constructor = METHOD_CONSTRUCTOR,
     EpochTick = 2,
+    SetEntryEnabled = 3,
+    RegisterEntry = 4,
+    DeregisterEntry = 5,
 }
 
+/// The number of registered actors called each epoch, in rotation.
+/// Actors register only with the experimental `storage-rent` feature.
+pub const REGISTERED_CALLS_PER_EPOCH: usize = 20;
+
+/// The gas limit of each call to a registered actor.
+pub const REGISTERED_CALL_GAS_LIMIT: u64 = 10_000_000;
+
+/// The maximum number of actors registered at once.
+pub const MAX_REGISTERED: u64 = 10_000;
+
 /// Constructor parameters for Cron actor, contains entries
 /// of actors and methods to call on each epoch
 #[derive(Default, Debug, Serialize_tuple, Deserialize_tuple)]
@@ -39,6 +58,22 @@ pub struct ConstructorParams {
     pub entries: Vec<Entry>,
 }
 
+/// Parameters for enabling or disabling the entry calling a method on a receiver.
+#[derive(Debug, Serialize_tuple, Deserialize_tuple)]
+pub struct SetEntryEnabledParams {
+    pub receiver: Address,
+    pub method_num: MethodNum,
+    pub enabled: bool,
+}
+
+/// Parameters for registering the caller to be called in rotation during EpochTick.
+#[derive(Debug, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct RegisterEntryParams {
+    /// The method to call on the caller (must accept empty parameters)
+    pub method_num: MethodNum,
+}
+
 /// Cron actor
 pub struct Actor;
 
@@ -46,7 +81,7 @@ impl Actor {
     /// Constructor for Cron actor
     fn constructor(rt: &impl Runtime, params: ConstructorParams) -> Result<(), ActorError> {
         rt.validate_immediate_caller_is(std::iter::once(&SYSTEM_ACTOR_ADDR))?;
-        rt.create(&State { entries: params.entries })?;
+        rt.create(&State::new(rt.store(), params.entries)?)?;
         Ok(())
     }
     /// Executes built-in periodic actions, run at every Epoch.
@@ -56,13 +91,21 @@ impl Actor {
         rt.validate_immediate_caller_is(std::iter::once(&SYSTEM_ACTOR_ADDR))?;
 
         let st: State = rt.state()?;
-        for entry in st.entries {
+        for entry in st.entries_by_priority() {
+            if entry.disabled {
+                if let Err(e) = emit::entry_skipped(rt, entry) {
+                    log::error!("cron failed to emit skipped event for {}: {}", entry.receiver, e);
+                }
+                continue;
+            }
             // Intentionally ignore any error when calling cron methods
-            let res = extract_send_result(rt.send_simple(
+            let res = extract_send_result(rt.send(
                 &entry.receiver,
                 entry.method_num,
                 None,
                 TokenAmount::zero(),
+                entry.gas_limit,
+                SendFlags::empty(),
             ));
             if let Err(e) = res {
                 log::error!(
@@ -70,10 +113,124 @@ impl Actor {
                     entry.receiver,
                     e
                 );
+                if let (ExitCode::SYS_OUT_OF_GAS, Some(gas_limit)) =
+                    (e.exit_code(), entry.gas_limit)
+                {
+                    if let Err(e) = emit::entry_out_of_gas(rt, entry, gas_limit) {
+                        log::error!(
+                            "cron failed to emit out of gas event for {}: {}",
+                            entry.receiver,
+                            e
+                        );
+                    }
+                }
             }
         }
+        #[cfg(feature = "storage-rent")]
+        Self::call_registered(rt)?;
         Ok(())
     }
+
+    /// Calls the next registered actors in rotation, resuming after those called last epoch.
+    #[cfg(feature = "storage-rent")]
+    fn call_registered(rt: &impl Runtime) -> Result<(), ActorError> {
+        let mut calls = Vec::new();
+        rt.transaction(|st: &mut State, rt| {
+            let mut registered = st.load_registered(rt.store())?;
+            let (_, next) = registered.for_each_ranged(
+                st.registered_cursor.as_ref(),
+                Some(REGISTERED_CALLS_PER_EPOCH),
+                |receiver, method_num| {
+                    calls.push((receiver, *method_num));
+                    Ok(())
+                },
+            )?;
+            st.registered_cursor = next;
+            Ok(())
+        })?;
+        for (receiver, method_num) in calls {
+            // As for entries, errors are ignored.
+            let res = extract_send_result(rt.send(
+                &receiver,
+                method_num,
+                None,
+                TokenAmount::zero(),
+                Some(REGISTERED_CALL_GAS_LIMIT),
+                SendFlags::empty(),
+            ));
+            if let Err(e) = res {
+                log::error!(
+                    "cron failed to send registered call to {}, send error code {}",
+                    receiver,
+                    e
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Registers the calling EVM contract to be called with a method in rotation during
+    /// EpochTick, replacing any method it registered before.
+    #[cfg(feature = "storage-rent")]
+    fn register_entry(rt: &impl Runtime, params: RegisterEntryParams) -> Result<(), ActorError> {
+        rt.validate_immediate_caller_type(std::iter::once(&Type::EVM))?;
+        if params.method_num == 0 {
+            return Err(actor_error!(illegal_argument, "cannot register method 0"));
+        }
+        let receiver = rt.message().caller();
+        rt.transaction(|st: &mut State, rt| {
+            let mut registered = st.load_registered(rt.store())?;
+            if registered.set(&receiver, params.method_num)?.is_none() {
+                if st.registered_count >= MAX_REGISTERED {
+                    return Err(actor_error!(
+                        forbidden,
+                        "cannot register {}: {} actors already registered",
+                        receiver,
+                        st.registered_count
+                    ));
+                }
+                st.registered_count += 1;
+            }
+            st.save_registered(&mut registered)
+        })
+    }
+
+    /// Deregisters the calling EVM contract, if registered.
+    #[cfg(feature = "storage-rent")]
+    fn deregister_entry(rt: &impl Runtime) -> Result<(), ActorError> {
+        rt.validate_immediate_caller_type(std::iter::once(&Type::EVM))?;
+        let receiver = rt.message().caller();
+        rt.transaction(|st: &mut State, rt| {
+            let mut registered = st.load_registered(rt.store())?;
+            if registered.delete(&receiver)?.is_some() {
+                st.registered_count -= 1;
+            }
+            st.save_registered(&mut registered)
+        })
+    }
+
+    /// Disables or re-enables the entry calling a method on a receiver.
+    /// Disabled entries remain in state but are skipped during EpochTick.
+    fn set_entry_enabled(
+        rt: &impl Runtime,
+        params: SetEntryEnabledParams,
+    ) -> Result<(), ActorError> {
+        rt.validate_immediate_caller_is(std::iter::once(&SYSTEM_ACTOR_ADDR))?;
+
+        rt.transaction(|st: &mut State, _| {
+            let entry =
+                st.find_entry_mut(&params.receiver, params.method_num).ok_or_else(|| {
+                    actor_error!(
+                        not_found,
+                        "no cron entry for method {} on {}",
+                        params.method_num,
+                        params.receiver
+                    )
+                })?;
+            entry.disabled = !params.enabled;
+            Ok(())
+        })
+    }
 }
 
 impl ActorCode for Actor {
@@ -83,8 +240,18 @@ impl ActorCode for Actor {
         "Cron"
     }
 
+    fil_actors_runtime::actor_info! {
+        interface_revisions: [1],
+        features: [],
+    }
+
     actor_dispatch! {
         Constructor => constructor,
         EpochTick => epoch_tick,
+        SetEntryEnabled => set_entry_enabled,
+        #[cfg(feature = "storage-rent")]
+        RegisterEntry => register_entry,
+        #[cfg(feature = "storage-rent")]
+        DeregisterEntry => deregister_entry,
     }
 }
@@ -1,15 +1,68 @@
 // Copyright 2019-2022 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use cid::Cid;
+use fil_actors_runtime::{ActorError, Config, Map2, DEFAULT_HAMT_CONFIG};
+use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::tuple::*;
 use fvm_shared::address::Address;
 use fvm_shared::MethodNum;
 
+pub type RegisteredMap<BS> = Map2<BS, Address, MethodNum>;
+pub const REGISTERED_CONFIG: Config = DEFAULT_HAMT_CONFIG;
+
 /// Cron actor state which holds entries to call during epoch tick
-#[derive(Default, Serialize_tuple, Deserialize_tuple, Clone, Debug)]
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug)]
 pub struct State {
     /// Entries is a set of actors (and corresponding methods) to call during EpochTick.
     pub entries: Vec<Entry>,
+    /// Actors that registered themselves to be called in rotation, a bounded number each
+    /// EpochTick, with the method each registered.
+    pub registered: Cid, // HAMT[Address]MethodNum
+    /// The number of registered actors.
+    pub registered_count: u64,
+    /// The registered actor at which the next EpochTick's calls resume, or None to start from
+    /// the first.
+    pub registered_cursor: Option<Address>,
+}
+
+impl State {
+    pub fn new<BS: Blockstore>(store: &BS, entries: Vec<Entry>) -> Result<Self, ActorError> {
+        let registered = RegisteredMap::flush_empty(store, REGISTERED_CONFIG)?;
+        Ok(Self { entries, registered, registered_count: 0, registered_cursor: None })
+    }
+
+    pub fn load_registered<BS: Blockstore>(
+        &self,
+        store: BS,
+    ) -> Result<RegisteredMap<BS>, ActorError> {
+        RegisteredMap::load(store, &self.registered, REGISTERED_CONFIG, "registered")
+    }
+
+    pub fn save_registered<BS: Blockstore>(
+        &mut self,
+        registered: &mut RegisteredMap<BS>,
+    ) -> Result<(), ActorError> {
+        self.registered = registered.flush()?;
+        Ok(())
+    }
+
+    /// Returns the entries in the order in which they are called during EpochTick.
+    pub fn entries_by_priority(&self) -> Vec<&Entry> {
+        let mut entries: Vec<&Entry> = self.entries.iter().collect();
+        // Sort is stable, preserving the configured order of entries with equal priority.
+        entries.sort_by(|a, b| b.priority.cmp(&a.priority));
+        entries
+    }
+
+    /// Returns a mutable reference to the entry calling the method on the receiver, if any.
+    pub fn find_entry_mut(
+        &mut self,
+        receiver: &Address,
+        method_num: MethodNum,
+    ) -> Option<&mut Entry> {
+        self.entries.iter_mut().find(|e| &e.receiver == receiver && e.method_num == method_num)
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Serialize_tuple, Deserialize_tuple)]
@@ -18,4 +71,11 @@ pub struct Entry {
     pub receiver: Address,
     /// The method number to call (must accept empty parameters)
     pub method_num: MethodNum,
+    /// Entries with a higher priority are called first.
+    /// Entries with equal priority are called in the order they were configured.
+    pub priority: u64,
+    /// The maximum gas the call may consume, or unbounded if none
+    pub gas_limit: Option<u64>,
+    /// Disabled entries are skipped during EpochTick
+    pub disabled: bool,
 }
@@ -1,7 +1,7 @@
 use fil_actors_runtime::MessageAccumulator;
 use fvm_shared::address::Protocol;
 
-use crate::State;
+use crate::{State, MAX_REGISTERED};
 
 pub struct StateSummary {
     pub entry_count: usize,
@@ -19,7 +19,12 @@ pub fn check_state_invariants(state: &State) -> (StateSummary, MessageAccumulato
             entry.method_num > 0,
             format!("entry {i} has invalid method number {}", entry.method_num),
         );
+        acc.require(entry.gas_limit != Some(0), format!("entry {i} has zero gas limit"));
     });
+    acc.require(
+        state.registered_count <= MAX_REGISTERED,
+        format!("{} actors registered, more than {MAX_REGISTERED}", state.registered_count),
+    );
 
     (StateSummary { entry_count: state.entries.len() }, acc)
 }
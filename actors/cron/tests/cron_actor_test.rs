@@ -4,13 +4,17 @@
 use std::cell::RefCell;
 
 use fil_actor_cron::testing::check_state_invariants;
-use fil_actor_cron::{Actor as CronActor, ConstructorParams, Entry, State};
+use fil_actor_cron::{
+    Actor as CronActor, ConstructorParams, Entry, Method, SetEntryEnabledParams, State,
+};
 use fil_actors_runtime::test_utils::*;
-use fil_actors_runtime::SYSTEM_ACTOR_ADDR;
+use fil_actors_runtime::{EventBuilder, SYSTEM_ACTOR_ADDR};
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_shared::address::Address;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
+use fvm_shared::sys::SendFlags;
+use fvm_shared::MethodNum;
 use num_traits::Zero;
 
 fn check_state(rt: &MockRuntime) {
@@ -42,10 +46,10 @@ fn construct_with_empty_entries() {
 fn construct_with_entries() {
     let rt = construct_runtime();
 
-    let entry1 = Entry { receiver: Address::new_id(1001), method_num: 1001 };
-    let entry2 = Entry { receiver: Address::new_id(1002), method_num: 1002 };
-    let entry3 = Entry { receiver: Address::new_id(1003), method_num: 1003 };
-    let entry4 = Entry { receiver: Address::new_id(1004), method_num: 1004 };
+    let entry1 = new_entry(1001, 1001);
+    let entry2 = new_entry(1002, 1002);
+    let entry3 = new_entry(1003, 1003);
+    let entry4 = new_entry(1004, 1004);
 
     let params = ConstructorParams { entries: vec![entry1, entry2, entry3, entry4] };
 
@@ -69,10 +73,10 @@ fn epoch_tick_with_empty_entries() {
 fn epoch_tick_with_entries() {
     let rt = construct_runtime();
 
-    let entry1 = Entry { receiver: Address::new_id(1001), method_num: 1001 };
-    let entry2 = Entry { receiver: Address::new_id(1002), method_num: 1002 };
-    let entry3 = Entry { receiver: Address::new_id(1003), method_num: 1003 };
-    let entry4 = Entry { receiver: Address::new_id(1004), method_num: 1004 };
+    let entry1 = new_entry(1001, 1001);
+    let entry2 = new_entry(1002, 1002);
+    let entry3 = new_entry(1003, 1003);
+    let entry4 = new_entry(1004, 1004);
 
     let params = ConstructorParams {
         entries: vec![entry1.clone(), entry2.clone(), entry3.clone(), entry4.clone()],
@@ -117,6 +121,210 @@ fn epoch_tick_with_entries() {
     epoch_tick_and_verify(&rt);
 }
 
+#[test]
+fn epoch_tick_calls_entries_by_priority() {
+    let rt = construct_runtime();
+
+    let entry1 = new_entry(1001, 1001);
+    let entry2 = Entry { priority: 2, ..new_entry(1002, 1002) };
+    let entry3 = Entry { priority: 1, ..new_entry(1003, 1003) };
+    let entry4 = Entry { priority: 2, ..new_entry(1004, 1004) };
+
+    let params = ConstructorParams {
+        entries: vec![entry1.clone(), entry2.clone(), entry3.clone(), entry4.clone()],
+    };
+    construct_and_verify(&rt, &params);
+
+    // Higher priorities first, ties in configured order.
+    for entry in [&entry2, &entry4, &entry3, &entry1] {
+        rt.expect_send_simple(
+            entry.receiver,
+            entry.method_num,
+            None,
+            TokenAmount::zero(),
+            None,
+            ExitCode::OK,
+        );
+    }
+
+    epoch_tick_and_verify(&rt);
+}
+
+#[test]
+fn epoch_tick_limits_entry_gas() {
+    let rt = construct_runtime();
+
+    let entry1 = Entry { gas_limit: Some(1_000_000), ..new_entry(1001, 1001) };
+    let entry2 = new_entry(1002, 1002);
+
+    let params = ConstructorParams { entries: vec![entry1.clone(), entry2.clone()] };
+    construct_and_verify(&rt, &params);
+
+    rt.expect_send(
+        entry1.receiver,
+        entry1.method_num,
+        None,
+        TokenAmount::zero(),
+        entry1.gas_limit,
+        SendFlags::empty(),
+        None,
+        ExitCode::SYS_OUT_OF_GAS,
+        None,
+    );
+    rt.expect_emitted_event(
+        EventBuilder::new()
+            .typ("cron-entry-out-of-gas")
+            .field_indexed("receiver", &entry1.receiver)
+            .field_indexed("method", &entry1.method_num)
+            .field("gas-limit", &1_000_000u64)
+            .build()
+            .unwrap(),
+    );
+    // Exhausting one entry's budget does not prevent later entries from running.
+    rt.expect_send_simple(
+        entry2.receiver,
+        entry2.method_num,
+        None,
+        TokenAmount::zero(),
+        None,
+        ExitCode::OK,
+    );
+
+    epoch_tick_and_verify(&rt);
+}
+
+#[test]
+fn disabled_entries_are_skipped_until_re_enabled() {
+    let rt = construct_runtime();
+
+    let entry1 = new_entry(1001, 1001);
+    let entry2 = new_entry(1002, 1002);
+
+    let params = ConstructorParams { entries: vec![entry1.clone(), entry2.clone()] };
+    construct_and_verify(&rt, &params);
+
+    set_entry_enabled_and_verify(&rt, entry1.receiver, entry1.method_num, false);
+    let state: State = rt.get_state();
+    assert!(state.entries[0].disabled);
+
+    rt.expect_emitted_event(
+        EventBuilder::new()
+            .typ("cron-entry-skipped")
+            .field_indexed("receiver", &entry1.receiver)
+            .field_indexed("method", &entry1.method_num)
+            .build()
+            .unwrap(),
+    );
+    rt.expect_send_simple(
+        entry2.receiver,
+        entry2.method_num,
+        None,
+        TokenAmount::zero(),
+        None,
+        ExitCode::OK,
+    );
+    epoch_tick_and_verify(&rt);
+
+    set_entry_enabled_and_verify(&rt, entry1.receiver, entry1.method_num, true);
+    for entry in [&entry1, &entry2] {
+        rt.expect_send_simple(
+            entry.receiver,
+            entry.method_num,
+            None,
+            TokenAmount::zero(),
+            None,
+            ExitCode::OK,
+        );
+    }
+    epoch_tick_and_verify(&rt);
+}
+
+#[test]
+fn set_entry_enabled_fails_for_unknown_entry() {
+    let rt = construct_runtime();
+    construct_and_verify(&rt, &ConstructorParams { entries: vec![new_entry(1001, 1001)] });
+
+    rt.expect_validate_caller_addr(vec![SYSTEM_ACTOR_ADDR]);
+    let params =
+        SetEntryEnabledParams { receiver: Address::new_id(1001), method_num: 1002, enabled: false };
+    expect_abort(
+        ExitCode::USR_NOT_FOUND,
+        rt.call::<CronActor>(
+            Method::SetEntryEnabled as MethodNum,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        ),
+    );
+    rt.verify();
+    check_state(&rt);
+}
+
+#[test]
+fn set_entry_enabled_requires_system_caller() {
+    let rt = construct_runtime();
+    construct_and_verify(&rt, &ConstructorParams { entries: vec![new_entry(1001, 1001)] });
+
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, Address::new_id(1234));
+    rt.expect_validate_caller_addr(vec![SYSTEM_ACTOR_ADDR]);
+    let params =
+        SetEntryEnabledParams { receiver: Address::new_id(1001), method_num: 1001, enabled: false };
+    expect_abort(
+        ExitCode::USR_FORBIDDEN,
+        rt.call::<CronActor>(
+            Method::SetEntryEnabled as MethodNum,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        ),
+    );
+    rt.verify();
+}
+
+#[cfg(not(feature = "storage-rent"))]
+#[test]
+fn register_requires_storage_rent() {
+    let rt = construct_runtime();
+    construct_and_verify(&rt, &ConstructorParams { entries: vec![] });
+
+    rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(2000));
+    expect_abort(
+        ExitCode::USR_UNHANDLED_MESSAGE,
+        rt.call::<CronActor>(
+            Method::RegisterEntry as MethodNum,
+            IpldBlock::serialize_cbor(&fil_actor_cron::RegisterEntryParams { method_num: 42 })
+                .unwrap(),
+        ),
+    );
+    rt.verify();
+}
+
+fn new_entry(receiver: u64, method_num: MethodNum) -> Entry {
+    Entry {
+        receiver: Address::new_id(receiver),
+        method_num,
+        priority: 0,
+        gas_limit: None,
+        disabled: false,
+    }
+}
+
+fn set_entry_enabled_and_verify(
+    rt: &MockRuntime,
+    receiver: Address,
+    method_num: MethodNum,
+    enabled: bool,
+) {
+    rt.set_caller(*SYSTEM_ACTOR_CODE_ID, SYSTEM_ACTOR_ADDR);
+    rt.expect_validate_caller_addr(vec![SYSTEM_ACTOR_ADDR]);
+    let params = SetEntryEnabledParams { receiver, method_num, enabled };
+    let ret = rt
+        .call::<CronActor>(
+            Method::SetEntryEnabled as MethodNum,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        )
+        .unwrap();
+    assert!(ret.is_none());
+    rt.verify();
+    check_state(rt);
+}
+
 fn construct_and_verify(rt: &MockRuntime, params: &ConstructorParams) {
     rt.set_caller(*SYSTEM_ACTOR_CODE_ID, SYSTEM_ACTOR_ADDR);
     rt.expect_validate_caller_addr(vec![SYSTEM_ACTOR_ADDR]);
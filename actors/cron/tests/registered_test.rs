@@ -0,0 +1,209 @@
+#![cfg(feature = "storage-rent")]
+
+use std::cell::RefCell;
+
+use fil_actor_cron::testing::check_state_invariants;
+use fil_actor_cron::{
+    Actor as CronActor, ConstructorParams, Entry, Method, RegisterEntryParams, State,
+    MAX_REGISTERED, REGISTERED_CALLS_PER_EPOCH, REGISTERED_CALL_GAS_LIMIT,
+};
+use fil_actors_runtime::runtime::builtins::Type;
+use fil_actors_runtime::test_utils::*;
+use fil_actors_runtime::SYSTEM_ACTOR_ADDR;
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_shared::address::Address;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
+use fvm_shared::sys::SendFlags;
+use fvm_shared::MethodNum;
+use num_traits::Zero;
+
+#[test]
+fn registered_actors_are_called_in_rotation() {
+    let rt = construct_runtime(vec![new_entry(1001, 1001)]);
+
+    let count = REGISTERED_CALLS_PER_EPOCH + 5;
+    for id in 2000..2000 + count as u64 {
+        register_and_verify(&rt, Address::new_id(id), 42);
+    }
+    // Registering again replaces the method.
+    register_and_verify(&rt, Address::new_id(2000), 43);
+
+    let mut registered = Vec::new();
+    let state: State = rt.get_state();
+    assert_eq!(count as u64, state.registered_count);
+    state
+        .load_registered(rt.store())
+        .unwrap()
+        .for_each(|receiver, method_num| {
+            registered.push((receiver, *method_num));
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(count, registered.len());
+    assert!(registered.contains(&(Address::new_id(2000), 43)));
+
+    // Entries are called each epoch, followed by the next registered actors, resuming after
+    // those called the epoch before and starting over after the last.
+    let pages = [
+        &registered[..REGISTERED_CALLS_PER_EPOCH],
+        &registered[REGISTERED_CALLS_PER_EPOCH..],
+        &registered[..REGISTERED_CALLS_PER_EPOCH],
+    ];
+    for page in pages {
+        // A failing call doesn't prevent later calls.
+        epoch_tick_and_verify(&rt, page, ExitCode::USR_ILLEGAL_ARGUMENT);
+    }
+
+    // A deregistered actor is no longer called, and the rotation resumes after it.
+    let (deregistered, _) = registered[REGISTERED_CALLS_PER_EPOCH];
+    deregister_and_verify(&rt, deregistered);
+    // Deregistering again has no effect.
+    deregister_and_verify(&rt, deregistered);
+    assert_eq!(count as u64 - 1, rt.get_state::<State>().registered_count);
+    epoch_tick_and_verify(&rt, &registered[REGISTERED_CALLS_PER_EPOCH + 1..], ExitCode::OK);
+}
+
+#[test]
+fn only_evm_contracts_register() {
+    let rt = construct_runtime(vec![]);
+
+    let register = IpldBlock::serialize_cbor(&RegisterEntryParams { method_num: 42 }).unwrap();
+    for (method, params) in [(Method::RegisterEntry, register), (Method::DeregisterEntry, None)] {
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, Address::new_id(2000));
+        rt.expect_validate_caller_type(vec![Type::EVM]);
+        expect_abort(ExitCode::USR_FORBIDDEN, rt.call::<CronActor>(method as MethodNum, params));
+        rt.verify();
+    }
+}
+
+#[test]
+fn register_rejects_method_zero() {
+    let rt = construct_runtime(vec![]);
+
+    rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(2000));
+    rt.expect_validate_caller_type(vec![Type::EVM]);
+    expect_abort(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        rt.call::<CronActor>(
+            Method::RegisterEntry as MethodNum,
+            IpldBlock::serialize_cbor(&RegisterEntryParams { method_num: 0 }).unwrap(),
+        ),
+    );
+    rt.verify();
+}
+
+#[test]
+fn registered_set_is_capped() {
+    let rt = construct_runtime(vec![]);
+    register_and_verify(&rt, Address::new_id(2000), 42);
+
+    let mut state: State = rt.get_state();
+    state.registered_count = MAX_REGISTERED;
+    rt.replace_state(&state);
+
+    // A registered actor may still replace its method, but no more actors may register.
+    register_and_verify(&rt, Address::new_id(2000), 43);
+    rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(2001));
+    rt.expect_validate_caller_type(vec![Type::EVM]);
+    expect_abort(
+        ExitCode::USR_FORBIDDEN,
+        rt.call::<CronActor>(
+            Method::RegisterEntry as MethodNum,
+            IpldBlock::serialize_cbor(&RegisterEntryParams { method_num: 42 }).unwrap(),
+        ),
+    );
+    rt.verify();
+
+    // Deregistering makes room for another.
+    deregister_and_verify(&rt, Address::new_id(2000));
+    register_and_verify(&rt, Address::new_id(2001), 42);
+    assert_eq!(MAX_REGISTERED, rt.get_state::<State>().registered_count);
+}
+
+fn construct_runtime(entries: Vec<Entry>) -> MockRuntime {
+    let rt = MockRuntime {
+        receiver: Address::new_id(100),
+        caller: RefCell::new(SYSTEM_ACTOR_ADDR),
+        caller_type: RefCell::new(*SYSTEM_ACTOR_CODE_ID),
+        ..Default::default()
+    };
+    rt.expect_validate_caller_addr(vec![SYSTEM_ACTOR_ADDR]);
+    let params = ConstructorParams { entries };
+    rt.call::<CronActor>(
+        Method::Constructor as MethodNum,
+        IpldBlock::serialize_cbor(&params).unwrap(),
+    )
+    .unwrap();
+    rt.verify();
+    rt
+}
+
+fn new_entry(receiver: u64, method_num: MethodNum) -> Entry {
+    Entry {
+        receiver: Address::new_id(receiver),
+        method_num,
+        priority: 0,
+        gas_limit: None,
+        disabled: false,
+    }
+}
+
+fn register_and_verify(rt: &MockRuntime, receiver: Address, method_num: MethodNum) {
+    rt.set_caller(*EVM_ACTOR_CODE_ID, receiver);
+    rt.expect_validate_caller_type(vec![Type::EVM]);
+    let ret = rt
+        .call::<CronActor>(
+            Method::RegisterEntry as MethodNum,
+            IpldBlock::serialize_cbor(&RegisterEntryParams { method_num }).unwrap(),
+        )
+        .unwrap();
+    assert!(ret.is_none());
+    rt.verify();
+    check_state(rt);
+}
+
+fn deregister_and_verify(rt: &MockRuntime, receiver: Address) {
+    rt.set_caller(*EVM_ACTOR_CODE_ID, receiver);
+    rt.expect_validate_caller_type(vec![Type::EVM]);
+    let ret = rt.call::<CronActor>(Method::DeregisterEntry as MethodNum, None).unwrap();
+    assert!(ret.is_none());
+    rt.verify();
+    check_state(rt);
+}
+
+// Expects the entry configured at construction to be called, followed by the registered actors.
+fn epoch_tick_and_verify(rt: &MockRuntime, calls: &[(Address, MethodNum)], exit_code: ExitCode) {
+    rt.expect_send_simple(
+        Address::new_id(1001),
+        1001,
+        None,
+        TokenAmount::zero(),
+        None,
+        ExitCode::OK,
+    );
+    for (receiver, method_num) in calls {
+        rt.expect_send(
+            *receiver,
+            *method_num,
+            None,
+            TokenAmount::zero(),
+            Some(REGISTERED_CALL_GAS_LIMIT),
+            SendFlags::empty(),
+            None,
+            exit_code,
+            None,
+        );
+    }
+    rt.set_caller(*SYSTEM_ACTOR_CODE_ID, SYSTEM_ACTOR_ADDR);
+    rt.expect_validate_caller_addr(vec![SYSTEM_ACTOR_ADDR]);
+    let ret = rt.call::<CronActor>(Method::EpochTick as MethodNum, None).unwrap();
+    assert!(ret.is_none());
+    rt.verify();
+    check_state(rt);
+}
+
+fn check_state(rt: &MockRuntime) {
+    let (_, acc) = check_state_invariants(&rt.get_state());
+    acc.assert_empty();
+}
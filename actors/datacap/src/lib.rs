@@ -11,6 +11,7 @@ use fvm_actor_utils::util::ActorRuntime;
 use fvm_ipld_encoding::RawBytes;
 use fvm_shared::address::Address;
 use fvm_shared::bigint::BigInt;
+use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::{ErrorNumber, ExitCode};
 use fvm_shared::Response;
@@ -18,6 +19,7 @@ use fvm_shared::{ActorID, MethodNum, METHOD_CONSTRUCTOR};
 use lazy_static::lazy_static;
 use log::info;
 use num_derive::FromPrimitive;
+use num_traits::Zero;
 
 use fil_actors_runtime::runtime::{ActorCode, Runtime};
 use fil_actors_runtime::{
@@ -27,6 +29,7 @@ use fil_actors_runtime::{
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 
 pub use self::state::State;
+pub use self::state::VestingGrant;
 pub use self::types::*;
 
 #[cfg(feature = "fil-actor")]
@@ -82,6 +85,10 @@ pub enum Method {
     BurnExported = frc42_dispatch::method_hash!("Burn"),
     BurnFromExported = frc42_dispatch::method_hash!("BurnFrom"),
     AllowanceExported = frc42_dispatch::method_hash!("Allowance"),
+    MintLockedExported = frc42_dispatch::method_hash!("MintLocked"),
+    LockedBalanceExported = frc42_dispatch::method_hash!("LockedBalance"),
+    IncreaseAllowanceWithTermsExported = frc42_dispatch::method_hash!("IncreaseAllowanceWithTerms"),
+    AllowanceTermsExported = frc42_dispatch::method_hash!("AllowanceTerms"),
 }
 
 pub struct Actor;
@@ -135,12 +142,17 @@ impl Actor {
         token.balance_of(&params.address).map(|balance| BalanceReturn { balance }).actor_result()
     }
 
+    /// Returns the allowance of an operator for an owner, which is zero if it has expired.
     pub fn allowance(
         rt: &impl Runtime,
         params: GetAllowanceParams,
     ) -> Result<GetAllowanceReturn, ActorError> {
         rt.validate_immediate_caller_accept_any()?;
         let mut st: State = rt.state()?;
+        let terms = get_allowance_terms(&st, rt, &params.owner, &params.operator)?;
+        if is_expired(&terms, rt.curr_epoch()) {
+            return Ok(GetAllowanceReturn { allowance: TokenAmount::zero() });
+        }
         let syscalls = SyscallProvider { rt };
         let runtime = ActorRuntime::new(&syscalls, syscalls.rt.store());
         let token = as_token(&mut st, &runtime);
@@ -150,47 +162,84 @@ impl Actor {
             .actor_result()
     }
 
+    /// Returns the allowance of an operator for an owner together with the terms restricting
+    /// its use.
+    /// This method is not part of the fungible token standard.
+    pub fn allowance_terms(
+        rt: &impl Runtime,
+        params: GetAllowanceParams,
+    ) -> Result<AllowanceTermsReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let mut st: State = rt.state()?;
+        let terms = get_allowance_terms(&st, rt, &params.owner, &params.operator)?;
+        if is_expired(&terms, rt.curr_epoch()) {
+            return Ok(AllowanceTermsReturn { allowance: TokenAmount::zero(), terms });
+        }
+        let syscalls = SyscallProvider { rt };
+        let runtime = ActorRuntime::new(&syscalls, syscalls.rt.store());
+        let token = as_token(&mut st, &runtime);
+        token
+            .allowance(&params.owner, &params.operator)
+            .map(|allowance| AllowanceTermsReturn { allowance, terms })
+            .actor_result()
+    }
+
     /// Mints new data cap tokens for an address (a verified client).
     /// Simultaneously sets the allowance for any specified operators to effectively infinite.
     /// Only the governor can call this method.
     /// This method is not part of the fungible token standard.
     pub fn mint(rt: &impl Runtime, params: MintParams) -> Result<MintReturn, ActorError> {
-        let mut hook = rt
-            .transaction(|st: &mut State, rt| {
-                // Only the governor can mint datacap tokens.
-                rt.validate_immediate_caller_is(std::iter::once(&st.governor))?;
-                let operator = st.governor;
-
-                let syscalls = SyscallProvider { rt };
-                let runtime = ActorRuntime::new(&syscalls, syscalls.rt.store());
-                let mut token = as_token(st, &runtime);
-                // Mint tokens "from" the operator to the beneficiary.
-                let ret = token
-                    .mint(
-                        &operator,
-                        &params.to,
-                        &params.amount,
-                        RawBytes::default(),
-                        RawBytes::default(),
-                    )
-                    .actor_result();
-
-                // Set allowance for any specified operators.
-                for delegate in &params.operators {
-                    token
-                        .set_allowance(&params.to, delegate, &INFINITE_ALLOWANCE)
-                        .actor_result()?;
-                }
+        mint_tokens(rt, &params.to, &params.amount, &params.operators, None)
+    }
 
-                ret
-            })
-            .context("state transaction failed")?;
+    /// Mints new data cap tokens for an address (a verified client), locked until they unlock
+    /// according to a vesting schedule.
+    /// Locked tokens count towards the holder's balance but cannot be transferred.
+    /// Simultaneously sets the allowance for any specified operators to effectively infinite.
+    /// Only the governor can call this method.
+    /// This method is not part of the fungible token standard.
+    pub fn mint_locked(
+        rt: &impl Runtime,
+        params: MintLockedParams,
+    ) -> Result<MintReturn, ActorError> {
+        let schedule = &params.schedule;
+        if schedule.tranches == 0 || schedule.tranche_period <= 0 {
+            return Err(actor_error!(
+                illegal_argument,
+                "invalid vesting schedule {:?}: tranches and tranche period must be positive",
+                schedule
+            ));
+        }
+        if !params.amount.is_positive() {
+            return Err(actor_error!(
+                illegal_argument,
+                "locked mint amount {} must be positive",
+                params.amount
+            ));
+        }
+        mint_tokens(rt, &params.to, &params.amount, &params.operators, Some(schedule))
+    }
 
-        let mut st: State = rt.state()?;
-        let syscalls = SyscallProvider { rt };
-        let intermediate = hook.call(&as_actor_runtime(&syscalls)).actor_result()?;
-        let runtime = ActorRuntime::new(&syscalls, syscalls.rt.store());
-        as_token(&mut st, &runtime).mint_return(intermediate).actor_result()
+    /// Returns how much of an address's balance is locked by vesting grants, and how much
+    /// may be spent.
+    /// This method is not part of the fungible token standard.
+    pub fn locked_balance(
+        rt: &impl Runtime,
+        params: BalanceParams,
+    ) -> Result<LockedBalanceReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let st: State = rt.state()?;
+        let (locked, balance) = match rt.resolve_address(&params.address) {
+            Some(holder) => (
+                st.locked_balance(rt.store(), holder, rt.curr_epoch())?,
+                st.balance(rt.store(), holder)?,
+            ),
+            None => (TokenAmount::zero(), TokenAmount::zero()),
+        };
+        let unlocked = spendable(&balance, &locked);
+        // Granted tokens destroyed by the governor come out of the unlocked balance first, so
+        // once the balance falls below the locked amount the whole balance is locked.
+        Ok(LockedBalanceReturn { locked: &balance - &unlocked, unlocked })
     }
 
     /// Destroys data cap tokens for an address (a verified client).
@@ -240,6 +289,7 @@ impl Actor {
                         st.governor
                     ));
                 }
+                check_unlocked(st, rt, from, &params.amount)?;
 
                 let syscalls = SyscallProvider { rt };
                 let runtime = ActorRuntime::new(&syscalls, syscalls.rt.store());
@@ -291,6 +341,8 @@ impl Actor {
                         st.governor
                     ));
                 }
+                check_unlocked(st, rt, &from, &params.amount)?;
+                check_allowance_terms(st, rt, &from, &operator, &params.amount)?;
 
                 let syscalls = SyscallProvider { rt };
                 let runtime = ActorRuntime::new(&syscalls, syscalls.rt.store());
@@ -324,6 +376,7 @@ impl Actor {
         let operator = params.operator;
 
         rt.transaction(|st: &mut State, rt| {
+            clear_expired_allowance(st, rt, &owner, &operator)?;
             let syscalls = SyscallProvider { rt };
             let runtime = ActorRuntime::new(&syscalls, syscalls.rt.store());
             let mut token = as_token(st, &runtime);
@@ -335,6 +388,55 @@ impl Actor {
         .context("state transaction failed")
     }
 
+    /// Increases the allowance of an operator and sets the terms restricting its use,
+    /// replacing any existing terms.
+    /// An expired allowance is cleared before being increased.
+    /// This method is not part of the fungible token standard.
+    pub fn increase_allowance_with_terms(
+        rt: &impl Runtime,
+        params: IncreaseAllowanceWithTermsParams,
+    ) -> Result<IncreaseAllowanceReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let owner = rt.message().caller();
+        let operator = params.operator;
+        let terms = params.terms;
+        if let Some(expiration) = terms.expiration {
+            if expiration <= rt.curr_epoch() {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "allowance expiration {} must be after current epoch {}",
+                    expiration,
+                    rt.curr_epoch()
+                ));
+            }
+        }
+        if let Some(max_per_transfer) = &terms.max_per_transfer {
+            if !max_per_transfer.is_positive() {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "per-transfer limit {} must be positive",
+                    max_per_transfer
+                ));
+            }
+        }
+
+        rt.transaction(|st: &mut State, rt| {
+            clear_expired_allowance(st, rt, &owner, &operator)?;
+            let syscalls = SyscallProvider { rt };
+            let runtime = ActorRuntime::new(&syscalls, syscalls.rt.store());
+            let mut token = as_token(st, &runtime);
+            let new_allowance =
+                token.increase_allowance(&owner, &operator, &params.increase).actor_result()?;
+
+            // The operator's account exists once the allowance is set.
+            let (owner_id, operator_id) = resolve_allowance_pair(rt, &owner, &operator)
+                .context_code(ExitCode::USR_ILLEGAL_ARGUMENT, "failed to resolve operator")?;
+            st.set_allowance_terms(rt.store(), owner_id, operator_id, terms)?;
+            Ok(IncreaseAllowanceReturn { new_allowance })
+        })
+        .context("state transaction failed")
+    }
+
     pub fn decrease_allowance(
         rt: &impl Runtime,
         params: DecreaseAllowanceParams,
@@ -344,6 +446,7 @@ impl Actor {
         let operator = &params.operator;
 
         rt.transaction(|st: &mut State, rt| {
+            clear_expired_allowance(st, rt, owner, operator)?;
             let syscalls = SyscallProvider { rt };
             let runtime = ActorRuntime::new(&syscalls, syscalls.rt.store());
             let mut token = as_token(st, &runtime);
@@ -364,13 +467,14 @@ impl Actor {
         let operator = &params.operator;
 
         rt.transaction(|st: &mut State, rt| {
+            // An expired allowance is reported as zero.
+            clear_expired_allowance(st, rt, owner, operator)?;
             let syscalls = SyscallProvider { rt };
             let runtime = ActorRuntime::new(&syscalls, syscalls.rt.store());
             let mut token = as_token(st, &runtime);
-            token
-                .revoke_allowance(owner, operator)
-                .map(|old_allowance| RevokeAllowanceReturn { old_allowance })
-                .actor_result()
+            let old_allowance = token.revoke_allowance(owner, operator).actor_result()?;
+            clear_allowance_terms(st, rt, owner, operator)?;
+            Ok(RevokeAllowanceReturn { old_allowance })
         })
         .context("state transaction failed")
     }
@@ -397,6 +501,7 @@ impl Actor {
         let owner = &params.owner;
 
         rt.transaction(|st: &mut State, rt| {
+            check_allowance_terms(st, rt, owner, operator, &params.amount)?;
             let syscalls = SyscallProvider { rt };
             let runtime = ActorRuntime::new(&syscalls, syscalls.rt.store());
             let mut token = as_token(st, &runtime);
@@ -406,6 +511,192 @@ impl Actor {
     }
 }
 
+// Mints tokens to an address as the governor, optionally locking them under a vesting schedule,
+// and sets the allowance for any specified operators to effectively infinite.
+fn mint_tokens(
+    rt: &impl Runtime,
+    to: &Address,
+    amount: &TokenAmount,
+    operators: &[Address],
+    schedule: Option<&VestingSchedule>,
+) -> Result<MintReturn, ActorError> {
+    let mut hook = rt
+        .transaction(|st: &mut State, rt| {
+            // Only the governor can mint datacap tokens.
+            rt.validate_immediate_caller_is(std::iter::once(&st.governor))?;
+            let operator = st.governor;
+
+            let syscalls = SyscallProvider { rt };
+            let runtime = ActorRuntime::new(&syscalls, syscalls.rt.store());
+            let mut token = as_token(st, &runtime);
+            // Mint tokens "from" the operator to the beneficiary.
+            let ret = token
+                .mint(&operator, to, amount, RawBytes::default(), RawBytes::default())
+                .actor_result();
+
+            // Set allowance for any specified operators.
+            for delegate in operators {
+                token.set_allowance(to, delegate, &INFINITE_ALLOWANCE).actor_result()?;
+            }
+
+            let ret = ret?;
+            // Operator allowances are reset without terms.
+            for delegate in operators {
+                clear_allowance_terms(st, rt, to, delegate)?;
+            }
+            if let Some(schedule) = schedule {
+                // The recipient's account exists once the tokens are minted.
+                let holder = rt
+                    .resolve_address(to)
+                    .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to resolve recipient")?;
+                let grant = VestingGrant { amount: amount.clone(), schedule: schedule.clone() };
+                st.add_grant(rt.store(), holder, grant, rt.curr_epoch())?;
+            }
+            Ok(ret)
+        })
+        .context("state transaction failed")?;
+
+    let mut st: State = rt.state()?;
+    let syscalls = SyscallProvider { rt };
+    let intermediate = hook.call(&as_actor_runtime(&syscalls)).actor_result()?;
+    let runtime = ActorRuntime::new(&syscalls, syscalls.rt.store());
+    as_token(&mut st, &runtime).mint_return(intermediate).actor_result()
+}
+
+// Checks that an amount to be spent from a holder is covered by the holder's unlocked balance.
+// Datacap destroyed by the governor is taken from the unlocked balance first.
+fn check_unlocked(
+    st: &mut State,
+    rt: &impl Runtime,
+    holder: &Address,
+    amount: &TokenAmount,
+) -> Result<(), ActorError> {
+    let holder = match rt.resolve_address(holder) {
+        Some(id) => id,
+        // A holder without an ID has no tokens to be locked.
+        None => return Ok(()),
+    };
+    st.prune_unlocked_grants(rt.store(), holder, rt.curr_epoch())?;
+    let locked = st.locked_balance(rt.store(), holder, rt.curr_epoch())?;
+    if locked.is_zero() {
+        return Ok(());
+    }
+    let unlocked = spendable(&st.balance(rt.store(), holder)?, &locked);
+    if *amount > unlocked {
+        return Err(actor_error!(
+            insufficient_funds,
+            "amount {} exceeds unlocked balance {} of {} ({} locked)",
+            amount,
+            unlocked,
+            holder,
+            locked
+        ));
+    }
+    Ok(())
+}
+
+// Resolves an owner and operator to the IDs keying the terms of the operator's allowance.
+fn resolve_allowance_pair(
+    rt: &impl Runtime,
+    owner: &Address,
+    operator: &Address,
+) -> Option<(ActorID, ActorID)> {
+    Some((rt.resolve_address(owner)?, rt.resolve_address(operator)?))
+}
+
+// Returns the terms restricting an operator's use of an owner's allowance.
+fn get_allowance_terms(
+    st: &State,
+    rt: &impl Runtime,
+    owner: &Address,
+    operator: &Address,
+) -> Result<AllowanceTerms, ActorError> {
+    match resolve_allowance_pair(rt, owner, operator) {
+        Some((owner, operator)) => {
+            Ok(st.get_allowance_terms(rt.store(), owner, operator)?.unwrap_or_default())
+        }
+        // Without IDs there can be no allowance to restrict.
+        None => Ok(AllowanceTerms::default()),
+    }
+}
+
+// Removes the terms restricting an operator's use of an owner's allowance.
+fn clear_allowance_terms(
+    st: &mut State,
+    rt: &impl Runtime,
+    owner: &Address,
+    operator: &Address,
+) -> Result<(), ActorError> {
+    if let Some((owner, operator)) = resolve_allowance_pair(rt, owner, operator) {
+        st.set_allowance_terms(rt.store(), owner, operator, AllowanceTerms::default())?;
+    }
+    Ok(())
+}
+
+// Revokes an operator's allowance, along with its terms, if the allowance has expired.
+fn clear_expired_allowance(
+    st: &mut State,
+    rt: &impl Runtime,
+    owner: &Address,
+    operator: &Address,
+) -> Result<(), ActorError> {
+    let terms = get_allowance_terms(st, rt, owner, operator)?;
+    if !is_expired(&terms, rt.curr_epoch()) {
+        return Ok(());
+    }
+    clear_allowance_terms(st, rt, owner, operator)?;
+    let syscalls = SyscallProvider { rt };
+    let runtime = ActorRuntime::new(&syscalls, syscalls.rt.store());
+    as_token(st, &runtime).revoke_allowance(owner, operator).actor_result()?;
+    Ok(())
+}
+
+// Checks that an operator may spend an amount of an owner's allowance under its terms.
+fn check_allowance_terms(
+    st: &State,
+    rt: &impl Runtime,
+    owner: &Address,
+    operator: &Address,
+    amount: &TokenAmount,
+) -> Result<(), ActorError> {
+    let terms = get_allowance_terms(st, rt, owner, operator)?;
+    if is_expired(&terms, rt.curr_epoch()) {
+        return Err(actor_error!(
+            forbidden,
+            "allowance of {} for {} expired at epoch {}",
+            operator,
+            owner,
+            terms.expiration.unwrap_or_default()
+        ));
+    }
+    if let Some(max_per_transfer) = &terms.max_per_transfer {
+        if amount > max_per_transfer {
+            return Err(actor_error!(
+                forbidden,
+                "amount {} exceeds per-transfer limit {} of {} for {}",
+                amount,
+                max_per_transfer,
+                operator,
+                owner
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn is_expired(terms: &AllowanceTerms, curr_epoch: ChainEpoch) -> bool {
+    terms.expiration.is_some_and(|expiration| curr_epoch >= expiration)
+}
+
+// Returns the part of a balance not covered by locked tokens.
+fn spendable(balance: &TokenAmount, locked: &TokenAmount) -> TokenAmount {
+    if balance > locked {
+        balance - locked
+    } else {
+        TokenAmount::zero()
+    }
+}
+
 /// Implementation of the token library's messenger trait in terms of the built-in actors'
 /// runtime library.
 struct SyscallProvider<'a, RT> {
@@ -504,6 +795,11 @@ impl ActorCode for Actor {
         "DataCap"
     }
 
+    fil_actors_runtime::actor_info! {
+        interface_revisions: [1],
+        features: [],
+    }
+
     actor_dispatch! {
         Constructor => constructor,
         MintExported => mint,
@@ -521,5 +817,9 @@ impl ActorCode for Actor {
         BurnExported => burn,
         BurnFromExported => burn_from,
         AllowanceExported => allowance,
+        MintLockedExported => mint_locked,
+        LockedBalanceExported => locked_balance,
+        IncreaseAllowanceWithTermsExported => increase_allowance_with_terms,
+        AllowanceTermsExported => allowance_terms,
     }
 }
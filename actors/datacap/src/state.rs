@@ -1,24 +1,77 @@
+use cid::Cid;
 use frc46_token::token;
 use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::tuple::*;
 use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
-use fvm_shared::ActorID;
+use fvm_shared::{ActorID, HAMT_BIT_WIDTH};
+use num_traits::Zero;
 
-use fil_actors_runtime::{ActorError, AsActorError};
+use fil_actors_runtime::{ActorError, AsActorError, Config, Map2, MapMap, DEFAULT_HAMT_CONFIG};
+
+use crate::{AllowanceTerms, VestingSchedule};
+
+pub type GrantsMap<BS> = Map2<BS, ActorID, Vec<VestingGrant>>;
+pub const GRANTS_CONFIG: Config = DEFAULT_HAMT_CONFIG;
 
 #[derive(Serialize_tuple, Deserialize_tuple)]
 pub struct State {
     pub governor: Address,
     pub token: token::state::TokenState,
+    // Maps holders to the datacap granted to them that has not yet fully unlocked.
+    pub grants: Cid, // HAMT[ActorID]Vec<VestingGrant>
+    // Maps owners to the terms restricting each operator's use of their allowance.
+    pub allowance_terms: Cid, // HAMT[ActorID]HAMT[ActorID]AllowanceTerms
+}
+
+/// An amount of datacap minted to a holder that unlocks over time.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct VestingGrant {
+    pub amount: TokenAmount,
+    pub schedule: VestingSchedule,
+}
+
+impl VestingGrant {
+    /// The amount of the grant unlocked at an epoch.
+    /// Tranches unlock in equal parts (rounded down), with the last tranche unlocking the remainder.
+    pub fn unlocked_at(&self, epoch: ChainEpoch) -> TokenAmount {
+        let schedule = &self.schedule;
+        if epoch < schedule.start {
+            return TokenAmount::zero();
+        }
+        let vested = ((epoch - schedule.start) / schedule.tranche_period) as u64 + 1;
+        if vested >= schedule.tranches {
+            return self.amount.clone();
+        }
+        (&self.amount * vested).div_floor(schedule.tranches)
+    }
+
+    /// The amount of the grant still locked at an epoch.
+    pub fn locked_at(&self, epoch: ChainEpoch) -> TokenAmount {
+        &self.amount - self.unlocked_at(epoch)
+    }
 }
 
 impl State {
     pub fn new<BS: Blockstore>(store: &BS, governor: Address) -> Result<State, ActorError> {
         let token_state = token::state::TokenState::new(store)
             .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to create token state")?;
-        Ok(State { governor, token: token_state })
+        let empty_grants = GrantsMap::flush_empty(store, GRANTS_CONFIG)?;
+        let empty_terms = MapMap::<_, AllowanceTerms, ActorID, ActorID>::new(
+            store,
+            HAMT_BIT_WIDTH,
+            HAMT_BIT_WIDTH,
+        )
+        .flush()
+        .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to create empty terms map")?;
+        Ok(State {
+            governor,
+            token: token_state,
+            grants: empty_grants,
+            allowance_terms: empty_terms,
+        })
     }
 
     // Visible for testing
@@ -31,4 +84,108 @@ impl State {
             .get_balance(bs, owner)
             .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to get balance")
     }
+
+    pub fn load_grants<BS: Blockstore>(&self, store: BS) -> Result<GrantsMap<BS>, ActorError> {
+        GrantsMap::load(store, &self.grants, GRANTS_CONFIG, "grants")
+    }
+
+    // Records a grant of datacap to a holder, pruning any of the holder's fully-unlocked grants.
+    pub fn add_grant<BS: Blockstore>(
+        &mut self,
+        store: &BS,
+        holder: ActorID,
+        grant: VestingGrant,
+        curr_epoch: ChainEpoch,
+    ) -> Result<(), ActorError> {
+        let mut grants_map = self.load_grants(store)?;
+        let mut grants = grants_map.get(&holder)?.cloned().unwrap_or_default();
+        grants.retain(|g| g.locked_at(curr_epoch).is_positive());
+        grants.push(grant);
+        grants_map.set(&holder, grants)?;
+        self.grants = grants_map.flush()?;
+        Ok(())
+    }
+
+    // Returns the amount of a holder's granted datacap that is still locked at an epoch.
+    pub fn locked_balance<BS: Blockstore>(
+        &self,
+        store: &BS,
+        holder: ActorID,
+        curr_epoch: ChainEpoch,
+    ) -> Result<TokenAmount, ActorError> {
+        let grants_map = self.load_grants(store)?;
+        Ok(match grants_map.get(&holder)? {
+            Some(grants) => grants.iter().map(|g| g.locked_at(curr_epoch)).sum(),
+            None => TokenAmount::zero(),
+        })
+    }
+
+    // Removes a holder's grants that have fully unlocked by an epoch.
+    pub fn prune_unlocked_grants<BS: Blockstore>(
+        &mut self,
+        store: &BS,
+        holder: ActorID,
+        curr_epoch: ChainEpoch,
+    ) -> Result<(), ActorError> {
+        let mut grants_map = self.load_grants(store)?;
+        let grants = match grants_map.get(&holder)? {
+            Some(grants) => grants,
+            None => return Ok(()),
+        };
+        let locked: Vec<VestingGrant> =
+            grants.iter().filter(|g| g.locked_at(curr_epoch).is_positive()).cloned().collect();
+        if locked.len() == grants.len() {
+            return Ok(());
+        }
+        if locked.is_empty() {
+            grants_map.delete(&holder)?;
+        } else {
+            grants_map.set(&holder, locked)?;
+        }
+        self.grants = grants_map.flush()?;
+        Ok(())
+    }
+
+    pub fn load_allowance_terms<'a, BS: Blockstore>(
+        &self,
+        store: &'a BS,
+    ) -> Result<MapMap<'a, BS, AllowanceTerms, ActorID, ActorID>, ActorError> {
+        MapMap::from_root(store, &self.allowance_terms, HAMT_BIT_WIDTH, HAMT_BIT_WIDTH)
+            .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to load allowance terms")
+    }
+
+    pub fn get_allowance_terms<BS: Blockstore>(
+        &self,
+        store: &BS,
+        owner: ActorID,
+        operator: ActorID,
+    ) -> Result<Option<AllowanceTerms>, ActorError> {
+        let mut all_terms = self.load_allowance_terms(store)?;
+        let terms = all_terms
+            .get(owner, operator)
+            .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to get allowance terms")?;
+        Ok(terms.cloned())
+    }
+
+    // Sets the terms restricting an operator's use of an owner's allowance.
+    // Unrestricted terms are not stored.
+    pub fn set_allowance_terms<BS: Blockstore>(
+        &mut self,
+        store: &BS,
+        owner: ActorID,
+        operator: ActorID,
+        terms: AllowanceTerms,
+    ) -> Result<(), ActorError> {
+        let mut all_terms = self.load_allowance_terms(store)?;
+        if terms == AllowanceTerms::default() {
+            all_terms.remove(owner, operator)
+        } else {
+            all_terms.put(owner, operator, terms)
+        }
+        .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to set allowance terms")?;
+        self.allowance_terms = all_terms
+            .flush()
+            .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to flush allowance terms")?;
+        Ok(())
+    }
 }
@@ -1,10 +1,11 @@
-use frc46_token::token::state::StateSummary;
+use frc46_token::token::state::{decode_actor_id, StateSummary};
 use fvm_ipld_blockstore::Blockstore;
 use fvm_shared::address::Protocol;
+use fvm_shared::ActorID;
 
-use fil_actors_runtime::MessageAccumulator;
+use fil_actors_runtime::{Map2, MessageAccumulator, DEFAULT_HAMT_CONFIG};
 
-use crate::{State, DATACAP_GRANULARITY};
+use crate::{AllowanceTerms, State, DATACAP_GRANULARITY};
 
 /// Checks internal invariants of data cap token actor state.
 pub fn check_state_invariants<BS: Blockstore>(
@@ -17,5 +18,67 @@ pub fn check_state_invariants<BS: Blockstore>(
     for e in msgs {
         acc.add(e.to_string());
     }
+
+    match state.load_grants(store) {
+        Ok(grants) => {
+            let ret = grants.for_each(|holder, holder_grants| {
+                acc.require(!holder_grants.is_empty(), format!("holder {holder} has empty grants"));
+                for grant in holder_grants {
+                    acc.require(
+                        grant.amount.is_positive(),
+                        format!("holder {holder} grant amount {} not positive", grant.amount),
+                    );
+                    acc.require(
+                        grant.schedule.tranches > 0 && grant.schedule.tranche_period > 0,
+                        format!("holder {holder} grant has invalid schedule {:?}", grant.schedule),
+                    );
+                }
+                Ok(())
+            });
+            acc.require_no_error(ret, "error iterating grants");
+        }
+        Err(e) => acc.add(format!("error loading grants: {e}")),
+    }
+
+    match state.load_allowance_terms(store) {
+        Ok(all_terms) => {
+            let ret = all_terms.for_each(|owner_key, inner_root| {
+                let owner = decode_actor_id(owner_key).unwrap();
+                let inner = Map2::<&BS, ActorID, AllowanceTerms>::load(
+                    store,
+                    inner_root,
+                    DEFAULT_HAMT_CONFIG,
+                    "allowance terms inner",
+                );
+                match inner {
+                    Ok(owner_terms) => {
+                        let ret = owner_terms.for_each(|operator, terms| {
+                            acc.require(
+                                *terms != AllowanceTerms::default(),
+                                format!("owner {owner} operator {operator} has unrestricted terms"),
+                            );
+                            if let Some(max_per_transfer) = &terms.max_per_transfer {
+                                acc.require(
+                                    max_per_transfer.is_positive(),
+                                    format!(
+                                        "owner {owner} operator {operator} per-transfer limit {max_per_transfer} not positive"
+                                    ),
+                                );
+                            }
+                            Ok(())
+                        });
+                        acc.require_no_error(
+                            ret,
+                            format!("error iterating allowance terms for {owner}"),
+                        );
+                    }
+                    Err(e) => acc.add(format!("error loading allowance terms: {e}")),
+                }
+                Ok(())
+            });
+            acc.require_no_error(ret, "error iterating allowance terms");
+        }
+        Err(e) => acc.add(format!("error loading allowance terms: {e}")),
+    }
     (summary, acc)
 }
@@ -1,5 +1,6 @@
 use fvm_ipld_encoding::tuple::*;
 use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
@@ -83,3 +84,59 @@ pub struct DestroyParams {
 pub struct GranularityReturn {
     pub granularity: u64,
 }
+
+/// A schedule over which granted datacap unlocks in equal tranches.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct VestingSchedule {
+    // Epoch at which the first tranche unlocks.
+    pub start: ChainEpoch,
+    // Epochs between successive tranches.
+    pub tranche_period: ChainEpoch,
+    // Number of tranches.
+    pub tranches: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct MintLockedParams {
+    // Recipient of the newly minted tokens.
+    pub to: Address,
+    // Amount of tokens to mint, all initially locked.
+    pub amount: TokenAmount,
+    // Addresses to be granted effectively-infinite operator allowance for the recipient.
+    pub operators: Vec<Address>,
+    // Schedule over which the minted tokens unlock.
+    pub schedule: VestingSchedule,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct LockedBalanceReturn {
+    // Tokens held that have not yet unlocked.
+    pub locked: TokenAmount,
+    // Tokens held that may be spent.
+    pub unlocked: TokenAmount,
+}
+
+/// Restrictions on an operator's use of an allowance.
+/// Unset fields leave the allowance unrestricted in that respect.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct AllowanceTerms {
+    // Epoch from which the allowance can no longer be spent.
+    pub expiration: Option<ChainEpoch>,
+    // Maximum amount the operator may spend in a single transfer or burn.
+    pub max_per_transfer: Option<TokenAmount>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct IncreaseAllowanceWithTermsParams {
+    pub operator: Address,
+    pub increase: TokenAmount,
+    // Terms replacing any existing terms for the operator's allowance.
+    pub terms: AllowanceTerms,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct AllowanceTermsReturn {
+    // The allowance remaining, which is zero if it has expired.
+    pub allowance: TokenAmount,
+    pub terms: AllowanceTerms,
+}
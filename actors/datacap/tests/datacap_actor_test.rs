@@ -261,6 +261,389 @@ mod destroy {
     }
 }
 
+mod locked {
+    use crate::{make_harness, ALICE, BOB};
+    use fil_actor_datacap::{Actor, Method, MintLockedParams, State, VestingSchedule};
+    use fil_actors_runtime::test_utils::{
+        expect_abort, expect_abort_contains_message, MARKET_ACTOR_CODE_ID, VERIFREG_ACTOR_CODE_ID,
+    };
+    use fil_actors_runtime::{STORAGE_MARKET_ACTOR_ADDR, VERIFIED_REGISTRY_ACTOR_ADDR};
+    use fvm_ipld_encoding::ipld_block::IpldBlock;
+    use fvm_ipld_encoding::RawBytes;
+    use fvm_shared::econ::TokenAmount;
+    use fvm_shared::error::ExitCode;
+    use fvm_shared::MethodNum;
+
+    fn schedule(start: i64, tranche_period: i64, tranches: u64) -> VestingSchedule {
+        VestingSchedule { start, tranche_period, tranches }
+    }
+
+    #[test]
+    fn unlocks_in_tranches() {
+        let (rt, h) = make_harness();
+        let one = TokenAmount::from_whole(1);
+        rt.set_epoch(100);
+        h.mint_locked(&rt, &ALICE, &TokenAmount::from_whole(8), vec![], schedule(200, 100, 4))
+            .unwrap();
+        assert_eq!(TokenAmount::from_whole(8), h.get_balance(&rt, &ALICE));
+        let ret = h.get_locked_balance(&rt, &ALICE);
+        assert_eq!(TokenAmount::from_whole(8), ret.locked);
+        assert_eq!(TokenAmount::from_whole(0), ret.unlocked);
+
+        // Nothing can be spent before the first tranche.
+        expect_abort_contains_message(
+            ExitCode::USR_INSUFFICIENT_FUNDS,
+            "exceeds unlocked balance",
+            h.transfer(&rt, &ALICE, &h.governor, &one, RawBytes::default()),
+        );
+        rt.reset();
+
+        // Datacap minted without a schedule is spendable alongside locked datacap.
+        h.mint(&rt, &ALICE, &TokenAmount::from_whole(2), vec![]).unwrap();
+        h.transfer(&rt, &ALICE, &h.governor, &TokenAmount::from_whole(2), RawBytes::default())
+            .unwrap();
+        expect_abort(
+            ExitCode::USR_INSUFFICIENT_FUNDS,
+            h.transfer(&rt, &ALICE, &h.governor, &one, RawBytes::default()),
+        );
+        rt.reset();
+
+        // Each tranche unlocks a quarter of the grant.
+        rt.set_epoch(200);
+        let ret = h.get_locked_balance(&rt, &ALICE);
+        assert_eq!(TokenAmount::from_whole(6), ret.locked);
+        assert_eq!(TokenAmount::from_whole(2), ret.unlocked);
+        h.transfer(&rt, &ALICE, &h.governor, &TokenAmount::from_whole(2), RawBytes::default())
+            .unwrap();
+        expect_abort(
+            ExitCode::USR_INSUFFICIENT_FUNDS,
+            h.transfer(&rt, &ALICE, &h.governor, &one, RawBytes::default()),
+        );
+        rt.reset();
+
+        rt.set_epoch(399);
+        assert_eq!(TokenAmount::from_whole(2), h.get_locked_balance(&rt, &ALICE).unlocked);
+
+        // Once fully unlocked, the grant is pruned on the next spend, but not when queried.
+        rt.set_epoch(500);
+        let ret = h.get_locked_balance(&rt, &ALICE);
+        assert_eq!(TokenAmount::from_whole(0), ret.locked);
+        assert_eq!(TokenAmount::from_whole(6), ret.unlocked);
+        let st: State = rt.get_state();
+        assert!(st.load_grants(rt.store()).unwrap().get(&ALICE.id().unwrap()).unwrap().is_some());
+        h.transfer(&rt, &ALICE, &h.governor, &TokenAmount::from_whole(6), RawBytes::default())
+            .unwrap();
+        let st: State = rt.get_state();
+        assert!(st.load_grants(rt.store()).unwrap().get(&ALICE.id().unwrap()).unwrap().is_none());
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn transfer_from_respects_locks() {
+        let (rt, h) = make_harness();
+        let amt = TokenAmount::from_whole(4);
+        h.mint_locked(&rt, &ALICE, &amt, vec![*BOB], schedule(100, 100, 1)).unwrap();
+
+        expect_abort_contains_message(
+            ExitCode::USR_INSUFFICIENT_FUNDS,
+            "exceeds unlocked balance",
+            h.transfer_from(&rt, &BOB, &ALICE, &h.governor, &amt, RawBytes::default()),
+        );
+        rt.reset();
+
+        rt.set_epoch(100);
+        h.transfer_from(&rt, &BOB, &ALICE, &h.governor, &amt, RawBytes::default()).unwrap();
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn destroy_takes_unlocked_first() {
+        let (rt, h) = make_harness();
+        h.mint(&rt, &ALICE, &TokenAmount::from_whole(2), vec![]).unwrap();
+        h.mint_locked(&rt, &ALICE, &TokenAmount::from_whole(4), vec![], schedule(100, 100, 1))
+            .unwrap();
+
+        h.destroy(&rt, &ALICE, &TokenAmount::from_whole(3)).unwrap();
+        let ret = h.get_locked_balance(&rt, &ALICE);
+        assert_eq!(TokenAmount::from_whole(3), ret.locked);
+        assert_eq!(TokenAmount::from_whole(0), ret.unlocked);
+
+        rt.set_epoch(100);
+        let ret = h.get_locked_balance(&rt, &ALICE);
+        assert_eq!(TokenAmount::from_whole(0), ret.locked);
+        assert_eq!(TokenAmount::from_whole(3), ret.unlocked);
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn rejects_invalid_grants() {
+        let (rt, h) = make_harness();
+        let amt = TokenAmount::from_whole(4);
+
+        for (amount, schedule) in [
+            (amt.clone(), schedule(100, 100, 0)),
+            (amt.clone(), schedule(100, 0, 4)),
+            (TokenAmount::from_whole(0), schedule(100, 100, 4)),
+        ] {
+            let params = MintLockedParams { to: *ALICE, amount, operators: vec![], schedule };
+            rt.set_caller(*VERIFREG_ACTOR_CODE_ID, VERIFIED_REGISTRY_ACTOR_ADDR);
+            expect_abort(
+                ExitCode::USR_ILLEGAL_ARGUMENT,
+                rt.call::<Actor>(
+                    Method::MintLockedExported as MethodNum,
+                    IpldBlock::serialize_cbor(&params).unwrap(),
+                ),
+            );
+            rt.reset();
+        }
+
+        // Only the governor may mint.
+        let params = MintLockedParams {
+            to: *ALICE,
+            amount: amt,
+            operators: vec![],
+            schedule: schedule(100, 100, 4),
+        };
+        rt.expect_validate_caller_addr(vec![VERIFIED_REGISTRY_ACTOR_ADDR]);
+        rt.set_caller(*MARKET_ACTOR_CODE_ID, STORAGE_MARKET_ACTOR_ADDR);
+        expect_abort_contains_message(
+            ExitCode::USR_FORBIDDEN,
+            "caller address",
+            rt.call::<Actor>(
+                Method::MintLockedExported as MethodNum,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            ),
+        );
+        h.check_state(&rt);
+    }
+}
+
+mod allowance_terms {
+    use crate::{make_harness, ALICE, BOB};
+    use fil_actor_datacap::{
+        Actor, AllowanceTerms, IncreaseAllowanceWithTermsParams, Method, INFINITE_ALLOWANCE,
+    };
+    use fil_actors_runtime::test_utils::{
+        expect_abort, expect_abort_contains_message, ACCOUNT_ACTOR_CODE_ID,
+    };
+    use frc46_token::token::types::{BurnFromParams, GetAllowanceParams, IncreaseAllowanceParams};
+    use fvm_ipld_encoding::ipld_block::IpldBlock;
+    use fvm_ipld_encoding::RawBytes;
+    use fvm_shared::econ::TokenAmount;
+    use fvm_shared::error::ExitCode;
+    use fvm_shared::MethodNum;
+
+    fn terms(expiration: Option<i64>, max_per_transfer: Option<u64>) -> AllowanceTerms {
+        AllowanceTerms {
+            expiration,
+            max_per_transfer: max_per_transfer.map(TokenAmount::from_whole),
+        }
+    }
+
+    #[test]
+    fn expiring_allowance() {
+        let (rt, h) = make_harness();
+        let one = TokenAmount::from_whole(1);
+        h.mint(&rt, &ALICE, &TokenAmount::from_whole(10), vec![]).unwrap();
+        let ret = h
+            .increase_allowance_with_terms(
+                &rt,
+                &ALICE,
+                &BOB,
+                &TokenAmount::from_whole(5),
+                terms(Some(100), None),
+            )
+            .unwrap();
+        assert_eq!(TokenAmount::from_whole(5), ret.new_allowance);
+
+        rt.set_epoch(99);
+        h.transfer_from(&rt, &BOB, &ALICE, &h.governor, &one, RawBytes::default()).unwrap();
+        let ret = h.get_allowance_terms(&rt, &ALICE, &BOB);
+        assert_eq!(TokenAmount::from_whole(4), ret.allowance);
+        assert_eq!(terms(Some(100), None), ret.terms);
+
+        // The allowance can't be spent from its expiration.
+        rt.set_epoch(100);
+        expect_abort_contains_message(
+            ExitCode::USR_FORBIDDEN,
+            "expired at epoch 100",
+            h.transfer_from(&rt, &BOB, &ALICE, &h.governor, &one, RawBytes::default()),
+        );
+        rt.reset();
+        let ret = h.get_allowance_terms(&rt, &ALICE, &BOB);
+        assert_eq!(TokenAmount::from_whole(0), ret.allowance);
+        assert_eq!(terms(Some(100), None), ret.terms);
+        rt.expect_validate_caller_any();
+        let allowance: TokenAmount = rt
+            .call::<Actor>(
+                Method::AllowanceExported as MethodNum,
+                IpldBlock::serialize_cbor(&GetAllowanceParams { owner: *ALICE, operator: *BOB })
+                    .unwrap(),
+            )
+            .unwrap()
+            .unwrap()
+            .deserialize()
+            .unwrap();
+        assert_eq!(TokenAmount::from_whole(0), allowance);
+        rt.verify();
+
+        // Increasing an expired allowance starts from zero without terms.
+        rt.expect_validate_caller_any();
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, *ALICE);
+        rt.call::<Actor>(
+            Method::IncreaseAllowanceExported as MethodNum,
+            IpldBlock::serialize_cbor(&IncreaseAllowanceParams {
+                operator: *BOB,
+                increase: TokenAmount::from_whole(2),
+            })
+            .unwrap(),
+        )
+        .unwrap();
+        rt.verify();
+        let ret = h.get_allowance_terms(&rt, &ALICE, &BOB);
+        assert_eq!(TokenAmount::from_whole(2), ret.allowance);
+        assert_eq!(AllowanceTerms::default(), ret.terms);
+        h.transfer_from(&rt, &BOB, &ALICE, &h.governor, &one, RawBytes::default()).unwrap();
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn per_transfer_limit() {
+        let (rt, h) = make_harness();
+        h.mint(&rt, &ALICE, &TokenAmount::from_whole(10), vec![]).unwrap();
+        h.increase_allowance_with_terms(
+            &rt,
+            &ALICE,
+            &BOB,
+            &TokenAmount::from_whole(5),
+            terms(None, Some(2)),
+        )
+        .unwrap();
+
+        // Transfers and burns are both capped.
+        expect_abort_contains_message(
+            ExitCode::USR_FORBIDDEN,
+            "exceeds per-transfer limit",
+            h.transfer_from(
+                &rt,
+                &BOB,
+                &ALICE,
+                &h.governor,
+                &TokenAmount::from_whole(3),
+                RawBytes::default(),
+            ),
+        );
+        rt.reset();
+        let burn = |amount: u64| {
+            rt.expect_validate_caller_any();
+            rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, *BOB);
+            let params = BurnFromParams { owner: *ALICE, amount: TokenAmount::from_whole(amount) };
+            let ret = rt.call::<Actor>(
+                Method::BurnFromExported as MethodNum,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            );
+            rt.reset();
+            ret
+        };
+        expect_abort(ExitCode::USR_FORBIDDEN, burn(3));
+        burn(2).unwrap();
+        h.transfer_from(
+            &rt,
+            &BOB,
+            &ALICE,
+            &h.governor,
+            &TokenAmount::from_whole(2),
+            RawBytes::default(),
+        )
+        .unwrap();
+        assert_eq!(TokenAmount::from_whole(1), h.get_allowance_between(&rt, &ALICE, &BOB));
+
+        // Replacing the terms lifts the cap.
+        h.increase_allowance_with_terms(
+            &rt,
+            &ALICE,
+            &BOB,
+            &TokenAmount::from_whole(2),
+            terms(Some(1000), None),
+        )
+        .unwrap();
+        h.transfer_from(
+            &rt,
+            &BOB,
+            &ALICE,
+            &h.governor,
+            &TokenAmount::from_whole(3),
+            RawBytes::default(),
+        )
+        .unwrap();
+
+        // Revoking the allowance clears its terms.
+        h.revoke_allowance(&rt, &ALICE, &BOB).unwrap();
+        let ret = h.get_allowance_terms(&rt, &ALICE, &BOB);
+        assert_eq!(TokenAmount::from_whole(0), ret.allowance);
+        assert_eq!(AllowanceTerms::default(), ret.terms);
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn mint_resets_operator_terms() {
+        let (rt, h) = make_harness();
+        h.mint(&rt, &ALICE, &TokenAmount::from_whole(10), vec![]).unwrap();
+        h.increase_allowance_with_terms(
+            &rt,
+            &ALICE,
+            &BOB,
+            &TokenAmount::from_whole(5),
+            terms(Some(100), Some(1)),
+        )
+        .unwrap();
+
+        h.mint(&rt, &ALICE, &TokenAmount::from_whole(1), vec![*BOB]).unwrap();
+        let ret = h.get_allowance_terms(&rt, &ALICE, &BOB);
+        assert_eq!(*INFINITE_ALLOWANCE, ret.allowance);
+        assert_eq!(AllowanceTerms::default(), ret.terms);
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn rejects_invalid_terms() {
+        let (rt, h) = make_harness();
+        let five = TokenAmount::from_whole(5);
+        rt.set_epoch(100);
+        expect_abort_contains_message(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            "must be after current epoch",
+            h.increase_allowance_with_terms(&rt, &ALICE, &BOB, &five, terms(Some(100), None)),
+        );
+        rt.reset();
+        let params = IncreaseAllowanceWithTermsParams {
+            operator: *BOB,
+            increase: five.clone(),
+            terms: AllowanceTerms {
+                expiration: None,
+                max_per_transfer: Some(TokenAmount::from_atto(0)),
+            },
+        };
+        rt.expect_validate_caller_any();
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, *ALICE);
+        expect_abort_contains_message(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            "must be positive",
+            rt.call::<Actor>(
+                Method::IncreaseAllowanceWithTermsExported as MethodNum,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            ),
+        );
+        rt.reset();
+
+        // Unrestricted terms aren't stored.
+        h.increase_allowance_with_terms(&rt, &ALICE, &BOB, &five, AllowanceTerms::default())
+            .unwrap();
+        assert_eq!(five, h.get_allowance_terms(&rt, &ALICE, &BOB).allowance);
+        h.check_state(&rt);
+    }
+}
+
 fn make_harness() -> (MockRuntime, Harness) {
     let rt = new_runtime();
     let h = Harness { governor: VERIFIED_REGISTRY_ACTOR_ADDR };
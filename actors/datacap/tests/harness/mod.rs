@@ -2,7 +2,8 @@ use std::cell::RefCell;
 
 use frc46_token::receiver::{FRC46TokenReceived, FRC46_TOKEN_TYPE};
 use frc46_token::token::types::{
-    BurnReturn, MintReturn, TransferFromParams, TransferFromReturn, TransferParams, TransferReturn,
+    BurnReturn, GetAllowanceParams, MintReturn, RevokeAllowanceParams, TransferFromParams,
+    TransferFromReturn, TransferParams, TransferReturn,
 };
 use fvm_actor_utils::receiver::UniversalReceiverParams;
 use fvm_ipld_encoding::RawBytes;
@@ -13,7 +14,11 @@ use fvm_shared::MethodNum;
 use num_traits::Zero;
 
 use fil_actor_datacap::testing::check_state_invariants;
-use fil_actor_datacap::{Actor as DataCapActor, DestroyParams, Method, MintParams, State};
+use fil_actor_datacap::{
+    Actor as DataCapActor, AllowanceTerms, AllowanceTermsReturn, DestroyParams,
+    IncreaseAllowanceReturn, IncreaseAllowanceWithTermsParams, LockedBalanceReturn, Method,
+    MintLockedParams, MintParams, RevokeAllowanceReturn, State, VestingSchedule,
+};
 use fil_actors_runtime::cbor::serialize;
 use fil_actors_runtime::runtime::Runtime;
 use fil_actors_runtime::test_utils::*;
@@ -69,31 +74,7 @@ impl Harness {
         operators: Vec<Address>,
     ) -> Result<MintReturn, ActorError> {
         rt.expect_validate_caller_addr(vec![VERIFIED_REGISTRY_ACTOR_ADDR]);
-
-        // Expect the token receiver hook to be called.
-        let hook_params = UniversalReceiverParams {
-            type_: FRC46_TOKEN_TYPE,
-            payload: serialize(
-                &FRC46TokenReceived {
-                    from: DATACAP_TOKEN_ACTOR_ADDR.id().unwrap(),
-                    to: to.id().unwrap(),
-                    operator: VERIFIED_REGISTRY_ACTOR_ADDR.id().unwrap(),
-                    amount: amount.clone(),
-                    operator_data: Default::default(),
-                    token_data: Default::default(),
-                },
-                "hook payload",
-            )?,
-        };
-        // UniversalReceiverParams
-        rt.expect_send_simple(
-            *to,
-            frc42_dispatch::method_hash!("Receive"),
-            IpldBlock::serialize_cbor(&hook_params).unwrap(),
-            TokenAmount::zero(),
-            None,
-            ExitCode::OK,
-        );
+        expect_mint_hook(rt, to, amount)?;
 
         let params = MintParams { to: *to, amount: amount.clone(), operators };
         rt.set_caller(*VERIFREG_ACTOR_CODE_ID, VERIFIED_REGISTRY_ACTOR_ADDR);
@@ -106,6 +87,28 @@ impl Harness {
         Ok(ret.unwrap().deserialize().unwrap())
     }
 
+    pub fn mint_locked(
+        &self,
+        rt: &MockRuntime,
+        to: &Address,
+        amount: &TokenAmount,
+        operators: Vec<Address>,
+        schedule: VestingSchedule,
+    ) -> Result<MintReturn, ActorError> {
+        rt.expect_validate_caller_addr(vec![VERIFIED_REGISTRY_ACTOR_ADDR]);
+        expect_mint_hook(rt, to, amount)?;
+
+        let params = MintLockedParams { to: *to, amount: amount.clone(), operators, schedule };
+        rt.set_caller(*VERIFREG_ACTOR_CODE_ID, VERIFIED_REGISTRY_ACTOR_ADDR);
+        let ret = rt.call::<DataCapActor>(
+            Method::MintLockedExported as MethodNum,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        )?;
+
+        rt.verify();
+        Ok(ret.unwrap().deserialize().unwrap())
+    }
+
     pub fn destroy(
         &self,
         rt: &MockRuntime,
@@ -220,6 +223,50 @@ impl Harness {
         Ok(ret.unwrap().deserialize().unwrap())
     }
 
+    pub fn increase_allowance_with_terms(
+        &self,
+        rt: &MockRuntime,
+        owner: &Address,
+        operator: &Address,
+        increase: &TokenAmount,
+        terms: AllowanceTerms,
+    ) -> Result<IncreaseAllowanceReturn, ActorError> {
+        rt.expect_validate_caller_any();
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, *owner);
+
+        let params = IncreaseAllowanceWithTermsParams {
+            operator: *operator,
+            increase: increase.clone(),
+            terms,
+        };
+        let ret = rt.call::<DataCapActor>(
+            Method::IncreaseAllowanceWithTermsExported as MethodNum,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        )?;
+
+        rt.verify();
+        Ok(ret.unwrap().deserialize().unwrap())
+    }
+
+    pub fn revoke_allowance(
+        &self,
+        rt: &MockRuntime,
+        owner: &Address,
+        operator: &Address,
+    ) -> Result<RevokeAllowanceReturn, ActorError> {
+        rt.expect_validate_caller_any();
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, *owner);
+
+        let params = RevokeAllowanceParams { operator: *operator };
+        let ret = rt.call::<DataCapActor>(
+            Method::RevokeAllowanceExported as MethodNum,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        )?;
+
+        rt.verify();
+        Ok(ret.unwrap().deserialize().unwrap())
+    }
+
     // Reads the total supply from state directly.
     pub fn get_supply(&self, rt: &MockRuntime) -> TokenAmount {
         rt.get_state::<State>().token.supply
@@ -241,6 +288,45 @@ impl Harness {
         ret
     }
 
+    pub fn get_locked_balance(&self, rt: &MockRuntime, address: &Address) -> LockedBalanceReturn {
+        rt.expect_validate_caller_any();
+        let state_before = *rt.state.borrow();
+        let ret = rt
+            .call::<DataCapActor>(
+                Method::LockedBalanceExported as MethodNum,
+                IpldBlock::serialize_cbor(&address).unwrap(),
+            )
+            .unwrap()
+            .unwrap()
+            .deserialize()
+            .unwrap();
+        rt.verify();
+        // The getter doesn't modify state, even to prune unlocked grants.
+        assert_eq!(state_before, *rt.state.borrow());
+        ret
+    }
+
+    pub fn get_allowance_terms(
+        &self,
+        rt: &MockRuntime,
+        owner: &Address,
+        operator: &Address,
+    ) -> AllowanceTermsReturn {
+        rt.expect_validate_caller_any();
+        let params = GetAllowanceParams { owner: *owner, operator: *operator };
+        let ret = rt
+            .call::<DataCapActor>(
+                Method::AllowanceTermsExported as MethodNum,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            )
+            .unwrap()
+            .unwrap()
+            .deserialize()
+            .unwrap();
+        rt.verify();
+        ret
+    }
+
     // Reads allowance from state directly
     pub fn get_allowance_between(
         &self,
@@ -259,3 +345,35 @@ impl Harness {
         acc.assert_empty();
     }
 }
+
+// Expects the token receiver hook to be called for tokens minted by the governor.
+fn expect_mint_hook(
+    rt: &MockRuntime,
+    to: &Address,
+    amount: &TokenAmount,
+) -> Result<(), ActorError> {
+    let hook_params = UniversalReceiverParams {
+        type_: FRC46_TOKEN_TYPE,
+        payload: serialize(
+            &FRC46TokenReceived {
+                from: DATACAP_TOKEN_ACTOR_ADDR.id().unwrap(),
+                to: to.id().unwrap(),
+                operator: VERIFIED_REGISTRY_ACTOR_ADDR.id().unwrap(),
+                amount: amount.clone(),
+                operator_data: Default::default(),
+                token_data: Default::default(),
+            },
+            "hook payload",
+        )?,
+    };
+    // UniversalReceiverParams
+    rt.expect_send_simple(
+        *to,
+        frc42_dispatch::method_hash!("Receive"),
+        IpldBlock::serialize_cbor(&hook_params).unwrap(),
+        TokenAmount::zero(),
+        None,
+        ExitCode::OK,
+    );
+    Ok(())
+}
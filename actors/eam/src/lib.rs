@@ -9,17 +9,18 @@ use ext::{
     init::{Exec4Params, Exec4Return},
 };
 use fil_actors_runtime::{
-    actor_dispatch_unrestricted, actor_error, deserialize_block, extract_send_result, ActorError,
-    AsActorError, EAM_ACTOR_ID, INIT_ACTOR_ADDR, SYSTEM_ACTOR_ADDR,
+    actor_dispatch_unrestricted, actor_error, deserialize_block, dispatch_actor_info,
+    extract_send_result, ActorError, AsActorError, EAM_ACTOR_ID, INIT_ACTOR_ADDR,
+    SYSTEM_ACTOR_ADDR,
 };
 
 use fvm_ipld_encoding::ipld_block::IpldBlock;
-use fvm_shared::{error::ExitCode, sys::SendFlags, ActorID, METHOD_CONSTRUCTOR};
+use fvm_shared::{error::ExitCode, sys::SendFlags, ActorID, MethodNum, METHOD_CONSTRUCTOR};
 use serde::{Deserialize, Serialize};
 
 pub mod ext;
 
-use fil_actors_runtime::runtime::builtins::Type;
+use fil_actors_runtime::runtime::builtins::{ActorType, Type};
 use fil_actors_runtime::runtime::{ActorCode, Runtime};
 
 use fvm_ipld_encoding::{strict_bytes, tuple::*, RawBytes};
@@ -37,8 +38,16 @@ pub enum Method {
     Create = 2,
     Create2 = 3,
     CreateExternal = 4,
+    CreateDeterministic = 5,
+    // Method numbers derived from FRC-0042 standards
+    CreateDeterministicExported = frc42_dispatch::method_hash!("CreateDeterministic"),
+    GetActorInfo = frc42_dispatch::method_hash!("GetActorInfo"),
 }
 
+/// Initcode of the proxy used by CREATE3 factories, which deploys its calldata as initcode with
+/// CREATE. Only its hash feeds into the derived address.
+const CREATE3_PROXY_INITCODE: [u8; 16] = hex_literal::hex!("67363d3d37363d34f03d5260086018f3");
+
 /// Compute the a new actor address using the EVM's CREATE rules.
 pub fn compute_address_create(rt: &impl Runtime, from: &EthAddress, nonce: u64) -> EthAddress {
     let mut stream = rlp::RlpStream::new();
@@ -57,6 +66,20 @@ pub fn compute_address_create2(
     EthAddress(hash_20(rt, &[&[0xff], &from.0[..], salt, &inithash].concat()))
 }
 
+/// Compute the a new actor address from the deployer and a salt alone, per the CREATE3 rules.
+///
+/// This is the address at which a proxy, itself deployed with CREATE2 from the deployer and salt,
+/// would deploy the contract with its first CREATE. No proxy is actually deployed, but the address
+/// matches that of a CREATE3 factory deployment on other EVM chains.
+pub fn compute_address_create3(
+    rt: &impl Runtime,
+    from: &EthAddress,
+    salt: &[u8; 32],
+) -> EthAddress {
+    let proxy = compute_address_create2(rt, from, salt, &CREATE3_PROXY_INITCODE);
+    compute_address_create(rt, &proxy, 1)
+}
+
 pub fn compute_address_create_external(rt: &impl Runtime, from: &EthAddress) -> EthAddress {
     compute_address_create(rt, from, rt.message().nonce())
 }
@@ -76,6 +99,14 @@ pub struct Create2Params {
     pub salt: [u8; 32],
 }
 
+#[derive(Serialize_tuple, Deserialize_tuple)]
+pub struct CreateDeterministicParams {
+    #[serde(with = "strict_bytes")]
+    pub initcode: Vec<u8>,
+    #[serde(with = "strict_bytes")]
+    pub salt: [u8; 32],
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct CreateExternalParams(#[serde(with = "strict_bytes")] pub Vec<u8>);
@@ -90,6 +121,7 @@ pub struct Return {
 pub type CreateReturn = Return;
 pub type Create2Return = Return;
 pub type CreateExternalReturn = Return;
+pub type CreateDeterministicReturn = Return;
 
 impl Return {
     fn from_exec4(exec4: Exec4Return, eth_address: EthAddress) -> Self {
@@ -132,9 +164,9 @@ fn create_actor(
     if let Some(id) = rt.resolve_address(&f4_addr) {
         // Try to resurrect it if it is already an EVM actor (must be "dead")
         let caller_code_cid = rt.get_actor_code_cid(&id).expect("failed to lookup actor code");
-        match rt.resolve_builtin_actor_type(&caller_code_cid) {
+        match rt.resolve_builtin_actor_type_checked(&caller_code_cid) {
             // If it's an EVM actor, resurrect it.
-            Some(Type::EVM) => {
+            ActorType::EVM => {
                 extract_send_result(rt.send_simple(
                     &Address::new_id(id),
                     RESURRECT_METHOD,
@@ -144,9 +176,23 @@ fn create_actor(
                 return Ok(Return { actor_id: id, robust_address: None, eth_address: new_addr });
             }
             // If it's a Placeholder, continue on to create it.
-            Some(Type::Placeholder) => {}
+            ActorType::Placeholder => {}
             // Otherwise, return an error.
-            _ => {
+            ActorType::System
+            | ActorType::Init
+            | ActorType::Cron
+            | ActorType::Account
+            | ActorType::Power
+            | ActorType::Miner
+            | ActorType::Market
+            | ActorType::PaymentChannel
+            | ActorType::Multisig
+            | ActorType::Reward
+            | ActorType::VerifiedRegistry
+            | ActorType::DataCap
+            | ActorType::EAM
+            | ActorType::EthAccount
+            | ActorType::Unknown => {
                 return Err(
                     actor_error!(forbidden; "cannot deploy contract over existing contract at address {new_addr}"),
                 );
@@ -187,8 +233,8 @@ fn resolve_caller_external(rt: &impl Runtime) -> Result<(EthAddress, EthAddress)
     let caller = rt.message().caller();
     let caller_id = caller.id().unwrap();
     let caller_code_cid = rt.get_actor_code_cid(&caller_id).expect("failed to lookup caller code");
-    match rt.resolve_builtin_actor_type(&caller_code_cid) {
-        Some(Type::Account) => {
+    match rt.resolve_builtin_actor_type_checked(&caller_code_cid) {
+        ActorType::Account => {
             let result = rt
                 .send(
                     &caller,
@@ -215,12 +261,27 @@ fn resolve_caller_external(rt: &impl Runtime) -> Result<(EthAddress, EthAddress)
 
             Ok((EthAddress::from_id(caller_id), EthAddress(robust_eth_bytes)))
         }
-        Some(Type::EthAccount) => {
+        ActorType::EthAccount => {
             let addr = resolve_eth_address(rt, caller_id)?;
             Ok((addr, addr))
         }
-        Some(t) => Err(ActorError::forbidden(format!("disallowed caller type {}", t.name()))),
-        None => Err(ActorError::forbidden(format!("disallowed caller code {caller_code_cid}"))),
+        t @ (ActorType::System
+        | ActorType::Init
+        | ActorType::Cron
+        | ActorType::Power
+        | ActorType::Miner
+        | ActorType::Market
+        | ActorType::PaymentChannel
+        | ActorType::Multisig
+        | ActorType::Reward
+        | ActorType::VerifiedRegistry
+        | ActorType::DataCap
+        | ActorType::Placeholder
+        | ActorType::EVM
+        | ActorType::EAM) => Err(ActorError::forbidden(format!("disallowed caller type {:?}", t))),
+        ActorType::Unknown => {
+            Err(ActorError::forbidden(format!("disallowed caller code {caller_code_cid}")))
+        }
     }
 }
 
@@ -286,6 +347,52 @@ impl EamActor {
         let eth_addr = compute_address_create_external(rt, &stable_addr);
         create_actor(rt, owner_addr, eth_addr, params.0)
     }
+
+    /// Create a new contract at an address derived from the deployer and a salt, per the CREATE3
+    /// rules. Unlike CREATE2, the address doesn't depend on the initcode, so the same deployer can
+    /// deploy different code at the same address on different networks.
+    ///
+    /// When called by an EVM contract, the deployer is the contract's eth address. When called by
+    /// an account, the deployer is derived as for `CreateExternal`.
+    ///
+    /// Permissions: May be called by the EVM, or by builtin or eth accounts.
+    pub fn create_deterministic(
+        rt: &impl Runtime,
+        params: CreateDeterministicParams,
+    ) -> Result<CreateDeterministicReturn, ActorError> {
+        // The caller's type is checked below.
+        rt.validate_immediate_caller_accept_any()?;
+
+        let caller_id = rt.message().caller().id().unwrap();
+        let caller_code_cid =
+            rt.get_actor_code_cid(&caller_id).expect("failed to lookup caller code");
+        let caller_type = rt.resolve_builtin_actor_type_checked(&caller_code_cid);
+        let (owner_addr, stable_addr) = match caller_type {
+            ActorType::EVM => {
+                let addr = resolve_eth_address(rt, caller_id)?;
+                (addr, addr)
+            }
+            // Otherwise, we only accept calls by top-level accounts, whose type is checked when
+            // resolving their addresses.
+            _ if rt.message().caller() == rt.message().origin() => resolve_caller_external(rt)?,
+            _ => {
+                return Err(
+                    actor_error!(forbidden; "caller must be an EVM actor or top-level account"),
+                )
+            }
+        };
+
+        let eth_addr = compute_address_create3(rt, &stable_addr, &params.salt);
+        create_actor(rt, owner_addr, eth_addr, params.initcode)
+    }
+    /// Describes the actor's code. Routed explicitly, as the unrestricted dispatch doesn't.
+    pub fn get_actor_info(
+        rt: &impl Runtime,
+        _method: MethodNum,
+        args: Option<IpldBlock>,
+    ) -> Result<Option<IpldBlock>, ActorError> {
+        dispatch_actor_info::<Self, _>(rt, args)
+    }
 }
 
 impl ActorCode for EamActor {
@@ -295,11 +402,18 @@ impl ActorCode for EamActor {
         "EVMAddressManager"
     }
 
+    fil_actors_runtime::actor_info! {
+        interface_revisions: [1],
+        features: [],
+    }
+
     actor_dispatch_unrestricted! {
         Constructor => constructor,
         Create => create,
         Create2 => create2,
         CreateExternal => create_external,
+        CreateDeterministic|CreateDeterministicExported => create_deterministic,
+        GetActorInfo => get_actor_info [raw],
     }
 }
 
@@ -308,7 +422,7 @@ mod test {
     use fil_actors_runtime::test_utils::MockRuntime;
     use fvm_shared::error::ExitCode;
 
-    use crate::compute_address_create2;
+    use crate::{compute_address_create2, compute_address_create3};
 
     use super::{compute_address_create, create_actor, EthAddress};
 
@@ -383,4 +497,21 @@ mod test {
             assert_eq!(result.0[..], expected[..]);
         }
     }
+
+    #[test]
+    fn test_create_address3() {
+        let rt = MockRuntime::default();
+        // check addresses against externally generated cases
+        for (from, salt, expected) in &[
+            ([0u8; 20], [0u8; 32], hex_literal::hex!("1d5153834339f0a751ce73318987dea1812c83ce")),
+            (
+                [0x99u8; 20],
+                [0x42; 32],
+                hex_literal::hex!("de6fab391b0859337e2dfbc8a2f5f52349b3f2f6"),
+            ),
+        ] {
+            let result = compute_address_create3(&rt, &EthAddress(*from), salt);
+            assert_eq!(result.0[..], expected[..]);
+        }
+    }
 }
@@ -1,14 +1,17 @@
 use eam::ext::evm::RESURRECT_METHOD;
 use eam::ext::init::{Exec4Params, Exec4Return, EXEC4_METHOD};
-use eam::{compute_address_create, Create2Params, CreateParams, Return};
+use eam::{
+    compute_address_create, compute_address_create3, Create2Params, CreateDeterministicParams,
+    CreateParams, Return,
+};
 use fil_actor_eam as eam;
 use fil_actor_eam::CreateExternalParams;
 use fil_actors_evm_shared::address::EthAddress;
 use fil_actors_runtime::runtime::builtins::Type;
-use fil_actors_runtime::runtime::Primitives;
+use fil_actors_runtime::runtime::{ActorInfo, Primitives, GET_ACTOR_INFO_METHOD};
 use fil_actors_runtime::test_utils::{
-    expect_empty, MockRuntime, ETHACCOUNT_ACTOR_CODE_ID, EVM_ACTOR_CODE_ID,
-    PLACEHOLDER_ACTOR_CODE_ID, SYSTEM_ACTOR_CODE_ID,
+    expect_empty, make_identity_cid, MockRuntime, ETHACCOUNT_ACTOR_CODE_ID, EVM_ACTOR_CODE_ID,
+    MULTISIG_ACTOR_CODE_ID, PLACEHOLDER_ACTOR_CODE_ID, SYSTEM_ACTOR_CODE_ID,
 };
 use fil_actors_runtime::{INIT_ACTOR_ADDR, SYSTEM_ACTOR_ADDR};
 use fvm_ipld_encoding::ipld_block::IpldBlock;
@@ -147,6 +150,59 @@ fn call_create_external_over_placeholder() {
     rt.verify();
 }
 
+#[test]
+fn call_create_external_over_existing_actor() {
+    let rt = construct_and_verify();
+
+    let caller_id_addr = Address::new_id(110);
+    let caller_eth_addr = EthAddress(hex_literal::hex!("CAFEB0BA00000000000000000000000000000000"));
+    let caller_f4_eth_addr = Address::new_delegated(10, &caller_eth_addr.0).unwrap();
+    rt.set_delegated_address(caller_id_addr.id().unwrap(), caller_f4_eth_addr);
+    rt.set_caller(*ETHACCOUNT_ACTOR_CODE_ID, caller_id_addr);
+    rt.set_origin(caller_id_addr);
+
+    let target_id_addr = Address::new_id(111);
+    let target_eth_addr = compute_address_create(&rt, &caller_eth_addr, 0);
+    let target_f4_eth_addr = Address::new_delegated(10, &target_eth_addr.0).unwrap();
+    rt.set_delegated_address(target_id_addr.id().unwrap(), target_f4_eth_addr);
+
+    // Only placeholders may be replaced, and only EVM actors resurrected.
+    for code in [*ETHACCOUNT_ACTOR_CODE_ID, make_identity_cid(b"unknown")] {
+        rt.set_address_actor_type(target_id_addr, code);
+        rt.expect_validate_caller_addr(vec![caller_id_addr]);
+        let err = rt
+            .call::<eam::EamActor>(
+                eam::Method::CreateExternal as u64,
+                IpldBlock::serialize_cbor(&CreateExternalParams(vec![0xff])).unwrap(),
+            )
+            .unwrap_err();
+        assert_eq!(ExitCode::USR_FORBIDDEN, err.exit_code());
+        rt.verify();
+    }
+}
+
+#[test]
+fn call_create_external_by_disallowed_caller() {
+    let rt = construct_and_verify();
+
+    let caller_id_addr = Address::new_id(110);
+    rt.set_origin(caller_id_addr);
+
+    // Only accounts and eth accounts may create contracts from off-chain.
+    for code in [*MULTISIG_ACTOR_CODE_ID, make_identity_cid(b"unknown")] {
+        rt.set_caller(code, caller_id_addr);
+        rt.expect_validate_caller_addr(vec![caller_id_addr]);
+        let err = rt
+            .call::<eam::EamActor>(
+                eam::Method::CreateExternal as u64,
+                IpldBlock::serialize_cbor(&CreateExternalParams(vec![0xff])).unwrap(),
+            )
+            .unwrap_err();
+        assert_eq!(ExitCode::USR_FORBIDDEN, err.exit_code());
+        rt.verify();
+    }
+}
+
 #[test]
 fn call_resurrect() {
     let rt = construct_and_verify();
@@ -266,6 +322,114 @@ fn call_create2() {
     rt.verify();
 }
 
+#[test]
+fn call_create_deterministic() {
+    let rt = construct_and_verify();
+
+    let id_addr = Address::new_id(110);
+    let eth_addr = EthAddress(hex_literal::hex!("CAFEB0BA00000000000000000000000000000000"));
+    let f4_eth_addr = Address::new_delegated(10, &eth_addr.0).unwrap();
+    rt.set_delegated_address(id_addr.id().unwrap(), f4_eth_addr);
+
+    rt.set_caller(*EVM_ACTOR_CODE_ID, id_addr);
+
+    let salt = [0; 32];
+    let new_eth_addr = compute_address_create3(&rt, &eth_addr, &salt);
+    assert_eq!(new_eth_addr.0, hex_literal::hex!("30bb3ab9e13a5541b6a564aea4fecfd73dede982"));
+
+    // The address is the same whatever the initcode.
+    for initcode in [vec![0xff], vec![0x60, 0x00]] {
+        rt.expect_validate_caller_any();
+
+        let evm_params = eam::ext::evm::ConstructorParams {
+            creator: eth_addr,
+            initcode: initcode.clone().into(),
+        };
+        let params = Exec4Params {
+            code_cid: *EVM_ACTOR_CODE_ID,
+            constructor_params: RawBytes::serialize(evm_params).unwrap(),
+            subaddress: new_eth_addr.0[..].to_owned().into(),
+        };
+
+        let send_return = IpldBlock::serialize_cbor(&Exec4Return {
+            id_address: Address::new_id(111),
+            robust_address: Address::new_id(0), // not a robust address but im hacking here and nobody checks
+        })
+        .unwrap();
+
+        rt.expect_send_simple(
+            INIT_ACTOR_ADDR,
+            EXEC4_METHOD,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+            TokenAmount::from_atto(0),
+            send_return,
+            ExitCode::OK,
+        );
+
+        let create_params = CreateDeterministicParams { initcode, salt };
+        let result = rt
+            .call::<eam::EamActor>(
+                eam::Method::CreateDeterministicExported as u64,
+                IpldBlock::serialize_cbor(&create_params).unwrap(),
+            )
+            .unwrap()
+            .unwrap()
+            .deserialize::<Return>()
+            .unwrap();
+
+        let expected_return = Return {
+            actor_id: 111,
+            robust_address: Some(Address::new_id(0)),
+            eth_address: new_eth_addr,
+        };
+
+        assert_eq!(result, expected_return);
+        rt.verify();
+        rt.reset();
+    }
+}
+
+#[test]
+fn call_create_deterministic_by_internal_account() {
+    let rt = construct_and_verify();
+
+    let caller_id_addr = Address::new_id(110);
+    let caller_eth_addr = EthAddress(hex_literal::hex!("CAFEB0BA00000000000000000000000000000000"));
+    let caller_f4_eth_addr = Address::new_delegated(10, &caller_eth_addr.0).unwrap();
+    rt.set_delegated_address(caller_id_addr.id().unwrap(), caller_f4_eth_addr);
+
+    // Accounts may only call CreateDeterministic directly, not through other actors.
+    rt.set_caller(*ETHACCOUNT_ACTOR_CODE_ID, caller_id_addr);
+    rt.set_origin(Address::new_id(112));
+
+    rt.expect_validate_caller_any();
+    let create_params = CreateDeterministicParams { initcode: vec![0xff], salt: [0; 32] };
+    let err = rt
+        .call::<eam::EamActor>(
+            eam::Method::CreateDeterministic as u64,
+            IpldBlock::serialize_cbor(&create_params).unwrap(),
+        )
+        .unwrap_err();
+    assert_eq!(ExitCode::USR_FORBIDDEN, err.exit_code());
+    rt.verify();
+}
+
+#[test]
+fn get_actor_info() {
+    let rt = construct_and_verify();
+
+    rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(110));
+    rt.expect_validate_caller_any();
+    let info: ActorInfo = rt
+        .call::<eam::EamActor>(GET_ACTOR_INFO_METHOD, None)
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    assert_eq!(vec![1], info.interface_revisions);
+}
+
 pub fn construct_and_verify() -> MockRuntime {
     let rt = MockRuntime { receiver: Address::new_id(10), ..Default::default() };
 
@@ -2,6 +2,8 @@ pub mod types;
 
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_shared::address::Payload;
+use fvm_shared::crypto::hash::SupportedHashes;
+use fvm_shared::crypto::signature::{SECP_SIG_LEN, SECP_SIG_MESSAGE_HASH_SIZE};
 use fvm_shared::{MethodNum, METHOD_CONSTRUCTOR};
 use num_derive::FromPrimitive;
 
@@ -11,6 +13,8 @@ use fil_actors_runtime::{
     SYSTEM_ACTOR_ADDR,
 };
 
+use crate::types::AuthenticateMessageParams;
+
 #[cfg(feature = "fil-actor")]
 fil_actors_runtime::wasm_trampoline!(EthAccountActor);
 
@@ -19,6 +23,7 @@ fil_actors_runtime::wasm_trampoline!(EthAccountActor);
 #[repr(u64)]
 pub enum Method {
     Constructor = METHOD_CONSTRUCTOR,
+    AuthenticateMessageExported = frc42_dispatch::method_hash!("AuthenticateMessage"),
 }
 
 /// Ethereum Account actor.
@@ -50,6 +55,52 @@ impl EthAccountActor {
         Ok(())
     }
 
+    /// Authenticates whether the provided signature is valid for the provided message.
+    /// The signature is a 65-byte secp256k1 signature `[r, s, v]` over the EIP-191 personal
+    /// message hash of the message, as produced by Ethereum wallets' `personal_sign`, from the key
+    /// whose Ethereum address is this actor's delegated address. The recovery id `v` may be
+    /// either 0/1 or the legacy 27/28.
+    /// Errors with USR_ILLEGAL_ARGUMENT if the authentication is invalid.
+    pub fn authenticate_message(
+        rt: &impl Runtime,
+        params: AuthenticateMessageParams,
+    ) -> Result<bool, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let eth_address = match rt
+            .lookup_delegated_address(rt.message().receiver().id().unwrap())
+            .map(|a| *a.payload())
+        {
+            Some(Payload::Delegated(da)) if da.namespace() == EAM_ACTOR_ID => {
+                da.subaddress().to_vec()
+            }
+            _ => return Err(actor_error!(illegal_state; "account has no Ethereum address")),
+        };
+
+        let mut signature: [u8; SECP_SIG_LEN] =
+            params.signature.as_slice().try_into().map_err(|_| {
+                actor_error!(illegal_argument; "invalid signature length {}", params.signature.len())
+            })?;
+        // Wallets commonly encode the recovery id with the legacy offset of 27.
+        if let v @ 27..=28 = signature[SECP_SIG_LEN - 1] {
+            signature[SECP_SIG_LEN - 1] = v - 27;
+        }
+        let mut hash = [0u8; SECP_SIG_MESSAGE_HASH_SIZE];
+        hash.copy_from_slice(
+            &rt.hash(SupportedHashes::Keccak256, &personal_message(&params.message)),
+        );
+        let pubkey = rt.recover_secp_public_key(&hash, &signature).map_err(|e| {
+            actor_error!(illegal_argument; "failed to authenticate message, signature invalid: {}", e)
+        })?;
+
+        // The Ethereum address is the last 20 bytes of the hash of the uncompressed public key.
+        let signer = rt.hash(SupportedHashes::Keccak256, &pubkey[1..]);
+        if signer[12..] != eth_address[..] {
+            return Err(actor_error!(illegal_argument;
+                "failed to authenticate message, signature invalid"));
+        }
+        Ok(true)
+    }
+
     // Always succeeds, accepting any transfers.
     pub fn fallback(
         rt: &impl Runtime,
@@ -65,6 +116,14 @@ impl EthAccountActor {
     }
 }
 
+/// Prefixes a message as an EIP-191 (version 0x45) personal message, so that a signature over it
+/// can't be mistaken for a signature over an Ethereum transaction.
+fn personal_message(message: &[u8]) -> Vec<u8> {
+    let mut prefixed = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+    prefixed.extend_from_slice(message);
+    prefixed
+}
+
 impl ActorCode for EthAccountActor {
     type Methods = Method;
 
@@ -72,8 +131,14 @@ impl ActorCode for EthAccountActor {
         "EVMAccount"
     }
 
+    fil_actors_runtime::actor_info! {
+        interface_revisions: [1],
+        features: [],
+    }
+
     actor_dispatch! {
         Constructor => constructor,
+        AuthenticateMessageExported => authenticate_message,
         _ => fallback,
     }
 }
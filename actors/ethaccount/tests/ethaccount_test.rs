@@ -46,3 +46,119 @@ fn token_receiver() {
         .unwrap();
     assert!(ret.is_none());
 }
+
+mod authenticate_message {
+    use fil_actor_ethaccount::types::AuthenticateMessageParams;
+    use fil_actor_ethaccount::{EthAccountActor, Method};
+    use fil_actors_runtime::runtime::Primitives;
+    use fil_actors_runtime::test_utils::{MockRuntime, ACCOUNT_ACTOR_CODE_ID};
+    use fil_actors_runtime::EAM_ACTOR_ID;
+    use fvm_ipld_encoding::ipld_block::IpldBlock;
+    use fvm_shared::address::Address;
+    use fvm_shared::crypto::hash::SupportedHashes;
+    use fvm_shared::MethodNum;
+    use libsecp256k1::{sign, Message, PublicKey, SecretKey};
+
+    use crate::util::*;
+
+    // Returns the 65-byte [r, s, v] signature over the EIP-191 personal message hash of the
+    // message, as personal_sign produces it but with a recovery id of 0 or 1.
+    fn eth_sign(rt: &MockRuntime, key: &SecretKey, message: &[u8]) -> Vec<u8> {
+        let mut prefixed = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+        prefixed.extend_from_slice(message);
+        sign_hash(rt, key, &prefixed)
+    }
+
+    // Returns the 65-byte [r, s, v] signature over the Keccak-256 hash of the bytes.
+    fn sign_hash(rt: &MockRuntime, key: &SecretKey, bytes: &[u8]) -> Vec<u8> {
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(&rt.hash(SupportedHashes::Keccak256, bytes));
+        let (sig, recovery_id) = sign(&Message::parse(&hash), key);
+        let mut bytes = sig.serialize().to_vec();
+        bytes.push(recovery_id.serialize());
+        bytes
+    }
+
+    // Sets up an account whose delegated address is the Ethereum address of the key.
+    fn setup_with_key(key: &SecretKey) -> MockRuntime {
+        let rt = setup();
+        let pubkey = PublicKey::from_secret_key(key).serialize();
+        let eth_address = rt.hash(SupportedHashes::Keccak256, &pubkey[1..]);
+        rt.set_delegated_address(
+            EOA.id().unwrap(),
+            Address::new_delegated(EAM_ACTOR_ID, &eth_address[12..]).unwrap(),
+        );
+        rt
+    }
+
+    fn authenticate(rt: &MockRuntime, signature: Vec<u8>, message: &[u8]) -> Result<bool, String> {
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, Address::new_id(1234));
+        rt.expect_validate_caller_any();
+        let ret = rt.call::<EthAccountActor>(
+            Method::AuthenticateMessageExported as MethodNum,
+            IpldBlock::serialize_cbor(&AuthenticateMessageParams {
+                signature,
+                message: message.to_vec(),
+            })
+            .unwrap(),
+        );
+        rt.verify();
+        ret.map(|r| r.unwrap().deserialize().unwrap()).map_err(|e| e.msg().to_string())
+    }
+
+    #[test]
+    fn valid_signature() {
+        let key = SecretKey::parse(&[7u8; 32]).unwrap();
+        let rt = setup_with_key(&key);
+        let message = b"voucher bytes";
+        assert_eq!(Ok(true), authenticate(&rt, eth_sign(&rt, &key, message), message));
+    }
+
+    #[test]
+    fn legacy_recovery_id() {
+        let key = SecretKey::parse(&[7u8; 32]).unwrap();
+        let rt = setup_with_key(&key);
+        let message = b"voucher bytes";
+        let mut signature = eth_sign(&rt, &key, message);
+        signature[64] += 27;
+        assert_eq!(Ok(true), authenticate(&rt, signature, message));
+    }
+
+    #[test]
+    fn signature_without_personal_message_prefix() {
+        let key = SecretKey::parse(&[7u8; 32]).unwrap();
+        let rt = setup_with_key(&key);
+        let message = b"voucher bytes";
+        let err = authenticate(&rt, sign_hash(&rt, &key, message), message).unwrap_err();
+        assert!(err.contains("signature invalid"), "{}", err);
+    }
+
+    #[test]
+    fn signature_from_other_key() {
+        let key = SecretKey::parse(&[7u8; 32]).unwrap();
+        let other = SecretKey::parse(&[8u8; 32]).unwrap();
+        let rt = setup_with_key(&key);
+        let message = b"voucher bytes";
+        let err = authenticate(&rt, eth_sign(&rt, &other, message), message).unwrap_err();
+        assert!(err.contains("signature invalid"), "{}", err);
+    }
+
+    #[test]
+    fn signature_over_other_message() {
+        let key = SecretKey::parse(&[7u8; 32]).unwrap();
+        let rt = setup_with_key(&key);
+        let signature = eth_sign(&rt, &key, b"voucher bytes");
+        assert!(authenticate(&rt, signature, b"other bytes").is_err());
+    }
+
+    #[test]
+    fn malformed_signature() {
+        let key = SecretKey::parse(&[7u8; 32]).unwrap();
+        let rt = setup_with_key(&key);
+        let message = b"voucher bytes";
+        let mut signature = eth_sign(&rt, &key, message);
+        signature.pop();
+        let err = authenticate(&rt, signature, message).unwrap_err();
+        assert!(err.contains("invalid signature length 64"), "{}", err);
+    }
+}
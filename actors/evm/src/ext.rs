@@ -28,3 +28,17 @@ pub mod eam {
         pub eth_address: EthAddress,
     }
 }
+
+pub mod cron {
+    use fvm_ipld_encoding::tuple::*;
+    use fvm_shared::MethodNum;
+
+    pub const REGISTER_ENTRY_METHOD: u64 = 4;
+    pub const DEREGISTER_ENTRY_METHOD: u64 = 5;
+
+    #[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, PartialEq, Eq)]
+    #[serde(transparent)]
+    pub struct RegisterEntryParams {
+        pub method_num: MethodNum,
+    }
+}
@@ -163,6 +163,9 @@ pub mod opcodes {
         0x59: MSIZE,
         0x5a: GAS,
         0x5b: JUMPDEST,
+        0x5c: TLOAD,
+        0x5d: TSTORE,
+        0x5e: MCOPY,
         0x5F: PUSH0,
         0x60: PUSH1,
         0x61: PUSH2,
@@ -399,6 +402,9 @@ mod tests {
             MSTORE8,
             SLOAD,
             SSTORE,
+            TLOAD,
+            TSTORE,
+            MCOPY,
             LOG0,
             LOG1,
             LOG2,
@@ -4,7 +4,7 @@ use crate::BytecodeHash;
 use cid::Cid;
 use fil_actors_evm_shared::address::EthAddress;
 use fil_actors_evm_shared::uints::U256;
-use fil_actors_runtime::runtime::builtins::Type;
+use fil_actors_runtime::runtime::builtins::ActorType;
 use fil_actors_runtime::ActorError;
 use fil_actors_runtime::{deserialize_block, AsActorError};
 use fvm_ipld_blockstore::Blockstore;
@@ -110,12 +110,26 @@ pub fn get_contract_type<RT: Runtime>(rt: &RT, addr: &EthAddress) -> ContractTyp
     let addr: Address = addr.into();
     rt.resolve_address(&addr) // resolve actor id
         .and_then(|id| rt.get_actor_code_cid(&id).map(|cid| (id, cid))) // resolve code cid
-        .map(|(id, cid)| match rt.resolve_builtin_actor_type(&cid) {
+        .map(|(id, cid)| match rt.resolve_builtin_actor_type_checked(&cid) {
             // TODO part of current account abstraction hack where placeholders are accounts
-            Some(Type::Account | Type::Placeholder | Type::EthAccount) => ContractType::Account,
-            Some(Type::EVM) => ContractType::EVM(Address::new_id(id)),
-            // remaining builtin actors are native
-            _ => ContractType::Native(cid),
+            ActorType::Account | ActorType::Placeholder | ActorType::EthAccount => {
+                ContractType::Account
+            }
+            ActorType::EVM => ContractType::EVM(Address::new_id(id)),
+            // remaining actors are native
+            ActorType::System
+            | ActorType::Init
+            | ActorType::Cron
+            | ActorType::Power
+            | ActorType::Miner
+            | ActorType::Market
+            | ActorType::PaymentChannel
+            | ActorType::Multisig
+            | ActorType::Reward
+            | ActorType::VerifiedRegistry
+            | ActorType::DataCap
+            | ActorType::EAM
+            | ActorType::Unknown => ContractType::Native(cid),
         })
         .unwrap_or(ContractType::NotFound)
 }
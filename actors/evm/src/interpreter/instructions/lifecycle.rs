@@ -101,7 +101,7 @@ fn create_common(
     // Then we explicitly check the endowment. We could just try and deal with the error, but then
     // we'd need to add some logic for decrementing the nonce. It's easier to check up-front.
     let endowment = TokenAmount::from(&endowment);
-    if endowment > system.rt.current_balance() {
+    if endowment > system.spendable_balance() {
         return Ok(U256::zero());
     }
 
@@ -166,7 +166,7 @@ pub fn selfdestruct(
     // 3. This call would cause us to exceed some system limit (e.g., a memory limit).
     let beneficiary: EthAddress = beneficiary.into();
     let beneficiary: Address = beneficiary.into();
-    let balance = system.rt.current_balance();
+    let balance = system.spendable_balance();
     extract_send_result(system.rt.send_simple(&beneficiary, METHOD_SEND, None, balance)).map_err(
         |e| {
             ActorError::unchecked(
@@ -134,6 +134,26 @@ pub fn mstore8(
     Ok(())
 }
 
+#[inline]
+pub fn mcopy(
+    state: &mut ExecutionState,
+    _system: &System<impl Runtime>,
+    dest_index: U256,
+    src_index: U256,
+    size: U256,
+) -> Result<(), ActorError> {
+    // Expand memory to cover both the source and destination regions.
+    let src = get_memory_region(&mut state.memory, src_index, size)?;
+    let dest = get_memory_region(&mut state.memory, dest_index, size)?;
+
+    if let (Some(src), Some(dest)) = (src, dest) {
+        // The regions may overlap.
+        state.memory.copy_within(src.offset..src.offset + src.size.get(), dest.offset);
+    }
+
+    Ok(())
+}
+
 #[inline]
 pub fn msize(
     state: &mut ExecutionState,
@@ -495,6 +515,129 @@ mod tests {
         };
     }
 
+    #[test]
+    fn test_mcopy_basic() {
+        evm_unit_test! {
+            (m) {
+                PUSH1;
+                {3}; // size
+                PUSH0; // source offset
+                PUSH1;
+                {40}; // destination offset
+                MCOPY;
+            }
+            m.state.memory.grow(32);
+            m.state.memory[..3].copy_from_slice(&[1, 2, 3]);
+
+            for _ in 0..4 {
+                m.step().expect("execution step failed");
+            }
+
+            assert_eq!(m.state.stack.len(), 0);
+            assert_eq!(m.state.memory[40..43], [1, 2, 3]);
+            // memory is expanded to cover the destination
+            assert_eq!(m.state.memory.len(), 64);
+            // the source is untouched
+            assert_eq!(m.state.memory[..3], [1, 2, 3]);
+        };
+    }
+
+    #[test]
+    fn test_mcopy_overlap() {
+        evm_unit_test! {
+            (m) {
+                PUSH1;
+                {4}; // size
+                PUSH0; // source offset
+                PUSH1;
+                {2}; // destination offset
+                MCOPY;
+            }
+            m.state.memory.grow(32);
+            m.state.memory[..6].copy_from_slice(&[1, 2, 3, 4, 5, 6]);
+
+            for _ in 0..4 {
+                m.step().expect("execution step failed");
+            }
+
+            assert_eq!(m.state.memory[..6], [1, 2, 1, 2, 3, 4]);
+        };
+    }
+
+    #[test]
+    fn test_mcopy_expands_for_source() {
+        evm_unit_test! {
+            (m) {
+                PUSH1;
+                {32}; // size
+                PUSH1;
+                {64}; // source offset
+                PUSH0; // destination offset
+                MCOPY;
+            }
+            m.state.memory.grow(32);
+            m.state.memory[..32].copy_from_slice(&[0xff; 32]);
+
+            for _ in 0..4 {
+                m.step().expect("execution step failed");
+            }
+
+            // reading past the end of memory expands it with zeroes
+            assert_eq!(m.state.memory.len(), 96);
+            assert_eq!(m.state.memory[..32], [0; 32]);
+        };
+    }
+
+    #[test]
+    fn test_mcopy_zero_size() {
+        evm_unit_test! {
+            (m) {
+                PUSH0; // size
+                PUSH4; // garbage source offset
+                0xff;
+                0xff;
+                0xff;
+                0xff;
+                PUSH4; // garbage destination offset
+                0xff;
+                0xff;
+                0xff;
+                0xff;
+                MCOPY;
+            }
+
+            for _ in 0..4 {
+                m.step().expect("execution step failed");
+            }
+
+            // nothing is copied and memory isn't expanded
+            assert_eq!(m.state.memory.len(), 0);
+        };
+    }
+
+    #[test]
+    fn test_mcopy_overflow() {
+        evm_unit_test! {
+            (m) {
+                PUSH1;
+                {32}; // size
+                PUSH0; // source offset
+                PUSH4; // destination offset overflows u32 with size
+                0xff;
+                0xff;
+                0xff;
+                0xff;
+                MCOPY;
+            }
+
+            for _ in 0..3 {
+                m.step().expect("execution step failed");
+            }
+            let result = m.step();
+            assert_eq!(result.unwrap_err().exit_code(), EVM_CONTRACT_ILLEGAL_MEMORY_ACCESS);
+        };
+    }
+
     macro_rules! check_mem {
         ($mem:ident, $region:ident, $len:expr) => {
             match $region {
@@ -30,9 +30,9 @@ pub fn selfbalance(
     _state: &mut ExecutionState,
     system: &System<impl Runtime>,
 ) -> Result<U256, ActorError> {
-    // Returns native FIL balance of the receiver. Value precision is identical to Ethereum, so
-    // no conversion needed (atto, 1e18).
-    Ok(U256::from(&system.rt.current_balance()))
+    // Returns native FIL balance of the receiver, less any prepaid storage rent it can't spend.
+    // Value precision is identical to Ethereum, so no conversion needed (atto, 1e18).
+    Ok(U256::from(&system.spendable_balance()))
 }
 
 #[cfg(test)]
@@ -0,0 +1,727 @@
+//! BLS12-381 precompiles.
+//!
+//! The curve operations follow [EIP-2537](https://eips.ethereum.org/EIPS/eip-2537) and live at the
+//! addresses assigned there. They're charged the EIP's gas schedule on top of the FVM's execution
+//! gas, and fail without charging if the call's gas limit doesn't cover it. Signature verification
+//! is delegated to the FVM's BLS syscall, which charges its own gas.
+
+use std::collections::BTreeSet;
+
+use bls12_381::{
+    multi_miller_loop, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt, Scalar,
+};
+use fil_actors_runtime::runtime::Runtime;
+use fvm_shared::crypto::signature::{BLS_PUB_LEN, BLS_SIG_LEN};
+
+use crate::interpreter::{precompiles::PrecompileError, System};
+use crate::reader::ValueReader;
+use crate::EVM_WORD_SIZE;
+
+use super::{PrecompileContext, PrecompileResult};
+
+/// Length of a base field element.
+const FP_LEN: usize = 48;
+/// Length of a base field element as encoded by EIP-2537, left-padded with zeros.
+const PADDED_FP_LEN: usize = 64;
+const SCALAR_LEN: usize = 32;
+const G1_LEN: usize = 2 * PADDED_FP_LEN;
+const G2_LEN: usize = 4 * PADDED_FP_LEN;
+
+/// The position of each coordinate of a G2 point's uncompressed encoding (`x.c1 | x.c0 | y.c1 |
+/// y.c0`) in the EIP-2537 encoding, which places the `c0` component of each Fp2 element first.
+const G2_COORDINATE_ORDER: [usize; 4] = [1, 0, 3, 2];
+
+// Gas schedule of EIP-2537.
+const G1_ADD_GAS: u64 = 375;
+const G1_MUL_GAS: u64 = 12000;
+const G2_ADD_GAS: u64 = 600;
+const G2_MUL_GAS: u64 = 22500;
+const PAIRING_BASE_GAS: u64 = 37700;
+const PAIRING_PER_PAIR_GAS: u64 = 32600;
+const MSM_MULTIPLIER: u64 = 1000;
+
+/// Per mille discounts of a G1 multi-scalar multiplication of `k` points, indexed by `k - 1`.
+/// Larger multiplications get the last discount.
+#[rustfmt::skip]
+const G1_MSM_DISCOUNTS: [u64; 128] = [
+    1000, 949, 848, 797, 764, 750, 738, 728, 719, 712, 705, 698, 692, 687, 682, 677,
+    673, 669, 665, 661, 658, 654, 651, 648, 645, 642, 640, 637, 635, 632, 630, 627,
+    625, 623, 621, 619, 617, 615, 613, 611, 609, 608, 606, 604, 603, 601, 599, 598,
+    596, 595, 593, 592, 591, 589, 588, 586, 585, 584, 582, 581, 580, 579, 577, 576,
+    575, 574, 573, 572, 570, 569, 568, 567, 566, 565, 564, 563, 562, 561, 560, 559,
+    558, 557, 556, 555, 554, 553, 552, 551, 550, 549, 548, 547, 547, 546, 545, 544,
+    543, 542, 541, 540, 540, 539, 538, 537, 536, 536, 535, 534, 533, 532, 532, 531,
+    530, 529, 528, 528, 527, 526, 525, 525, 524, 523, 522, 522, 521, 520, 520, 519,
+];
+
+/// As [`G1_MSM_DISCOUNTS`], for G2.
+#[rustfmt::skip]
+const G2_MSM_DISCOUNTS: [u64; 128] = [
+    1000, 1000, 923, 884, 855, 832, 812, 796, 782, 770, 759, 749, 740, 732, 724, 717,
+    711, 704, 699, 693, 688, 683, 679, 674, 670, 666, 663, 659, 655, 652, 649, 646,
+    643, 640, 637, 634, 632, 629, 627, 624, 622, 620, 618, 615, 613, 611, 609, 607,
+    606, 604, 602, 600, 598, 597, 595, 593, 592, 590, 589, 587, 586, 584, 583, 582,
+    580, 579, 578, 576, 575, 574, 573, 571, 570, 569, 568, 567, 566, 565, 563, 562,
+    561, 560, 559, 558, 557, 556, 555, 554, 553, 552, 552, 551, 550, 549, 548, 547,
+    546, 545, 545, 544, 543, 542, 541, 541, 540, 539, 538, 537, 537, 536, 535, 535,
+    534, 533, 532, 532, 531, 530, 530, 529, 528, 528, 527, 526, 526, 525, 524, 524,
+];
+
+/// The gas charged for a multi-scalar multiplication of `k` points.
+fn msm_gas(k: usize, mul_gas: u64, discounts: &[u64; 128]) -> u64 {
+    let discount = discounts[k.min(discounts.len()) - 1];
+    (k as u64).saturating_mul(mul_gas).saturating_mul(discount) / MSM_MULTIPLIER
+}
+
+/// The gas charged for a pairing check of `k` pairs.
+fn pairing_gas(k: usize) -> u64 {
+    (k as u64).saturating_mul(PAIRING_PER_PAIR_GAS).saturating_add(PAIRING_BASE_GAS)
+}
+
+/// Charges a precompile's gas, failing if it exceeds the gas available to the call.
+fn charge_gas<RT: Runtime>(
+    system: &mut System<RT>,
+    ctx: &PrecompileContext,
+    name: &'static str,
+    gas: u64,
+) -> Result<(), PrecompileError> {
+    if gas > ctx.gas_limit {
+        return Err(PrecompileError::OutOfGas);
+    }
+    system.rt.charge_gas(name, gas as i64);
+    Ok(())
+}
+
+/// Reads a padded base field element. The uncompressed point encoding uses the top three bits of
+/// a field element as flags, but these are always zero in a valid field element.
+fn read_fp(input: &[u8]) -> Result<&[u8], PrecompileError> {
+    let (padding, fp) = input.split_at(PADDED_FP_LEN - FP_LEN);
+    if padding.iter().any(|&b| b != 0) || fp[0] & 0xe0 != 0 {
+        return Err(PrecompileError::InvalidInput);
+    }
+    Ok(fp)
+}
+
+/// Reads a G1 point, checking that it's on the curve and, if `subgroup_check` is set, that it's in
+/// the prime order subgroup. The point at infinity is encoded as zeros.
+fn read_g1(input: &[u8], subgroup_check: bool) -> Result<G1Affine, PrecompileError> {
+    if input.iter().all(|&b| b == 0) {
+        return Ok(G1Affine::identity());
+    }
+    let mut buf = [0u8; 2 * FP_LEN];
+    for (i, dst) in buf.chunks_exact_mut(FP_LEN).enumerate() {
+        dst.copy_from_slice(read_fp(&input[i * PADDED_FP_LEN..(i + 1) * PADDED_FP_LEN])?);
+    }
+    let point: Option<G1Affine> = G1Affine::from_uncompressed_unchecked(&buf).into();
+    match point {
+        Some(p)
+            if bool::from(p.is_on_curve())
+                && (!subgroup_check || bool::from(p.is_torsion_free())) =>
+        {
+            Ok(p)
+        }
+        _ => Err(PrecompileError::InvalidInput),
+    }
+}
+
+/// Reads a G2 point, as for [`read_g1`].
+fn read_g2(input: &[u8], subgroup_check: bool) -> Result<G2Affine, PrecompileError> {
+    if input.iter().all(|&b| b == 0) {
+        return Ok(G2Affine::identity());
+    }
+    let mut buf = [0u8; 4 * FP_LEN];
+    for (dst, i) in buf.chunks_exact_mut(FP_LEN).zip(G2_COORDINATE_ORDER) {
+        dst.copy_from_slice(read_fp(&input[i * PADDED_FP_LEN..(i + 1) * PADDED_FP_LEN])?);
+    }
+    let point: Option<G2Affine> = G2Affine::from_uncompressed_unchecked(&buf).into();
+    match point {
+        Some(p)
+            if bool::from(p.is_on_curve())
+                && (!subgroup_check || bool::from(p.is_torsion_free())) =>
+        {
+            Ok(p)
+        }
+        _ => Err(PrecompileError::InvalidInput),
+    }
+}
+
+/// Reads a big-endian scalar. Scalars needn't be reduced, so this is only valid for multiplying
+/// points in the prime order subgroup.
+fn read_scalar(input: &[u8]) -> Scalar {
+    let mut wide = [0u8; 2 * SCALAR_LEN];
+    for (dst, src) in wide.iter_mut().zip(input.iter().rev()) {
+        *dst = *src;
+    }
+    Scalar::from_bytes_wide(&wide)
+}
+
+fn write_g1(point: G1Affine) -> Vec<u8> {
+    let mut output = vec![0; G1_LEN];
+    if !bool::from(point.is_identity()) {
+        for (i, fp) in point.to_uncompressed().chunks_exact(FP_LEN).enumerate() {
+            output[(i + 1) * PADDED_FP_LEN - FP_LEN..(i + 1) * PADDED_FP_LEN].copy_from_slice(fp);
+        }
+    }
+    output
+}
+
+fn write_g2(point: G2Affine) -> Vec<u8> {
+    let mut output = vec![0; G2_LEN];
+    if !bool::from(point.is_identity()) {
+        for (fp, i) in point.to_uncompressed().chunks_exact(FP_LEN).zip(G2_COORDINATE_ORDER) {
+            output[(i + 1) * PADDED_FP_LEN - FP_LEN..(i + 1) * PADDED_FP_LEN].copy_from_slice(fp);
+        }
+    }
+    output
+}
+
+fn bool_to_word(value: bool) -> Vec<u8> {
+    let mut output = vec![0; EVM_WORD_SIZE];
+    output[EVM_WORD_SIZE - 1] = value as u8;
+    output
+}
+
+/// add 2 points together on the BLS12-381 G1 curve
+pub(super) fn bls12_g1_add<RT: Runtime>(
+    system: &mut System<RT>,
+    input: &[u8],
+    ctx: PrecompileContext,
+) -> PrecompileResult {
+    if input.len() != 2 * G1_LEN {
+        return Err(PrecompileError::IncorrectInputSize);
+    }
+    charge_gas(system, &ctx, "OnBls12G1Add", G1_ADD_GAS)?;
+    let a = read_g1(&input[..G1_LEN], false)?;
+    let b = read_g1(&input[G1_LEN..], false)?;
+    Ok(write_g1((G1Projective::from(a) + b).into()))
+}
+
+/// multiply points on the BLS12-381 G1 curve by scalars, and sum the products
+pub(super) fn bls12_g1_msm<RT: Runtime>(
+    system: &mut System<RT>,
+    input: &[u8],
+    ctx: PrecompileContext,
+) -> PrecompileResult {
+    const PAIR_LEN: usize = G1_LEN + SCALAR_LEN;
+    if input.is_empty() || input.len() % PAIR_LEN != 0 {
+        return Err(PrecompileError::IncorrectInputSize);
+    }
+    let gas = msm_gas(input.len() / PAIR_LEN, G1_MUL_GAS, &G1_MSM_DISCOUNTS);
+    charge_gas(system, &ctx, "OnBls12G1Msm", gas)?;
+    let mut sum = G1Projective::identity();
+    for pair in input.chunks_exact(PAIR_LEN) {
+        let (point, scalar) = pair.split_at(G1_LEN);
+        sum += read_g1(point, true)? * read_scalar(scalar);
+    }
+    Ok(write_g1(sum.into()))
+}
+
+/// add 2 points together on the BLS12-381 G2 curve
+pub(super) fn bls12_g2_add<RT: Runtime>(
+    system: &mut System<RT>,
+    input: &[u8],
+    ctx: PrecompileContext,
+) -> PrecompileResult {
+    if input.len() != 2 * G2_LEN {
+        return Err(PrecompileError::IncorrectInputSize);
+    }
+    charge_gas(system, &ctx, "OnBls12G2Add", G2_ADD_GAS)?;
+    let a = read_g2(&input[..G2_LEN], false)?;
+    let b = read_g2(&input[G2_LEN..], false)?;
+    Ok(write_g2((G2Projective::from(a) + b).into()))
+}
+
+/// multiply points on the BLS12-381 G2 curve by scalars, and sum the products
+pub(super) fn bls12_g2_msm<RT: Runtime>(
+    system: &mut System<RT>,
+    input: &[u8],
+    ctx: PrecompileContext,
+) -> PrecompileResult {
+    const PAIR_LEN: usize = G2_LEN + SCALAR_LEN;
+    if input.is_empty() || input.len() % PAIR_LEN != 0 {
+        return Err(PrecompileError::IncorrectInputSize);
+    }
+    let gas = msm_gas(input.len() / PAIR_LEN, G2_MUL_GAS, &G2_MSM_DISCOUNTS);
+    charge_gas(system, &ctx, "OnBls12G2Msm", gas)?;
+    let mut sum = G2Projective::identity();
+    for pair in input.chunks_exact(PAIR_LEN) {
+        let (point, scalar) = pair.split_at(G2_LEN);
+        sum += read_g2(point, true)? * read_scalar(scalar);
+    }
+    Ok(write_g2(sum.into()))
+}
+
+/// checks that the product of the pairings of pairs of G1 and G2 points is the identity
+pub(super) fn bls12_pairing_check<RT: Runtime>(
+    system: &mut System<RT>,
+    input: &[u8],
+    ctx: PrecompileContext,
+) -> PrecompileResult {
+    const PAIR_LEN: usize = G1_LEN + G2_LEN;
+    if input.is_empty() || input.len() % PAIR_LEN != 0 {
+        return Err(PrecompileError::IncorrectInputSize);
+    }
+    charge_gas(system, &ctx, "OnBls12PairingCheck", pairing_gas(input.len() / PAIR_LEN))?;
+    let pairs = input
+        .chunks_exact(PAIR_LEN)
+        .map(|pair| {
+            let (g1, g2) = pair.split_at(G1_LEN);
+            Ok((read_g1(g1, true)?, G2Prepared::from(read_g2(g2, true)?)))
+        })
+        .collect::<Result<Vec<_>, PrecompileError>>()?;
+    let terms: Vec<_> = pairs.iter().map(|(g1, g2)| (g1, g2)).collect();
+    Ok(bool_to_word(multi_miller_loop(&terms).final_exponentiation() == Gt::identity()))
+}
+
+/// Verifies a Filecoin BLS signature, or an aggregate of BLS signatures over distinct messages.
+///
+/// Parameters are encoded according to the solidity ABI, with no function selector:
+///
+/// ```text
+/// bytes   signature   (a 96 byte compressed G2 point)
+/// bytes[] public_keys (48 byte compressed G1 points)
+/// bytes[] messages    (the message signed by each public key)
+/// ```
+///
+/// Returns 1 if the signature is valid and 0 otherwise, as a u256. Reverts if the signature or any
+/// of the public keys can't be decoded, or if the numbers of public keys and messages differ.
+///
+/// The signature is verified by the FVM's BLS syscall, as for Filecoin's own BLS signatures, so
+/// aggregates over duplicate messages are rejected, as those would admit rogue key attacks.
+pub(super) fn verify_bls_signature<RT: Runtime>(
+    system: &mut System<RT>,
+    input: &[u8],
+    _: PrecompileContext,
+) -> PrecompileResult {
+    let mut input_params = ValueReader::new(input);
+
+    let signature_off: u32 = input_params.read_value()?;
+    let public_keys_off: u32 = input_params.read_value()?;
+    let messages_off: u32 = input_params.read_value()?;
+
+    let signature = read_bytes(&mut input_params, input.len(), signature_off.try_into()?)?;
+    let public_keys =
+        read_bytes_array(&mut input_params, input.len(), public_keys_off.try_into()?)?;
+    let messages = read_bytes_array(&mut input_params, input.len(), messages_off.try_into()?)?;
+
+    let signature: [u8; BLS_SIG_LEN] =
+        signature.as_slice().try_into().map_err(|_| PrecompileError::InvalidInput)?;
+    if Option::<G2Affine>::from(G2Affine::from_compressed(&signature)).is_none() {
+        return Err(PrecompileError::InvalidInput);
+    }
+
+    if public_keys.is_empty() || public_keys.len() != messages.len() {
+        return Err(PrecompileError::InvalidInput);
+    }
+    let public_keys = public_keys
+        .iter()
+        .map(|key| {
+            let key: [u8; BLS_PUB_LEN] =
+                key.as_slice().try_into().map_err(|_| PrecompileError::InvalidInput)?;
+            match Option::<G1Affine>::from(G1Affine::from_compressed(&key)) {
+                Some(point) => Ok((key, bool::from(point.is_identity()))),
+                None => Err(PrecompileError::InvalidInput),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let distinct: BTreeSet<_> = messages.iter().collect();
+    if distinct.len() != messages.len() || public_keys.iter().any(|(_, identity)| *identity) {
+        return Ok(bool_to_word(false));
+    }
+
+    let public_keys: Vec<_> = public_keys.into_iter().map(|(key, _)| key).collect();
+    let plaintexts: Vec<&[u8]> = messages.iter().map(|m| m.as_slice()).collect();
+    let valid = system.rt.verify_bls_aggregate(&signature, &public_keys, &plaintexts).is_ok();
+    Ok(bool_to_word(valid))
+}
+
+/// Reads ABI encoded `bytes` at an offset. The length is bounded by that of the input, so that
+/// malicious lengths can't force large allocations.
+fn read_bytes(
+    reader: &mut ValueReader,
+    input_len: usize,
+    offset: usize,
+) -> Result<Vec<u8>, PrecompileError> {
+    reader.seek(offset);
+    let len: u32 = reader.read_value()?;
+    let len: usize = len.try_into()?;
+    if len > input_len {
+        return Err(PrecompileError::InvalidInput);
+    }
+    Ok(reader.read_padded(len).into_owned())
+}
+
+/// Reads an ABI encoded `bytes[]` at an offset.
+fn read_bytes_array(
+    reader: &mut ValueReader,
+    input_len: usize,
+    offset: usize,
+) -> Result<Vec<Vec<u8>>, PrecompileError> {
+    reader.seek(offset);
+    let len: u32 = reader.read_value()?;
+    let len: usize = len.try_into()?;
+    if len > input_len / EVM_WORD_SIZE {
+        return Err(PrecompileError::InvalidInput);
+    }
+    // Element offsets are relative to the start of the array's contents, following its length.
+    let start = offset.checked_add(EVM_WORD_SIZE).ok_or(PrecompileError::InvalidInput)?;
+    let offsets = (0..len).map(|_| reader.read_value::<u32>()).collect::<Result<Vec<_>, _>>()?;
+    offsets
+        .into_iter()
+        .map(|off| {
+            let off = start.checked_add(off.try_into()?).ok_or(PrecompileError::InvalidInput)?;
+            read_bytes(reader, input_len, off)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bls12_381::hash_to_curve::{ExpandMsgXmd, HashToCurve};
+    use bls_signatures::{aggregate, verify_messages, PrivateKey, Serialize};
+    use fil_actors_runtime::test_utils::{ExpectVerifyBlsAggregate, MockRuntime};
+    use sha2::Sha256;
+
+    /// Domain separation tag with which Filecoin hashes messages to G2 for BLS signatures.
+    const FILECOIN_BLS_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+
+    fn encode_scalar(scalar: u64) -> Vec<u8> {
+        let mut output = vec![0; SCALAR_LEN];
+        output[SCALAR_LEN - 8..].copy_from_slice(&scalar.to_be_bytes());
+        output
+    }
+
+    fn encode_bytes(data: &[u8]) -> Vec<u8> {
+        let mut output = vec![0; EVM_WORD_SIZE];
+        output[EVM_WORD_SIZE - 4..].copy_from_slice(&(data.len() as u32).to_be_bytes());
+        output.extend_from_slice(data);
+        output.resize(EVM_WORD_SIZE + data.len().div_ceil(EVM_WORD_SIZE) * EVM_WORD_SIZE, 0);
+        output
+    }
+
+    fn encode_word(value: usize) -> Vec<u8> {
+        let mut output = vec![0; EVM_WORD_SIZE];
+        output[EVM_WORD_SIZE - 8..].copy_from_slice(&(value as u64).to_be_bytes());
+        output
+    }
+
+    fn encode_bytes_array(items: &[Vec<u8>]) -> Vec<u8> {
+        let mut head = encode_word(items.len());
+        let mut tail = Vec::new();
+        for item in items {
+            head.extend(encode_word(items.len() * EVM_WORD_SIZE + tail.len()));
+            tail.extend(encode_bytes(item));
+        }
+        head.extend(tail);
+        head
+    }
+
+    /// ABI encodes the parameters of the signature verification precompile.
+    fn encode_verify_params(
+        signature: &[u8],
+        public_keys: &[Vec<u8>],
+        messages: &[Vec<u8>],
+    ) -> Vec<u8> {
+        let signature = encode_bytes(signature);
+        let public_keys = encode_bytes_array(public_keys);
+        let messages = encode_bytes_array(messages);
+        let head_len = 3 * EVM_WORD_SIZE;
+        [
+            encode_word(head_len),
+            encode_word(head_len + signature.len()),
+            encode_word(head_len + signature.len() + public_keys.len()),
+            signature,
+            public_keys,
+            messages,
+        ]
+        .concat()
+    }
+
+    fn private_keys(count: u8) -> Vec<PrivateKey> {
+        (0..count).map(|i| PrivateKey::new([i + 1; 32])).collect()
+    }
+
+    #[test]
+    fn g1_add() {
+        let rt = MockRuntime::default();
+        let mut system = System::create(&rt).unwrap();
+
+        let g = G1Affine::generator();
+        let two_g = G1Affine::from(G1Projective::from(g).double());
+
+        for _ in 0..5 {
+            rt.expect_gas_charge(G1_ADD_GAS as i64);
+        }
+        let input = [write_g1(g), write_g1(g)].concat();
+        let res = bls12_g1_add(&mut system, &input, PrecompileContext::default()).unwrap();
+        assert_eq!(write_g1(two_g), res);
+
+        // adding the identity
+        let input = [write_g1(g), vec![0; G1_LEN]].concat();
+        let res = bls12_g1_add(&mut system, &input, PrecompileContext::default()).unwrap();
+        assert_eq!(write_g1(g), res);
+
+        // adding the inverse
+        let input = [write_g1(g), write_g1(-g)].concat();
+        let res = bls12_g1_add(&mut system, &input, PrecompileContext::default()).unwrap();
+        assert_eq!(vec![0; G1_LEN], res);
+
+        // point not on the curve
+        let mut bad = write_g1(g);
+        bad[G1_LEN - 1] ^= 1;
+        let input = [write_g1(g), bad].concat();
+        let res = bls12_g1_add(&mut system, &input, PrecompileContext::default());
+        assert!(matches!(res, Err(PrecompileError::InvalidInput)));
+
+        // non-zero padding
+        let mut bad = write_g1(g);
+        bad[0] = 1;
+        let input = [write_g1(g), bad].concat();
+        let res = bls12_g1_add(&mut system, &input, PrecompileContext::default());
+        assert!(matches!(res, Err(PrecompileError::InvalidInput)));
+
+        let res = bls12_g1_add(&mut system, &input[1..], PrecompileContext::default());
+        assert!(matches!(res, Err(PrecompileError::IncorrectInputSize)));
+    }
+
+    #[test]
+    fn g1_msm() {
+        let rt = MockRuntime::default();
+        let mut system = System::create(&rt).unwrap();
+
+        let g = G1Affine::generator();
+        let expected = G1Affine::from(g * Scalar::from(5u64));
+
+        rt.expect_gas_charge(22776);
+        rt.expect_gas_charge(22776);
+
+        // 2g + 3g
+        let input = [write_g1(g), encode_scalar(2), write_g1(g), encode_scalar(3)].concat();
+        let res = bls12_g1_msm(&mut system, &input, PrecompileContext::default()).unwrap();
+        assert_eq!(write_g1(expected), res);
+
+        // scalars needn't be reduced
+        let minus_one: Vec<u8> = (-Scalar::one()).to_bytes().iter().rev().copied().collect();
+        // -1 + 6 == 5 (mod r)
+        let input = [write_g1(g), minus_one, write_g1(g), encode_scalar(6)].concat();
+        let res = bls12_g1_msm(&mut system, &input, PrecompileContext::default()).unwrap();
+        assert_eq!(write_g1(expected), res);
+
+        let res = bls12_g1_msm(&mut system, &[], PrecompileContext::default());
+        assert!(matches!(res, Err(PrecompileError::IncorrectInputSize)));
+    }
+
+    #[test]
+    fn g2_add_and_msm() {
+        let rt = MockRuntime::default();
+        let mut system = System::create(&rt).unwrap();
+
+        let g = G2Affine::generator();
+        let two_g = G2Affine::from(G2Projective::from(g).double());
+
+        rt.expect_gas_charge(G2_ADD_GAS as i64);
+        rt.expect_gas_charge(G2_MUL_GAS as i64);
+        rt.expect_gas_charge(G2_ADD_GAS as i64);
+
+        let input = [write_g2(g), write_g2(g)].concat();
+        let res = bls12_g2_add(&mut system, &input, PrecompileContext::default()).unwrap();
+        assert_eq!(write_g2(two_g), res);
+
+        let input = [write_g2(g), encode_scalar(2)].concat();
+        let res = bls12_g2_msm(&mut system, &input, PrecompileContext::default()).unwrap();
+        assert_eq!(write_g2(two_g), res);
+
+        // swapping the components of an Fp2 coordinate moves the point off the curve
+        let mut bad = write_g2(g);
+        bad[..PADDED_FP_LEN].copy_from_slice(&write_g2(g)[PADDED_FP_LEN..2 * PADDED_FP_LEN]);
+        bad[PADDED_FP_LEN..2 * PADDED_FP_LEN].copy_from_slice(&write_g2(g)[..PADDED_FP_LEN]);
+        let input = [write_g2(g), bad].concat();
+        let res = bls12_g2_add(&mut system, &input, PrecompileContext::default());
+        assert!(matches!(res, Err(PrecompileError::InvalidInput)));
+    }
+
+    #[test]
+    fn pairing_check() {
+        let rt = MockRuntime::default();
+        let mut system = System::create(&rt).unwrap();
+
+        let p = G1Affine::generator();
+        let q = G2Affine::generator();
+        let a = Scalar::from(7u64);
+        rt.expect_gas_charge(102900);
+        rt.expect_gas_charge(102900);
+
+        // e(a * p, q) * e(-p, a * q) == 1
+        let input = [write_g1((p * a).into()), write_g2(q), write_g1(-p), write_g2((q * a).into())]
+            .concat();
+        let res = bls12_pairing_check(&mut system, &input, PrecompileContext::default()).unwrap();
+        assert_eq!(bool_to_word(true), res);
+
+        // e(a * p, q) * e(p, a * q) != 1
+        let input =
+            [write_g1((p * a).into()), write_g2(q), write_g1(p), write_g2((q * a).into())].concat();
+        let res = bls12_pairing_check(&mut system, &input, PrecompileContext::default()).unwrap();
+        assert_eq!(bool_to_word(false), res);
+
+        let res = bls12_pairing_check(&mut system, &[], PrecompileContext::default());
+        assert!(matches!(res, Err(PrecompileError::IncorrectInputSize)));
+    }
+
+    #[test]
+    fn gas_schedule() {
+        assert_eq!(12000, msm_gas(1, G1_MUL_GAS, &G1_MSM_DISCOUNTS));
+        assert_eq!(22776, msm_gas(2, G1_MUL_GAS, &G1_MSM_DISCOUNTS));
+        assert_eq!(128 * 12000 * 519 / 1000, msm_gas(128, G1_MUL_GAS, &G1_MSM_DISCOUNTS));
+        // the discount is capped
+        assert_eq!(1000 * 12000 * 519 / 1000, msm_gas(1000, G1_MUL_GAS, &G1_MSM_DISCOUNTS));
+        assert_eq!(2 * 22500, msm_gas(2, G2_MUL_GAS, &G2_MSM_DISCOUNTS));
+        assert_eq!(1000 * 22500 * 524 / 1000, msm_gas(1000, G2_MUL_GAS, &G2_MSM_DISCOUNTS));
+        assert_eq!(37700 + 32600, pairing_gas(1));
+
+        // calls fail without charging if their gas limit doesn't cover the schedule
+        let rt = MockRuntime::default();
+        let mut system = System::create(&rt).unwrap();
+        let g = G1Affine::generator();
+        let input = [write_g1(g), write_g1(g)].concat();
+        let ctx = PrecompileContext { gas_limit: G1_ADD_GAS - 1, ..Default::default() };
+        let res = bls12_g1_add(&mut system, &input, ctx);
+        assert!(matches!(res, Err(PrecompileError::OutOfGas)));
+        rt.verify();
+    }
+
+    /// Encodes the pairing check that an aggregate signature is valid:
+    /// e(pk_1, H(m_1)) * ... * e(pk_n, H(m_n)) * e(-g1, signature) == 1
+    fn encode_signature_pairing(
+        signature: &bls_signatures::Signature,
+        public_keys: &[bls_signatures::PublicKey],
+        messages: &[Vec<u8>],
+    ) -> Vec<u8> {
+        let mut input = Vec::new();
+        for (key, message) in public_keys.iter().zip(messages) {
+            let key: [u8; BLS_PUB_LEN] = key.as_bytes().try_into().unwrap();
+            input.extend(write_g1(G1Affine::from_compressed(&key).unwrap()));
+            let hash = <G2Projective as HashToCurve<ExpandMsgXmd<Sha256>>>::hash_to_curve(
+                message,
+                FILECOIN_BLS_DST,
+            );
+            input.extend(write_g2(hash.into()));
+        }
+        let signature: [u8; BLS_SIG_LEN] = signature.as_bytes().try_into().unwrap();
+        input.extend(write_g1(-G1Affine::generator()));
+        input.extend(write_g2(G2Affine::from_compressed(&signature).unwrap()));
+        input
+    }
+
+    // The FVM's BLS signature syscalls are implemented with the bls-signatures crate, so the
+    // curve precompiles are checked against it by verifying signatures with a pairing check.
+    #[test]
+    fn pairing_check_against_native() {
+        let rt = MockRuntime::default();
+        let mut system = System::create(&rt).unwrap();
+
+        let keys = private_keys(3);
+        let messages: Vec<Vec<u8>> =
+            (0..keys.len()).map(|i| format!("message {}", i).into_bytes()).collect();
+        let signatures: Vec<_> = keys.iter().zip(&messages).map(|(k, m)| k.sign(m)).collect();
+
+        let mut check =
+            |signature: &bls_signatures::Signature, signers: &[usize], signed: &[Vec<u8>]| {
+                let public_keys: Vec<_> = signers.iter().map(|&i| keys[i].public_key()).collect();
+                let refs: Vec<&[u8]> = signed.iter().map(|m| m.as_slice()).collect();
+                let expected = verify_messages(signature, &refs, &public_keys);
+                let input = encode_signature_pairing(signature, &public_keys, signed);
+                rt.expect_gas_charge(pairing_gas(signers.len() + 1) as i64);
+                let res =
+                    bls12_pairing_check(&mut system, &input, PrecompileContext::default()).unwrap();
+                assert_eq!(bool_to_word(expected), res);
+                expected
+            };
+
+        // single signatures
+        assert!(check(&signatures[0], &[0], &messages[..1]));
+        assert!(!check(&signatures[0], &[1], &messages[..1]));
+        assert!(!check(&signatures[0], &[0], &messages[1..2]));
+
+        // aggregates
+        let all = aggregate(&signatures).unwrap();
+        assert!(check(&all, &[0, 1, 2], &messages));
+        assert!(!check(&all, &[0, 1], &messages[..2]));
+        assert!(!check(&all, &[1, 0, 2], &messages));
+        rt.verify();
+    }
+
+    #[test]
+    fn verify_signature_with_syscall() {
+        let rt = MockRuntime::default();
+        let mut system = System::create(&rt).unwrap();
+
+        let keys = private_keys(2);
+        let messages: Vec<Vec<u8>> = vec![b"message 0".to_vec(), b"message 1".to_vec()];
+        let signature = aggregate(&[keys[0].sign(&messages[0]), keys[1].sign(&messages[1])])
+            .unwrap()
+            .as_bytes();
+        let public_keys: Vec<_> = keys.iter().map(|k| k.public_key().as_bytes()).collect();
+        let input = encode_verify_params(&signature, &public_keys, &messages);
+
+        for valid in [true, false] {
+            rt.expect_verify_bls_aggregate(ExpectVerifyBlsAggregate {
+                sig: signature.clone().try_into().unwrap(),
+                pub_keys: public_keys.iter().map(|k| k.clone().try_into().unwrap()).collect(),
+                plaintexts: messages.clone(),
+                result: if valid { Ok(()) } else { Err(anyhow::anyhow!("invalid")) },
+            });
+            let res =
+                verify_bls_signature(&mut system, &input, PrecompileContext::default()).unwrap();
+            assert_eq!(bool_to_word(valid), res);
+            rt.verify();
+        }
+
+        // duplicate messages are rejected without verifying
+        let duplicated = vec![messages[0].clone(), messages[0].clone()];
+        let input = encode_verify_params(&signature, &public_keys, &duplicated);
+        let res = verify_bls_signature(&mut system, &input, PrecompileContext::default()).unwrap();
+        assert_eq!(bool_to_word(false), res);
+        rt.verify();
+    }
+
+    #[test]
+    fn verify_signature_invalid_input() {
+        let rt = MockRuntime::default();
+        let mut system = System::create(&rt).unwrap();
+
+        let keys = private_keys(1);
+        let key = &keys[0];
+        let message = b"message".to_vec();
+        let signature = key.sign(&message).as_bytes();
+        let public_key = key.public_key().as_bytes();
+
+        // truncated signature
+        let input =
+            encode_verify_params(&signature[1..], &[public_key.clone()], &[message.clone()]);
+        let res = verify_bls_signature(&mut system, &input, PrecompileContext::default());
+        assert!(matches!(res, Err(PrecompileError::InvalidInput)));
+
+        // mismatched public keys and messages
+        let input = encode_verify_params(
+            &signature,
+            &[public_key.clone()],
+            &[message.clone(), message.clone()],
+        );
+        let res = verify_bls_signature(&mut system, &input, PrecompileContext::default());
+        assert!(matches!(res, Err(PrecompileError::InvalidInput)));
+
+        // no signers
+        let input = encode_verify_params(&signature, &[], &[]);
+        let res = verify_bls_signature(&mut system, &input, PrecompileContext::default());
+        assert!(matches!(res, Err(PrecompileError::InvalidInput)));
+
+        // public key not in compressed form
+        let mut bad_key = public_key;
+        bad_key[0] &= 0x7f;
+        let input = encode_verify_params(&signature, &[bad_key], &[message]);
+        let res = verify_bls_signature(&mut system, &input, PrecompileContext::default());
+        assert!(matches!(res, Err(PrecompileError::InvalidInput)));
+    }
+}
@@ -1,8 +1,11 @@
 use crate::{EVM_MAX_RESERVED_METHOD, EVM_WORD_SIZE};
 use fil_actors_evm_shared::uints::U256;
+use fil_actors_runtime::runtime::builtins::ActorType;
 use fil_actors_runtime::runtime::Runtime;
 use fvm_ipld_encoding::ipld_block::IpldBlock;
-use fvm_shared::{address::Address, econ::TokenAmount, sys::SendFlags, METHOD_SEND};
+use fvm_shared::error::ErrorNumber;
+use fvm_shared::{address::Address, econ::TokenAmount, sys::SendFlags, Response, METHOD_SEND};
+use num_traits::Zero;
 
 use crate::interpreter::{CallKind, System};
 
@@ -186,37 +189,140 @@ pub(super) fn call_actor_shared<RT: Runtime>(
         )?
     };
 
-    // ------ Build Output -------
+    Ok(encode_call_result(result))
+}
 
-    let output = {
-        // negative values are syscall errors
-        // positive values are user/actor errors
-        // success is 0
-        let (exit_code, data) = match result {
-            Err(errno) => {
-                let exit_code = U256::from(errno as u32).i256_neg();
+/// Maximum size of the return value accepted by the `call_actor_view` precompile.
+pub(super) const CALL_ACTOR_VIEW_MAX_RETURN_SIZE: usize = 64 << 10;
 
-                // no return only exit code
-                (exit_code, None)
-            }
-            Ok(resp) => (U256::from(resp.exit_code.value()), resp.return_data),
-        };
+/// Calls a read-only method on a built-in actor by the actor's actor ID.
+///
+/// Parameters are encoded according to the solidity ABI, with no function selector:
+///
+/// ```text
+/// u64   method
+/// u64   codec (0x51 for "cbor", or `0` for "nothing")
+/// bytes params (must be empty if the codec is 0x0)
+/// u64   actor_id
+/// ```
+///
+/// Returns (also solidity ABI encoded):
+///
+/// ```text
+/// i256  exit_code
+/// u64   codec
+/// bytes return_value
+/// ```
+///
+/// with exit codes as for `call_actor`.
+///
+/// The call is always made read-only and transfers no value, so unlike `call_actor` this
+/// precompile may be invoked with any call kind, including from a static context. The target must
+/// be a built-in actor other than an EVM contract (use `STATICCALL` to call those), and the call
+/// reverts if the return value exceeds [`CALL_ACTOR_VIEW_MAX_RETURN_SIZE`].
+pub(super) fn call_actor_view<RT: Runtime>(
+    system: &mut System<RT>,
+    input: &[u8],
+    ctx: PrecompileContext,
+) -> PrecompileResult {
+    // ----- Input Parameters -------
 
-        let ret_blk = data.unwrap_or(IpldBlock { codec: 0, data: vec![] });
-
-        let mut output = Vec::with_capacity(4 * EVM_WORD_SIZE + ret_blk.data.len());
-        output.extend_from_slice(&exit_code.to_bytes());
-        output.extend_from_slice(&U256::from(ret_blk.codec).to_bytes());
-        output.extend_from_slice(&U256::from(output.len() + EVM_WORD_SIZE).to_bytes());
-        output.extend_from_slice(&U256::from(ret_blk.data.len()).to_bytes());
-        output.extend_from_slice(&ret_blk.data);
-        // Pad out to the next increment of 32 bytes for solidity compatibility.
-        let offset = output.len() % EVM_WORD_SIZE;
-        if offset > 0 {
-            output.resize(output.len() - offset + EVM_WORD_SIZE, 0);
+    if !ctx.value.is_zero() {
+        return Err(PrecompileError::CallForbidden);
+    }
+
+    let mut input_params = ValueReader::new(input);
+
+    let method: u64 = input_params.read_value()?;
+    let codec: u64 = input_params.read_value()?;
+    let params_off: u32 = input_params.read_value()?;
+    let actor_id: u64 = input_params.read_value()?;
+
+    input_params.seek(params_off.try_into()?);
+    let params_len: u32 = input_params.read_value()?;
+    let params = input_params.read_padded(params_len.try_into()?);
+
+    if method <= EVM_MAX_RESERVED_METHOD {
+        return Err(PrecompileError::InvalidInput);
+    }
+
+    let params = match codec {
+        fvm_ipld_encoding::CBOR => Some(IpldBlock { codec, data: params.into() }),
+        0 if params.is_empty() => None,
+        _ => return Err(PrecompileError::InvalidInput),
+    };
+
+    let target_type = system
+        .rt
+        .get_actor_code_cid(&actor_id)
+        .map_or(ActorType::Unknown, |code| system.rt.resolve_builtin_actor_type_checked(&code));
+    match target_type {
+        ActorType::EVM | ActorType::Unknown => return Err(PrecompileError::CallForbidden),
+        ActorType::System
+        | ActorType::Init
+        | ActorType::Cron
+        | ActorType::Account
+        | ActorType::Power
+        | ActorType::Miner
+        | ActorType::Market
+        | ActorType::PaymentChannel
+        | ActorType::Multisig
+        | ActorType::Reward
+        | ActorType::VerifiedRegistry
+        | ActorType::DataCap
+        | ActorType::Placeholder
+        | ActorType::EAM
+        | ActorType::EthAccount => {}
+    }
+
+    // ------ Begin Call -------
+
+    let result = system.send_raw(
+        &Address::new_id(actor_id),
+        method,
+        params,
+        TokenAmount::zero(),
+        Some(ctx.gas_limit),
+        SendFlags::READ_ONLY,
+    )?;
+
+    if let Ok(Response { return_data: Some(ref ret), .. }) = result {
+        if ret.data.len() > CALL_ACTOR_VIEW_MAX_RETURN_SIZE {
+            return Err(PrecompileError::ReturnTooLarge);
+        }
+    }
+
+    Ok(encode_call_result(result))
+}
+
+/// Encodes the result of a send as the solidity ABI encoded `(i256, u64, bytes)` tuple returned
+/// by the actor call precompiles.
+fn encode_call_result(result: Result<Response, ErrorNumber>) -> Vec<u8> {
+    // negative values are syscall errors
+    // positive values are user/actor errors
+    // success is 0
+    let (exit_code, data) = match result {
+        Err(errno) => {
+            let exit_code = U256::from(errno as u32).i256_neg();
+
+            // no return only exit code
+            (exit_code, None)
         }
-        output
+        Ok(resp) => (U256::from(resp.exit_code.value()), resp.return_data),
     };
 
-    Ok(output)
+    let ret_blk = data.unwrap_or(IpldBlock { codec: 0, data: vec![] });
+
+    let mut output = Vec::with_capacity(4 * EVM_WORD_SIZE + ret_blk.data.len());
+    output.extend_from_slice(&exit_code.to_bytes());
+    output.extend_from_slice(&U256::from(ret_blk.codec).to_bytes());
+    output.extend_from_slice(&U256::from(output.len() + EVM_WORD_SIZE).to_bytes());
+    output.extend_from_slice(&U256::from(ret_blk.data.len()).to_bytes());
+    output.extend_from_slice(&ret_blk.data);
+    // Pad out to the next increment of 32 bytes for solidity compatibility.
+    let offset = output.len() % EVM_WORD_SIZE;
+    if offset > 0 {
+        output.resize(output.len() - offset + EVM_WORD_SIZE, 0);
+    }
+    output
 }
@@ -9,11 +9,16 @@ use crate::reader::OverflowError;
 
 use super::{CallKind, System};
 mod blake2f_impl;
+mod bls;
 mod evm;
 mod fvm;
 
+use bls::{
+    bls12_g1_add, bls12_g1_msm, bls12_g2_add, bls12_g2_msm, bls12_pairing_check,
+    verify_bls_signature,
+};
 use evm::{blake2f, ec_add, ec_mul, ec_pairing, ec_recover, identity, modexp, ripemd160, sha256};
-use fvm::{call_actor, call_actor_id, lookup_delegated_address, resolve_address};
+use fvm::{call_actor, call_actor_id, call_actor_view, lookup_delegated_address, resolve_address};
 
 type PrecompileFn<RT> = fn(&mut System<RT>, &[u8], PrecompileContext) -> PrecompileResult;
 pub type PrecompileResult = Result<Vec<u8>, PrecompileError>;
@@ -41,25 +46,33 @@ pub struct Precompiles<RT>(PhantomData<RT>);
 
 impl<RT: Runtime> Precompiles<RT> {
     /// FEVM specific precompiles (0xfe prefix)
-    const NATIVE_PRECOMPILES: PrecompileTable<RT, 5> = PrecompileTable([
+    const NATIVE_PRECOMPILES: PrecompileTable<RT, 7> = PrecompileTable([
         Some(resolve_address::<RT>),          // 0xfe00..01
         Some(lookup_delegated_address::<RT>), // 0xfe00..02
         Some(call_actor::<RT>),               // 0xfe00..03
         None,                                 // 0xfe00..04 DISABLED
         Some(call_actor_id::<RT>),            // 0xfe00..05
+        Some(call_actor_view::<RT>),          // 0xfe00..06
+        Some(verify_bls_signature::<RT>),     // 0xfe00..07
     ]);
 
     /// EVM specific precompiles
-    const EVM_PRECOMPILES: PrecompileTable<RT, 9> = PrecompileTable([
-        Some(ec_recover::<RT>), // 0x01 ecrecover
-        Some(sha256::<RT>),     // 0x02 SHA2-256
-        Some(ripemd160::<RT>),  // 0x03 ripemd160
-        Some(identity::<RT>),   // 0x04 identity
-        Some(modexp::<RT>),     // 0x05 modexp
-        Some(ec_add::<RT>),     // 0x06 ecAdd
-        Some(ec_mul::<RT>),     // 0x07 ecMul
-        Some(ec_pairing::<RT>), // 0x08 ecPairing
-        Some(blake2f::<RT>),    // 0x09 blake2f
+    const EVM_PRECOMPILES: PrecompileTable<RT, 15> = PrecompileTable([
+        Some(ec_recover::<RT>),          // 0x01 ecrecover
+        Some(sha256::<RT>),              // 0x02 SHA2-256
+        Some(ripemd160::<RT>),           // 0x03 ripemd160
+        Some(identity::<RT>),            // 0x04 identity
+        Some(modexp::<RT>),              // 0x05 modexp
+        Some(ec_add::<RT>),              // 0x06 ecAdd
+        Some(ec_mul::<RT>),              // 0x07 ecMul
+        Some(ec_pairing::<RT>),          // 0x08 ecPairing
+        Some(blake2f::<RT>),             // 0x09 blake2f
+        None,                            // 0x0a kzg point evaluation UNSUPPORTED
+        Some(bls12_g1_add::<RT>),        // 0x0b bls12G1Add
+        Some(bls12_g1_msm::<RT>),        // 0x0c bls12G1Msm
+        Some(bls12_g2_add::<RT>),        // 0x0d bls12G2Add
+        Some(bls12_g2_msm::<RT>),        // 0x0e bls12G2Msm
+        Some(bls12_pairing_check::<RT>), // 0x0f bls12PairingCheck
     ]);
 
     fn lookup_precompile(addr: &EthAddress) -> Option<PrecompileFn<RT>> {
@@ -93,7 +106,8 @@ impl<RT: Runtime> Precompiles<RT> {
         // precompile reverts.
         //
         // This shouldn't be observable as the only precompile with side-effects is the call_actor
-        // precompile, and that precompile can only be called with delegatecall.
+        // precompile, and that precompile can only be called with delegatecall. The
+        // call_actor_view precompile has no side-effects and refuses any value.
         if !context.value.is_zero() {
             let fil_addr: Address = precompile_addr.into();
             system
@@ -115,9 +129,11 @@ pub enum PrecompileError {
     // EVM precompile errors
     EcErr(CurveError),
     IncorrectInputSize,
+    OutOfGas,
     // FVM precompile errors
     InvalidInput,
     CallForbidden,
+    ReturnTooLarge,
     TransferFailed,
     VMError(ActorError),
 }
@@ -187,6 +203,22 @@ mod test {
         assert!(is_reserved_precompile_address(&addr));
     }
 
+    #[test]
+    fn is_bls_precompile() {
+        for index in 0x0b..=0x0f {
+            let mut addr = EthAddress([0u8; 20]);
+            addr.0[19] = index;
+            assert!(Precompiles::<MockRuntime>::is_precompile(&addr));
+        }
+        // KZG point evaluation isn't supported
+        let addr = EthAddress(hex_literal::hex!("000000000000000000000000000000000000000a"));
+        assert!(!Precompiles::<MockRuntime>::is_precompile(&addr));
+        assert!(is_reserved_precompile_address(&addr));
+
+        let addr = EthAddress(hex_literal::hex!("fe00000000000000000000000000000000000007"));
+        assert!(Precompiles::<MockRuntime>::is_precompile(&addr));
+    }
+
     #[test]
     fn is_over_precompile() {
         let addr = EthAddress(hex_literal::hex!("ff00000000000000000000000000000000000001"));
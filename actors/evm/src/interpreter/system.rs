@@ -16,7 +16,8 @@ use fvm_shared::error::{ErrorNumber, ExitCode};
 use fvm_shared::sys::SendFlags;
 use fvm_shared::{MethodNum, Response, IPLD_RAW, METHOD_SEND};
 
-use crate::state::{State, Tombstone};
+use crate::rent::RentState;
+use crate::state::{State, Tombstone, TransientData, TransientDataLifespan};
 use crate::BytecodeHash;
 
 use cid::Cid;
@@ -91,8 +92,15 @@ pub struct System<'r, RT: Runtime> {
     bytecode: Option<EvmBytecode>,
     /// The contract's EVM storage slots.
     slots: StateKamt<RT::Blockstore>,
+    /// The contract's transient storage slots (EIP-1153).
+    /// Reads and writes are charged by the FVM like any other state access, as for `slots`.
+    transient_slots: StateKamt<RT::Blockstore>,
+    /// The top-level message in which the transient slots were written, if they have been.
+    transient_data_lifespan: Option<TransientDataLifespan>,
     /// The contracts "nonce" (incremented when creating new actors).
     pub(crate) nonce: u64,
+    /// The contract's storage rent account (only charged with the `storage-rent` feature).
+    rent: RentState,
     /// The last saved state root. None if the current state hasn't been saved yet.
     saved_state_root: Option<Cid>,
     /// Read Only context (staticcall)
@@ -113,8 +121,11 @@ impl<'r, RT: Runtime> System<'r, RT> {
         let store = rt.store().clone();
         Self {
             rt,
-            slots: StateKamt::new_with_config(store, KAMT_CONFIG.clone()),
+            slots: StateKamt::new_with_config(store.clone(), KAMT_CONFIG.clone()),
+            transient_slots: StateKamt::new_with_config(store, KAMT_CONFIG.clone()),
+            transient_data_lifespan: None,
             nonce: 1,
+            rent: RentState::new(rt.curr_epoch()),
             saved_state_root: None,
             bytecode: None,
             readonly,
@@ -177,12 +188,17 @@ impl<'r, RT: Runtime> System<'r, RT> {
         }
 
         let read_only = rt.read_only();
+        let (transient_slots, transient_data_lifespan) =
+            load_transient_data(rt, store.clone(), state.transient_data)?;
 
         Ok(Self {
             rt,
             slots: StateKamt::load_with_config(&state.contract_state, store, KAMT_CONFIG.clone())
                 .context_code(ExitCode::USR_ILLEGAL_STATE, "state not in blockstore")?,
+            transient_slots,
+            transient_data_lifespan,
             nonce: state.nonce,
+            rent: state.rent,
             saved_state_root: Some(state_root),
             bytecode: Some(EvmBytecode::new(state.bytecode, state.bytecode_hash)),
             readonly: read_only,
@@ -198,10 +214,20 @@ impl<'r, RT: Runtime> System<'r, RT> {
 
     /// Transfers funds to the receiver. This doesn't bother saving/reloading state.
     pub fn transfer(&mut self, to: &Address, value: TokenAmount) -> Result<(), ActorError> {
+        #[cfg(feature = "storage-rent")]
+        if value > self.spendable_balance() {
+            return Err(actor_error!(insufficient_funds; "cannot transfer prepaid storage rent"));
+        }
         extract_send_result(self.rt.send_simple(to, METHOD_SEND, None, value))?;
         Ok(())
     }
 
+    /// The contract's balance, less the prepaid storage rent held in escrow, which the contract
+    /// can't spend.
+    pub fn spendable_balance(&self) -> TokenAmount {
+        self.rt.current_balance() - &self.rent.balance
+    }
+
     /// Generalized send
     pub fn send(
         &mut self,
@@ -243,6 +269,11 @@ impl<'r, RT: Runtime> System<'r, RT> {
         gas_limit: Option<u64>,
         send_flags: SendFlags,
     ) -> Result<Result<Response, ErrorNumber>, ActorError> {
+        #[cfg(feature = "storage-rent")]
+        if value > self.spendable_balance() {
+            // Prepaid storage rent can't be spent, as if it weren't in the balance.
+            return Ok(Err(ErrorNumber::InsufficientFunds));
+        }
         self.flush()?;
         let result = self.rt.send(to, method, params, value, gas_limit, send_flags);
 
@@ -283,6 +314,17 @@ impl<'r, RT: Runtime> System<'r, RT> {
                     )?,
                     nonce: self.nonce,
                     tombstone: self.tombstone,
+                    transient_data: match self.transient_data_lifespan {
+                        Some(transient_data_lifespan) => Some(TransientData {
+                            transient_data_state: self.transient_slots.flush().context_code(
+                                ExitCode::USR_ILLEGAL_STATE,
+                                "failed to flush transient storage",
+                            )?,
+                            transient_data_lifespan,
+                        }),
+                        None => None,
+                    },
+                    rent: self.rent.clone(),
                 },
                 Code::Blake2b256,
             )
@@ -314,7 +356,22 @@ impl<'r, RT: Runtime> System<'r, RT> {
         self.slots
             .set_root(&state.contract_state)
             .context_code(ExitCode::USR_ILLEGAL_STATE, "state not in blockstore")?;
+        match state.transient_data {
+            Some(data)
+                if data.transient_data_lifespan
+                    == crate::current_transient_data_lifespan(self.rt) =>
+            {
+                self.transient_slots
+                    .set_root(&data.transient_data_state)
+                    .context_code(ExitCode::USR_ILLEGAL_STATE, "state not in blockstore")?;
+                self.transient_data_lifespan = Some(data.transient_data_lifespan);
+            }
+            // Transient storage written during the current message is never discarded, so
+            // there's none to reload.
+            _ => {}
+        }
         self.nonce = state.nonce;
+        self.rent = state.rent;
         self.saved_state_root = Some(root);
         self.bytecode = Some(EvmBytecode::new(state.bytecode, state.bytecode_hash));
         self.tombstone = state.tombstone;
@@ -355,6 +412,17 @@ impl<'r, RT: Runtime> System<'r, RT> {
         Ok(bytecode)
     }
 
+    /// Get the contract's storage rent account.
+    pub fn rent(&self) -> &RentState {
+        &self.rent
+    }
+
+    /// Update the contract's storage rent account.
+    pub fn update_rent<T>(&mut self, f: impl FnOnce(&mut RentState) -> T) -> T {
+        self.saved_state_root = None; // dirty.
+        f(&mut self.rent)
+    }
+
     /// Get value of a storage key.
     pub fn get_storage(&mut self, key: U256) -> Result<U256, ActorError> {
         Ok(self
@@ -368,15 +436,26 @@ impl<'r, RT: Runtime> System<'r, RT> {
     /// Set value of a storage key.
     pub fn set_storage(&mut self, key: U256, value: U256) -> Result<(), ActorError> {
         let changed = if value.is_zero() {
-            self.slots
+            let cleared = self
+                .slots
                 .delete(&key)
                 .map(|v| v.is_some())
-                .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to clear storage slot")?
+                .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to clear storage slot")?;
+            #[cfg(feature = "storage-rent")]
+            if cleared {
+                self.rent.slots -= 1;
+            }
+            cleared
         } else {
-            self.slots
+            let prev = self
+                .slots
                 .set(key, value)
-                .map(|v| v != Some(value))
-                .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to update storage slot")?
+                .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to update storage slot")?;
+            #[cfg(feature = "storage-rent")]
+            if prev.is_none() {
+                self.rent.slots += 1;
+            }
+            prev != Some(value)
         };
 
         if changed {
@@ -385,6 +464,38 @@ impl<'r, RT: Runtime> System<'r, RT> {
         Ok(())
     }
 
+    /// Get value of a transient storage key.
+    pub fn get_transient_storage(&mut self, key: U256) -> Result<U256, ActorError> {
+        Ok(self
+            .transient_slots
+            .get(&key)
+            .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to get transient storage slot")?
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    /// Set value of a transient storage key.
+    /// The value is cleared at the end of the current top-level message.
+    pub fn set_transient_storage(&mut self, key: U256, value: U256) -> Result<(), ActorError> {
+        let changed = if value.is_zero() {
+            self.transient_slots.delete(&key).map(|v| v.is_some()).context_code(
+                ExitCode::USR_ILLEGAL_STATE,
+                "failed to clear transient storage slot",
+            )?
+        } else {
+            self.transient_slots.set(key, value).map(|v| v != Some(value)).context_code(
+                ExitCode::USR_ILLEGAL_STATE,
+                "failed to update transient storage slot",
+            )?
+        };
+
+        if changed {
+            self.transient_data_lifespan = Some(crate::current_transient_data_lifespan(self.rt));
+            self.saved_state_root = None; // dirty.
+        };
+        Ok(())
+    }
+
     /// Resolve the address to the ethereum equivalent, if possible.
     ///
     /// - Eth f4 maps directly to an Eth address.
@@ -446,3 +557,23 @@ impl<'r, RT: Runtime> System<'r, RT> {
         self.tombstone = Some(crate::current_tombstone(self.rt));
     }
 }
+
+/// Loads transient storage from state if it was written during the current top-level message.
+/// Otherwise returns empty transient storage, discarding any left over from an earlier message.
+fn load_transient_data<RT: Runtime>(
+    rt: &RT,
+    store: RT::Blockstore,
+    transient_data: Option<TransientData>,
+) -> Result<(StateKamt<RT::Blockstore>, Option<TransientDataLifespan>), ActorError> {
+    match transient_data {
+        Some(data)
+            if data.transient_data_lifespan == crate::current_transient_data_lifespan(rt) =>
+        {
+            let slots =
+                StateKamt::load_with_config(&data.transient_data_state, store, KAMT_CONFIG.clone())
+                    .context_code(ExitCode::USR_ILLEGAL_STATE, "state not in blockstore")?;
+            Ok((slots, Some(data.transient_data_lifespan)))
+        }
+        _ => Ok((StateKamt::new_with_config(store, KAMT_CONFIG.clone()), None)),
+    }
+}
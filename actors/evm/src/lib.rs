@@ -3,12 +3,16 @@ use fil_actors_runtime::{
     actor_dispatch_unrestricted, actor_error, ActorError, AsActorError, WithCodec, EAM_ACTOR_ADDR,
     INIT_ACTOR_ADDR,
 };
+#[cfg(feature = "storage-rent")]
+use fil_actors_runtime::{extract_send_result, BURNT_FUNDS_ACTOR_ADDR, CRON_ACTOR_ADDR};
 use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_ipld_encoding::{BytesSer, DAG_CBOR};
 use fvm_shared::address::Address;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
+#[cfg(feature = "storage-rent")]
+use fvm_shared::METHOD_SEND;
 
 use crate::interpreter::Outcome;
 use crate::interpreter::{execute, Bytecode, ExecutionState, System};
@@ -24,6 +28,7 @@ pub use types::*;
 pub mod ext;
 pub mod interpreter;
 pub(crate) mod reader;
+pub mod rent;
 mod state;
 mod types;
 
@@ -65,6 +70,10 @@ pub enum Method {
     GetBytecodeHash = 4,
     GetStorageAt = 5,
     InvokeContractDelegate = 6,
+    #[cfg(feature = "storage-rent")]
+    DepositStorageRent = 7,
+    #[cfg(feature = "storage-rent")]
+    ChargeStorageRent = 8,
     InvokeContract = frc42_dispatch::method_hash!("InvokeEVM"),
 }
 
@@ -75,6 +84,14 @@ pub(crate) fn current_tombstone(rt: &impl Runtime) -> Tombstone {
     Tombstone { origin: rt.message().origin().id().unwrap(), nonce: rt.message().nonce() }
 }
 
+/// Returns the lifespan of transient storage written during the current top-level message.
+pub(crate) fn current_transient_data_lifespan(rt: &impl Runtime) -> TransientDataLifespan {
+    TransientDataLifespan {
+        origin: rt.message().origin().id().unwrap(),
+        nonce: rt.message().nonce(),
+    }
+}
+
 /// Returns true if the contract is "dead". A contract is dead if:
 ///
 /// 1. It has a tombstone.
@@ -159,6 +176,11 @@ where
     RT: Runtime,
     RT::Blockstore: Clone,
 {
+    #[cfg(feature = "storage-rent")]
+    if system.rent().suspended {
+        return Err(actor_error!(forbidden; "contract is suspended for unpaid storage rent"));
+    }
+
     let bytecode = match load_bytecode(system.rt.store(), bytecode_cid)? {
         Some(bytecode) => bytecode,
         // an EVM contract with no code returns immediately
@@ -194,7 +216,10 @@ impl EvmContractActor {
         RT::Blockstore: Clone,
     {
         rt.validate_immediate_caller_is(&[INIT_ACTOR_ADDR])?;
-        initialize_evm_contract(&mut System::create(rt)?, params.creator, params.initcode.into())
+        initialize_evm_contract(&mut System::create(rt)?, params.creator, params.initcode.into())?;
+        #[cfg(feature = "storage-rent")]
+        register_for_storage_rent(rt)?;
+        Ok(())
     }
 
     pub fn resurrect<RT>(rt: &RT, params: ResurrectParams) -> Result<(), ActorError>
@@ -203,7 +228,14 @@ impl EvmContractActor {
         RT::Blockstore: Clone,
     {
         rt.validate_immediate_caller_is(&[EAM_ACTOR_ADDR])?;
-        initialize_evm_contract(&mut System::resurrect(rt)?, params.creator, params.initcode.into())
+        initialize_evm_contract(
+            &mut System::resurrect(rt)?,
+            params.creator,
+            params.initcode.into(),
+        )?;
+        #[cfg(feature = "storage-rent")]
+        register_for_storage_rent(rt)?;
+        Ok(())
     }
 
     /// Invoke the contract with some _alternative_ bytecode. This can only be called by the
@@ -335,6 +367,77 @@ impl EvmContractActor {
     }
 }
 
+#[cfg(feature = "storage-rent")]
+impl EvmContractActor {
+    /// Adds the value sent to the contract's prepaid storage rent, held in escrow, reviving it if
+    /// it was suspended and the balance now covers an epoch of rent.
+    pub fn deposit_storage_rent<RT>(rt: &RT) -> Result<(), ActorError>
+    where
+        RT: Runtime,
+        RT::Blockstore: Clone,
+    {
+        rt.validate_immediate_caller_accept_any()?;
+
+        let state: State = rt.state()?;
+        if is_dead(rt, &state) {
+            return Err(actor_error!(forbidden; "cannot deposit storage rent to a dead contract"));
+        }
+        let mut system = System::load(rt)?;
+        let amount = rt.message().value_received();
+        system.update_rent(|rent| rent.deposit(&amount, rt.curr_epoch()));
+        system.flush()
+    }
+
+    /// Cron hook charging the storage rent due since it was last charged, and burning it from
+    /// escrow. Suspends the contract if its prepaid balance is depleted.
+    /// A dead contract's escrowed balance is burnt, and it stops being charged.
+    pub fn charge_storage_rent<RT>(rt: &RT) -> Result<(), ActorError>
+    where
+        RT: Runtime,
+        RT::Blockstore: Clone,
+    {
+        rt.validate_immediate_caller_is(std::iter::once(&CRON_ACTOR_ADDR))?;
+
+        let state: State = rt.state()?;
+        if is_dead(rt, &state) {
+            burn_funds(rt, state.rent.balance)?;
+            extract_send_result(rt.send_simple(
+                &CRON_ACTOR_ADDR,
+                ext::cron::DEREGISTER_ENTRY_METHOD,
+                None,
+                TokenAmount::zero(),
+            ))?;
+            return Ok(());
+        }
+        let mut system = System::load(rt)?;
+        let charged = system.update_rent(|rent| rent.charge(rt.curr_epoch()));
+        system.flush()?;
+        // The charge was held in escrow, so is in the balance.
+        burn_funds(rt, charged)
+    }
+}
+
+/// Registers the contract with the cron actor, to be charged storage rent.
+#[cfg(feature = "storage-rent")]
+fn register_for_storage_rent(rt: &impl Runtime) -> Result<(), ActorError> {
+    let params = ext::cron::RegisterEntryParams { method_num: Method::ChargeStorageRent as u64 };
+    extract_send_result(rt.send_simple(
+        &CRON_ACTOR_ADDR,
+        ext::cron::REGISTER_ENTRY_METHOD,
+        IpldBlock::serialize_cbor(&params)?,
+        TokenAmount::zero(),
+    ))?;
+    Ok(())
+}
+
+#[cfg(feature = "storage-rent")]
+fn burn_funds(rt: &impl Runtime, amount: TokenAmount) -> Result<(), ActorError> {
+    if amount.is_positive() {
+        extract_send_result(rt.send_simple(&BURNT_FUNDS_ACTOR_ADDR, METHOD_SEND, None, amount))?;
+    }
+    Ok(())
+}
+
 /// Format "filecoin_native_method" input parameters.
 fn handle_filecoin_method_input(method: u64, codec: u64, params: &[u8]) -> Vec<u8> {
     let static_args =
@@ -417,6 +520,11 @@ impl ActorCode for EvmContractActor {
         "EVMContract"
     }
 
+    fil_actors_runtime::actor_info! {
+        interface_revisions: [1],
+        features: ["storage-rent"],
+    }
+
     actor_dispatch_unrestricted! {
         Constructor => constructor,
         InvokeContract => invoke_contract [default_params],
@@ -425,6 +533,10 @@ impl ActorCode for EvmContractActor {
         GetStorageAt => storage_at,
         InvokeContractDelegate => invoke_contract_delegate,
         Resurrect => resurrect,
+        #[cfg(feature = "storage-rent")]
+        DepositStorageRent => deposit_storage_rent,
+        #[cfg(feature = "storage-rent")]
+        ChargeStorageRent => charge_storage_rent,
         _ => handle_filecoin_method,
     }
 }
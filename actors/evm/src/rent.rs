@@ -0,0 +1,139 @@
+//! Experimental storage rent for EVM contracts, enabled by the `storage-rent` feature.
+//!
+//! A contract pays rent each epoch for every storage slot it holds, from a balance prepaid by
+//! sending funds with `DepositStorageRent`. The prepaid balance is held in escrow: it stays in the
+//! contract's balance, but the contract can't spend it. Contracts register with the cron actor
+//! when constructed, which calls `ChargeStorageRent` in rotation with other registered actors.
+//! That takes the rent due since it was last charged, at the contract's slot count at that time,
+//! from escrow and burns it.
+//!
+//! A contract whose prepaid balance can't cover the rent due is suspended: its remaining balance is
+//! taken, and it can't execute, whether invoked directly, by delegate call or by a Filecoin method,
+//! until a deposit covers at least one epoch of rent. No rent accrues while suspended.
+//!
+//! Without the feature the rent account is stored but stays empty, and contracts are never charged.
+
+use fvm_ipld_encoding::tuple::*;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+use num_traits::Zero;
+
+/// Rent charged per storage slot per epoch, in attoFIL.
+pub const RENT_PER_SLOT_PER_EPOCH: u64 = 100;
+
+/// A contract's storage rent account.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct RentState {
+    /// The number of non-zero storage slots held by the contract.
+    pub slots: u64,
+    /// Prepaid rent not yet charged, held in escrow in the contract's balance.
+    pub balance: TokenAmount,
+    /// The epoch up to which rent has been charged.
+    pub paid_through: ChainEpoch,
+    /// Whether the contract is suspended for running out of prepaid rent.
+    pub suspended: bool,
+}
+
+impl RentState {
+    /// An empty account for a contract created at an epoch.
+    pub fn new(epoch: ChainEpoch) -> Self {
+        Self { paid_through: epoch, ..Default::default() }
+    }
+
+    /// The rent due for one epoch at the current slot count.
+    pub fn rent_per_epoch(&self) -> TokenAmount {
+        TokenAmount::from_atto(RENT_PER_SLOT_PER_EPOCH) * self.slots
+    }
+
+    /// Charges the rent due up to an epoch from the prepaid balance, suspending the contract if
+    /// the balance can't cover it.
+    /// Returns the amount taken from the balance.
+    pub fn charge(&mut self, epoch: ChainEpoch) -> TokenAmount {
+        if self.suspended || epoch <= self.paid_through {
+            return TokenAmount::zero();
+        }
+        let due = self.rent_per_epoch() * (epoch - self.paid_through);
+        self.paid_through = epoch;
+        if due <= self.balance {
+            self.balance -= &due;
+            due
+        } else {
+            self.suspended = true;
+            std::mem::take(&mut self.balance)
+        }
+    }
+
+    /// Adds to the prepaid balance, reviving a suspended contract if the balance now covers an
+    /// epoch of rent.
+    pub fn deposit(&mut self, amount: &TokenAmount, epoch: ChainEpoch) {
+        self.balance += amount;
+        if self.suspended && self.balance >= self.rent_per_epoch() {
+            self.suspended = false;
+            // Rent resumes from revival.
+            self.paid_through = epoch;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fvm_shared::econ::TokenAmount;
+    use num_traits::Zero;
+
+    use super::{RentState, RENT_PER_SLOT_PER_EPOCH};
+
+    fn rent(atto: u64) -> TokenAmount {
+        TokenAmount::from_atto(atto)
+    }
+
+    #[test]
+    fn charges_rent_per_slot_per_epoch() {
+        let mut st = RentState::new(10);
+        st.slots = 2;
+        st.deposit(&rent(10 * RENT_PER_SLOT_PER_EPOCH), 10);
+
+        // Nothing is due within the epoch already paid.
+        assert_eq!(TokenAmount::zero(), st.charge(10));
+        assert_eq!(rent(6 * RENT_PER_SLOT_PER_EPOCH), st.charge(13));
+        assert_eq!(rent(4 * RENT_PER_SLOT_PER_EPOCH), st.balance);
+        assert_eq!(13, st.paid_through);
+        assert!(!st.suspended);
+
+        // A balance exactly covering the rent due is spent without suspension.
+        assert_eq!(rent(4 * RENT_PER_SLOT_PER_EPOCH), st.charge(15));
+        assert_eq!(TokenAmount::zero(), st.balance);
+        assert!(!st.suspended);
+    }
+
+    #[test]
+    fn suspends_on_depletion_and_revives_on_top_up() {
+        let mut st = RentState::new(0);
+        st.slots = 1;
+        st.deposit(&rent(3 * RENT_PER_SLOT_PER_EPOCH), 0);
+
+        // The remaining balance is taken when it can't cover the rent due.
+        assert_eq!(rent(3 * RENT_PER_SLOT_PER_EPOCH), st.charge(5));
+        assert!(st.suspended);
+        assert_eq!(TokenAmount::zero(), st.balance);
+
+        // No rent accrues while suspended.
+        assert_eq!(TokenAmount::zero(), st.charge(10));
+        assert_eq!(5, st.paid_through);
+
+        // A deposit too small for an epoch of rent doesn't revive the contract.
+        st.deposit(&rent(RENT_PER_SLOT_PER_EPOCH - 1), 20);
+        assert!(st.suspended);
+        st.deposit(&rent(1), 30);
+        assert!(!st.suspended);
+        assert_eq!(30, st.paid_through);
+        assert_eq!(rent(RENT_PER_SLOT_PER_EPOCH), st.charge(31));
+    }
+
+    #[test]
+    fn contract_without_slots_pays_nothing() {
+        let mut st = RentState::new(0);
+        assert_eq!(TokenAmount::zero(), st.charge(100));
+        assert!(!st.suspended);
+        assert_eq!(100, st.paid_through);
+    }
+}
@@ -8,6 +8,8 @@ use fvm_ipld_encoding::strict_bytes;
 use fvm_ipld_encoding::tuple::*;
 use serde::{Deserialize, Serialize};
 
+use crate::rent::RentState;
+
 /// A tombstone indicating that the contract has been self-destructed.
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize_tuple, Deserialize_tuple)]
 pub struct Tombstone {
@@ -17,6 +19,27 @@ pub struct Tombstone {
     pub nonce: u64,
 }
 
+/// The top-level message in which an EVM contract's transient storage was written.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize_tuple, Deserialize_tuple)]
+pub struct TransientDataLifespan {
+    /// The origin of the message.
+    pub origin: ActorID,
+    /// The nonce of the message.
+    pub nonce: u64,
+}
+
+/// Transient storage (EIP-1153) written by an EVM contract during a top-level message.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize_tuple, Deserialize_tuple)]
+pub struct TransientData {
+    /// The transient storage dictionary.
+    ///
+    /// KAMT<U256, U256>
+    pub transient_data_state: Cid,
+
+    /// The top-level message during which the transient storage is live.
+    pub transient_data_lifespan: TransientDataLifespan,
+}
+
 /// A Keccak256 digest of EVM bytecode.
 #[derive(Deserialize, Serialize, Clone, Copy, Eq, PartialEq)]
 #[serde(transparent)]
@@ -127,13 +150,31 @@ pub struct State {
     ///
     /// See https://github.com/filecoin-project/ref-fvm/issues/1174 for some context.
     pub tombstone: Option<Tombstone>,
+
+    /// Possibly transient storage written during a top-level message.
+    ///
+    /// Transient storage lives until the end of the top-level message that wrote it. Like
+    /// tombstones, this is tracked by recording the message's origin and nonce: transient storage
+    /// written during any other message is treated as empty, and discarded on the next write to
+    /// state. It's absent from states written before it was added.
+    #[serde(default)]
+    pub transient_data: Option<TransientData>,
+
+    /// The contract's storage rent account.
+    ///
+    /// This is only charged when built with the experimental `storage-rent` feature, and is
+    /// otherwise always empty. It's absent from states written before it was added.
+    #[serde(default)]
+    pub rent: RentState,
 }
 
 #[cfg(test)]
 mod test {
+    use fil_actors_runtime::runtime::EMPTY_ARR_CID;
     use fvm_ipld_encoding::{from_slice, to_vec, BytesDe};
 
-    use crate::BytecodeHash;
+    use crate::rent::RentState;
+    use crate::{BytecodeHash, State, Tombstone, TransientData};
 
     #[test]
     fn test_bytecode_hash_serde() {
@@ -158,4 +199,35 @@ mod test {
             "BytecodeHash(0000000000000000000000000000000000000000000000000000000000000000)"
         );
     }
+
+    #[test]
+    fn test_state_v12() {
+        let encoded = to_vec(&(
+            EMPTY_ARR_CID,
+            BytecodeHash::EMPTY,
+            EMPTY_ARR_CID,
+            1u64,
+            Some(Tombstone { origin: 100, nonce: 2 }),
+        ))
+        .unwrap();
+        let state: State = from_slice(&encoded).unwrap();
+        assert_eq!(Some(Tombstone { origin: 100, nonce: 2 }), state.tombstone);
+        assert_eq!(None, state.transient_data);
+        assert_eq!(RentState::default(), state.rent);
+    }
+
+    #[test]
+    fn test_state_without_rent() {
+        let encoded = to_vec(&(
+            EMPTY_ARR_CID,
+            BytecodeHash::EMPTY,
+            EMPTY_ARR_CID,
+            1u64,
+            None::<Tombstone>,
+            None::<TransientData>,
+        ))
+        .unwrap();
+        let state: State = from_slice(&encoded).unwrap();
+        assert_eq!(RentState::default(), state.rent);
+    }
 }
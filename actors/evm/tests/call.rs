@@ -1177,6 +1177,7 @@ impl ContractTester {
         rt.set_origin(FILAddress::new_id(0));
         // first actor created is 0
         rt.set_delegated_address(0, Address::new_delegated(EAM_ACTOR_ID, &addr.0).unwrap());
+        util::expect_register_for_storage_rent(&rt);
 
         assert!(rt
             .call::<evm::EvmContractActor>(
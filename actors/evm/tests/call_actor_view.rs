@@ -0,0 +1,248 @@
+mod asm;
+
+use fil_actors_evm_shared::uints::U256;
+use fil_actors_runtime::test_utils::*;
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_ipld_encoding::{CBOR, IPLD_RAW};
+use fvm_shared::address::Address;
+use fvm_shared::bigint::Zero;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::{ErrorNumber, ExitCode};
+use fvm_shared::sys::SendFlags;
+use fvm_shared::{ActorID, MethodNum};
+
+mod util;
+
+use util::{NativePrecompile, PrecompileCallOpcode, PrecompileExit, PrecompileTest};
+
+const TARGET: ActorID = 1000;
+const VIEW_METHOD: MethodNum = 0x1234_5678;
+// Mirrors the precompile's return size cap.
+const MAX_RETURN_SIZE: usize = 64 << 10;
+
+fn new_tester() -> MockRuntime {
+    let (init, body) = PrecompileTest::test_runner_assembly();
+    let rt = util::construct_and_verify(
+        asm::new_contract("call_actor_view-precompile-test", &init, &body).unwrap(),
+    );
+    rt.set_address_actor_type(Address::new_id(TARGET), *MARKET_ACTOR_CODE_ID);
+    rt
+}
+
+fn view_test(call_op: PrecompileCallOpcode, input: Vec<u8>) -> PrecompileTest {
+    PrecompileTest {
+        precompile_address: NativePrecompile::CallActorView.eth_address(),
+        output_size: 32,
+        gas_avaliable: 10_000_000_000u64,
+        call_op,
+        // overwritten in tests
+        expected_return: vec![],
+        expected_exit_code: PrecompileExit::Success,
+        input,
+    }
+}
+
+/// Encodes (method, codec, params, actor_id) according to the solidity ABI.
+fn view_input(method: MethodNum, codec: u64, params: &[u8], actor_id: ActorID) -> Vec<u8> {
+    let mut out = [method, codec, 4 * 32, actor_id, params.len() as u64]
+        .iter()
+        .flat_map(|w| U256::from(*w).to_bytes())
+        .collect::<Vec<u8>>();
+    out.extend_from_slice(params);
+    out.resize(out.len().next_multiple_of(32), 0);
+    out
+}
+
+/// Encodes the (exit_code, codec, return_value) tuple returned by the precompile.
+fn view_output(exit_code: U256, codec: u64, data: &[u8]) -> Vec<u8> {
+    let mut out = [exit_code, U256::from(codec), U256::from(3 * 32), U256::from(data.len())]
+        .iter()
+        .flat_map(|w| w.to_bytes())
+        .collect::<Vec<u8>>();
+    out.extend_from_slice(data);
+    out.resize(out.len().next_multiple_of(32), 0);
+    out
+}
+
+fn expect_view_send(
+    rt: &MockRuntime,
+    method: MethodNum,
+    params: Option<IpldBlock>,
+    ret: Option<IpldBlock>,
+    exit_code: ExitCode,
+    send_error: Option<ErrorNumber>,
+) {
+    rt.expect_send(
+        Address::new_id(TARGET),
+        method,
+        params,
+        TokenAmount::zero(),
+        Some(0),
+        SendFlags::READ_ONLY,
+        ret,
+        exit_code,
+        send_error,
+    );
+}
+
+#[test]
+fn call_actor_view_success() {
+    let rt = new_tester();
+    let params = vec![0x82, 0x01, 0x02];
+    let ret = vec![0x43, 0xff, 0xfe, 0xfd];
+
+    // Any call kind may be used, as the call can't have side effects.
+    for call_op in [
+        PrecompileCallOpcode::StaticCall,
+        PrecompileCallOpcode::Call(0),
+        PrecompileCallOpcode::DelegateCall,
+    ] {
+        expect_view_send(
+            &rt,
+            VIEW_METHOD,
+            Some(IpldBlock { codec: CBOR, data: params.clone() }),
+            Some(IpldBlock { codec: CBOR, data: ret.clone() }),
+            ExitCode::OK,
+            None,
+        );
+        let mut test = view_test(call_op, view_input(VIEW_METHOD, CBOR, &params, TARGET));
+        test.run_test_expecting(
+            &rt,
+            view_output(U256::from(0), CBOR, &ret),
+            PrecompileExit::Success,
+        );
+    }
+
+    // No parameters.
+    expect_view_send(&rt, VIEW_METHOD, None, None, ExitCode::OK, None);
+    let mut test =
+        view_test(PrecompileCallOpcode::StaticCall, view_input(VIEW_METHOD, 0, &[], TARGET));
+    test.run_test_expecting(&rt, view_output(U256::from(0), 0, &[]), PrecompileExit::Success);
+}
+
+#[test]
+fn call_actor_view_reports_failures() {
+    let rt = new_tester();
+
+    // Actor errors are returned as positive exit codes, with any return data.
+    let ret = vec![0x40];
+    expect_view_send(
+        &rt,
+        VIEW_METHOD,
+        None,
+        Some(IpldBlock { codec: CBOR, data: ret.clone() }),
+        ExitCode::USR_FORBIDDEN,
+        None,
+    );
+    let mut test =
+        view_test(PrecompileCallOpcode::StaticCall, view_input(VIEW_METHOD, 0, &[], TARGET));
+    test.run_test_expecting(
+        &rt,
+        view_output(U256::from(ExitCode::USR_FORBIDDEN.value()), CBOR, &ret),
+        PrecompileExit::Success,
+    );
+
+    // Syscall errors (e.g. the target attempting a write) are returned as negative exit codes.
+    expect_view_send(
+        &rt,
+        VIEW_METHOD,
+        None,
+        None,
+        ExitCode::new(0xffff),
+        Some(ErrorNumber::ReadOnly),
+    );
+    let mut test =
+        view_test(PrecompileCallOpcode::StaticCall, view_input(VIEW_METHOD, 0, &[], TARGET));
+    test.run_test_expecting(
+        &rt,
+        view_output(U256::from(ErrorNumber::ReadOnly as u32).i256_neg(), 0, &[]),
+        PrecompileExit::Success,
+    );
+}
+
+#[test]
+fn call_actor_view_rejects_value() {
+    let rt = new_tester();
+    rt.set_balance(TokenAmount::from_atto(100));
+
+    // No send or transfer is expected.
+    let mut test =
+        view_test(PrecompileCallOpcode::Call(1), view_input(VIEW_METHOD, 0, &[], TARGET));
+    test.run_test_expecting(&rt, vec![], PrecompileExit::Reverted);
+    assert_eq!(TokenAmount::from_atto(100), rt.get_balance());
+}
+
+#[test]
+fn call_actor_view_rejects_reserved_methods() {
+    let rt = new_tester();
+    for method in [0, 1, 2, 1023] {
+        let mut test =
+            view_test(PrecompileCallOpcode::StaticCall, view_input(method, 0, &[], TARGET));
+        test.run_test_expecting(&rt, vec![], PrecompileExit::Reverted);
+    }
+}
+
+#[test]
+fn call_actor_view_rejects_non_builtin_targets() {
+    let rt = new_tester();
+    let evm = 1001;
+    rt.set_address_actor_type(Address::new_id(evm), *EVM_ACTOR_CODE_ID);
+    let custom = 1002;
+    rt.set_address_actor_type(Address::new_id(custom), *util::DUMMY_ACTOR_CODE_ID);
+    let missing = 1003;
+
+    for target in [evm, custom, missing] {
+        let mut test =
+            view_test(PrecompileCallOpcode::StaticCall, view_input(VIEW_METHOD, 0, &[], target));
+        test.run_test_expecting(&rt, vec![], PrecompileExit::Reverted);
+    }
+}
+
+#[test]
+fn call_actor_view_rejects_invalid_params() {
+    let rt = new_tester();
+
+    // Unsupported codec.
+    let mut test = view_test(
+        PrecompileCallOpcode::StaticCall,
+        view_input(VIEW_METHOD, IPLD_RAW, &[0x01], TARGET),
+    );
+    test.run_test_expecting(&rt, vec![], PrecompileExit::Reverted);
+
+    // Parameters without a codec.
+    let mut test =
+        view_test(PrecompileCallOpcode::StaticCall, view_input(VIEW_METHOD, 0, &[0x01], TARGET));
+    test.run_test_expecting(&rt, vec![], PrecompileExit::Reverted);
+}
+
+#[test]
+fn call_actor_view_caps_return_size() {
+    let rt = new_tester();
+
+    // A return value at the cap is passed through.
+    let ret = vec![0xaa; MAX_RETURN_SIZE];
+    expect_view_send(
+        &rt,
+        VIEW_METHOD,
+        None,
+        Some(IpldBlock { codec: CBOR, data: ret.clone() }),
+        ExitCode::OK,
+        None,
+    );
+    let mut test =
+        view_test(PrecompileCallOpcode::StaticCall, view_input(VIEW_METHOD, 0, &[], TARGET));
+    test.run_test_expecting(&rt, view_output(U256::from(0), CBOR, &ret), PrecompileExit::Success);
+
+    // A larger one reverts.
+    expect_view_send(
+        &rt,
+        VIEW_METHOD,
+        None,
+        Some(IpldBlock { codec: CBOR, data: vec![0xaa; MAX_RETURN_SIZE + 1] }),
+        ExitCode::OK,
+        None,
+    );
+    let mut test =
+        view_test(PrecompileCallOpcode::StaticCall, view_input(VIEW_METHOD, 0, &[], TARGET));
+    test.run_test_expecting(&rt, vec![], PrecompileExit::Reverted);
+}
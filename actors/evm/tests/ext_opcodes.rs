@@ -141,6 +141,15 @@ non_existent:
         rt.reset();
     }
 
+    // built-in actors other than accounts and the EVM are native too
+    rt.set_address_actor_type(native_actor, *MARKET_ACTOR_CODE_ID);
+    {
+        let result = util::invoke_contract(&rt, &method);
+        rt.verify();
+        assert_eq!(U256::from_big_endian(&result), expected);
+        rt.reset();
+    }
+
     // EVM account
     let method = util::dispatch_num_word(2);
     let expected = U256::from(0x00);
@@ -151,6 +160,15 @@ non_existent:
         rt.reset();
     }
 
+    // eth accounts are accounts
+    rt.set_address_actor_type(evm_account, *ETHACCOUNT_ACTOR_CODE_ID);
+    {
+        let result = util::invoke_contract(&rt, &method);
+        rt.verify();
+        assert_eq!(U256::from_big_endian(&result), expected);
+        rt.reset();
+    }
+
     // native account
     let method = util::dispatch_num_word(3);
     let expected = U256::from(0x00);
@@ -300,3 +300,48 @@ fn test_precompile_failure() {
     assert_eq!(&[1u8], result.as_slice());
     rt.reset();
 }
+
+#[test]
+fn test_bls_precompiles() {
+    let rt = util::construct_and_verify(tester_bytecode());
+
+    // adding the point at infinity to itself
+    let mut test = PrecompileTest {
+        precompile_address: util::precompile_address(0x00, 0x0b),
+        output_size: 128,
+        expected_exit_code: PrecompileExit::Success,
+        gas_avaliable: 10_000_000_000,
+        call_op: util::PrecompileCallOpcode::StaticCall,
+        input: vec![0; 256],
+        expected_return: vec![0; 128],
+    };
+    // charged according to EIP-2537
+    rt.expect_gas_charge(375);
+    test.run_test(&rt);
+
+    // calls with too little gas for the schedule revert
+    test.gas_avaliable = 374;
+    test.run_test_expecting(&rt, vec![], PrecompileExit::Reverted);
+
+    // inputs of the wrong size revert
+    test.gas_avaliable = 10_000_000_000;
+    test.input = vec![0; 255];
+    test.run_test_expecting(&rt, vec![], PrecompileExit::Reverted);
+
+    // signature verification reverts on malformed input
+    let mut input = vec![0u8; 3 * 32];
+    input[31] = 0x60;
+    input[63] = 0x60;
+    input[95] = 0x60;
+    input.extend_from_slice(&[0u8; 32]);
+    let test = PrecompileTest {
+        precompile_address: NativePrecompile::VerifyBlsSignature.eth_address(),
+        output_size: 32,
+        expected_exit_code: PrecompileExit::Reverted,
+        gas_avaliable: 10_000_000_000,
+        call_op: util::PrecompileCallOpcode::StaticCall,
+        input,
+        expected_return: vec![],
+    };
+    test.run_test(&rt);
+}
@@ -80,6 +80,7 @@ fn test_selfdestruct() {
     // We should now be able to resurrect.
     rt.set_caller(*EAM_ACTOR_CODE_ID, EAM_ACTOR_ADDR);
     rt.expect_validate_caller_addr(vec![EAM_ACTOR_ADDR]);
+    util::expect_register_for_storage_rent(&rt);
     rt.call::<EvmContractActor>(Method::Resurrect as MethodNum, resurrect_params).unwrap();
     rt.verify();
 
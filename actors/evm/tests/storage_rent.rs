@@ -0,0 +1,267 @@
+#![cfg(feature = "storage-rent")]
+
+use fil_actor_evm::ext::cron::DEREGISTER_ENTRY_METHOD;
+use fil_actor_evm::interpreter::opcodes::*;
+use fil_actor_evm::rent::RENT_PER_SLOT_PER_EPOCH;
+use fil_actor_evm::{DelegateCallParams, EvmContractActor, Method, State};
+use fil_actors_evm_shared::address::EthAddress;
+use fil_actors_evm_shared::uints::U256;
+use fil_actors_runtime::runtime::{ActorCode, EMPTY_ARR_CID};
+use fil_actors_runtime::test_utils::*;
+use fil_actors_runtime::{BURNT_FUNDS_ACTOR_ADDR, CRON_ACTOR_ADDR};
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_ipld_encoding::{BytesSer, RawBytes, DAG_CBOR};
+use fvm_shared::address::Address;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
+use fvm_shared::METHOD_SEND;
+use num_traits::Zero;
+
+mod util;
+
+// Wraps runtime bytecode in initcode that returns it.
+fn initcode(code: &[u8]) -> Vec<u8> {
+    let mut initcode = vec![
+        PUSH1,
+        code.len() as u8,
+        DUP1,
+        PUSH1,
+        11, // offset of the code, following this initcode
+        PUSH1,
+        0x00,
+        CODECOPY,
+        PUSH1,
+        0x00,
+        RETURN,
+    ];
+    initcode.extend_from_slice(code);
+    initcode
+}
+
+// Stores the second word of input at the slot given by the first.
+fn store() -> Vec<u8> {
+    initcode(&[PUSH1, 0x20, CALLDATALOAD, PUSH0, CALLDATALOAD, SSTORE, STOP])
+}
+
+// Returns the contract's balance.
+fn self_balance() -> Vec<u8> {
+    initcode(&[SELFBALANCE, PUSH0, MSTORE, PUSH1, 0x20, PUSH0, RETURN])
+}
+
+// Self-destructs, sending the contract's funds to the beneficiary given as input.
+fn destruct() -> Vec<u8> {
+    initcode(&[PUSH0, CALLDATALOAD, SELFDESTRUCT])
+}
+
+fn store_input(key: u64, value: u64) -> Vec<u8> {
+    let mut input = U256::from(key).to_bytes().to_vec();
+    input.extend_from_slice(&U256::from(value).to_bytes());
+    input
+}
+
+fn rent(atto: u64) -> TokenAmount {
+    TokenAmount::from_atto(atto)
+}
+
+fn deposit(rt: &MockRuntime, amount: TokenAmount) {
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, Address::new_id(100));
+    rt.add_balance(amount.clone());
+    rt.set_received(amount);
+    rt.expect_validate_caller_any();
+    rt.call::<EvmContractActor>(Method::DepositStorageRent as u64, None).unwrap();
+    rt.verify();
+    rt.set_received(TokenAmount::zero());
+}
+
+fn charge(rt: &MockRuntime, expect_burnt: TokenAmount) {
+    rt.set_caller(*CRON_ACTOR_CODE_ID, CRON_ACTOR_ADDR);
+    rt.expect_validate_caller_addr(vec![CRON_ACTOR_ADDR]);
+    if expect_burnt.is_positive() {
+        rt.expect_send_simple(
+            BURNT_FUNDS_ACTOR_ADDR,
+            METHOD_SEND,
+            None,
+            expect_burnt,
+            None,
+            ExitCode::OK,
+        );
+    }
+    rt.call::<EvmContractActor>(Method::ChargeStorageRent as u64, None).unwrap();
+    rt.verify();
+}
+
+#[test]
+fn counts_storage_slots() {
+    let rt = util::construct_and_verify(store());
+
+    util::invoke_contract(&rt, &store_input(1, 0x42));
+    util::invoke_contract(&rt, &store_input(2, 0x42));
+    assert_eq!(2, rt.get_state::<State>().rent.slots);
+
+    // Overwriting a slot doesn't add one.
+    util::invoke_contract(&rt, &store_input(1, 0x43));
+    assert_eq!(2, rt.get_state::<State>().rent.slots);
+
+    // Clearing a slot removes it, and clearing an empty one does nothing.
+    util::invoke_contract(&rt, &store_input(1, 0));
+    util::invoke_contract(&rt, &store_input(3, 0));
+    assert_eq!(1, rt.get_state::<State>().rent.slots);
+}
+
+#[test]
+fn charges_rent_and_suspends_on_depletion() {
+    let rt = util::construct_and_verify(store());
+    util::invoke_contract(&rt, &store_input(1, 0x42));
+    util::invoke_contract(&rt, &store_input(2, 0x42));
+    deposit(&rt, rent(25 * RENT_PER_SLOT_PER_EPOCH));
+
+    // Rent for two slots over ten epochs is burnt from the prepaid balance.
+    rt.set_epoch(10);
+    charge(&rt, rent(20 * RENT_PER_SLOT_PER_EPOCH));
+    let st: State = rt.get_state();
+    assert_eq!(rent(5 * RENT_PER_SLOT_PER_EPOCH), st.rent.balance);
+    assert_eq!(10, st.rent.paid_through);
+    assert!(!st.rent.suspended);
+
+    // The remaining balance is burnt when it can't cover the rent due, suspending the contract.
+    rt.set_epoch(20);
+    charge(&rt, rent(5 * RENT_PER_SLOT_PER_EPOCH));
+    assert!(rt.get_state::<State>().rent.suspended);
+
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, Address::new_id(100));
+    rt.expect_validate_caller_any();
+    expect_abort_contains_message(
+        ExitCode::USR_FORBIDDEN,
+        "suspended",
+        rt.call::<EvmContractActor>(
+            Method::InvokeContract as u64,
+            IpldBlock::serialize_cbor(&BytesSer(&store_input(3, 0x42))).unwrap(),
+        ),
+    );
+    rt.reset();
+
+    // Nor can it execute by delegate call, or to handle a Filecoin method.
+    rt.set_caller(*EVM_ACTOR_CODE_ID, rt.receiver);
+    rt.expect_validate_caller_addr(vec![rt.receiver]);
+    let params = DelegateCallParams {
+        code: EMPTY_ARR_CID,
+        input: vec![],
+        caller: EthAddress::from_id(100),
+        value: TokenAmount::zero(),
+    };
+    expect_abort_contains_message(
+        ExitCode::USR_FORBIDDEN,
+        "suspended",
+        rt.call::<EvmContractActor>(
+            Method::InvokeContractDelegate as u64,
+            Some(IpldBlock {
+                codec: DAG_CBOR,
+                data: RawBytes::serialize(params).unwrap().to_vec(),
+            }),
+        ),
+    );
+    rt.reset();
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, Address::new_id(100));
+    rt.expect_validate_caller_any();
+    expect_abort_contains_message(
+        ExitCode::USR_FORBIDDEN,
+        "suspended",
+        rt.call::<EvmContractActor>(frc42_dispatch::method_hash!("Handle"), None),
+    );
+    rt.reset();
+
+    // No rent is charged while suspended.
+    rt.set_epoch(30);
+    charge(&rt, TokenAmount::zero());
+}
+
+#[test]
+fn top_up_revives_suspended_contract() {
+    let rt = util::construct_and_verify(store());
+    util::invoke_contract(&rt, &store_input(1, 0x42));
+    rt.set_epoch(10);
+    charge(&rt, TokenAmount::zero());
+    assert!(rt.get_state::<State>().rent.suspended);
+
+    // A deposit short of an epoch's rent leaves the contract suspended.
+    rt.set_epoch(15);
+    deposit(&rt, rent(RENT_PER_SLOT_PER_EPOCH - 1));
+    assert!(rt.get_state::<State>().rent.suspended);
+
+    deposit(&rt, rent(RENT_PER_SLOT_PER_EPOCH * 10));
+    let st: State = rt.get_state();
+    assert!(!st.rent.suspended);
+    assert_eq!(15, st.rent.paid_through);
+
+    // The contract can be invoked again, and pays rent from revival.
+    util::invoke_contract(&rt, &store_input(2, 0x42));
+    rt.set_epoch(17);
+    charge(&rt, rent(4 * RENT_PER_SLOT_PER_EPOCH));
+}
+
+#[test]
+fn prepaid_rent_is_held_in_escrow() {
+    let rt = util::construct_and_verify(self_balance());
+    rt.add_balance(rent(7));
+    deposit(&rt, rent(25 * RENT_PER_SLOT_PER_EPOCH));
+
+    // The contract's balance excludes the escrowed rent, which it can't spend.
+    assert_eq!(U256::from(7), U256::from_big_endian(&util::invoke_contract(&rt, &[])));
+}
+
+#[test]
+fn dead_contract_burns_escrow_and_deregisters() {
+    let rt = util::construct_and_verify(destruct());
+    rt.set_origin(Address::new_id(100));
+    rt.add_balance(rent(7));
+    deposit(&rt, rent(10 * RENT_PER_SLOT_PER_EPOCH));
+
+    // Self-destructing sends away only the funds not in escrow.
+    let beneficiary = EthAddress::from_id(1001).as_evm_word().to_bytes();
+    rt.expect_send_simple(Address::new_id(1001), METHOD_SEND, None, rent(7), None, ExitCode::OK);
+    util::invoke_contract(&rt, &beneficiary);
+    rt.verify();
+
+    // Once dead, the escrowed rent is burnt and the contract stops being charged.
+    rt.set_origin(Address::new_id(101));
+    rt.set_caller(*CRON_ACTOR_CODE_ID, CRON_ACTOR_ADDR);
+    rt.expect_validate_caller_addr(vec![CRON_ACTOR_ADDR]);
+    rt.expect_send_simple(
+        BURNT_FUNDS_ACTOR_ADDR,
+        METHOD_SEND,
+        None,
+        rent(10 * RENT_PER_SLOT_PER_EPOCH),
+        None,
+        ExitCode::OK,
+    );
+    rt.expect_send_simple(
+        CRON_ACTOR_ADDR,
+        DEREGISTER_ENTRY_METHOD,
+        None,
+        TokenAmount::zero(),
+        None,
+        ExitCode::OK,
+    );
+    rt.call::<EvmContractActor>(Method::ChargeStorageRent as u64, None).unwrap();
+    rt.verify();
+    assert_eq!(TokenAmount::zero(), rt.get_balance());
+}
+
+#[test]
+fn charge_requires_cron_caller() {
+    let rt = util::construct_and_verify(store());
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, Address::new_id(100));
+    rt.expect_validate_caller_addr(vec![CRON_ACTOR_ADDR]);
+    expect_abort(
+        ExitCode::USR_FORBIDDEN,
+        rt.call::<EvmContractActor>(Method::ChargeStorageRent as u64, None),
+    );
+}
+
+#[test]
+fn reports_storage_rent_feature() {
+    // The contract's own code handles GetActorInfo's method number, so the actor's info is only
+    // available to built-in code.
+    let info = EvmContractActor::info();
+    assert!(info.features.contains(&"storage-rent".to_string()));
+}
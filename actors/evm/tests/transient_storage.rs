@@ -0,0 +1,85 @@
+use fil_actor_evm::interpreter::opcodes::*;
+use fil_actor_evm::State;
+use fil_actors_evm_shared::uints::U256;
+use fvm_shared::address::Address;
+
+mod util;
+
+// Wraps runtime bytecode in initcode that returns it.
+fn initcode(code: &[u8]) -> Vec<u8> {
+    let mut initcode = vec![
+        PUSH1,
+        code.len() as u8,
+        DUP1,
+        PUSH1,
+        11, // offset of the code, following this initcode
+        PUSH1,
+        0x00,
+        CODECOPY,
+        PUSH1,
+        0x00,
+        RETURN,
+    ];
+    initcode.extend_from_slice(code);
+    initcode
+}
+
+// Returns the transient value at slot zero and, if called with a word of input, replaces it.
+fn transient_counter() -> Vec<u8> {
+    initcode(&[
+        PUSH0,
+        TLOAD,
+        CALLDATASIZE,
+        ISZERO,
+        PUSH1,
+        11,
+        JUMPI,
+        PUSH0,
+        CALLDATALOAD,
+        PUSH0,
+        TSTORE,
+        JUMPDEST,
+        PUSH0,
+        MSTORE,
+        PUSH1,
+        0x20,
+        PUSH0,
+        RETURN,
+    ])
+}
+
+#[test]
+fn test_transient_storage_lifespan() {
+    let rt = util::init_construct_and_verify(transient_counter(), |rt| {
+        rt.set_origin(Address::new_id(100));
+    });
+    let value = U256::from(0x42);
+
+    // Transient storage persists across calls within the same top-level message.
+    assert_eq!(U256::from_big_endian(&util::invoke_contract(&rt, &value.to_bytes())), U256::zero());
+    rt.verify();
+    assert_eq!(U256::from_big_endian(&util::invoke_contract(&rt, &[])), value);
+    rt.verify();
+    let state: State = rt.get_state();
+    assert!(state.transient_data.is_some());
+
+    // A new top-level message starts with empty transient storage.
+    rt.set_origin(Address::new_id(101));
+    assert_eq!(U256::from_big_endian(&util::invoke_contract(&rt, &[])), U256::zero());
+    rt.verify();
+
+    // Stale transient storage is discarded the next time state is written.
+    let other = U256::from(0x43);
+    assert_eq!(U256::from_big_endian(&util::invoke_contract(&rt, &other.to_bytes())), U256::zero());
+    rt.verify();
+    let state: State = rt.get_state();
+    assert_eq!(101, state.transient_data.unwrap().transient_data_lifespan.origin);
+    assert_eq!(U256::from_big_endian(&util::invoke_contract(&rt, &[])), other);
+    rt.verify();
+
+    // Clearing a slot leaves it empty for the rest of the message.
+    assert_eq!(U256::from_big_endian(&util::invoke_contract(&rt, &U256::zero().to_bytes())), other);
+    rt.verify();
+    assert_eq!(U256::from_big_endian(&util::invoke_contract(&rt, &[])), U256::zero());
+    rt.verify();
+}
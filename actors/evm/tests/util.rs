@@ -49,6 +49,7 @@ pub fn init_construct_and_verify<F: FnOnce(&MockRuntime)>(
         creator: EthAddress::from_id(fil_actors_runtime::EAM_ACTOR_ADDR.id().unwrap()),
         initcode: initcode.into(),
     };
+    expect_register_for_storage_rent(&rt);
 
     assert!(rt
         .call::<evm::EvmContractActor>(
@@ -65,6 +66,30 @@ pub fn init_construct_and_verify<F: FnOnce(&MockRuntime)>(
     rt
 }
 
+/// Expects a constructed contract to register with cron to be charged storage rent.
+#[cfg(feature = "storage-rent")]
+pub fn expect_register_for_storage_rent(rt: &MockRuntime) {
+    use fil_actor_evm::ext::cron::{RegisterEntryParams, REGISTER_ENTRY_METHOD};
+    use fvm_shared::econ::TokenAmount;
+    use fvm_shared::error::ExitCode;
+    use num_traits::Zero;
+
+    rt.expect_send_simple(
+        fil_actors_runtime::CRON_ACTOR_ADDR,
+        REGISTER_ENTRY_METHOD,
+        IpldBlock::serialize_cbor(&RegisterEntryParams {
+            method_num: evm::Method::ChargeStorageRent as u64,
+        })
+        .unwrap(),
+        TokenAmount::zero(),
+        None,
+        ExitCode::OK,
+    );
+}
+
+#[cfg(not(feature = "storage-rent"))]
+pub fn expect_register_for_storage_rent(_rt: &MockRuntime) {}
+
 #[allow(dead_code)]
 pub fn invoke_contract(rt: &MockRuntime, input_data: &[u8]) -> Vec<u8> {
     rt.expect_validate_caller_any();
@@ -114,6 +139,8 @@ pub enum NativePrecompile {
     CallActor = 3,
     GetActorType = 4,
     CallActorId = 5,
+    CallActorView = 6,
+    VerifyBlsSignature = 7,
 }
 
 #[allow(dead_code)]
@@ -2,13 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 use cid::Cid;
-use fil_actors_runtime::runtime::builtins::Type;
+use fil_actors_runtime::runtime::builtins::{ActorType, Type};
 use fil_actors_runtime::runtime::{ActorCode, Runtime};
 
 use fil_actors_runtime::{
     actor_dispatch, actor_error, extract_send_result, ActorContext, ActorError, AsActorError,
     EAM_ACTOR_ADDR, SYSTEM_ACTOR_ADDR,
 };
+use fvm_ipld_encoding::RawBytes;
 use fvm_shared::address::Address;
 use fvm_shared::error::ExitCode;
 use fvm_shared::{ActorID, METHOD_CONSTRUCTOR};
@@ -31,6 +32,9 @@ pub enum Method {
     Constructor = METHOD_CONSTRUCTOR,
     Exec = 2,
     Exec4 = 3,
+    ExecDelegated = 4,
+    // Method numbers derived from FRC-0042 standards
+    ExecExported = frc42_dispatch::method_hash!("Exec"),
 }
 
 /// Init actor
@@ -119,51 +123,107 @@ impl Actor {
 
         log::trace!("delegated address: {:?}", &delegated_address);
 
-        // Compute a re-org-stable address.
-        // This address exists for use by messages coming from outside the system, in order to
-        // stably address the newly created actor even if a chain re-org causes it to end up with
-        // a different ID.
-        let robust_address = rt.new_actor_address()?;
+        let (id_address, robust_address) = create_with_delegated_address(
+            rt,
+            params.code_cid,
+            params.constructor_params,
+            delegated_address,
+        )?;
+        Ok(Exec4Return { id_address, robust_address })
+    }
 
-        log::trace!("robust address: {:?}", &robust_address);
+    /// Creates an actor with an f4 address in the calling address manager's namespace.
+    ///
+    /// This is the equivalent of Exec4 for builtin actor factories other than the EAM (currently
+    /// only the power actor, creating miners), which derive the subaddress themselves so that the
+    /// new actor's address can be known before it is created.
+    pub fn exec_delegated(
+        rt: &impl Runtime,
+        params: ExecDelegatedParams,
+    ) -> Result<ExecDelegatedReturn, ActorError> {
+        rt.validate_immediate_caller_type(std::iter::once(&Type::Power))?;
 
-        // Allocate an ID for this actor.
-        // Store mapping of actor addresses to the actor ID.
-        let (id_address, existing): (ActorID, bool) = rt.transaction(|s: &mut State, rt| {
-            s.map_addresses_to_id(rt.store(), &robust_address, Some(&delegated_address))
-                .context("failed to map addresses to ID")
+        let caller_id = rt.message().caller().id().unwrap();
+        let caller_code = rt.get_actor_code_cid(&caller_id).ok_or_else(|| {
+            actor_error!(illegal_state, "no code for caller as {}", rt.message().caller())
         })?;
-
-        // If the f4 address was already assigned, make sure we're deploying over a placeholder and not
-        // some other existing actor (and make sure the target actor wasn't deleted either).
-        if existing {
-            let code_cid = rt
-                .get_actor_code_cid(&id_address)
-                .context_code(ExitCode::USR_FORBIDDEN, "cannot redeploy a deleted actor")?;
-            let placeholder_cid = rt.get_code_cid_for_type(Type::Placeholder);
-            if code_cid != placeholder_cid {
-                return Err(ActorError::forbidden(format!(
-                    "cannot replace an existing non-placeholder actor with code: {code_cid}"
-                )));
-            }
+        if !can_exec(rt, &caller_code, &params.code_cid) {
+            return Err(actor_error!(forbidden;
+                    "called type {} cannot exec actor type {}",
+                    &caller_code, &params.code_cid
+            ));
         }
 
-        // Create an empty actor
-        rt.create_actor(params.code_cid, id_address, Some(delegated_address))?;
+        // Compute the f4 address.
+        let delegated_address =
+            Address::new_delegated(caller_id, &params.subaddress).map_err(|e| {
+                ActorError::illegal_argument(format!("invalid delegated address: {}", e))
+            })?;
 
-        // Invoke constructor
-        extract_send_result(rt.send_simple(
-            &Address::new_id(id_address),
-            METHOD_CONSTRUCTOR,
-            params.constructor_params.into(),
-            rt.message().value_received(),
-        ))
-        .context("constructor failed")?;
+        log::trace!("delegated address: {:?}", &delegated_address);
 
-        Ok(Exec4Return { id_address: Address::new_id(id_address), robust_address })
+        let (id_address, robust_address) = create_with_delegated_address(
+            rt,
+            params.code_cid,
+            params.constructor_params,
+            delegated_address,
+        )?;
+        Ok(ExecDelegatedReturn { id_address, robust_address, delegated_address })
     }
 }
 
+/// Creates an actor with a delegated address, as well as a robust address, and invokes its
+/// constructor. Returns the new actor's ID and robust addresses.
+fn create_with_delegated_address(
+    rt: &impl Runtime,
+    code_cid: Cid,
+    constructor_params: RawBytes,
+    delegated_address: Address,
+) -> Result<(Address, Address), ActorError> {
+    // Compute a re-org-stable address.
+    // This address exists for use by messages coming from outside the system, in order to
+    // stably address the newly created actor even if a chain re-org causes it to end up with
+    // a different ID.
+    let robust_address = rt.new_actor_address()?;
+
+    log::trace!("robust address: {:?}", &robust_address);
+
+    // Allocate an ID for this actor.
+    // Store mapping of actor addresses to the actor ID.
+    let (id_address, existing): (ActorID, bool) = rt.transaction(|s: &mut State, rt| {
+        s.map_addresses_to_id(rt.store(), &robust_address, Some(&delegated_address))
+            .context("failed to map addresses to ID")
+    })?;
+
+    // If the f4 address was already assigned, make sure we're deploying over a placeholder and not
+    // some other existing actor (and make sure the target actor wasn't deleted either).
+    if existing {
+        let existing_code = rt
+            .get_actor_code_cid(&id_address)
+            .context_code(ExitCode::USR_FORBIDDEN, "cannot redeploy a deleted actor")?;
+        let placeholder_cid = rt.get_code_cid_for_type(Type::Placeholder);
+        if existing_code != placeholder_cid {
+            return Err(ActorError::forbidden(format!(
+                "cannot replace an existing non-placeholder actor with code: {existing_code}"
+            )));
+        }
+    }
+
+    // Create an empty actor
+    rt.create_actor(code_cid, id_address, Some(delegated_address))?;
+
+    // Invoke constructor
+    extract_send_result(rt.send_simple(
+        &Address::new_id(id_address),
+        METHOD_CONSTRUCTOR,
+        constructor_params.into(),
+        rt.message().value_received(),
+    ))
+    .context("constructor failed")?;
+
+    Ok((Address::new_id(id_address), robust_address))
+}
+
 impl ActorCode for Actor {
     type Methods = Method;
 
@@ -171,19 +231,37 @@ impl ActorCode for Actor {
         "Init"
     }
 
+    fil_actors_runtime::actor_info! {
+        interface_revisions: [1],
+        features: [],
+    }
+
     actor_dispatch! {
         Constructor => constructor,
         Exec => exec,
         Exec4 => exec4,
+        ExecDelegated => exec_delegated,
+        ExecExported => exec,
     }
 }
 
 fn can_exec(rt: &impl Runtime, caller: &Cid, exec: &Cid) -> bool {
-    rt.resolve_builtin_actor_type(exec)
-        .map(|typ| match typ {
-            Type::Multisig | Type::PaymentChannel => true,
-            Type::Miner if rt.resolve_builtin_actor_type(caller) == Some(Type::Power) => true,
-            _ => false,
-        })
-        .unwrap_or(false)
+    match rt.resolve_builtin_actor_type_checked(exec) {
+        ActorType::Multisig | ActorType::PaymentChannel => true,
+        ActorType::Miner => rt.resolve_builtin_actor_type_checked(caller) == ActorType::Power,
+        ActorType::System
+        | ActorType::Init
+        | ActorType::Cron
+        | ActorType::Account
+        | ActorType::Power
+        | ActorType::Market
+        | ActorType::Reward
+        | ActorType::VerifiedRegistry
+        | ActorType::DataCap
+        | ActorType::Placeholder
+        | ActorType::EVM
+        | ActorType::EAM
+        | ActorType::EthAccount
+        | ActorType::Unknown => false,
+    }
 }
@@ -38,3 +38,23 @@ pub struct Exec4Params {
 
 /// Init actor Exec4 Return value
 pub type Exec4Return = ExecReturn;
+
+/// Init actor ExecDelegated Params
+#[derive(Serialize_tuple, Deserialize_tuple)]
+pub struct ExecDelegatedParams {
+    pub code_cid: Cid,
+    pub constructor_params: RawBytes,
+    /// Subaddress of the new actor's f4 address, in the caller's namespace
+    pub subaddress: RawBytes,
+}
+
+/// Init actor ExecDelegated Return value
+#[derive(Debug, Serialize_tuple, Deserialize_tuple)]
+pub struct ExecDelegatedReturn {
+    /// ID based address for created actor
+    pub id_address: Address,
+    /// Reorg safe address for actor
+    pub robust_address: Address,
+    /// Delegated (f4) address for actor, in the caller's namespace
+    pub delegated_address: Address,
+}
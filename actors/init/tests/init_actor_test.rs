@@ -6,13 +6,15 @@ use std::cell::RefCell;
 use cid::Cid;
 use fil_actor_init::testing::check_state_invariants;
 use fil_actor_init::{
-    Actor as InitActor, ConstructorParams, Exec4Params, Exec4Return, ExecParams, ExecReturn,
-    Method, State,
+    Actor as InitActor, ConstructorParams, Exec4Params, Exec4Return, ExecDelegatedParams,
+    ExecDelegatedReturn, ExecParams, ExecReturn, Method, State,
 };
+use fil_actors_runtime::runtime::builtins::Type;
 use fil_actors_runtime::runtime::Runtime;
 use fil_actors_runtime::{test_utils::*, EAM_ACTOR_ADDR, EAM_ACTOR_ID};
 use fil_actors_runtime::{
-    ActorError, Multimap, FIRST_NON_SINGLETON_ADDR, STORAGE_POWER_ACTOR_ADDR, SYSTEM_ACTOR_ADDR,
+    ActorError, Multimap, FIRST_NON_SINGLETON_ADDR, STORAGE_POWER_ACTOR_ADDR,
+    STORAGE_POWER_ACTOR_ID, SYSTEM_ACTOR_ADDR,
 };
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_ipld_encoding::RawBytes;
@@ -51,6 +53,23 @@ fn abort_cant_call_exec() {
     check_state(&rt);
 }
 
+// Only the power actor may create miners, and no one may create actors of unknown code.
+#[test]
+fn abort_exec_by_actor_type() {
+    let rt = construct_runtime();
+    construct_and_verify(&rt);
+
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, Address::new_id(1001));
+    let err = exec_and_verify(&rt, *MINER_ACTOR_CODE_ID, &"").expect_err("Exec should have failed");
+    assert_eq!(err.exit_code(), ExitCode::USR_FORBIDDEN);
+
+    rt.set_caller(*POWER_ACTOR_CODE_ID, STORAGE_POWER_ACTOR_ADDR);
+    let unknown_code = make_identity_cid(b"unknown");
+    let err = exec_and_verify(&rt, unknown_code, &"").expect_err("Exec should have failed");
+    assert_eq!(err.exit_code(), ExitCode::USR_FORBIDDEN);
+    check_state(&rt);
+}
+
 #[test]
 fn repeated_robust_address() {
     let rt = construct_runtime();
@@ -160,6 +179,56 @@ fn create_2_payment_channels() {
     }
 }
 
+#[test]
+fn contract_creates_payment_channel() {
+    let rt = construct_runtime();
+    construct_and_verify(&rt);
+    let contract = Address::new_id(1001);
+    rt.set_caller(*EVM_ACTOR_CODE_ID, contract);
+
+    let paych = b"paych";
+    let unique_address = Address::new_actor(paych);
+    rt.new_actor_addr.replace(Some(unique_address));
+    let fake_params = ConstructorParams { network_name: String::from("fake_param") };
+    let exec_params = ExecParams {
+        code_cid: *PAYCH_ACTOR_CODE_ID,
+        constructor_params: RawBytes::serialize(&fake_params).unwrap(),
+    };
+
+    // Contracts may not call the internal method number.
+    expect_abort_contains_message(
+        ExitCode::USR_FORBIDDEN,
+        "must be built-in",
+        rt.call::<InitActor>(Method::Exec as u64, IpldBlock::serialize_cbor(&exec_params).unwrap()),
+    );
+    rt.verify();
+
+    let expected_id_addr = Address::new_id(100);
+    rt.expect_create_actor(*PAYCH_ACTOR_CODE_ID, 100, None);
+    rt.expect_send_simple(
+        expected_id_addr,
+        METHOD_CONSTRUCTOR,
+        IpldBlock::serialize_cbor(&fake_params).unwrap(),
+        TokenAmount::zero(),
+        None,
+        ExitCode::OK,
+    );
+    rt.expect_validate_caller_any();
+    let ret: ExecReturn = rt
+        .call::<InitActor>(
+            Method::ExecExported as u64,
+            IpldBlock::serialize_cbor(&exec_params).unwrap(),
+        )
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    assert_eq!(unique_address, ret.robust_address);
+    assert_eq!(expected_id_addr, ret.id_address);
+    check_state(&rt);
+}
+
 #[test]
 fn create_storage_miner() {
     let rt = construct_runtime();
@@ -411,6 +480,105 @@ fn call_exec4_placeholder() {
     assert_eq!(expected_id_addr, resolved_id, "f4 address not assigned to the right actor");
 }
 
+#[test]
+fn call_exec_delegated() {
+    let rt = construct_runtime();
+    construct_and_verify(&rt);
+
+    let unique_address = Address::new_actor(b"miner");
+    rt.new_actor_addr.replace(Some(unique_address));
+
+    // The f4 address is in the power actor's namespace.
+    let subaddr = b"foobar";
+    let f4_addr = Address::new_delegated(STORAGE_POWER_ACTOR_ID, subaddr).unwrap();
+
+    let expected_id = 100;
+    let expected_id_addr = Address::new_id(expected_id);
+    rt.expect_create_actor(*MINER_ACTOR_CODE_ID, expected_id, Some(f4_addr));
+
+    let fake_params = ConstructorParams { network_name: String::from("fake_param") };
+    rt.expect_send_simple(
+        expected_id_addr,
+        METHOD_CONSTRUCTOR,
+        IpldBlock::serialize_cbor(&fake_params).unwrap(),
+        TokenAmount::zero(),
+        None,
+        ExitCode::OK,
+    );
+
+    let exec_ret = exec_delegated_and_verify(
+        &rt,
+        *POWER_ACTOR_CODE_ID,
+        STORAGE_POWER_ACTOR_ADDR,
+        subaddr,
+        *MINER_ACTOR_CODE_ID,
+        &fake_params,
+    )
+    .unwrap();
+    assert_eq!(expected_id_addr, exec_ret.id_address);
+    assert_eq!(unique_address, exec_ret.robust_address);
+    assert_eq!(f4_addr, exec_ret.delegated_address);
+
+    // Both addresses resolve to the new actor.
+    let state: State = rt.get_state();
+    assert_eq!(Some(expected_id_addr), state.resolve_address(rt.store(), &f4_addr).unwrap());
+    assert_eq!(Some(expected_id_addr), state.resolve_address(rt.store(), &unique_address).unwrap());
+
+    // The same f4 address can't be assigned again.
+    rt.new_actor_addr.replace(Some(Address::new_actor(b"miner2")));
+    let exec_err = exec_delegated_and_verify(
+        &rt,
+        *POWER_ACTOR_CODE_ID,
+        STORAGE_POWER_ACTOR_ADDR,
+        subaddr,
+        *MINER_ACTOR_CODE_ID,
+        &fake_params,
+    )
+    .unwrap_err();
+    assert_eq!(ExitCode::USR_FORBIDDEN, exec_err.exit_code());
+}
+
+#[test]
+fn exec_delegated_restricted() {
+    let rt = construct_runtime();
+    construct_and_verify(&rt);
+    let fake_params = ConstructorParams { network_name: String::from("fake_param") };
+
+    // The EAM and other actors can't call it.
+    let err = exec_delegated_and_verify(
+        &rt,
+        *EAM_ACTOR_CODE_ID,
+        EAM_ACTOR_ADDR,
+        b"foobar",
+        *MINER_ACTOR_CODE_ID,
+        &fake_params,
+    )
+    .unwrap_err();
+    assert_eq!(ExitCode::USR_FORBIDDEN, err.exit_code());
+    let err = exec_delegated_and_verify(
+        &rt,
+        *ACCOUNT_ACTOR_CODE_ID,
+        Address::new_id(1001),
+        b"foobar",
+        *MULTISIG_ACTOR_CODE_ID,
+        &fake_params,
+    )
+    .unwrap_err();
+    assert_eq!(ExitCode::USR_FORBIDDEN, err.exit_code());
+
+    // The power actor may only create miners.
+    let err = exec_delegated_and_verify(
+        &rt,
+        *POWER_ACTOR_CODE_ID,
+        STORAGE_POWER_ACTOR_ADDR,
+        b"foobar",
+        *ACCOUNT_ACTOR_CODE_ID,
+        &fake_params,
+    )
+    .unwrap_err();
+    assert_eq!(ExitCode::USR_FORBIDDEN, err.exit_code());
+}
+
 fn construct_and_verify(rt: &MockRuntime) {
     rt.set_caller(*SYSTEM_ACTOR_CODE_ID, SYSTEM_ACTOR_ADDR);
     rt.expect_validate_caller_addr(vec![SYSTEM_ACTOR_ADDR]);
@@ -480,3 +648,32 @@ where
     check_state(rt);
     ret.and_then(|v| v.unwrap().deserialize().map_err(|e| e.into()))
 }
+
+fn exec_delegated_and_verify<S: Serialize>(
+    rt: &MockRuntime,
+    caller_code: Cid,
+    caller: Address,
+    subaddr: &[u8],
+    code_id: Cid,
+    params: &S,
+) -> Result<ExecDelegatedReturn, ActorError>
+where
+    S: Serialize,
+{
+    rt.set_caller(caller_code, caller);
+    rt.expect_validate_caller_type(vec![Type::Power]);
+    let exec_params = ExecDelegatedParams {
+        code_cid: code_id,
+        constructor_params: RawBytes::serialize(params).unwrap(),
+        subaddress: subaddr.to_owned().into(),
+    };
+
+    let ret = rt.call::<InitActor>(
+        Method::ExecDelegated as u64,
+        IpldBlock::serialize_cbor(&exec_params).unwrap(),
+    );
+
+    rt.verify();
+    check_state(rt);
+    ret.and_then(|v| v.unwrap().deserialize().map_err(|e| e.into()))
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 use cid::{Cid, Version};
+use fil_actors_runtime::query::Indexed;
 use fvm_ipld_encoding::tuple::*;
 use fvm_ipld_encoding::BytesSer;
 use fvm_shared::address::Address;
@@ -13,7 +14,9 @@ use fvm_shared::piece::PaddedPieceSize;
 use fvm_shared::sector::SectorNumber;
 use libipld_core::ipld::Ipld;
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_repr::{Deserialize_repr, Serialize_repr};
 use std::convert::{TryFrom, TryInto};
+use std::fmt;
 
 /// Cid prefix for piece Cids
 pub fn is_piece_cid(c: &Cid) -> bool {
@@ -127,11 +130,89 @@ impl DealProposal {
     }
 }
 
+impl Indexed for DealProposal {
+    const INDEXED_FIELDS: &'static [&'static str] =
+        &["client", "provider", "piece-size", "verified", "start-epoch", "end-epoch"];
+
+    fn field(&self, name: &str) -> Option<i128> {
+        match name {
+            "client" => self.client.id().ok().map(i128::from),
+            "provider" => self.provider.id().ok().map(i128::from),
+            "piece-size" => Some(self.piece_size.0.into()),
+            "verified" => Some(self.verified_deal.into()),
+            "start-epoch" => Some(self.start_epoch.into()),
+            "end-epoch" => Some(self.end_epoch.into()),
+            _ => None,
+        }
+    }
+}
+
+/// The message a client authenticates to authorize a deal proposal.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+pub enum ProposalAuthScheme {
+    /// The proposal's CBOR encoding.
+    #[default]
+    Cbor = 0,
+    /// The proposal's EIP-712 typed-data hash, computed by
+    /// [`deal_proposal_typed_data_hash`](crate::deal_proposal_typed_data_hash).
+    TypedData = 1,
+}
+
 /// ClientDealProposal is a DealProposal signed by a client
-#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ClientDealProposal {
     pub proposal: DealProposal,
     pub client_signature: Signature,
+    pub auth_scheme: ProposalAuthScheme,
+}
+
+/// Serialize the ClientDealProposal as a tuple, omitting the authentication scheme if it's the
+/// default so the encoding of CBOR-authenticated proposals is unchanged.
+impl Serialize for ClientDealProposal {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.auth_scheme {
+            ProposalAuthScheme::Cbor => {
+                (&self.proposal, &self.client_signature).serialize(serializer)
+            }
+            scheme => (&self.proposal, &self.client_signature, scheme).serialize(serializer),
+        }
+    }
+}
+
+/// Deserialize the ClientDealProposal from a tuple with an optional authentication scheme.
+impl<'de> Deserialize<'de> for ClientDealProposal {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct TupleVisitor;
+
+        impl<'de> de::Visitor<'de> for TupleVisitor {
+            type Value = ClientDealProposal;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a tuple of a deal proposal, signature and optional auth scheme")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::SeqAccess<'de>,
+            {
+                let proposal =
+                    seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                let client_signature =
+                    seq.next_element()?.ok_or_else(|| de::Error::invalid_length(1, &self))?;
+                let auth_scheme = seq.next_element()?.unwrap_or_default();
+                Ok(ClientDealProposal { proposal, client_signature, auth_scheme })
+            }
+        }
+
+        deserializer.deserialize_seq(TupleVisitor)
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Copy, Serialize_tuple, Deserialize_tuple)]
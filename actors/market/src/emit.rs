@@ -1,7 +1,10 @@
 use fil_actors_runtime::runtime::Runtime;
-use fil_actors_runtime::{ActorError, EventBuilder};
+use fil_actors_runtime::{emit_penalty_assessed, ActorError, EventBuilder, PenaltyCategory};
+use fvm_shared::clock::ChainEpoch;
 use fvm_shared::deal::DealID;
+use fvm_shared::econ::TokenAmount;
 use fvm_shared::ActorID;
+use num_traits::Zero;
 
 /// Indicates a deal has been published.
 pub fn deal_published(
@@ -48,6 +51,55 @@ pub fn deal_terminated(
     )
 }
 
+/// Indicates a deal has been cancelled by its client before activation.
+pub fn deal_cancelled(
+    rt: &impl Runtime,
+    deal_id: DealID,
+    client: ActorID,
+    provider: ActorID,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("deal-cancelled")
+            .with_parties(deal_id, client, provider)
+            .build()?,
+    )
+}
+
+/// Indicates a provider has requested a later start epoch for a deal, pending client approval.
+pub fn deal_activation_extension_requested(
+    rt: &impl Runtime,
+    deal_id: DealID,
+    client: ActorID,
+    provider: ActorID,
+    start_epoch: ChainEpoch,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("deal-activation-extension-requested")
+            .with_parties(deal_id, client, provider)
+            .field("start-epoch", &start_epoch)
+            .build()?,
+    )
+}
+
+/// Indicates a deal's start epoch, and so its activation deadline, has been moved later.
+pub fn deal_activation_extended(
+    rt: &impl Runtime,
+    deal_id: DealID,
+    client: ActorID,
+    provider: ActorID,
+    start_epoch: ChainEpoch,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("deal-activation-extended")
+            .with_parties(deal_id, client, provider)
+            .field("start-epoch", &start_epoch)
+            .build()?,
+    )
+}
+
 /// Indicates a deal has been completed successfully.
 pub fn deal_completed(
     rt: &impl Runtime,
@@ -63,6 +115,16 @@ pub fn deal_completed(
     )
 }
 
+/// Indicates a provider's collateral has been slashed and burnt.
+pub fn collateral_slashed(
+    rt: &impl Runtime,
+    provider: ActorID,
+    category: PenaltyCategory,
+    amount: &TokenAmount,
+) -> Result<(), ActorError> {
+    emit_penalty_assessed(rt, provider, category, amount, amount, &TokenAmount::zero())
+}
+
 trait WithParties {
     fn with_parties(self, id: DealID, client: ActorID, provider: ActorID) -> EventBuilder;
 }
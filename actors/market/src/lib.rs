@@ -33,10 +33,13 @@ use fil_actors_runtime::runtime::builtins::Type;
 use fil_actors_runtime::runtime::{ActorCode, Policy, Runtime};
 use fil_actors_runtime::{
     actor_dispatch, actor_error, deserialize_block, ActorContext, ActorDowncast, ActorError,
-    AsActorError, BURNT_FUNDS_ACTOR_ADDR, CRON_ACTOR_ADDR, DATACAP_TOKEN_ACTOR_ADDR,
-    REWARD_ACTOR_ADDR, STORAGE_POWER_ACTOR_ADDR, SYSTEM_ACTOR_ADDR, VERIFIED_REGISTRY_ACTOR_ADDR,
+    AsActorError, PenaltyCategory, BURNT_FUNDS_ACTOR_ADDR, CRON_ACTOR_ADDR,
+    DATACAP_TOKEN_ACTOR_ADDR, REWARD_ACTOR_ADDR, STORAGE_POWER_ACTOR_ADDR, SYSTEM_ACTOR_ADDR,
+    VERIFIED_REGISTRY_ACTOR_ADDR,
+};
+use fil_actors_runtime::{
+    extract_send_result, verify_key_signatures, BatchReturnGen, FIRST_ACTOR_SPECIFIC_EXIT_CODE,
 };
-use fil_actors_runtime::{extract_send_result, BatchReturnGen, FIRST_ACTOR_SPECIFIC_EXIT_CODE};
 
 use crate::balance_table::BalanceTable;
 use crate::ext::verifreg::{AllocationID, AllocationRequest};
@@ -44,6 +47,7 @@ use crate::ext::verifreg::{AllocationID, AllocationRequest};
 pub use self::deal::*;
 use self::policy::*;
 pub use self::state::*;
+pub use self::typed_data::*;
 pub use self::types::*;
 
 // exports for testing
@@ -56,6 +60,7 @@ pub mod testing;
 mod deal;
 mod emit;
 mod state;
+mod typed_data;
 mod types;
 
 #[cfg(feature = "fil-actor")]
@@ -99,6 +104,11 @@ pub enum Method {
     GetDealSectorExported = frc42_dispatch::method_hash!("GetDealSector"),
     SettleDealPaymentsExported = frc42_dispatch::method_hash!("SettleDealPayments"),
     SectorContentChangedExported = ext::miner::SECTOR_CONTENT_CHANGED,
+    GetClientDealStatsExported = frc42_dispatch::method_hash!("GetClientDealStats"),
+    CancelPublishedDealExported = frc42_dispatch::method_hash!("CancelPublishedDeal"),
+    RequestActivationExtensionExported = frc42_dispatch::method_hash!("RequestActivationExtension"),
+    ApproveActivationExtensionExported = frc42_dispatch::method_hash!("ApproveActivationExtension"),
+    ListDealsExported = frc42_dispatch::method_hash!("ListDeals"),
 }
 
 /// Market Actor
@@ -210,41 +220,64 @@ impl Actor {
             .get_actor_code_cid(&provider_id)
             .ok_or_else(|| actor_error!(not_found, "no code ID for address {}", provider_id))?;
 
-        if rt.resolve_builtin_actor_type(&code_id) != Some(Type::Miner) {
+        if !rt.resolve_builtin_actor_type_checked(&code_id).is_storage_provider() {
             return Err(actor_error!(
                 illegal_argument,
                 "deal provider is not a storage miner actor"
             ));
         }
 
-        let caller = rt.message().caller();
-        let caller_status: ext::miner::IsControllingAddressReturn =
-            deserialize_block(extract_send_result(rt.send_simple(
-                &Address::new_id(provider_id),
-                ext::miner::IS_CONTROLLING_ADDRESS_EXPORTED,
-                IpldBlock::serialize_cbor(&ext::miner::IsControllingAddressParam {
-                    address: caller,
-                })?,
-                TokenAmount::zero(),
-            ))?)?;
-        if !caller_status.is_controlling {
-            return Err(actor_error!(
-                forbidden,
-                "caller {} is not worker or control address of provider {}",
-                caller,
-                provider_id
-            ));
-        }
+        // The provider may publish its own deals, e.g. when onboarding a sector with them.
+        validate_caller_controls_provider(rt, provider_id)?;
         // Deals that passed `AuthenticateMessage` and other state-less checks.
         let mut validity_index: Vec<bool> = Vec::with_capacity(params.deals.len());
 
         let baseline_power = request_current_baseline_power(rt)?;
         let (network_raw_power, _) = request_current_network_power(rt)?;
 
+        // The message each client must authenticate, per the proposal's auth scheme.
+        let auth_messages: Vec<Option<Vec<u8>>> = params
+            .deals
+            .iter()
+            .enumerate()
+            .map(|(di, deal)| match proposal_auth_message(rt, deal) {
+                Ok(message) => Some(message),
+                Err(e) => {
+                    info!("invalid deal {}: {}", di, e);
+                    None
+                }
+            })
+            .collect();
+
+        // Proposals from clients with key addresses are authenticated with a single batched
+        // signature verification, and the rest with `AuthenticateMessage`.
+        let mut key_signatures_valid = verify_key_signatures(
+            rt,
+            params.deals.iter().zip(&auth_messages).filter_map(|(deal, message)| {
+                message.as_ref().map(|message| {
+                    (deal.proposal.client, deal.client_signature.bytes.clone(), message.clone())
+                })
+            }),
+        )?
+        .into_iter();
+
         // We perform these checks before loading state since the call to `AuthenticateMessage` could recurse
-        for (di, deal) in params.deals.iter().enumerate() {
-            let valid = if let Err(e) = validate_deal(rt, deal, &network_raw_power, &baseline_power)
-            {
+        for (di, (deal, message)) in params.deals.iter().zip(auth_messages).enumerate() {
+            let message = match message {
+                Some(message) => message,
+                None => {
+                    validity_index.push(false);
+                    continue;
+                }
+            };
+            let valid = if let Err(e) = validate_deal(
+                rt,
+                deal,
+                message,
+                key_signatures_valid.next().flatten(),
+                &network_raw_power,
+                &baseline_power,
+            ) {
                 info!("invalid deal {}: {}", di, e);
                 false
             } else {
@@ -465,6 +498,18 @@ impl Actor {
             st.put_deal_proposals(rt.store(), &deal_proposals)?;
             st.put_pending_deal_allocation_ids(rt.store(), &pending_deal_allocation_ids)?;
             st.put_deals_by_epoch(rt.store(), &deals_by_epoch)?;
+
+            let client_deals: Vec<(ActorID, u64, TokenAmount)> = valid_deals
+                .iter()
+                .map(|d| {
+                    (
+                        d.proposal.client.id().unwrap(),
+                        d.proposal.piece_size.0,
+                        d.proposal.total_storage_fee(),
+                    )
+                })
+                .collect();
+            st.record_client_deals(rt.store(), rt.curr_epoch(), &client_deals)?;
             Ok(())
         })?;
 
@@ -493,11 +538,13 @@ impl Actor {
             )?;
         }
 
+        rt.increment_counter("market.deals_published", new_deal_ids.len() as u64);
         Ok(PublishStorageDealsReturn { ids: new_deal_ids, valid_deals: valid_input_bf })
     }
 
     /// Verify that a given set of storage deals is valid for a sector currently being PreCommitted
     /// and return UnsealedCID for the set of deals.
+    /// The deals are recorded as pre-committed, after which their clients cannot cancel them.
     fn verify_deals_for_activation(
         rt: &impl Runtime,
         params: VerifyDealsForActivationParams,
@@ -508,6 +555,7 @@ impl Actor {
 
         let st: State = rt.state()?;
         let proposal_array = st.load_proposals(rt.store())?;
+        let mut verified_deals = vec![];
 
         let mut unsealed_cids = Vec::with_capacity(params.sectors.len());
         for sector in params.sectors.iter() {
@@ -533,8 +581,13 @@ impl Actor {
             };
 
             unsealed_cids.push(commd);
+            verified_deals.extend_from_slice(&sector.deal_ids);
         }
 
+        rt.transaction(|st: &mut State, rt| {
+            st.put_precommitted_deals(rt.store(), &verified_deals, curr_epoch)
+        })?;
+
         Ok(VerifyDealsForActivationReturn { unsealed_cids })
     }
 
@@ -659,6 +712,8 @@ impl Actor {
             st.put_deal_states(rt.store(), &deal_states)?;
             st.put_sector_deal_ids(rt.store(), miner_addr.id().unwrap(), &sectors_deals)?;
             st.save_pending_deal_allocation_ids(&mut pending_deal_allocation_ids)?;
+            let activated: Vec<DealID> = deal_states.iter().map(|(id, _)| *id).collect();
+            st.remove_precommitted_deals(rt.store(), &activated)?;
             Ok((activations, batch_gen.gen()))
         })?;
 
@@ -775,6 +830,8 @@ impl Actor {
             st.put_deal_states(rt.store(), &deal_states)?;
             st.put_sector_deal_ids(rt.store(), miner_addr.id().unwrap(), &sectors_deals)?;
             st.save_pending_deal_allocation_ids(&mut pending_deal_allocation_ids)?;
+            let activated: Vec<DealID> = deal_states.iter().map(|(id, _)| *id).collect();
+            st.remove_precommitted_deals(rt.store(), &activated)?;
 
             assert_eq!(sectors_ret.len(), params.sectors.len(), "mismatched sector returns");
             Ok(sectors_ret)
@@ -786,102 +843,69 @@ impl Actor {
     /// Terminate a set of deals in response to their containing sector being terminated.
     /// Slash provider collateral, refund client collateral, and refund partial unpaid escrow
     /// amount to client.
+    ///
+    /// The sectors' deals are terminated until at least `max_deal_terminations_per_batch` have
+    /// been, and the remaining sectors are queued for cron to terminate their deals in later epochs.
+    /// A miner's termination of many sectors at once is thus not limited by the deals they hold.
     fn on_miner_sectors_terminate(
         rt: &impl Runtime,
         params: OnMinerSectorsTerminateParams,
     ) -> Result<(), ActorError> {
         rt.validate_immediate_caller_type(std::iter::once(&Type::Miner))?;
         let miner_addr = rt.message().caller();
+        let max_terminations = rt.policy().max_deal_terminations_per_batch;
 
         let burn_amount = rt.transaction(|st: &mut State, rt| {
             // The sector deals mapping is removed all at once.
             // Note there may be some deal states that are not removed here,
             // despite deletion of this mapping, e.g. for expired but not-yet-settled deals.
             // The sector->deal mapping is no longer needed (the deal state has sector number too).
-            let all_deal_ids = st.pop_sector_deal_ids(
+            let provider = miner_addr.id().unwrap();
+            let (deal_ids, deferred) = st.pop_sector_deal_ids_limited(
                 rt.store(),
-                miner_addr.id().unwrap(),
+                provider,
                 params.sectors.iter(),
+                max_terminations,
             )?;
 
             let mut total_slashed = TokenAmount::zero();
-            for id in all_deal_ids {
-                let deal = st.find_proposal(rt.store(), id)?;
-                // The deal may have expired and been deleted before the sector is terminated.
-                // Nothing to do, but continue execution for the other deals.
-                if deal.is_none() {
-                    info!("couldn't find deal {}", id);
-                    continue;
-                }
-                let deal = deal.unwrap();
-
-                if deal.provider != miner_addr {
-                    return Err(actor_error!(
-                        illegal_state,
-                        "caller {} is not the provider {} of deal {}",
-                        miner_addr,
-                        deal.provider,
-                        id
-                    ));
-                }
-
-                // do not slash expired deals
-                if deal.end_epoch <= params.epoch {
-                    info!("deal {} expired, not slashing", id);
-                    continue;
-                }
-
-                let mut state: DealState = st
-                    .find_deal_state(rt.store(), id)?
-                    // A deal with a proposal but no state is not activated, but then it should not be
-                    // part of a sector that is terminating.
-                    .ok_or_else(|| actor_error!(illegal_argument, "no state for deal {}", id))?;
-
-                // If a deal is already slashed, there should be no existing state for it
-                // but we process it here for deletion anyway
-                if state.slash_epoch != EPOCH_UNDEFINED {
-                    warn!("deal {}, already slashed, terminating now anyway", id);
-                }
-
-                // Deals that were never processed may still have a pending proposal linked
-                if state.last_updated_epoch == EPOCH_UNDEFINED {
-                    let dcid = deal_cid(rt, &deal)?;
-                    st.remove_pending_deal(rt.store(), dcid)?;
-                }
-
-                state.slash_epoch = params.epoch;
-                total_slashed += st.process_slashed_deal(rt.store(), &deal, &state)?;
-                st.remove_completed_deal(rt.store(), id)?;
-
-                emit::deal_terminated(
-                    rt,
-                    id,
-                    deal.client.id().unwrap(),
-                    deal.provider.id().unwrap(),
-                )?;
+            for id in deal_ids {
+                total_slashed += terminate_sector_deal(rt, st, &miner_addr, id, params.epoch)?;
             }
 
+            // Deals in the remaining sectors are left untouched until cron terminates them.
+            let deferred = BitField::try_from_bits(deferred)
+                .context_code(ExitCode::USR_ILLEGAL_ARGUMENT, "invalid sector numbers")?;
+            st.queue_sector_terminations(rt.store(), provider, params.epoch, deferred)?;
+
             Ok(total_slashed)
         })?;
 
-        if burn_amount.is_positive() {
-            extract_send_result(rt.send_simple(
-                &BURNT_FUNDS_ACTOR_ADDR,
-                METHOD_SEND,
-                None,
-                burn_amount,
-            ))?;
-        }
-        Ok(())
+        let mut slashed = Slashings::default();
+        slashed.add(&miner_addr, PenaltyCategory::DealTermination, burn_amount);
+        slashed.burn(rt)
     }
 
     fn cron_tick(rt: &impl Runtime) -> Result<(), ActorError> {
         rt.validate_immediate_caller_is(std::iter::once(&CRON_ACTOR_ADDR))?;
 
-        let mut amount_slashed = TokenAmount::zero();
+        let mut slashed = Slashings::default();
         let curr_epoch = rt.curr_epoch();
 
         rt.transaction(|st: &mut State, rt| {
+            // Terminate a batch of the deals in sectors whose termination was deferred.
+            let pending = st.pop_pending_deal_terminations(
+                rt.store(),
+                rt.policy().max_deal_terminations_per_batch,
+            )?;
+            for (provider, epoch, deal_ids) in pending {
+                let provider = Address::new_id(provider);
+                for deal_id in deal_ids {
+                    let amount = terminate_sector_deal(rt, st, &provider, deal_id, epoch)?;
+                    slashed.add(&provider, PenaltyCategory::DealTermination, amount);
+                }
+            }
+
             let last_cron = st.last_cron;
             let mut provider_deals_to_remove =
                 BTreeMap::<ActorID, BTreeMap<SectorNumber, Vec<DealID>>>::new();
@@ -910,7 +934,11 @@ impl Actor {
                     )? {
                         LoadDealState::Loaded(state) => state,
                         LoadDealState::ProposalExpired(expiration_penalty) => {
-                            amount_slashed += expiration_penalty;
+                            slashed.add(
+                                &deal_proposal.provider,
+                                PenaltyCategory::DealActivationTimeout,
+                                expiration_penalty,
+                            );
                             continue;
                         }
                         LoadDealState::TooEarly => {
@@ -930,6 +958,7 @@ impl Actor {
                                 "failed to delete pending proposal: does not exist"
                             )
                         })?;
+                        st.remove_activation_extensions(rt.store(), &[deal_id])?;
 
                         // newly activated deals are not scheduled for cron processing. they are handled explicitly by
                         // calling ProcessDealUpdates method with specific deal ids.
@@ -940,6 +969,22 @@ impl Actor {
                     // https://github.com/filecoin-project/builtin-actors/issues/1389
                     // handling of legacy deals is still done in cron. we handle such deals here and continue to
                     // reschedule them. eventually, all legacy deals will expire and the below code can be removed.
+
+                    // Deals in sectors awaiting deferred termination are not paid, but remain
+                    // scheduled until they are terminated.
+                    let provider = deal_proposal.provider.id().unwrap();
+                    if st
+                        .find_pending_sector_termination(rt.store(), provider, state.sector_number)?
+                        .is_some()
+                    {
+                        let next_epoch = next_update_epoch(
+                            deal_id,
+                            rt.policy().deal_updates_interval,
+                            curr_epoch + 1,
+                        );
+                        new_updates_scheduled.entry(next_epoch).or_default().push(deal_id);
+                        continue;
+                    }
                     let (slash_amount, _payment_amount, completed, remove_deal) = st
                         .process_deal_update(
                             rt.store(),
@@ -952,7 +997,11 @@ impl Actor {
                     if remove_deal {
                         // TODO: remove handling for terminated-deal slashing when marked-for-termination deals are all processed
                         // https://github.com/filecoin-project/builtin-actors/issues/1388
-                        amount_slashed += slash_amount;
+                        slashed.add(
+                            &deal_proposal.provider,
+                            PenaltyCategory::DealTermination,
+                            slash_amount,
+                        );
 
                         // Delete proposal and state simultaneously.
                         st.remove_completed_deal(rt.store(), deal_id)?;
@@ -1017,15 +1066,7 @@ impl Actor {
             Ok(())
         })?;
 
-        if !amount_slashed.is_zero() {
-            extract_send_result(rt.send_simple(
-                &BURNT_FUNDS_ACTOR_ADDR,
-                METHOD_SEND,
-                None,
-                amount_slashed,
-            ))?;
-        }
-        Ok(())
+        slashed.burn(rt)
     }
 
     /// Returns the data commitment and size of a deal proposal.
@@ -1163,6 +1204,42 @@ impl Actor {
         }
     }
 
+    /// Returns a client's deal publishing activity over the trailing statistics window,
+    /// with per-epoch averages over the whole window.
+    fn get_client_deal_stats(
+        rt: &impl Runtime,
+        params: GetClientDealStatsParams,
+    ) -> Result<GetClientDealStatsReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let client = rt.resolve_address(&params.client).ok_or_else(|| {
+            actor_error!(illegal_argument, "failed to resolve address {}", params.client)
+        })?;
+
+        let st: State = rt.state()?;
+        let totals = st.get_client_deal_stats(rt.store(), client, rt.curr_epoch())?;
+        Ok(GetClientDealStatsReturn {
+            deals_published: totals.deals_published,
+            data_onboarded: totals.data_onboarded,
+            fees_committed_per_epoch: totals.fees_committed.div_floor(CLIENT_DEAL_STATS_WINDOW),
+            fees_committed: totals.fees_committed,
+            window_epochs: CLIENT_DEAL_STATS_WINDOW,
+            data_onboarded_per_epoch: totals.data_onboarded / CLIENT_DEAL_STATS_WINDOW as u64,
+        })
+    }
+
+    /// Lists a page of the deal proposals that have been published and not yet cleaned up,
+    /// in deal ID order.
+    fn list_deals(
+        rt: &impl Runtime,
+        params: ListDealsParams,
+    ) -> Result<ListDealsReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        params.request.validate::<DealProposal>()?;
+        let st: State = rt.state()?;
+        let (entries, next_cursor) = st.list_deals(rt.store(), &params.request)?;
+        Ok(ListDealsReturn { entries, next_cursor })
+    }
+
     /// Fetches the sector in which a deal is stored.
     /// This is available from after a deal is activated until it is finally settled
     /// (either normally or by termination).
@@ -1215,7 +1292,7 @@ impl Actor {
         let mut batch_gen = BatchReturnGen::new(params.deal_ids.len() as usize);
         let mut settlements: Vec<DealSettlementSummary> = Vec::new();
         // accumulates slashed amounts from timed out deal proposals that weren't activated in time
-        let mut total_slashed = TokenAmount::zero();
+        let mut slashed = Slashings::default();
 
         rt.transaction(|st: &mut State, rt| {
             let mut new_deal_states: Vec<(DealID, DealState)> = Vec::new();
@@ -1263,7 +1340,11 @@ impl Actor {
                     }
                     LoadDealState::ProposalExpired(penalty) => {
                         // deal proposal was not activated in time
-                        total_slashed += penalty;
+                        slashed.add(
+                            &deal_proposal.provider,
+                            PenaltyCategory::DealActivationTimeout,
+                            penalty,
+                        );
                         batch_gen.add_fail(EX_DEAL_EXPIRED);
                         continue;
                     }
@@ -1280,6 +1361,23 @@ impl Actor {
                     ));
                 }
 
+                // Deals in sectors awaiting deferred termination are settled by their termination.
+                match st.find_pending_sector_termination(
+                    rt.store(),
+                    deal_proposal.provider.id().unwrap(),
+                    deal_state.sector_number,
+                ) {
+                    Ok(None) => {}
+                    Ok(Some(_)) => {
+                        batch_gen.add_fail(ExitCode::USR_FORBIDDEN);
+                        continue;
+                    }
+                    Err(e) => {
+                        batch_gen.add_fail(e.exit_code());
+                        continue;
+                    }
+                }
+
                 let (_, payment_amount, completed, remove_deal) = match st.process_deal_update(
                     rt.store(),
                     &deal_state,
@@ -1293,6 +1391,10 @@ impl Actor {
                         continue;
                     }
                 };
+                // The first update removed the deal's pending proposal, so those it superseded too.
+                if deal_state.last_updated_epoch == EPOCH_UNDEFINED {
+                    st.remove_activation_extensions(rt.store(), &[deal_id])?;
+                }
 
                 if remove_deal {
                     st.remove_completed_deal(rt.store(), deal_id)?;
@@ -1337,17 +1439,296 @@ impl Actor {
             Ok(())
         })?;
 
-        if !total_slashed.is_zero() {
-            extract_send_result(rt.send_simple(
-                &BURNT_FUNDS_ACTOR_ADDR,
-                METHOD_SEND,
-                None,
-                total_slashed,
-            ))?;
-        }
+        slashed.burn(rt)?;
 
         Ok(SettleDealPaymentsReturn { results: batch_gen.gen(), settlements })
     }
+
+    /// Cancels a published deal that has not been activated, at the request of its client.
+    /// The deal must not have reached its start epoch, nor been verified for a pre-committed sector.
+    /// The provider is compensated with a fee of a fraction of the deal's storage fee, but no
+    /// less than a fraction of its provider collateral, paid from the client's released funds
+    /// and any shortfall from its available escrow. The rest of the client's and provider's
+    /// locked funds are released to their escrow balances.
+    fn cancel_published_deal(
+        rt: &impl Runtime,
+        params: CancelPublishedDealParams,
+    ) -> Result<CancelPublishedDealReturn, ActorError> {
+        let deal_id = params.deal_id;
+        let st: State = rt.state()?;
+        let proposal = st.get_proposal(rt.store(), deal_id)?;
+        // All proposals are stored with normalised addresses.
+        rt.validate_immediate_caller_is(std::iter::once(&proposal.client))?;
+
+        if rt.curr_epoch() >= proposal.start_epoch {
+            return Err(actor_error!(
+                forbidden,
+                "deal {} cannot be cancelled at or after its start epoch {}",
+                deal_id,
+                proposal.start_epoch
+            ));
+        }
+
+        let dcid = deal_cid(rt, &proposal)?;
+        let provider_fee = deal_cancellation_fee(rt.policy(), &proposal);
+        let client_refund = rt.transaction(|st: &mut State, rt| {
+            if st.find_deal_state(rt.store(), deal_id)?.is_some() {
+                return Err(actor_error!(
+                    forbidden,
+                    "deal {} has been activated and cannot be cancelled",
+                    deal_id
+                ));
+            }
+            if st.is_precommitted_deal(rt.store(), deal_id)? {
+                return Err(actor_error!(
+                    forbidden,
+                    "deal {} has been pre-committed and cannot be cancelled",
+                    deal_id
+                ));
+            }
+            st.process_deal_cancelled(rt.store(), deal_id, &proposal, &dcid, &provider_fee)
+        })?;
+
+        emit::deal_cancelled(
+            rt,
+            deal_id,
+            proposal.client.id().unwrap(),
+            proposal.provider.id().unwrap(),
+        )?;
+
+        Ok(CancelPublishedDealReturn { client_refund, provider_fee })
+    }
+
+    /// Moves deals that have not been activated to a later start epoch, at the request of
+    /// their provider, so that they are not slashed when sealing takes longer than planned.
+    /// Each deal's end epoch moves by the same amount, so its duration and fees are unchanged.
+    /// A deal is extended immediately if its start epoch moves in total no further than the
+    /// policy's slack from that with which it was published. Otherwise the request awaits
+    /// approval by the deal's client.
+    /// Verified deals cannot be extended, as their allocations expire at the original start epoch.
+    fn request_activation_extension(
+        rt: &impl Runtime,
+        params: RequestActivationExtensionParams,
+    ) -> Result<RequestActivationExtensionReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let new_start_epoch = params.new_start_epoch;
+        let proposals = load_unstarted_proposals(rt, &params.deal_ids)?;
+
+        // All proposals are stored with normalised addresses.
+        let provider = proposals[0].1.provider;
+        if proposals.iter().any(|(_, proposal)| proposal.provider != provider) {
+            return Err(actor_error!(illegal_argument, "deals must all have the same provider"));
+        }
+        validate_caller_controls_provider(rt, provider.id().unwrap())?;
+
+        for (deal_id, proposal) in &proposals {
+            if proposal.verified_deal {
+                return Err(actor_error!(
+                    forbidden,
+                    "verified deal {} cannot have its start epoch extended",
+                    deal_id
+                ));
+            }
+            if new_start_epoch <= proposal.start_epoch {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "deal {} new start epoch {} must be after start epoch {}",
+                    deal_id,
+                    new_start_epoch,
+                    proposal.start_epoch
+                ));
+            }
+        }
+
+        let extended = rt.transaction(|st: &mut State, rt| {
+            let mut extended = BitField::new();
+            let mut extensions = Vec::with_capacity(proposals.len());
+            for (i, (deal_id, proposal)) in proposals.iter().enumerate() {
+                validate_deal_not_activated(rt, st, *deal_id)?;
+                let mut extension = st.get_activation_extension(rt.store(), *deal_id)?.unwrap_or(
+                    ActivationExtension {
+                        original_start_epoch: proposal.start_epoch,
+                        requested_start_epoch: None,
+                        superseded_proposals: vec![],
+                    },
+                );
+                if new_start_epoch - extension.original_start_epoch
+                    <= rt.policy().deal_activation_extension_slack
+                {
+                    extend_deal_start(rt, st, *deal_id, proposal, &mut extension, new_start_epoch)?;
+                    extension.requested_start_epoch = None;
+                    extended.set(i as u64);
+                } else {
+                    extension.requested_start_epoch = Some(new_start_epoch);
+                }
+                extensions.push((*deal_id, extension));
+            }
+            st.put_activation_extensions(rt.store(), &extensions)?;
+            Ok(extended)
+        })?;
+
+        for (i, (deal_id, proposal)) in proposals.iter().enumerate() {
+            let client = proposal.client.id().unwrap();
+            let provider = proposal.provider.id().unwrap();
+            if extended.get(i as u64) {
+                emit::deal_activation_extended(rt, *deal_id, client, provider, new_start_epoch)?;
+            } else {
+                emit::deal_activation_extension_requested(
+                    rt,
+                    *deal_id,
+                    client,
+                    provider,
+                    new_start_epoch,
+                )?;
+            }
+        }
+
+        Ok(RequestActivationExtensionReturn { extended })
+    }
+
+    /// Approves the later start epochs requested by providers for deals of the calling client.
+    fn approve_activation_extension(
+        rt: &impl Runtime,
+        params: ApproveActivationExtensionParams,
+    ) -> Result<(), ActorError> {
+        let proposals = load_unstarted_proposals(rt, &params.deal_ids)?;
+
+        // All proposals are stored with normalised addresses.
+        let client = proposals[0].1.client;
+        if proposals.iter().any(|(_, proposal)| proposal.client != client) {
+            return Err(actor_error!(illegal_argument, "deals must all have the same client"));
+        }
+        rt.validate_immediate_caller_is(std::iter::once(&client))?;
+
+        let start_epochs = rt.transaction(|st: &mut State, rt| {
+            let mut start_epochs = Vec::with_capacity(proposals.len());
+            let mut extensions = Vec::with_capacity(proposals.len());
+            for (deal_id, proposal) in &proposals {
+                validate_deal_not_activated(rt, st, *deal_id)?;
+                let mut extension = st
+                    .get_activation_extension(rt.store(), *deal_id)?
+                    .filter(|extension| extension.requested_start_epoch.is_some())
+                    .ok_or_else(|| {
+                        actor_error!(
+                            not_found,
+                            "no start epoch extension requested for deal {}",
+                            deal_id
+                        )
+                    })?;
+                let start_epoch = extension.requested_start_epoch.take().unwrap();
+                extend_deal_start(rt, st, *deal_id, proposal, &mut extension, start_epoch)?;
+                start_epochs.push(start_epoch);
+                extensions.push((*deal_id, extension));
+            }
+            st.put_activation_extensions(rt.store(), &extensions)?;
+            Ok(start_epochs)
+        })?;
+
+        for ((deal_id, proposal), start_epoch) in proposals.iter().zip(start_epochs) {
+            emit::deal_activation_extended(
+                rt,
+                *deal_id,
+                proposal.client.id().unwrap(),
+                proposal.provider.id().unwrap(),
+                start_epoch,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks that the caller is a provider, or its owner, worker or a control address.
+fn validate_caller_controls_provider(
+    rt: &impl Runtime,
+    provider_id: ActorID,
+) -> Result<(), ActorError> {
+    let caller = rt.message().caller();
+    if caller == Address::new_id(provider_id) {
+        return Ok(());
+    }
+    let caller_status: ext::miner::IsControllingAddressReturn =
+        deserialize_block(extract_send_result(rt.send_simple(
+            &Address::new_id(provider_id),
+            ext::miner::IS_CONTROLLING_ADDRESS_EXPORTED,
+            IpldBlock::serialize_cbor(&ext::miner::IsControllingAddressParam { address: caller })?,
+            TokenAmount::zero(),
+        ))?)?;
+    if !caller_status.is_controlling {
+        return Err(actor_error!(
+            forbidden,
+            "caller {} is not worker or control address of provider {}",
+            caller,
+            provider_id
+        ));
+    }
+    Ok(())
+}
+
+/// Loads the proposals of distinct deals that have not reached their start epochs.
+fn load_unstarted_proposals(
+    rt: &impl Runtime,
+    deal_ids: &[DealID],
+) -> Result<Vec<(DealID, DealProposal)>, ActorError> {
+    if deal_ids.is_empty() {
+        return Err(actor_error!(illegal_argument, "no deals specified"));
+    }
+    let st: State = rt.state()?;
+    let mut seen_deal_ids = BTreeSet::new();
+    let mut proposals = Vec::with_capacity(deal_ids.len());
+    for &deal_id in deal_ids {
+        if !seen_deal_ids.insert(deal_id) {
+            return Err(actor_error!(illegal_argument, "duplicate deal ID {}", deal_id));
+        }
+        let proposal = st.get_proposal(rt.store(), deal_id)?;
+        if rt.curr_epoch() >= proposal.start_epoch {
+            return Err(actor_error!(
+                forbidden,
+                "deal {} cannot be extended at or after its start epoch {}",
+                deal_id,
+                proposal.start_epoch
+            ));
+        }
+        proposals.push((deal_id, proposal));
+    }
+    Ok(proposals)
+}
+
+fn validate_deal_not_activated(
+    rt: &impl Runtime,
+    st: &State,
+    deal_id: DealID,
+) -> Result<(), ActorError> {
+    if st.find_deal_state(rt.store(), deal_id)?.is_some() {
+        return Err(actor_error!(
+            forbidden,
+            "deal {} has been activated and cannot be extended",
+            deal_id
+        ));
+    }
+    Ok(())
+}
+
+/// Moves the start epoch of a deal that has not been activated, keeping its duration.
+fn extend_deal_start(
+    rt: &impl Runtime,
+    st: &mut State,
+    deal_id: DealID,
+    proposal: &DealProposal,
+    extension: &mut ActivationExtension,
+    start_epoch: ChainEpoch,
+) -> Result<(), ActorError> {
+    let mut amended = proposal.clone();
+    amended.end_epoch += start_epoch - proposal.start_epoch;
+    amended.start_epoch = start_epoch;
+    extension.superseded_proposals.push(deal_cid(rt, proposal)?);
+    st.reschedule_deal_start(
+        rt.store(),
+        deal_id,
+        proposal,
+        &amended,
+        &deal_cid(rt, &amended)?,
+        rt.policy().deal_updates_interval,
+    )
 }
 
 fn get_proposals<BS: Blockstore>(
@@ -1470,6 +1851,94 @@ fn preactivate_deal<BS: Blockstore>(
     Ok(Ok(proposal))
 }
 
+/// Provider collateral slashed while processing deals, accumulated by provider and reason
+/// so that it is burnt in a single send.
+#[derive(Default)]
+struct Slashings(BTreeMap<(ActorID, PenaltyCategory), TokenAmount>);
+
+impl Slashings {
+    fn add(&mut self, provider: &Address, category: PenaltyCategory, amount: TokenAmount) {
+        if amount.is_positive() {
+            // All proposals are stored with normalised addresses.
+            *self.0.entry((provider.id().unwrap(), category)).or_default() += amount;
+        }
+    }
+
+    /// Burns the slashed collateral and reports the penalty to each provider.
+    fn burn(self, rt: &impl Runtime) -> Result<(), ActorError> {
+        let total: TokenAmount = self.0.values().sum();
+        if total.is_zero() {
+            return Ok(());
+        }
+        extract_send_result(rt.send_simple(&BURNT_FUNDS_ACTOR_ADDR, METHOD_SEND, None, total))?;
+        for ((provider, category), amount) in self.0 {
+            emit::collateral_slashed(rt, provider, category, &amount)?;
+        }
+        Ok(())
+    }
+}
+
+/// Terminates a deal in a provider's sector terminated at an epoch, settling it and removing it
+/// from state. Returns the provider collateral to be burnt.
+fn terminate_sector_deal(
+    rt: &impl Runtime,
+    st: &mut State,
+    provider: &Address,
+    id: DealID,
+    epoch: ChainEpoch,
+) -> Result<TokenAmount, ActorError> {
+    let deal = st.find_proposal(rt.store(), id)?;
+    // The deal may have expired and been deleted before the sector is terminated.
+    // Nothing to do, but continue execution for the other deals.
+    if deal.is_none() {
+        info!("couldn't find deal {}", id);
+        return Ok(TokenAmount::zero());
+    }
+    let deal = deal.unwrap();
+
+    if deal.provider != *provider {
+        return Err(actor_error!(
+            illegal_state,
+            "caller {} is not the provider {} of deal {}",
+            provider,
+            deal.provider,
+            id
+        ));
+    }
+
+    // do not slash expired deals
+    if deal.end_epoch <= epoch {
+        info!("deal {} expired, not slashing", id);
+        return Ok(TokenAmount::zero());
+    }
+
+    let mut state: DealState = st
+        .find_deal_state(rt.store(), id)?
+        // A deal with a proposal but no state is not activated, but then it should not be
+        // part of a sector that is terminating.
+        .ok_or_else(|| actor_error!(illegal_argument, "no state for deal {}", id))?;
+
+    // If a deal is already slashed, there should be no existing state for it
+    // but we process it here for deletion anyway
+    if state.slash_epoch != EPOCH_UNDEFINED {
+        warn!("deal {}, already slashed, terminating now anyway", id);
+    }
+    state.slash_epoch = epoch;
+
+    // Deals that were never processed may still have a pending proposal linked
+    if state.last_updated_epoch == EPOCH_UNDEFINED {
+        let dcid = deal_cid(rt, &deal)?;
+        st.remove_pending_deal(rt.store(), dcid)?;
+        st.remove_activation_extensions(rt.store(), &[id])?;
+    }
+
+    let slashed = st.process_slashed_deal(rt.store(), &deal, &state)?;
+    st.remove_completed_deal(rt.store(), id)?;
+
+    emit::deal_terminated(rt, id, deal.client.id().unwrap(), deal.provider.id().unwrap())?;
+    Ok(slashed)
+}
+
 fn alloc_request_for_deal(
     // Deal proposal must have ID addresses
     deal: &DealProposal,
@@ -1604,10 +2073,16 @@ fn validate_deal_can_activate(
 fn validate_deal(
     rt: &impl Runtime,
     deal: &ClientDealProposal,
+    auth_message: Vec<u8>,
+    signature_valid: Option<bool>,
     network_raw_power: &StoragePower,
     baseline_power: &StoragePower,
 ) -> Result<(), ActorError> {
-    deal_proposal_is_internally_valid(rt, deal)?;
+    match signature_valid {
+        Some(true) => {}
+        Some(false) => return Err(actor_error!(illegal_argument, "proposal signature invalid")),
+        None => deal_proposal_is_internally_valid(rt, deal, auth_message)?,
+    }
 
     let proposal = &deal.proposal;
 
@@ -1675,20 +2150,32 @@ fn validate_deal(
     Ok(())
 }
 
+/// Returns the message a client authenticates to authorize a deal proposal.
+fn proposal_auth_message(
+    rt: &impl Runtime,
+    proposal: &ClientDealProposal,
+) -> Result<Vec<u8>, ActorError> {
+    match proposal.auth_scheme {
+        ProposalAuthScheme::Cbor => Ok(serialize(&proposal.proposal, "deal proposal")?.to_vec()),
+        ProposalAuthScheme::TypedData => {
+            Ok(deal_proposal_typed_data_hash(rt, rt.chain_id(), &proposal.proposal)?.to_vec())
+        }
+    }
+}
+
 fn deal_proposal_is_internally_valid(
     rt: &impl Runtime,
     proposal: &ClientDealProposal,
+    auth_message: Vec<u8>,
 ) -> Result<(), ActorError> {
     let signature_bytes = proposal.client_signature.bytes.clone();
-    // Generate unsigned bytes
-    let proposal_bytes = serialize(&proposal.proposal, "deal proposal")?;
 
     if !extract_send_result(rt.send(
         &proposal.proposal.client,
         ext::account::AUTHENTICATE_MESSAGE_METHOD,
         IpldBlock::serialize_cbor(&ext::account::AuthenticateMessageParams {
             signature: signature_bytes,
-            message: proposal_bytes.to_vec(),
+            message: auth_message,
         })?,
         TokenAmount::zero(),
         None,
@@ -1750,7 +2237,7 @@ fn escrow_address(
 
     let nominal_addr = Address::new_id(nominal);
 
-    if rt.resolve_builtin_actor_type(&code_id) == Some(Type::Miner) {
+    if rt.resolve_builtin_actor_type_checked(&code_id).is_storage_provider() {
         // Storage miner actor entry; implied funds recipient is the associated owner address.
         let (owner_addr, worker_addr, _) = request_miner_control_addrs(rt, nominal)?;
         return Ok((nominal_addr, owner_addr, vec![owner_addr, worker_addr]));
@@ -1802,6 +2289,11 @@ impl ActorCode for Actor {
         "StorageMarket"
     }
 
+    fil_actors_runtime::actor_info! {
+        interface_revisions: [1],
+        features: [],
+    }
+
     actor_dispatch! {
         Constructor => constructor,
         AddBalance|AddBalanceExported => add_balance,
@@ -1823,7 +2315,12 @@ impl ActorCode for Actor {
         GetDealVerifiedExported => get_deal_verified,
         GetDealActivationExported => get_deal_activation,
         GetDealSectorExported => get_deal_sector,
+        GetClientDealStatsExported => get_client_deal_stats,
         SettleDealPaymentsExported => settle_deal_payments,
         SectorContentChangedExported => sector_content_changed,
+        CancelPublishedDealExported => cancel_published_deal,
+        RequestActivationExtensionExported => request_activation_extension,
+        ApproveActivationExtensionExported => approve_activation_extension,
+        ListDealsExported => list_deals,
     }
 }
@@ -13,6 +13,8 @@ use fvm_shared::sector::StoragePower;
 use lazy_static::lazy_static;
 use num_traits::Zero;
 
+use crate::DealProposal;
+
 pub mod detail {
     /// Maximum length of a deal label.
     pub const DEAL_MAX_LABEL_SIZE: usize = 256;
@@ -24,6 +26,11 @@ lazy_static! {
     pub static ref TOTAL_FILECOIN: TokenAmount = TokenAmount::from_whole(2_000_000_000);
 }
 
+/// Length of the rolling window over which client deal statistics are reported.
+pub const CLIENT_DEAL_STATS_WINDOW: ChainEpoch = 30 * EPOCHS_IN_DAY;
+/// Granularity of client deal statistics. The window must be a multiple of this.
+pub const CLIENT_DEAL_STATS_BUCKET_EPOCHS: ChainEpoch = EPOCHS_IN_DAY;
+
 /// Bounds (inclusive) on deal duration.
 pub(super) fn deal_duration_bounds(_size: PaddedPieceSize) -> (ChainEpoch, ChainEpoch) {
     (180 * EPOCHS_IN_DAY, 1278 * EPOCHS_IN_DAY)
@@ -63,6 +70,17 @@ pub(super) fn deal_client_collateral_bounds(
     (TokenAmount::zero(), TOTAL_FILECOIN.clone()) // PARAM_FINISH
 }
 
+/// Fee paid by the client to the provider when the client cancels a deal before it is activated.
+/// This is a fraction of the deal's storage fee, but no less than a fraction of the provider's
+/// collateral, so that cancelling deals without a storage fee is not free.
+pub(super) fn deal_cancellation_fee(policy: &Policy, proposal: &DealProposal) -> TokenAmount {
+    let storage_fee_share = (proposal.total_storage_fee() * policy.deal_cancellation_fee_num)
+        .div_floor(policy.deal_cancellation_fee_denom);
+    let minimum = (&proposal.provider_collateral * policy.deal_cancellation_min_fee_num)
+        .div_floor(policy.deal_cancellation_min_fee_denom);
+    max(storage_fee_share, minimum)
+}
+
 /// Penalty to provider deal collateral if the deadline expires before sector commitment.
 pub(super) fn collateral_penalty_for_deal_activation_missed(
     provider_collateral: TokenAmount,
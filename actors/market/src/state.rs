@@ -5,6 +5,7 @@ use std::cmp::{max, min};
 use std::collections::BTreeMap;
 
 use cid::Cid;
+use fvm_ipld_bitfield::BitField;
 use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::tuple::*;
 use fvm_shared::address::Address;
@@ -16,6 +17,7 @@ use fvm_shared::sector::SectorNumber;
 use fvm_shared::{ActorID, HAMT_BIT_WIDTH};
 use num_traits::Zero;
 
+use fil_actors_runtime::query::ListRequest;
 use fil_actors_runtime::{
     actor_error, ActorContext, ActorError, Array, AsActorError, Config, Map2, Set, SetMultimap,
     SetMultimapConfig, DEFAULT_HAMT_CONFIG,
@@ -26,7 +28,7 @@ use crate::ext::verifreg::AllocationID;
 
 use super::policy::*;
 use super::types::*;
-use super::{DealProposal, DealState, EX_DEAL_EXPIRED};
+use super::{next_update_epoch, DealProposal, DealState, EX_DEAL_EXPIRED};
 
 pub enum Reason {
     ClientCollateral,
@@ -86,6 +88,25 @@ pub struct State {
     /// of multiple sectors all belonging to the same provider.
     /// HAMT[ActorID]HAMT[SectorNumber][]DealID
     pub provider_sectors: Cid,
+
+    /// Deal publishing activity per client over a rolling window, in daily buckets.
+    /// HAMT[ActorID]ClientDealStats
+    pub client_deal_stats: Cid,
+
+    /// Terminated sectors whose deals' termination has been deferred, with the epochs at which
+    /// they were terminated. Their deals remain in the provider sectors mapping, unslashed and
+    /// excluded from settlement, until cron terminates them in batches.
+    /// HAMT[ActorID][]PendingSectorTerminations
+    pub pending_deal_terminations: Cid,
+
+    /// Deals not yet activated whose start epoch a provider has extended or requested to extend.
+    /// HAMT[DealID]ActivationExtension
+    pub activation_extensions: Cid,
+
+    /// Deals not yet activated that a provider has verified for a pre-committed sector, with the
+    /// epoch at which they were verified. Their clients can no longer cancel them.
+    /// HAMT[DealID]ChainEpoch
+    pub precommitted_deals: Cid,
 }
 
 pub type PendingProposalsSet<BS> = Set<BS, Cid>;
@@ -103,6 +124,22 @@ pub type ProviderSectorsMap<BS> = Map2<BS, ActorID, Cid>;
 pub const PROVIDER_SECTORS_CONFIG: Config =
     Config { bit_width: HAMT_BIT_WIDTH, ..DEFAULT_HAMT_CONFIG };
 
+pub type ClientDealStatsMap<BS> = Map2<BS, ActorID, ClientDealStats>;
+pub const CLIENT_DEAL_STATS_CONFIG: Config =
+    Config { bit_width: HAMT_BIT_WIDTH, ..DEFAULT_HAMT_CONFIG };
+
+pub type PendingDealTerminationsMap<BS> = Map2<BS, ActorID, Vec<PendingSectorTerminations>>;
+pub const PENDING_DEAL_TERMINATIONS_CONFIG: Config =
+    Config { bit_width: HAMT_BIT_WIDTH, ..DEFAULT_HAMT_CONFIG };
+
+pub type ActivationExtensionsMap<BS> = Map2<BS, DealID, ActivationExtension>;
+pub const ACTIVATION_EXTENSIONS_CONFIG: Config =
+    Config { bit_width: HAMT_BIT_WIDTH, ..DEFAULT_HAMT_CONFIG };
+
+pub type PrecommittedDealsMap<BS> = Map2<BS, DealID, ChainEpoch>;
+pub const PRECOMMITTED_DEALS_CONFIG: Config =
+    Config { bit_width: HAMT_BIT_WIDTH, ..DEFAULT_HAMT_CONFIG };
+
 pub type SectorDealsMap<BS> = Map2<BS, SectorNumber, Vec<DealID>>;
 pub const SECTOR_DEALS_CONFIG: Config = Config { bit_width: HAMT_BIT_WIDTH, ..DEFAULT_HAMT_CONFIG };
 
@@ -137,6 +174,28 @@ impl State {
         let empty_sector_deals_hamt =
             ProviderSectorsMap::empty(store, PROVIDER_SECTORS_CONFIG, "sector deals").flush()?;
 
+        let empty_client_deal_stats =
+            ClientDealStatsMap::empty(store, CLIENT_DEAL_STATS_CONFIG, "client deal stats")
+                .flush()?;
+
+        let empty_pending_deal_terminations = PendingDealTerminationsMap::empty(
+            store,
+            PENDING_DEAL_TERMINATIONS_CONFIG,
+            "pending deal terminations",
+        )
+        .flush()?;
+
+        let empty_activation_extensions = ActivationExtensionsMap::empty(
+            store,
+            ACTIVATION_EXTENSIONS_CONFIG,
+            "activation extensions",
+        )
+        .flush()?;
+
+        let empty_precommitted_deals =
+            PrecommittedDealsMap::empty(store, PRECOMMITTED_DEALS_CONFIG, "precommitted deals")
+                .flush()?;
+
         Ok(Self {
             proposals: empty_proposals_array,
             states: empty_states_array,
@@ -152,6 +211,10 @@ impl State {
             total_client_storage_fee: TokenAmount::default(),
             pending_deal_allocation_ids: empty_pending_deal_allocation_map,
             provider_sectors: empty_sector_deals_hamt,
+            client_deal_stats: empty_client_deal_stats,
+            pending_deal_terminations: empty_pending_deal_terminations,
+            activation_extensions: empty_activation_extensions,
+            precommitted_deals: empty_precommitted_deals,
         })
     }
 
@@ -161,6 +224,63 @@ impl State {
             + &self.total_client_storage_fee
     }
 
+    /// Records newly published deals in their clients' rolling statistics.
+    /// Each entry is a client, the deal's padded piece size and its total storage fee.
+    pub fn record_client_deals<BS>(
+        &mut self,
+        store: &BS,
+        curr_epoch: ChainEpoch,
+        deals: &[(ActorID, u64, TokenAmount)],
+    ) -> Result<(), ActorError>
+    where
+        BS: Blockstore,
+    {
+        let mut by_client: BTreeMap<ActorID, ClientDealStatsBucket> = BTreeMap::new();
+        let bucket = client_stats_bucket(curr_epoch);
+        for (client, size, fee) in deals {
+            let entry = by_client
+                .entry(*client)
+                .or_insert_with(|| ClientDealStatsBucket { bucket, ..Default::default() });
+            entry.deals_published += 1;
+            entry.data_onboarded += size;
+            entry.fees_committed += fee;
+        }
+
+        let mut stats_map = ClientDealStatsMap::load(
+            store,
+            &self.client_deal_stats,
+            CLIENT_DEAL_STATS_CONFIG,
+            "client deal stats",
+        )?;
+        for (client, added) in by_client {
+            let mut stats = stats_map.get(&client)?.cloned().unwrap_or_default();
+            stats.prune(curr_epoch);
+            stats.add(added);
+            stats_map.set(&client, stats)?;
+        }
+        self.client_deal_stats = stats_map.flush()?;
+        Ok(())
+    }
+
+    /// Returns a client's deal publishing totals over the window ending at the current epoch.
+    pub fn get_client_deal_stats<BS>(
+        &self,
+        store: &BS,
+        client: ActorID,
+        curr_epoch: ChainEpoch,
+    ) -> Result<ClientDealStatsBucket, ActorError>
+    where
+        BS: Blockstore,
+    {
+        let stats_map = ClientDealStatsMap::load(
+            store,
+            &self.client_deal_stats,
+            CLIENT_DEAL_STATS_CONFIG,
+            "client deal stats",
+        )?;
+        Ok(stats_map.get(&client)?.map(|s| s.window_total(curr_epoch)).unwrap_or_default())
+    }
+
     pub fn load_deal_states<'bs, BS>(
         &self,
         store: &'bs BS,
@@ -236,6 +356,27 @@ impl State {
             .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to load deal proposal array")
     }
 
+    /// Lists the proposals matching a request's filter among the next page of proposals,
+    /// in deal ID order.
+    /// Returns the matching deals, and the deal ID at which to resume if any proposals remain.
+    pub fn list_deals<BS: Blockstore>(
+        &self,
+        store: &BS,
+        request: &ListRequest<DealID>,
+    ) -> Result<(Vec<DealRecord>, Option<DealID>), ActorError> {
+        let proposals = self.load_proposals(store)?;
+        let mut deals = Vec::new();
+        let (_, next) = proposals
+            .for_each_ranged(request.cursor, Some(request.limit as usize), |id, proposal| {
+                if request.matches(proposal) {
+                    deals.push(DealRecord { id, proposal: proposal.clone() });
+                }
+                Ok(())
+            })
+            .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to iterate deal proposals")?;
+        Ok((deals, next))
+    }
+
     pub fn get_proposal<BS: Blockstore>(
         &self,
         store: &BS,
@@ -609,12 +750,30 @@ impl State {
         provider: ActorID,
         sector_numbers: impl Iterator<Item = SectorNumber>,
     ) -> Result<Vec<DealID>, ActorError> {
+        let (popped, _) =
+            self.pop_sector_deal_ids_limited(store, provider, sector_numbers, u64::MAX)?;
+        Ok(popped)
+    }
+
+    /// Removes sectors from the sector deals mapping in order, until at least max deals have been
+    /// removed. Returns the removed deals and the sectors not reached.
+    pub fn pop_sector_deal_ids_limited(
+        &mut self,
+        store: &impl Blockstore,
+        provider: ActorID,
+        mut sector_numbers: impl Iterator<Item = SectorNumber>,
+        max: u64,
+    ) -> Result<(Vec<DealID>, Vec<SectorNumber>), ActorError> {
         let mut provider_sectors = self.load_provider_sectors(store)?;
         let mut sector_deals = load_provider_sector_deals(store, &provider_sectors, provider)?;
 
         let mut popped_sector_deals = Vec::new();
         let mut flush = false;
-        for sector_number in sector_numbers {
+        while (popped_sector_deals.len() as u64) < max {
+            let sector_number = match sector_numbers.next() {
+                Some(sector_number) => sector_number,
+                None => break,
+            };
             let deals: Option<Vec<DealID>> = sector_deals
                 .delete(&sector_number)
                 .with_context(|| format!("provider {}", provider))?;
@@ -624,10 +783,8 @@ impl State {
             }
         }
 
-        // Flush if any of the requested sectors were found.
         if flush {
             if sector_deals.is_empty() {
-                // Remove from top-level map
                 provider_sectors
                     .delete(&provider)
                     .with_context_code(ExitCode::USR_ILLEGAL_STATE, || {
@@ -639,7 +796,7 @@ impl State {
             self.save_provider_sectors(&mut provider_sectors)?;
         }
 
-        Ok(popped_sector_deals)
+        Ok((popped_sector_deals, sector_numbers.collect()))
     }
 
     // Removes specified deals from the sector deals mapping.
@@ -704,6 +861,281 @@ impl State {
         Ok(())
     }
 
+    /// Records a provider's terminated sectors whose deals' termination is deferred.
+    pub fn queue_sector_terminations<BS>(
+        &mut self,
+        store: &BS,
+        provider: ActorID,
+        epoch: ChainEpoch,
+        sectors: BitField,
+    ) -> Result<(), ActorError>
+    where
+        BS: Blockstore,
+    {
+        if sectors.is_empty() {
+            return Ok(());
+        }
+        let mut pending = self.load_pending_deal_terminations(store)?;
+        let mut terminations = pending.get(&provider)?.cloned().unwrap_or_default();
+        terminations.push(PendingSectorTerminations { epoch, sectors });
+        pending.set(&provider, terminations)?;
+        self.pending_deal_terminations = pending.flush()?;
+        Ok(())
+    }
+
+    /// Returns the epoch at which a provider's sector was terminated, if the termination of its
+    /// deals is deferred.
+    pub fn find_pending_sector_termination<BS>(
+        &self,
+        store: &BS,
+        provider: ActorID,
+        sector_number: SectorNumber,
+    ) -> Result<Option<ChainEpoch>, ActorError>
+    where
+        BS: Blockstore,
+    {
+        let pending = self.load_pending_deal_terminations(store)?;
+        Ok(pending.get(&provider)?.and_then(|terminations| {
+            terminations.iter().find(|t| t.sectors.get(sector_number)).map(|t| t.epoch)
+        }))
+    }
+
+    /// Removes deferred sector terminations in queue order, popping their sectors' deals until at
+    /// least max deals are popped or none remain. Returns the popped deals of each provider, with
+    /// the epoch at which their sectors were terminated.
+    pub fn pop_pending_deal_terminations<BS>(
+        &mut self,
+        store: &BS,
+        max: u64,
+    ) -> Result<Vec<(ActorID, ChainEpoch, Vec<DealID>)>, ActorError>
+    where
+        BS: Blockstore,
+    {
+        let mut pending = self.load_pending_deal_terminations(store)?;
+        let mut popped = Vec::new();
+        let mut popped_count = 0;
+        while popped_count < max {
+            let mut next = None;
+            pending.for_each_ranged(None, Some(1), |provider, terminations| {
+                next = Some((provider, terminations.clone()));
+                Ok(())
+            })?;
+            let (provider, mut terminations) = match next {
+                Some(next) => next,
+                None => break,
+            };
+
+            while popped_count < max && !terminations.is_empty() {
+                let termination = &mut terminations[0];
+                let (deals, remaining) = self.pop_sector_deal_ids_limited(
+                    store,
+                    provider,
+                    termination.sectors.iter(),
+                    max - popped_count,
+                )?;
+                popped_count += deals.len() as u64;
+                popped.push((provider, termination.epoch, deals));
+                if remaining.is_empty() {
+                    terminations.remove(0);
+                } else {
+                    termination.sectors = BitField::try_from_bits(remaining)
+                        .context_code(ExitCode::USR_ILLEGAL_STATE, "invalid sector numbers")?;
+                }
+            }
+            if terminations.is_empty() {
+                pending.delete(&provider)?;
+            } else {
+                pending.set(&provider, terminations)?;
+            }
+        }
+        if !popped.is_empty() {
+            self.pending_deal_terminations = pending.flush()?;
+        }
+        Ok(popped)
+    }
+
+    fn load_pending_deal_terminations<BS>(
+        &self,
+        store: BS,
+    ) -> Result<PendingDealTerminationsMap<BS>, ActorError>
+    where
+        BS: Blockstore,
+    {
+        PendingDealTerminationsMap::load(
+            store,
+            &self.pending_deal_terminations,
+            PENDING_DEAL_TERMINATIONS_CONFIG,
+            "pending deal terminations",
+        )
+    }
+
+    /// Returns the start epoch extension record of a deal, if any.
+    pub fn get_activation_extension<BS>(
+        &self,
+        store: &BS,
+        deal_id: DealID,
+    ) -> Result<Option<ActivationExtension>, ActorError>
+    where
+        BS: Blockstore,
+    {
+        let extensions = self.load_activation_extensions(store)?;
+        Ok(extensions.get(&deal_id)?.cloned())
+    }
+
+    pub fn put_activation_extensions<BS>(
+        &mut self,
+        store: &BS,
+        new_extensions: &[(DealID, ActivationExtension)],
+    ) -> Result<(), ActorError>
+    where
+        BS: Blockstore,
+    {
+        let mut extensions = self.load_activation_extensions(store)?;
+        for (deal_id, extension) in new_extensions {
+            extensions.set(deal_id, extension.clone())?;
+        }
+        self.activation_extensions = extensions.flush()?;
+        Ok(())
+    }
+
+    /// Removes the start epoch extension records of deals, if present, along with the
+    /// superseded proposals they keep pending.
+    pub fn remove_activation_extensions<BS>(
+        &mut self,
+        store: &BS,
+        deal_ids: &[DealID],
+    ) -> Result<(), ActorError>
+    where
+        BS: Blockstore,
+    {
+        if deal_ids.is_empty() {
+            return Ok(());
+        }
+        let mut extensions = self.load_activation_extensions(store)?;
+        let mut superseded = Vec::new();
+        for deal_id in deal_ids {
+            if let Some(extension) = extensions.delete(deal_id)? {
+                superseded.extend(extension.superseded_proposals);
+            }
+        }
+        self.activation_extensions = extensions.flush()?;
+        for dcid in superseded {
+            self.remove_pending_deal(store, dcid)?;
+        }
+        Ok(())
+    }
+
+    fn load_activation_extensions<BS>(
+        &self,
+        store: BS,
+    ) -> Result<ActivationExtensionsMap<BS>, ActorError>
+    where
+        BS: Blockstore,
+    {
+        ActivationExtensionsMap::load(
+            store,
+            &self.activation_extensions,
+            ACTIVATION_EXTENSIONS_CONFIG,
+            "activation extensions",
+        )
+    }
+
+    /// Records deals as verified for a pre-committed sector at an epoch.
+    pub fn put_precommitted_deals<BS>(
+        &mut self,
+        store: &BS,
+        deal_ids: &[DealID],
+        epoch: ChainEpoch,
+    ) -> Result<(), ActorError>
+    where
+        BS: Blockstore,
+    {
+        if deal_ids.is_empty() {
+            return Ok(());
+        }
+        let mut precommitted = self.load_precommitted_deals(store)?;
+        for deal_id in deal_ids {
+            precommitted.set(deal_id, epoch)?;
+        }
+        self.precommitted_deals = precommitted.flush()?;
+        Ok(())
+    }
+
+    /// Returns whether a deal has been verified for a pre-committed sector.
+    pub fn is_precommitted_deal<BS>(&self, store: &BS, deal_id: DealID) -> Result<bool, ActorError>
+    where
+        BS: Blockstore,
+    {
+        Ok(self.load_precommitted_deals(store)?.contains_key(&deal_id)?)
+    }
+
+    /// Removes the pre-commit records of deals, if present.
+    pub fn remove_precommitted_deals<BS>(
+        &mut self,
+        store: &BS,
+        deal_ids: &[DealID],
+    ) -> Result<(), ActorError>
+    where
+        BS: Blockstore,
+    {
+        if deal_ids.is_empty() {
+            return Ok(());
+        }
+        let mut precommitted = self.load_precommitted_deals(store)?;
+        for deal_id in deal_ids {
+            precommitted.delete(deal_id)?;
+        }
+        self.precommitted_deals = precommitted.flush()?;
+        Ok(())
+    }
+
+    fn load_precommitted_deals<BS>(&self, store: BS) -> Result<PrecommittedDealsMap<BS>, ActorError>
+    where
+        BS: Blockstore,
+    {
+        PrecommittedDealsMap::load(
+            store,
+            &self.precommitted_deals,
+            PRECOMMITTED_DEALS_CONFIG,
+            "precommitted deals",
+        )
+    }
+
+    /// Replaces the proposal of a deal that is not yet activated with one starting later,
+    /// adding its CID to the pending proposals and rescheduling its cron processing to match.
+    /// The replaced proposal remains pending, so it can't be published again as a new deal.
+    pub fn reschedule_deal_start<BS>(
+        &mut self,
+        store: &BS,
+        deal_id: DealID,
+        proposal: &DealProposal,
+        amended: &DealProposal,
+        amended_cid: &Cid,
+        update_interval: i64,
+    ) -> Result<(), ActorError>
+    where
+        BS: Blockstore,
+    {
+        if self.has_pending_deal(store, amended_cid)? {
+            return Err(actor_error!(
+                illegal_argument,
+                "deal {} amended proposal {} is already pending",
+                deal_id,
+                amended_cid
+            ));
+        }
+        self.put_pending_deals(store, &[*amended_cid])?;
+        self.put_deal_proposals(store, &[(deal_id, amended.clone())])?;
+
+        let mut deals_by_epoch = self.load_deal_ops(store)?;
+        deals_by_epoch
+            .remove(&next_update_epoch(deal_id, update_interval, proposal.start_epoch), deal_id)?;
+        deals_by_epoch
+            .put(&next_update_epoch(deal_id, update_interval, amended.start_epoch), deal_id)?;
+        self.deal_ops_by_epoch = deals_by_epoch.flush()?;
+        Ok(())
+    }
+
     /// Delete proposal and state simultaneously.
     pub fn remove_completed_deal<BS>(
         &mut self,
@@ -778,6 +1210,8 @@ impl State {
 
                 // delete pending deal allocation id (if present)
                 self.remove_pending_deal_allocation_id(store, deal_id)?;
+                self.remove_activation_extensions(store, &[deal_id])?;
+                self.remove_precommitted_deals(store, &[deal_id])?;
 
                 Ok(LoadDealState::ProposalExpired(slashed))
             }
@@ -985,6 +1419,64 @@ impl State {
         Ok(amount_slashed)
     }
 
+    /// Client cancelled a deal before it was activated.
+    /// Unlock the collaterals and storage fee for both provider and client, pay the provider
+    /// a fee from the client's available balance, and delete the deal.
+    /// Returns the amount unlocked for the client, net of the fee.
+    pub fn process_deal_cancelled<BS>(
+        &mut self,
+        store: &BS,
+        deal_id: DealID,
+        deal: &DealProposal,
+        dcid: &Cid,
+        provider_fee: &TokenAmount,
+    ) -> Result<TokenAmount, ActorError>
+    where
+        BS: Blockstore,
+    {
+        self.unlock_balance(
+            store,
+            &deal.client,
+            &deal.total_storage_fee(),
+            Reason::ClientStorageFee,
+        )
+        .context("unlocking client storage fee")?;
+
+        self.unlock_balance(store, &deal.client, &deal.client_collateral, Reason::ClientCollateral)
+            .context("unlocking client collateral")?;
+
+        self.transfer_available_balance(store, &deal.client, &deal.provider, provider_fee)
+            .context("paying provider cancellation fee")?;
+
+        self.unlock_balance(
+            store,
+            &deal.provider,
+            deal.provider_balance_requirement(),
+            Reason::ProviderCollateral,
+        )
+        .context("unlocking deal provider balance")?;
+
+        self.remove_proposal(store, deal_id)?.ok_or_else(|| {
+            actor_error!(
+                illegal_state,
+                "failed to delete deal {} proposal: does not exist",
+                deal_id
+            )
+        })?;
+        self.remove_pending_deal(store, *dcid)?.ok_or_else(|| {
+            actor_error!(
+                illegal_state,
+                "failed to delete pending deal {}: cid {} does not exist",
+                deal_id,
+                dcid
+            )
+        })?;
+        self.remove_pending_deal_allocation_id(store, deal_id)?;
+        self.remove_activation_extensions(store, &[deal_id])?;
+
+        Ok(max(deal.client_balance_requirement() - provider_fee, TokenAmount::zero()))
+    }
+
     /// Normal expiration. Unlock collaterals for both miner and client.
     fn process_deal_expired<BS>(
         &mut self,
@@ -1147,6 +1639,37 @@ impl State {
         Ok(())
     }
 
+    /// Moves funds from the available (unlocked) escrow of one address to that of another.
+    fn transfer_available_balance<BS>(
+        &mut self,
+        store: &BS,
+        from_addr: &Address,
+        to_addr: &Address,
+        amount: &TokenAmount,
+    ) -> Result<(), ActorError>
+    where
+        BS: Blockstore,
+    {
+        if amount.is_negative() {
+            return Err(actor_error!(illegal_state, "transfer negative amount: {}", amount));
+        }
+
+        let mut escrow_table = BalanceTable::from_root(store, &self.escrow_table, "escrow table")?;
+        let locked_table = BalanceTable::from_root(store, &self.locked_table, "locked table")?;
+
+        let available = escrow_table.get(from_addr)? - locked_table.get(from_addr)?;
+        if &available < amount {
+            return Err(actor_error!(insufficient_funds;
+                "not enough available balance for addr {}: available {} < amount {}",
+                from_addr, available, amount));
+        }
+
+        escrow_table.must_subtract(from_addr, amount)?;
+        escrow_table.add(to_addr, amount)?;
+        self.escrow_table = escrow_table.root()?;
+        Ok(())
+    }
+
     fn slash_balance<BS>(
         &mut self,
         store: &BS,
@@ -1221,6 +1744,85 @@ pub fn get_proposal<BS: Blockstore>(
     Ok(found)
 }
 
+/// A provider's sectors terminated at the same epoch, whose deals' termination is deferred.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct PendingSectorTerminations {
+    pub epoch: ChainEpoch,
+    pub sectors: BitField,
+}
+
+/// The start epoch extensions of a deal that is not yet activated.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ActivationExtension {
+    /// The start epoch with which the deal was published.
+    pub original_start_epoch: ChainEpoch,
+    /// A later start epoch requested by the provider, awaiting the client's approval.
+    pub requested_start_epoch: Option<ChainEpoch>,
+    /// The CIDs of the proposals the deal was published or extended with before its current one.
+    /// They remain pending proposals, so can't be published again, for as long as the deal's
+    /// current proposal does.
+    pub superseded_proposals: Vec<Cid>,
+}
+
+/// Deal publishing activity for a single client, bucketed by day.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ClientDealStats {
+    /// Buckets in ascending order, none older than the stats window.
+    pub buckets: Vec<ClientDealStatsBucket>,
+}
+
+/// Deal publishing activity within one bucket of epochs.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ClientDealStatsBucket {
+    /// Index of the bucket, i.e. the epoch divided by the bucket length.
+    pub bucket: i64,
+    pub deals_published: u64,
+    /// Total padded piece size of published deals, in bytes.
+    pub data_onboarded: u64,
+    /// Total storage fees of published deals.
+    pub fees_committed: TokenAmount,
+}
+
+impl ClientDealStats {
+    /// Drops buckets that have fallen out of the window ending at the current epoch.
+    fn prune(&mut self, curr_epoch: ChainEpoch) {
+        let oldest = oldest_window_bucket(curr_epoch);
+        self.buckets.retain(|b| b.bucket >= oldest);
+    }
+
+    fn add(&mut self, added: ClientDealStatsBucket) {
+        match self.buckets.last_mut() {
+            Some(last) if last.bucket == added.bucket => {
+                last.deals_published += added.deals_published;
+                last.data_onboarded += added.data_onboarded;
+                last.fees_committed += added.fees_committed;
+            }
+            _ => self.buckets.push(added),
+        }
+    }
+
+    /// Sums the buckets within the window ending at the current epoch.
+    fn window_total(&self, curr_epoch: ChainEpoch) -> ClientDealStatsBucket {
+        let oldest = oldest_window_bucket(curr_epoch);
+        let mut total =
+            ClientDealStatsBucket { bucket: client_stats_bucket(curr_epoch), ..Default::default() };
+        for b in self.buckets.iter().filter(|b| b.bucket >= oldest) {
+            total.deals_published += b.deals_published;
+            total.data_onboarded += b.data_onboarded;
+            total.fees_committed += &b.fees_committed;
+        }
+        total
+    }
+}
+
+fn client_stats_bucket(epoch: ChainEpoch) -> i64 {
+    epoch.div_euclid(CLIENT_DEAL_STATS_BUCKET_EPOCHS)
+}
+
+fn oldest_window_bucket(curr_epoch: ChainEpoch) -> i64 {
+    client_stats_bucket(curr_epoch) - CLIENT_DEAL_STATS_WINDOW / CLIENT_DEAL_STATS_BUCKET_EPOCHS + 1
+}
+
 pub fn find_proposal<BS>(
     proposals: &DealArray<BS>,
     deal_id: DealID,
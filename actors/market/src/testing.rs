@@ -28,9 +28,11 @@ use fil_actors_runtime::{
 
 use crate::ext::verifreg::AllocationID;
 use crate::{
-    balance_table::BalanceTable, DealArray, DealMetaArray, DealOpsByEpoch, DealProposal,
-    PendingProposalsSet, ProviderSectorsMap, SectorDealsMap, State, DEAL_OPS_BY_EPOCH_CONFIG,
-    PENDING_PROPOSALS_CONFIG, PROVIDER_SECTORS_CONFIG, SECTOR_DEALS_CONFIG,
+    balance_table::BalanceTable, ActivationExtensionsMap, DealArray, DealMetaArray, DealOpsByEpoch,
+    DealProposal, PendingDealTerminationsMap, PendingProposalsSet, PrecommittedDealsMap,
+    ProviderSectorsMap, SectorDealsMap, State, ACTIVATION_EXTENSIONS_CONFIG,
+    DEAL_OPS_BY_EPOCH_CONFIG, PENDING_DEAL_TERMINATIONS_CONFIG, PENDING_PROPOSALS_CONFIG,
+    PRECOMMITTED_DEALS_CONFIG, PROVIDER_SECTORS_CONFIG, SECTOR_DEALS_CONFIG,
 };
 
 #[derive(Clone)]
@@ -67,6 +69,9 @@ pub struct StateSummary {
     pub alloc_id_to_deal_id: BTreeMap<u64, DealID>,
     pub pending_proposal_count: u64,
     pub deal_state_count: u64,
+    pub pending_termination_sector_count: u64,
+    pub activation_extension_count: u64,
+    pub precommitted_deal_count: u64,
     pub lock_table_count: u64,
     pub deal_op_epoch_count: u64,
     pub deal_op_count: u64,
@@ -225,6 +230,119 @@ pub fn check_state_invariants<BS: Blockstore>(
         Err(e) => acc.add(format!("error loading deal states: {e}")),
     };
 
+    // Deferred sector terminations
+    // Each provider has sectors awaiting termination of their deals, terminated no later than now.
+    let mut pending_termination_sector_count = 0;
+    match PendingDealTerminationsMap::load(
+        store,
+        &state.pending_deal_terminations,
+        PENDING_DEAL_TERMINATIONS_CONFIG,
+        "pending deal terminations",
+    ) {
+        Ok(pending_terminations) => {
+            let ret = pending_terminations.for_each(|provider, terminations| {
+                acc.require(
+                    !terminations.is_empty(),
+                    format!("provider {provider} has an empty pending terminations entry"),
+                );
+                for termination in terminations {
+                    let epoch = termination.epoch;
+                    acc.require(
+                        epoch <= current_epoch,
+                        format!("provider {provider} pending termination at {epoch} after current epoch {current_epoch}"),
+                    );
+                    acc.require(
+                        !termination.sectors.is_empty(),
+                        format!("provider {provider} pending termination at {epoch} has no sectors"),
+                    );
+                    pending_termination_sector_count += termination.sectors.len();
+                }
+                Ok(())
+            });
+            acc.require_no_error(ret, "error iterating pending deal terminations");
+        }
+        Err(e) => acc.add(format!("error loading pending deal terminations: {e}")),
+    };
+
+    // Deal start epoch extensions
+    // Each is for a deal starting no earlier than it was published to, whose superseded proposals
+    // remain pending.
+    let mut activation_extension_count = 0;
+    let mut superseded_proposal_cids = BTreeSet::<Cid>::new();
+    match ActivationExtensionsMap::load(
+        store,
+        &state.activation_extensions,
+        ACTIVATION_EXTENSIONS_CONFIG,
+        "activation extensions",
+    ) {
+        Ok(extensions) => {
+            let ret = extensions.for_each(|deal_id, extension| {
+                if let Some(stats) = proposal_stats.get(&deal_id) {
+                    acc.require(
+                        extension.original_start_epoch <= stats.start_epoch,
+                        format!(
+                            "deal {deal_id} original start epoch {} after start epoch {}",
+                            extension.original_start_epoch, stats.start_epoch
+                        ),
+                    );
+                    if let Some(requested) = extension.requested_start_epoch {
+                        acc.require(
+                            requested > stats.start_epoch,
+                            format!(
+                                "deal {deal_id} requested start epoch {requested} not after start epoch {}",
+                                stats.start_epoch
+                            ),
+                        );
+                    }
+                } else {
+                    acc.add(format!("deal {deal_id} activation extension not found in proposals"));
+                }
+                for superseded in &extension.superseded_proposals {
+                    acc.require(
+                        !proposal_cids.contains(superseded),
+                        format!("deal {deal_id} superseded proposal {superseded} is a deal proposal"),
+                    );
+                    superseded_proposal_cids.insert(*superseded);
+                }
+                activation_extension_count += 1;
+                Ok(())
+            });
+            acc.require_no_error(ret, "error iterating activation extensions");
+        }
+        Err(e) => acc.add(format!("error loading activation extensions: {e}")),
+    };
+
+    // Pre-committed deals
+    // Each is for a deal not yet activated, verified no later than the current epoch.
+    let mut precommitted_deal_count = 0;
+    match PrecommittedDealsMap::load(
+        store,
+        &state.precommitted_deals,
+        PRECOMMITTED_DEALS_CONFIG,
+        "precommitted deals",
+    ) {
+        Ok(precommitted) => {
+            let ret = precommitted.for_each(|deal_id, epoch| {
+                if let Some(stats) = proposal_stats.get(&deal_id) {
+                    acc.require(
+                        stats.sector_start_epoch == EPOCH_UNDEFINED,
+                        format!("pre-committed deal {deal_id} has been activated"),
+                    );
+                } else {
+                    acc.add(format!("pre-committed deal {deal_id} not found in proposals"));
+                }
+                acc.require(
+                    *epoch <= current_epoch,
+                    format!("deal {deal_id} pre-committed in the future at {epoch}"),
+                );
+                precommitted_deal_count += 1;
+                Ok(())
+            });
+            acc.require_no_error(ret, "error iterating pre-committed deals");
+        }
+        Err(e) => acc.add(format!("error loading pre-committed deals: {e}")),
+    };
+
     // Provider->sector->deal mapping
     // Each entry corresponds to non-terminated deal state.
     // A deal may have expired but remain in the mapping until settlement.
@@ -305,7 +423,8 @@ pub fn check_state_invariants<BS: Blockstore>(
                 let proposal_cid = Cid::try_from(key.to_owned())
                     .context_code(ExitCode::USR_ILLEGAL_STATE, "not a CID")?;
                 acc.require(
-                    proposal_cids.contains(&proposal_cid),
+                    proposal_cids.contains(&proposal_cid)
+                        || superseded_proposal_cids.remove(&proposal_cid),
                     format!("pending proposal with cid {proposal_cid} not found within proposals"),
                 );
 
@@ -313,6 +432,10 @@ pub fn check_state_invariants<BS: Blockstore>(
                 Ok(())
             });
             acc.require_no_error(ret, "error iterating pending proposals");
+            acc.require(
+                superseded_proposal_cids.is_empty(),
+                format!("superseded proposals {superseded_proposal_cids:?} not pending"),
+            );
         }
         Err(e) => acc.add(format!("error loading pending proposals: {e}")),
     };
@@ -398,6 +521,9 @@ pub fn check_state_invariants<BS: Blockstore>(
             provider_sector_deals,
             pending_proposal_count,
             deal_state_count,
+            pending_termination_sector_count,
+            activation_extension_count,
+            precommitted_deal_count,
             lock_table_count,
             deal_op_epoch_count,
             deal_op_count,
@@ -0,0 +1,110 @@
+// Copyright 2019-2022 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use fil_actors_runtime::runtime::Primitives;
+use fil_actors_runtime::{actor_error, ActorError, STORAGE_MARKET_ACTOR_ID};
+use fvm_shared::bigint::Sign;
+use fvm_shared::chainid::ChainID;
+use fvm_shared::crypto::hash::SupportedHashes;
+use fvm_shared::econ::TokenAmount;
+
+use crate::{DealProposal, Label};
+
+/// Name of the storage market's EIP-712 signing domain.
+pub const TYPED_DATA_DOMAIN_NAME: &str = "Filecoin Storage Market";
+/// Version of the storage market's EIP-712 signing domain.
+pub const TYPED_DATA_DOMAIN_VERSION: &str = "1";
+
+const DOMAIN_TYPE: &str =
+    "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+const DEAL_PROPOSAL_TYPE: &str = "DealProposal(bytes pieceCid,uint64 pieceSize,bool verifiedDeal,\
+    bytes client,bytes provider,bytes label,bool labelIsString,int64 startEpoch,int64 endEpoch,\
+    uint256 storagePricePerEpoch,uint256 providerCollateral,uint256 clientCollateral)";
+
+type Word = [u8; 32];
+
+/// Computes the EIP-712 typed-data hash of a deal proposal.
+///
+/// A client authorizing a proposal with the typed-data scheme authenticates this hash in place of
+/// the proposal's CBOR encoding, so that an EVM contract client can check signatures made with
+/// an Ethereum wallet's typed-data signing. The signing domain is bound to the chain ID and to the
+/// storage market's (masked ID) Ethereum address.
+pub fn deal_proposal_typed_data_hash(
+    primitives: &dyn Primitives,
+    chain_id: ChainID,
+    proposal: &DealProposal,
+) -> Result<Word, ActorError> {
+    let keccak = |data: &[u8]| -> Word {
+        let mut word = [0u8; 32];
+        word.copy_from_slice(&primitives.hash(SupportedHashes::Keccak256, data));
+        word
+    };
+
+    let mut market_address = [0u8; 32];
+    market_address[12] = 0xff;
+    market_address[24..].copy_from_slice(&STORAGE_MARKET_ACTOR_ID.to_be_bytes());
+    let domain_separator = keccak(
+        &[
+            keccak(DOMAIN_TYPE.as_bytes()),
+            keccak(TYPED_DATA_DOMAIN_NAME.as_bytes()),
+            keccak(TYPED_DATA_DOMAIN_VERSION.as_bytes()),
+            encode_u64(u64::from(chain_id)),
+            market_address,
+        ]
+        .concat(),
+    );
+
+    let (label, label_is_string) = match &proposal.label {
+        Label::String(s) => (s.as_bytes(), true),
+        Label::Bytes(b) => (b.as_slice(), false),
+    };
+    let struct_hash = keccak(
+        &[
+            keccak(DEAL_PROPOSAL_TYPE.as_bytes()),
+            keccak(&proposal.piece_cid.to_bytes()),
+            encode_u64(proposal.piece_size.0),
+            encode_bool(proposal.verified_deal),
+            keccak(&proposal.client.to_bytes()),
+            keccak(&proposal.provider.to_bytes()),
+            keccak(label),
+            encode_bool(label_is_string),
+            encode_i64(proposal.start_epoch),
+            encode_i64(proposal.end_epoch),
+            encode_token_amount(&proposal.storage_price_per_epoch)?,
+            encode_token_amount(&proposal.provider_collateral)?,
+            encode_token_amount(&proposal.client_collateral)?,
+        ]
+        .concat(),
+    );
+
+    let mut typed_data = vec![0x19, 0x01];
+    typed_data.extend_from_slice(&domain_separator);
+    typed_data.extend_from_slice(&struct_hash);
+    Ok(keccak(&typed_data))
+}
+
+fn encode_u64(value: u64) -> Word {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+fn encode_i64(value: i64) -> Word {
+    let mut word = if value < 0 { [0xff; 32] } else { [0; 32] };
+    word[24..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+fn encode_bool(value: bool) -> Word {
+    encode_u64(value as u64)
+}
+
+fn encode_token_amount(amount: &TokenAmount) -> Result<Word, ActorError> {
+    let (sign, bytes) = amount.atto().to_bytes_be();
+    if sign == Sign::Minus || bytes.len() > 32 {
+        return Err(actor_error!(illegal_argument, "token amount {} is not a uint256", amount));
+    }
+    let mut word = [0u8; 32];
+    word[32 - bytes.len()..].copy_from_slice(&bytes);
+    Ok(word)
+}
@@ -3,6 +3,7 @@
 
 use super::ext::verifreg::AllocationID;
 use cid::Cid;
+use fil_actors_runtime::query::{ListPage, ListRequest};
 use fil_actors_runtime::Array;
 use fil_actors_runtime::BatchReturn;
 use fvm_ipld_bitfield::BitField;
@@ -248,6 +249,28 @@ pub struct GetDealSectorReturn {
     pub sector: SectorNumber,
 }
 
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct GetClientDealStatsParams {
+    pub client: Address,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct GetClientDealStatsReturn {
+    /// Number of deals the client published within the window.
+    pub deals_published: u64,
+    /// Total padded piece size of those deals, in bytes.
+    pub data_onboarded: u64,
+    /// Total storage fees of those deals.
+    pub fees_committed: TokenAmount,
+    /// Length of the window ending at the current epoch, in epochs.
+    pub window_epochs: ChainEpoch,
+    /// Average bytes onboarded per epoch over the window.
+    pub data_onboarded_per_epoch: u64,
+    /// Average storage fees committed per epoch over the window.
+    pub fees_committed_per_epoch: TokenAmount,
+}
+
 // Interface market clients can implement to receive notifications from builtin market
 pub const MARKET_NOTIFY_DEAL_METHOD: u64 = frc42_dispatch::method_hash!("MarketNotifyDeal");
 
@@ -272,6 +295,55 @@ pub struct SettleDealPaymentsReturn {
     pub settlements: Vec<DealSettlementSummary>,
 }
 
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct CancelPublishedDealParams {
+    pub deal_id: DealID,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct CancelPublishedDealReturn {
+    /// Funds unlocked in the client's escrow balance
+    pub client_refund: TokenAmount,
+    /// Funds transferred from the client to the provider as compensation
+    pub provider_fee: TokenAmount,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct ListDealsParams {
+    pub request: ListRequest<DealID>,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct DealRecord {
+    pub id: DealID,
+    pub proposal: DealProposal,
+}
+
+pub type ListDealsReturn = ListPage<DealRecord, DealID>;
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct RequestActivationExtensionParams {
+    /// Deals from a single provider, none of them activated
+    pub deal_ids: Vec<DealID>,
+    /// The later start epoch requested for every deal
+    pub new_start_epoch: ChainEpoch,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, PartialEq)] // Add Eq when BitField does
+pub struct RequestActivationExtensionReturn {
+    /// Indices of the deals extended immediately. The rest await their client's approval.
+    pub extended: BitField,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct ApproveActivationExtensionParams {
+    /// Deals of the calling client with extensions requested by their providers
+    pub deal_ids: Vec<DealID>,
+}
+
 #[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
 pub struct DealSettlementSummary {
     /// Incremental amount of funds transferred from client to provider for deal payment
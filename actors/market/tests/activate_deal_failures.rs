@@ -7,7 +7,6 @@ use fvm_shared::deal::DealID;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
 use fvm_shared::sector::{RegisteredSealProof, SectorNumber};
-use fvm_shared::METHOD_SEND;
 use num_traits::Zero;
 
 use fil_actor_market::ext::miner::{PieceReturn, SectorChanges};
@@ -15,7 +14,7 @@ use fil_actor_market::BatchActivateDealsResult;
 use fil_actor_market::{DealProposal, SectorDeals, EX_DEAL_EXPIRED, NO_ALLOCATION_ID};
 use fil_actors_runtime::network::EPOCHS_IN_DAY;
 use fil_actors_runtime::test_utils::*;
-use fil_actors_runtime::BURNT_FUNDS_ACTOR_ADDR;
+use fil_actors_runtime::PenaltyCategory;
 use harness::*;
 
 mod harness;
@@ -98,13 +97,11 @@ fn reject_proposal_expired() {
     assert_activation_failure(&rt, deal_id, &deal, 1, sector_expiry, EX_DEAL_EXPIRED);
 
     // Show the same behaviour after the deal is cleaned up from state.
-    rt.expect_send_simple(
-        BURNT_FUNDS_ACTOR_ADDR,
-        METHOD_SEND,
-        None,
-        deal.provider_collateral.clone(),
-        None,
-        ExitCode::OK,
+    expect_slash(
+        &rt,
+        &deal.provider,
+        PenaltyCategory::DealActivationTimeout,
+        &deal.provider_collateral,
     );
     cron_tick(&rt);
     assert_deal_deleted(&rt, deal_id, &deal, 0);
@@ -0,0 +1,248 @@
+use fil_actor_market::{
+    ApproveActivationExtensionParams, Method, RequestActivationExtensionParams,
+};
+use fil_actors_runtime::network::EPOCHS_IN_DAY;
+use fil_actors_runtime::test_utils::*;
+use fil_actors_runtime::PenaltyCategory;
+use fvm_ipld_bitfield::BitField;
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::error::ExitCode;
+
+mod harness;
+
+use harness::*;
+
+const START_EPOCH: ChainEpoch = 50;
+const END_EPOCH: ChainEpoch = START_EPOCH + 200 * EPOCHS_IN_DAY;
+const SECTOR_EXPIRY: ChainEpoch = END_EPOCH + EPOCHS_IN_DAY;
+
+#[test]
+fn provider_extends_deal_within_slack() {
+    let rt = setup();
+    let (deal_id, deal) = generate_and_publish_deal(
+        &rt,
+        CLIENT_ADDR,
+        &MinerAddresses::default(),
+        START_EPOCH,
+        END_EPOCH,
+    );
+    let client_locked = get_balance(&rt, &CLIENT_ADDR).locked;
+    let provider_locked = get_balance(&rt, &PROVIDER_ADDR).locked;
+
+    let new_start = START_EPOCH + rt.policy.deal_activation_extension_slack;
+    let ret = request_activation_extension(&rt, &[deal_id], new_start, &[deal_id]).unwrap();
+    assert_eq!(BitField::try_from_bits([0]).unwrap(), ret.extended);
+
+    // The deal moves later with its duration and fees unchanged.
+    let extended = get_deal_proposal(&rt, deal_id);
+    assert_eq!(new_start, extended.start_epoch);
+    assert_eq!(deal.duration(), extended.duration());
+    assert_eq!(deal.total_storage_fee(), extended.total_storage_fee());
+    assert_eq!(client_locked, get_balance(&rt, &CLIENT_ADDR).locked);
+    assert_eq!(provider_locked, get_balance(&rt, &PROVIDER_ADDR).locked);
+    check_state(&rt);
+
+    // The deal can be activated after its original start epoch.
+    activate_deals(&rt, SECTOR_EXPIRY, PROVIDER_ADDR, START_EPOCH + 1, 1, &[deal_id]);
+    assert_eq!(1, get_activation_extension(&rt, deal_id).unwrap().superseded_proposals.len());
+    check_state(&rt);
+
+    // The superseded proposal stops pending along with the deal's current one.
+    rt.set_epoch(new_start + 1);
+    settle_deal_payments(&rt, PROVIDER_ADDR, &[deal_id], &[], &[]);
+    assert!(get_activation_extension(&rt, deal_id).is_none());
+    check_state(&rt);
+}
+
+#[test]
+fn superseded_proposal_cannot_be_republished() {
+    let rt = setup();
+    let addrs = MinerAddresses::default();
+    let (deal_id, deal) =
+        generate_and_publish_deal(&rt, CLIENT_ADDR, &addrs, START_EPOCH, END_EPOCH);
+    let new_start = START_EPOCH + rt.policy.deal_activation_extension_slack;
+    request_activation_extension(&rt, &[deal_id], new_start, &[deal_id]).unwrap();
+
+    // Funds would cover the original proposal again, but it remains pending.
+    add_provider_funds(&rt, deal.provider_collateral.clone(), &addrs);
+    add_participant_funds(&rt, CLIENT_ADDR, deal.client_balance_requirement());
+    publish_deals_expect_abort(&rt, &addrs, deal, ExitCode::USR_ILLEGAL_ARGUMENT);
+    check_state(&rt);
+}
+
+#[test]
+fn extension_beyond_slack_awaits_client_approval() {
+    let rt = setup();
+    let (deal_id, deal) = generate_and_publish_deal(
+        &rt,
+        CLIENT_ADDR,
+        &MinerAddresses::default(),
+        START_EPOCH,
+        END_EPOCH,
+    );
+
+    let new_start = START_EPOCH + rt.policy.deal_activation_extension_slack + 1;
+    let ret = request_activation_extension(&rt, &[deal_id], new_start, &[]).unwrap();
+    assert!(ret.extended.is_empty());
+    assert_eq!(deal, get_deal_proposal(&rt, deal_id));
+    assert_eq!(
+        Some(new_start),
+        get_activation_extension(&rt, deal_id).unwrap().requested_start_epoch
+    );
+    check_state(&rt);
+
+    // Only the client may approve.
+    for caller in [PROVIDER_ADDR, WORKER_ADDR] {
+        expect_abort(
+            ExitCode::USR_FORBIDDEN,
+            approve_activation_extension(&rt, caller, &[deal_id]),
+        );
+        rt.reset();
+    }
+
+    approve_activation_extension(&rt, CLIENT_ADDR, &[deal_id]).unwrap();
+    let extended = get_deal_proposal(&rt, deal_id);
+    assert_eq!(new_start, extended.start_epoch);
+    assert_eq!(deal.duration(), extended.duration());
+    let extension = get_activation_extension(&rt, deal_id).unwrap();
+    assert_eq!(START_EPOCH, extension.original_start_epoch);
+    assert_eq!(None, extension.requested_start_epoch);
+
+    // There is nothing left to approve.
+    expect_abort_contains_message(
+        ExitCode::USR_NOT_FOUND,
+        "no start epoch extension requested",
+        approve_activation_extension(&rt, CLIENT_ADDR, &[deal_id]),
+    );
+    rt.reset();
+    check_state(&rt);
+}
+
+#[test]
+fn slack_bounds_total_extension() {
+    let rt = setup();
+    let (deal_id, _) = generate_and_publish_deal(
+        &rt,
+        CLIENT_ADDR,
+        &MinerAddresses::default(),
+        START_EPOCH,
+        END_EPOCH,
+    );
+    let slack = rt.policy.deal_activation_extension_slack;
+
+    request_activation_extension(&rt, &[deal_id], START_EPOCH + slack / 2, &[deal_id]).unwrap();
+    request_activation_extension(&rt, &[deal_id], START_EPOCH + slack, &[deal_id]).unwrap();
+    // Further extension from the original start requires approval.
+    request_activation_extension(&rt, &[deal_id], START_EPOCH + slack + 1, &[]).unwrap();
+    assert_eq!(START_EPOCH + slack, get_deal_proposal(&rt, deal_id).start_epoch);
+    check_state(&rt);
+}
+
+#[test]
+fn extended_deal_times_out_at_new_start() {
+    let rt = setup();
+    let (deal_id, _) = generate_and_publish_deal(
+        &rt,
+        CLIENT_ADDR,
+        &MinerAddresses::default(),
+        START_EPOCH,
+        END_EPOCH,
+    );
+    let new_start = START_EPOCH + rt.policy.deal_activation_extension_slack;
+    request_activation_extension(&rt, &[deal_id], new_start, &[deal_id]).unwrap();
+    let extended = get_deal_proposal(&rt, deal_id);
+
+    rt.set_epoch(process_epoch(new_start, deal_id));
+    expect_slash(
+        &rt,
+        &extended.provider,
+        PenaltyCategory::DealActivationTimeout,
+        &extended.provider_collateral,
+    );
+    cron_tick(&rt);
+    assert_deal_deleted(&rt, deal_id, &extended, 0);
+    assert!(get_activation_extension(&rt, deal_id).is_none());
+    check_state(&rt);
+}
+
+#[test]
+fn rejects_invalid_extensions() {
+    let rt = setup();
+    let addrs = MinerAddresses::default();
+    let (deal_id, _) = generate_and_publish_deal(&rt, CLIENT_ADDR, &addrs, START_EPOCH, END_EPOCH);
+    let (activated_id, _) =
+        generate_and_publish_deal(&rt, CLIENT_ADDR, &addrs, START_EPOCH + 1, END_EPOCH);
+    let verified_id =
+        generate_and_publish_verified_deal(&rt, CLIENT_ADDR, &addrs, START_EPOCH + 2, END_EPOCH, 1);
+    activate_deals(&rt, SECTOR_EXPIRY, PROVIDER_ADDR, 0, 1, &[activated_id]);
+    let new_start = START_EPOCH + EPOCHS_IN_DAY / 4;
+
+    expect_abort_contains_message(
+        ExitCode::USR_FORBIDDEN,
+        "verified deal",
+        request_activation_extension(&rt, &[verified_id], new_start, &[]),
+    );
+    rt.reset();
+
+    expect_abort_contains_message(
+        ExitCode::USR_FORBIDDEN,
+        "has been activated",
+        request_activation_extension(&rt, &[activated_id], new_start, &[]),
+    );
+    rt.reset();
+
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "must be after start epoch",
+        request_activation_extension(&rt, &[deal_id], START_EPOCH, &[]),
+    );
+    rt.reset();
+
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "duplicate deal",
+        request_activation_extension(&rt, &[deal_id, deal_id], new_start, &[]),
+    );
+    rt.reset();
+
+    // Only the provider or its control addresses may request an extension.
+    let caller = Address::new_id(1001);
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, caller);
+    rt.expect_validate_caller_any();
+    expect_provider_is_control_address(&rt, PROVIDER_ADDR, caller, false);
+    let params =
+        RequestActivationExtensionParams { deal_ids: vec![deal_id], new_start_epoch: new_start };
+    expect_abort(
+        ExitCode::USR_FORBIDDEN,
+        rt.call::<fil_actor_market::Actor>(
+            Method::RequestActivationExtensionExported as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        ),
+    );
+    rt.reset();
+
+    // No extension may be requested or approved from the deal's start epoch.
+    rt.set_epoch(START_EPOCH);
+    expect_abort_contains_message(
+        ExitCode::USR_FORBIDDEN,
+        "at or after its start epoch",
+        request_activation_extension(&rt, &[deal_id], new_start, &[]),
+    );
+    rt.reset();
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, CLIENT_ADDR);
+    expect_abort_contains_message(
+        ExitCode::USR_FORBIDDEN,
+        "at or after its start epoch",
+        rt.call::<fil_actor_market::Actor>(
+            Method::ApproveActivationExtensionExported as u64,
+            IpldBlock::serialize_cbor(&ApproveActivationExtensionParams {
+                deal_ids: vec![deal_id],
+            })
+            .unwrap(),
+        ),
+    );
+    rt.reset();
+    check_state(&rt);
+}
@@ -0,0 +1,180 @@
+use fil_actor_market::{DealProposal, SectorDeals, NO_ALLOCATION_ID};
+use fil_actors_runtime::network::EPOCHS_IN_DAY;
+use fil_actors_runtime::test_utils::*;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
+use fvm_shared::sector::RegisteredSealProof;
+use num_traits::Zero;
+
+mod harness;
+
+use harness::*;
+
+const START_EPOCH: ChainEpoch = 50;
+const END_EPOCH: ChainEpoch = START_EPOCH + 200 * EPOCHS_IN_DAY;
+const SECTOR_EXPIRY: ChainEpoch = END_EPOCH + EPOCHS_IN_DAY;
+
+#[test]
+fn client_cancels_unactivated_deal() {
+    let rt = setup();
+    let (deal_id, deal) = generate_and_publish_deal(
+        &rt,
+        CLIENT_ADDR,
+        &MinerAddresses::default(),
+        START_EPOCH,
+        END_EPOCH,
+    );
+    let client_escrow = get_balance(&rt, &CLIENT_ADDR).balance;
+    let provider_escrow = get_balance(&rt, &PROVIDER_ADDR).balance;
+
+    let ret = cancel_published_deal(&rt, CLIENT_ADDR, deal_id).unwrap();
+    // The share of the storage fee exceeds the minimum fee for this deal's collateral.
+    let expected_fee = (deal.total_storage_fee() * rt.policy.deal_cancellation_fee_num)
+        .div_floor(rt.policy.deal_cancellation_fee_denom);
+    assert!(expected_fee > minimum_fee(&rt, &deal));
+    assert_eq!(expected_fee, ret.provider_fee);
+    assert_eq!(deal.client_balance_requirement() - &expected_fee, ret.client_refund);
+
+    // The client pays the fee to the provider, and all funds are unlocked.
+    let client_acct = get_balance(&rt, &CLIENT_ADDR);
+    assert_eq!(client_escrow - &expected_fee, client_acct.balance);
+    assert!(client_acct.locked.is_zero());
+    let provider_acct = get_balance(&rt, &PROVIDER_ADDR);
+    assert_eq!(provider_escrow + &expected_fee, provider_acct.balance);
+    assert!(provider_acct.locked.is_zero());
+    assert_deal_deleted(&rt, deal_id, &deal, 0);
+
+    // Nothing is slashed when the deal's scheduled cron epoch is reached.
+    rt.set_epoch(process_epoch(START_EPOCH, deal_id));
+    cron_tick(&rt);
+    check_state(&rt);
+}
+
+#[test]
+fn only_client_can_cancel() {
+    let rt = setup();
+    let (deal_id, _) = generate_and_publish_deal(
+        &rt,
+        CLIENT_ADDR,
+        &MinerAddresses::default(),
+        START_EPOCH,
+        END_EPOCH,
+    );
+
+    for caller in [PROVIDER_ADDR, WORKER_ADDR] {
+        expect_abort(ExitCode::USR_FORBIDDEN, cancel_published_deal(&rt, caller, deal_id));
+        rt.reset();
+    }
+    check_state(&rt);
+}
+
+#[test]
+fn cannot_cancel_started_or_activated_deal() {
+    let rt = setup();
+    let addrs = MinerAddresses::default();
+    let (started_id, _) =
+        generate_and_publish_deal(&rt, CLIENT_ADDR, &addrs, START_EPOCH, END_EPOCH);
+    let (activated_id, _) =
+        generate_and_publish_deal(&rt, CLIENT_ADDR, &addrs, START_EPOCH + 1, END_EPOCH);
+    activate_deals(&rt, SECTOR_EXPIRY, PROVIDER_ADDR, 0, 1, &[activated_id]);
+
+    expect_abort_contains_message(
+        ExitCode::USR_FORBIDDEN,
+        "has been activated",
+        cancel_published_deal(&rt, CLIENT_ADDR, activated_id),
+    );
+    rt.reset();
+
+    rt.set_epoch(START_EPOCH);
+    expect_abort_contains_message(
+        ExitCode::USR_FORBIDDEN,
+        "start epoch",
+        cancel_published_deal(&rt, CLIENT_ADDR, started_id),
+    );
+    rt.reset();
+    check_state(&rt);
+}
+
+#[test]
+fn cancellation_fee_is_at_least_a_share_of_provider_collateral() {
+    let rt = setup();
+    let addrs = MinerAddresses::default();
+    let deal = generate_deal_with_collateral_and_add_funds(
+        &rt,
+        CLIENT_ADDR,
+        &addrs,
+        TokenAmount::from_whole(1),
+        TokenAmount::zero(),
+        START_EPOCH,
+        END_EPOCH,
+    );
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, addrs.worker);
+    let deal_id =
+        publish_deals(&rt, &addrs, &[deal.clone()], TokenAmount::zero(), NO_ALLOCATION_ID)[0];
+    let expected_fee = minimum_fee(&rt, &deal);
+    assert!(expected_fee > deal.total_storage_fee());
+
+    // The fee exceeds the funds the deal releases, and the client has no other funds to pay it.
+    expect_abort(
+        ExitCode::USR_INSUFFICIENT_FUNDS,
+        cancel_published_deal(&rt, CLIENT_ADDR, deal_id),
+    );
+    rt.reset();
+
+    // The shortfall is paid from the client's available escrow.
+    add_participant_funds(&rt, CLIENT_ADDR, TokenAmount::from_whole(1));
+    let client_escrow = get_balance(&rt, &CLIENT_ADDR).balance;
+    let provider_escrow = get_balance(&rt, &PROVIDER_ADDR).balance;
+    let ret = cancel_published_deal(&rt, CLIENT_ADDR, deal_id).unwrap();
+    assert_eq!(expected_fee, ret.provider_fee);
+    assert!(ret.client_refund.is_zero());
+
+    let client_acct = get_balance(&rt, &CLIENT_ADDR);
+    assert_eq!(client_escrow - &expected_fee, client_acct.balance);
+    assert!(client_acct.locked.is_zero());
+    let provider_acct = get_balance(&rt, &PROVIDER_ADDR);
+    assert_eq!(provider_escrow + &expected_fee, provider_acct.balance);
+    assert!(provider_acct.locked.is_zero());
+    assert_deal_deleted(&rt, deal_id, &deal, 0);
+    check_state(&rt);
+}
+
+#[test]
+fn cannot_cancel_precommitted_deal() {
+    let rt = setup();
+    let (deal_id, _) = generate_and_publish_deal(
+        &rt,
+        CLIENT_ADDR,
+        &MinerAddresses::default(),
+        START_EPOCH,
+        END_EPOCH,
+    );
+    verify_deals_for_activation(
+        &rt,
+        PROVIDER_ADDR,
+        vec![SectorDeals {
+            sector_number: 1,
+            sector_type: RegisteredSealProof::StackedDRG2KiBV1P1,
+            sector_expiry: SECTOR_EXPIRY,
+            deal_ids: vec![deal_id],
+        }],
+        |_| None,
+    );
+
+    expect_abort_contains_message(
+        ExitCode::USR_FORBIDDEN,
+        "has been pre-committed",
+        cancel_published_deal(&rt, CLIENT_ADDR, deal_id),
+    );
+    rt.reset();
+
+    // The deal is no longer recorded as pre-committed once activated.
+    activate_deals(&rt, SECTOR_EXPIRY, PROVIDER_ADDR, 0, 1, &[deal_id]);
+    check_state(&rt);
+}
+
+fn minimum_fee(rt: &MockRuntime, deal: &DealProposal) -> TokenAmount {
+    (&deal.provider_collateral * rt.policy.deal_cancellation_min_fee_num)
+        .div_floor(rt.policy.deal_cancellation_min_fee_denom)
+}
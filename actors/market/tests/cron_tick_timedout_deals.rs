@@ -2,17 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 use fil_actor_market::{
-    Actor as MarketActor, ClientDealProposal, Method, PublishStorageDealsParams,
+    Actor as MarketActor, ClientDealProposal, Method, ProposalAuthScheme, PublishStorageDealsParams,
 };
 use fil_actors_runtime::network::EPOCHS_IN_DAY;
 use fil_actors_runtime::test_utils::*;
-use fil_actors_runtime::BURNT_FUNDS_ACTOR_ADDR;
+use fil_actors_runtime::PenaltyCategory;
 use fvm_ipld_encoding::RawBytes;
 use fvm_shared::clock::ChainEpoch;
 use fvm_shared::crypto::signature::Signature;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
-use fvm_shared::METHOD_SEND;
 
 use fil_actor_market::ext::account::{AuthenticateMessageParams, AUTHENTICATE_MESSAGE_METHOD};
 use fvm_ipld_encoding::ipld_block::IpldBlock;
@@ -41,13 +40,11 @@ fn timed_out_deal_is_slashed_and_deleted() {
 
     // do a cron tick for it -> should time out and get slashed
     rt.set_epoch(process_epoch(START_EPOCH, deal_id));
-    rt.expect_send_simple(
-        BURNT_FUNDS_ACTOR_ADDR,
-        METHOD_SEND,
-        None,
-        deal_proposal.provider_collateral.clone(),
-        None,
-        ExitCode::OK,
+    expect_slash(
+        &rt,
+        &deal_proposal.provider,
+        PenaltyCategory::DealActivationTimeout,
+        &deal_proposal.provider_collateral,
     );
     cron_tick(&rt);
 
@@ -81,8 +78,11 @@ fn publishing_timed_out_deal_again_should_work_after_cron_tick_as_it_should_no_l
     );
     let buf = RawBytes::serialize(deal_proposal2.clone()).expect("failed to marshal deal proposal");
     let sig = Signature::new_bls(buf.to_vec());
-    let client_deal_proposal =
-        ClientDealProposal { proposal: deal_proposal2.clone(), client_signature: sig };
+    let client_deal_proposal = ClientDealProposal {
+        proposal: deal_proposal2.clone(),
+        client_signature: sig,
+        auth_scheme: ProposalAuthScheme::Cbor,
+    };
     let params = PublishStorageDealsParams { deals: vec![client_deal_proposal] };
     rt.expect_validate_caller_any();
     expect_provider_is_control_address(&rt, PROVIDER_ADDR, WORKER_ADDR, true);
@@ -117,13 +117,11 @@ fn publishing_timed_out_deal_again_should_work_after_cron_tick_as_it_should_no_l
 
     // do a cron tick for it -> should time out and get slashed
     rt.set_epoch(process_epoch(START_EPOCH, deal_id));
-    rt.expect_send_simple(
-        BURNT_FUNDS_ACTOR_ADDR,
-        METHOD_SEND,
-        None,
-        deal_proposal.provider_collateral.clone(),
-        None,
-        ExitCode::OK,
+    expect_slash(
+        &rt,
+        &deal_proposal.provider,
+        PenaltyCategory::DealActivationTimeout,
+        &deal_proposal.provider_collateral,
     );
     cron_tick(&rt);
     assert_deal_deleted(&rt, deal_id, &deal_proposal, 0);
@@ -178,14 +176,7 @@ fn timed_out_and_verified_deals_are_slashed_deleted() {
     rt.set_epoch(process_epoch(START_EPOCH, *deal_ids.last().unwrap()));
 
     let expected_burn = 3 * &deal1.provider_collateral;
-    rt.expect_send_simple(
-        BURNT_FUNDS_ACTOR_ADDR,
-        METHOD_SEND,
-        None,
-        expected_burn,
-        None,
-        ExitCode::OK,
-    );
+    expect_slash(&rt, &PROVIDER_ADDR, PenaltyCategory::DealActivationTimeout, &expected_burn);
     cron_tick(&rt);
 
     // a second cron tick for the same epoch should not change anything
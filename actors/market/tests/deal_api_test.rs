@@ -5,13 +5,17 @@ use fvm_shared::error::ExitCode;
 use num_traits::Zero;
 use serde::de::DeserializeOwned;
 
+use fil_actor_market::policy::CLIENT_DEAL_STATS_WINDOW;
 use fil_actor_market::{
-    Actor as MarketActor, DealQueryParams, GetDealActivationReturn, GetDealClientCollateralReturn,
+    Actor as MarketActor, DealQueryParams, DealRecord, GetClientDealStatsParams,
+    GetClientDealStatsReturn, GetDealActivationReturn, GetDealClientCollateralReturn,
     GetDealClientReturn, GetDealDataCommitmentReturn, GetDealLabelReturn,
     GetDealProviderCollateralReturn, GetDealProviderReturn, GetDealSectorReturn, GetDealTermReturn,
-    GetDealTotalPriceReturn, GetDealVerifiedReturn, Method, EX_DEAL_EXPIRED, EX_DEAL_NOT_ACTIVATED,
+    GetDealTotalPriceReturn, GetDealVerifiedReturn, ListDealsParams, ListDealsReturn, Method,
+    EX_DEAL_EXPIRED, EX_DEAL_NOT_ACTIVATED,
 };
 use fil_actors_runtime::network::EPOCHS_IN_DAY;
+use fil_actors_runtime::query::{FieldFilter, FilterOp, ListRequest};
 use fil_actors_runtime::test_utils::{
     expect_abort, expect_abort_contains_message, MockRuntime, ACCOUNT_ACTOR_CODE_ID,
 };
@@ -144,6 +148,132 @@ fn activation() {
     query_deal_fails(&rt, Method::GetDealSectorExported, id + 1, ExitCode::USR_NOT_FOUND);
 }
 
+#[test]
+fn client_deal_stats() {
+    let start_epoch = 50 * EPOCHS_IN_DAY;
+    let end_epoch = start_epoch + 200 * EPOCHS_IN_DAY;
+
+    let rt = setup();
+    rt.set_epoch(1);
+    assert_eq!(0, query_client_stats(&rt).deals_published);
+
+    // Publish one deal on the first day and another ten days later.
+    let (_, first) = generate_and_publish_deal(
+        &rt,
+        CLIENT_ADDR,
+        &MinerAddresses::default(),
+        start_epoch,
+        end_epoch,
+    );
+    let stats = query_client_stats(&rt);
+    assert_eq!(1, stats.deals_published);
+    assert_eq!(first.piece_size.0, stats.data_onboarded);
+    assert_eq!(first.total_storage_fee(), stats.fees_committed);
+    assert_eq!(CLIENT_DEAL_STATS_WINDOW, stats.window_epochs);
+    assert_eq!(
+        first.total_storage_fee().div_floor(CLIENT_DEAL_STATS_WINDOW),
+        stats.fees_committed_per_epoch
+    );
+
+    rt.set_epoch(1 + 10 * EPOCHS_IN_DAY);
+    let (_, second) = generate_and_publish_deal(
+        &rt,
+        CLIENT_ADDR,
+        &MinerAddresses::default(),
+        start_epoch,
+        end_epoch + 1,
+    );
+    let stats = query_client_stats(&rt);
+    assert_eq!(2, stats.deals_published);
+    assert_eq!(first.piece_size.0 + second.piece_size.0, stats.data_onboarded);
+    assert_eq!(first.total_storage_fee() + second.total_storage_fee(), stats.fees_committed);
+
+    // The first deal falls out of the window after thirty days.
+    rt.set_epoch(1 + CLIENT_DEAL_STATS_WINDOW);
+    let stats = query_client_stats(&rt);
+    assert_eq!(1, stats.deals_published);
+    assert_eq!(second.total_storage_fee(), stats.fees_committed);
+
+    rt.set_epoch(1 + 10 * EPOCHS_IN_DAY + CLIENT_DEAL_STATS_WINDOW);
+    let stats = query_client_stats(&rt);
+    assert_eq!(0, stats.deals_published);
+    assert!(stats.fees_committed.is_zero());
+    check_state(&rt);
+}
+
+#[test]
+fn list_deals() {
+    let start_epoch = 50 * EPOCHS_IN_DAY;
+    let end_epoch = start_epoch + 200 * EPOCHS_IN_DAY;
+
+    let rt = setup();
+    rt.set_epoch(1);
+    let expected: Vec<DealRecord> = (0..3)
+        .map(|i| {
+            let (id, proposal) = generate_and_publish_deal(
+                &rt,
+                CLIENT_ADDR,
+                &MinerAddresses::default(),
+                start_epoch,
+                end_epoch + i,
+            );
+            DealRecord { id, proposal }
+        })
+        .collect();
+
+    let page = list_deals_raw(&rt, ListRequest::first(10)).unwrap();
+    assert_eq!(expected, page.entries);
+    assert_eq!(None, page.next_cursor);
+
+    // Page through the deals ending after the first one, one deal at a time.
+    // The first page traverses only the first deal, so holds no entries.
+    let mut request = ListRequest::first(1);
+    request.filter.push(FieldFilter::new("end-epoch", FilterOp::Gt, end_epoch));
+    let page = list_deals_raw(&rt, request.clone()).unwrap();
+    assert!(page.entries.is_empty());
+    assert_eq!(Some(expected[1].id), page.next_cursor);
+
+    request.cursor = page.next_cursor;
+    let page = list_deals_raw(&rt, request.clone()).unwrap();
+    assert_eq!(expected[1..2], page.entries);
+    assert_eq!(Some(expected[2].id), page.next_cursor);
+
+    request.cursor = page.next_cursor;
+    let page = list_deals_raw(&rt, request).unwrap();
+    assert_eq!(expected[2..], page.entries);
+    assert_eq!(None, page.next_cursor);
+
+    // Only indexed fields may be filtered on.
+    let mut request = ListRequest::first(10);
+    request.filter.push(FieldFilter::new("label", FilterOp::Eq, 0));
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "cannot filter on label",
+        list_deals_raw(&rt, request),
+    );
+    rt.reset();
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "page limit 0",
+        list_deals_raw(&rt, ListRequest::first(0)),
+    );
+    rt.reset();
+    check_state(&rt);
+}
+
+fn query_client_stats(rt: &MockRuntime) -> GetClientDealStatsReturn {
+    let params = GetClientDealStatsParams { client: CLIENT_ADDR };
+    rt.expect_validate_caller_any();
+    rt.call::<MarketActor>(
+        Method::GetClientDealStatsExported as u64,
+        IpldBlock::serialize_cbor(&params).unwrap(),
+    )
+    .unwrap()
+    .unwrap()
+    .deserialize()
+    .unwrap()
+}
+
 fn query_deal<T: DeserializeOwned>(rt: &MockRuntime, method: Method, id: u64) -> T {
     query_deal_raw(rt, method, id).unwrap().unwrap().deserialize().unwrap()
 }
@@ -161,3 +291,16 @@ fn query_deal_raw(
     rt.expect_validate_caller_any();
     rt.call::<MarketActor>(method as u64, IpldBlock::serialize_cbor(&params).unwrap())
 }
+
+fn list_deals_raw(
+    rt: &MockRuntime,
+    request: ListRequest<u64>,
+) -> Result<ListDealsReturn, ActorError> {
+    let params = ListDealsParams { request };
+    rt.expect_validate_caller_any();
+    let ret = rt.call::<MarketActor>(
+        Method::ListDealsExported as u64,
+        IpldBlock::serialize_cbor(&params).unwrap(),
+    )?;
+    Ok(ret.unwrap().deserialize().unwrap())
+}
@@ -10,6 +10,7 @@ use frc46_token::token::types::{TransferFromParams, TransferFromReturn};
 use fvm_ipld_bitfield::BitField;
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_ipld_encoding::{to_vec, RawBytes};
+use fvm_shared::address::Protocol;
 use fvm_shared::bigint::BigInt;
 use fvm_shared::clock::{ChainEpoch, EPOCH_UNDEFINED};
 use fvm_shared::crypto::signature::Signature;
@@ -29,16 +30,20 @@ use fil_actor_market::ext::miner::{
 };
 use fil_actor_market::ext::verifreg::{AllocationID, AllocationRequest, AllocationsResponse};
 use fil_actor_market::{
-    deal_cid, deal_get_payment_remaining, BatchActivateDealsParams, BatchActivateDealsResult,
-    DealOpsByEpoch, PendingDealAllocationsMap, PendingProposalsSet, ProviderSectorsMap,
+    deal_cid, deal_get_payment_remaining, ActivationExtension, ApproveActivationExtensionParams,
+    BatchActivateDealsParams, BatchActivateDealsResult, CancelPublishedDealParams,
+    CancelPublishedDealReturn, DealOpsByEpoch, PendingDealAllocationsMap,
+    PendingDealTerminationsMap, PendingProposalsSet, PendingSectorTerminations, ProviderSectorsMap,
     SectorDealsMap, SettleDealPaymentsParams, SettleDealPaymentsReturn, PENDING_ALLOCATIONS_CONFIG,
-    PENDING_PROPOSALS_CONFIG, PROVIDER_SECTORS_CONFIG, SECTOR_DEALS_CONFIG,
+    PENDING_DEAL_TERMINATIONS_CONFIG, PENDING_PROPOSALS_CONFIG, PROVIDER_SECTORS_CONFIG,
+    SECTOR_DEALS_CONFIG,
 };
 use fil_actor_market::{
     ext, ext::miner::GetControlAddressesReturnParams, next_update_epoch,
     testing::check_state_invariants, Actor as MarketActor, ClientDealProposal, DealArray,
     DealMetaArray, DealProposal, DealState, GetBalanceReturn, Label, MarketNotifyDealParams,
-    Method, OnMinerSectorsTerminateParams, PublishStorageDealsParams, PublishStorageDealsReturn,
+    Method, OnMinerSectorsTerminateParams, ProposalAuthScheme, PublishStorageDealsParams,
+    PublishStorageDealsReturn, RequestActivationExtensionParams, RequestActivationExtensionReturn,
     SectorDeals, State, VerifyDealsForActivationParams, VerifyDealsForActivationReturn,
     WithdrawBalanceParams, WithdrawBalanceReturn, MARKET_NOTIFY_DEAL_METHOD, NO_ALLOCATION_ID,
 };
@@ -47,11 +52,12 @@ use fil_actor_reward::Method as RewardMethod;
 use fil_actors_runtime::cbor::serialize;
 use fil_actors_runtime::{
     network::EPOCHS_IN_DAY,
-    runtime::{builtins::Type, Policy, Runtime},
+    runtime::{builtins::Type, Policy, Runtime, SignatureVerifyInfo},
     test_utils::*,
-    ActorError, BatchReturn, EventBuilder, BURNT_FUNDS_ACTOR_ADDR, CRON_ACTOR_ADDR,
-    DATACAP_TOKEN_ACTOR_ADDR, REWARD_ACTOR_ADDR, STORAGE_MARKET_ACTOR_ADDR,
-    STORAGE_POWER_ACTOR_ADDR, SYSTEM_ACTOR_ADDR, VERIFIED_REGISTRY_ACTOR_ADDR,
+    ActorError, BatchReturn, EventBuilder, PenaltyCategory, BURNT_FUNDS_ACTOR_ADDR,
+    CRON_ACTOR_ADDR, DATACAP_TOKEN_ACTOR_ADDR, PENALTY_ASSESSED_EVENT, REWARD_ACTOR_ADDR,
+    STORAGE_MARKET_ACTOR_ADDR, STORAGE_POWER_ACTOR_ADDR, SYSTEM_ACTOR_ADDR,
+    VERIFIED_REGISTRY_ACTOR_ADDR,
 };
 
 // Define common set of actor ids that will be used across all tests.
@@ -572,6 +578,29 @@ pub fn get_sector_deal_ids(
     }
 }
 
+/// Returns each provider's sectors whose deals' termination is deferred, in the order cron will
+/// process them.
+pub fn get_pending_deal_terminations(
+    rt: &MockRuntime,
+) -> Vec<(ActorID, Vec<PendingSectorTerminations>)> {
+    let st: State = rt.get_state();
+    let pending = PendingDealTerminationsMap::load(
+        &rt.store,
+        &st.pending_deal_terminations,
+        PENDING_DEAL_TERMINATIONS_CONFIG,
+        "pending deal terminations",
+    )
+    .unwrap();
+    let mut terminations = vec![];
+    pending
+        .for_each(|provider, pending| {
+            terminations.push((provider, pending.clone()));
+            Ok(())
+        })
+        .unwrap();
+    terminations
+}
+
 pub fn update_last_updated(rt: &MockRuntime, deal_id: DealID, new_last_updated: ChainEpoch) {
     let st: State = rt.get_state();
     let mut states = DealMetaArray::load(&st.states, &rt.store).unwrap();
@@ -613,14 +642,6 @@ pub fn cron_tick_and_assert_balances(
     // end epoch for payment calc
     let mut payment_end = d.end_epoch;
     if s.slash_epoch != EPOCH_UNDEFINED {
-        rt.expect_send_simple(
-            BURNT_FUNDS_ACTOR_ADDR,
-            METHOD_SEND,
-            None,
-            d.provider_collateral.clone(),
-            None,
-            ExitCode::OK,
-        );
         amount_slashed = d.provider_collateral;
 
         if s.slash_epoch < d.start_epoch {
@@ -661,6 +682,9 @@ pub fn cron_tick_and_assert_balances(
             provider_addr.id().unwrap(),
         );
     }
+    if amount_slashed.is_positive() {
+        expect_slash(rt, &provider_addr, PenaltyCategory::DealTermination, &amount_slashed);
+    }
 
     cron_tick(rt);
 
@@ -701,18 +725,21 @@ pub fn publish_deals(
     let st: State = rt.get_state();
     let next_deal_id = st.next_id;
     rt.expect_validate_caller_any();
-    let return_value = ext::miner::IsControllingAddressReturn { is_controlling: true };
-    rt.expect_send_simple(
-        addrs.provider,
-        ext::miner::IS_CONTROLLING_ADDRESS_EXPORTED,
-        IpldBlock::serialize_cbor(&ext::miner::IsControllingAddressParam {
-            address: *rt.caller.borrow(),
-        })
-        .unwrap(),
-        TokenAmount::zero(),
-        IpldBlock::serialize_cbor(&return_value).unwrap(),
-        ExitCode::OK,
-    );
+    // A provider publishing its own deals needn't be checked as a controlling address.
+    if *rt.caller.borrow() != addrs.provider {
+        let return_value = ext::miner::IsControllingAddressReturn { is_controlling: true };
+        rt.expect_send_simple(
+            addrs.provider,
+            ext::miner::IS_CONTROLLING_ADDRESS_EXPORTED,
+            IpldBlock::serialize_cbor(&ext::miner::IsControllingAddressParam {
+                address: *rt.caller.borrow(),
+            })
+            .unwrap(),
+            TokenAmount::zero(),
+            IpldBlock::serialize_cbor(&return_value).unwrap(),
+            ExitCode::OK,
+        );
+    }
 
     expect_query_network_info(rt);
 
@@ -732,8 +759,11 @@ pub fn publish_deals(
         // create a client proposal with a valid signature
         let buf = RawBytes::serialize(deal.clone()).expect("failed to marshal deal proposal");
         let sig = Signature::new_bls("does not matter".as_bytes().to_vec());
-        let client_proposal =
-            ClientDealProposal { proposal: deal.clone(), client_signature: sig.clone() };
+        let client_proposal = ClientDealProposal {
+            proposal: deal.clone(),
+            client_signature: sig.clone(),
+            auth_scheme: ProposalAuthScheme::Cbor,
+        };
         params.deals.push(client_proposal);
 
         // Expect an invocation of authenticate_message to verify the signature.
@@ -922,7 +952,11 @@ pub fn publish_deals_expect_abort(
 
     rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, WORKER_ADDR);
     let deal_params = PublishStorageDealsParams {
-        deals: vec![ClientDealProposal { proposal, client_signature }],
+        deals: vec![ClientDealProposal {
+            proposal,
+            client_signature,
+            auth_scheme: ProposalAuthScheme::Cbor,
+        }],
     };
     expect_abort(
         expected_exit_code,
@@ -982,6 +1016,117 @@ pub fn settle_deal_payments(
     res
 }
 
+pub fn cancel_published_deal(
+    rt: &MockRuntime,
+    caller: Address,
+    deal_id: DealID,
+) -> Result<CancelPublishedDealReturn, ActorError> {
+    let deal = get_deal_proposal(rt, deal_id);
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, caller);
+    rt.expect_validate_caller_addr(vec![deal.client]);
+    expect_emitted(
+        rt,
+        "deal-cancelled",
+        deal_id,
+        deal.client.id().unwrap(),
+        deal.provider.id().unwrap(),
+    );
+
+    let params = IpldBlock::serialize_cbor(&CancelPublishedDealParams { deal_id }).unwrap();
+    let ret: CancelPublishedDealReturn = rt
+        .call::<MarketActor>(Method::CancelPublishedDealExported as u64, params)?
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    Ok(ret)
+}
+
+/// Requests a later start epoch for deals as the provider's worker, expecting the deals in
+/// `extended` to be extended immediately and the rest to await their client's approval.
+pub fn request_activation_extension(
+    rt: &MockRuntime,
+    deal_ids: &[DealID],
+    new_start_epoch: ChainEpoch,
+    extended: &[DealID],
+) -> Result<RequestActivationExtensionReturn, ActorError> {
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, WORKER_ADDR);
+    rt.expect_validate_caller_any();
+    expect_provider_is_control_address(rt, PROVIDER_ADDR, WORKER_ADDR, true);
+    for deal_id in deal_ids {
+        let typ = if extended.contains(deal_id) {
+            "deal-activation-extended"
+        } else {
+            "deal-activation-extension-requested"
+        };
+        expect_start_epoch_emitted(rt, typ, *deal_id, new_start_epoch);
+    }
+
+    let params = IpldBlock::serialize_cbor(&RequestActivationExtensionParams {
+        deal_ids: deal_ids.to_vec(),
+        new_start_epoch,
+    })
+    .unwrap();
+    let ret: RequestActivationExtensionReturn = rt
+        .call::<MarketActor>(Method::RequestActivationExtensionExported as u64, params)?
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    Ok(ret)
+}
+
+/// Approves the requested start epoch extensions of deals, which must share a client.
+pub fn approve_activation_extension(
+    rt: &MockRuntime,
+    caller: Address,
+    deal_ids: &[DealID],
+) -> Result<(), ActorError> {
+    let client = get_deal_proposal(rt, deal_ids[0]).client;
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, caller);
+    rt.expect_validate_caller_addr(vec![client]);
+    for deal_id in deal_ids {
+        if let Some(requested) =
+            get_activation_extension(rt, *deal_id).and_then(|e| e.requested_start_epoch)
+        {
+            expect_start_epoch_emitted(rt, "deal-activation-extended", *deal_id, requested);
+        }
+    }
+
+    let params = IpldBlock::serialize_cbor(&ApproveActivationExtensionParams {
+        deal_ids: deal_ids.to_vec(),
+    })
+    .unwrap();
+    let ret = rt.call::<MarketActor>(Method::ApproveActivationExtensionExported as u64, params)?;
+    assert!(ret.is_none());
+    rt.verify();
+    Ok(())
+}
+
+pub fn get_activation_extension(rt: &MockRuntime, deal_id: DealID) -> Option<ActivationExtension> {
+    let st: State = rt.get_state();
+    st.get_activation_extension(&rt.store, deal_id).unwrap()
+}
+
+fn expect_start_epoch_emitted(
+    rt: &MockRuntime,
+    typ: &str,
+    deal_id: DealID,
+    start_epoch: ChainEpoch,
+) {
+    let deal = get_deal_proposal(rt, deal_id);
+    rt.expect_emitted_event(
+        EventBuilder::new()
+            .typ(typ)
+            .field_indexed("id", &deal_id)
+            .field_indexed("client", &deal.client.id().unwrap())
+            .field_indexed("provider", &deal.provider.id().unwrap())
+            .field("start-epoch", &start_epoch)
+            .build()
+            .unwrap(),
+    );
+}
+
 pub fn settle_deal_payments_no_change(
     rt: &MockRuntime,
     caller: Address,
@@ -1257,32 +1402,48 @@ where
 
     let buf = RawBytes::serialize(deal_proposal.clone()).expect("failed to marshal deal proposal");
     let sig = Signature::new_bls(buf.to_vec());
-    let auth_param = IpldBlock::serialize_cbor(&AuthenticateMessageParams {
-        signature: buf.to_vec(),
-        message: buf.to_vec(),
-    })
-    .unwrap();
+    if deal_proposal.client.protocol() == Protocol::BLS {
+        // Signatures by key addresses are verified directly, in a batch.
+        rt.expect_verify_signatures_batch(
+            vec![SignatureVerifyInfo {
+                signature: sig.clone(),
+                signer: deal_proposal.client,
+                plaintext: buf.to_vec(),
+            }],
+            vec![sig_valid],
+        );
+    } else {
+        let auth_param = IpldBlock::serialize_cbor(&AuthenticateMessageParams {
+            signature: buf.to_vec(),
+            message: buf.to_vec(),
+        })
+        .unwrap();
 
-    rt.expect_send(
-        deal_proposal.client,
-        AUTHENTICATE_MESSAGE_METHOD,
-        auth_param,
-        TokenAmount::zero(),
-        None,
-        SendFlags::READ_ONLY,
-        match sig_valid {
-            true => AUTHENTICATE_MESSAGE_RESPONSE.clone(),
-            false => None,
-        },
-        match sig_valid {
-            true => ExitCode::OK,
-            false => ExitCode::USR_ILLEGAL_ARGUMENT,
-        },
-        None,
-    );
+        rt.expect_send(
+            deal_proposal.client,
+            AUTHENTICATE_MESSAGE_METHOD,
+            auth_param,
+            TokenAmount::zero(),
+            None,
+            SendFlags::READ_ONLY,
+            match sig_valid {
+                true => AUTHENTICATE_MESSAGE_RESPONSE.clone(),
+                false => None,
+            },
+            match sig_valid {
+                true => ExitCode::OK,
+                false => ExitCode::USR_ILLEGAL_ARGUMENT,
+            },
+            None,
+        );
+    }
 
     let params: PublishStorageDealsParams = PublishStorageDealsParams {
-        deals: vec![ClientDealProposal { proposal: deal_proposal, client_signature: sig }],
+        deals: vec![ClientDealProposal {
+            proposal: deal_proposal,
+            client_signature: sig,
+            auth_scheme: ProposalAuthScheme::Cbor,
+        }],
     };
 
     assert_eq!(
@@ -1608,18 +1769,8 @@ pub fn terminate_deals(
         }
     }
 
-    if total_slashed.is_positive() {
-        rt.expect_send_simple(
-            BURNT_FUNDS_ACTOR_ADDR,
-            METHOD_SEND,
-            None,
-            total_slashed,
-            None,
-            ExitCode::OK,
-        );
-    }
-
-    let ret = terminate_deals_raw(rt, miner_addr, sectors, expected_terminations).unwrap();
+    let ret = terminate_deals_raw(rt, miner_addr, sectors, expected_terminations, &total_slashed)
+        .unwrap();
     assert!(ret.is_none());
     rt.verify();
 }
@@ -1653,6 +1804,7 @@ pub fn terminate_deals_raw(
     miner_addr: Address,
     sector_numbers: &[SectorNumber],
     terminated_deals: &[DealID],
+    slashed: &TokenAmount,
 ) -> Result<Option<IpldBlock>, ActorError> {
     rt.set_caller(*MINER_ACTOR_CODE_ID, miner_addr);
     rt.expect_validate_caller_type(vec![Type::Miner]);
@@ -1670,6 +1822,9 @@ pub fn terminate_deals_raw(
             d.provider.id().unwrap(),
         )
     }
+    if slashed.is_positive() {
+        expect_slash(rt, &miner_addr, PenaltyCategory::DealTermination, slashed);
+    }
 
     rt.call::<MarketActor>(
         Method::OnMinerSectorsTerminate as u64,
@@ -1766,3 +1921,41 @@ pub fn expect_emitted(rt: &MockRuntime, typ: &str, id: DealID, client: ActorID,
             .unwrap(),
     );
 }
+
+/// Expects provider collateral to be burnt and reported as slashed from a single provider.
+pub fn expect_slash(
+    rt: &MockRuntime,
+    provider: &Address,
+    category: PenaltyCategory,
+    amount: &TokenAmount,
+) {
+    rt.expect_send_simple(
+        BURNT_FUNDS_ACTOR_ADDR,
+        METHOD_SEND,
+        None,
+        amount.clone(),
+        None,
+        ExitCode::OK,
+    );
+    expect_collateral_slashed(rt, provider, category, amount);
+}
+
+pub fn expect_collateral_slashed(
+    rt: &MockRuntime,
+    provider: &Address,
+    category: PenaltyCategory,
+    amount: &TokenAmount,
+) {
+    rt.expect_emitted_event(
+        EventBuilder::new()
+            .typ(PENALTY_ASSESSED_EVENT)
+            .field("actor", &STORAGE_MARKET_ACTOR_ADDR.id().unwrap())
+            .field_indexed("subject", &provider.id().unwrap())
+            .field_indexed("category", category.as_str())
+            .field("amount", amount)
+            .field("burned", amount)
+            .field("withheld", &TokenAmount::zero())
+            .build()
+            .unwrap(),
+    );
+}
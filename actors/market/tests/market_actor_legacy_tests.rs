@@ -8,12 +8,11 @@ use fil_actor_market::{next_update_epoch, State};
 use fil_actors_runtime::network::EPOCHS_IN_DAY;
 use fil_actors_runtime::runtime::{Runtime, RuntimePolicy};
 use fil_actors_runtime::test_utils::*;
-use fil_actors_runtime::{BURNT_FUNDS_ACTOR_ADDR, EPOCHS_IN_YEAR};
+use fil_actors_runtime::{PenaltyCategory, EPOCHS_IN_YEAR};
 use fvm_shared::address::Address;
 use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
-use fvm_shared::METHOD_SEND;
 use regex::Regex;
 
 use num_traits::Zero;
@@ -348,13 +347,11 @@ fn locked_fund_tracking_states() {
     // make payment for p1 and p2, p3 times out as it has not been activated
     let curr = rt.set_epoch(process_epoch(start_epoch, deal_id3));
     let last_payment_epoch = curr;
-    rt.expect_send_simple(
-        BURNT_FUNDS_ACTOR_ADDR,
-        METHOD_SEND,
-        None,
-        d3.provider_collateral.clone(),
-        None,
-        ExitCode::OK,
+    expect_slash(
+        &rt,
+        &d3.provider,
+        PenaltyCategory::DealActivationTimeout,
+        &d3.provider_collateral,
     );
     cron_tick(&rt);
     let duration = curr - start_epoch;
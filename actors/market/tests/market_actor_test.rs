@@ -17,7 +17,7 @@ use fvm_shared::error::ExitCode;
 use fvm_shared::piece::PaddedPieceSize;
 use fvm_shared::sector::{RegisteredSealProof, StoragePower};
 use fvm_shared::sys::SendFlags;
-use fvm_shared::{MethodNum, METHOD_CONSTRUCTOR, METHOD_SEND};
+use fvm_shared::{MethodNum, METHOD_CONSTRUCTOR};
 use num_traits::{FromPrimitive, Zero};
 use regex::Regex;
 
@@ -28,17 +28,17 @@ use fil_actor_market::policy::detail::DEAL_MAX_LABEL_SIZE;
 use fil_actor_market::{
     ext, Actor as MarketActor, BatchActivateDealsResult, ClientDealProposal, DealArray,
     DealMetaArray, DealOpsByEpoch, Label, MarketNotifyDealParams, Method,
-    PendingDealAllocationsMap, PendingProposalsSet, PublishStorageDealsParams,
+    PendingDealAllocationsMap, PendingProposalsSet, ProposalAuthScheme, PublishStorageDealsParams,
     PublishStorageDealsReturn, SectorDeals, State, WithdrawBalanceParams, DEAL_OPS_BY_EPOCH_CONFIG,
     EX_DEAL_EXPIRED, MARKET_NOTIFY_DEAL_METHOD, PENDING_ALLOCATIONS_CONFIG,
     PENDING_PROPOSALS_CONFIG, PROPOSALS_AMT_BITWIDTH, STATES_AMT_BITWIDTH,
 };
 use fil_actors_runtime::cbor::{deserialize, serialize};
 use fil_actors_runtime::network::EPOCHS_IN_DAY;
-use fil_actors_runtime::runtime::{Policy, Runtime};
+use fil_actors_runtime::runtime::{Policy, Runtime, SignatureVerifyInfo};
 use fil_actors_runtime::test_utils::*;
 use fil_actors_runtime::{
-    ActorError, BatchReturn, SetMultimap, SetMultimapConfig, BURNT_FUNDS_ACTOR_ADDR,
+    ActorError, BatchReturn, PenaltyCategory, SetMultimap, SetMultimapConfig,
     DATACAP_TOKEN_ACTOR_ADDR, DEFAULT_HAMT_CONFIG, SYSTEM_ACTOR_ADDR, VERIFIED_REGISTRY_ACTOR_ADDR,
 };
 use harness::*;
@@ -704,14 +704,42 @@ fn simple_deal() {
         next_allocation_id,
     )[0];
 
+    // Publish from the provider itself.
+    let mut deal3 = generate_deal_and_add_funds(
+        &rt,
+        CLIENT_ADDR,
+        &MinerAddresses::default(),
+        start_epoch + 2,
+        end_epoch + 2,
+    );
+    deal3.verified_deal = false;
+    rt.set_caller(*MINER_ACTOR_CODE_ID, PROVIDER_ADDR);
+    let deal3_id = publish_deals(
+        &rt,
+        &MinerAddresses::default(),
+        &[deal3],
+        TokenAmount::zero(),
+        next_allocation_id,
+    )[0];
+
     // activate the deal
-    activate_deals(&rt, end_epoch + 1, PROVIDER_ADDR, publish_epoch, 1, &[deal1_id, deal2_id]);
+    activate_deals(
+        &rt,
+        end_epoch + 2,
+        PROVIDER_ADDR,
+        publish_epoch,
+        1,
+        &[deal1_id, deal2_id, deal3_id],
+    );
     let deal1st = get_deal_state(&rt, deal1_id);
     assert_eq!(publish_epoch, deal1st.sector_start_epoch);
 
     let deal2st = get_deal_state(&rt, deal2_id);
     assert_eq!(publish_epoch, deal2st.sector_start_epoch);
 
+    let deal3st = get_deal_state(&rt, deal3_id);
+    assert_eq!(publish_epoch, deal3st.sector_start_epoch);
+
     check_state(&rt);
 }
 
@@ -744,13 +772,11 @@ fn deal_expires() {
     )[0];
 
     rt.set_epoch(start_epoch + Policy::default().deal_updates_interval + 1);
-    rt.expect_send_simple(
-        BURNT_FUNDS_ACTOR_ADDR,
-        METHOD_SEND,
-        None,
-        deal.provider_collateral,
-        None,
-        ExitCode::OK,
+    expect_slash(
+        &rt,
+        &deal.provider,
+        PenaltyCategory::DealActivationTimeout,
+        &deal.provider_collateral,
     );
     cron_tick(&rt);
 
@@ -851,26 +877,16 @@ fn provider_and_client_addresses_are_resolved_before_persisting_state_and_sent_t
     let mut params = PublishStorageDealsParams { deals: vec![] };
     let buf = RawBytes::serialize(&deal).expect("failed to marshal deal proposal");
     let sig = Signature::new_bls(buf.to_vec());
-    let client_proposal = ClientDealProposal { client_signature: sig, proposal: deal.clone() };
+    let client_proposal = ClientDealProposal {
+        client_signature: sig.clone(),
+        proposal: deal.clone(),
+        auth_scheme: ProposalAuthScheme::Cbor,
+    };
     params.deals.push(client_proposal);
-    // expect a call to verify the above signature
-
-    let auth_param = IpldBlock::serialize_cbor(&AuthenticateMessageParams {
-        signature: buf.to_vec(),
-        message: buf.to_vec(),
-    })
-    .unwrap();
-
-    rt.expect_send(
-        deal.client,
-        AUTHENTICATE_MESSAGE_METHOD,
-        auth_param,
-        TokenAmount::zero(),
-        None,
-        SendFlags::READ_ONLY,
-        AUTHENTICATE_MESSAGE_RESPONSE.clone(),
-        ExitCode::OK,
-        None,
+    // expect the above signature to be verified directly, since the client is a BLS address
+    rt.expect_verify_signatures_batch(
+        vec![SignatureVerifyInfo { signature: sig, signer: deal.client, plaintext: buf.to_vec() }],
+        vec![true],
     );
 
     // Data cap transfer is requested using the resolved address (not that it matters).
@@ -1471,7 +1487,11 @@ fn cannot_publish_the_same_deal_twice_before_a_cron_tick() {
     let buf = RawBytes::serialize(d2.clone()).expect("failed to marshal deal proposal");
     let sig = Signature::new_bls(buf.to_vec());
     let params = PublishStorageDealsParams {
-        deals: vec![ClientDealProposal { proposal: d2.clone(), client_signature: sig }],
+        deals: vec![ClientDealProposal {
+            proposal: d2.clone(),
+            client_signature: sig,
+            auth_scheme: ProposalAuthScheme::Cbor,
+        }],
     };
     rt.expect_validate_caller_any();
     expect_provider_is_control_address(&rt, PROVIDER_ADDR, WORKER_ADDR, true);
@@ -1713,13 +1733,11 @@ fn locked_fund_tracking_states() {
     // make payment for p1 and p2, p3 times out as it has not been activated
     let curr = rt.set_epoch(curr + 100);
     let last_payment_epoch = curr;
-    rt.expect_send_simple(
-        BURNT_FUNDS_ACTOR_ADDR,
-        METHOD_SEND,
-        None,
-        d3.provider_collateral.clone(),
-        None,
-        ExitCode::OK,
+    expect_slash(
+        &rt,
+        &d3.provider,
+        PenaltyCategory::DealActivationTimeout,
+        &d3.provider_collateral,
     );
     settle_deal_payments(&rt, OWNER_ADDR, &[deal_id1, deal_id2, deal_id3], &[], &[]);
     let duration = curr - start_epoch;
@@ -1899,8 +1917,16 @@ fn insufficient_client_balance_in_a_batch() {
     let sig2 = Signature::new_bls(buf2.to_vec());
     let params = PublishStorageDealsParams {
         deals: vec![
-            ClientDealProposal { proposal: deal1.clone(), client_signature: sig1 },
-            ClientDealProposal { proposal: deal2.clone(), client_signature: sig2 },
+            ClientDealProposal {
+                proposal: deal1.clone(),
+                client_signature: sig1,
+                auth_scheme: ProposalAuthScheme::Cbor,
+            },
+            ClientDealProposal {
+                proposal: deal2.clone(),
+                client_signature: sig2,
+                auth_scheme: ProposalAuthScheme::Cbor,
+            },
         ],
     };
 
@@ -2047,8 +2073,16 @@ fn insufficient_provider_balance_in_a_batch() {
 
     let params = PublishStorageDealsParams {
         deals: vec![
-            ClientDealProposal { proposal: deal1.clone(), client_signature: sig1 },
-            ClientDealProposal { proposal: deal2.clone(), client_signature: sig2 },
+            ClientDealProposal {
+                proposal: deal1.clone(),
+                client_signature: sig1,
+                auth_scheme: ProposalAuthScheme::Cbor,
+            },
+            ClientDealProposal {
+                proposal: deal2.clone(),
+                client_signature: sig2,
+                auth_scheme: ProposalAuthScheme::Cbor,
+            },
         ],
     };
 
@@ -2202,7 +2236,11 @@ fn psd_restricted_correctly() {
     let sig = Signature::new_bls(buf.to_vec());
 
     let params = PublishStorageDealsParams {
-        deals: vec![ClientDealProposal { proposal: deal.clone(), client_signature: sig }],
+        deals: vec![ClientDealProposal {
+            proposal: deal.clone(),
+            client_signature: sig,
+            auth_scheme: ProposalAuthScheme::Cbor,
+        }],
     };
 
     // set caller to not-builtin
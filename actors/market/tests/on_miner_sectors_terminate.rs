@@ -3,12 +3,17 @@
 
 use fvm_ipld_bitfield::BitField;
 
-use fil_actor_market::{Actor as MarketActor, Method, OnMinerSectorsTerminateParams};
+use fil_actor_market::{
+    Actor as MarketActor, DealProposal, Method, OnMinerSectorsTerminateParams,
+    PendingSectorTerminations,
+};
 use fil_actors_runtime::network::EPOCHS_IN_DAY;
 use fil_actors_runtime::runtime::builtins::Type;
 use fil_actors_runtime::test_utils::*;
+use fil_actors_runtime::PenaltyCategory;
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_shared::address::Address;
+use fvm_shared::clock::EPOCH_UNDEFINED;
 use fvm_shared::deal::DealID;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
@@ -351,3 +356,80 @@ fn fail_when_caller_is_not_a_storage_miner_actor() {
 
     check_state(&rt);
 }
+
+#[test]
+fn terminations_beyond_batch_are_deferred_to_cron() {
+    let start_epoch = 10;
+    let end_epoch = start_epoch + 200 * EPOCHS_IN_DAY;
+    let sector_expiry = end_epoch + 100;
+    let current_epoch = 5;
+    let sectors = [7, 8, 9];
+
+    let mut rt = setup();
+    rt.policy.max_deal_terminations_per_batch = 1;
+    rt.set_epoch(current_epoch);
+
+    // One deal in each sector.
+    let addrs = MinerAddresses::default();
+    let deals: Vec<(DealID, DealProposal)> = (0..3)
+        .map(|i| generate_and_publish_deal(&rt, CLIENT_ADDR, &addrs, start_epoch, end_epoch + i))
+        .collect();
+    let ids: Vec<DealID> = deals.iter().map(|(id, _)| *id).collect();
+    for (sector, id) in sectors.iter().zip(&ids) {
+        activate_deals_legacy(&rt, sector_expiry, PROVIDER_ADDR, current_epoch, *sector, &[*id]);
+    }
+
+    // Only the first sector's deal is terminated immediately.
+    terminate_deals_raw(&rt, PROVIDER_ADDR, &sectors, &[ids[0]], &deals[0].1.provider_collateral)
+        .unwrap();
+    rt.verify();
+    assert_deal_deleted(&rt, ids[0], &deals[0].1, sectors[0]);
+
+    // The remaining sectors are queued, with their deals left untouched.
+    assert_eq!(
+        vec![(
+            PROVIDER_ID,
+            vec![PendingSectorTerminations {
+                epoch: current_epoch,
+                sectors: BitField::try_from_bits(sectors[1..].iter().copied()).unwrap(),
+            }]
+        )],
+        get_pending_deal_terminations(&rt)
+    );
+    for (sector, id) in sectors[1..].iter().zip(&ids[1..]) {
+        assert_eq!(vec![*id], get_sector_deal_ids(&rt, PROVIDER_ID, &[*sector]));
+        assert_eq!(EPOCH_UNDEFINED, get_deal_state(&rt, *id).slash_epoch);
+    }
+    check_state(&rt);
+
+    // Deals awaiting termination can't be settled, even once started.
+    rt.set_epoch(start_epoch + 1);
+    let ret = settle_deal_payments(&rt, PROVIDER_ADDR, &ids[1..2], &[], &[]);
+    assert_eq!(vec![ExitCode::USR_FORBIDDEN], ret.results.codes());
+
+    // Cron terminates one sector's deals each tick, at the epoch the sector was terminated.
+    for (i, (sector, (id, deal))) in sectors[1..].iter().zip(&deals[1..]).enumerate() {
+        rt.set_epoch(start_epoch + 2 + i as i64);
+        expect_emitted(
+            &rt,
+            "deal-terminated",
+            *id,
+            deal.client.id().unwrap(),
+            deal.provider.id().unwrap(),
+        );
+        expect_slash(
+            &rt,
+            &PROVIDER_ADDR,
+            PenaltyCategory::DealTermination,
+            &deal.provider_collateral,
+        );
+        cron_tick(&rt);
+        assert_deal_deleted(&rt, *id, deal, *sector);
+        check_state(&rt);
+    }
+    assert!(get_pending_deal_terminations(&rt).is_empty());
+
+    // The client paid nothing for storage, as the deals were terminated before they started.
+    let client = get_balance(&rt, &CLIENT_ADDR);
+    assert!(client.locked.is_zero());
+}
@@ -4,10 +4,11 @@
 use fil_actor_market::policy::deal_provider_collateral_bounds;
 use fil_actor_market::{
     ext, Actor as MarketActor, ClientDealProposal, DealProposal, MarketNotifyDealParams, Method,
-    PublishStorageDealsParams, PublishStorageDealsReturn, State, MARKET_NOTIFY_DEAL_METHOD,
+    ProposalAuthScheme, PublishStorageDealsParams, PublishStorageDealsReturn, State,
+    MARKET_NOTIFY_DEAL_METHOD,
 };
 use fil_actors_runtime::network::EPOCHS_IN_DAY;
-use fil_actors_runtime::runtime::Policy;
+use fil_actors_runtime::runtime::{Policy, SignatureVerifyInfo};
 use fil_actors_runtime::test_utils::*;
 use fvm_ipld_encoding::RawBytes;
 use fvm_shared::address::Address;
@@ -166,6 +167,14 @@ fn signature_is_invalid() {
     assert_deal_failure(true, f, ExitCode::USR_ILLEGAL_ARGUMENT, false);
 }
 
+#[test]
+fn signature_is_invalid_for_key_address() {
+    let f = |_rt: &MockRuntime, d: &mut DealProposal| {
+        d.client = new_bls_addr(1);
+    };
+    assert_deal_failure(true, f, ExitCode::USR_ILLEGAL_ARGUMENT, false);
+}
+
 #[test]
 fn no_entry_for_client_in_locked_balance_table() {
     let f = |rt: &MockRuntime, d: &mut DealProposal| {
@@ -251,7 +260,11 @@ fn fail_when_provider_has_some_funds_but_not_enough_for_a_deal() {
     let buf = RawBytes::serialize(deal1.clone()).expect("failed to marshal deal proposal");
     let sig = Signature::new_bls(buf.to_vec());
     let params = PublishStorageDealsParams {
-        deals: vec![ClientDealProposal { proposal: deal1.clone(), client_signature: sig }],
+        deals: vec![ClientDealProposal {
+            proposal: deal1.clone(),
+            client_signature: sig,
+            auth_scheme: ProposalAuthScheme::Cbor,
+        }],
     };
 
     rt.expect_validate_caller_any();
@@ -315,8 +328,16 @@ fn fail_when_deals_have_different_providers() {
     let sig2 = Signature::new_bls(buf2.to_vec());
     let params = PublishStorageDealsParams {
         deals: vec![
-            ClientDealProposal { proposal: deal1.clone(), client_signature: sig1 },
-            ClientDealProposal { proposal: deal2.clone(), client_signature: sig2 },
+            ClientDealProposal {
+                proposal: deal1.clone(),
+                client_signature: sig1,
+                auth_scheme: ProposalAuthScheme::Cbor,
+            },
+            ClientDealProposal {
+                proposal: deal2.clone(),
+                client_signature: sig2,
+                auth_scheme: ProposalAuthScheme::Cbor,
+            },
         ],
     };
 
@@ -399,6 +420,111 @@ fn fail_when_deals_have_different_providers() {
     check_state(&rt);
 }
 
+#[test]
+fn key_address_signatures_are_verified_in_batch() {
+    let start_epoch = 10;
+    let end_epoch = start_epoch + 200 * EPOCHS_IN_DAY;
+
+    let rt = setup();
+    let st: State = rt.get_state();
+    let next_deal_id = st.next_id;
+
+    let deal1 = generate_deal_proposal(new_bls_addr(1), PROVIDER_ADDR, start_epoch, end_epoch);
+    let deal2 = generate_deal_and_add_funds(
+        &rt,
+        CLIENT_ADDR,
+        &MinerAddresses::default(),
+        start_epoch,
+        end_epoch,
+    );
+
+    let buf1 = RawBytes::serialize(deal1.clone()).expect("failed to marshal deal proposal");
+    let buf2 = RawBytes::serialize(deal2.clone()).expect("failed to marshal deal proposal");
+    let sig1 = Signature::new_bls(buf1.to_vec());
+    let sig2 = Signature::new_bls(buf2.to_vec());
+    let params = PublishStorageDealsParams {
+        deals: vec![
+            ClientDealProposal {
+                proposal: deal1.clone(),
+                client_signature: sig1.clone(),
+                auth_scheme: ProposalAuthScheme::Cbor,
+            },
+            ClientDealProposal {
+                proposal: deal2.clone(),
+                client_signature: sig2,
+                auth_scheme: ProposalAuthScheme::Cbor,
+            },
+        ],
+    };
+
+    rt.expect_validate_caller_any();
+    expect_provider_is_control_address(&rt, PROVIDER_ADDR, WORKER_ADDR, true);
+    expect_query_network_info(&rt);
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, WORKER_ADDR);
+
+    // The key address client's signature is verified directly, and found invalid.
+    rt.expect_verify_signatures_batch(
+        vec![SignatureVerifyInfo {
+            signature: sig1,
+            signer: deal1.client,
+            plaintext: buf1.to_vec(),
+        }],
+        vec![false],
+    );
+    // The ID address client is asked to authenticate its proposal.
+    rt.expect_send(
+        deal2.client,
+        AUTHENTICATE_MESSAGE_METHOD,
+        IpldBlock::serialize_cbor(&AuthenticateMessageParams {
+            signature: buf2.to_vec(),
+            message: buf2.to_vec(),
+        })
+        .unwrap(),
+        TokenAmount::zero(),
+        None,
+        SendFlags::READ_ONLY,
+        AUTHENTICATE_MESSAGE_RESPONSE.clone(),
+        ExitCode::OK,
+        None,
+    );
+
+    rt.expect_send_simple(
+        deal2.client,
+        MARKET_NOTIFY_DEAL_METHOD,
+        IpldBlock::serialize_cbor(&MarketNotifyDealParams {
+            proposal: buf2.to_vec(),
+            deal_id: next_deal_id,
+        })
+        .unwrap(),
+        TokenAmount::zero(),
+        None,
+        ExitCode::OK,
+    );
+    expect_emitted(
+        &rt,
+        "deal-published",
+        next_deal_id,
+        deal2.client.id().unwrap(),
+        deal2.provider.id().unwrap(),
+    );
+
+    let psd_ret: PublishStorageDealsReturn = rt
+        .call::<MarketActor>(
+            Method::PublishStorageDeals as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        )
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+
+    let valid: Vec<u64> = psd_ret.valid_deals.bounded_iter(std::u64::MAX).unwrap().collect();
+    assert_eq!(vec![1], valid);
+
+    rt.verify();
+    check_state(&rt);
+}
+
 #[test]
 fn fail_when_no_deals_in_params() {
     let rt = setup();
@@ -426,7 +552,11 @@ fn fail_to_resolve_provider_address() {
 
     let sig = Signature::new_bls("does not matter".as_bytes().to_vec());
     let params = PublishStorageDealsParams {
-        deals: vec![ClientDealProposal { proposal: deal, client_signature: sig }],
+        deals: vec![ClientDealProposal {
+            proposal: deal,
+            client_signature: sig,
+            auth_scheme: ProposalAuthScheme::Cbor,
+        }],
     };
     rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, WORKER_ADDR);
     rt.expect_validate_caller_any();
@@ -449,7 +579,11 @@ fn caller_is_not_the_same_as_the_worker_address_for_miner() {
     let deal = generate_deal_proposal(CLIENT_ADDR, PROVIDER_ADDR, start_epoch, end_epoch);
     let sig = Signature::new_bls("does not matter".as_bytes().to_vec());
     let params = PublishStorageDealsParams {
-        deals: vec![ClientDealProposal { proposal: deal, client_signature: sig }],
+        deals: vec![ClientDealProposal {
+            proposal: deal,
+            client_signature: sig,
+            auth_scheme: ProposalAuthScheme::Cbor,
+        }],
     };
 
     rt.expect_validate_caller_any();
@@ -478,7 +612,11 @@ fn fails_if_provider_is_not_a_storage_miner_actor() {
 
     let sig = Signature::new_bls("does not matter".as_bytes().to_vec());
     let params = PublishStorageDealsParams {
-        deals: vec![ClientDealProposal { proposal: deal, client_signature: sig }],
+        deals: vec![ClientDealProposal {
+            proposal: deal,
+            client_signature: sig,
+            auth_scheme: ProposalAuthScheme::Cbor,
+        }],
     };
 
     rt.expect_validate_caller_any();
@@ -510,7 +648,11 @@ fn fails_if_notify_deal_fails() {
     let buf = RawBytes::serialize(deal.clone()).expect("failed to marshal deal proposal");
     let sig = Signature::new_bls("does not matter".as_bytes().to_vec());
     let params = PublishStorageDealsParams {
-        deals: vec![ClientDealProposal { proposal: deal, client_signature: sig.clone() }],
+        deals: vec![ClientDealProposal {
+            proposal: deal,
+            client_signature: sig.clone(),
+            auth_scheme: ProposalAuthScheme::Cbor,
+        }],
     };
 
     rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, WORKER_ADDR);
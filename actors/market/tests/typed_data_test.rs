@@ -0,0 +1,166 @@
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_ipld_encoding::RawBytes;
+use fvm_shared::address::Address;
+use fvm_shared::chainid::ChainID;
+use fvm_shared::crypto::signature::Signature;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
+use fvm_shared::piece::PaddedPieceSize;
+use fvm_shared::sys::SendFlags;
+use hex_literal::hex;
+use num_traits::Zero;
+
+use fil_actor_market::ext::account::{AuthenticateMessageParams, AUTHENTICATE_MESSAGE_METHOD};
+use fil_actor_market::{
+    deal_proposal_typed_data_hash, Actor as MarketActor, ClientDealProposal, DealProposal, Label,
+    MarketNotifyDealParams, Method, ProposalAuthScheme, PublishStorageDealsParams,
+    PublishStorageDealsReturn, State, MARKET_NOTIFY_DEAL_METHOD,
+};
+use fil_actors_runtime::network::EPOCHS_IN_DAY;
+use fil_actors_runtime::test_utils::*;
+use harness::*;
+
+mod harness;
+
+#[test]
+fn typed_data_hash() {
+    let rt = setup();
+    let proposal = DealProposal {
+        piece_cid: make_piece_cid(b"1"),
+        piece_size: PaddedPieceSize(2048),
+        verified_deal: true,
+        client: Address::new_id(103),
+        provider: Address::new_id(101),
+        label: Label::String("label".into()),
+        start_epoch: 100,
+        // Negative, to check sign extension.
+        end_epoch: -1,
+        storage_price_per_epoch: TokenAmount::from_atto(10),
+        provider_collateral: TokenAmount::from_whole(1),
+        client_collateral: TokenAmount::zero(),
+    };
+
+    let hash = deal_proposal_typed_data_hash(&rt, ChainID::from(314), &proposal).unwrap();
+    assert_eq!(hex!("cede843ed022042a2fcf341f88cd05b883bd898d98f32e77de1e299c76696ab9"), hash);
+
+    // The hash commits to the chain, and to the kind of label.
+    assert_ne!(hash, deal_proposal_typed_data_hash(&rt, ChainID::from(1), &proposal).unwrap());
+    let bytes_label = DealProposal { label: Label::Bytes(b"label".to_vec()), ..proposal.clone() };
+    assert_ne!(hash, deal_proposal_typed_data_hash(&rt, ChainID::from(314), &bytes_label).unwrap());
+
+    // Token amounts must be representable as a uint256.
+    let negative_price =
+        DealProposal { storage_price_per_epoch: TokenAmount::from_atto(-1), ..proposal };
+    assert_eq!(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        deal_proposal_typed_data_hash(&rt, ChainID::from(314), &negative_price)
+            .unwrap_err()
+            .exit_code()
+    );
+}
+
+#[test]
+fn client_deal_proposal_encoding() {
+    let proposal = generate_deal_proposal(CLIENT_ADDR, PROVIDER_ADDR, 10, 10 + 200 * EPOCHS_IN_DAY);
+    let signature = Signature::new_bls(b"signature".to_vec());
+
+    // Proposals authenticated by their CBOR encoding are encoded as before.
+    let cbor = ClientDealProposal {
+        proposal: proposal.clone(),
+        client_signature: signature.clone(),
+        auth_scheme: ProposalAuthScheme::Cbor,
+    };
+    let encoded = RawBytes::serialize(&cbor).unwrap();
+    assert_eq!(RawBytes::serialize((&proposal, &signature)).unwrap(), encoded);
+    assert_eq!(cbor, encoded.deserialize().unwrap());
+
+    // Other schemes are appended to the tuple.
+    let typed_data = ClientDealProposal { auth_scheme: ProposalAuthScheme::TypedData, ..cbor };
+    let encoded = RawBytes::serialize(&typed_data).unwrap();
+    assert_eq!(RawBytes::serialize((&proposal, &signature, 1u8)).unwrap(), encoded);
+    assert_eq!(typed_data, encoded.deserialize().unwrap());
+}
+
+#[test]
+fn publish_typed_data_deal() {
+    let start_epoch = 10;
+    let end_epoch = start_epoch + 200 * EPOCHS_IN_DAY;
+
+    let rt = setup();
+    let st: State = rt.get_state();
+    let next_deal_id = st.next_id;
+
+    let deal = generate_deal_and_add_funds(
+        &rt,
+        CLIENT_ADDR,
+        &MinerAddresses::default(),
+        start_epoch,
+        end_epoch,
+    );
+    let signature = b"signature".to_vec();
+    let params = PublishStorageDealsParams {
+        deals: vec![ClientDealProposal {
+            proposal: deal.clone(),
+            client_signature: Signature::new_bls(signature.clone()),
+            auth_scheme: ProposalAuthScheme::TypedData,
+        }],
+    };
+
+    rt.expect_validate_caller_any();
+    expect_provider_is_control_address(&rt, PROVIDER_ADDR, WORKER_ADDR, true);
+    expect_query_network_info(&rt);
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, WORKER_ADDR);
+
+    // The client is asked to authenticate the proposal's typed-data hash.
+    let typed_data_hash = deal_proposal_typed_data_hash(&rt, rt.chain_id, &deal).unwrap();
+    rt.expect_send(
+        deal.client,
+        AUTHENTICATE_MESSAGE_METHOD,
+        IpldBlock::serialize_cbor(&AuthenticateMessageParams {
+            signature,
+            message: typed_data_hash.to_vec(),
+        })
+        .unwrap(),
+        TokenAmount::zero(),
+        None,
+        SendFlags::READ_ONLY,
+        AUTHENTICATE_MESSAGE_RESPONSE.clone(),
+        ExitCode::OK,
+        None,
+    );
+
+    // The client is notified of the deal with its CBOR encoding, as usual.
+    rt.expect_send_simple(
+        deal.client,
+        MARKET_NOTIFY_DEAL_METHOD,
+        IpldBlock::serialize_cbor(&MarketNotifyDealParams {
+            proposal: RawBytes::serialize(&deal).unwrap().to_vec(),
+            deal_id: next_deal_id,
+        })
+        .unwrap(),
+        TokenAmount::zero(),
+        None,
+        ExitCode::OK,
+    );
+    expect_emitted(
+        &rt,
+        "deal-published",
+        next_deal_id,
+        deal.client.id().unwrap(),
+        deal.provider.id().unwrap(),
+    );
+
+    let ret: PublishStorageDealsReturn = rt
+        .call::<MarketActor>(
+            Method::PublishStorageDeals as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        )
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    assert_eq!(vec![next_deal_id], ret.ids);
+
+    rt.verify();
+    check_state(&rt);
+}
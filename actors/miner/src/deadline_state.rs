@@ -564,6 +564,8 @@ impl Deadline {
         Ok((popped, modified))
     }
 
+    /// Terminates sectors in the deadline's partitions, returning the active power lost and the
+    /// sectors terminated.
     #[allow(clippy::too_many_arguments)]
     pub fn terminate_sectors<BS: Blockstore>(
         &mut self,
@@ -574,10 +576,11 @@ impl Deadline {
         partition_sectors: &mut PartitionSectorMap,
         sector_size: SectorSize,
         quant: QuantSpec,
-    ) -> anyhow::Result<PowerPair> {
+    ) -> anyhow::Result<(PowerPair, BitField)> {
         let mut partitions = self.partitions_amt(store)?;
 
         let mut power_lost = PowerPair::zero();
+        let mut terminated = BitField::new();
         for (partition_idx, sector_numbers) in partition_sectors.iter() {
             let mut partition = partitions
                 .get(partition_idx)
@@ -622,13 +625,15 @@ impl Deadline {
 
             // Aggregate power lost from active sectors
             power_lost += &removed.active_power;
+            terminated |= &removed.on_time_sectors;
+            terminated |= &removed.early_sectors;
         }
 
         // save partitions back
         self.partitions =
             partitions.flush().map_err(|e| e.downcast_wrap("failed to persist partitions"))?;
 
-        Ok(power_lost)
+        Ok((power_lost, terminated))
     }
 
     /// RemovePartitions removes the specified partitions, shifting the remaining
@@ -1122,6 +1127,9 @@ impl Deadline {
     /// changes to power (newly faulty power, power that should have been proven
     /// recovered but wasn't, and newly recovered power).
     ///
+    /// If `auto_recover_faults` is set, faulty sectors in the proven partitions that are
+    /// not skipped are first declared recovered, and so are recovered by the PoSt.
+    ///
     /// NOTE: This function does not actually _verify_ any proofs. The returned
     /// `sectors` and `ignored_sectors` must subsequently be validated against the PoSt
     /// submitted by the miner.
@@ -1133,6 +1141,7 @@ impl Deadline {
         quant: QuantSpec,
         fault_expiration: ChainEpoch,
         post_partitions: &mut [PoStPartition],
+        auto_recover_faults: bool,
     ) -> anyhow::Result<PoStResult> {
         let partition_indexes = BitField::try_from_bits(post_partitions.iter().map(|p| p.index))
             .map_err(|_| actor_error!(illegal_argument; "partition index out of bitfield range"))?;
@@ -1196,6 +1205,21 @@ impl Deadline {
                 rescheduled_partitions.push(post.index);
             }
 
+            // Declare the remaining faults recovered, so they're recovered below along with any
+            // previously declared recoveries. This follows processing of skipped faults, so that
+            // sectors skipped in this proof are never recovered by it.
+            if auto_recover_faults {
+                let unskipped_faults = &partition.faults - &post.skipped;
+                partition
+                    .declare_faults_recovered(sectors, sector_size, &unskipped_faults)
+                    .map_err(|e| {
+                        e.downcast_wrap(format!(
+                            "failed to declare faults recovered for partition {}",
+                            post.index
+                        ))
+                    })?;
+            }
+
             let recovered_power =
                 partition.recover_faults(store, sectors, sector_size, quant).map_err(|e| {
                     e.downcast_wrap(format!(
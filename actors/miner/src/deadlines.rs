@@ -127,6 +127,34 @@ pub fn deadline_available_for_compaction(
         )
 }
 
+// Returns true if sectors may be moved from one deadline to another in the current epoch.
+// Sectors may only be moved when:
+//
+// 1. The origin deadline is available for compaction.
+// 2. The destination deadline is mutable.
+// 3. The destination deadline's next challenge window opens no later than the origin's, so that
+//    moved sectors are proven no later than they would have been.
+pub fn deadline_available_for_move(
+    policy: &Policy,
+    proving_period_start: ChainEpoch,
+    orig_deadline_idx: u64,
+    dest_deadline_idx: u64,
+    current_epoch: ChainEpoch,
+) -> bool {
+    let next_open = |deadline_idx| {
+        new_deadline_info(policy, proving_period_start, deadline_idx, current_epoch)
+            .next_not_elapsed()
+            .open
+    };
+    deadline_available_for_compaction(
+        policy,
+        proving_period_start,
+        orig_deadline_idx,
+        current_epoch,
+    ) && deadline_is_mutable(policy, proving_period_start, dest_deadline_idx, current_epoch)
+        && next_open(dest_deadline_idx) <= next_open(orig_deadline_idx)
+}
+
 // Determine current period start and deadline index directly from current epoch and
 // the offset implied by the proving period. This works correctly even for the state
 // of a miner actor without an active deadline cron
@@ -1,6 +1,9 @@
 use cid::Cid;
 use fil_actors_runtime::runtime::Runtime;
-use fil_actors_runtime::{ActorError, EventBuilder};
+use fil_actors_runtime::{emit_penalty_assessed, ActorError, EventBuilder, PenaltyCategory};
+use fvm_ipld_bitfield::BitField;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
 use fvm_shared::sector::SectorNumber;
 
 /// Indicates a sector has been pre-committed.
@@ -47,6 +50,56 @@ pub fn sector_terminated(rt: &impl Runtime, sector: SectorNumber) -> Result<(),
     )
 }
 
+/// Indicates a committed capacity sector has been terminated at an age for which its
+/// termination fee is pro-rated.
+pub fn cc_sector_fast_removed(
+    rt: &impl Runtime,
+    sector: SectorNumber,
+    age: ChainEpoch,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("cc-sector-fast-removed")
+            .field_indexed("sector", &sector)
+            .field("age", &age)
+            .build()?,
+    )
+}
+
+/// Indicates sectors have been terminated with their data handed off as described by a manifest.
+pub fn termination_handoff(
+    rt: &impl Runtime,
+    manifest: &Cid,
+    sectors: &BitField,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("termination-handoff")
+            .field_indexed("manifest", manifest)
+            .field("sectors", sectors)
+            .build()?,
+    )
+}
+
+/// Indicates a penalty has been applied to this miner, of which `burned` has been burnt and
+/// `withheld` added to fee debt. Any remainder was paid to a reporter.
+pub fn penalty_assessed(
+    rt: &impl Runtime,
+    category: PenaltyCategory,
+    amount: &TokenAmount,
+    burned: &TokenAmount,
+    withheld: &TokenAmount,
+) -> Result<(), ActorError> {
+    emit_penalty_assessed(
+        rt,
+        rt.message().receiver().id().unwrap(),
+        category,
+        amount,
+        burned,
+        withheld,
+    )
+}
+
 trait WithSectorInfo {
     fn with_sector_info(
         self,
@@ -21,12 +21,19 @@ pub mod market {
     use super::*;
     use fvm_ipld_bitfield::BitField;
 
+    pub const PUBLISH_STORAGE_DEALS_METHOD: u64 = 4;
     pub const VERIFY_DEALS_FOR_ACTIVATION_METHOD: u64 = 5;
     pub const BATCH_ACTIVATE_DEALS_METHOD: u64 = 6;
     pub const ON_MINER_SECTORS_TERMINATE_METHOD: u64 = 7;
 
     pub const NO_ALLOCATION_ID: u64 = 0;
 
+    #[derive(Serialize_tuple, Deserialize_tuple)]
+    pub struct PublishStorageDealsReturn {
+        pub ids: Vec<DealID>,
+        pub valid_deals: BitField,
+    }
+
     #[derive(Serialize_tuple, Deserialize_tuple)]
     pub struct SectorDeals {
         pub sector_number: SectorNumber,
@@ -1,6 +1,16 @@
 // Copyright 2019-2022 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+//! The storage miner actor and its state.
+//!
+//! With the default `actor` feature disabled, this crate exposes only the state types and the
+//! pure accessors over them (sector lookup, deadline and proving period math, pledge and penalty
+//! math). That build leaves out the actor's method dispatch along with the dependencies only it
+//! needs, so light clients and browser tooling can read miner state without linking the actor.
+
+// Imports only the actor uses are left unused without its feature.
+#![cfg_attr(not(feature = "actor"), allow(unused_imports))]
+
 use std::cmp;
 use std::cmp::max;
 use std::collections::btree_map::Entry;
@@ -8,13 +18,14 @@ use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::ops::Neg;
 
 use anyhow::{anyhow, Error};
+#[cfg(feature = "actor")]
 use byteorder::{BigEndian, ByteOrder, WriteBytesExt};
 use cid::multihash::Code::Blake2b256;
 use cid::Cid;
 use fvm_ipld_bitfield::{BitField, Validate};
 use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::ipld_block::IpldBlock;
-use fvm_ipld_encoding::{from_slice, BytesDe, CborStore, RawBytes};
+use fvm_ipld_encoding::{from_slice, BytesDe, CborStore, RawBytes, DAG_CBOR};
 use fvm_shared::address::{Address, Payload, Protocol};
 use fvm_shared::bigint::{BigInt, Integer};
 use fvm_shared::clock::ChainEpoch;
@@ -31,9 +42,12 @@ use fvm_shared::sector::{
 };
 use fvm_shared::{ActorID, MethodNum, METHOD_CONSTRUCTOR, METHOD_SEND};
 use itertools::Itertools;
+#[cfg(feature = "actor")]
 use log::{error, info, warn};
+#[cfg(feature = "actor")]
 use num_derive::FromPrimitive;
 use num_traits::{Signed, Zero};
+use serde::de::IgnoredAny;
 
 pub use beneficiary::*;
 pub use bitfield_queue::*;
@@ -45,14 +59,14 @@ pub use deadlines::*;
 pub use expiration_queue::*;
 use fil_actors_runtime::cbor::{serialize, serialize_vec};
 use fil_actors_runtime::reward::{FilterEstimate, ThisEpochRewardReturn};
-use fil_actors_runtime::runtime::builtins::Type;
 use fil_actors_runtime::runtime::policy_constants::MAX_SECTOR_NUMBER;
 use fil_actors_runtime::runtime::{ActorCode, DomainSeparationTag, Policy, Runtime};
 use fil_actors_runtime::{
     actor_dispatch, actor_error, deserialize_block, extract_send_result, util, ActorContext,
     ActorDowncast, ActorError, AsActorError, BatchReturn, BatchReturnGen, DealWeight,
-    BURNT_FUNDS_ACTOR_ADDR, INIT_ACTOR_ADDR, REWARD_ACTOR_ADDR, STORAGE_MARKET_ACTOR_ADDR,
-    STORAGE_POWER_ACTOR_ADDR, SYSTEM_ACTOR_ADDR, VERIFIED_REGISTRY_ACTOR_ADDR,
+    PenaltyCategory, BURNT_FUNDS_ACTOR_ADDR, INIT_ACTOR_ADDR, REWARD_ACTOR_ADDR,
+    STORAGE_MARKET_ACTOR_ADDR, STORAGE_POWER_ACTOR_ADDR, SYSTEM_ACTOR_ADDR,
+    VERIFIED_REGISTRY_ACTOR_ADDR,
 };
 pub use monies::*;
 pub use partition_state::*;
@@ -66,6 +80,7 @@ pub use types::*;
 pub use vesting_state::*;
 
 use crate::ext::market::NO_ALLOCATION_ID;
+#[cfg(feature = "actor")]
 use crate::notifications::{notify_data_consumers, ActivationNotifications};
 
 // The following errors are particular cases of illegal state.
@@ -82,11 +97,13 @@ mod deadline_assignment;
 mod deadline_info;
 mod deadline_state;
 mod deadlines;
+#[cfg(feature = "actor")]
 mod emit;
 mod expiration_queue;
 #[doc(hidden)]
 pub mod ext;
 mod monies;
+#[cfg(feature = "actor")]
 mod notifications;
 mod partition_state;
 mod policy;
@@ -100,6 +117,7 @@ mod types;
 mod vesting_state;
 
 /// Storage Miner actor methods available
+#[cfg(feature = "actor")]
 #[derive(FromPrimitive)]
 #[repr(u64)]
 pub enum Method {
@@ -138,6 +156,9 @@ pub enum Method {
     // MovePartitions = 33,
     ProveCommitSectors3 = 34,
     ProveReplicaUpdates3 = 35,
+    OnboardSectorWithDeals = 36,
+    CompactSectorsAcrossDeadlines = 37,
+    OnVerifiedClaimsExpired = 38,
     // Method numbers derived from FRC-0042 standards
     ChangeWorkerAddressExported = frc42_dispatch::method_hash!("ChangeWorkerAddress"),
     ChangePeerIDExported = frc42_dispatch::method_hash!("ChangePeerID"),
@@ -152,23 +173,41 @@ pub enum Method {
     IsControllingAddressExported = frc42_dispatch::method_hash!("IsControllingAddress"),
     GetSectorSizeExported = frc42_dispatch::method_hash!("GetSectorSize"),
     GetAvailableBalanceExported = frc42_dispatch::method_hash!("GetAvailableBalance"),
+    GetWithdrawableBalanceExported = frc42_dispatch::method_hash!("GetWithdrawableBalance"),
     GetVestingFundsExported = frc42_dispatch::method_hash!("GetVestingFunds"),
     GetPeerIDExported = frc42_dispatch::method_hash!("GetPeerID"),
     GetMultiaddrsExported = frc42_dispatch::method_hash!("GetMultiaddrs"),
+    GetMinerEssentialsExported = frc42_dispatch::method_hash!("GetMinerEssentials"),
+    EstimateTerminationFeeExported = frc42_dispatch::method_hash!("EstimateTerminationFee"),
+    PreviewSkippedFaultsExported = frc42_dispatch::method_hash!("PreviewSkippedFaults"),
+    ListSectorsExported = frc42_dispatch::method_hash!("ListSectors"),
+    CancelOwnerChangeExported = frc42_dispatch::method_hash!("CancelOwnerChange"),
+    GetPendingOwnerChangeExported = frc42_dispatch::method_hash!("GetPendingOwnerChange"),
+    AuthorizeProverExported = frc42_dispatch::method_hash!("AuthorizeProver"),
+    RevokeProverExported = frc42_dispatch::method_hash!("RevokeProver"),
+    GetProverAuthorizationsExported = frc42_dispatch::method_hash!("GetProverAuthorizations"),
 }
 
+#[cfg(feature = "actor")]
 pub const SECTOR_CONTENT_CHANGED: MethodNum = frc42_dispatch::method_hash!("SectorContentChanged");
 
+#[cfg(feature = "actor")]
 pub const ERR_BALANCE_INVARIANTS_BROKEN: ExitCode = ExitCode::new(1000);
+#[cfg(feature = "actor")]
 pub const ERR_NOTIFICATION_SEND_FAILED: ExitCode = ExitCode::new(1001);
+#[cfg(feature = "actor")]
 pub const ERR_NOTIFICATION_RECEIVER_ABORTED: ExitCode = ExitCode::new(1002);
+#[cfg(feature = "actor")]
 pub const ERR_NOTIFICATION_RESPONSE_INVALID: ExitCode = ExitCode::new(1003);
+#[cfg(feature = "actor")]
 pub const ERR_NOTIFICATION_REJECTED: ExitCode = ExitCode::new(1004);
 
 /// Miner Actor
 /// here in order to update the Power Actor to v3.
+#[cfg(feature = "actor")]
 pub struct Actor;
 
+#[cfg(feature = "actor")]
 impl Actor {
     pub fn constructor(
         rt: &impl Runtime,
@@ -260,7 +299,18 @@ impl Actor {
         rt.validate_immediate_caller_accept_any()?;
         let state: State = rt.state()?;
         let info = get_miner_info(rt.store(), &state)?;
-        Ok(GetOwnerReturn { owner: info.owner, proposed: info.pending_owner_address })
+        let proposed = info.pending_owner_change_at(rt.curr_epoch()).map(|c| c.new_owner);
+        Ok(GetOwnerReturn { owner: info.owner, proposed })
+    }
+
+    /// Returns the proposed owner change awaiting confirmation, unless it has expired.
+    fn get_pending_owner_change(
+        rt: &impl Runtime,
+    ) -> Result<GetPendingOwnerChangeReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let state: State = rt.state()?;
+        let info = get_miner_info(rt.store(), &state)?;
+        Ok(GetPendingOwnerChangeReturn { pending: info.pending_owner_change_at(rt.curr_epoch()) })
     }
 
     /// Returns whether the provided address is "controlling".
@@ -303,6 +353,47 @@ impl Actor {
         Ok(GetAvailableBalanceReturn { available_balance })
     }
 
+    /// Returns the amount WithdrawBalance would withdraw now if requested without limit,
+    /// with the balances that determine it.
+    /// Unlike GetAvailableBalance, this accounts for funds vested but not yet unlocked,
+    /// the beneficiary's remaining quota, and outstanding early termination fees.
+    fn get_withdrawable_balance(
+        rt: &impl Runtime,
+    ) -> Result<GetWithdrawableBalanceReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let state: State = rt.state()?;
+        let info = get_miner_info(rt.store(), &state)?;
+        let curr_epoch = rt.curr_epoch();
+
+        // WithdrawBalance unlocks vested funds before computing the available balance.
+        let newly_vested = state.check_vested_funds(rt.store(), curr_epoch).map_err(|e| {
+            e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to check vested funds")
+        })?;
+        let mut vested = state.clone();
+        vested.locked_funds -= &newly_vested;
+        let limits = WithdrawalLimits::compute(&vested, &info, &rt.current_balance(), curr_epoch)?;
+        let early_terminations_pending = !state.early_terminations.is_empty();
+
+        let max_withdrawal = limits.max_withdrawal();
+        let withdrawable = if early_terminations_pending || max_withdrawal.is_negative() {
+            TokenAmount::zero()
+        } else {
+            max_withdrawal
+        };
+
+        Ok(GetWithdrawableBalanceReturn {
+            withdrawable,
+            available_balance: limits.available_balance,
+            newly_vested,
+            locked_funds: vested.locked_funds,
+            pre_commit_deposits: state.pre_commit_deposits,
+            initial_pledge: state.initial_pledge,
+            fee_debt: state.fee_debt,
+            beneficiary_quota: limits.beneficiary_quota,
+            early_terminations_pending,
+        })
+    }
+
     /// Returns the funds vesting in this miner as a list of (vesting_epoch, vesting_amount) tuples.
     fn get_vesting_funds(rt: &impl Runtime) -> Result<GetVestingFundsReturn, ActorError> {
         rt.validate_immediate_caller_accept_any()?;
@@ -380,6 +471,7 @@ impl Actor {
     /// current owner address, revokes any existing proposal.
     /// If invoked by the previously proposed address, with the same proposal, changes the current owner address to be
     /// that proposed address.
+    /// A proposal expires if it isn't confirmed within the policy's owner change expiry.
     fn change_owner_address(
         rt: &impl Runtime,
         params: ChangeOwnerAddressParams,
@@ -395,47 +487,164 @@ impl Actor {
         rt.transaction(|state: &mut State, rt| {
             let mut info = get_miner_info(rt.store(), state)?;
 
-            if rt.message().caller() == info.owner || info.pending_owner_address.is_none() {
-                rt.validate_immediate_caller_is(std::iter::once(&info.owner))?;
-                info.pending_owner_address = Some(new_address);
-            } else {
-                let pending_address = info.pending_owner_address.unwrap();
-                rt.validate_immediate_caller_is(std::iter::once(&pending_address))?;
-                if new_address != pending_address {
-                    return Err(actor_error!(
-                        illegal_argument,
-                        "expected confirmation of {} got {}",
-                        pending_address,
-                        new_address
-                    ));
-                }
+            match info.pending_owner_change() {
+                Some(pending) if rt.message().caller() != info.owner => {
+                    rt.validate_immediate_caller_is(std::iter::once(&pending.new_owner))?;
+                    if new_address != pending.new_owner {
+                        return Err(actor_error!(
+                            illegal_argument,
+                            "expected confirmation of {} got {}",
+                            pending.new_owner,
+                            new_address
+                        ));
+                    }
+                    if rt.curr_epoch() >= pending.expiration {
+                        return Err(actor_error!(
+                            forbidden,
+                            "owner change to {} expired at epoch {}",
+                            pending.new_owner,
+                            pending.expiration
+                        ));
+                    }
 
-                // Change beneficiary address to new owner if current beneficiary address equal to old owner address
-                if info.beneficiary == info.owner {
-                    info.beneficiary = pending_address;
-                }
-                // Cancel pending beneficiary term change when the owner changes
-                info.pending_beneficiary_term = None;
+                    // Change beneficiary address to new owner if current beneficiary address equal to old owner address
+                    if info.beneficiary == info.owner {
+                        info.beneficiary = pending.new_owner;
+                    }
+                    // Cancel pending beneficiary term change when the owner changes
+                    info.pending_beneficiary_term = None;
 
-                // Set the new owner address
-                info.owner = pending_address;
+                    // Set the new owner address
+                    info.owner = pending.new_owner;
+                    info.set_pending_owner_change(None);
+                }
+                _ => {
+                    rt.validate_immediate_caller_is(std::iter::once(&info.owner))?;
+                    // Proposing the current owner withdraws any pending change.
+                    info.set_pending_owner_change(if new_address == info.owner {
+                        None
+                    } else {
+                        Some(PendingOwnerChange {
+                            new_owner: new_address,
+                            expiration: rt.curr_epoch() + rt.policy().owner_change_expiry,
+                        })
+                    });
+                }
             }
 
-            // Clear any no-op change
-            if let Some(p_addr) = info.pending_owner_address {
-                if p_addr == info.owner {
-                    info.pending_owner_address = None;
-                }
+            state.save_info(rt.store(), &info).map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to save miner info")
+            })?;
+
+            Ok(())
+        })
+    }
+
+    /// Withdraws a proposed owner change, at the request of the owner or the proposed new owner.
+    fn cancel_owner_change(rt: &impl Runtime) -> Result<(), ActorError> {
+        rt.transaction(|state: &mut State, rt| {
+            let mut info = get_miner_info(rt.store(), state)?;
+            // An expired change may still be withdrawn, clearing it from the miner info.
+            let nominee = info.pending_owner_address;
+            rt.validate_immediate_caller_is(std::iter::once(&info.owner).chain(nominee.as_ref()))?;
+            if nominee.is_none() {
+                return Err(actor_error!(not_found, "no pending owner change"));
             }
+            info.set_pending_owner_change(None);
 
             state.save_info(rt.store(), &info).map_err(|e| {
                 e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to save miner info")
             })?;
+            Ok(())
+        })
+    }
+
+    /// Authorizes a prover to submit Window PoSts for this miner from the start epoch until the
+    /// expiration epoch, replacing any existing authorization of the same prover.
+    /// The prover gains no other rights over the miner.
+    fn authorize_prover(
+        rt: &impl Runtime,
+        params: AuthorizeProverParams,
+    ) -> Result<(), ActorError> {
+        let curr_epoch = rt.curr_epoch();
+        if params.expiration <= params.start || params.expiration <= curr_epoch {
+            return Err(actor_error!(
+                illegal_argument,
+                "prover authorization from {} to {} must end after it starts and after epoch {}",
+                params.start,
+                params.expiration,
+                curr_epoch
+            ));
+        }
+        let prover = rt.resolve_address(&params.prover).map(Address::new_id).ok_or_else(|| {
+            actor_error!(illegal_argument, "unable to resolve prover address: {}", params.prover)
+        })?;
+
+        rt.transaction(|state: &mut State, rt| {
+            let mut info = get_miner_info(rt.store(), state)?;
+            rt.validate_immediate_caller_is(std::iter::once(&info.owner))?;
+
+            // Expired authorizations are dropped to make room.
+            info.prover_authorizations.retain(|a| a.prover != prover && a.expiration > curr_epoch);
+            if info.prover_authorizations.len() >= rt.policy().max_prover_authorizations {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "cannot authorize more than {} provers",
+                    rt.policy().max_prover_authorizations
+                ));
+            }
+            info.prover_authorizations.push(ProverAuthorization {
+                prover,
+                start: params.start,
+                expiration: params.expiration,
+            });
 
+            state.save_info(rt.store(), &info).map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "could not save miner info")
+            })?;
+            Ok(())
+        })
+    }
+
+    /// Revokes a prover's authorization to submit Window PoSts for this miner.
+    fn revoke_prover(rt: &impl Runtime, params: RevokeProverParams) -> Result<(), ActorError> {
+        let prover = rt.resolve_address(&params.prover).map(Address::new_id);
+
+        rt.transaction(|state: &mut State, rt| {
+            let mut info = get_miner_info(rt.store(), state)?;
+            rt.validate_immediate_caller_is(std::iter::once(&info.owner))?;
+
+            let before = info.prover_authorizations.len();
+            info.prover_authorizations.retain(|a| Some(a.prover) != prover);
+            if info.prover_authorizations.len() == before {
+                return Err(actor_error!(not_found, "prover {} is not authorized", params.prover));
+            }
+
+            state.save_info(rt.store(), &info).map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "could not save miner info")
+            })?;
             Ok(())
         })
     }
 
+    /// Returns the provers authorized to submit Window PoSts for this miner, omitting expired
+    /// authorizations.
+    fn get_prover_authorizations(
+        rt: &impl Runtime,
+    ) -> Result<GetProverAuthorizationsReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let state: State = rt.state()?;
+        let info = get_miner_info(rt.store(), &state)?;
+        let curr_epoch = rt.curr_epoch();
+        Ok(GetProverAuthorizationsReturn {
+            provers: info
+                .prover_authorizations
+                .into_iter()
+                .filter(|a| a.expiration > curr_epoch)
+                .collect(),
+        })
+    }
+
     /// Returns the Peer ID for this miner.
     fn get_peer_id(rt: &impl Runtime) -> Result<GetPeerIDReturn, ActorError> {
         rt.validate_immediate_caller_accept_any()?;
@@ -473,6 +682,190 @@ impl Actor {
         Ok(GetMultiaddrsReturn { multi_addrs })
     }
 
+    /// Returns the miner's identifying addresses, proof type, sector size, peer ID
+    /// and any pending owner or worker change, as read from a single load of the miner info.
+    fn get_miner_essentials(rt: &impl Runtime) -> Result<GetMinerEssentialsReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let state: State = rt.state()?;
+        let info = get_miner_info(rt.store(), &state)?;
+        Ok(GetMinerEssentialsReturn {
+            owner: info.owner,
+            worker: info.worker,
+            control_addresses: info.control_addresses,
+            window_post_proof_type: info.window_post_proof_type,
+            sector_size: info.sector_size,
+            peer_id: info.peer_id,
+            pending_owner_change: info.pending_owner_change_at(rt.curr_epoch()),
+            pending_worker_key: info.pending_worker_key,
+        })
+    }
+
+    /// Returns the penalty for terminating the given live sectors in the current epoch, and the
+    /// initial pledge that would be released, as computed when the terminations are processed.
+    fn estimate_termination_fee(
+        rt: &impl Runtime,
+        params: EstimateTerminationFeeParams,
+    ) -> Result<EstimateTerminationFeeReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+
+        let sector_count = params
+            .sectors
+            .validate()
+            .map_err(|e| actor_error!(illegal_argument, "failed to parse sectors bitfield: {}", e))?
+            .len();
+        if sector_count > rt.policy().addressed_sectors_max {
+            return Err(actor_error!(
+                illegal_argument,
+                "too many sectors {}, limit {}",
+                sector_count,
+                rt.policy().addressed_sectors_max
+            ));
+        }
+
+        let state: State = rt.state()?;
+        let store = rt.store();
+        let info = get_miner_info(store, &state)?;
+
+        let mut live = BitField::new();
+        let deadlines = state.load_deadlines(store)?;
+        deadlines
+            .for_each(store, |_, deadline| {
+                deadline.for_each(store, |_, partition| {
+                    live |= &(&partition.live_sectors() & &params.sectors);
+                    Ok(())
+                })
+            })
+            .map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load partitions")
+            })?;
+        let not_live = &params.sectors - &live;
+        if !not_live.is_empty() {
+            return Err(actor_error!(
+                illegal_argument,
+                "sectors {:?} are not live",
+                not_live.iter().collect_vec()
+            ));
+        }
+
+        let sectors = state.load_sector_infos(store, &params.sectors).map_err(|e| {
+            e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load sectors")
+        })?;
+
+        let epoch_reward = request_current_epoch_block_reward(rt)?;
+        let pwr_total = request_current_total_power(rt)?;
+
+        let mut penalty = TokenAmount::zero();
+        let mut pledge_released = TokenAmount::zero();
+        for sector in &sectors {
+            penalty += termination_penalty(
+                rt.policy(),
+                info.sector_size,
+                rt.curr_epoch(),
+                sector,
+                &epoch_reward.this_epoch_reward_smoothed,
+                &pwr_total.quality_adj_power_smoothed,
+            );
+            pledge_released += &sector.initial_pledge;
+        }
+        Ok(EstimateTerminationFeeReturn { penalty, pledge_released })
+    }
+
+    /// Lists a page of the miner's proven sectors, in sector number order.
+    fn list_sectors(
+        rt: &impl Runtime,
+        params: ListSectorsParams,
+    ) -> Result<ListSectorsReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        params.request.validate::<SectorOnChainInfo>()?;
+        let state: State = rt.state()?;
+        let sectors = Sectors::load(rt.store(), &state.sectors).map_err(|e| {
+            e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load sectors")
+        })?;
+        let (entries, next_cursor) = sectors.list(&params.request)?;
+        Ok(ListSectorsReturn { entries, next_cursor })
+    }
+
+    /// Previews the effect of a Window PoSt for a deadline that skips some sectors, without
+    /// recording anything.
+    /// The partitions are as they would be submitted to SubmitWindowedPoSt, at the deadline's current
+    /// or next challenge window, assuming no faults are recovered. Skipped sectors pay no penalty when
+    /// the deadline closes, but are then charged the returned fault fee at each deadline close while
+    /// they remain faulty.
+    fn preview_skipped_faults(
+        rt: &impl Runtime,
+        mut params: PreviewSkippedFaultsParams,
+    ) -> Result<PreviewSkippedFaultsReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let policy = rt.policy();
+        if params.deadline >= policy.wpost_period_deadlines {
+            return Err(actor_error!(illegal_argument, "invalid deadline {}", params.deadline));
+        }
+
+        let state: State = rt.state()?;
+        let store = rt.store();
+        let info = get_miner_info(store, &state)?;
+
+        let submission_partition_limit = cmp::min(
+            load_partitions_sectors_max(policy, info.window_post_partition_sectors),
+            policy.posted_partitions_max,
+        );
+        if params.partitions.len() as u64 > submission_partition_limit {
+            return Err(actor_error!(
+                illegal_argument,
+                "too many partitions {}, limit {}",
+                params.partitions.len(),
+                submission_partition_limit
+            ));
+        }
+
+        let current_epoch = rt.curr_epoch();
+        let dl_info = new_deadline_info(
+            policy,
+            state.deadline_info(policy, current_epoch).period_start,
+            params.deadline,
+            current_epoch,
+        )
+        .next_not_elapsed();
+
+        let sectors = Sectors::load(store, &state.sectors).map_err(|e| {
+            e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load sectors")
+        })?;
+        let deadlines = state.load_deadlines(store)?;
+        // The deadline is modified as by SubmitWindowedPoSt, but never saved.
+        let mut deadline = deadlines.load_deadline(store, params.deadline)?;
+        let post_result = deadline
+            .record_proven_sectors(
+                store,
+                &sectors,
+                info.sector_size,
+                dl_info.quant_spec(),
+                dl_info.last() + policy.fault_max_age,
+                &mut params.partitions,
+                false,
+            )
+            .map_err(|e| {
+                e.downcast_default(
+                    ExitCode::USR_ILLEGAL_STATE,
+                    format!("failed to process post submission for deadline {}", params.deadline),
+                )
+            })?;
+
+        // Retracted recoveries remain faulty, and are charged along with the new faults.
+        let epoch_reward = request_current_epoch_block_reward(rt)?;
+        let pwr_total = request_current_total_power(rt)?;
+        let fault_fee = pledge_penalty_for_continued_fault(
+            &epoch_reward.this_epoch_reward_smoothed,
+            &pwr_total.quality_adj_power_smoothed,
+            &(&post_result.new_faulty_power.qa + &post_result.retracted_recovery_power.qa),
+        );
+        Ok(PreviewSkippedFaultsReturn {
+            power_delta: post_result.power_delta,
+            new_faulty_power: post_result.new_faulty_power,
+            retracted_recovery_power: post_result.retracted_recovery_power,
+            fault_fee,
+        })
+    }
+
     fn change_multiaddresses(
         rt: &impl Runtime,
         params: ChangeMultiaddrsParams,
@@ -541,17 +934,33 @@ impl Actor {
             }
         }
 
-        let post_result = rt.transaction(|state: &mut State, rt| {
+        let (post_result, fee_to_burn) = rt.transaction(|state: &mut State, rt| {
             let info = get_miner_info(rt.store(), state)?;
 
             let max_proof_size = info.window_post_proof_type.proof_size().map_err(|e| {
                 actor_error!(illegal_state, "failed to determine max window post proof size: {}", e)
             })?;
 
+            // Provers authorized at this epoch may submit alongside the controlling addresses.
             rt.validate_immediate_caller_is(
-                info.control_addresses.iter().chain(&[info.worker, info.owner]),
+                info.control_addresses
+                    .iter()
+                    .chain(&[info.worker, info.owner])
+                    .chain(info.active_provers(current_epoch)),
             )?;
 
+            // Recovering faults here is subject to the same conditions as DeclareFaultsRecovered.
+            let mut fee_to_burn = TokenAmount::zero();
+            if params.auto_recover_faults {
+                if consensus_fault_active(&info, current_epoch) {
+                    return Err(actor_error!(
+                        forbidden,
+                        "recovery not allowed during active consensus fault"
+                    ));
+                }
+                fee_to_burn = repay_debts_or_abort(rt, state)?;
+            }
+
             // Make sure the miner is using the correct proof type.
             if params.proofs[0].post_proof != info.window_post_proof_type {
                 return Err(actor_error!(
@@ -670,6 +1079,7 @@ impl Actor {
                     current_deadline.quant_spec(),
                     fault_expiration,
                     &mut params.partitions,
+                    params.auto_recover_faults,
                 )
                 .map_err(|e| {
                     e.downcast_default(
@@ -741,9 +1151,11 @@ impl Actor {
                 e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to save deadlines")
             })?;
 
-            Ok(post_result)
+            Ok((post_result, fee_to_burn))
         })?;
 
+        burn_funds(rt, fee_to_burn)?;
+
         // Restore power for recovered sectors. Remove power for new faults.
         // NOTE: It would be permissible to delay the power loss until the deadline closes, but that would require
         // additional accounting state.
@@ -753,6 +1165,7 @@ impl Actor {
         let state: State = rt.state()?;
         state.check_balance_invariants(&rt.current_balance()).map_err(balance_invariants_broken)?;
 
+        rt.increment_counter("miner.post_submissions", 1);
         Ok(())
     }
     /// Checks state of the corresponding sector pre-commitments and verifies aggregate proof of replication
@@ -1263,8 +1676,8 @@ impl Actor {
         let epoch_reward = request_current_epoch_block_reward(rt)?;
         let power_total = request_current_total_power(rt)?;
 
-        let (pledge_delta, mut to_burn, power_delta, to_reward) =
-            rt.transaction(|st: &mut State, rt| {
+        let (pledge_delta, mut to_burn, power_delta, mut to_reward, penalty_target, paid) = rt
+            .transaction(|st: &mut State, rt| {
                 let policy = rt.policy();
                 let dl_info = st.deadline_info(policy, current_epoch);
 
@@ -1415,6 +1828,7 @@ impl Actor {
                     })?;
 
                 let to_burn = &penalty_from_vesting + &penalty_from_balance;
+                let paid = penalty_paid(&penalty_target, &to_burn);
 
                 // Now, move as much of the target reward as
                 // we can from the burn to the reward.
@@ -1422,7 +1836,7 @@ impl Actor {
                 let to_burn = &to_burn - to_reward;
                 let pledge_delta = penalty_from_vesting.neg();
 
-                Ok((pledge_delta, to_burn, power_delta, to_reward.clone()))
+                Ok((pledge_delta, to_burn, power_delta, to_reward.clone(), penalty_target, paid))
             })?;
 
         request_update_power(rt, power_delta)?;
@@ -1431,12 +1845,19 @@ impl Actor {
                 extract_send_result(rt.send_simple(&reporter, METHOD_SEND, None, to_reward.clone()))
             {
                 error!("failed to send reward: {}", e);
-                to_burn += to_reward;
+                to_burn += std::mem::take(&mut to_reward);
             }
         }
 
         burn_funds(rt, to_burn)?;
         notify_pledge_changed(rt, &pledge_delta)?;
+        emit::penalty_assessed(
+            rt,
+            PenaltyCategory::InvalidWindowPost,
+            &penalty_target,
+            &(&paid - &to_reward),
+            &(&penalty_target - &paid),
+        )?;
 
         let st: State = rt.state()?;
         st.check_balance_invariants(&rt.current_balance()).map_err(balance_invariants_broken)?;
@@ -1472,6 +1893,36 @@ impl Actor {
         )
     }
 
+    /// Publishes storage deals and pre-commits a sector containing them, in a single message.
+    ///
+    /// Either every deal is published and the sector pre-committed, or the whole message aborts.
+    /// The allocations of verified deals are claimed when the sector is proven, as usual.
+    fn onboard_sector_with_deals(
+        rt: &impl Runtime,
+        params: OnboardSectorWithDealsParams,
+    ) -> Result<OnboardSectorWithDealsReturn, ActorError> {
+        // The market accepts deals published by their provider without checking the immediate
+        // caller, which is instead validated by the pre-commit below. Since a failure there
+        // aborts the message, nothing is published on behalf of an unauthorized caller.
+        let deal_ids = publish_deals(rt, params.deals)?;
+
+        let sector = params.sector;
+        Self::pre_commit_sector_batch_inner(
+            rt,
+            vec![SectorPreCommitInfoInner {
+                seal_proof: sector.seal_proof,
+                sector_number: sector.sector_number,
+                sealed_cid: sector.sealed_cid,
+                seal_rand_epoch: sector.seal_rand_epoch,
+                deal_ids: deal_ids.clone(),
+                expiration: sector.expiration,
+                unsealed_cid: Some(sector.unsealed_cid),
+            }],
+        )?;
+
+        Ok(OnboardSectorWithDealsReturn { deal_ids })
+    }
+
     /// This function combines old and new flows for PreCommit with use Option<CommpactCommD>
     /// The old PreCommits will call this with None, new ones with Some(CompactCommD).
     fn pre_commit_sector_batch_inner(
@@ -1631,7 +2082,7 @@ impl Actor {
             let mut clean_up_events = Vec::with_capacity(sectors.len());
             let deal_count_max = sector_deals_max(rt.policy(), info.sector_size);
 
-            let sector_weight_for_deposit = qa_power_max(info.sector_size);
+            let sector_weight_for_deposit = qa_power_max_for_policy(rt.policy(), info.sector_size);
             let deposit_req = pre_commit_deposit_for_power(&reward_stats.this_epoch_reward_smoothed, &power_total.quality_adj_power_smoothed, &sector_weight_for_deposit);
 
             for (i, precommit) in sectors.into_iter().enumerate() {
@@ -1738,6 +2189,7 @@ impl Actor {
                 CronEventPayload { event_type: CRON_EVENT_PROVING_DEADLINE },
             )?;
         }
+        rt.increment_counter("miner.sectors_precommitted", sector_numbers.len());
         Ok(())
     }
 
@@ -2356,6 +2808,18 @@ impl Actor {
             }
         }
 
+        if let Some(manifest) = &params.handoff_manifest {
+            if !is_valid_handoff_manifest(manifest) {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "invalid handoff manifest {}: must be a CIDv1 of at most {} bytes, \
+                    not an identity CID",
+                    manifest,
+                    MAX_HANDOFF_MANIFEST_CID_LEN
+                ));
+            }
+        }
+
         let mut to_process = DeadlineSectorMap::new();
 
         for term in params.terminations {
@@ -2382,83 +2846,91 @@ impl Actor {
                 })?;
         }
 
-        let (had_early_terminations, power_delta) = rt.transaction(|state: &mut State, rt| {
-            let had_early_terminations = have_pending_early_terminations(state);
-
-            let info = get_miner_info(rt.store(), state)?;
-
-            rt.validate_immediate_caller_is(
-                info.control_addresses.iter().chain(&[info.worker, info.owner]),
-            )?;
+        let (had_early_terminations, power_delta, terminated_sectors) =
+            rt.transaction(|state: &mut State, rt| {
+                let had_early_terminations = have_pending_early_terminations(state);
 
-            let store = rt.store();
-            let curr_epoch = rt.curr_epoch();
-            let mut power_delta = PowerPair::zero();
+                let info = get_miner_info(rt.store(), state)?;
 
-            let mut deadlines =
-                state.load_deadlines(store).map_err(|e| e.wrap("failed to load deadlines"))?;
+                rt.validate_immediate_caller_is(
+                    info.control_addresses.iter().chain(&[info.worker, info.owner]),
+                )?;
 
-            // We're only reading the sectors, so there's no need to save this back.
-            // However, we still want to avoid re-loading this array per-partition.
-            let sectors = Sectors::load(store, &state.sectors).map_err(|e| {
-                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load sectors")
-            })?;
+                let store = rt.store();
+                let curr_epoch = rt.curr_epoch();
+                let mut power_delta = PowerPair::zero();
+                let mut terminated_sectors = BitField::new();
 
-            for (deadline_idx, partition_sectors) in to_process.iter() {
-                // If the deadline is the current or next deadline to prove, don't allow terminating sectors.
-                // We assume that deadlines are immutable when being proven.
-                if !deadline_is_mutable(
-                    rt.policy(),
-                    state.current_proving_period_start(rt.policy(), curr_epoch),
-                    deadline_idx,
-                    curr_epoch,
-                ) {
-                    return Err(actor_error!(
-                        illegal_argument,
-                        "cannot terminate sectors in immutable deadline {}",
-                        deadline_idx
-                    ));
-                }
+                let mut deadlines =
+                    state.load_deadlines(store).map_err(|e| e.wrap("failed to load deadlines"))?;
 
-                let quant = state.quant_spec_for_deadline(rt.policy(), deadline_idx);
-                let mut deadline = deadlines.load_deadline(store, deadline_idx)?;
+                // We're only reading the sectors, so there's no need to save this back.
+                // However, we still want to avoid re-loading this array per-partition.
+                let sectors = Sectors::load(store, &state.sectors).map_err(|e| {
+                    e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load sectors")
+                })?;
 
-                let removed_power = deadline
-                    .terminate_sectors(
+                for (deadline_idx, partition_sectors) in to_process.iter() {
+                    // If the deadline is the current or next deadline to prove, don't allow terminating sectors.
+                    // We assume that deadlines are immutable when being proven.
+                    if !deadline_is_mutable(
                         rt.policy(),
-                        store,
-                        &sectors,
+                        state.current_proving_period_start(rt.policy(), curr_epoch),
+                        deadline_idx,
                         curr_epoch,
-                        partition_sectors,
-                        info.sector_size,
-                        quant,
-                    )
-                    .map_err(|e| {
-                        e.downcast_default(
-                            ExitCode::USR_ILLEGAL_STATE,
-                            format!("failed to terminate sectors in deadline {}", deadline_idx),
-                        )
-                    })?;
+                    ) {
+                        return Err(actor_error!(
+                            illegal_argument,
+                            "cannot terminate sectors in immutable deadline {}",
+                            deadline_idx
+                        ));
+                    }
 
-                state.early_terminations.set(deadline_idx);
-                power_delta -= &removed_power;
+                    let quant = state.quant_spec_for_deadline(rt.policy(), deadline_idx);
+                    let mut deadline = deadlines.load_deadline(store, deadline_idx)?;
 
-                deadlines.update_deadline(rt.policy(), store, deadline_idx, &deadline).map_err(
-                    |e| {
-                        e.downcast_default(
-                            ExitCode::USR_ILLEGAL_STATE,
-                            format!("failed to update deadline {}", deadline_idx),
+                    let (removed_power, removed_sectors) = deadline
+                        .terminate_sectors(
+                            rt.policy(),
+                            store,
+                            &sectors,
+                            curr_epoch,
+                            partition_sectors,
+                            info.sector_size,
+                            quant,
                         )
-                    },
-                )?;
-            }
+                        .map_err(|e| {
+                            e.downcast_default(
+                                ExitCode::USR_ILLEGAL_STATE,
+                                format!("failed to terminate sectors in deadline {}", deadline_idx),
+                            )
+                        })?;
 
-            state.save_deadlines(store, deadlines).map_err(|e| {
-                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to save deadlines")
+                    state.early_terminations.set(deadline_idx);
+                    power_delta -= &removed_power;
+                    terminated_sectors |= &removed_sectors;
+
+                    deadlines
+                        .update_deadline(rt.policy(), store, deadline_idx, &deadline)
+                        .map_err(|e| {
+                            e.downcast_default(
+                                ExitCode::USR_ILLEGAL_STATE,
+                                format!("failed to update deadline {}", deadline_idx),
+                            )
+                        })?;
+                }
+
+                state.save_deadlines(store, deadlines).map_err(|e| {
+                    e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to save deadlines")
+                })?;
+
+                Ok((had_early_terminations, power_delta, terminated_sectors))
             })?;
 
-            Ok((had_early_terminations, power_delta))
-        })?;
+        if let Some(manifest) = &params.handoff_manifest {
+            emit::termination_handoff(rt, manifest, &terminated_sectors)?;
+        }
+
         let epoch_reward = request_current_epoch_block_reward(rt)?;
         let pwr_total = request_current_total_power(rt)?;
 
@@ -2503,7 +2975,16 @@ impl Actor {
             let deadline = term.deadline;
             let partition = term.partition;
 
-            to_process.add(rt.policy(), deadline, partition, term.sectors).map_err(|e| {
+            let sectors = term.sectors.into_bitfield().map_err(|e| {
+                actor_error!(
+                    illegal_argument,
+                    "invalid sectors for deadline {}, partition {}: {}",
+                    deadline,
+                    partition,
+                    e
+                )
+            })?;
+            to_process.add(rt.policy(), deadline, partition, sectors).map_err(|e| {
                 actor_error!(
                     illegal_argument,
                     "failed to process deadline {}, partition {}: {}",
@@ -2636,7 +3117,16 @@ impl Actor {
             let deadline = term.deadline;
             let partition = term.partition;
 
-            to_process.add(rt.policy(), deadline, partition, term.sectors).map_err(|e| {
+            let sectors = term.sectors.into_bitfield().map_err(|e| {
+                actor_error!(
+                    illegal_argument,
+                    "invalid sectors for deadline {}, partition {}: {}",
+                    deadline,
+                    partition,
+                    e
+                )
+            })?;
+            to_process.add(rt.policy(), deadline, partition, sectors).map_err(|e| {
                 actor_error!(
                     illegal_argument,
                     "failed to process deadline {}, partition {}: {}",
@@ -2871,6 +3361,187 @@ impl Actor {
         Ok(())
     }
 
+    /// Removes partitions from any number of deadlines, moving their live sectors into the
+    /// destination deadline, where they fill its last partition and then new full partitions.
+    /// The destination's next challenge window must open no later than that of any source
+    /// deadline, so that moved sectors are proven no later than they otherwise would have been.
+    /// The destination may also be a source, in which case its partitions are compacted too.
+    fn compact_sectors_across_deadlines(
+        rt: &impl Runtime,
+        params: CompactSectorsAcrossDeadlinesParams,
+    ) -> Result<(), ActorError> {
+        let mut sources = BTreeMap::new();
+        let mut partition_count = 0;
+        {
+            let policy = rt.policy();
+            if params.dest_deadline >= policy.wpost_period_deadlines {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "invalid destination deadline {}",
+                    params.dest_deadline
+                ));
+            }
+            for source in &params.sources {
+                if source.deadline >= policy.wpost_period_deadlines {
+                    return Err(actor_error!(
+                        illegal_argument,
+                        "invalid deadline {}",
+                        source.deadline
+                    ));
+                }
+                let partitions = source.partitions.validate().map_err(|e| {
+                    actor_error!(illegal_argument, "failed to parse partitions bitfield: {}", e)
+                })?;
+                partition_count += partitions.len();
+                if sources.insert(source.deadline, partitions).is_some() {
+                    return Err(actor_error!(
+                        illegal_argument,
+                        "duplicate source deadline {}",
+                        source.deadline
+                    ));
+                }
+            }
+        }
+        let dest_deadline_idx = params.dest_deadline;
+
+        rt.transaction(|state: &mut State, rt| {
+            let info = get_miner_info(rt.store(), state)?;
+
+            rt.validate_immediate_caller_is(
+                info.control_addresses.iter().chain(&[info.worker, info.owner]),
+            )?;
+
+            let store = rt.store();
+            let policy = rt.policy();
+            let proving_period_start = state.current_proving_period_start(policy, rt.curr_epoch());
+
+            let submission_partition_limit =
+                load_partitions_sectors_max(policy, info.window_post_partition_sectors);
+            if partition_count > submission_partition_limit {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "too many partitions {}, limit {}",
+                    partition_count,
+                    submission_partition_limit
+                ));
+            }
+
+            let mut deadlines =
+                state.load_deadlines(store).map_err(|e| e.wrap("failed to load deadlines"))?;
+
+            let mut all_live = Vec::with_capacity(sources.len());
+            let mut removed_power = PowerPair::zero();
+            for (&deadline_idx, partitions) in &sources {
+                if !deadline_available_for_move(
+                    policy,
+                    proving_period_start,
+                    deadline_idx,
+                    dest_deadline_idx,
+                    rt.curr_epoch(),
+                ) {
+                    return Err(actor_error!(
+                        forbidden,
+                        "cannot move sectors from deadline {} to deadline {}: the source must be \
+                        available for compaction, and the destination must be mutable and due \
+                        to be challenged no later than the source",
+                        deadline_idx,
+                        dest_deadline_idx
+                    ));
+                }
+
+                let quant = state.quant_spec_for_deadline(policy, deadline_idx);
+                let mut deadline = deadlines.load_deadline(store, deadline_idx)?;
+                let (live, dead, power) =
+                    deadline.remove_partitions(store, partitions, quant).map_err(|e| {
+                        e.downcast_default(
+                            ExitCode::USR_ILLEGAL_STATE,
+                            format!("failed to remove partitions from deadline {}", deadline_idx),
+                        )
+                    })?;
+
+                state.delete_sectors(store, &dead).map_err(|e| {
+                    e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to delete dead sectors")
+                })?;
+
+                deadlines.update_deadline(policy, store, deadline_idx, &deadline).map_err(|e| {
+                    e.downcast_default(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        format!("failed to update deadline {}", deadline_idx),
+                    )
+                })?;
+
+                all_live.push(live);
+                removed_power += &power;
+            }
+
+            let live = BitField::union(&all_live);
+            let sectors = state.load_sector_infos(store, &live).map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load moved sectors")
+            })?;
+
+            let dest_quant = state.quant_spec_for_deadline(policy, dest_deadline_idx);
+            let mut dest_deadline = deadlines.load_deadline(store, dest_deadline_idx)?;
+            let proven = true;
+            let added_power = dest_deadline
+                .add_sectors(
+                    store,
+                    info.window_post_partition_sectors,
+                    proven,
+                    &sectors,
+                    info.sector_size,
+                    dest_quant,
+                )
+                .map_err(|e| {
+                    e.downcast_default(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        format!("failed to add moved sectors to deadline {}", dest_deadline_idx),
+                    )
+                })?;
+
+            if removed_power != added_power {
+                return Err(actor_error!(
+                    illegal_state,
+                    "power changed when moving sectors: was {:?}, is now {:?}",
+                    removed_power,
+                    added_power
+                ));
+            }
+
+            let dest_partition_count = dest_deadline
+                .partitions_amt(store)
+                .map_err(|e| {
+                    e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load partitions")
+                })?
+                .count();
+            if dest_partition_count > policy.max_partitions_per_deadline {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "deadline {} would have {} partitions, limit {}",
+                    dest_deadline_idx,
+                    dest_partition_count,
+                    policy.max_partitions_per_deadline
+                ));
+            }
+
+            deadlines.update_deadline(policy, store, dest_deadline_idx, &dest_deadline).map_err(
+                |e| {
+                    e.downcast_default(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        format!("failed to update deadline {}", dest_deadline_idx),
+                    )
+                },
+            )?;
+
+            state.save_deadlines(store, deadlines).map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to save deadlines")
+            })?;
+
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+
     /// Compacts sector number allocations to reduce the size of the allocated sector
     /// number bitfield.
     ///
@@ -2934,7 +3605,7 @@ impl Actor {
             ));
         }
 
-        let (pledge_delta_total, to_burn) = rt.transaction(|st: &mut State, rt| {
+        let (pledge_delta_total, to_burn, paid) = rt.transaction(|st: &mut State, rt| {
             let mut pledge_delta_total = TokenAmount::zero();
 
             rt.validate_immediate_caller_is(std::iter::once(&REWARD_ACTOR_ADDR))?;
@@ -2987,11 +3658,21 @@ impl Actor {
                 })?;
             pledge_delta_total -= &penalty_from_vesting;
             let to_burn = penalty_from_vesting + penalty_from_balance;
-            Ok((pledge_delta_total, to_burn))
+            let paid = penalty_paid(&params.penalty, &to_burn);
+            Ok((pledge_delta_total, to_burn, paid))
         })?;
 
         notify_pledge_changed(rt, &pledge_delta_total)?;
         burn_funds(rt, to_burn)?;
+        if params.penalty.is_positive() {
+            emit::penalty_assessed(
+                rt,
+                PenaltyCategory::BlockReward,
+                &params.penalty,
+                &paid,
+                &(&params.penalty - &paid),
+            )?;
+        }
         let st: State = rt.state()?;
         st.check_balance_invariants(&rt.current_balance()).map_err(balance_invariants_broken)?;
         Ok(())
@@ -3043,7 +3724,7 @@ impl Actor {
 
         let mut pledge_delta = TokenAmount::zero();
 
-        let (burn_amount, reward_amount) = rt.transaction(|st: &mut State, rt| {
+        let (burn_amount, reward_amount, paid) = rt.transaction(|st: &mut State, rt| {
             let mut info = get_miner_info(rt.store(), st)?;
 
             // Verify miner hasn't already been faulted
@@ -3073,6 +3754,7 @@ impl Actor {
 
             let mut burn_amount = &penalty_from_vesting + &penalty_from_balance;
             pledge_delta -= penalty_from_vesting;
+            let paid = penalty_paid(&fault_penalty, &burn_amount);
 
             // clamp reward at funds burnt
             let reward_amount = std::cmp::min(&burn_amount, &slasher_reward).clone();
@@ -3085,17 +3767,24 @@ impl Actor {
                 e.downcast_default(ExitCode::USR_SERIALIZATION, "failed to save miner info")
             })?;
 
-            Ok((burn_amount, reward_amount))
+            Ok((burn_amount, reward_amount, paid))
         })?;
 
         if let Err(e) =
-            extract_send_result(rt.send_simple(&reporter, METHOD_SEND, None, reward_amount))
+            extract_send_result(rt.send_simple(&reporter, METHOD_SEND, None, reward_amount.clone()))
         {
             error!("failed to send reward: {}", e);
         }
 
         burn_funds(rt, burn_amount)?;
         notify_pledge_changed(rt, &pledge_delta)?;
+        emit::penalty_assessed(
+            rt,
+            PenaltyCategory::ConsensusFault,
+            &fault_penalty,
+            &(&paid - &reward_amount),
+            &(&fault_penalty - &paid),
+        )?;
 
         let state: State = rt.state()?;
         state.check_balance_invariants(&rt.current_balance()).map_err(balance_invariants_broken)?;
@@ -3140,19 +3829,18 @@ impl Actor {
                 // available balance already accounts for fee debt so it is correct to call
                 // this before RepayDebts. We would have to
                 // subtract fee debt explicitly if we called this after.
-                let available_balance =
-                    state.get_available_balance(&rt.current_balance()).map_err(|e| {
-                        actor_error!(
-                            illegal_state,
-                            format!("failed to calculate available balance: {}", e)
-                        )
-                    })?;
+                let limits = WithdrawalLimits::compute(
+                    state,
+                    &info,
+                    &rt.current_balance(),
+                    rt.curr_epoch(),
+                )?;
 
                 // Verify unlocked funds cover both InitialPledgeRequirement and FeeDebt
                 // and repay fee debt now.
                 let fee_to_burn = repay_debts_or_abort(rt, state)?;
-                let mut amount_withdrawn =
-                    std::cmp::min(&available_balance, &params.amount_requested);
+                let amount_withdrawn =
+                    std::cmp::min(limits.max_withdrawal(), params.amount_requested.clone());
                 if amount_withdrawn.is_negative() {
                     return Err(actor_error!(
                         illegal_state,
@@ -3160,9 +3848,7 @@ impl Actor {
                         amount_withdrawn
                     ));
                 }
-                if info.beneficiary != info.owner {
-                    // remaining_quota always zero and positive
-                    let remaining_quota = info.beneficiary_term.available(rt.curr_epoch());
+                if let Some(remaining_quota) = &limits.beneficiary_quota {
                     if remaining_quota.is_zero() {
                         return Err(actor_error!(
                             forbidden,
@@ -3172,9 +3858,8 @@ impl Actor {
                             info.beneficiary_term.used_quota
                         ));
                     }
-                    amount_withdrawn = std::cmp::min(amount_withdrawn, &remaining_quota);
                     if amount_withdrawn.is_positive() {
-                        info.beneficiary_term.used_quota += amount_withdrawn;
+                        info.beneficiary_term.used_quota += &amount_withdrawn;
                         state.save_info(rt.store(), &info).map_err(|e| {
                             e.downcast_default(
                                 ExitCode::USR_ILLEGAL_STATE,
@@ -3417,8 +4102,47 @@ impl Actor {
         state.check_balance_invariants(&rt.current_balance()).map_err(balance_invariants_broken)?;
         Ok(())
     }
+
+    /// Records the space of verified claims that the verified registry has removed after they
+    /// reached their maximum term while still backing this miner's sectors.
+    /// The corresponding verified power is removed from the sectors at their next deadline cron.
+    /// Only sectors without simple QA power can outlive their claims, as they may have been
+    /// extended without regard to the claims' terms. Other sectors' extensions are capped at
+    /// their claims' maximum terms, so they expire no later than their claims.
+    fn on_verified_claims_expired(
+        rt: &impl Runtime,
+        params: OnVerifiedClaimsExpiredParams,
+    ) -> Result<(), ActorError> {
+        rt.validate_immediate_caller_is(std::iter::once(&VERIFIED_REGISTRY_ACTOR_ADDR))?;
+        let curr_epoch = rt.curr_epoch();
+        rt.transaction(|state: &mut State, rt| {
+            // Sectors that have expired or been terminated carry no verified power to remove.
+            let mut expired = Vec::with_capacity(params.sectors.len());
+            for e in params.sectors {
+                match state.get_sector(rt.store(), e.sector_number)? {
+                    Some(sector)
+                        if sector.expiration > curr_epoch
+                            && sector.verified_deal_weight.is_positive()
+                            && !sector.flags.contains(SectorOnChainInfoFlags::SIMPLE_QA_POWER) =>
+                    {
+                        expired.push(e)
+                    }
+                    _ => {
+                        info!(
+                            "ignoring expired claim space for inactive or simple QA power sector {}",
+                            e.sector_number
+                        );
+                    }
+                }
+            }
+            state.record_expired_claim_space(rt.store(), &expired).map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to record expired claims")
+            })
+        })
+    }
 }
 
+#[cfg(feature = "actor")]
 #[derive(Debug, PartialEq, Clone)]
 struct SectorPreCommitInfoInner {
     pub seal_proof: RegisteredSealProof,
@@ -3434,6 +4158,7 @@ struct SectorPreCommitInfoInner {
 
 /// ReplicaUpdate param with Option<Cid> for CommD
 /// None means unknown
+#[cfg(feature = "actor")]
 #[derive(Debug, Clone)]
 pub struct ReplicaUpdateInner {
     pub sector_number: SectorNumber,
@@ -3447,6 +4172,7 @@ pub struct ReplicaUpdateInner {
     pub replica_proof: RawBytes,
 }
 
+#[cfg(feature = "actor")]
 enum ExtensionKind {
     // handle only legacy sectors
     ExtendCommittmentLegacy,
@@ -3455,6 +4181,7 @@ enum ExtensionKind {
 }
 
 // ExtendSectorExpiration param
+#[cfg(feature = "actor")]
 struct ExtendExpirationsInner {
     extensions: Vec<ValidatedExpirationExtension>,
     // Map from sector being extended to (check, maintain)
@@ -3464,6 +4191,7 @@ struct ExtendExpirationsInner {
     claims: Option<BTreeMap<SectorNumber, (u64, u64)>>,
 }
 
+#[cfg(feature = "actor")]
 #[derive(Clone, Debug, PartialEq)]
 pub struct ValidatedExpirationExtension {
     pub deadline: u64,
@@ -3472,6 +4200,7 @@ pub struct ValidatedExpirationExtension {
     pub new_expiration: ChainEpoch,
 }
 
+#[cfg(feature = "actor")]
 impl From<ExpirationExtension2> for ValidatedExpirationExtension {
     fn from(e2: ExpirationExtension2) -> Self {
         let mut sectors = BitField::new();
@@ -3489,6 +4218,7 @@ impl From<ExpirationExtension2> for ValidatedExpirationExtension {
     }
 }
 
+#[cfg(feature = "actor")]
 fn validate_legacy_extension_declarations(
     extensions: &[ExpirationExtension],
     policy: &Policy,
@@ -3517,6 +4247,7 @@ fn validate_legacy_extension_declarations(
     Ok(ExtendExpirationsInner { extensions: vec_validated, claims: None })
 }
 
+#[cfg(feature = "actor")]
 fn validate_extension_declarations(
     rt: &impl Runtime,
     extensions: Vec<ExpirationExtension2>,
@@ -3576,6 +4307,7 @@ fn validate_extension_declarations(
     })
 }
 
+#[cfg(feature = "actor")]
 #[allow(clippy::too_many_arguments)]
 fn extend_sector_committment(
     policy: &Policy,
@@ -3606,6 +4338,7 @@ fn extend_sector_committment(
     }
 }
 
+#[cfg(feature = "actor")]
 fn extend_sector_committment_legacy(
     policy: &Policy,
     curr_epoch: ChainEpoch,
@@ -3627,6 +4360,7 @@ fn extend_sector_committment_legacy(
     extend_non_simple_qap_sector(new_expiration, curr_epoch, sector)
 }
 
+#[cfg(feature = "actor")]
 fn validate_extended_expiration(
     policy: &Policy,
     curr_epoch: ChainEpoch,
@@ -3667,6 +4401,7 @@ fn validate_extended_expiration(
     Ok(())
 }
 
+#[cfg(feature = "actor")]
 #[allow(clippy::too_many_arguments)]
 fn extend_simple_qap_sector(
     policy: &Policy,
@@ -3682,6 +4417,10 @@ fn extend_simple_qap_sector(
 
     new_sector.expiration = new_expiration;
     new_sector.power_base_epoch = curr_epoch;
+    new_sector.flags.set(
+        SectorOnChainInfoFlags::DURATION_MULTIPLIER,
+        policy.sector_duration_multiplier_enabled,
+    );
     let old_duration = sector.expiration - sector.power_base_epoch;
     let new_duration = new_sector.expiration - new_sector.power_base_epoch;
 
@@ -3728,15 +4467,15 @@ fn extend_simple_qap_sector(
         }
 
         new_sector.verified_deal_weight = BigInt::from(*new_verified_deal_space) * new_duration;
+    }
 
-        // We only bother updating the expected_day_reward, expected_storage_pledge, and replaced_day_reward
-        //  for verified deals, as it can increase power.
-        let qa_pow = qa_power_for_weight(
-            sector_size,
-            new_duration,
-            &new_sector.deal_weight,
-            &new_sector.verified_deal_weight,
-        );
+    // We only bother updating the expected_day_reward, expected_storage_pledge, and replaced_day_reward
+    // for verified deals or duration multiplied power, as they can increase power.
+    if sector.verified_deal_weight.is_positive()
+        || sector.flags.contains(SectorOnChainInfoFlags::DURATION_MULTIPLIER)
+        || new_sector.flags.contains(SectorOnChainInfoFlags::DURATION_MULTIPLIER)
+    {
+        let qa_pow = qa_power_for_sector(sector_size, &new_sector);
         new_sector.expected_day_reward = expected_reward_for_power(
             &reward_stats.this_epoch_reward_smoothed,
             &power_stats.quality_adj_power_smoothed,
@@ -3759,6 +4498,7 @@ fn extend_simple_qap_sector(
     Ok(new_sector)
 }
 
+#[cfg(feature = "actor")]
 fn extend_non_simple_qap_sector(
     new_expiration: ChainEpoch,
     curr_epoch: ChainEpoch,
@@ -3781,10 +4521,47 @@ fn extend_non_simple_qap_sector(
     Ok(new_sector)
 }
 
+// Converts up to `expired_space` bytes of a sector's remaining verified deal weight to
+// unverified deal weight, re-basing both weights at the current epoch.
+#[cfg(feature = "actor")]
+fn downgrade_expired_claims(
+    curr_epoch: ChainEpoch,
+    reward_smoothed: &FilterEstimate,
+    quality_adj_power_smoothed: &FilterEstimate,
+    sector_size: SectorSize,
+    sector: &SectorOnChainInfo,
+    expired_space: u64,
+) -> SectorOnChainInfo {
+    let mut new_sector = sector.clone();
+    let remaining = sector.expiration - curr_epoch;
+    let duration = BigInt::from(sector.expiration - sector.power_base_epoch);
+    let deal_weight = (&sector.deal_weight * remaining).div_floor(&duration);
+    let verified_deal_weight = (&sector.verified_deal_weight * remaining).div_floor(&duration);
+    let expired_weight =
+        cmp::min(verified_deal_weight.clone(), BigInt::from(expired_space) * remaining);
+
+    new_sector.deal_weight = deal_weight + &expired_weight;
+    new_sector.verified_deal_weight = verified_deal_weight - &expired_weight;
+    new_sector.power_base_epoch = curr_epoch;
+
+    // The initial pledge is not reduced, so the replaced reward is retained for termination fees.
+    let qa_pow = qa_power_for_sector(sector_size, &new_sector);
+    new_sector.replaced_day_reward =
+        max(&sector.expected_day_reward, &sector.replaced_day_reward).clone();
+    new_sector.expected_day_reward = expected_reward_for_power(
+        reward_smoothed,
+        quality_adj_power_smoothed,
+        &qa_pow,
+        fil_actors_runtime::network::EPOCHS_IN_DAY,
+    );
+    new_sector
+}
+
 // Validates a list of replica update requests and parallel sector infos.
 // Returns all pairs of update and sector info, even those that fail validation.
 // The proof verification inputs are needed as witnesses to verify an aggregate proof to allow
 // other, valid, updates to succeed.
+#[cfg(feature = "actor")]
 #[allow(clippy::too_many_arguments)]
 fn validate_replica_updates<'a, BS>(
     updates: &'a [ReplicaUpdateInner],
@@ -3941,6 +4718,7 @@ where
     Ok((batch.gen(), update_sector_infos))
 }
 
+#[cfg(feature = "actor")]
 fn update_replica_states<BS>(
     rt: &impl Runtime,
     updates_by_deadline: &BTreeMap<u64, Vec<ReplicaUpdateStateInputs>>,
@@ -3986,6 +4764,7 @@ where
                     &pledge_inputs,
                     sector_size,
                     rt.curr_epoch(),
+                    rt.policy().sector_duration_multiplier_enabled,
                 );
 
                 let mut partition = partitions
@@ -4098,16 +4877,19 @@ where
 }
 
 // Builds a new sector info representing newly activated data in an existing sector.
+#[cfg(feature = "actor")]
 fn update_existing_sector_info(
     sector_info: &SectorOnChainInfo,
     activated_data: &ReplicaUpdateActivatedData,
     pledge_inputs: &NetworkPledgeInputs,
     sector_size: SectorSize,
     curr_epoch: ChainEpoch,
+    duration_multiplier: bool,
 ) -> SectorOnChainInfo {
     let mut new_sector_info = sector_info.clone();
 
     new_sector_info.flags.set(SectorOnChainInfoFlags::SIMPLE_QA_POWER, true);
+    new_sector_info.flags.set(SectorOnChainInfoFlags::DURATION_MULTIPLIER, duration_multiplier);
     new_sector_info.sealed_cid = activated_data.seal_cid;
     new_sector_info.sector_key_cid = match new_sector_info.sector_key_cid {
         None => Some(sector_info.sealed_cid),
@@ -4122,12 +4904,7 @@ fn update_existing_sector_info(
     new_sector_info.verified_deal_weight = activated_data.verified_space.clone() * duration;
 
     // compute initial pledge
-    let qa_pow = qa_power_for_weight(
-        sector_size,
-        duration,
-        &new_sector_info.deal_weight,
-        &new_sector_info.verified_deal_weight,
-    );
+    let qa_pow = qa_power_for_sector(sector_size, &new_sector_info);
 
     new_sector_info.replaced_day_reward =
         max(&sector_info.expected_day_reward, &sector_info.replaced_day_reward).clone();
@@ -4161,13 +4938,17 @@ fn update_existing_sector_info(
 }
 
 // Note: We're using the current power+epoch reward, rather than at time of termination.
+#[cfg(feature = "actor")]
 fn process_early_terminations(
     rt: &impl Runtime,
     reward_smoothed: &FilterEstimate,
     quality_adj_power_smoothed: &FilterEstimate,
 ) -> Result</* more */ bool, ActorError> {
     let mut terminated_sector_nums = vec![];
+    let mut fast_removed = vec![];
     let mut sectors_with_data = vec![];
+    let mut penalty_assessed = TokenAmount::zero();
+    let mut penalty_burned = TokenAmount::zero();
     let (result, more, penalty, pledge_delta) = rt.transaction(|state: &mut State, rt| {
         let store = rt.store();
         let policy = rt.policy();
@@ -4204,18 +4985,18 @@ fn process_early_terminations(
 
             for sector in &sectors {
                 total_initial_pledge += &sector.initial_pledge;
-                let sector_power = qa_power_for_sector(info.sector_size, sector);
                 terminated_sector_nums.push(sector.sector_number);
-                total_penalty += pledge_penalty_for_termination(
-                    &sector.expected_day_reward,
-                    epoch - sector.power_base_epoch,
-                    &sector.expected_storage_pledge,
-                    quality_adj_power_smoothed,
-                    &sector_power,
+                total_penalty += termination_penalty(
+                    policy,
+                    info.sector_size,
+                    epoch,
+                    sector,
                     reward_smoothed,
-                    &sector.replaced_day_reward,
-                    sector.power_base_epoch - sector.activation,
+                    quality_adj_power_smoothed,
                 );
+                if is_cc_fast_removal(policy, sector, epoch) {
+                    fast_removed.push((sector.sector_number, epoch - sector.activation));
+                }
                 if sector.deal_weight.is_positive() || sector.verified_deal_weight.is_positive() {
                     sectors_with_data.push(sector.sector_number);
                 }
@@ -4246,6 +5027,8 @@ fn process_early_terminations(
 
         let penalty = &penalty_from_vesting + penalty_from_balance;
         pledge_delta -= penalty_from_vesting;
+        penalty_burned = penalty_paid(&total_penalty, &penalty);
+        penalty_assessed = total_penalty;
 
         Ok((result, more, penalty, pledge_delta))
     })?;
@@ -4263,6 +5046,15 @@ fn process_early_terminations(
         penalty
     );
     burn_funds(rt, penalty)?;
+    if penalty_assessed.is_positive() {
+        emit::penalty_assessed(
+            rt,
+            PenaltyCategory::EarlyTermination,
+            &penalty_assessed,
+            &penalty_burned,
+            &(&penalty_assessed - &penalty_burned),
+        )?;
+    }
 
     // Return pledge.
     notify_pledge_changed(rt, &pledge_delta)?;
@@ -4275,12 +5067,51 @@ fn process_early_terminations(
     for sector in terminated_sector_nums {
         emit::sector_terminated(rt, sector)?;
     }
+    for (sector, age) in fast_removed {
+        emit::cc_sector_fast_removed(rt, sector, age)?;
+    }
 
     // reschedule cron worker, if necessary.
     Ok(more)
 }
 
+/// Computes the penalty for terminating a sector at an epoch.
+/// A young committed capacity sector pays the penalty pro-rated to its age, above a floor of the
+/// reward it is expected to have earned.
+#[cfg(feature = "actor")]
+fn termination_penalty(
+    policy: &Policy,
+    sector_size: SectorSize,
+    epoch: ChainEpoch,
+    sector: &SectorOnChainInfo,
+    reward_smoothed: &FilterEstimate,
+    quality_adj_power_smoothed: &FilterEstimate,
+) -> TokenAmount {
+    let sector_power = qa_power_for_sector(sector_size, sector);
+    let penalty = pledge_penalty_for_termination(
+        &sector.expected_day_reward,
+        epoch - sector.power_base_epoch,
+        &sector.expected_storage_pledge,
+        quality_adj_power_smoothed,
+        &sector_power,
+        reward_smoothed,
+        &sector.replaced_day_reward,
+        sector.power_base_epoch - sector.activation,
+    );
+    if is_cc_fast_removal(policy, sector, epoch) {
+        pledge_penalty_for_cc_fast_removal(
+            &penalty,
+            &sector.expected_day_reward,
+            epoch - sector.activation,
+            policy.cc_sector_fast_removal_max_age,
+        )
+    } else {
+        penalty
+    }
+}
+
 /// Invoked at the end of the last epoch for each proving deadline.
+#[cfg(feature = "actor")]
 fn handle_proving_deadline(
     rt: &impl Runtime,
     reward_smoothed: &FilterEstimate,
@@ -4294,6 +5125,8 @@ fn handle_proving_deadline(
     let mut penalty_total = TokenAmount::zero();
     let mut pledge_delta_total = TokenAmount::zero();
     let mut continue_cron = false;
+    // Penalties applied, with the amount of each left as fee debt.
+    let mut penalties = Vec::new();
 
     let state: State = rt.transaction(|state: &mut State, rt| {
         let policy = rt.policy();
@@ -4320,6 +5153,7 @@ fn handle_proving_deadline(
         // Process pending worker change if any
         let mut info = get_miner_info(rt.store(), state)?;
         process_pending_worker(&mut info, rt, state)?;
+        process_expired_owner_change(&mut info, rt, state)?;
 
         let deposit_to_burn = state
             .cleanup_expired_pre_commits(policy, rt.store(), rt.curr_epoch())
@@ -4343,6 +5177,40 @@ fn handle_proving_deadline(
         // That way, don't re-schedule a cron callback if one is already scheduled.
         had_early_terminations = have_pending_early_terminations(state);
 
+        // Remove the verified power of sectors in the deadline ending now whose claims have expired.
+        let dl_info = state.deadline_info(policy, curr_epoch);
+        if dl_info.period_started() {
+            let (downgrade_power_delta, downgrade_pledge_delta) = state
+                .downgrade_expired_claim_sectors(
+                    policy,
+                    rt.store(),
+                    dl_info.index,
+                    curr_epoch,
+                    info.sector_size,
+                    |sector, expired_space| {
+                        downgrade_expired_claims(
+                            curr_epoch,
+                            reward_smoothed,
+                            quality_adj_power_smoothed,
+                            info.sector_size,
+                            sector,
+                            expired_space,
+                        )
+                    },
+                )
+                .map_err(|e| {
+                    e.downcast_default(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        "failed to remove power of expired claims",
+                    )
+                })?;
+            state
+                .add_initial_pledge(&downgrade_pledge_delta)
+                .map_err(|e| actor_error!(illegal_state, "failed to add initial pledge: {}", e))?;
+            power_delta_total += &downgrade_power_delta;
+            pledge_delta_total += &downgrade_pledge_delta;
+        }
+
         let result = state.advance_deadline(policy, rt.store(), rt.curr_epoch()).map_err(|e| {
             e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to advance deadline")
         })?;
@@ -4381,6 +5249,12 @@ fn handle_proving_deadline(
         penalty_total = &penalty_from_vesting + penalty_from_balance;
         pledge_delta_total -= penalty_from_vesting;
 
+        // The repayment pays the penalties in the order they were applied.
+        let deposit_paid = penalty_paid(&deposit_to_burn, &penalty_total);
+        let fault_paid = penalty_paid(&penalty_target, &(&penalty_total - &deposit_paid));
+        penalties.push((PenaltyCategory::PreCommitExpiry, deposit_to_burn, deposit_paid));
+        penalties.push((PenaltyCategory::ContinuedFault, penalty_target, fault_paid));
+
         continue_cron = state.continue_deadline_cron();
         if !continue_cron {
             state.deadline_cron_active = false;
@@ -4393,6 +5267,11 @@ fn handle_proving_deadline(
     request_update_power(rt, power_delta_total)?;
     burn_funds(rt, penalty_total)?;
     notify_pledge_changed(rt, &pledge_delta_total)?;
+    for (category, amount, burned) in penalties {
+        if amount.is_positive() {
+            emit::penalty_assessed(rt, category, &amount, &burned, &(&amount - &burned))?;
+        }
+    }
 
     // Schedule cron callback for next deadline's last epoch.
     if continue_cron {
@@ -4426,6 +5305,7 @@ fn handle_proving_deadline(
     Ok(())
 }
 
+#[cfg(feature = "actor")]
 fn validate_expiration(
     policy: &Policy,
     curr_epoch: ChainEpoch,
@@ -4484,6 +5364,7 @@ fn validate_expiration(
     Ok(())
 }
 
+#[cfg(feature = "actor")]
 fn enroll_cron_event(
     rt: &impl Runtime,
     event_epoch: ChainEpoch,
@@ -4502,6 +5383,7 @@ fn enroll_cron_event(
     Ok(())
 }
 
+#[cfg(feature = "actor")]
 fn request_update_power(rt: &impl Runtime, delta: PowerPair) -> Result<(), ActorError> {
     if delta.is_zero() {
         return Ok(());
@@ -4523,6 +5405,7 @@ fn request_update_power(rt: &impl Runtime, delta: PowerPair) -> Result<(), Actor
     Ok(())
 }
 
+#[cfg(feature = "actor")]
 fn request_terminate_deals(
     rt: &impl Runtime,
     epoch: ChainEpoch,
@@ -4554,6 +5437,7 @@ fn request_terminate_deals(
     Ok(())
 }
 
+#[cfg(feature = "actor")]
 fn schedule_early_termination_work(rt: &impl Runtime) -> Result<(), ActorError> {
     info!("scheduling early terminations with cron...");
     enroll_cron_event(
@@ -4563,12 +5447,14 @@ fn schedule_early_termination_work(rt: &impl Runtime) -> Result<(), ActorError>
     )
 }
 
+#[cfg(feature = "actor")]
 fn have_pending_early_terminations(state: &State) -> bool {
     let no_early_terminations = state.early_terminations.is_empty();
     !no_early_terminations
 }
 
 // returns true if valid, false if invalid, error if failed to validate either way!
+#[cfg(feature = "actor")]
 fn verify_windowed_post(
     rt: &impl Runtime,
     challenge_epoch: ChainEpoch,
@@ -4615,6 +5501,7 @@ fn verify_windowed_post(
     Ok(result.is_ok())
 }
 
+#[cfg(feature = "actor")]
 struct SectorSealProofInput {
     pub registered_proof: RegisteredSealProof,
     pub sector_number: SectorNumber,
@@ -4626,6 +5513,7 @@ struct SectorSealProofInput {
     pub unsealed_cid: Cid,
 }
 
+#[cfg(feature = "actor")]
 impl SectorSealProofInput {
     fn to_seal_verify_info(&self, miner_actor_id: u64, proof: &RawBytes) -> SealVerifyInfo {
         SealVerifyInfo {
@@ -4655,6 +5543,7 @@ impl SectorSealProofInput {
 // Returns seal proof verification inputs for every pre-commit, even those that fail validation.
 // The proof verification inputs are needed as witnesses to verify an aggregated proof to allow
 // other, valid, sectors to succeed.
+#[cfg(feature = "actor")]
 fn validate_precommits(
     rt: &impl Runtime,
     precommits: &[SectorPreCommitOnChainInfo],
@@ -4763,6 +5652,7 @@ fn validate_precommits(
 }
 
 // Validates a batch of sector sealing proofs.
+#[cfg(feature = "actor")]
 fn validate_seal_proofs(
     seal_proof_type: RegisteredSealProof,
     proofs: &[RawBytes],
@@ -4784,6 +5674,7 @@ fn validate_seal_proofs(
     Ok(())
 }
 
+#[cfg(feature = "actor")]
 fn validate_seal_aggregate_proof(
     proof: &RawBytes,
     sector_count: u64,
@@ -4815,6 +5706,7 @@ fn validate_seal_aggregate_proof(
     Ok(())
 }
 
+#[cfg(feature = "actor")]
 fn verify_aggregate_seal(
     rt: &impl Runtime,
     proof_inputs: &[SectorSealProofInput],
@@ -4837,6 +5729,7 @@ fn verify_aggregate_seal(
 }
 
 // Compute and burn the aggregate network fee.
+#[cfg(feature = "actor")]
 fn pay_aggregate_seal_proof_fee(
     rt: &impl Runtime,
     aggregate_size: usize,
@@ -4859,6 +5752,7 @@ fn pay_aggregate_seal_proof_fee(
     state.check_balance_invariants(&rt.current_balance()).map_err(balance_invariants_broken)
 }
 
+#[cfg(feature = "actor")]
 fn verify_deals(
     rt: &impl Runtime,
     sectors: &[ext::market::SectorDeals],
@@ -4882,8 +5776,34 @@ fn verify_deals(
     ))?)
 }
 
+/// Publishes storage deals with this actor as their provider, requiring that all are published.
+#[cfg(feature = "actor")]
+fn publish_deals(rt: &impl Runtime, deals: RawBytes) -> Result<Vec<DealID>, ActorError> {
+    let (proposals,): (Vec<IgnoredAny>,) = deals
+        .deserialize()
+        .map_err(|e| actor_error!(serialization, "failed to decode deal proposals: {}", e))?;
+    let ret: ext::market::PublishStorageDealsReturn =
+        deserialize_block(extract_send_result(rt.send_simple(
+            &STORAGE_MARKET_ACTOR_ADDR,
+            ext::market::PUBLISH_STORAGE_DEALS_METHOD,
+            Some(IpldBlock { codec: DAG_CBOR, data: deals.into() }),
+            TokenAmount::zero(),
+        ))?)?;
+
+    if ret.ids.len() != proposals.len() {
+        return Err(actor_error!(
+            illegal_argument,
+            "only {} of {} deals published, all must be valid to onboard a sector",
+            ret.ids.len(),
+            proposals.len()
+        ));
+    }
+    Ok(ret.ids)
+}
+
 /// Requests the current epoch target block reward from the reward actor.
 /// return value includes reward, smoothed estimate of reward, and baseline power
+#[cfg(feature = "actor")]
 fn request_current_epoch_block_reward(
     rt: &impl Runtime,
 ) -> Result<ThisEpochRewardReturn, ActorError> {
@@ -4899,6 +5819,7 @@ fn request_current_epoch_block_reward(
 }
 
 /// Requests the current network total power and pledge from the power actor.
+#[cfg(feature = "actor")]
 fn request_current_total_power(
     rt: &impl Runtime,
 ) -> Result<ext::power::CurrentTotalPowerReturn, ActorError> {
@@ -4915,6 +5836,7 @@ fn request_current_total_power(
 
 /// Resolves an address to an ID address and verifies that it is address of an account actor with an associated BLS key.
 /// The worker must be BLS since the worker key will be used alongside a BLS-VRF.
+#[cfg(feature = "actor")]
 fn resolve_worker_address(rt: &impl Runtime, raw: Address) -> Result<ActorID, ActorError> {
     let resolved = rt
         .resolve_address(&raw)
@@ -4923,7 +5845,7 @@ fn resolve_worker_address(rt: &impl Runtime, raw: Address) -> Result<ActorID, Ac
     let worker_code = rt
         .get_actor_code_cid(&resolved)
         .ok_or_else(|| actor_error!(illegal_argument, "no code for address: {}", resolved))?;
-    if rt.resolve_builtin_actor_type(&worker_code) != Some(Type::Account) {
+    if !rt.resolve_builtin_actor_type_checked(&worker_code).is_native_account() {
         return Err(actor_error!(
             illegal_argument,
             "worker actor type must be an account, was {}",
@@ -4950,6 +5872,15 @@ fn resolve_worker_address(rt: &impl Runtime, raw: Address) -> Result<ActorID, Ac
     Ok(resolved)
 }
 
+/// Returns the portion of a penalty paid out of the fee debt repaid in the same call.
+/// Penalties are paid ahead of any fee debt the miner already owed, so that debt is never
+/// reported as withheld from a new penalty.
+#[cfg(feature = "actor")]
+fn penalty_paid(penalty: &TokenAmount, repaid: &TokenAmount) -> TokenAmount {
+    std::cmp::min(penalty, repaid).clone()
+}
+
+#[cfg(feature = "actor")]
 fn burn_funds(rt: &impl Runtime, amount: TokenAmount) -> Result<(), ActorError> {
     log::debug!("storage provder {} burning {}", rt.message().receiver(), amount);
     if amount.is_positive() {
@@ -4958,6 +5889,7 @@ fn burn_funds(rt: &impl Runtime, amount: TokenAmount) -> Result<(), ActorError>
     Ok(())
 }
 
+#[cfg(feature = "actor")]
 fn notify_pledge_changed(rt: &impl Runtime, pledge_delta: &TokenAmount) -> Result<(), ActorError> {
     if !pledge_delta.is_zero() {
         extract_send_result(rt.send_simple(
@@ -4970,6 +5902,7 @@ fn notify_pledge_changed(rt: &impl Runtime, pledge_delta: &TokenAmount) -> Resul
     Ok(())
 }
 
+#[cfg(feature = "actor")]
 fn get_claims(
     rt: &impl Runtime,
     ids: &[ext::verifreg::ClaimID],
@@ -4993,6 +5926,7 @@ fn get_claims(
 
 /// Assigns proving period offset randomly in the range [0, WPoStProvingPeriod) by hashing
 /// the actor's address and current epoch.
+#[cfg(feature = "actor")]
 fn assign_proving_period_offset(
     policy: &Policy,
     addr: Address,
@@ -5014,6 +5948,7 @@ fn assign_proving_period_offset(
 /// Computes the epoch at which a proving period should start such that it is greater than the current epoch, and
 /// has a defined offset from being an exact multiple of WPoStProvingPeriod.
 /// A miner is exempt from Winow PoSt until the first full proving period starts.
+#[cfg(feature = "actor")]
 fn current_proving_period_start(
     policy: &Policy,
     current_epoch: ChainEpoch,
@@ -5030,6 +5965,7 @@ fn current_proving_period_start(
     current_epoch - period_progress
 }
 
+#[cfg(feature = "actor")]
 fn current_deadline_index(
     policy: &Policy,
     current_epoch: ChainEpoch,
@@ -5041,6 +5977,7 @@ fn current_deadline_index(
 /// Computes deadline information for a fault or recovery declaration.
 /// If the deadline has not yet elapsed, the declaration is taken as being for the current proving period.
 /// If the deadline has elapsed, it's instead taken as being for the next proving period after the current epoch.
+#[cfg(feature = "actor")]
 fn declaration_deadline_info(
     policy: &Policy,
     period_start: ChainEpoch,
@@ -5061,6 +5998,7 @@ fn declaration_deadline_info(
 }
 
 /// Checks that a fault or recovery declaration at a specific deadline is outside the exclusion window for the deadline.
+#[cfg(feature = "actor")]
 fn validate_fr_declaration_deadline(deadline: &DeadlineInfo) -> anyhow::Result<()> {
     if deadline.fault_cutoff_passed() {
         Err(anyhow!("late fault or recovery declaration"))
@@ -5082,6 +6020,7 @@ fn validate_partition_contains_sectors(
     }
 }
 
+#[cfg(feature = "actor")]
 fn consensus_fault_active(info: &MinerInfo, curr_epoch: ChainEpoch) -> bool {
     // For penalization period to last for exactly finality epochs
     // consensus faults are active until currEpoch exceeds ConsensusFaultElapsed
@@ -5102,6 +6041,7 @@ pub fn power_for_sectors(sector_size: SectorSize, sectors: &[SectorOnChainInfo])
     PowerPair { raw: BigInt::from(sector_size as u64) * BigInt::from(sectors.len()), qa }
 }
 
+#[cfg(feature = "actor")]
 fn get_miner_info<BS>(store: &BS, state: &State) -> Result<MinerInfo, ActorError>
 where
     BS: Blockstore,
@@ -5111,6 +6051,7 @@ where
         .map_err(|e| e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "could not read miner info"))
 }
 
+#[cfg(feature = "actor")]
 fn process_pending_worker(
     info: &mut MinerInfo,
     rt: &impl Runtime,
@@ -5134,6 +6075,63 @@ fn process_pending_worker(
         .map_err(|e| e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to save miner info"))
 }
 
+/// Clears a pending owner change once it can no longer be confirmed.
+#[cfg(feature = "actor")]
+fn process_expired_owner_change(
+    info: &mut MinerInfo,
+    rt: &impl Runtime,
+    state: &mut State,
+) -> Result<(), ActorError> {
+    if info.pending_owner_address.is_none() || rt.curr_epoch() < info.pending_owner_expiration {
+        return Ok(());
+    }
+
+    info.set_pending_owner_change(None);
+
+    state
+        .save_info(rt.store(), info)
+        .map_err(|e| e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to save miner info"))
+}
+
+/// The balances limiting a withdrawal, computed once vested funds are unlocked.
+/// Shared by WithdrawBalance and GetWithdrawableBalance, which previews it.
+#[cfg(feature = "actor")]
+struct WithdrawalLimits {
+    /// The actor balance less locked funds, pre-commit deposits, initial pledge and fee debt.
+    available_balance: TokenAmount,
+    /// The beneficiary's remaining quota, if the beneficiary is not the owner.
+    beneficiary_quota: Option<TokenAmount>,
+}
+
+#[cfg(feature = "actor")]
+impl WithdrawalLimits {
+    fn compute(
+        state: &State,
+        info: &MinerInfo,
+        balance: &TokenAmount,
+        curr_epoch: ChainEpoch,
+    ) -> Result<Self, ActorError> {
+        let available_balance = state.get_available_balance(balance).map_err(|e| {
+            actor_error!(illegal_state, "failed to calculate available balance: {}", e)
+        })?;
+        let beneficiary_quota = if info.beneficiary != info.owner {
+            Some(info.beneficiary_term.available(curr_epoch))
+        } else {
+            None
+        };
+        Ok(Self { available_balance, beneficiary_quota })
+    }
+
+    /// The most that may be withdrawn: the available balance, capped by the beneficiary's quota.
+    /// Negative if the available balance is.
+    fn max_withdrawal(&self) -> TokenAmount {
+        match &self.beneficiary_quota {
+            Some(quota) => std::cmp::min(&self.available_balance, quota).clone(),
+            None => self.available_balance.clone(),
+        }
+    }
+}
+
 /// Repays all fee debt and then verifies that the miner has amount needed to cover
 /// the pledge requirement after burning all fee debt.  If not aborts.
 /// Returns an amount that must be burnt by the actor.
@@ -5141,6 +6139,7 @@ fn process_pending_worker(
 /// may be slightly lower than the true amount. Computing vesting here would be
 /// almost always redundant since vesting is quantized to ~daily units.  Vesting
 /// will be at most one proving period old if computed in the cron callback.
+#[cfg(feature = "actor")]
 fn repay_debts_or_abort(rt: &impl Runtime, state: &mut State) -> Result<TokenAmount, ActorError> {
     let res = state.repay_debts(&rt.current_balance()).map_err(|e| {
         e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "unlocked balance can not repay fee debt")
@@ -5149,6 +6148,7 @@ fn repay_debts_or_abort(rt: &impl Runtime, state: &mut State) -> Result<TokenAmo
     Ok(res)
 }
 
+#[cfg(feature = "actor")]
 fn check_control_addresses(policy: &Policy, control_addrs: &[Address]) -> Result<(), ActorError> {
     if control_addrs.len() > policy.max_control_addresses {
         return Err(actor_error!(
@@ -5162,6 +6162,7 @@ fn check_control_addresses(policy: &Policy, control_addrs: &[Address]) -> Result
     Ok(())
 }
 
+#[cfg(feature = "actor")]
 fn check_valid_post_proof_type(
     policy: &Policy,
     proof_type: RegisteredPoStProof,
@@ -5177,6 +6178,7 @@ fn check_valid_post_proof_type(
     }
 }
 
+#[cfg(feature = "actor")]
 fn check_peer_info(
     policy: &Policy,
     peer_id: &[u8],
@@ -5211,6 +6213,7 @@ fn check_peer_info(
     Ok(())
 }
 
+#[cfg(feature = "actor")]
 fn activate_new_sector_infos(
     rt: &impl Runtime,
     precommits: Vec<&SectorPreCommitOnChainInfo>,
@@ -5246,11 +6249,18 @@ fn activate_new_sector_infos(
             let deal_weight = &deal_spaces.unverified_space * duration;
             let verified_deal_weight = &deal_spaces.verified_space * duration;
 
+            let mut flags = SectorOnChainInfoFlags::SIMPLE_QA_POWER;
+            flags.set(
+                SectorOnChainInfoFlags::DURATION_MULTIPLIER,
+                policy.sector_duration_multiplier_enabled,
+            );
+
             let power = qa_power_for_weight(
                 info.sector_size,
                 duration,
                 &deal_weight,
                 &verified_deal_weight,
+                flags.contains(SectorOnChainInfoFlags::DURATION_MULTIPLIER),
             );
 
             let day_reward = expected_reward_for_power(
@@ -5296,7 +6306,7 @@ fn activate_new_sector_infos(
                 power_base_epoch: activation_epoch,
                 replaced_day_reward: TokenAmount::zero(),
                 sector_key_cid: None,
-                flags: SectorOnChainInfoFlags::SIMPLE_QA_POWER,
+                flags,
             };
 
             new_sector_numbers.push(new_sector_info.sector_number);
@@ -5356,6 +6366,7 @@ fn activate_new_sector_infos(
     Ok(())
 }
 
+#[cfg(feature = "actor")]
 pub struct SectorPiecesActivationInput {
     pub piece_manifests: Vec<PieceActivationManifest>,
     pub sector_expiry: ChainEpoch,
@@ -5365,6 +6376,7 @@ pub struct SectorPiecesActivationInput {
 }
 
 // Inputs for activating builtin market deals for one sector
+#[cfg(feature = "actor")]
 #[derive(Debug, Clone)]
 pub struct DealsActivationInput {
     pub deal_ids: Vec<DealID>,
@@ -5373,6 +6385,7 @@ pub struct DealsActivationInput {
     pub sector_type: RegisteredSealProof,
 }
 
+#[cfg(feature = "actor")]
 impl From<SectorPreCommitOnChainInfo> for DealsActivationInput {
     fn from(pci: SectorPreCommitOnChainInfo) -> DealsActivationInput {
         DealsActivationInput {
@@ -5384,6 +6397,7 @@ impl From<SectorPreCommitOnChainInfo> for DealsActivationInput {
     }
 }
 
+#[cfg(feature = "actor")]
 impl From<&UpdateAndSectorInfo<'_>> for DealsActivationInput {
     fn from(usi: &UpdateAndSectorInfo) -> DealsActivationInput {
         DealsActivationInput {
@@ -5396,6 +6410,7 @@ impl From<&UpdateAndSectorInfo<'_>> for DealsActivationInput {
 }
 
 // Data activation results for one sector
+#[cfg(feature = "actor")]
 #[derive(Clone)]
 struct DataActivationOutput {
     pub unverified_space: BigInt,
@@ -5406,6 +6421,7 @@ struct DataActivationOutput {
 }
 
 // Track information needed to update a sector info's data during ProveReplicaUpdate
+#[cfg(feature = "actor")]
 #[derive(Clone, Debug)]
 struct UpdateAndSectorInfo<'a> {
     update: &'a ReplicaUpdateInner,
@@ -5413,6 +6429,7 @@ struct UpdateAndSectorInfo<'a> {
 }
 
 // Inputs to state update for a single sector replica update.
+#[cfg(feature = "actor")]
 struct ReplicaUpdateStateInputs<'a> {
     deadline: u64,
     partition: u64,
@@ -5421,6 +6438,7 @@ struct ReplicaUpdateStateInputs<'a> {
 }
 
 // Summary of activated data for a replica update.
+#[cfg(feature = "actor")]
 struct ReplicaUpdateActivatedData {
     seal_cid: Cid,
     unverified_space: BigInt,
@@ -5433,6 +6451,7 @@ struct ReplicaUpdateActivatedData {
 // is calculated from the pieces and must match.
 // This method never returns CommDs in the output type; either the caller provided
 // them and they are correct, or the caller did not provide anything that needs checking.
+#[cfg(feature = "actor")]
 fn activate_sectors_pieces(
     rt: &impl Runtime,
     activation_inputs: Vec<SectorPiecesActivationInput>,
@@ -5521,6 +6540,7 @@ fn activate_sectors_pieces(
 /// Deals and claims are grouped by sectors
 /// Successfully activated sectors have their DealSpaces returned
 /// Failure to claim datacap for any verified deal results in the whole batch failing
+#[cfg(feature = "actor")]
 fn activate_sectors_deals(
     rt: &impl Runtime,
     activation_infos: &[DealsActivationInput],
@@ -5629,6 +6649,7 @@ fn activate_sectors_deals(
     Ok((batch_activation_res.activation_results, activation_and_claim_results))
 }
 
+#[cfg(feature = "actor")]
 fn batch_claim_allocations(
     rt: &impl Runtime,
     verified_claims: Vec<ext::verifreg::SectorAllocationClaims>,
@@ -5663,6 +6684,7 @@ fn batch_claim_allocations(
     Ok(claim_res)
 }
 
+#[cfg(feature = "actor")]
 fn unsealed_cid_from_pieces(
     rt: &impl Runtime,
     pieces: &[PieceActivationManifest],
@@ -5683,6 +6705,7 @@ fn unsealed_cid_from_pieces(
 }
 
 // Network inputs to calculation of sector pledge and associated parameters.
+#[cfg(feature = "actor")]
 struct NetworkPledgeInputs {
     pub network_qap: FilterEstimate,
     pub network_baseline: StoragePower,
@@ -5691,6 +6714,7 @@ struct NetworkPledgeInputs {
 }
 
 // Note: probably better to push this one level down into state
+#[cfg(feature = "actor")]
 fn balance_invariants_broken(e: Error) -> ActorError {
     ActorError::unchecked(
         ERR_BALANCE_INVARIANTS_BROKEN,
@@ -5698,6 +6722,7 @@ fn balance_invariants_broken(e: Error) -> ActorError {
     )
 }
 
+#[cfg(feature = "actor")]
 impl ActorCode for Actor {
     type Methods = Method;
 
@@ -5705,6 +6730,11 @@ impl ActorCode for Actor {
         "StorageMiner"
     }
 
+    fil_actors_runtime::actor_info! {
+        interface_revisions: [1],
+        features: [],
+    }
+
     actor_dispatch! {
         Constructor => constructor,
         ControlAddresses => control_addresses,
@@ -5739,13 +6769,26 @@ impl ActorCode for Actor {
         IsControllingAddressExported => is_controlling_address,
         GetSectorSizeExported => get_sector_size,
         GetAvailableBalanceExported => get_available_balance,
+        GetWithdrawableBalanceExported => get_withdrawable_balance,
         GetVestingFundsExported => get_vesting_funds,
         GetPeerIDExported => get_peer_id,
         GetMultiaddrsExported => get_multiaddresses,
+        GetMinerEssentialsExported => get_miner_essentials,
+        EstimateTerminationFeeExported => estimate_termination_fee,
+        PreviewSkippedFaultsExported => preview_skipped_faults,
+        ListSectorsExported => list_sectors,
+        CancelOwnerChangeExported => cancel_owner_change,
+        GetPendingOwnerChangeExported => get_pending_owner_change,
+        AuthorizeProverExported => authorize_prover,
+        RevokeProverExported => revoke_prover,
+        GetProverAuthorizationsExported => get_prover_authorizations,
         ProveCommitSectors3 => prove_commit_sectors3,
         ProveReplicaUpdates3 => prove_replica_updates3,
+        OnboardSectorWithDeals => onboard_sector_with_deals,
+        CompactSectorsAcrossDeadlines => compact_sectors_across_deadlines,
+        OnVerifiedClaimsExpired => on_verified_claims_expired,
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "actor"))]
 mod internal_tests;
@@ -4,8 +4,8 @@
 use std::cmp::{self, max};
 
 use fil_actors_runtime::network::EPOCHS_IN_DAY;
-use fil_actors_runtime::reward::math::PRECISION;
-use fil_actors_runtime::reward::{smooth, FilterEstimate};
+pub use fil_actors_runtime::reward::expected_reward_for_power;
+use fil_actors_runtime::reward::FilterEstimate;
 use fil_actors_runtime::EXPECTED_LEADERS_PER_EPOCH;
 use fvm_shared::bigint::{BigInt, Integer};
 use fvm_shared::clock::ChainEpoch;
@@ -72,36 +72,13 @@ const TERMINATION_PENALTY_LOWER_BOUND_PROJECTIONS_PERIOD: ChainEpoch = (EPOCHS_I
 // Maximum number of lifetime days penalized when a sector is terminated.
 pub const TERMINATION_LIFETIME_CAP: ChainEpoch = 140;
 
+// Days of expected block reward charged beyond the reward earned when a young committed capacity
+// sector is removed.
+pub const CC_FAST_REMOVAL_PENALTY_FLOOR_DAYS: ChainEpoch = 3;
+
 // Multiplier of whole per-winner rewards for a consensus fault penalty.
 const CONSENSUS_FAULT_FACTOR: u64 = 5;
 
-/// The projected block reward a sector would earn over some period.
-/// Also known as "BR(t)".
-/// BR(t) = ProjectedRewardFraction(t) * SectorQualityAdjustedPower
-/// ProjectedRewardFraction(t) is the sum of estimated reward over estimated total power
-/// over all epochs in the projection period [t t+projectionDuration]
-pub fn expected_reward_for_power(
-    reward_estimate: &FilterEstimate,
-    network_qa_power_estimate: &FilterEstimate,
-    qa_sector_power: &StoragePower,
-    projection_duration: ChainEpoch,
-) -> TokenAmount {
-    let network_qa_power_smoothed = network_qa_power_estimate.estimate();
-
-    if network_qa_power_smoothed.is_zero() {
-        return TokenAmount::from_atto(reward_estimate.estimate());
-    }
-
-    let expected_reward_for_proving_period = smooth::extrapolated_cum_sum_of_ratio(
-        projection_duration,
-        0,
-        reward_estimate,
-        network_qa_power_estimate,
-    );
-    let br128 = qa_sector_power * expected_reward_for_proving_period; // Q.0 * Q.128 => Q.128
-    TokenAmount::from_atto(std::cmp::max(br128 >> PRECISION, Default::default()))
-}
-
 pub mod detail {
     use super::*;
 
@@ -209,6 +186,27 @@ pub fn pledge_penalty_for_termination(
     )
 }
 
+/// Penalty for the termination of a committed capacity sector within the fast removal age of its
+/// activation: the full termination penalty pro-rated to the sector's age.
+/// The fee is never less than the block reward the sector is expected to have earned plus
+/// CC_FAST_REMOVAL_PENALTY_FLOOR_DAYS of its block reward, so committing and removing a sector is
+/// never profitable, nor more than the full termination penalty.
+pub fn pledge_penalty_for_cc_fast_removal(
+    termination_penalty: &TokenAmount,
+    day_reward: &TokenAmount,
+    sector_age: ChainEpoch,
+    fast_removal_max_age: ChainEpoch,
+) -> TokenAmount {
+    if sector_age >= fast_removal_max_age {
+        return termination_penalty.clone();
+    }
+    let sector_age = max(sector_age, 0);
+    let pro_rated = (termination_penalty * sector_age).div_floor(fast_removal_max_age);
+    let floor = (day_reward * (sector_age + CC_FAST_REMOVAL_PENALTY_FLOOR_DAYS * EPOCHS_IN_DAY))
+        .div_floor(EPOCHS_IN_DAY);
+    cmp::min(termination_penalty.clone(), max(pro_rated, floor))
+}
+
 // The penalty for optimistically proving a sector with an invalid window PoSt.
 pub fn pledge_penalty_for_invalid_windowpost(
     reward_estimate: &FilterEstimate,
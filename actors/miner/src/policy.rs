@@ -12,9 +12,11 @@ use fvm_shared::clock::ChainEpoch;
 use fvm_shared::commcid::{FIL_COMMITMENT_SEALED, POSEIDON_BLS12_381_A1_FC1};
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::sector::{RegisteredPoStProof, RegisteredSealProof, SectorSize, StoragePower};
+use fvm_shared::IDENTITY_HASH;
 use lazy_static::lazy_static;
+use num_traits::Zero;
 
-use super::types::SectorOnChainInfo;
+use super::types::{SectorOnChainInfo, SectorOnChainInfoFlags};
 use super::{PowerPair, BASE_REWARD_FOR_DISPUTED_WINDOW_POST};
 
 /// Precision used for making QA power calculations
@@ -46,6 +48,18 @@ pub fn is_sealed_sector(c: &Cid) -> bool {
         && c.hash().size() == 32
 }
 
+/// Maximum length in bytes of the CID of a data handoff manifest attested to when terminating
+/// sectors.
+pub const MAX_HANDOFF_MANIFEST_CID_LEN: usize = 64;
+
+/// Checks that a data handoff manifest CID links to a block of bounded size, rather than inlining
+/// arbitrary data.
+pub fn is_valid_handoff_manifest(c: &Cid) -> bool {
+    c.version() == Version::V1
+        && c.hash().code() != IDENTITY_HASH
+        && c.to_bytes().len() <= MAX_HANDOFF_MANIFEST_CID_LEN
+}
+
 /// List of proof types which can be used when creating new miner actors
 pub fn can_pre_commit_seal_proof(policy: &Policy, proof: RegisteredSealProof) -> bool {
     policy.valid_pre_commit_proof_type.contains(proof)
@@ -141,21 +155,82 @@ pub fn qa_power_max(size: SectorSize) -> StoragePower {
         .div_floor(&QUALITY_BASE_MULTIPLIER)
 }
 
+/// Sector term up to which no duration multiplier is applied.
+pub const DURATION_MULTIPLIER_BASE_TERM: ChainEpoch = EPOCHS_IN_YEAR;
+
+/// Sector term from which the maximum duration multiplier is applied.
+pub const DURATION_MULTIPLIER_MAX_TERM: ChainEpoch = 5 * EPOCHS_IN_YEAR;
+
+/// Maximum bonus of the duration multiplier, as a fraction of the sector's power (i.e. 1.5x).
+pub const DURATION_MULTIPLIER_MAX_BONUS_NUM: i64 = 1;
+pub const DURATION_MULTIPLIER_MAX_BONUS_DENOM: i64 = 2;
+
+/// Returns the multiplier applied to the QA power of a sector committed for a duration,
+/// scaled up by SECTOR_QUALITY_PRECISION.
+/// The multiplier is one up to DURATION_MULTIPLIER_BASE_TERM and grows linearly to its maximum
+/// at DURATION_MULTIPLIER_MAX_TERM.
+pub fn duration_multiplier(duration: ChainEpoch) -> BigInt {
+    let unit = BigInt::from(1) << SECTOR_QUALITY_PRECISION;
+    if duration <= DURATION_MULTIPLIER_BASE_TERM {
+        return unit;
+    }
+    let bonus_term =
+        cmp::min(duration, DURATION_MULTIPLIER_MAX_TERM) - DURATION_MULTIPLIER_BASE_TERM;
+    let bonus = (BigInt::from(bonus_term * DURATION_MULTIPLIER_MAX_BONUS_NUM)
+        << SECTOR_QUALITY_PRECISION)
+        .div_floor(&BigInt::from(
+            (DURATION_MULTIPLIER_MAX_TERM - DURATION_MULTIPLIER_BASE_TERM)
+                * DURATION_MULTIPLIER_MAX_BONUS_DENOM,
+        ));
+    unit + bonus
+}
+
+/// Returns maximum achievable QA power, including the duration multiplier if enabled.
+pub fn qa_power_max_for_policy(policy: &Policy, size: SectorSize) -> StoragePower {
+    let power = qa_power_max(size);
+    if policy.sector_duration_multiplier_enabled {
+        (power * duration_multiplier(DURATION_MULTIPLIER_MAX_TERM)) >> SECTOR_QUALITY_PRECISION
+    } else {
+        power
+    }
+}
+
 /// Returns the power for a sector size and weight.
+/// If `with_duration_multiplier` is set, the power is scaled by the duration multiplier.
 pub fn qa_power_for_weight(
     size: SectorSize,
     duration: ChainEpoch,
     deal_weight: &DealWeight,
     verified_weight: &DealWeight,
+    with_duration_multiplier: bool,
 ) -> StoragePower {
     let quality = quality_for_weight(size, duration, deal_weight, verified_weight);
-    (BigInt::from(size as u64) * quality) >> SECTOR_QUALITY_PRECISION
+    let power = (BigInt::from(size as u64) * quality) >> SECTOR_QUALITY_PRECISION;
+    if with_duration_multiplier {
+        (power * duration_multiplier(duration)) >> SECTOR_QUALITY_PRECISION
+    } else {
+        power
+    }
 }
 
 /// Returns the quality-adjusted power for a sector.
 pub fn qa_power_for_sector(size: SectorSize, sector: &SectorOnChainInfo) -> StoragePower {
     let duration = sector.expiration - sector.power_base_epoch;
-    qa_power_for_weight(size, duration, &sector.deal_weight, &sector.verified_deal_weight)
+    qa_power_for_weight(
+        size,
+        duration,
+        &sector.deal_weight,
+        &sector.verified_deal_weight,
+        sector.flags.contains(SectorOnChainInfoFlags::DURATION_MULTIPLIER),
+    )
+}
+
+/// Whether a sector terminated at an epoch is a committed capacity sector young enough for its
+/// termination fee to be pro-rated to its age.
+pub fn is_cc_fast_removal(policy: &Policy, sector: &SectorOnChainInfo, epoch: ChainEpoch) -> bool {
+    sector.deal_weight.is_zero()
+        && sector.verified_deal_weight.is_zero()
+        && epoch - sector.activation < policy.cc_sector_fast_removal_max_age
 }
 
 /// Determine maximum number of deal miner's sector can hold
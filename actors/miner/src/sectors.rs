@@ -5,6 +5,7 @@ use std::collections::BTreeSet;
 
 use anyhow::anyhow;
 use cid::Cid;
+use fil_actors_runtime::query::ListRequest;
 use fil_actors_runtime::runtime::policy_constants::MAX_SECTOR_NUMBER;
 use fil_actors_runtime::{actor_error, ActorDowncast, ActorError, Array, AsActorError};
 use fvm_ipld_amt::Error as AmtError;
@@ -46,6 +47,26 @@ impl<'db, BS: Blockstore> Sectors<'db, BS> {
         Ok(sector_infos)
     }
 
+    /// Lists the sectors matching a request's filter among the next page of sectors,
+    /// in sector number order.
+    /// Returns the matching sectors, and the sector number at which to resume if any remain.
+    pub fn list(
+        &self,
+        request: &ListRequest<SectorNumber>,
+    ) -> Result<(Vec<SectorOnChainInfo>, Option<SectorNumber>), ActorError> {
+        let mut sectors = Vec::new();
+        let (_, next) = self
+            .amt
+            .for_each_ranged(request.cursor, Some(request.limit as usize), |_, sector| {
+                if request.matches(sector) {
+                    sectors.push(sector.clone());
+                }
+                Ok(())
+            })
+            .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to iterate sectors")?;
+        Ok((sectors, next))
+    }
+
     pub fn get(
         &self,
         sector_number: SectorNumber,
@@ -3,6 +3,7 @@
 
 use std::borrow::Borrow;
 use std::cmp;
+use std::collections::BTreeMap;
 use std::ops::Neg;
 
 use anyhow::{anyhow, Error};
@@ -42,6 +43,10 @@ use super::{
 pub type PreCommitMap<BS> = Map2<BS, SectorNumber, SectorPreCommitOnChainInfo>;
 pub const PRECOMMIT_CONFIG: Config = Config { bit_width: HAMT_BIT_WIDTH, ..DEFAULT_HAMT_CONFIG };
 
+pub type ExpiredClaimSpaceMap<BS> = Map2<BS, u64, Vec<SectorExpiredClaimSpace>>;
+pub const EXPIRED_CLAIM_SPACE_CONFIG: Config =
+    Config { bit_width: HAMT_BIT_WIDTH, ..DEFAULT_HAMT_CONFIG };
+
 const PRECOMMIT_EXPIRY_AMT_BITWIDTH: u32 = 6;
 pub const SECTORS_AMT_BITWIDTH: u32 = 5;
 
@@ -112,6 +117,12 @@ pub struct State {
 
     // True when miner cron is active, false otherwise
     pub deadline_cron_active: bool,
+
+    /// Space of verified claims that reached their maximum term while backing a sector,
+    /// to be removed from the sector's power at its next deadline cron.
+    /// Grouped by the index of the sectors' deadline, so each cron loads only its own.
+    /// Map, HAMT<u64, []SectorExpiredClaimSpace>
+    pub expired_claim_space: Cid,
 }
 
 #[derive(PartialEq, Eq)]
@@ -131,6 +142,8 @@ impl State {
     ) -> Result<Self, ActorError> {
         let empty_precommit_map =
             PreCommitMap::empty(store, PRECOMMIT_CONFIG, "precommits").flush()?;
+        let empty_expired_claim_space_map =
+            ExpiredClaimSpaceMap::flush_empty(store, EXPIRED_CLAIM_SPACE_CONFIG)?;
 
         let empty_precommits_cleanup_array =
             Array::<BitField, BS>::new_with_bit_width(store, PRECOMMIT_EXPIRY_AMT_BITWIDTH)
@@ -189,6 +202,7 @@ impl State {
             early_terminations: BitField::new(),
             deadline_cron_active: false,
             pre_committed_sectors_cleanup: empty_precommits_cleanup_array,
+            expired_claim_space: empty_expired_claim_space_map,
         })
     }
 
@@ -486,6 +500,125 @@ impl State {
         Ok(all_replaced)
     }
 
+    /// Records the space of verified claims that have reached their maximum term while backing
+    /// sectors, to be removed from the sectors' power at their next deadline cron.
+    pub fn record_expired_claim_space<BS: Blockstore>(
+        &mut self,
+        store: &BS,
+        expired: &[SectorExpiredClaimSpace],
+    ) -> anyhow::Result<()> {
+        let mut expired_space = ExpiredClaimSpaceMap::load(
+            store,
+            &self.expired_claim_space,
+            EXPIRED_CLAIM_SPACE_CONFIG,
+            "expired claim space",
+        )?;
+        for e in expired {
+            let (deadline_idx, _) = self.find_sector(store, e.sector_number)?;
+            let mut entries = expired_space.get(&deadline_idx)?.cloned().unwrap_or_default();
+            match entries.iter_mut().find(|entry| entry.sector_number == e.sector_number) {
+                Some(entry) => entry.space += e.space,
+                None => entries.push(e.clone()),
+            }
+            expired_space.set(&deadline_idx, entries)?;
+        }
+        self.expired_claim_space = expired_space.flush()?;
+        Ok(())
+    }
+
+    /// Removes the power of expired verified claims from the active sectors in a deadline,
+    /// replacing each such sector with the result of `downgrade` applied to it and its
+    /// expired claim space.
+    /// Records for sectors that are no longer live, or that have reached their expiration,
+    /// are discarded. Records for faulty or unproven sectors remain until the sectors are active.
+    /// Returns the change in power and pledge requirement.
+    pub fn downgrade_expired_claim_sectors<BS: Blockstore>(
+        &mut self,
+        policy: &Policy,
+        store: &BS,
+        deadline_idx: u64,
+        current_epoch: ChainEpoch,
+        sector_size: SectorSize,
+        downgrade: impl Fn(&SectorOnChainInfo, u64) -> SectorOnChainInfo,
+    ) -> anyhow::Result<(PowerPair, TokenAmount)> {
+        let mut expired_space = ExpiredClaimSpaceMap::load(
+            store,
+            &self.expired_claim_space,
+            EXPIRED_CLAIM_SPACE_CONFIG,
+            "expired claim space",
+        )?;
+        let entries = match expired_space.delete(&deadline_idx)? {
+            Some(entries) => entries,
+            None => return Ok((PowerPair::zero(), TokenAmount::zero())),
+        };
+        let space_by_sector: BTreeMap<SectorNumber, u64> =
+            entries.iter().map(|e| (e.sector_number, e.space)).collect();
+        let pending = BitField::try_from_bits(space_by_sector.keys().copied())?;
+
+        let mut sectors = Sectors::load(store, &self.sectors)?;
+        let mut deadlines = self.load_deadlines(store)?;
+        let mut deadline = deadlines.load_deadline(store, deadline_idx)?;
+        let mut partitions = deadline.partitions_amt(store)?;
+        let quant = self.quant_spec_for_deadline(policy, deadline_idx);
+
+        let mut found_by_partition = Vec::new();
+        partitions.for_each(|partition_idx, partition| {
+            let found = &partition.live_sectors() & &pending;
+            if !found.is_empty() {
+                found_by_partition.push((partition_idx, found));
+            }
+            Ok(())
+        })?;
+
+        let mut power_delta = PowerPair::zero();
+        let mut pledge_delta = TokenAmount::zero();
+        let mut waiting = Vec::new();
+        for (partition_idx, found) in found_by_partition {
+            let mut partition = partitions.get(partition_idx)?.cloned().ok_or_else(|| {
+                anyhow!("no partition {} in deadline {}", partition_idx, deadline_idx)
+            })?;
+            let active = &found & &partition.active_sectors();
+            for sector_number in (&found - &active).iter() {
+                waiting.push(SectorExpiredClaimSpace {
+                    sector_number,
+                    space: space_by_sector[&sector_number],
+                });
+            }
+
+            let mut old_sectors = Vec::new();
+            let mut new_sectors = Vec::new();
+            for sector in sectors.load_sector(&active)? {
+                if sector.expiration > current_epoch {
+                    new_sectors.push(downgrade(&sector, space_by_sector[&sector.sector_number]));
+                    old_sectors.push(sector);
+                }
+            }
+            if !old_sectors.is_empty() {
+                let (partition_power_delta, partition_pledge_delta) = partition.replace_sectors(
+                    store,
+                    &old_sectors,
+                    &new_sectors,
+                    sector_size,
+                    quant,
+                )?;
+                power_delta += &partition_power_delta;
+                pledge_delta += partition_pledge_delta;
+                sectors.store(new_sectors)?;
+                partitions.set(partition_idx, partition)?;
+            }
+        }
+        if !waiting.is_empty() {
+            expired_space.set(&deadline_idx, waiting)?;
+        }
+
+        deadline.partitions = partitions.flush()?;
+        deadlines.update_deadline(policy, store, deadline_idx, &deadline)?;
+        self.save_deadlines(store, deadlines)?;
+        self.sectors = sectors.amt.flush()?;
+        self.expired_claim_space = expired_space.flush()?;
+        Ok((power_delta, pledge_delta))
+    }
+
     /// Assign new sectors to deadlines.
     pub fn assign_sectors_to_deadlines<BS: Blockstore>(
         &mut self,
@@ -1239,7 +1372,8 @@ pub struct MinerInfo {
     pub consensus_fault_elapsed: ChainEpoch,
 
     /// A proposed new owner account for this miner.
-    /// Must be confirmed by a message from the pending address itself.
+    /// Must be confirmed by a message from the pending address itself before
+    /// `pending_owner_expiration`.
     pub pending_owner_address: Option<Address>,
 
     /// Account for receive miner benefits, withdraw on miner must send to this address,
@@ -1252,6 +1386,13 @@ pub struct MinerInfo {
 
     /// A proposal new beneficiary message for this miner
     pub pending_beneficiary_term: Option<PendingBeneficiaryChange>,
+
+    /// Epoch from which the pending owner change can no longer be confirmed,
+    /// or EPOCH_UNDEFINED if there is none.
+    pub pending_owner_expiration: ChainEpoch,
+
+    /// Provers authorized to submit Window PoSts for this miner, without other control rights.
+    pub prover_authorizations: Vec<ProverAuthorization>,
 }
 
 impl MinerInfo {
@@ -1287,6 +1428,32 @@ impl MinerInfo {
             window_post_partition_sectors,
             consensus_fault_elapsed: EPOCH_UNDEFINED,
             pending_owner_address: None,
+            pending_owner_expiration: EPOCH_UNDEFINED,
+            prover_authorizations: Vec::new(),
+        })
+    }
+
+    /// Returns the pending owner change, whether or not it has expired.
+    pub fn pending_owner_change(&self) -> Option<PendingOwnerChange> {
+        self.pending_owner_address.map(|new_owner| PendingOwnerChange {
+            new_owner,
+            expiration: self.pending_owner_expiration,
         })
     }
+
+    /// Returns the pending owner change, if it can still be confirmed at an epoch.
+    pub fn pending_owner_change_at(&self, epoch: ChainEpoch) -> Option<PendingOwnerChange> {
+        self.pending_owner_change().filter(|change| epoch < change.expiration)
+    }
+
+    /// Records a proposed owner change, replacing any pending one, or withdraws it if `None`.
+    pub fn set_pending_owner_change(&mut self, change: Option<PendingOwnerChange>) {
+        self.pending_owner_address = change.as_ref().map(|c| c.new_owner);
+        self.pending_owner_expiration = change.map_or(EPOCH_UNDEFINED, |c| c.expiration);
+    }
+
+    /// Returns the provers authorized to submit Window PoSts at an epoch.
+    pub fn active_provers(&self, epoch: ChainEpoch) -> impl Iterator<Item = &Address> {
+        self.prover_authorizations.iter().filter(move |a| a.is_active(epoch)).map(|a| &a.prover)
+    }
 }
@@ -1,7 +1,7 @@
 use crate::{
-    power_for_sectors, BitFieldQueue, Deadline, ExpirationQueue, MinerInfo, Partition, PowerPair,
-    PreCommitMap, QuantSpec, SectorOnChainInfo, SectorOnChainInfoFlags, Sectors, State,
-    NO_QUANTIZATION, PRECOMMIT_CONFIG,
+    power_for_sectors, BitFieldQueue, Deadline, ExpirationQueue, ExpiredClaimSpaceMap, MinerInfo,
+    Partition, PowerPair, PreCommitMap, QuantSpec, SectorOnChainInfo, SectorOnChainInfoFlags,
+    Sectors, State, EXPIRED_CLAIM_SPACE_CONFIG, NO_QUANTIZATION, PRECOMMIT_CONFIG,
 };
 use fil_actors_runtime::runtime::Policy;
 use fil_actors_runtime::{DealWeight, MessageAccumulator};
@@ -9,7 +9,7 @@ use fvm_ipld_bitfield::BitField;
 use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::CborStore;
 use fvm_shared::address::Protocol;
-use fvm_shared::clock::ChainEpoch;
+use fvm_shared::clock::{ChainEpoch, EPOCH_UNDEFINED};
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::sector::{RegisteredPoStProof, SectorNumber, SectorSize};
 use num_traits::Zero;
@@ -89,6 +89,13 @@ pub fn check_state_invariants<BS: Blockstore>(
                         },
                     );
                 }
+                acc.require(
+                    !sector.flags.contains(SectorOnChainInfoFlags::DURATION_MULTIPLIER)
+                        || sector.flags.contains(SectorOnChainInfoFlags::SIMPLE_QA_POWER),
+                    format!(
+                        "duration multiplier applied to legacy QA power sector {sector_number}"
+                    ),
+                );
                 acc.require(
                     sector.activation <= sector.power_base_epoch,
                     format!("invalid power base for {sector_number}"),
@@ -141,6 +148,8 @@ pub fn check_state_invariants<BS: Blockstore>(
         }
     };
 
+    check_expired_claim_space(policy, state, store, &all_sectors, &acc);
+
     (miner_summary, acc)
 }
 
@@ -190,6 +199,19 @@ fn check_miner_info(info: MinerInfo, acc: &MessageAccumulator) {
             format!("control address {} is not an ID address", address),
         )
     });
+    info.prover_authorizations.iter().for_each(|auth| {
+        acc.require(
+            auth.prover.protocol() == Protocol::ID,
+            format!("prover address {} is not an ID address", auth.prover),
+        );
+        acc.require(
+            auth.start < auth.expiration,
+            format!(
+                "prover {} authorization start {} is not before expiration {}",
+                auth.prover, auth.start, auth.expiration
+            ),
+        );
+    });
 
     if let Some(pending_worker_key) = info.pending_worker_key {
         acc.require(
@@ -220,6 +242,14 @@ fn check_miner_info(info: MinerInfo, acc: &MessageAccumulator) {
                 pending_owner_address, info.owner
             ),
         );
+    } else {
+        acc.require(
+            info.pending_owner_expiration == EPOCH_UNDEFINED,
+            format!(
+                "pending owner expiration {} is set with no pending owner",
+                info.pending_owner_expiration
+            ),
+        );
     }
 
     if let RegisteredPoStProof::Invalid(id) = info.window_post_proof_type {
@@ -312,6 +342,62 @@ fn check_miner_balances<BS: Blockstore>(
     }
 }
 
+fn check_expired_claim_space<BS: Blockstore>(
+    policy: &Policy,
+    state: &State,
+    store: &BS,
+    all_sectors: &BTreeMap<SectorNumber, SectorOnChainInfo>,
+    acc: &MessageAccumulator,
+) {
+    let expired_space = match ExpiredClaimSpaceMap::load(
+        store,
+        &state.expired_claim_space,
+        EXPIRED_CLAIM_SPACE_CONFIG,
+        "expired claim space",
+    ) {
+        Ok(expired_space) => expired_space,
+        Err(e) => {
+            acc.add(format!("error loading expired claim space: {e}"));
+            return;
+        }
+    };
+    let mut recorded = BTreeSet::new();
+    let ret = expired_space.for_each(|deadline_idx, entries| {
+        acc.require(
+            deadline_idx < policy.wpost_period_deadlines,
+            format!("expired claim space recorded for invalid deadline {deadline_idx}"),
+        );
+        acc.require(!entries.is_empty(), format!("empty expired claim space for {deadline_idx}"));
+        for entry in entries {
+            let sector_number = entry.sector_number;
+            acc.require(
+                recorded.insert(sector_number),
+                format!("expired claim space recorded twice for sector {sector_number}"),
+            );
+            acc.require(entry.space > 0, format!("no expired claim space for {sector_number}"));
+            // Records for sectors since terminated remain until their deadline's cron.
+            if let Some(sector) = all_sectors.get(&sector_number) {
+                acc.require(
+                    !sector.flags.contains(SectorOnChainInfoFlags::SIMPLE_QA_POWER),
+                    format!("expired claim space recorded for simple QA sector {sector_number}"),
+                );
+                match state.find_sector(store, sector_number) {
+                    Ok((found_idx, _)) => acc.require(
+                        found_idx == deadline_idx,
+                        format!(
+                            "expired claim space for sector {sector_number} recorded in deadline \
+                            {deadline_idx}, but sector is in {found_idx}"
+                        ),
+                    ),
+                    Err(e) => acc.add(format!("error finding sector {sector_number}: {e}")),
+                }
+            }
+        }
+        Ok(())
+    });
+    acc.require_no_error(ret, "error iterating expired claim space");
+}
+
 fn check_precommits<BS: Blockstore>(
     policy: &Policy,
     state: &State,
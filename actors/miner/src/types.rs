@@ -17,14 +17,17 @@ use fvm_shared::sector::{
     RegisteredUpdateProof, SectorNumber, SectorSize, StoragePower,
 };
 use fvm_shared::ActorID;
+use num_traits::Zero;
 use serde::{Deserialize, Serialize};
 
+use fil_actors_runtime::query::{Indexed, ListPage, ListRequest};
 use fil_actors_runtime::reward::FilterEstimate;
-use fil_actors_runtime::{BatchReturn, DealWeight};
+use fil_actors_runtime::{BatchReturn, DealWeight, SectorSet};
 
 use crate::commd::CompactCommD;
 use crate::ext::verifreg::AllocationID;
 use crate::ext::verifreg::ClaimID;
+use crate::PowerPair;
 
 use super::beneficiary::*;
 
@@ -127,6 +130,12 @@ pub struct SubmitWindowedPoStParams {
     pub chain_commit_epoch: ChainEpoch,
     /// The ticket randomness on the chain at the `chain_commit_epoch` on the chain this post is committed to.
     pub chain_commit_rand: Randomness,
+    /// Whether to declare recovered all faulty sectors in the proven partitions that are not skipped.
+    /// Such sectors must be included in the proof, and their power is restored on its verification,
+    /// as if their recovery had been declared with DeclareFaultsRecovered ahead of the deadline.
+    /// May be omitted by submitters predating automatic recovery, and defaults to false.
+    #[serde(default)]
+    pub auto_recover_faults: bool,
 }
 
 #[derive(Serialize_tuple, Deserialize_tuple)]
@@ -256,6 +265,11 @@ impl From<&ExpirationExtension> for ExpirationExtension2 {
 #[derive(Serialize_tuple, Deserialize_tuple)]
 pub struct TerminateSectorsParams {
     pub terminations: Vec<TerminationDeclaration>,
+    /// CID of a manifest describing where the terminated sectors' data has been handed off to,
+    /// recorded in an event for clients migrating their data away from this provider.
+    /// May be omitted by callers predating handoff manifests.
+    #[serde(default)]
+    pub handoff_manifest: Option<Cid>,
 }
 
 #[derive(Serialize_tuple, Deserialize_tuple)]
@@ -286,8 +300,8 @@ pub struct FaultDeclaration {
     pub deadline: u64,
     /// Partition index within the deadline containing the faulty sectors.
     pub partition: u64,
-    /// Sectors in the partition being declared faulty.
-    pub sectors: BitField,
+    /// Sectors in the partition being declared faulty, as a bitfield or list of ranges.
+    pub sectors: SectorSet,
 }
 
 #[derive(Serialize_tuple, Deserialize_tuple)]
@@ -301,8 +315,8 @@ pub struct RecoveryDeclaration {
     pub deadline: u64,
     /// Partition index within the deadline containing the recovered sectors.
     pub partition: u64,
-    /// Sectors in the partition being declared recovered.
-    pub sectors: BitField,
+    /// Sectors in the partition being declared recovered, as a bitfield or list of ranges.
+    pub sectors: SectorSet,
 }
 
 #[derive(Serialize_tuple, Deserialize_tuple)]
@@ -311,11 +325,31 @@ pub struct CompactPartitionsParams {
     pub partitions: BitField,
 }
 
+#[derive(Serialize_tuple, Deserialize_tuple)]
+pub struct CompactSectorsAcrossDeadlinesParams {
+    /// Partitions to empty, by deadline.
+    pub sources: Vec<CompactPartitionsParams>,
+    /// Deadline to which the live sectors of the removed partitions are moved.
+    pub dest_deadline: u64,
+}
+
 #[derive(Serialize_tuple, Deserialize_tuple)]
 pub struct CompactSectorNumbersParams {
     pub mask_sector_numbers: BitField,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct OnVerifiedClaimsExpiredParams {
+    pub sectors: Vec<SectorExpiredClaimSpace>,
+}
+
+/// Space of a sector's verified claims that have reached their maximum term.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct SectorExpiredClaimSpace {
+    pub sector_number: SectorNumber,
+    pub space: u64,
+}
+
 #[derive(Serialize_tuple, Deserialize_tuple)]
 pub struct ReportConsensusFaultParams {
     #[serde(with = "strict_bytes")]
@@ -344,6 +378,34 @@ pub struct WorkerKeyChange {
     pub effective_at: ChainEpoch,
 }
 
+/// A proposed change of owner, awaiting confirmation by the new owner.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct PendingOwnerChange {
+    /// Must be an ID address
+    pub new_owner: Address,
+    /// Epoch from which the change can no longer be confirmed.
+    pub expiration: ChainEpoch,
+}
+
+/// A prover authorized to submit Window PoSts on a miner's behalf, for a limited period.
+/// Unlike a control address, a prover may not send any other message to the miner.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ProverAuthorization {
+    /// Must be an ID address
+    pub prover: Address,
+    /// Epoch from which the prover may submit Window PoSts.
+    pub start: ChainEpoch,
+    /// Epoch from which the prover may no longer submit Window PoSts.
+    pub expiration: ChainEpoch,
+}
+
+impl ProverAuthorization {
+    /// Whether the prover may submit Window PoSts at an epoch.
+    pub fn is_active(&self, epoch: ChainEpoch) -> bool {
+        self.start <= epoch && epoch < self.expiration
+    }
+}
+
 #[derive(Debug, Default, PartialEq, Eq, Clone, Serialize_tuple, Deserialize_tuple)]
 pub struct PreCommitSectorParams {
     pub seal_proof: RegisteredSealProof,
@@ -386,6 +448,34 @@ pub struct SectorPreCommitInfo {
     pub unsealed_cid: CompactCommD,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Serialize_tuple, Deserialize_tuple)]
+pub struct OnboardSectorWithDealsParams {
+    /// CBOR-encoded storage market PublishStorageDealsParams, for the deals to be stored
+    /// in the sector. This actor must be the provider of every deal.
+    pub deals: RawBytes,
+    /// The sector to pre-commit with the published deals.
+    pub sector: OnboardingSectorInfo,
+}
+
+/// A sector ready to pre-commit, save for the IDs of its deals which are yet to be published.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Serialize_tuple, Deserialize_tuple)]
+pub struct OnboardingSectorInfo {
+    pub seal_proof: RegisteredSealProof,
+    pub sector_number: SectorNumber,
+    /// CommR
+    pub sealed_cid: Cid,
+    pub seal_rand_epoch: ChainEpoch,
+    pub expiration: ChainEpoch,
+    /// CommD
+    pub unsealed_cid: CompactCommD,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize_tuple, Deserialize_tuple)]
+pub struct OnboardSectorWithDealsReturn {
+    /// IDs of the published deals, in the order of the proposals.
+    pub deal_ids: Vec<DealID>,
+}
+
 /// Information stored on-chain for a pre-committed sector.
 #[derive(Debug, PartialEq, Eq, Clone, Serialize_tuple, Deserialize_tuple)]
 pub struct SectorPreCommitOnChainInfo {
@@ -435,6 +525,24 @@ bitflags::bitflags! {
     pub struct SectorOnChainInfoFlags: u32 {
         /// QA power mechanism introduced in FIP-0045
         const SIMPLE_QA_POWER = 0x1;
+        /// QA power scaled by the sector duration multiplier
+        const DURATION_MULTIPLIER = 0x2;
+    }
+}
+
+impl Indexed for SectorOnChainInfo {
+    const INDEXED_FIELDS: &'static [&'static str] =
+        &["activation", "expiration", "power-base-epoch", "has-deals", "verified"];
+
+    fn field(&self, name: &str) -> Option<i128> {
+        match name {
+            "activation" => Some(self.activation.into()),
+            "expiration" => Some(self.expiration.into()),
+            "power-base-epoch" => Some(self.power_base_epoch.into()),
+            "has-deals" => Some((!self.deal_weight.is_zero()).into()),
+            "verified" => Some((!self.verified_deal_weight.is_zero()).into()),
+            _ => None,
+        }
     }
 }
 
@@ -552,6 +660,32 @@ pub struct GetOwnerReturn {
     pub proposed: Option<Address>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct GetPendingOwnerChangeReturn {
+    pub pending: Option<PendingOwnerChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct AuthorizeProverParams {
+    pub prover: Address,
+    pub start: ChainEpoch,
+    pub expiration: ChainEpoch,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct RevokeProverParams {
+    pub prover: Address,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct GetProverAuthorizationsReturn {
+    /// Authorizations that haven't expired, including those yet to start.
+    pub provers: Vec<ProverAuthorization>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
 #[serde(transparent)]
 pub struct IsControllingAddressParam {
@@ -576,6 +710,27 @@ pub struct GetAvailableBalanceReturn {
     pub available_balance: TokenAmount,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct GetWithdrawableBalanceReturn {
+    /// The amount WithdrawBalance would withdraw now if requested without limit.
+    /// Zero if the withdrawal would fail.
+    pub withdrawable: TokenAmount,
+    /// Balance available once vested funds are unlocked: the actor balance less locked funds,
+    /// pre-commit deposits, initial pledge and fee debt. Negative if the fee debt can't be repaid.
+    pub available_balance: TokenAmount,
+    /// Funds that have vested but are yet to be unlocked.
+    pub newly_vested: TokenAmount,
+    /// Funds remaining locked in the vesting schedule.
+    pub locked_funds: TokenAmount,
+    pub pre_commit_deposits: TokenAmount,
+    pub initial_pledge: TokenAmount,
+    pub fee_debt: TokenAmount,
+    /// The beneficiary's remaining quota, if the beneficiary is not the owner.
+    pub beneficiary_quota: Option<TokenAmount>,
+    /// Whether early termination fees are outstanding, which prevents any withdrawal.
+    pub early_terminations_pending: bool,
+}
+
 #[derive(Serialize_tuple, Deserialize_tuple)]
 pub struct GetVestingFundsReturn {
     pub vesting_funds: Vec<(ChainEpoch, TokenAmount)>,
@@ -592,6 +747,64 @@ pub struct GetMultiaddrsReturn {
     pub multi_addrs: Vec<BytesDe>,
 }
 
+#[derive(Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct GetMinerEssentialsReturn {
+    pub owner: Address,
+    pub worker: Address,
+    pub control_addresses: Vec<Address>,
+    pub window_post_proof_type: RegisteredPoStProof,
+    pub sector_size: SectorSize,
+    #[serde(with = "strict_bytes")]
+    pub peer_id: Vec<u8>,
+    /// Owner change awaiting confirmation by the new owner, if it hasn't expired.
+    pub pending_owner_change: Option<PendingOwnerChange>,
+    /// Worker key change awaiting its effective epoch.
+    pub pending_worker_key: Option<WorkerKeyChange>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct EstimateTerminationFeeParams {
+    /// Live sectors whose termination to estimate.
+    pub sectors: BitField,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct EstimateTerminationFeeReturn {
+    /// Penalty for terminating the sectors in the current epoch.
+    pub penalty: TokenAmount,
+    /// Initial pledge that terminating the sectors would release.
+    pub pledge_released: TokenAmount,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct ListSectorsParams {
+    pub request: ListRequest<SectorNumber>,
+}
+
+pub type ListSectorsReturn = ListPage<SectorOnChainInfo, SectorNumber>;
+
+#[derive(Serialize_tuple, Deserialize_tuple)]
+pub struct PreviewSkippedFaultsParams {
+    pub deadline: u64,
+    /// Partitions to be proven, with the sectors to be skipped.
+    pub partitions: Vec<PoStPartition>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct PreviewSkippedFaultsReturn {
+    /// Power activated or deactivated by the PoSt.
+    pub power_delta: PowerPair,
+    /// Power of skipped sectors that would become faulty.
+    pub new_faulty_power: PowerPair,
+    /// Power of skipped sectors that were declared recovering, and would remain faulty.
+    pub retracted_recovery_power: PowerPair,
+    /// Fee charged at each deadline close while the skipped sectors remain faulty, at the current
+    /// reward and network power estimates.
+    pub fault_fee: TokenAmount,
+}
+
 // Notification of change committed to one or more sectors.
 // The relevant state must be already committed so the receiver can observe any impacts
 // at the sending miner actor.
@@ -93,6 +93,7 @@ fn valid_precommits_then_aggregate_provecommit() {
         expiration - *rt.epoch.borrow(),
         &deal_weight,
         &verified_deal_weight,
+        false,
     );
     assert_eq!(expected_power, qa_power);
     let expected_initial_pledge = initial_pledge_for_power(
@@ -5,6 +5,7 @@ use fil_actor_power::Method as PowerMethod;
 use fil_actors_runtime::runtime::Runtime;
 use fil_actors_runtime::runtime::RuntimePolicy;
 use fil_actors_runtime::test_utils::REWARD_ACTOR_CODE_ID;
+use fil_actors_runtime::PenaltyCategory;
 use fil_actors_runtime::BURNT_FUNDS_ACTOR_ADDR;
 use fil_actors_runtime::REWARD_ACTOR_ADDR;
 use fil_actors_runtime::STORAGE_POWER_ACTOR_ADDR;
@@ -136,10 +137,12 @@ fn penalty_is_partially_burnt_and_stored_as_fee_debt() {
         BURNT_FUNDS_ACTOR_ADDR,
         METHOD_SEND,
         None,
-        expect_burnt,
+        expect_burnt.clone(),
         None,
         ExitCode::OK,
     );
+    // the portion of the penalty that can't be paid is withheld as fee debt
+    expect_penalty_event(&rt, PenaltyCategory::BlockReward, &penalty, &expect_burnt, &amt);
 
     let params = ApplyRewardParams { reward, penalty };
     rt.call::<Actor>(Method::ApplyRewards as u64, IpldBlock::serialize_cbor(&params).unwrap())
@@ -153,6 +156,54 @@ fn penalty_is_partially_burnt_and_stored_as_fee_debt() {
     h.check_state(&rt);
 }
 
+#[test]
+fn penalty_is_paid_before_existing_fee_debt() {
+    let h = ActorHarness::new(PERIOD_OFFSET);
+    let rt = h.new_runtime();
+    rt.set_balance(BIG_BALANCE.clone());
+    h.construct_and_verify(&rt);
+
+    let amt = rt.get_balance();
+    let mut st = h.get_state(&rt);
+    st.fee_debt = 2 * &amt;
+    rt.replace_state(&st);
+
+    let reward = amt.clone();
+    let penalty = amt.clone();
+    rt.set_balance(&amt + &reward);
+
+    rt.set_caller(*REWARD_ACTOR_CODE_ID, REWARD_ACTOR_ADDR);
+    rt.expect_validate_caller_addr(vec![REWARD_ACTOR_ADDR]);
+
+    // the whole balance, including the locked reward, is burnt to repay debt
+    let expect_burnt = 2 * &amt;
+    rt.expect_send_simple(
+        BURNT_FUNDS_ACTOR_ADDR,
+        METHOD_SEND,
+        None,
+        expect_burnt,
+        None,
+        ExitCode::OK,
+    );
+    // the penalty is paid in full, and the debt owed before it remains
+    expect_penalty_event(
+        &rt,
+        PenaltyCategory::BlockReward,
+        &penalty,
+        &penalty,
+        &TokenAmount::zero(),
+    );
+
+    let params = ApplyRewardParams { reward, penalty };
+    rt.call::<Actor>(Method::ApplyRewards as u64, IpldBlock::serialize_cbor(&params).unwrap())
+        .unwrap();
+    rt.verify();
+
+    let st = h.get_state(&rt);
+    assert_eq!(amt, st.fee_debt);
+    assert!(st.locked_funds.is_zero());
+}
+
 // The system should not reach this state since fee debt removes mining eligibility
 // But if invariants are violated this should work.
 #[test]
@@ -1,9 +1,13 @@
-use fil_actor_miner::{Actor, GetOwnerReturn, Method};
+use fil_actor_miner::{
+    new_deadline_info_from_offset_and_epoch, Actor, GetOwnerReturn, GetPendingOwnerChangeReturn,
+    Method, PendingOwnerChange,
+};
 use fil_actors_runtime::test_utils::{
     expect_abort, expect_abort_contains_message, new_bls_addr, MockRuntime, ACCOUNT_ACTOR_CODE_ID,
     EVM_ACTOR_CODE_ID, MULTISIG_ACTOR_CODE_ID,
 };
 use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_shared::clock::EPOCH_UNDEFINED;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::{address::Address, error::ExitCode};
 
@@ -60,7 +64,7 @@ fn successful_change() {
     let info = h.get_info(&rt);
     assert_eq!(NEW_ADDRESS, info.owner);
     assert_eq!(NEW_ADDRESS, info.beneficiary);
-    assert!(info.pending_owner_address.is_none());
+    assert!(info.pending_owner_change().is_none());
 
     h.check_state(&rt);
 }
@@ -89,7 +93,7 @@ fn change_owner_address_restricted_correctly() {
 
     let info = h.get_info(&rt);
     assert_eq!(h.owner, info.owner);
-    assert_eq!(NEW_ADDRESS, info.pending_owner_address.unwrap());
+    assert_eq!(NEW_ADDRESS, info.pending_owner_change().unwrap().new_owner);
 
     // new owner can also call the exported method
 
@@ -101,7 +105,7 @@ fn change_owner_address_restricted_correctly() {
     let info = h.get_info(&rt);
     assert_eq!(NEW_ADDRESS, info.owner);
     assert_eq!(NEW_ADDRESS, info.beneficiary);
-    assert!(info.pending_owner_address.is_none());
+    assert!(info.pending_owner_change().is_none());
 
     h.check_state(&rt);
 }
@@ -133,7 +137,7 @@ fn successful_keep_beneficiary_when_change_owner() {
     let info = h.get_info(&rt);
     assert_eq!(NEW_ADDRESS, info.owner);
     assert_eq!(OTHER_ADDRESS, info.beneficiary);
-    assert!(info.pending_owner_address.is_none());
+    assert!(info.pending_owner_change().is_none());
 
     h.check_state(&rt);
 }
@@ -170,7 +174,7 @@ fn withdraw_proposal() {
 
     let info = h.get_info(&rt);
     assert_eq!(h.owner, info.owner);
-    assert!(info.pending_owner_address.is_none());
+    assert!(info.pending_owner_change().is_none());
 
     // new address cannot confirm
     rt.set_caller(*MULTISIG_ACTOR_CODE_ID, NEW_ADDRESS);
@@ -217,7 +221,7 @@ fn only_owner_can_change_proposal() {
 
     let info = h.get_info(&rt);
     assert_eq!(h.owner, info.owner);
-    assert_eq!(OTHER_ADDRESS, info.pending_owner_address.unwrap());
+    assert_eq!(OTHER_ADDRESS, info.pending_owner_change().unwrap().new_owner);
 
     h.check_state(&rt);
 }
@@ -234,7 +238,7 @@ fn only_nominee_can_confirm() {
     h.change_owner_address(&rt, NEW_ADDRESS).unwrap();
     let info = h.get_info(&rt);
     assert_eq!(h.owner, info.owner);
-    assert_eq!(NEW_ADDRESS, info.pending_owner_address.unwrap());
+    assert_eq!(NEW_ADDRESS, info.pending_owner_change().unwrap().new_owner);
 
     rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.worker);
     let result = h.change_owner_address(&rt, OTHER_ADDRESS);
@@ -250,7 +254,7 @@ fn only_nominee_can_confirm() {
 
     let info = h.get_info(&rt);
     assert_eq!(NEW_ADDRESS, info.owner);
-    assert!(info.pending_owner_address.is_none());
+    assert!(info.pending_owner_change().is_none());
 
     h.check_state(&rt);
 }
@@ -272,3 +276,113 @@ fn nominee_must_confirm_self_explicitly() {
 
     h.check_state(&rt);
 }
+
+fn get_pending_owner_change(rt: &MockRuntime) -> Option<PendingOwnerChange> {
+    rt.set_caller(*EVM_ACTOR_CODE_ID, OTHER_ADDRESS);
+    rt.expect_validate_caller_any();
+    let ret: GetPendingOwnerChangeReturn = rt
+        .call::<Actor>(Method::GetPendingOwnerChangeExported as u64, None)
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    ret.pending
+}
+
+#[test]
+fn proposal_expires() {
+    let (h, rt) = setup();
+    let proposal_epoch = 200;
+    let expiration = proposal_epoch + rt.policy.owner_change_expiry;
+    rt.set_epoch(proposal_epoch);
+    rt.set_caller(*MULTISIG_ACTOR_CODE_ID, h.owner);
+    h.change_owner_address(&rt, NEW_ADDRESS).unwrap();
+    assert_eq!(
+        Some(PendingOwnerChange { new_owner: NEW_ADDRESS, expiration }),
+        get_pending_owner_change(&rt)
+    );
+
+    // The proposal can't be confirmed once expired, and is no longer reported.
+    rt.set_epoch(expiration);
+    assert_eq!(None, get_pending_owner_change(&rt));
+    rt.set_caller(*MULTISIG_ACTOR_CODE_ID, NEW_ADDRESS);
+    expect_abort_contains_message(
+        ExitCode::USR_FORBIDDEN,
+        "expired at epoch",
+        h.change_owner_address(&rt, NEW_ADDRESS),
+    );
+    assert_eq!(h.owner, h.get_info(&rt).owner);
+
+    // The owner can propose the change again, to be confirmed before the new expiration.
+    rt.set_caller(*MULTISIG_ACTOR_CODE_ID, h.owner);
+    h.change_owner_address(&rt, NEW_ADDRESS).unwrap();
+    assert_eq!(
+        expiration + rt.policy.owner_change_expiry,
+        h.get_info(&rt).pending_owner_change().unwrap().expiration
+    );
+    rt.set_epoch(expiration + rt.policy.owner_change_expiry - 1);
+    rt.set_caller(*MULTISIG_ACTOR_CODE_ID, NEW_ADDRESS);
+    h.change_owner_address(&rt, NEW_ADDRESS).unwrap();
+
+    let info = h.get_info(&rt);
+    assert_eq!(NEW_ADDRESS, info.owner);
+    assert!(info.pending_owner_change().is_none());
+    h.check_state(&rt);
+}
+
+#[test]
+fn owner_or_nominee_can_cancel() {
+    let (h, rt) = setup();
+
+    // Nothing to cancel.
+    rt.set_caller(*MULTISIG_ACTOR_CODE_ID, h.owner);
+    expect_abort(ExitCode::USR_NOT_FOUND, h.cancel_owner_change(&rt));
+
+    // The owner cancels a proposal.
+    h.change_owner_address(&rt, NEW_ADDRESS).unwrap();
+    h.cancel_owner_change(&rt).unwrap();
+    assert!(h.get_info(&rt).pending_owner_change().is_none());
+    rt.set_caller(*MULTISIG_ACTOR_CODE_ID, NEW_ADDRESS);
+    expect_abort(ExitCode::USR_FORBIDDEN, h.change_owner_address(&rt, NEW_ADDRESS));
+
+    // The nominee declines a proposal.
+    rt.set_caller(*MULTISIG_ACTOR_CODE_ID, h.owner);
+    h.change_owner_address(&rt, NEW_ADDRESS).unwrap();
+    rt.set_caller(*MULTISIG_ACTOR_CODE_ID, NEW_ADDRESS);
+    h.cancel_owner_change(&rt).unwrap();
+    assert!(h.get_info(&rt).pending_owner_change().is_none());
+
+    // No one else can cancel.
+    rt.set_caller(*MULTISIG_ACTOR_CODE_ID, h.owner);
+    h.change_owner_address(&rt, NEW_ADDRESS).unwrap();
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.worker);
+    expect_abort(ExitCode::USR_FORBIDDEN, h.cancel_owner_change(&rt));
+    assert_eq!(NEW_ADDRESS, h.get_info(&rt).pending_owner_change().unwrap().new_owner);
+
+    h.check_state(&rt);
+}
+
+#[test]
+fn deadline_cron_clears_expired_proposal() {
+    let (h, rt) = setup();
+    rt.set_caller(*MULTISIG_ACTOR_CODE_ID, h.owner);
+    h.change_owner_address(&rt, NEW_ADDRESS).unwrap();
+    let expiration = h.get_info(&rt).pending_owner_expiration;
+
+    // A cron before the expiration keeps the proposal.
+    rt.set_epoch(h.deadline(&rt).last());
+    h.on_deadline_cron(&rt, CronConfig { no_enrollment: true, ..CronConfig::default() });
+    assert_eq!(Some(NEW_ADDRESS), h.get_info(&rt).pending_owner_address);
+
+    // The first cron from the expiration clears it.
+    let st = h.get_state(&rt);
+    let deadline =
+        new_deadline_info_from_offset_and_epoch(&rt.policy, st.proving_period_start, expiration);
+    rt.set_epoch(deadline.last());
+    h.on_deadline_cron(&rt, CronConfig { no_enrollment: true, ..CronConfig::default() });
+    let info = h.get_info(&rt);
+    assert_eq!(None, info.pending_owner_address);
+    assert_eq!(EPOCH_UNDEFINED, info.pending_owner_expiration);
+    h.check_state(&rt);
+}
@@ -18,7 +18,9 @@ const PERIOD_OFFSET: ChainEpoch = 100;
 
 fn setup() -> (ActorHarness, MockRuntime) {
     let h = ActorHarness::new(PERIOD_OFFSET);
-    let rt = h.new_runtime();
+    let mut rt = h.new_runtime();
+    // Terminated sectors pay the full termination fee.
+    rt.policy.cc_sector_fast_removal_max_age = 0;
     h.construct_and_verify(&rt);
     rt.balance.replace(BIG_BALANCE.clone());
 
@@ -0,0 +1,191 @@
+use fil_actor_miner::State;
+use fil_actors_runtime::{
+    runtime::RuntimePolicy,
+    test_utils::{expect_abort, expect_abort_contains_message, MockRuntime},
+};
+use fvm_ipld_bitfield::BitField;
+use fvm_shared::{clock::ChainEpoch, error::ExitCode, sector::SectorNumber};
+
+mod util;
+use itertools::Itertools;
+use util::*;
+const PERIOD_OFFSET: ChainEpoch = 100;
+
+fn setup() -> (ActorHarness, MockRuntime) {
+    let h = ActorHarness::new(PERIOD_OFFSET);
+    let rt = h.new_runtime();
+    h.construct_and_verify(&rt);
+    rt.balance.replace(BIG_BALANCE.clone());
+
+    (h, rt)
+}
+
+fn assert_sector_location(
+    rt: &MockRuntime,
+    sector_number: SectorNumber,
+    expected_partition: u64,
+    expected_deadline: u64,
+) {
+    let state: State = rt.get_state();
+    let (deadline, pid) = state.find_sector(rt.store(), sector_number).unwrap();
+    assert_eq!(expected_partition, pid);
+    assert_eq!(expected_deadline, deadline);
+}
+
+#[test]
+fn moves_live_sectors_to_a_deadline_challenged_sooner() {
+    let (mut h, rt) = setup();
+    rt.set_epoch(200);
+
+    // create 4 sectors in partition 0 of deadline 0
+    let sectors_info = h.commit_and_prove_sectors(
+        &rt,
+        4,
+        DEFAULT_SECTOR_EXPIRATION,
+        vec![vec![10], vec![20], vec![30], vec![40]],
+        true,
+    );
+    h.advance_and_submit_posts(&rt, &sectors_info);
+    let sectors = sectors_info.iter().map(|info| info.sector_number).collect_vec();
+    for sector in &sectors {
+        assert_sector_location(&rt, *sector, 0, 0);
+    }
+
+    // Wait out the dispute window so deadline 0 can be compacted.
+    let target_epoch = *rt.epoch.borrow() + rt.policy().wpost_dispute_window;
+    h.advance_to_epoch_with_cron(&rt, target_epoch);
+
+    // The destination is challenged later in this proving period, before deadline 0 is
+    // challenged again.
+    let dest_deadline = h.current_deadline(&rt).index + 2;
+    assert!(dest_deadline < rt.policy().wpost_period_deadlines);
+    h.compact_sectors_across_deadlines(&rt, vec![(0, bitfield_from_slice(&[0]))], dest_deadline)
+        .unwrap();
+
+    for sector in &sectors {
+        assert_sector_location(&rt, *sector, 0, dest_deadline);
+    }
+    h.check_state(&rt);
+
+    // The moved sectors are proven at their new deadline.
+    h.advance_and_submit_posts(&rt, &sectors_info);
+    h.check_state(&rt);
+}
+
+#[test]
+fn fails_to_move_sectors_to_a_deadline_challenged_later() {
+    let (h, rt) = setup();
+    rt.set_epoch(PERIOD_OFFSET);
+    let current_deadline = h.current_deadline(&rt).index;
+
+    let result = h.compact_sectors_across_deadlines(
+        &rt,
+        vec![(current_deadline + 3, BitField::default())],
+        current_deadline + 5,
+    );
+    expect_abort_contains_message(
+        ExitCode::USR_FORBIDDEN,
+        &format!(
+            "cannot move sectors from deadline {} to deadline {}",
+            current_deadline + 3,
+            current_deadline + 5
+        ),
+        result,
+    );
+
+    h.compact_sectors_across_deadlines(
+        &rt,
+        vec![(current_deadline + 5, BitField::default())],
+        current_deadline + 3,
+    )
+    .unwrap();
+    h.check_state(&rt);
+}
+
+#[test]
+fn fails_to_move_sectors_to_an_immutable_deadline() {
+    let (h, rt) = setup();
+    rt.set_epoch(PERIOD_OFFSET);
+    let current_deadline = h.current_deadline(&rt).index;
+
+    let result = h.compact_sectors_across_deadlines(
+        &rt,
+        vec![(current_deadline + 3, BitField::default())],
+        current_deadline + 1,
+    );
+    expect_abort(ExitCode::USR_FORBIDDEN, result);
+    h.check_state(&rt);
+}
+
+#[test]
+fn fails_if_source_deadline_is_not_available_for_compaction() {
+    let (h, rt) = setup();
+    rt.set_epoch(PERIOD_OFFSET);
+    // The last deadline of the previous proving period is still in its dispute window.
+    let last_deadline = rt.policy().wpost_period_deadlines - 1;
+
+    let result = h.compact_sectors_across_deadlines(
+        &rt,
+        vec![(last_deadline, BitField::default())],
+        h.current_deadline(&rt).index + 2,
+    );
+    expect_abort(ExitCode::USR_FORBIDDEN, result);
+    h.check_state(&rt);
+}
+
+#[test]
+fn fails_if_deadlines_are_invalid() {
+    let (h, rt) = setup();
+    let w_post_period_deadlines = rt.policy().wpost_period_deadlines;
+
+    let result = h.compact_sectors_across_deadlines(&rt, vec![], w_post_period_deadlines);
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        &format!("invalid destination deadline {w_post_period_deadlines}"),
+        result,
+    );
+    rt.reset();
+
+    let result = h.compact_sectors_across_deadlines(
+        &rt,
+        vec![(w_post_period_deadlines, BitField::default())],
+        2,
+    );
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        &format!("invalid deadline {w_post_period_deadlines}"),
+        result,
+    );
+    rt.reset();
+
+    let result = h.compact_sectors_across_deadlines(
+        &rt,
+        vec![(3, BitField::default()), (3, BitField::default())],
+        2,
+    );
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "duplicate source deadline 3",
+        result,
+    );
+    rt.reset();
+    h.check_state(&rt);
+}
+
+#[test]
+fn fails_if_partition_count_is_above_limit() {
+    let (h, rt) = setup();
+
+    // partition limit is 4 for the default construction, across all source deadlines
+    let result = h.compact_sectors_across_deadlines(
+        &rt,
+        vec![(3, bitfield_from_slice(&[1, 2, 3])), (4, bitfield_from_slice(&[1, 2]))],
+        2,
+    );
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "too many partitions 5, limit 4",
+        result,
+    );
+    h.check_state(&rt);
+}
@@ -95,6 +95,7 @@ fn add_sectors(
                 PoStPartition { index: 1, skipped: BitField::default() },
                 PoStPartition { index: 2, skipped: BitField::default() },
             ],
+            false,
         )
         .unwrap();
 
@@ -476,15 +477,17 @@ fn terminate_sectors(
         partition_sector_map.add(partition, sectors).unwrap();
     }
 
-    deadline.terminate_sectors(
-        &Policy::default(),
-        &store,
-        &sectors_array,
-        epoch,
-        &mut partition_sector_map,
-        SECTOR_SIZE,
-        QUANT_SPEC,
-    )
+    deadline
+        .terminate_sectors(
+            &Policy::default(),
+            &store,
+            &sectors_array,
+            epoch,
+            &mut partition_sector_map,
+            SECTOR_SIZE,
+            QUANT_SPEC,
+        )
+        .map(|(removed_power, _)| removed_power)
 }
 
 #[test]
@@ -681,6 +684,7 @@ fn post_all_the_things() {
             QUANT_SPEC,
             fault_expiration_epoch,
             &mut post_partitions,
+            false,
         )
         .unwrap();
     assert_bitfield_equals(&post_result1.sectors, &[1, 2, 3, 4, 5, 6, 7, 8]);
@@ -709,6 +713,7 @@ fn post_all_the_things() {
             QUANT_SPEC,
             fault_expiration_epoch,
             &mut post_partitions,
+            false,
         )
         .unwrap();
     assert_bitfield_equals(&post_result2.sectors, &[9, 10]);
@@ -805,6 +810,7 @@ fn post_with_unproven_faults_recoveries_untracted_recoveries() {
             QUANT_SPEC,
             fault_expiration_epoch,
             &mut post_partitions,
+            false,
         )
         .unwrap();
 
@@ -891,6 +897,7 @@ fn post_with_skipped_unproven() {
             QUANT_SPEC,
             fault_expiration_epoch,
             &mut post_partitions,
+            false,
         )
         .unwrap();
 
@@ -959,6 +966,7 @@ fn post_missing_partition() {
         QUANT_SPEC,
         fault_expiration_epoch,
         &mut post_partitions,
+        false,
     );
 
     let err = post_result
@@ -996,6 +1004,7 @@ fn post_partition_twice() {
         QUANT_SPEC,
         fault_expiration_epoch,
         &mut post_partitions,
+        false,
     );
 
     let err = post_result
@@ -1071,6 +1080,7 @@ fn retract_recoveries() {
                 PoStPartition { index: 1, skipped: BitField::default() },
                 PoStPartition { index: 2, skipped: BitField::default() },
             ],
+            false,
         )
         .unwrap();
 
@@ -1,8 +1,13 @@
 use fil_actor_miner::pledge_penalty_for_continued_fault;
 use fil_actor_miner::power_for_sectors;
+use fil_actor_miner::{DeclareFaultsParams, FaultDeclaration, Method};
+use fil_actors_runtime::test_utils::{expect_abort_contains_message, ACCOUNT_ACTOR_CODE_ID};
+use fil_actors_runtime::SectorRange;
 
+use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
 
 use num_traits::Zero;
 
@@ -64,3 +69,53 @@ fn declare_fault_pays_fee_at_window_post() {
     );
     h.check_state(&rt);
 }
+
+#[test]
+fn declare_faults_as_sector_ranges() {
+    let mut h = ActorHarness::new(PERIOD_OFFSET);
+    let rt = h.new_runtime();
+    rt.set_balance(BIG_BALANCE.clone());
+    h.construct_and_verify(&rt);
+    let all_sectors =
+        h.commit_and_prove_sectors(&rt, 1, DEFAULT_SECTOR_EXPIRATION as u64, vec![], true);
+    let pwr = power_for_sectors(h.sector_size, &all_sectors);
+    let sector_number = all_sectors[0].sector_number;
+
+    let st = h.get_state(&rt);
+    let (dl_idx, p_idx) = st.find_sector(&rt.store, sector_number).unwrap();
+    h.advance_and_submit_posts(&rt, &all_sectors);
+
+    // Empty ranges are rejected.
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.worker);
+    let params = DeclareFaultsParams {
+        faults: vec![FaultDeclaration {
+            deadline: dl_idx,
+            partition: p_idx,
+            sectors: vec![SectorRange { start: sector_number, count: 0 }].into(),
+        }],
+    };
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "empty range",
+        rt.call::<Actor>(Method::DeclareFaults as u64, IpldBlock::serialize_cbor(&params).unwrap()),
+    );
+    rt.reset();
+
+    // A range covering the sector declares it faulty, just as a bitfield would.
+    rt.expect_validate_caller_addr(h.caller_addrs());
+    expect_update_power(&rt, -pwr.clone());
+    let params = DeclareFaultsParams {
+        faults: vec![FaultDeclaration {
+            deadline: dl_idx,
+            partition: p_idx,
+            sectors: vec![SectorRange { start: sector_number, count: 1 }].into(),
+        }],
+    };
+    rt.call::<Actor>(Method::DeclareFaults as u64, IpldBlock::serialize_cbor(&params).unwrap())
+        .unwrap();
+    rt.verify();
+
+    let dl = h.get_deadline(&rt, dl_idx);
+    assert_eq!(pwr, dl.faulty_power);
+    h.check_state(&rt);
+}
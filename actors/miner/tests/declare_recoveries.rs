@@ -77,6 +77,7 @@ fn recovery_must_pay_back_fee_debt() {
         &rt,
         CronConfig {
             continued_faults_penalty: TokenAmount::zero(), // fee is instead added to debt
+            continued_faults_withheld: ff.clone(),
             ..Default::default()
         },
     );
@@ -1,12 +1,15 @@
 use fil_actor_miner::{
-    Actor, GetAvailableBalanceReturn, GetOwnerReturn, GetSectorSizeReturn,
-    IsControllingAddressParam, IsControllingAddressReturn, Method,
+    Actor, GetAvailableBalanceReturn, GetMinerEssentialsReturn, GetOwnerReturn,
+    GetSectorSizeReturn, IsControllingAddressParam, IsControllingAddressReturn, Method,
 };
+use fil_actors_runtime::query::{FieldFilter, FilterOp, ListRequest};
 use fil_actors_runtime::runtime::policy_constants::MAX_SECTOR_NUMBER;
-use fil_actors_runtime::test_utils::EVM_ACTOR_CODE_ID;
+use fil_actors_runtime::test_utils::expect_abort_contains_message;
+use fil_actors_runtime::test_utils::{MockRuntime, EVM_ACTOR_CODE_ID, MULTISIG_ACTOR_CODE_ID};
 use fil_actors_runtime::INIT_ACTOR_ADDR;
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_shared::address::Address;
+use fvm_shared::error::ExitCode;
 use fvm_shared::{clock::ChainEpoch, econ::TokenAmount};
 use std::ops::Sub;
 
@@ -94,6 +97,60 @@ fn info_getters() {
     h.check_state(&rt);
 }
 
+fn get_miner_essentials(rt: &MockRuntime) -> GetMinerEssentialsReturn {
+    // set caller to not-builtin
+    rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(1234));
+    rt.expect_validate_caller_any();
+    let ret = rt
+        .call::<Actor>(Method::GetMinerEssentialsExported as u64, None)
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    ret
+}
+
+#[test]
+fn essentials_getter() {
+    let h = ActorHarness::new(PERIOD_OFFSET);
+    let rt = h.new_runtime();
+    rt.set_balance(BIG_BALANCE.clone());
+    h.construct_and_verify(&rt);
+
+    let ret = get_miner_essentials(&rt);
+    assert_eq!(h.owner, ret.owner);
+    assert_eq!(h.worker, ret.worker);
+    assert_eq!(h.control_addrs, ret.control_addresses);
+    assert_eq!(h.window_post_proof_type, ret.window_post_proof_type);
+    assert_eq!(h.sector_size, ret.sector_size);
+    assert_eq!(vec![0], ret.peer_id);
+    assert_eq!(None, ret.pending_owner_change);
+    assert_eq!(None, ret.pending_worker_key);
+
+    // propose new owner and worker
+    let new_owner = Address::new_id(1001);
+    let new_worker = Address::new_id(999);
+    rt.set_caller(*MULTISIG_ACTOR_CODE_ID, h.owner);
+    h.change_owner_address(&rt, new_owner).unwrap();
+    h.change_worker_address(&rt, new_worker, h.control_addrs.clone()).unwrap();
+
+    let ret = get_miner_essentials(&rt);
+    assert_eq!(h.owner, ret.owner);
+    assert_eq!(h.worker, ret.worker);
+    let pending_owner_change = ret.pending_owner_change.unwrap();
+    assert_eq!(new_owner, pending_owner_change.new_owner);
+    assert_eq!(*rt.epoch.borrow() + rt.policy.owner_change_expiry, pending_owner_change.expiration);
+    let pending_worker_key = ret.pending_worker_key.unwrap();
+    assert_eq!(new_worker, pending_worker_key.new_worker);
+    assert_eq!(
+        *rt.epoch.borrow() + rt.policy.worker_key_change_delay,
+        pending_worker_key.effective_at
+    );
+
+    h.check_state(&rt);
+}
+
 #[test]
 fn collateral_getters() {
     let h = ActorHarness::new(PERIOD_OFFSET);
@@ -150,3 +207,45 @@ fn collateral_getters() {
 
     h.check_state(&rt);
 }
+
+#[test]
+fn list_sectors() {
+    let mut h = ActorHarness::new(PERIOD_OFFSET);
+    let rt = h.new_runtime();
+    rt.set_balance(BIG_BALANCE.clone());
+    h.construct_and_verify(&rt);
+
+    let sectors =
+        h.commit_and_prove_sectors(&rt, 3, DEFAULT_SECTOR_EXPIRATION as u64, vec![], true);
+
+    let page = h.list_sectors(&rt, ListRequest::first(10)).unwrap();
+    assert_eq!(sectors, page.entries);
+    assert_eq!(None, page.next_cursor);
+
+    // Page through the sectors two at a time.
+    let page = h.list_sectors(&rt, ListRequest::first(2)).unwrap();
+    assert_eq!(sectors[..2], page.entries);
+    assert_eq!(Some(sectors[2].sector_number), page.next_cursor);
+    let mut request = ListRequest::first(2);
+    request.cursor = page.next_cursor;
+    let page = h.list_sectors(&rt, request).unwrap();
+    assert_eq!(sectors[2..], page.entries);
+    assert_eq!(None, page.next_cursor);
+
+    // None of the sectors hold deals.
+    let mut request = ListRequest::first(10);
+    request.filter.push(FieldFilter::new("has-deals", FilterOp::Eq, 1));
+    let page = h.list_sectors(&rt, request).unwrap();
+    assert!(page.entries.is_empty());
+    assert_eq!(None, page.next_cursor);
+
+    let mut request = ListRequest::first(10);
+    request.filter.push(FieldFilter::new("sealed-cid", FilterOp::Eq, 0));
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "cannot filter on sealed-cid",
+        h.list_sectors(&rt, request),
+    );
+    rt.reset();
+    h.check_state(&rt);
+}
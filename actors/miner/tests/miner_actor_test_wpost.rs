@@ -15,6 +15,9 @@ use fvm_shared::sector::RegisteredSealProof;
 mod util;
 
 use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_ipld_encoding::tuple::*;
+use fvm_ipld_encoding::{from_slice, to_vec};
+use fvm_shared::sector::PoStProof;
 use num_traits::Zero;
 use util::*;
 
@@ -127,6 +130,7 @@ fn invalid_submissions() {
             proofs: make_post_proofs(h.window_post_proof_type),
             chain_commit_epoch: dlinfo.challenge,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            auto_recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -148,6 +152,7 @@ fn invalid_submissions() {
             proofs: make_post_proofs(h.window_post_proof_type),
             chain_commit_epoch: dlinfo.challenge,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            auto_recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -176,6 +181,7 @@ fn invalid_submissions() {
             proofs: make_post_proofs(h.window_post_proof_type),
             chain_commit_epoch: dlinfo.challenge,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            auto_recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -201,6 +207,7 @@ fn invalid_submissions() {
             proofs: make_post_proofs(h.window_post_proof_type),
             chain_commit_epoch: dlinfo.challenge,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            auto_recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -222,6 +229,7 @@ fn invalid_submissions() {
             proofs: make_post_proofs(h.window_post_proof_type),
             chain_commit_epoch: dlinfo.challenge,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            auto_recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -247,6 +255,7 @@ fn invalid_submissions() {
             proofs: Vec::new(),
             chain_commit_epoch: dlinfo.challenge,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            auto_recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -272,6 +281,7 @@ fn invalid_submissions() {
             proofs: make_post_proofs(RegisteredPoStProof::StackedDRGWindow8MiBV1P1),
             chain_commit_epoch: dlinfo.challenge,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            auto_recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -297,6 +307,7 @@ fn invalid_submissions() {
             proofs: make_post_proofs(RegisteredPoStProof::StackedDRGWindow64GiBV1P1),
             chain_commit_epoch: dlinfo.challenge,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            auto_recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -324,6 +335,7 @@ fn invalid_submissions() {
             proofs,
             chain_commit_epoch: dlinfo.challenge,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            auto_recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -349,6 +361,7 @@ fn invalid_submissions() {
             proofs: make_post_proofs(h.window_post_proof_type),
             chain_commit_epoch: dlinfo.challenge,
             chain_commit_rand: Randomness(b"123456789012345678901234567890123".to_vec()),
+            auto_recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -375,6 +388,7 @@ fn invalid_submissions() {
             proofs: make_post_proofs(h.window_post_proof_type),
             chain_commit_epoch: dlinfo.challenge + rt.policy.wpost_proving_period / 2,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            auto_recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -403,6 +417,7 @@ fn invalid_submissions() {
             proofs: make_post_proofs(h.window_post_proof_type),
             chain_commit_epoch: dlinfo.challenge - 1,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            auto_recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -428,6 +443,7 @@ fn invalid_submissions() {
             proofs: make_post_proofs(h.window_post_proof_type),
             chain_commit_epoch: *rt.epoch.borrow(),
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            auto_recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -453,6 +469,7 @@ fn invalid_submissions() {
             proofs: make_post_proofs(h.window_post_proof_type),
             chain_commit_epoch: dlinfo.challenge,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            auto_recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -478,6 +495,7 @@ fn invalid_submissions() {
             proofs: make_post_proofs(h.window_post_proof_type),
             chain_commit_epoch: dlinfo.challenge,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            auto_recover_faults: false,
         };
         h.submit_window_post_raw(
             &rt,
@@ -547,6 +565,7 @@ fn duplicate_proof_rejected() {
         proofs: make_post_proofs(h.window_post_proof_type),
         chain_commit_epoch: dlinfo.challenge,
         chain_commit_rand: Randomness(commit_rand.clone().into()),
+        auto_recover_faults: false,
     };
 
     h.expect_query_network_info(&rt);
@@ -644,6 +663,7 @@ fn duplicate_proof_rejected_with_many_partitions() {
             proofs: make_post_proofs(h.window_post_proof_type),
             chain_commit_epoch: dlinfo.challenge,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            auto_recover_faults: false,
         };
         let result = h.submit_window_post_raw(
             &rt,
@@ -816,6 +836,7 @@ fn skipped_faults_adjust_power() {
         proofs: make_post_proofs(h.window_post_proof_type),
         chain_commit_epoch: dlinfo.challenge,
         chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+        auto_recover_faults: false,
     };
 
     // Now all sectors are faulty so there's nothing to prove.
@@ -843,6 +864,72 @@ fn skipped_faults_adjust_power() {
     h.check_state(&rt);
 }
 
+#[test]
+fn skipped_faults_preview_matches_post() {
+    let period_offset = ChainEpoch::from(100);
+    let precommit_epoch = ChainEpoch::from(1);
+
+    let mut h = ActorHarness::new(period_offset);
+    h.set_proof_type(RegisteredSealProof::StackedDRG2KiBV1P1);
+
+    let rt = h.new_runtime();
+    rt.epoch.replace(precommit_epoch);
+    rt.balance.replace(BIG_BALANCE.clone());
+
+    h.construct_and_verify(&rt);
+
+    let infos = h.commit_and_prove_sectors(&rt, 2, DEFAULT_SECTOR_EXPIRATION, vec![], true);
+
+    h.apply_rewards(&rt, BIG_REWARDS.clone(), TokenAmount::zero());
+
+    let state = h.get_state(&rt);
+    let (dlidx, pidx) = state.find_sector(&rt.store, infos[0].sector_number).unwrap();
+    let infos1 = vec![infos[0].clone()];
+    let infos2 = vec![infos[1].clone()];
+    let skip = || {
+        vec![miner::PoStPartition {
+            index: pidx,
+            skipped: make_bitfield(&[infos[0].sector_number]),
+        }]
+    };
+
+    // The deadline's next PoSt can be previewed ahead of time, without changing state.
+    let state_root = *rt.state.borrow();
+    let preview = h.preview_skipped_faults(&rt, dlidx, skip()).unwrap();
+    assert_eq!(state_root, *rt.state.borrow());
+    let power_active = miner::power_for_sectors(h.sector_size, &infos2);
+    assert_eq!(power_active, preview.power_delta);
+    assert_eq!(miner::power_for_sectors(h.sector_size, &infos1), preview.new_faulty_power);
+    assert_eq!(PowerPair::zero(), preview.retracted_recovery_power);
+    assert_eq!(h.continued_fault_penalty(&infos1), preview.fault_fee);
+
+    // The PoSt activates the previewed power, and the skipped sector pays the previewed fee.
+    let dlinfo = h.advance_to_deadline(&rt, dlidx);
+    assert_eq!(preview, h.preview_skipped_faults(&rt, dlidx, skip()).unwrap());
+    h.submit_window_post(
+        &rt,
+        &dlinfo,
+        skip(),
+        infos2.clone(),
+        PoStConfig::with_expected_power_delta(&preview.power_delta),
+    );
+    h.advance_deadline(&rt, CronConfig::with_continued_faults_penalty(preview.fault_fee));
+
+    // Invalid parameters are rejected.
+    expect_abort(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        h.preview_skipped_faults(&rt, rt.policy.wpost_period_deadlines, skip()),
+    );
+    rt.reset();
+    let outside = vec![miner::PoStPartition {
+        index: pidx,
+        skipped: make_bitfield(&[infos[1].sector_number + 100]),
+    }];
+    expect_abort(ExitCode::USR_ILLEGAL_ARGUMENT, h.preview_skipped_faults(&rt, dlidx, outside));
+    rt.reset();
+    h.check_state(&rt);
+}
+
 #[test]
 fn skipping_all_sectors_in_a_partition_rejected() {
     let period_offset = ChainEpoch::from(100);
@@ -881,6 +968,7 @@ fn skipping_all_sectors_in_a_partition_rejected() {
         proofs: make_post_proofs(h.window_post_proof_type),
         chain_commit_epoch: dlinfo.challenge,
         chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+        auto_recover_faults: false,
     };
     let result = h.submit_window_post_raw(&rt, &dlinfo, infos.clone(), params, PoStConfig::empty());
     expect_abort(ExitCode::USR_ILLEGAL_ARGUMENT, result);
@@ -944,6 +1032,175 @@ fn skipped_recoveries_are_penalized_and_do_not_recover_power() {
     h.check_state(&rt);
 }
 
+#[test]
+fn submit_params_decode_without_auto_recovery() {
+    // Params as encoded before faults could be recovered automatically.
+    #[derive(Serialize_tuple)]
+    struct LegacySubmitWindowedPoStParams {
+        deadline: u64,
+        partitions: Vec<miner::PoStPartition>,
+        proofs: Vec<PoStProof>,
+        chain_commit_epoch: ChainEpoch,
+        chain_commit_rand: Randomness,
+    }
+
+    let legacy = LegacySubmitWindowedPoStParams {
+        deadline: 3,
+        partitions: vec![miner::PoStPartition { index: 1, skipped: make_empty_bitfield() }],
+        proofs: make_post_proofs(RegisteredPoStProof::StackedDRGWindow2KiBV1P1),
+        chain_commit_epoch: 10,
+        chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+    };
+    let params: miner::SubmitWindowedPoStParams = from_slice(&to_vec(&legacy).unwrap()).unwrap();
+    assert_eq!(3, params.deadline);
+    assert_eq!(1, params.partitions.len());
+    assert_eq!(10, params.chain_commit_epoch);
+    assert!(!params.auto_recover_faults);
+}
+
+#[test]
+fn auto_recovered_faults_recover_power() {
+    let period_offset = ChainEpoch::from(100);
+    let precommit_epoch = ChainEpoch::from(1);
+
+    let mut h = ActorHarness::new(period_offset);
+    h.set_proof_type(RegisteredSealProof::StackedDRG2KiBV1P1);
+
+    let rt = h.new_runtime();
+    rt.epoch.replace(precommit_epoch);
+    rt.balance.replace(BIG_BALANCE.clone());
+
+    h.construct_and_verify(&rt);
+
+    let infos = h.commit_and_prove_sectors(&rt, 2, DEFAULT_SECTOR_EXPIRATION, vec![], true);
+    let pwr = miner::power_for_sectors(h.sector_size, &infos);
+
+    h.apply_rewards(&rt, BIG_REWARDS.clone(), TokenAmount::zero());
+    let initial_locked = h.get_locked_funds(&rt);
+
+    // Submit first PoSt to ensure we are sufficiently early to add a fault
+    // advance to next proving period
+    h.advance_and_submit_posts(&rt, &infos);
+
+    // advance deadline and declare fault
+    h.advance_deadline(&rt, CronConfig::empty());
+    h.declare_faults(&rt, &infos);
+
+    // Skip to the due deadline without declaring recovery.
+    let state = h.get_state(&rt);
+    let (dlidx, pidx) = state.find_sector(&rt.store, infos[0].sector_number).unwrap();
+    let dlinfo = h.advance_to_deadline(&rt, dlidx);
+
+    // Submit PoSt recovering the faults.
+    // Power should return for both sectors.
+    let partition = miner::PoStPartition { index: pidx, skipped: make_empty_bitfield() };
+    let params = miner::SubmitWindowedPoStParams {
+        deadline: dlinfo.index,
+        partitions: vec![partition],
+        proofs: make_post_proofs(h.window_post_proof_type),
+        chain_commit_epoch: dlinfo.challenge,
+        chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+        auto_recover_faults: true,
+    };
+    h.submit_window_post_raw(
+        &rt,
+        &dlinfo,
+        infos.clone(),
+        params,
+        PoStConfig::with_expected_power_delta(&pwr),
+    )
+    .unwrap();
+    rt.verify();
+
+    // faulty power has been removed, partition no longer has faults or recoveries
+    let (deadline, partition) = h.find_sector(&rt, infos[0].sector_number);
+    assert_eq!(miner::PowerPair::zero(), deadline.faulty_power);
+    assert_eq!(miner::PowerPair::zero(), partition.faulty_power);
+    assert!(partition.faults.is_empty());
+    assert!(partition.recoveries.is_empty());
+
+    // We restored power, so we should not have recorded a post.
+    let deadline = h.get_deadline(&rt, dlidx);
+    let posts = amt_to_vec::<miner::WindowedPoSt>(&rt, &deadline.optimistic_post_submissions);
+    assert!(posts.is_empty());
+
+    // Next deadline cron does not charge for the faults
+    h.advance_deadline(&rt, CronConfig::empty());
+
+    assert_eq!(initial_locked, h.get_locked_funds(&rt));
+
+    h.check_state(&rt);
+}
+
+#[test]
+fn skipped_faults_are_not_auto_recovered() {
+    let period_offset = ChainEpoch::from(100);
+    let precommit_epoch = ChainEpoch::from(1);
+
+    let mut h = ActorHarness::new(period_offset);
+    h.set_proof_type(RegisteredSealProof::StackedDRG2KiBV1P1);
+
+    let rt = h.new_runtime();
+    rt.epoch.replace(precommit_epoch);
+    rt.balance.replace(BIG_BALANCE.clone());
+
+    h.construct_and_verify(&rt);
+
+    let infos = h.commit_and_prove_sectors(&rt, 2, DEFAULT_SECTOR_EXPIRATION, vec![], true);
+
+    h.apply_rewards(&rt, BIG_REWARDS.clone(), TokenAmount::zero());
+
+    // Submit first PoSt to ensure we are sufficiently early to add a fault
+    // advance to next proving period
+    h.advance_and_submit_posts(&rt, &infos);
+
+    // advance deadline and declare both sectors faulty
+    h.advance_deadline(&rt, CronConfig::empty());
+    h.declare_faults(&rt, &infos);
+
+    // Skip to the due deadline.
+    let state = h.get_state(&rt);
+    let (dlidx, pidx) = state.find_sector(&rt.store, infos[0].sector_number).unwrap();
+    let dlinfo = h.advance_to_deadline(&rt, dlidx);
+
+    // Submit PoSt recovering the faults, but skip the first sector.
+    // Only the second sector's power should return.
+    let infos1 = vec![infos[0].clone()];
+    let infos2 = vec![infos[1].clone()];
+    let pwr = miner::power_for_sectors(h.sector_size, &infos2);
+    let partition =
+        miner::PoStPartition { index: pidx, skipped: make_bitfield(&[infos[0].sector_number]) };
+    let params = miner::SubmitWindowedPoStParams {
+        deadline: dlinfo.index,
+        partitions: vec![partition],
+        proofs: make_post_proofs(h.window_post_proof_type),
+        chain_commit_epoch: dlinfo.challenge,
+        chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+        auto_recover_faults: true,
+    };
+    h.submit_window_post_raw(
+        &rt,
+        &dlinfo,
+        infos.clone(),
+        params,
+        PoStConfig::with_expected_power_delta(&pwr),
+    )
+    .unwrap();
+    rt.verify();
+
+    // The skipped sector remains faulty, and isn't recovering.
+    let (_, partition) = h.find_sector(&rt, infos[0].sector_number);
+    assert_bitfield_equals(&partition.faults, &[infos[0].sector_number]);
+    assert!(partition.recoveries.is_empty());
+    assert_eq!(miner::power_for_sectors(h.sector_size, &infos1), partition.faulty_power);
+
+    // sector will be charged ongoing fee at proving period cron
+    let ongoing_fee = h.continued_fault_penalty(&infos1);
+    h.advance_deadline(&rt, CronConfig::with_continued_faults_penalty(ongoing_fee));
+
+    h.check_state(&rt);
+}
+
 #[test]
 fn skipping_a_fault_from_the_wrong_partition_is_an_error() {
     let period_offset = ChainEpoch::from(100);
@@ -984,6 +1241,7 @@ fn skipping_a_fault_from_the_wrong_partition_is_an_error() {
         proofs: make_post_proofs(h.window_post_proof_type),
         chain_commit_epoch: dlinfo.challenge,
         chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+        auto_recover_faults: false,
     };
     let result = h.submit_window_post_raw(&rt, &dlinfo, infos, params, PoStConfig::empty());
     expect_abort_contains_message(
@@ -1283,6 +1541,7 @@ fn bad_post_fails_when_verified() {
         proofs: make_post_proofs(h.window_post_proof_type),
         chain_commit_epoch: dlinfo.challenge,
         chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+        auto_recover_faults: false,
     };
     let result = h.submit_window_post_raw(&rt, &dlinfo, infos, params, post_config);
     expect_abort_contains_message(
@@ -0,0 +1,196 @@
+use fil_actor_miner::{
+    power_for_sector, Actor, DeadlineInfo, Method, OnVerifiedClaimsExpiredParams, PoStPartition,
+    PowerPair, SectorExpiredClaimSpace, SectorOnChainInfo, SectorOnChainInfoFlags, State,
+};
+use fil_actors_runtime::test_utils::{
+    expect_abort_contains_message, MockRuntime, ACCOUNT_ACTOR_CODE_ID,
+};
+use fil_actors_runtime::{DealWeight, VERIFIED_REGISTRY_ACTOR_ADDR};
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::error::ExitCode;
+use fvm_shared::sector::RegisteredSealProof;
+
+mod util;
+use util::*;
+
+const DEFAULT_SECTOR_EXPIRATION: ChainEpoch = 220;
+const PERIOD_OFFSET: ChainEpoch = 100;
+
+fn setup() -> (ActorHarness, MockRuntime) {
+    let mut h = ActorHarness::new(PERIOD_OFFSET);
+    h.set_proof_type(RegisteredSealProof::StackedDRG512MiBV1);
+    let rt = h.new_runtime();
+    rt.set_balance(BIG_BALANCE.clone());
+    rt.set_epoch(1);
+    h.construct_and_verify(&rt);
+    (h, rt)
+}
+
+fn commit_verified_sector(h: &mut ActorHarness, rt: &MockRuntime) -> SectorOnChainInfo {
+    let half = h.sector_size as u64 / 2;
+    let mut pcc = ProveCommitConfig::empty();
+    pcc.add_activated_deals(
+        h.next_sector_no,
+        vec![test_activated_deal(half, 1), test_activated_deal(half, 2)],
+    );
+    let sector = h
+        .commit_and_prove_sectors_with_cfgs(
+            rt,
+            1,
+            DEFAULT_SECTOR_EXPIRATION as u64,
+            vec![vec![1, 2]],
+            true,
+            pcc,
+        )
+        .remove(0);
+    h.advance_and_submit_posts(rt, &[sector.clone()]);
+    sector
+}
+
+// Clears a sector's simple QA power flag, as for sectors committed before extensions were
+// capped at their claims' terms, which may outlive their claims.
+fn make_legacy(rt: &MockRuntime, sector: SectorOnChainInfo) -> SectorOnChainInfo {
+    let mut legacy = sector;
+    legacy.flags.remove(SectorOnChainInfoFlags::SIMPLE_QA_POWER);
+    let mut state: State = rt.get_state();
+    state.put_sectors(rt.store(), vec![legacy.clone()]).unwrap();
+    rt.replace_state(&state);
+    legacy
+}
+
+// Advances to the sector's next deadline and proves it, ahead of the deadline cron.
+fn prove_sector(h: &ActorHarness, rt: &MockRuntime, sector: &SectorOnChainInfo) -> DeadlineInfo {
+    let state: State = rt.get_state();
+    let (dlidx, pidx) = state.find_sector(rt.store(), sector.sector_number).unwrap();
+    let dlinfo = h.advance_to_deadline(rt, dlidx);
+    h.submit_window_post(
+        rt,
+        &dlinfo,
+        vec![PoStPartition { index: pidx, skipped: Default::default() }],
+        vec![sector.clone()],
+        PoStConfig::empty(),
+    );
+    dlinfo
+}
+
+#[test]
+fn expired_claim_space_becomes_unverified_at_deadline_cron() {
+    let (mut h, rt) = setup();
+    let sector = commit_verified_sector(&mut h, &rt);
+    let sector = make_legacy(&rt, sector);
+    let half = h.sector_size as u64 / 2;
+
+    h.on_verified_claims_expired(
+        &rt,
+        OnVerifiedClaimsExpiredParams {
+            sectors: vec![SectorExpiredClaimSpace {
+                sector_number: sector.sector_number,
+                space: half,
+            }],
+        },
+    )
+    .unwrap();
+    // Recording the expired space alone doesn't change the sector.
+    assert_eq!(sector, h.get_sector(&rt, sector.sector_number));
+
+    // The cron for the sector's deadline moves half of the remaining weight to unverified.
+    let cron_epoch = prove_sector(&h, &rt, &sector).last();
+    let remaining = sector.expiration - cron_epoch;
+    let mut expected = sector.clone();
+    expected.deal_weight = DealWeight::from(half * remaining as u64);
+    expected.verified_deal_weight = DealWeight::from(half * remaining as u64);
+    expected.power_base_epoch = cron_epoch;
+
+    let old_power = power_for_sector(h.sector_size, &sector);
+    let new_power = power_for_sector(h.sector_size, &expected);
+    let cfg = CronConfig {
+        expired_claims_power_delta: Some(PowerPair {
+            raw: new_power.raw - old_power.raw,
+            qa: new_power.qa - old_power.qa,
+        }),
+        ..CronConfig::empty()
+    };
+    h.advance_deadline(&rt, cfg);
+
+    let downgraded = h.get_sector(&rt, sector.sector_number);
+    assert_eq!(cron_epoch, downgraded.power_base_epoch);
+    assert_eq!(expected.deal_weight, downgraded.deal_weight);
+    assert_eq!(expected.verified_deal_weight, downgraded.verified_deal_weight);
+    assert_eq!(sector.initial_pledge, downgraded.initial_pledge);
+    assert_eq!(sector.expected_day_reward, downgraded.replaced_day_reward);
+
+    // The record is consumed, so the next cron for the deadline changes nothing.
+    prove_sector(&h, &rt, &downgraded);
+    h.advance_deadline(&rt, CronConfig::empty());
+    assert_eq!(downgraded, h.get_sector(&rt, sector.sector_number));
+    h.check_state(&rt);
+}
+
+#[test]
+fn ignores_unknown_and_unverified_sectors() {
+    let (mut h, rt) = setup();
+    let sector = h.commit_and_prove_sectors(&rt, 1, DEFAULT_SECTOR_EXPIRATION as u64, vec![], true)
+        [0]
+    .clone();
+    h.advance_and_submit_posts(&rt, &[sector.clone()]);
+    let sector = make_legacy(&rt, sector);
+
+    h.on_verified_claims_expired(
+        &rt,
+        OnVerifiedClaimsExpiredParams {
+            sectors: vec![
+                SectorExpiredClaimSpace { sector_number: sector.sector_number, space: 1 },
+                SectorExpiredClaimSpace { sector_number: sector.sector_number + 100, space: 1 },
+            ],
+        },
+    )
+    .unwrap();
+
+    // No power changes at the sector's next deadline cron.
+    prove_sector(&h, &rt, &sector);
+    h.advance_deadline(&rt, CronConfig::empty());
+    assert_eq!(sector, h.get_sector(&rt, sector.sector_number));
+    h.check_state(&rt);
+}
+
+#[test]
+fn ignores_simple_qa_power_sectors() {
+    let (mut h, rt) = setup();
+    let sector = commit_verified_sector(&mut h, &rt);
+    let half = h.sector_size as u64 / 2;
+
+    // The sector's claims can't have expired before it, so nothing is recorded.
+    h.on_verified_claims_expired(
+        &rt,
+        OnVerifiedClaimsExpiredParams {
+            sectors: vec![SectorExpiredClaimSpace {
+                sector_number: sector.sector_number,
+                space: half,
+            }],
+        },
+    )
+    .unwrap();
+
+    prove_sector(&h, &rt, &sector);
+    h.advance_deadline(&rt, CronConfig::empty());
+    assert_eq!(sector, h.get_sector(&rt, sector.sector_number));
+    h.check_state(&rt);
+}
+
+#[test]
+fn rejects_caller_other_than_verified_registry() {
+    let (h, rt) = setup();
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.worker);
+    rt.expect_validate_caller_addr(vec![VERIFIED_REGISTRY_ACTOR_ADDR]);
+    expect_abort_contains_message(
+        ExitCode::USR_FORBIDDEN,
+        "caller",
+        rt.call::<Actor>(
+            Method::OnVerifiedClaimsExpired as u64,
+            IpldBlock::serialize_cbor(&OnVerifiedClaimsExpiredParams { sectors: vec![] }).unwrap(),
+        ),
+    );
+    rt.reset();
+    h.check_state(&rt);
+}
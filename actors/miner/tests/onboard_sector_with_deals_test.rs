@@ -0,0 +1,220 @@
+use fvm_ipld_bitfield::BitField;
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_ipld_encoding::{RawBytes, DAG_CBOR};
+use fvm_shared::address::Address;
+use fvm_shared::crypto::signature::Signature;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
+use fvm_shared::piece::PaddedPieceSize;
+use fvm_shared::sector::SectorNumber;
+use fvm_shared::{clock::ChainEpoch, ActorID};
+use num_traits::Zero;
+
+use fil_actor_market::{
+    ClientDealProposal, DealProposal, Label, Method as MarketMethod, ProposalAuthScheme,
+    PublishStorageDealsParams, PublishStorageDealsReturn,
+};
+use fil_actor_miner::{
+    Method, OnboardSectorWithDealsParams, OnboardingSectorInfo, SectorPreCommitInfo,
+};
+use fil_actors_runtime::test_utils::{
+    expect_abort_contains_message, make_piece_cid, MockRuntime, ACCOUNT_ACTOR_CODE_ID,
+};
+use fil_actors_runtime::{EPOCHS_IN_DAY, STORAGE_MARKET_ACTOR_ADDR};
+use util::*;
+
+mod util;
+
+const CLIENT_ID: ActorID = 1000;
+const SECTOR_NUMBER: SectorNumber = 100;
+const SECTOR_EXPIRATION_DAYS: ChainEpoch = 220;
+
+#[test]
+fn onboards_sector_with_deals() {
+    let (h, rt) = setup();
+    let (deals, mut precommit) = make_deals_and_precommit(&h, &rt, 2);
+    precommit.deal_ids = vec![10, 11];
+
+    let ret = h.onboard_sector_with_deals(&rt, deals, &precommit, true).unwrap();
+    assert_eq!(vec![10, 11], ret.deal_ids);
+
+    // The sector is pre-committed with the published deals.
+    let on_chain = h.get_precommit(&rt, SECTOR_NUMBER);
+    assert_eq!(precommit, on_chain.info);
+    h.check_state(&rt);
+}
+
+#[test]
+fn fails_unless_all_deals_are_published() {
+    let (h, rt) = setup();
+    let (deals, precommit) = make_deals_and_precommit(&h, &rt, 2);
+
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.worker);
+    // The second deal is invalid, so only the first is published.
+    rt.expect_send_simple(
+        STORAGE_MARKET_ACTOR_ADDR,
+        MarketMethod::PublishStorageDeals as u64,
+        Some(IpldBlock { codec: DAG_CBOR, data: deals.to_vec() }),
+        TokenAmount::zero(),
+        IpldBlock::serialize_cbor(&PublishStorageDealsReturn {
+            ids: vec![10],
+            valid_deals: BitField::try_from_bits([0]).unwrap(),
+        })
+        .unwrap(),
+        ExitCode::OK,
+    );
+
+    let res = rt.call::<fil_actor_miner::Actor>(
+        Method::OnboardSectorWithDeals as u64,
+        IpldBlock::serialize_cbor(&onboard_params(deals, &precommit)).unwrap(),
+    );
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "only 1 of 2 deals published",
+        res,
+    );
+    rt.verify();
+    h.check_state(&rt);
+}
+
+#[test]
+fn fails_if_publish_fails() {
+    let (h, rt) = setup();
+    let (deals, precommit) = make_deals_and_precommit(&h, &rt, 1);
+
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.worker);
+    rt.expect_send_simple(
+        STORAGE_MARKET_ACTOR_ADDR,
+        MarketMethod::PublishStorageDeals as u64,
+        Some(IpldBlock { codec: DAG_CBOR, data: deals.to_vec() }),
+        TokenAmount::zero(),
+        None,
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+    );
+
+    let res = rt.call::<fil_actor_miner::Actor>(
+        Method::OnboardSectorWithDeals as u64,
+        IpldBlock::serialize_cbor(&onboard_params(deals, &precommit)).unwrap(),
+    );
+    assert_eq!(ExitCode::USR_ILLEGAL_ARGUMENT, res.unwrap_err().exit_code());
+    rt.verify();
+    h.check_state(&rt);
+}
+
+#[test]
+fn fails_if_caller_is_not_controlling() {
+    let (h, rt) = setup();
+    let (deals, mut precommit) = make_deals_and_precommit(&h, &rt, 1);
+    precommit.deal_ids = vec![10];
+
+    // The deals are published before the caller is validated, but the whole message aborts.
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, Address::new_id(CLIENT_ID));
+    rt.expect_send_simple(
+        STORAGE_MARKET_ACTOR_ADDR,
+        MarketMethod::PublishStorageDeals as u64,
+        Some(IpldBlock { codec: DAG_CBOR, data: deals.to_vec() }),
+        TokenAmount::zero(),
+        IpldBlock::serialize_cbor(&PublishStorageDealsReturn {
+            ids: vec![10],
+            valid_deals: BitField::try_from_bits([0]).unwrap(),
+        })
+        .unwrap(),
+        ExitCode::OK,
+    );
+    h.expect_query_network_info(&rt);
+    rt.expect_send_simple(
+        STORAGE_MARKET_ACTOR_ADDR,
+        MarketMethod::VerifyDealsForActivation as u64,
+        IpldBlock::serialize_cbor(&fil_actor_market::VerifyDealsForActivationParams {
+            sectors: vec![fil_actor_market::SectorDeals {
+                sector_number: precommit.sector_number,
+                sector_type: precommit.seal_proof,
+                sector_expiry: precommit.expiration,
+                deal_ids: precommit.deal_ids.clone(),
+            }],
+        })
+        .unwrap(),
+        TokenAmount::zero(),
+        IpldBlock::serialize_cbor(&fil_actor_market::VerifyDealsForActivationReturn {
+            unsealed_cids: vec![precommit.unsealed_cid.0],
+        })
+        .unwrap(),
+        ExitCode::OK,
+    );
+    rt.expect_validate_caller_addr(h.caller_addrs());
+
+    let res = rt.call::<fil_actor_miner::Actor>(
+        Method::OnboardSectorWithDeals as u64,
+        IpldBlock::serialize_cbor(&onboard_params(deals, &precommit)).unwrap(),
+    );
+    assert_eq!(ExitCode::USR_FORBIDDEN, res.unwrap_err().exit_code());
+    rt.verify();
+    h.check_state(&rt);
+}
+
+fn setup() -> (ActorHarness, MockRuntime) {
+    let h = ActorHarness::new_with_options(HarnessOptions::default());
+    let rt = h.new_runtime();
+    rt.set_balance(BIG_BALANCE.clone());
+    h.construct_and_verify(&rt);
+    rt.set_epoch(1);
+    (h, rt)
+}
+
+// Makes publish params for some deals, each filling an equal part of a sector, and the sector's
+// pre-commit info without deal IDs.
+fn make_deals_and_precommit(
+    h: &ActorHarness,
+    rt: &MockRuntime,
+    count: u64,
+) -> (RawBytes, SectorPreCommitInfo) {
+    let epoch = *rt.epoch.borrow();
+    let expiration = epoch + SECTOR_EXPIRATION_DAYS * EPOCHS_IN_DAY;
+    let piece_cids: Vec<_> =
+        (0..count).map(|i| make_piece_cid(format!("piece-{}", i).as_bytes())).collect();
+    let deals = piece_cids
+        .iter()
+        .map(|piece_cid| ClientDealProposal {
+            proposal: DealProposal {
+                piece_cid: *piece_cid,
+                piece_size: PaddedPieceSize(h.sector_size as u64 / count),
+                verified_deal: false,
+                client: Address::new_id(CLIENT_ID),
+                provider: h.receiver,
+                label: Label::String("label".into()),
+                start_epoch: epoch + rt.policy.pre_commit_challenge_delay + EPOCHS_IN_DAY,
+                end_epoch: expiration,
+                storage_price_per_epoch: TokenAmount::zero(),
+                provider_collateral: TokenAmount::zero(),
+                client_collateral: TokenAmount::zero(),
+            },
+            client_signature: Signature::new_bls(b"signature".to_vec()),
+            auth_scheme: ProposalAuthScheme::Cbor,
+        })
+        .collect();
+    let precommit = h.make_pre_commit_params_v2(
+        SECTOR_NUMBER,
+        epoch - 1,
+        expiration,
+        vec![],
+        sector_commd_from_pieces(&piece_cids),
+    );
+    (RawBytes::serialize(PublishStorageDealsParams { deals }).unwrap(), precommit)
+}
+
+fn onboard_params(
+    deals: RawBytes,
+    precommit: &SectorPreCommitInfo,
+) -> OnboardSectorWithDealsParams {
+    OnboardSectorWithDealsParams {
+        deals,
+        sector: OnboardingSectorInfo {
+            seal_proof: precommit.seal_proof,
+            sector_number: precommit.sector_number,
+            sealed_cid: precommit.sealed_cid,
+            seal_rand_epoch: precommit.seal_rand_epoch,
+            expiration: precommit.expiration,
+            unsealed_cid: precommit.unsealed_cid.clone(),
+        },
+    }
+}
@@ -1,11 +1,13 @@
 use fil_actor_miner::{
-    pledge_penalty_for_termination, pledge_penalty_for_termination_lower_bound,
+    pledge_penalty_for_cc_fast_removal, pledge_penalty_for_termination,
+    pledge_penalty_for_termination_lower_bound, CC_FAST_REMOVAL_PENALTY_FLOOR_DAYS,
     INITIAL_PLEDGE_FACTOR, TERMINATION_LIFETIME_CAP, TERMINATION_REWARD_FACTOR_DENOM,
     TERMINATION_REWARD_FACTOR_NUM,
 };
 use fil_actors_runtime::reward::FilterEstimate;
 use fil_actors_runtime::EPOCHS_IN_DAY;
 use fvm_shared::bigint::{BigInt, Zero};
+use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::sector::StoragePower;
 
@@ -228,3 +230,86 @@ fn charges_for_replaced_sector_at_replaced_sector_day_rate() {
 
     assert_eq!(expected_fee, fee);
 }
+
+// The full termination fee of a sector that replaced no other sector.
+fn full_termination_fee(day_reward: &TokenAmount, sector_age: ChainEpoch) -> TokenAmount {
+    pledge_penalty_for_termination(
+        day_reward,
+        sector_age,
+        &(day_reward * INITIAL_PLEDGE_FACTOR),
+        &power_estimate(),
+        &qa_sector_power(),
+        &reward_estimate(),
+        &TokenAmount::zero(),
+        0,
+    )
+}
+
+#[test]
+fn cc_fast_removal_fee_is_pro_rated_by_age() {
+    let day_reward = undeclared_penalty().div_floor(INITIAL_PLEDGE_FACTOR);
+    let max_age = 30 * EPOCHS_IN_DAY;
+    let age = 28 * EPOCHS_IN_DAY;
+    let full_fee = full_termination_fee(&day_reward, age);
+
+    // Late in the fast removal age the pro-rated fee exceeds the floor.
+    assert_eq!(
+        (&full_fee * age).div_floor(max_age),
+        pledge_penalty_for_cc_fast_removal(&full_fee, &day_reward, age, max_age)
+    );
+    assert_eq!(
+        full_fee,
+        pledge_penalty_for_cc_fast_removal(&full_fee, &day_reward, max_age, max_age)
+    );
+    assert_eq!(
+        full_fee,
+        pledge_penalty_for_cc_fast_removal(&full_fee, &day_reward, 2 * max_age, max_age)
+    );
+}
+
+#[test]
+fn cc_fast_removal_fee_is_floored_at_expected_reward() {
+    let day_reward = undeclared_penalty().div_floor(INITIAL_PLEDGE_FACTOR);
+    let max_age = 30 * EPOCHS_IN_DAY;
+    let full_fee = full_termination_fee(&day_reward, 0);
+
+    // A sector removed at activation pays the floor days of block reward.
+    let fee = pledge_penalty_for_cc_fast_removal(&full_fee, &day_reward, 0, max_age);
+    assert!(fee.is_positive());
+    assert_eq!(&day_reward * CC_FAST_REMOVAL_PENALTY_FLOOR_DAYS, fee);
+
+    // Early in the fast removal age the reward earned plus the floor exceeds the pro-rated fee.
+    let age = EPOCHS_IN_DAY;
+    let fee = pledge_penalty_for_cc_fast_removal(&full_fee, &day_reward, age, max_age);
+    assert_eq!(&day_reward * (1 + CC_FAST_REMOVAL_PENALTY_FLOOR_DAYS), fee);
+
+    // The floor never raises the fee above the full termination fee.
+    let small_fee = TokenAmount::from_atto(1);
+    assert_eq!(small_fee, pledge_penalty_for_cc_fast_removal(&small_fee, &day_reward, 0, max_age));
+}
+
+#[test]
+fn cc_fast_removal_is_never_profitable() {
+    let day_reward = undeclared_penalty().div_floor(INITIAL_PLEDGE_FACTOR);
+    let max_age = 30 * EPOCHS_IN_DAY;
+
+    let mut last = TokenAmount::zero();
+    for age in (0..=max_age).step_by((EPOCHS_IN_DAY / 4) as usize) {
+        let full_fee = full_termination_fee(&day_reward, age);
+        let fee = pledge_penalty_for_cc_fast_removal(&full_fee, &day_reward, age, max_age);
+        let earned = (&day_reward * age).div_floor(EPOCHS_IN_DAY);
+
+        // The fee exceeds the reward the sector is expected to have earned, by at least the
+        // floor days of reward while it is within the fast removal age.
+        assert!(fee > earned);
+        if age < max_age {
+            assert!(fee >= &earned + &day_reward * CC_FAST_REMOVAL_PENALTY_FLOOR_DAYS);
+        } else {
+            assert_eq!(full_fee, fee);
+        }
+        // The fee never decreases with age and never exceeds the full termination fee.
+        assert!(fee >= last);
+        assert!(fee <= full_fee);
+        last = fee;
+    }
+}
@@ -1,7 +1,8 @@
-use fil_actor_miner::{qa_power_for_weight, quality_for_weight};
+use fil_actor_miner::{duration_multiplier, qa_power_for_weight, quality_for_weight};
 use fil_actor_miner::{
-    DEAL_WEIGHT_MULTIPLIER, QUALITY_BASE_MULTIPLIER, SECTOR_QUALITY_PRECISION,
-    VERIFIED_DEAL_WEIGHT_MULTIPLIER,
+    DEAL_WEIGHT_MULTIPLIER, DURATION_MULTIPLIER_BASE_TERM, DURATION_MULTIPLIER_MAX_BONUS_DENOM,
+    DURATION_MULTIPLIER_MAX_BONUS_NUM, DURATION_MULTIPLIER_MAX_TERM, QUALITY_BASE_MULTIPLIER,
+    SECTOR_QUALITY_PRECISION, VERIFIED_DEAL_WEIGHT_MULTIPLIER,
 };
 use fil_actors_runtime::{EPOCHS_IN_DAY, SECONDS_IN_DAY};
 use fvm_shared::bigint::{BigInt, Zero};
@@ -111,7 +112,7 @@ fn empty_sector_has_power_equal_to_size() {
             let expected_power = BigInt::from(size as i64);
             assert_eq!(
                 expected_power,
-                qa_power_for_weight(size, *duration, &BigInt::zero(), &BigInt::zero())
+                qa_power_for_weight(size, *duration, &BigInt::zero(), &BigInt::zero(), false)
             );
         }
     }
@@ -140,7 +141,7 @@ fn verified_sector_has_power_a_multiple_of_size() {
             let expected_power = size as i64 * &verified_multiplier;
             assert_eq!(
                 expected_power,
-                qa_power_for_weight(size, *duration, &BigInt::zero(), &verified_weight)
+                qa_power_for_weight(size, *duration, &BigInt::zero(), &verified_weight, false)
             );
         }
     }
@@ -191,6 +192,7 @@ fn verified_weight_adds_proportional_power() {
                 sector_duration,
                 &BigInt::zero(),
                 &verified_weight,
+                false,
             );
             let power_error = expected_power - power;
             assert!(power_error <= max_error);
@@ -209,16 +211,22 @@ fn demonstrate_standard_sectors() {
 
     assert_eq!(
         BigInt::from(sector_size as u64),
-        qa_power_for_weight(sector_size, sector_duration, &BigInt::zero(), &BigInt::zero())
+        qa_power_for_weight(sector_size, sector_duration, &BigInt::zero(), &BigInt::zero(), false)
     );
     assert_eq!(
         &vmul * sector_size as u64,
-        qa_power_for_weight(sector_size, sector_duration, &BigInt::zero(), &sector_weight)
+        qa_power_for_weight(sector_size, sector_duration, &BigInt::zero(), &sector_weight, false)
     );
     let half_verified_power = ((sector_size as u64) / 2) + (&vmul * (sector_size as u64) / 2);
     assert_eq!(
         half_verified_power,
-        qa_power_for_weight(sector_size, sector_duration, &BigInt::zero(), &(sector_weight / 2))
+        qa_power_for_weight(
+            sector_size,
+            sector_duration,
+            &BigInt::zero(),
+            &(sector_weight / 2),
+            false
+        )
     );
 
     // 64GiB
@@ -227,16 +235,62 @@ fn demonstrate_standard_sectors() {
 
     assert_eq!(
         BigInt::from(sector_size as u64),
-        qa_power_for_weight(sector_size, sector_duration, &BigInt::zero(), &BigInt::zero())
+        qa_power_for_weight(sector_size, sector_duration, &BigInt::zero(), &BigInt::zero(), false)
     );
     assert_eq!(
         &vmul * sector_size as u64,
-        qa_power_for_weight(sector_size, sector_duration, &BigInt::zero(), &sector_weight)
+        qa_power_for_weight(sector_size, sector_duration, &BigInt::zero(), &sector_weight, false)
     );
     let half_verified_power = ((sector_size as u64) / 2) + (&vmul * (sector_size as u64) / 2);
     assert_eq!(
         half_verified_power,
-        qa_power_for_weight(sector_size, sector_duration, &BigInt::zero(), &(sector_weight / 2))
+        qa_power_for_weight(
+            sector_size,
+            sector_duration,
+            &BigInt::zero(),
+            &(sector_weight / 2),
+            false
+        )
+    );
+}
+
+#[test]
+fn duration_multiplier_grows_linearly_between_base_and_max_term() {
+    let unit = BigInt::from(1 << SECTOR_QUALITY_PRECISION);
+    let max_bonus = &unit * DURATION_MULTIPLIER_MAX_BONUS_NUM / DURATION_MULTIPLIER_MAX_BONUS_DENOM;
+
+    // No bonus up to the base term.
+    assert_eq!(unit, duration_multiplier(180 * EPOCHS_IN_DAY));
+    assert_eq!(unit, duration_multiplier(DURATION_MULTIPLIER_BASE_TERM));
+
+    // Half the bonus half way to the max term.
+    let mid_term = (DURATION_MULTIPLIER_BASE_TERM + DURATION_MULTIPLIER_MAX_TERM) / 2;
+    assert_eq!(&unit + &max_bonus / 2, duration_multiplier(mid_term));
+
+    // Bonus is capped at the max term.
+    assert_eq!(&unit + &max_bonus, duration_multiplier(DURATION_MULTIPLIER_MAX_TERM));
+    assert_eq!(&unit + &max_bonus, duration_multiplier(2 * DURATION_MULTIPLIER_MAX_TERM));
+}
+
+#[test]
+fn duration_multiplier_scales_qa_power() {
+    let sector_size = SectorSize::_32GiB;
+
+    // A sector committed for the max term earns the full bonus.
+    let long_duration = DURATION_MULTIPLIER_MAX_TERM;
+    let verified_weight = weight(sector_size, long_duration);
+    let base_power =
+        qa_power_for_weight(sector_size, long_duration, &BigInt::zero(), &verified_weight, false);
+    assert_eq!(
+        base_power * 3 / 2,
+        qa_power_for_weight(sector_size, long_duration, &BigInt::zero(), &verified_weight, true)
+    );
+
+    // A sector committed for less than the base term is unaffected.
+    let short_duration = 180 * EPOCHS_IN_DAY;
+    assert_eq!(
+        qa_power_for_weight(sector_size, short_duration, &BigInt::zero(), &BigInt::zero(), false),
+        qa_power_for_weight(sector_size, short_duration, &BigInt::zero(), &BigInt::zero(), true)
     );
 }
 
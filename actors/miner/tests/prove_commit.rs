@@ -105,7 +105,7 @@ fn prove_single_sector() {
     let expected_power = StoragePower::from(h.sector_size as u64)
         * (VERIFIED_DEAL_WEIGHT_MULTIPLIER / QUALITY_BASE_MULTIPLIER);
     let qa_power =
-        qa_power_for_weight(h.sector_size, duration, &deal_weight, &verified_deal_weight);
+        qa_power_for_weight(h.sector_size, duration, &deal_weight, &verified_deal_weight, false);
     assert_eq!(expected_power, qa_power);
     let sector_power =
         PowerPair { raw: StoragePower::from(h.sector_size as u64), qa: qa_power.clone() };
@@ -204,6 +204,7 @@ fn prove_sectors_from_batch_pre_commit() {
         sector_expiration - prove_commit_epoch,
         &DealWeight::zero(),
         &DealWeight::zero(),
+        false,
     );
     let no_deal_pledge = initial_pledge_for_power(
         &no_deal_power,
@@ -217,6 +218,7 @@ fn prove_sectors_from_batch_pre_commit() {
         sector_expiration - prove_commit_epoch,
         &deal_weight,
         &verified_deal_weight,
+        false,
     );
     let expected_power = StoragePower::from(h.sector_size as u64)
         * (VERIFIED_DEAL_WEIGHT_MULTIPLIER / QUALITY_BASE_MULTIPLIER);
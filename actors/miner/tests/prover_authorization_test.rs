@@ -0,0 +1,205 @@
+use fil_actor_miner::{
+    Actor, ChangePeerIDParams, DeadlineInfo, Method, PoStPartition, ProverAuthorization,
+    SectorOnChainInfo, State, SubmitWindowedPoStParams,
+};
+use fil_actors_runtime::test_utils::{
+    expect_abort, expect_abort_contains_message, MockRuntime, ACCOUNT_ACTOR_CODE_ID,
+};
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::error::ExitCode;
+use fvm_shared::randomness::Randomness;
+use fvm_shared::sector::RegisteredSealProof;
+
+mod util;
+use util::*;
+
+const DEFAULT_SECTOR_EXPIRATION: ChainEpoch = 220;
+const PERIOD_OFFSET: ChainEpoch = 100;
+const PROVER: Address = Address::new_id(1000);
+
+fn setup() -> (ActorHarness, MockRuntime) {
+    let mut h = ActorHarness::new(PERIOD_OFFSET);
+    h.set_proof_type(RegisteredSealProof::StackedDRG512MiBV1);
+    let rt = h.new_runtime();
+    rt.set_balance(BIG_BALANCE.clone());
+    rt.set_epoch(1);
+    h.construct_and_verify(&rt);
+    (h, rt)
+}
+
+fn commit_sector(h: &mut ActorHarness, rt: &MockRuntime) -> SectorOnChainInfo {
+    let sector = h.commit_and_prove_sectors(rt, 1, DEFAULT_SECTOR_EXPIRATION as u64, vec![], true)
+        [0]
+    .clone();
+    h.advance_and_submit_posts(rt, &[sector.clone()]);
+    sector
+}
+
+// Advances to the sector's next deadline, returning it and the sector's partition.
+fn advance_to_sector_deadline(
+    h: &ActorHarness,
+    rt: &MockRuntime,
+    sector: &SectorOnChainInfo,
+) -> (DeadlineInfo, u64) {
+    let state: State = rt.get_state();
+    let (dlidx, pidx) = state.find_sector(rt.store(), sector.sector_number).unwrap();
+    (h.advance_to_deadline(rt, dlidx), pidx)
+}
+
+fn submit_post(
+    h: &ActorHarness,
+    rt: &MockRuntime,
+    dlinfo: &DeadlineInfo,
+    pidx: u64,
+    sector: &SectorOnChainInfo,
+    caller: Address,
+) -> Result<Option<IpldBlock>, fil_actors_runtime::ActorError> {
+    let params = SubmitWindowedPoStParams {
+        deadline: dlinfo.index,
+        partitions: vec![PoStPartition { index: pidx, skipped: Default::default() }],
+        proofs: make_post_proofs(h.window_post_proof_type),
+        chain_commit_epoch: dlinfo.challenge,
+        chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+        auto_recover_faults: false,
+    };
+    let cfg = PoStConfig { caller: Some(caller), ..PoStConfig::empty() };
+    let ret = h.submit_window_post_raw(rt, dlinfo, vec![sector.clone()], params, cfg);
+    if ret.is_ok() {
+        rt.verify();
+    } else {
+        rt.reset();
+    }
+    ret
+}
+
+#[test]
+fn prover_submits_window_post_only_while_authorized() {
+    let (mut h, rt) = setup();
+    let sector = commit_sector(&mut h, &rt);
+
+    let (dlinfo, pidx) = advance_to_sector_deadline(&h, &rt, &sector);
+    let start = dlinfo.current + 1;
+    let expiration = start + 10;
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.owner);
+    h.authorize_prover(&rt, PROVER, start, expiration).unwrap();
+
+    // The authorization isn't active until its start epoch.
+    expect_abort(ExitCode::USR_FORBIDDEN, submit_post(&h, &rt, &dlinfo, pidx, &sector, PROVER));
+    rt.set_epoch(start);
+    submit_post(&h, &rt, &dlinfo, pidx, &sector, PROVER).unwrap();
+    h.advance_deadline(&rt, CronConfig::empty());
+
+    // The prover can't send other messages that a control address could.
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, PROVER);
+    rt.expect_validate_caller_addr(h.caller_addrs());
+    expect_abort(
+        ExitCode::USR_FORBIDDEN,
+        rt.call::<Actor>(
+            Method::ChangePeerID as u64,
+            IpldBlock::serialize_cbor(&ChangePeerIDParams { new_id: vec![1, 2, 3] }).unwrap(),
+        ),
+    );
+    rt.reset();
+
+    // By the sector's next deadline the authorization has expired, but the worker may still submit.
+    let (dlinfo, pidx) = advance_to_sector_deadline(&h, &rt, &sector);
+    assert!(dlinfo.current >= expiration);
+    expect_abort(ExitCode::USR_FORBIDDEN, submit_post(&h, &rt, &dlinfo, pidx, &sector, PROVER));
+    submit_post(&h, &rt, &dlinfo, pidx, &sector, h.worker).unwrap();
+    assert!(h.get_prover_authorizations(&rt).is_empty());
+    h.check_state(&rt);
+}
+
+#[test]
+fn revoked_prover_cannot_submit() {
+    let (mut h, rt) = setup();
+    let sector = commit_sector(&mut h, &rt);
+
+    let (dlinfo, pidx) = advance_to_sector_deadline(&h, &rt, &sector);
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.owner);
+    h.authorize_prover(&rt, PROVER, dlinfo.current, dlinfo.current + 1000).unwrap();
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.owner);
+    h.revoke_prover(&rt, PROVER).unwrap();
+
+    expect_abort(ExitCode::USR_FORBIDDEN, submit_post(&h, &rt, &dlinfo, pidx, &sector, PROVER));
+    assert!(h.get_prover_authorizations(&rt).is_empty());
+
+    // Revoking again finds nothing to revoke.
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.owner);
+    expect_abort_contains_message(
+        ExitCode::USR_NOT_FOUND,
+        "is not authorized",
+        h.revoke_prover(&rt, PROVER),
+    );
+    h.check_state(&rt);
+}
+
+#[test]
+fn owner_manages_authorizations() {
+    let (h, rt) = setup();
+    let epoch = *rt.epoch.borrow();
+
+    // Only the owner may authorize provers.
+    for caller in [h.worker, PROVER] {
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, caller);
+        expect_abort(ExitCode::USR_FORBIDDEN, h.authorize_prover(&rt, PROVER, epoch, epoch + 10));
+    }
+
+    // An authorization must end after it starts, and in the future.
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.owner);
+    for (start, expiration) in [(epoch + 10, epoch + 10), (epoch - 10, epoch)] {
+        expect_abort_contains_message(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            "must end after it starts",
+            h.authorize_prover(&rt, PROVER, start, expiration),
+        );
+    }
+
+    // Authorizing a prover again replaces its authorization.
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.owner);
+    h.authorize_prover(&rt, PROVER, epoch, epoch + 10).unwrap();
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.owner);
+    h.authorize_prover(&rt, PROVER, epoch + 5, epoch + 20).unwrap();
+    assert_eq!(
+        vec![ProverAuthorization { prover: PROVER, start: epoch + 5, expiration: epoch + 20 }],
+        h.get_prover_authorizations(&rt)
+    );
+
+    // The number of authorizations is bounded, but expired ones don't count.
+    let max = rt.policy.max_prover_authorizations as u64;
+    for i in 1..max {
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.owner);
+        h.authorize_prover(&rt, Address::new_id(1000 + i), epoch, epoch + 10).unwrap();
+    }
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.owner);
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "cannot authorize more than",
+        h.authorize_prover(&rt, Address::new_id(1000 + max), epoch, epoch + 10),
+    );
+    rt.set_epoch(epoch + 10);
+    assert_eq!(
+        vec![PROVER],
+        h.get_prover_authorizations(&rt).iter().map(|a| a.prover).collect::<Vec<_>>()
+    );
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.owner);
+    h.authorize_prover(&rt, Address::new_id(1000 + max), epoch + 10, epoch + 20).unwrap();
+    assert_eq!(2, h.get_info(&rt).prover_authorizations.len());
+    h.check_state(&rt);
+}
+
+#[test]
+fn prover_must_resolve() {
+    let (h, rt) = setup();
+    let epoch = *rt.epoch.borrow();
+    let unresolvable = Address::new_actor(b"nobody");
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.owner);
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "unable to resolve prover address",
+        h.authorize_prover(&rt, unresolvable, epoch, epoch + 10),
+    );
+    h.check_state(&rt);
+}
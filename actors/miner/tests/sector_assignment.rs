@@ -125,6 +125,7 @@ mod sector_assignment {
                         quant_spec,
                         0,
                         &mut post_partitions,
+                        false,
                     )
                     .unwrap();
 
@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use fil_actor_miner::{
+    expected_reward_for_power, pre_commit_deposit_for_power, qa_power_for_sector,
+    qa_power_for_weight, qa_power_max, qa_power_max_for_policy, ExpirationExtension2,
+    ExtendSectorExpiration2Params, SectorOnChainInfoFlags, State,
+};
+use fil_actors_runtime::runtime::Runtime;
+use fil_actors_runtime::{DealWeight, EPOCHS_IN_DAY};
+use fvm_shared::bigint::Zero;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+
+mod util;
+use util::*;
+
+// an expiration ~10 days greater than effective min expiration taking into account 30 days max
+// between pre and prove commit
+const DEFAULT_SECTOR_EXPIRATION: ChainEpoch = 220;
+const PERIOD_OFFSET: ChainEpoch = 100;
+
+#[test]
+fn prove_commit_applies_duration_multiplier() {
+    let h = ActorHarness::new(PERIOD_OFFSET);
+    let mut rt = h.new_runtime();
+    rt.policy.sector_duration_multiplier_enabled = true;
+    rt.balance.replace(BIG_BALANCE.clone());
+
+    let precommit_epoch = PERIOD_OFFSET + 1;
+    rt.set_epoch(precommit_epoch);
+    h.construct_and_verify(&rt);
+    let dl_info = h.deadline(&rt);
+
+    // Commit for well over the base term, so the multiplier raises the sector's power.
+    let sector_no = 100;
+    let prove_commit_epoch = precommit_epoch + rt.policy.pre_commit_challenge_delay + 1;
+    let expiration = dl_info.period_end() + 1000 * rt.policy.wpost_proving_period;
+    let precommit_params =
+        h.make_pre_commit_params(sector_no, precommit_epoch - 1, expiration, vec![]);
+    let precommit =
+        h.pre_commit_sector_and_get(&rt, precommit_params, PreCommitConfig::empty(), true);
+
+    // The deposit covers the maximum power of a sector, including the multiplier.
+    let pwr_estimate = qa_power_max_for_policy(&rt.policy, h.sector_size);
+    assert!(pwr_estimate > qa_power_max(h.sector_size));
+    let expected_deposit = pre_commit_deposit_for_power(
+        &h.epoch_reward_smooth,
+        &h.epoch_qa_power_smooth,
+        &pwr_estimate,
+    );
+    assert_eq!(expected_deposit, precommit.pre_commit_deposit);
+
+    rt.set_epoch(prove_commit_epoch);
+    rt.balance.replace(TokenAmount::from_whole(1000));
+    let sector = h
+        .prove_commit_sector_and_confirm(
+            &rt,
+            &precommit,
+            h.make_prove_commit_params(sector_no),
+            ProveCommitConfig::empty(),
+        )
+        .unwrap();
+    assert!(sector.flags.contains(SectorOnChainInfoFlags::DURATION_MULTIPLIER));
+
+    let duration = expiration - prove_commit_epoch;
+    let zero = DealWeight::zero();
+    let qa_power = qa_power_for_weight(h.sector_size, duration, &zero, &zero, true);
+    assert!(qa_power > qa_power_for_weight(h.sector_size, duration, &zero, &zero, false));
+    assert_eq!(qa_power, qa_power_for_sector(h.sector_size, &sector));
+
+    // The initial pledge is computed from the multiplied power.
+    let expected_initial_pledge = h.initial_pledge_for_power(&rt, &qa_power);
+    assert_eq!(expected_initial_pledge, sector.initial_pledge);
+    assert_eq!(expected_initial_pledge, h.get_state(&rt).initial_pledge);
+    h.check_state(&rt);
+}
+
+#[test]
+fn extension_applies_duration_multiplier() {
+    let mut h = ActorHarness::new(PERIOD_OFFSET);
+    let mut rt = h.new_runtime();
+    rt.balance.replace(BIG_BALANCE.clone());
+    rt.set_epoch(PERIOD_OFFSET + 1);
+    h.construct_and_verify(&rt);
+
+    // The sector is committed before the multiplier is enabled.
+    let old_sector =
+        h.commit_and_prove_sectors(&rt, 1, DEFAULT_SECTOR_EXPIRATION as u64, vec![], true)[0]
+            .to_owned();
+    assert!(!old_sector.flags.contains(SectorOnChainInfoFlags::DURATION_MULTIPLIER));
+    h.advance_and_submit_posts(&rt, &[old_sector.clone()]);
+
+    rt.policy.sector_duration_multiplier_enabled = true;
+    let state: State = rt.get_state();
+    let (deadline, partition) = state.find_sector(rt.store(), old_sector.sector_number).unwrap();
+    let new_expiration = old_sector.expiration + 500 * rt.policy.wpost_proving_period;
+    let params = ExtendSectorExpiration2Params {
+        extensions: vec![ExpirationExtension2 {
+            deadline,
+            partition,
+            sectors: bitfield_from_slice(&[old_sector.sector_number]),
+            sectors_with_claims: vec![],
+            new_expiration,
+        }],
+    };
+    h.extend_sectors2(&rt, params, HashMap::new()).unwrap();
+
+    // The extended sector takes the multiplier, raising its power and expected reward.
+    let new_sector = h.get_sector(&rt, old_sector.sector_number);
+    assert!(new_sector.flags.contains(SectorOnChainInfoFlags::DURATION_MULTIPLIER));
+    let qa_power = qa_power_for_sector(h.sector_size, &new_sector);
+    assert!(qa_power > qa_power_for_sector(h.sector_size, &old_sector));
+    let expected_day_reward = expected_reward_for_power(
+        &h.epoch_reward_smooth,
+        &h.epoch_qa_power_smooth,
+        &qa_power,
+        EPOCHS_IN_DAY,
+    );
+    assert_eq!(expected_day_reward, new_sector.expected_day_reward);
+    assert_eq!(old_sector.expected_day_reward, new_sector.replaced_day_reward);
+    h.check_state(&rt);
+}
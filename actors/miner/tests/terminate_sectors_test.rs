@@ -1,16 +1,21 @@
+use cid::multihash::{Code, Multihash, MultihashDigest};
+use cid::Cid;
 use fil_actor_miner::{
-    expected_reward_for_power, pledge_penalty_for_termination, qa_power_for_sector, Actor,
-    CronEventPayload, DeferredCronEventParams, Method, SectorOnChainInfo, State,
-    TerminateSectorsParams, TerminationDeclaration, CRON_EVENT_PROCESS_EARLY_TERMINATIONS,
-    INITIAL_PLEDGE_PROJECTION_PERIOD,
+    expected_reward_for_power, is_cc_fast_removal, pledge_penalty_for_cc_fast_removal,
+    pledge_penalty_for_termination, qa_power_for_sector, Actor, CronEventPayload,
+    DeferredCronEventParams, Method, SectorOnChainInfo, State, TerminateSectorsParams,
+    TerminationDeclaration, CRON_EVENT_PROCESS_EARLY_TERMINATIONS,
+    INITIAL_PLEDGE_PROJECTION_PERIOD, MAX_HANDOFF_MANIFEST_CID_LEN,
 };
 use fil_actors_runtime::{
+    runtime::policy_constants::CC_SECTOR_FAST_REMOVAL_MAX_AGE,
     runtime::Runtime,
     test_utils::{expect_abort_contains_message, MockRuntime, ACCOUNT_ACTOR_CODE_ID},
-    BURNT_FUNDS_ACTOR_ADDR, EPOCHS_IN_DAY, STORAGE_MARKET_ACTOR_ADDR, STORAGE_POWER_ACTOR_ADDR,
-    SYSTEM_ACTOR_ADDR,
+    PenaltyCategory, BURNT_FUNDS_ACTOR_ADDR, EPOCHS_IN_DAY, STORAGE_MARKET_ACTOR_ADDR,
+    STORAGE_POWER_ACTOR_ADDR, SYSTEM_ACTOR_ADDR,
 };
 use fvm_ipld_bitfield::BitField;
+use fvm_ipld_encoding::DAG_CBOR;
 use fvm_shared::{econ::TokenAmount, error::ExitCode, METHOD_SEND};
 use std::collections::HashMap;
 
@@ -23,6 +28,7 @@ use fil_actor_miner::ext::market::{
 use fil_actor_miner::ext::power::UPDATE_PLEDGE_TOTAL_METHOD;
 use fil_actors_runtime::test_utils::POWER_ACTOR_CODE_ID;
 use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_ipld_encoding::tuple::*;
 use fvm_ipld_encoding::RawBytes;
 use fvm_shared::piece::PaddedPieceSize;
 use fvm_shared::sector::SectorNumber;
@@ -35,7 +41,9 @@ fn setup() -> (ActorHarness, MockRuntime) {
     let precommit_epoch = 1;
 
     let h = ActorHarness::new(period_offset);
-    let rt = h.new_runtime();
+    let mut rt = h.new_runtime();
+    // Sectors pay the full termination fee unless a test enables fast removal.
+    rt.policy.cc_sector_fast_removal_max_age = 0;
     h.construct_and_verify(&rt);
     rt.balance.replace(TokenAmount::from_atto(big_balance));
     rt.set_epoch(precommit_epoch);
@@ -161,6 +169,7 @@ fn cannot_terminate_a_sector_when_the_challenge_window_is_open() {
             partition: partition_index,
             sectors: util::make_bitfield(&[sector.sector_number]),
         }],
+        handoff_manifest: None,
     };
 
     rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.worker);
@@ -222,10 +231,17 @@ fn owner_cannot_terminate_if_market_fails() {
         BURNT_FUNDS_ACTOR_ADDR,
         METHOD_SEND,
         None,
-        expected_fee,
+        expected_fee.clone(),
         None,
         ExitCode::OK,
     );
+    expect_penalty_event(
+        &rt,
+        PenaltyCategory::EarlyTermination,
+        &expected_fee,
+        &expected_fee,
+        &TokenAmount::zero(),
+    );
 
     rt.expect_send_simple(
         STORAGE_POWER_ACTOR_ADDR,
@@ -262,6 +278,7 @@ fn owner_cannot_terminate_if_market_fails() {
                     partition: partition_index,
                     sectors: make_bitfield(&[sector.sector_number]),
                 }],
+                handoff_manifest: None,
             })
             .unwrap(),
         )
@@ -309,6 +326,220 @@ fn system_can_terminate_if_market_cron_fails() {
     h.check_state(&rt);
 }
 
+#[test]
+fn terminate_with_handoff_manifest() {
+    let (mut h, rt) = setup();
+
+    let sector_info =
+        h.commit_and_prove_sectors(&rt, 2, DEFAULT_SECTOR_EXPIRATION, Vec::new(), true);
+    h.advance_and_submit_posts(&rt, &sector_info);
+
+    let expected_fee = sector_info
+        .iter()
+        .fold(TokenAmount::zero(), |acc, s| acc + calc_expected_fee_for_termination(&h, &rt, s));
+    let snos: Vec<SectorNumber> = sector_info.iter().map(|s| s.sector_number).collect();
+
+    // The harness expects an event recording the manifest and all the terminated sectors.
+    let manifest = Cid::new_v1(DAG_CBOR, Code::Blake2b256.digest(b"handoff manifest"));
+    h.terminate_sectors_with_handoff(
+        &rt,
+        &bitfield_from_slice(&snos),
+        expected_fee,
+        Some(manifest),
+    );
+
+    let state: State = rt.get_state();
+    assert!(state.initial_pledge.is_zero());
+    h.check_state(&rt);
+}
+
+#[test]
+fn cannot_terminate_with_invalid_handoff_manifest() {
+    let (mut h, rt) = setup();
+
+    let sector_info =
+        h.commit_and_prove_sectors(&rt, 1, DEFAULT_SECTOR_EXPIRATION, Vec::new(), true);
+    h.advance_and_submit_posts(&rt, &sector_info);
+    let sector = sector_info.into_iter().next().unwrap();
+
+    let state: State = rt.get_state();
+    let (deadline_index, partition_index) =
+        state.find_sector(rt.store(), sector.sector_number).unwrap();
+
+    let inline = Cid::new_v1(DAG_CBOR, Multihash::wrap(0, b"inline manifest").unwrap());
+    let oversized = Cid::new_v1(DAG_CBOR, Code::Blake2b512.digest(b"handoff manifest"));
+    assert!(oversized.to_bytes().len() > MAX_HANDOFF_MANIFEST_CID_LEN);
+    for manifest in [inline, oversized] {
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.worker);
+        let res = rt.call::<Actor>(
+            Method::TerminateSectors as u64,
+            IpldBlock::serialize_cbor(&TerminateSectorsParams {
+                terminations: vec![TerminationDeclaration {
+                    deadline: deadline_index,
+                    partition: partition_index,
+                    sectors: make_bitfield(&[sector.sector_number]),
+                }],
+                handoff_manifest: Some(manifest),
+            })
+            .unwrap(),
+        );
+        expect_abort_contains_message(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            "invalid handoff manifest",
+            res,
+        );
+        rt.verify();
+    }
+
+    h.check_state(&rt);
+}
+
+#[test]
+fn terminate_params_without_handoff_manifest_decode() {
+    #[derive(Serialize_tuple)]
+    struct LegacyTerminateSectorsParams {
+        terminations: Vec<TerminationDeclaration>,
+    }
+
+    let terminations =
+        vec![TerminationDeclaration { deadline: 1, partition: 2, sectors: make_bitfield(&[3, 4]) }];
+    let legacy = LegacyTerminateSectorsParams { terminations };
+    let decoded: TerminateSectorsParams =
+        RawBytes::serialize(&legacy).unwrap().deserialize().unwrap();
+    assert!(decoded.handoff_manifest.is_none());
+    assert_eq!(1, decoded.terminations.len());
+
+    let manifest = Cid::new_v1(DAG_CBOR, Code::Blake2b256.digest(b"handoff manifest"));
+    let params = TerminateSectorsParams { handoff_manifest: Some(manifest), ..decoded };
+    let decoded: TerminateSectorsParams =
+        RawBytes::serialize(&params).unwrap().deserialize().unwrap();
+    assert_eq!(Some(manifest), decoded.handoff_manifest);
+    assert_eq!(1, decoded.terminations.len());
+}
+
+#[test]
+fn estimated_termination_fee_matches_termination() {
+    let (mut h, rt) = setup();
+
+    let sector_info =
+        h.commit_and_prove_sectors(&rt, 2, DEFAULT_SECTOR_EXPIRATION, Vec::new(), true);
+    h.advance_and_submit_posts(&rt, &sector_info);
+    h.apply_rewards(&rt, BIG_REWARDS.clone(), TokenAmount::zero());
+
+    let sectors = bitfield_from_slice(
+        &sector_info.iter().map(|sector| sector.sector_number).collect::<Vec<_>>(),
+    );
+    let estimate = h.estimate_termination_fee(&rt, &sectors).unwrap();
+    let expected_fee: TokenAmount =
+        sector_info.iter().map(|sector| calc_expected_fee_for_termination(&h, &rt, sector)).sum();
+    let expected_pledge: TokenAmount =
+        sector_info.iter().map(|sector| sector.initial_pledge.clone()).sum();
+    assert_eq!(expected_fee, estimate.penalty);
+    assert_eq!(expected_pledge, estimate.pledge_released);
+
+    // Terminating the sectors in the same epoch burns exactly the estimated penalty.
+    h.terminate_sectors(&rt, &sectors, estimate.penalty);
+
+    // Terminated sectors can't be estimated.
+    let result = h.estimate_termination_fee(&rt, &sectors);
+    expect_abort_contains_message(ExitCode::USR_ILLEGAL_ARGUMENT, "are not live", result);
+    rt.reset();
+    h.check_state(&rt);
+}
+
+#[test]
+fn young_cc_sector_pays_pro_rated_fee() {
+    let (mut h, mut rt) = setup();
+    rt.policy.cc_sector_fast_removal_max_age = CC_SECTOR_FAST_REMOVAL_MAX_AGE;
+
+    let sector_info =
+        h.commit_and_prove_sectors(&rt, 1, DEFAULT_SECTOR_EXPIRATION, Vec::new(), true);
+    h.advance_and_submit_posts(&rt, &sector_info);
+    h.apply_rewards(&rt, BIG_REWARDS.clone(), TokenAmount::zero());
+    let sector = sector_info.into_iter().next().unwrap();
+    assert!(is_cc_fast_removal(&rt.policy, &sector, *rt.epoch.borrow()));
+
+    let full_fee = calc_expected_fee_for_termination(&h, &rt, &sector);
+    let expected_fee = calc_fast_removal_fee_for_termination(&rt, &sector, &full_fee);
+    assert!(expected_fee < full_fee);
+    // The fee covers at least the reward the sector is expected to have earned.
+    let sector_age = *rt.epoch.borrow() - sector.activation;
+    assert!(expected_fee >= (&sector.expected_day_reward * sector_age).div_floor(EPOCHS_IN_DAY));
+
+    // The harness expects the cc-sector-fast-removed event for the sector.
+    h.terminate_sectors(&rt, &bitfield_from_slice(&[sector.sector_number]), expected_fee);
+    h.check_state(&rt);
+}
+
+#[test]
+fn cc_sector_past_fast_removal_age_pays_full_fee() {
+    let (mut h, mut rt) = setup();
+    rt.policy.cc_sector_fast_removal_max_age = 1;
+
+    let sector_info =
+        h.commit_and_prove_sectors(&rt, 1, DEFAULT_SECTOR_EXPIRATION, Vec::new(), true);
+    h.advance_and_submit_posts(&rt, &sector_info);
+    h.apply_rewards(&rt, BIG_REWARDS.clone(), TokenAmount::zero());
+    let sector = sector_info.into_iter().next().unwrap();
+    assert!(!is_cc_fast_removal(&rt.policy, &sector, *rt.epoch.borrow()));
+
+    let expected_fee = calc_expected_fee_for_termination(&h, &rt, &sector);
+    h.terminate_sectors(&rt, &bitfield_from_slice(&[sector.sector_number]), expected_fee);
+    h.check_state(&rt);
+}
+
+#[test]
+fn young_sector_with_deals_pays_full_fee() {
+    let (mut h, mut rt) = setup();
+    rt.policy.cc_sector_fast_removal_max_age = CC_SECTOR_FAST_REMOVAL_MAX_AGE;
+    // One sector with no data and one with a deal.
+    let sectors = h.commit_and_prove_sectors_with_cfgs(
+        &rt,
+        2,
+        DEFAULT_SECTOR_EXPIRATION,
+        vec![vec![], vec![1]],
+        true,
+        ProveCommitConfig {
+            verify_deals_exit: Default::default(),
+            claim_allocs_exit: Default::default(),
+            activated_deals: HashMap::from_iter(vec![(
+                1,
+                vec![ActivatedDeal {
+                    client: 0,
+                    allocation_id: NO_ALLOCATION_ID,
+                    data: Default::default(),
+                    size: PaddedPieceSize(1024),
+                }],
+            )]),
+        },
+    );
+    h.advance_and_submit_posts(&rt, &sectors);
+    h.apply_rewards(&rt, BIG_REWARDS.clone(), TokenAmount::zero());
+    let epoch = *rt.epoch.borrow();
+    assert!(is_cc_fast_removal(&rt.policy, &sectors[0], epoch));
+    assert!(!is_cc_fast_removal(&rt.policy, &sectors[1], epoch));
+
+    let cc_full_fee = calc_expected_fee_for_termination(&h, &rt, &sectors[0]);
+    let expected_fee = calc_fast_removal_fee_for_termination(&rt, &sectors[0], &cc_full_fee)
+        + calc_expected_fee_for_termination(&h, &rt, &sectors[1]);
+    let snos: Vec<SectorNumber> = sectors.iter().map(|s| s.sector_number).collect();
+    h.terminate_sectors(&rt, &bitfield_from_slice(&snos), expected_fee);
+    h.check_state(&rt);
+}
+
+fn calc_fast_removal_fee_for_termination(
+    rt: &MockRuntime,
+    sector: &SectorOnChainInfo,
+    full_fee: &TokenAmount,
+) -> TokenAmount {
+    pledge_penalty_for_cc_fast_removal(
+        full_fee,
+        &sector.expected_day_reward,
+        *rt.epoch.borrow() - sector.activation,
+        rt.policy.cc_sector_fast_removal_max_age,
+    )
+}
+
 fn calc_expected_fee_for_termination(
     h: &ActorHarness,
     rt: &MockRuntime,
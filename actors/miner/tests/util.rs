@@ -16,7 +16,7 @@ use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::de::Deserialize;
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_ipld_encoding::ser::Serialize;
-use fvm_ipld_encoding::{BytesDe, CborStore, RawBytes};
+use fvm_ipld_encoding::{BytesDe, CborStore, RawBytes, DAG_CBOR};
 use fvm_shared::address::Address;
 use fvm_shared::bigint::BigInt;
 use fvm_shared::bigint::Zero;
@@ -44,7 +44,7 @@ use num_traits::Signed;
 use fil_actor_account::Method as AccountMethod;
 use fil_actor_market::{
     ActivatedDeal, BatchActivateDealsParams, BatchActivateDealsResult, Method as MarketMethod,
-    OnMinerSectorsTerminateParams, SectorDealActivation, SectorDeals,
+    OnMinerSectorsTerminateParams, PublishStorageDealsReturn, SectorDealActivation, SectorDeals,
     VerifyDealsForActivationParams, VerifyDealsForActivationReturn, NO_ALLOCATION_ID,
 };
 use fil_actor_miner::{
@@ -57,44 +57,51 @@ use fil_actor_miner::{
         SectorAllocationClaims, SectorClaimSummary, CLAIM_ALLOCATIONS_METHOD,
     },
     ext::verifreg::{Claim as FILPlusClaim, ClaimID, GetClaimsParams, GetClaimsReturn},
-    initial_pledge_for_power, locked_reward_from_reward, max_prove_commit_duration,
-    new_deadline_info_from_offset_and_epoch, pledge_penalty_for_continued_fault, power_for_sectors,
-    qa_power_for_sector, qa_power_for_weight, reward_for_consensus_slash_report,
+    initial_pledge_for_power, is_cc_fast_removal, locked_reward_from_reward,
+    max_prove_commit_duration, new_deadline_info_from_offset_and_epoch,
+    pledge_penalty_for_continued_fault, power_for_sectors, qa_power_for_sector,
+    qa_power_for_weight, reward_for_consensus_slash_report,
     testing::{check_deadline_state_invariants, check_state_invariants, DeadlineStateSummary},
-    ActiveBeneficiary, Actor, ApplyRewardParams, BeneficiaryTerm, BitFieldQueue,
-    ChangeBeneficiaryParams, ChangeMultiaddrsParams, ChangePeerIDParams, ChangeWorkerAddressParams,
-    CheckSectorProvenParams, CompactCommD, CompactPartitionsParams, CompactSectorNumbersParams,
-    ConfirmSectorProofsParams, CronEventPayload, DataActivationNotification, Deadline,
-    DeadlineInfo, Deadlines, DeclareFaultsParams, DeclareFaultsRecoveredParams,
-    DeferredCronEventParams, DisputeWindowedPoStParams, ExpirationQueue, ExpirationSet,
-    ExtendSectorExpiration2Params, ExtendSectorExpirationParams, FaultDeclaration,
-    GetAvailableBalanceReturn, GetBeneficiaryReturn, GetControlAddressesReturn,
-    GetMultiaddrsReturn, GetPeerIDReturn, Method, Method as MinerMethod,
-    MinerConstructorParams as ConstructorParams, MinerInfo, Partition, PendingBeneficiaryChange,
-    PieceActivationManifest, PieceChange, PieceReturn, PoStPartition, PowerPair,
-    PreCommitSectorBatchParams, PreCommitSectorBatchParams2, PreCommitSectorParams,
+    ActiveBeneficiary, Actor, ApplyRewardParams, AuthorizeProverParams, BeneficiaryTerm,
+    BitFieldQueue, ChangeBeneficiaryParams, ChangeMultiaddrsParams, ChangePeerIDParams,
+    ChangeWorkerAddressParams, CheckSectorProvenParams, CompactCommD, CompactPartitionsParams,
+    CompactSectorNumbersParams, CompactSectorsAcrossDeadlinesParams, ConfirmSectorProofsParams,
+    CronEventPayload, DataActivationNotification, Deadline, DeadlineInfo, Deadlines,
+    DeclareFaultsParams, DeclareFaultsRecoveredParams, DeferredCronEventParams,
+    DisputeWindowedPoStParams, EstimateTerminationFeeParams, EstimateTerminationFeeReturn,
+    ExpirationQueue, ExpirationSet, ExtendSectorExpiration2Params, ExtendSectorExpirationParams,
+    FaultDeclaration, GetAvailableBalanceReturn, GetBeneficiaryReturn, GetControlAddressesReturn,
+    GetMultiaddrsReturn, GetPeerIDReturn, GetProverAuthorizationsReturn,
+    GetWithdrawableBalanceReturn, Method, Method as MinerMethod,
+    MinerConstructorParams as ConstructorParams, MinerInfo, OnVerifiedClaimsExpiredParams,
+    OnboardSectorWithDealsParams, OnboardSectorWithDealsReturn, OnboardingSectorInfo, Partition,
+    PendingBeneficiaryChange, PieceActivationManifest, PieceChange, PieceReturn, PoStPartition,
+    PowerPair, PreCommitSectorBatchParams, PreCommitSectorBatchParams2, PreCommitSectorParams,
     ProveCommitAggregateParams, ProveCommitSectorParams, ProveCommitSectors3Params,
-    ProveCommitSectors3Return, QuantSpec, RecoveryDeclaration, ReportConsensusFaultParams,
-    SectorActivationManifest, SectorChanges, SectorContentChangedParams,
-    SectorContentChangedReturn, SectorOnChainInfo, SectorPreCommitInfo, SectorPreCommitOnChainInfo,
-    SectorReturn, SectorUpdateManifest, Sectors, State, SubmitWindowedPoStParams,
-    TerminateSectorsParams, TerminationDeclaration, VerifiedAllocationKey, VestingFunds,
-    WindowedPoSt, WithdrawBalanceParams, WithdrawBalanceReturn, CRON_EVENT_PROVING_DEADLINE,
-    NO_QUANTIZATION, REWARD_VESTING_SPEC, SECTORS_AMT_BITWIDTH, SECTOR_CONTENT_CHANGED,
+    ProveCommitSectors3Return, ProverAuthorization, QuantSpec, RecoveryDeclaration,
+    ReportConsensusFaultParams, RevokeProverParams, SectorActivationManifest, SectorChanges,
+    SectorContentChangedParams, SectorContentChangedReturn, SectorOnChainInfo,
+    SectorOnChainInfoFlags, SectorPreCommitInfo, SectorPreCommitOnChainInfo, SectorReturn,
+    SectorUpdateManifest, Sectors, State, SubmitWindowedPoStParams, TerminateSectorsParams,
+    TerminationDeclaration, VerifiedAllocationKey, VestingFunds, WindowedPoSt,
+    WithdrawBalanceParams, WithdrawBalanceReturn, CRON_EVENT_PROVING_DEADLINE, NO_QUANTIZATION,
+    REWARD_VESTING_SPEC, SECTORS_AMT_BITWIDTH, SECTOR_CONTENT_CHANGED,
 };
+use fil_actor_miner::{ListSectorsParams, ListSectorsReturn};
 use fil_actor_miner::{ProveReplicaUpdates3Params, ProveReplicaUpdates3Return};
 use fil_actor_power::{
     CurrentTotalPowerReturn, EnrollCronEventParams, Method as PowerMethod, UpdateClaimedPowerParams,
 };
 use fil_actor_reward::{Method as RewardMethod, ThisEpochRewardReturn};
 use fil_actors_runtime::cbor::serialize;
+use fil_actors_runtime::query::ListRequest;
 use fil_actors_runtime::runtime::{DomainSeparationTag, Runtime, RuntimePolicy};
 use fil_actors_runtime::test_blockstores::MemoryBlockstore;
 use fil_actors_runtime::{test_utils::*, BatchReturn, BatchReturnGen, EventBuilder};
 use fil_actors_runtime::{
-    ActorDowncast, ActorError, Array, DealWeight, MessageAccumulator, BURNT_FUNDS_ACTOR_ADDR,
-    INIT_ACTOR_ADDR, REWARD_ACTOR_ADDR, STORAGE_MARKET_ACTOR_ADDR, STORAGE_POWER_ACTOR_ADDR,
-    VERIFIED_REGISTRY_ACTOR_ADDR,
+    ActorDowncast, ActorError, Array, DealWeight, MessageAccumulator, PenaltyCategory,
+    BURNT_FUNDS_ACTOR_ADDR, INIT_ACTOR_ADDR, PENALTY_ASSESSED_EVENT, REWARD_ACTOR_ADDR,
+    STORAGE_MARKET_ACTOR_ADDR, STORAGE_POWER_ACTOR_ADDR, VERIFIED_REGISTRY_ACTOR_ADDR,
 };
 
 const RECEIVER_ID: u64 = 1000;
@@ -509,6 +516,21 @@ impl ActorHarness {
         rt.verify();
     }
 
+    pub fn on_verified_claims_expired(
+        &self,
+        rt: &MockRuntime,
+        params: OnVerifiedClaimsExpiredParams,
+    ) -> Result<Option<IpldBlock>, ActorError> {
+        rt.set_caller(*VERIFREG_ACTOR_CODE_ID, VERIFIED_REGISTRY_ACTOR_ADDR);
+        rt.expect_validate_caller_addr(vec![VERIFIED_REGISTRY_ACTOR_ADDR]);
+        let ret = rt.call::<Actor>(
+            Method::OnVerifiedClaimsExpired as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        )?;
+        rt.verify();
+        Ok(ret)
+    }
+
     pub fn get_deadline_info(&self, rt: &MockRuntime) -> DeadlineInfo {
         let state = self.get_state(rt);
         state.recorded_deadline_info(&rt.policy, *rt.epoch.borrow())
@@ -593,7 +615,72 @@ impl ActorHarness {
     ) -> Result<Option<IpldBlock>, ActorError> {
         rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, self.worker);
         rt.expect_validate_caller_addr(self.caller_addrs());
+        self.expect_pre_commit_sector_batch_v2(rt, sectors, first_for_miner, base_fee);
+
+        let param = PreCommitSectorBatchParams2 { sectors: sectors.into() };
+        let result = rt.call::<Actor>(
+            Method::PreCommitSectorBatch2 as u64,
+            IpldBlock::serialize_cbor(&param).unwrap(),
+        );
+        result
+    }
+
+    /// Publishes deals and pre-commits a sector with them, expecting the market to publish
+    /// the deals with the IDs in the sector's pre-commit info.
+    pub fn onboard_sector_with_deals(
+        &self,
+        rt: &MockRuntime,
+        deals: RawBytes,
+        sector: &SectorPreCommitInfo,
+        first_for_miner: bool,
+    ) -> Result<OnboardSectorWithDealsReturn, ActorError> {
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, self.worker);
+        rt.expect_validate_caller_addr(self.caller_addrs());
+        rt.expect_send_simple(
+            STORAGE_MARKET_ACTOR_ADDR,
+            MarketMethod::PublishStorageDeals as u64,
+            Some(IpldBlock { codec: DAG_CBOR, data: deals.to_vec() }),
+            TokenAmount::zero(),
+            IpldBlock::serialize_cbor(&PublishStorageDealsReturn {
+                ids: sector.deal_ids.clone(),
+                valid_deals: BitField::try_from_bits(0..sector.deal_ids.len() as u64).unwrap(),
+            })
+            .unwrap(),
+            ExitCode::OK,
+        );
+        self.expect_pre_commit_sector_batch_v2(
+            rt,
+            std::slice::from_ref(sector),
+            first_for_miner,
+            &TokenAmount::zero(),
+        );
+
+        let params = OnboardSectorWithDealsParams {
+            deals,
+            sector: OnboardingSectorInfo {
+                seal_proof: sector.seal_proof,
+                sector_number: sector.sector_number,
+                sealed_cid: sector.sealed_cid,
+                seal_rand_epoch: sector.seal_rand_epoch,
+                expiration: sector.expiration,
+                unsealed_cid: sector.unsealed_cid.clone(),
+            },
+        };
+        let result = rt.call::<Actor>(
+            Method::OnboardSectorWithDeals as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        )?;
+        rt.verify();
+        Ok(result.unwrap().deserialize().unwrap())
+    }
 
+    fn expect_pre_commit_sector_batch_v2(
+        &self,
+        rt: &MockRuntime,
+        sectors: &[SectorPreCommitInfo],
+        first_for_miner: bool,
+        base_fee: &TokenAmount,
+    ) {
         self.expect_query_network_info(rt);
         let mut sector_deals = Vec::new();
         let mut sector_deal_data = Vec::new();
@@ -663,13 +750,6 @@ impl ActorHarness {
         for sn in sectors {
             expect_event(rt, "sector-precommitted", &sn.sector_number);
         }
-
-        let param = PreCommitSectorBatchParams2 { sectors: sectors.into() };
-        let result = rt.call::<Actor>(
-            Method::PreCommitSectorBatch2 as u64,
-            IpldBlock::serialize_cbor(&param).unwrap(),
-        );
-        result
     }
 
     pub fn pre_commit_sector_batch_and_get(
@@ -1107,6 +1187,7 @@ impl ActorHarness {
                         duration,
                         &deal_weight,
                         &verified_deal_weight,
+                        rt.policy.sector_duration_multiplier_enabled,
                     );
                     expected_qa_power += &qa_power_delta;
                     expected_raw_power += self.sector_size as u64;
@@ -1274,8 +1355,13 @@ impl ActorHarness {
                     deal_size += piece.size.0 * duration as u64;
                 }
             }
-            let qa_power_delta =
-                qa_power_for_weight(self.sector_size, duration, &deal_size, &verified_size);
+            let qa_power_delta = qa_power_for_weight(
+                self.sector_size,
+                duration,
+                &deal_size,
+                &verified_size,
+                rt.policy.sector_duration_multiplier_enabled,
+            );
             expected_qa_power += &qa_power_delta;
             expected_pledge += self.initial_pledge_for_power(rt, &qa_power_delta);
         }
@@ -1488,9 +1574,13 @@ impl ActorHarness {
                 }
             }
 
-            let qa_power_delta =
-                qa_power_for_weight(self.sector_size, duration, &deal_size, &verified_size)
-                    - qa_power_for_sector(self.sector_size, &sector);
+            let qa_power_delta = qa_power_for_weight(
+                self.sector_size,
+                duration,
+                &deal_size,
+                &verified_size,
+                rt.policy.sector_duration_multiplier_enabled,
+            ) - qa_power_for_sector(self.sector_size, &sector);
             expected_qa_power += &qa_power_delta;
             expected_pledge += self.initial_pledge_for_power(rt, &qa_power_delta);
         }
@@ -1651,6 +1741,7 @@ impl ActorHarness {
         let mut power_delta = PowerPair::zero();
         power_delta += &cfg.detected_faults_power_delta.unwrap_or_else(PowerPair::zero);
         power_delta += &cfg.expired_sectors_power_delta.unwrap_or_else(PowerPair::zero);
+        power_delta += &cfg.expired_claims_power_delta.unwrap_or_else(PowerPair::zero);
         expect_update_power(rt, power_delta);
 
         let mut penalty_total = TokenAmount::zero();
@@ -1686,6 +1777,26 @@ impl ActorHarness {
 
         expect_update_pledge(rt, &pledge_delta);
 
+        if cfg.expired_precommit_penalty.is_positive() {
+            expect_penalty_event(
+                rt,
+                PenaltyCategory::PreCommitExpiry,
+                &cfg.expired_precommit_penalty,
+                &cfg.expired_precommit_penalty,
+                &TokenAmount::zero(),
+            );
+        }
+        let continued_faults_fee = &cfg.continued_faults_penalty + &cfg.continued_faults_withheld;
+        if continued_faults_fee.is_positive() {
+            expect_penalty_event(
+                rt,
+                PenaltyCategory::ContinuedFault,
+                &continued_faults_fee,
+                &cfg.continued_faults_penalty,
+                &cfg.continued_faults_withheld,
+            );
+        }
+
         // Re-enrollment for next period.
         if !cfg.no_enrollment {
             let params = make_deadline_cron_event_params(cfg.expected_enrollment);
@@ -1726,6 +1837,7 @@ impl ActorHarness {
             proofs: make_post_proofs(self.window_post_proof_type),
             chain_commit_epoch: deadline.challenge,
             chain_commit_rand: Randomness(TEST_RANDOMNESS_ARRAY_FROM_ONE.into()),
+            auto_recover_faults: false,
         };
         self.submit_window_post_raw(rt, deadline, infos, params, cfg).unwrap();
         rt.verify();
@@ -1739,7 +1851,7 @@ impl ActorHarness {
         params: SubmitWindowedPoStParams,
         cfg: PoStConfig,
     ) -> Result<Option<IpldBlock>, ActorError> {
-        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, self.worker);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, cfg.caller.unwrap_or(self.worker));
         let chain_commit_rand = match cfg.chain_randomness {
             Some(r) => r,
             None => TEST_RANDOMNESS_ARRAY_FROM_ONE.into(),
@@ -1750,7 +1862,9 @@ impl ActorHarness {
             Vec::new(),
             chain_commit_rand,
         );
-        rt.expect_validate_caller_addr(self.caller_addrs());
+        let mut caller_addrs = self.caller_addrs();
+        caller_addrs.extend(self.get_info(rt).active_provers(*rt.epoch.borrow()));
+        rt.expect_validate_caller_addr(caller_addrs);
 
         let challenge_rand = TEST_RANDOMNESS_ARRAY_FROM_TWO;
 
@@ -1761,9 +1875,15 @@ impl ActorHarness {
         for p in &params.partitions {
             let maybe_partition = dln.load_partition(&rt.store, p.index);
             if let Ok(partition) = maybe_partition {
-                let expected_faults = &partition.faults - &partition.recoveries;
+                // with auto-recovery, all faults that aren't skipped are recovered
+                let recoveries = if params.auto_recover_faults {
+                    &partition.faults - &p.skipped
+                } else {
+                    partition.recoveries.clone()
+                };
+                let expected_faults = &partition.faults - &recoveries;
                 all_ignored |= &(&expected_faults | &p.skipped);
-                all_recovered |= &(&partition.recoveries - &p.skipped);
+                all_recovered |= &(&recoveries - &p.skipped);
             }
         }
         let optimistic = all_recovered.is_empty();
@@ -1934,6 +2054,16 @@ impl ActorHarness {
                 let expected_pledge_delta = dispute_result.expected_pledge_delta.unwrap();
                 expect_update_pledge(rt, &expected_pledge_delta);
             }
+
+            let burned = dispute_result.expected_penalty.unwrap_or_default();
+            let rewarded = dispute_result.expected_reward.unwrap_or_default();
+            expect_penalty_event(
+                rt,
+                PenaltyCategory::InvalidWindowPost,
+                &(&burned + &rewarded),
+                &burned,
+                &TokenAmount::zero(),
+            );
         }
 
         let params =
@@ -2004,6 +2134,13 @@ impl ActorHarness {
                 None,
                 ExitCode::OK,
             );
+            expect_penalty_event(
+                rt,
+                PenaltyCategory::BlockReward,
+                &penalty,
+                &penalty,
+                &TokenAmount::zero(),
+            );
         }
 
         let params = ApplyRewardParams { reward: amt, penalty: penalty };
@@ -2168,8 +2305,11 @@ impl ActorHarness {
         }
 
         // Calculate params from faulted sector infos
-        let recovery =
-            RecoveryDeclaration { deadline: dlidx, partition: pidx, sectors: recovery_sectors };
+        let recovery = RecoveryDeclaration {
+            deadline: dlidx,
+            partition: pidx,
+            sectors: recovery_sectors.into(),
+        };
         let params = DeclareFaultsRecoveredParams { recoveries: vec![recovery] };
         let ret = rt.call::<Actor>(
             Method::DeclareFaultsRecovered as u64,
@@ -2297,10 +2437,17 @@ impl ActorHarness {
             BURNT_FUNDS_ACTOR_ADDR,
             METHOD_SEND,
             None,
-            to_burn,
+            to_burn.clone(),
             None,
             ExitCode::OK,
         );
+        expect_penalty_event(
+            rt,
+            PenaltyCategory::ConsensusFault,
+            &penalty_total,
+            &to_burn,
+            &TokenAmount::zero(),
+        );
 
         let result = rt.call::<Actor>(
             Method::ReportConsensusFault as u64,
@@ -2353,6 +2500,16 @@ impl ActorHarness {
         rt: &MockRuntime,
         sectors: &BitField,
         expected_fee: TokenAmount,
+    ) -> (PowerPair, TokenAmount) {
+        self.terminate_sectors_with_handoff(rt, sectors, expected_fee, None)
+    }
+
+    pub fn terminate_sectors_with_handoff(
+        &self,
+        rt: &MockRuntime,
+        sectors: &BitField,
+        expected_fee: TokenAmount,
+        handoff_manifest: Option<Cid>,
     ) -> (PowerPair, TokenAmount) {
         rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, self.worker);
         rt.expect_validate_caller_addr(self.caller_addrs());
@@ -2432,13 +2589,45 @@ impl ActorHarness {
             });
         }
 
+        if let Some(manifest) = &handoff_manifest {
+            rt.expect_emitted_event(
+                EventBuilder::new()
+                    .typ("termination-handoff")
+                    .field_indexed("manifest", manifest)
+                    .field("sectors", sectors)
+                    .build()
+                    .unwrap(),
+            );
+        }
+        if expected_fee.is_positive() {
+            expect_penalty_event(
+                rt,
+                PenaltyCategory::EarlyTermination,
+                &expected_fee,
+                &expected_fee,
+                &TokenAmount::zero(),
+            );
+        }
         for termination in terminations.iter() {
             for sector in termination.sectors.iter() {
                 expect_event(rt, "sector-terminated", &sector);
             }
         }
+        let epoch = *rt.epoch.borrow();
+        for sector_info in &sector_infos {
+            if is_cc_fast_removal(&rt.policy, sector_info, epoch) {
+                rt.expect_emitted_event(
+                    EventBuilder::new()
+                        .typ("cc-sector-fast-removed")
+                        .field_indexed("sector", &sector_info.sector_number)
+                        .field("age", &(epoch - sector_info.activation))
+                        .build()
+                        .unwrap(),
+                );
+            }
+        }
 
-        let params = TerminateSectorsParams { terminations };
+        let params = TerminateSectorsParams { terminations, handoff_manifest };
 
         rt.call::<Actor>(
             Method::TerminateSectors as u64,
@@ -2450,6 +2639,72 @@ impl ActorHarness {
         (sector_power.neg(), pledge_delta)
     }
 
+    pub fn estimate_termination_fee(
+        &self,
+        rt: &MockRuntime,
+        sectors: &BitField,
+    ) -> Result<EstimateTerminationFeeReturn, ActorError> {
+        // set caller to not-builtin
+        rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(1234));
+        rt.expect_validate_caller_any();
+        self.expect_query_network_info(rt);
+
+        let params = EstimateTerminationFeeParams { sectors: sectors.clone() };
+        let ret = rt
+            .call::<Actor>(
+                Method::EstimateTerminationFeeExported as u64,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            )?
+            .unwrap()
+            .deserialize()
+            .unwrap();
+        rt.verify();
+        Ok(ret)
+    }
+
+    pub fn preview_skipped_faults(
+        &self,
+        rt: &MockRuntime,
+        deadline: u64,
+        partitions: Vec<PoStPartition>,
+    ) -> Result<PreviewSkippedFaultsReturn, ActorError> {
+        rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(1234));
+        rt.expect_validate_caller_any();
+        self.expect_query_network_info(rt);
+
+        let params = PreviewSkippedFaultsParams { deadline, partitions };
+        let ret = rt
+            .call::<Actor>(
+                Method::PreviewSkippedFaultsExported as u64,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            )?
+            .unwrap()
+            .deserialize()
+            .unwrap();
+        rt.verify();
+        Ok(ret)
+    }
+
+    pub fn list_sectors(
+        &self,
+        rt: &MockRuntime,
+        request: ListRequest<SectorNumber>,
+    ) -> Result<ListSectorsReturn, ActorError> {
+        rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(1234));
+        rt.expect_validate_caller_any();
+        let params = ListSectorsParams { request };
+        let ret = rt
+            .call::<Actor>(
+                Method::ListSectorsExported as u64,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            )?
+            .unwrap()
+            .deserialize()
+            .unwrap();
+        rt.verify();
+        Ok(ret)
+    }
+
     pub fn change_peer_id(&self, rt: &MockRuntime, new_id: Vec<u8>) {
         let params = ChangePeerIDParams { new_id: new_id.to_owned() };
 
@@ -2808,6 +3063,7 @@ impl ActorHarness {
                 let mut new_sector = sector.clone();
                 new_sector.expiration = extension.new_expiration;
                 new_sector.power_base_epoch = *rt.epoch.borrow();
+                set_duration_multiplier_on_extension(rt, &mut new_sector);
                 qa_delta += qa_power_for_sector(self.sector_size, &new_sector)
                     - qa_power_for_sector(self.sector_size, &sector);
             }
@@ -2828,6 +3084,7 @@ impl ActorHarness {
                 new_sector.power_base_epoch = *rt.epoch.borrow();
                 new_sector.verified_deal_weight = BigInt::from(new_verified_deal_space)
                     * (new_sector.expiration - new_sector.power_base_epoch);
+                set_duration_multiplier_on_extension(rt, &mut new_sector);
                 qa_delta += qa_power_for_sector(self.sector_size, &new_sector)
                     - qa_power_for_sector(self.sector_size, &sector);
             }
@@ -2863,6 +3120,31 @@ impl ActorHarness {
         Ok(())
     }
 
+    pub fn compact_sectors_across_deadlines(
+        &self,
+        rt: &MockRuntime,
+        sources: Vec<(u64, BitField)>,
+        dest_deadline: u64,
+    ) -> Result<(), ActorError> {
+        let params = CompactSectorsAcrossDeadlinesParams {
+            sources: sources
+                .into_iter()
+                .map(|(deadline, partitions)| CompactPartitionsParams { deadline, partitions })
+                .collect(),
+            dest_deadline,
+        };
+
+        rt.expect_validate_caller_addr(self.caller_addrs());
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, self.worker);
+
+        rt.call::<Actor>(
+            Method::CompactSectorsAcrossDeadlines as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        )?;
+        rt.verify();
+        Ok(())
+    }
+
     pub fn get_info(&self, rt: &MockRuntime) -> MinerInfo {
         let state: State = rt.get_state();
         state.get_info(rt.store()).unwrap()
@@ -2876,8 +3158,8 @@ impl ActorHarness {
         let expected = if *rt.caller.borrow() == self.owner {
             self.owner
         } else {
-            if let Some(pending_owner) = self.get_info(rt).pending_owner_address {
-                pending_owner
+            if let Some(pending) = self.get_info(rt).pending_owner_change() {
+                pending.new_owner
             } else {
                 self.owner
             }
@@ -2896,6 +3178,72 @@ impl ActorHarness {
         ret
     }
 
+    pub fn cancel_owner_change(&self, rt: &MockRuntime) -> Result<Option<IpldBlock>, ActorError> {
+        let info = self.get_info(rt);
+        let mut expected = vec![info.owner];
+        expected.extend(info.pending_owner_change().map(|c| c.new_owner));
+        rt.expect_validate_caller_addr(expected);
+        let ret = rt.call::<Actor>(Method::CancelOwnerChangeExported as u64, None);
+
+        if ret.is_ok() {
+            rt.verify();
+        } else {
+            rt.reset();
+        }
+        ret
+    }
+
+    pub fn authorize_prover(
+        &self,
+        rt: &MockRuntime,
+        prover: Address,
+        start: ChainEpoch,
+        expiration: ChainEpoch,
+    ) -> Result<Option<IpldBlock>, ActorError> {
+        rt.expect_validate_caller_addr(vec![self.owner]);
+        let params = AuthorizeProverParams { prover, start, expiration };
+        let ret = rt.call::<Actor>(
+            Method::AuthorizeProverExported as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        );
+        if ret.is_ok() {
+            rt.verify();
+        } else {
+            rt.reset();
+        }
+        ret
+    }
+
+    pub fn revoke_prover(
+        &self,
+        rt: &MockRuntime,
+        prover: Address,
+    ) -> Result<Option<IpldBlock>, ActorError> {
+        rt.expect_validate_caller_addr(vec![self.owner]);
+        let ret = rt.call::<Actor>(
+            Method::RevokeProverExported as u64,
+            IpldBlock::serialize_cbor(&RevokeProverParams { prover }).unwrap(),
+        );
+        if ret.is_ok() {
+            rt.verify();
+        } else {
+            rt.reset();
+        }
+        ret
+    }
+
+    pub fn get_prover_authorizations(&self, rt: &MockRuntime) -> Vec<ProverAuthorization> {
+        rt.expect_validate_caller_any();
+        let ret: GetProverAuthorizationsReturn = rt
+            .call::<Actor>(Method::GetProverAuthorizationsExported as u64, None)
+            .unwrap()
+            .unwrap()
+            .deserialize()
+            .unwrap();
+        rt.verify();
+        ret.provers
+    }
+
     pub fn get_available_balance(&self, rt: &MockRuntime) -> Result<TokenAmount, ActorError> {
         // set caller to non-builtin
         rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(1234));
@@ -2907,6 +3255,19 @@ impl ActorHarness {
         rt.verify();
         Ok(available_balance_ret.available_balance)
     }
+
+    pub fn get_withdrawable_balance(&self, rt: &MockRuntime) -> GetWithdrawableBalanceReturn {
+        rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(1234));
+        rt.expect_validate_caller_any();
+        let ret = rt
+            .call::<Actor>(Method::GetWithdrawableBalanceExported as u64, None)
+            .unwrap()
+            .unwrap()
+            .deserialize()
+            .unwrap();
+        rt.verify();
+        ret
+    }
 }
 
 pub fn expect_sector_event(
@@ -2934,11 +3295,36 @@ pub fn expect_event(rt: &MockRuntime, typ: &str, sector: &SectorNumber) {
     );
 }
 
+#[allow(dead_code)]
+pub fn expect_penalty_event(
+    rt: &MockRuntime,
+    category: PenaltyCategory,
+    amount: &TokenAmount,
+    burned: &TokenAmount,
+    withheld: &TokenAmount,
+) {
+    let miner = rt.receiver.id().unwrap();
+    rt.expect_emitted_event(
+        EventBuilder::new()
+            .typ(PENALTY_ASSESSED_EVENT)
+            .field("actor", &miner)
+            .field_indexed("subject", &miner)
+            .field_indexed("category", category.as_str())
+            .field("amount", amount)
+            .field("burned", burned)
+            .field("withheld", withheld)
+            .build()
+            .unwrap(),
+    );
+}
+
 #[allow(dead_code)]
 pub struct PoStConfig {
     pub chain_randomness: Option<[u8; RANDOMNESS_LENGTH]>,
     pub expected_power_delta: Option<PowerPair>,
     pub verification_exit: Option<ExitCode>,
+    /// Address submitting the PoSt, if not the worker.
+    pub caller: Option<Address>,
 }
 
 #[allow(dead_code)]
@@ -2948,6 +3334,7 @@ impl PoStConfig {
             chain_randomness: None,
             expected_power_delta: Some(pwr.clone()),
             verification_exit: None,
+            caller: None,
         }
     }
 
@@ -2956,11 +3343,17 @@ impl PoStConfig {
             chain_randomness: Some(rand),
             expected_power_delta: None,
             verification_exit: None,
+            caller: None,
         }
     }
 
     pub fn empty() -> PoStConfig {
-        PoStConfig { chain_randomness: None, expected_power_delta: None, verification_exit: None }
+        PoStConfig {
+            chain_randomness: None,
+            expected_power_delta: None,
+            verification_exit: None,
+            caller: None,
+        }
     }
 }
 
@@ -3074,9 +3467,12 @@ pub struct CronConfig {
     pub expected_enrollment: ChainEpoch,
     pub detected_faults_power_delta: Option<PowerPair>,
     pub expired_sectors_power_delta: Option<PowerPair>,
+    pub expired_claims_power_delta: Option<PowerPair>,
     pub expired_sectors_pledge_delta: TokenAmount,
     pub continued_faults_penalty: TokenAmount,
     // Expected amount burnt to pay continued fault penalties.
+    pub continued_faults_withheld: TokenAmount,
+    // Expected amount of continued fault penalties added to fee debt.
     pub expired_precommit_penalty: TokenAmount,
     // Expected amount burnt to pay for expired precommits
     pub repaid_fee_debt: TokenAmount,
@@ -3092,8 +3488,10 @@ impl CronConfig {
             expected_enrollment: 0,
             detected_faults_power_delta: None,
             expired_sectors_power_delta: None,
+            expired_claims_power_delta: None,
             expired_sectors_pledge_delta: TokenAmount::zero(),
             continued_faults_penalty: TokenAmount::zero(),
+            continued_faults_withheld: TokenAmount::zero(),
             expired_precommit_penalty: TokenAmount::zero(),
             repaid_fee_debt: TokenAmount::zero(),
             penalty_from_unlocked: TokenAmount::zero(),
@@ -3475,30 +3873,20 @@ fn make_fault_params_from_faulting_sectors(
     state: &State,
     fault_sector_infos: &[SectorOnChainInfo],
 ) -> DeclareFaultsParams {
-    let mut declaration_map: BTreeMap<(u64, u64), FaultDeclaration> = BTreeMap::new();
+    let mut declaration_map: BTreeMap<(u64, u64), BitField> = BTreeMap::new();
     for sector in fault_sector_infos {
         let (dlidx, pidx) = state.find_sector(&rt.store, sector.sector_number).unwrap();
-        match declaration_map.get_mut(&(dlidx, pidx)) {
-            Some(declaration) => {
-                declaration.sectors.set(sector.sector_number);
-            }
-            None => {
-                let mut bf = BitField::new();
-                bf.set(sector.sector_number);
-
-                let declaration =
-                    FaultDeclaration { deadline: dlidx, partition: pidx, sectors: bf };
-
-                declaration_map.insert((dlidx, pidx), declaration);
-            }
-        }
+        declaration_map.entry((dlidx, pidx)).or_default().set(sector.sector_number);
     }
 
-    // I want to just write:
-    // let declarations = declaration_map.values().collect();
-    // but the compiler doesn't let me; so I do it by hand like a savange
-    let keys: Vec<(u64, u64)> = declaration_map.keys().cloned().collect();
-    let declarations = keys.iter().map(|k| declaration_map.remove(k).unwrap()).collect();
+    let declarations = declaration_map
+        .into_iter()
+        .map(|((dlidx, pidx), bf)| FaultDeclaration {
+            deadline: dlidx,
+            partition: pidx,
+            sectors: bf.into(),
+        })
+        .collect();
 
     DeclareFaultsParams { faults: declarations }
 }
@@ -3656,7 +4044,19 @@ fn expect_update_pledge(rt: &MockRuntime, pledge_delta: &TokenAmount) {
     }
 }
 
-fn expect_update_power(rt: &MockRuntime, delta: PowerPair) {
+// Simple QA power sectors take the duration multiplier per the policy when extended.
+#[allow(dead_code)]
+fn set_duration_multiplier_on_extension(rt: &MockRuntime, sector: &mut SectorOnChainInfo) {
+    if sector.flags.contains(SectorOnChainInfoFlags::SIMPLE_QA_POWER) {
+        sector.flags.set(
+            SectorOnChainInfoFlags::DURATION_MULTIPLIER,
+            rt.policy.sector_duration_multiplier_enabled,
+        );
+    }
+}
+
+#[allow(dead_code)]
+pub fn expect_update_power(rt: &MockRuntime, delta: PowerPair) {
     if !(delta.is_zero()) {
         rt.expect_send_simple(
             STORAGE_POWER_ACTOR_ADDR,
@@ -1,5 +1,5 @@
 use fil_actor_miner::{
-    Actor, BeneficiaryTerm, Method, WithdrawBalanceParams, WithdrawBalanceReturn,
+    Actor, BeneficiaryTerm, Method, VestSpec, WithdrawBalanceParams, WithdrawBalanceReturn,
 };
 use fil_actors_runtime::test_utils::{
     expect_abort, expect_abort_contains_message, EVM_ACTOR_CODE_ID,
@@ -261,3 +261,98 @@ fn fail_withdraw_from_non_beneficiary() {
     h.withdraw_funds(&rt, first_beneficiary_id, &one, &one, &TokenAmount::zero()).unwrap();
     h.check_state(&rt);
 }
+
+#[test]
+fn withdrawable_balance_matches_withdrawal_after_fee_debt() {
+    let h = ActorHarness::new(PERIOD_OFFSET);
+    let rt = h.new_runtime();
+    rt.set_balance(BIG_BALANCE.clone());
+    h.construct_and_verify(&rt);
+
+    let mut st = h.get_state(&rt);
+    let fee_debt = &*BIG_BALANCE - &*ONE_PERCENT_BALANCE;
+    st.fee_debt = fee_debt.clone();
+    rt.replace_state(&st);
+
+    let preview = h.get_withdrawable_balance(&rt);
+    assert_eq!(*ONE_PERCENT_BALANCE, preview.withdrawable);
+    assert_eq!(*ONE_PERCENT_BALANCE, preview.available_balance);
+    assert_eq!(fee_debt, preview.fee_debt);
+    assert_eq!(None, preview.beneficiary_quota);
+    assert!(!preview.early_terminations_pending);
+
+    let requested = rt.balance.borrow().to_owned();
+    h.withdraw_funds(&rt, h.owner, &requested, &preview.withdrawable, &fee_debt).unwrap();
+    h.check_state(&rt);
+}
+
+#[test]
+fn withdrawable_balance_zero_if_fee_debt_unpayable() {
+    let h = ActorHarness::new(PERIOD_OFFSET);
+    let rt = h.new_runtime();
+    rt.set_balance(BIG_BALANCE.clone());
+    h.construct_and_verify(&rt);
+
+    let mut st = h.get_state(&rt);
+    st.fee_debt = &*BIG_BALANCE + TokenAmount::from_whole(1);
+    rt.replace_state(&st);
+
+    let preview = h.get_withdrawable_balance(&rt);
+    assert!(preview.withdrawable.is_zero());
+    assert_eq!(TokenAmount::from_whole(-1), preview.available_balance);
+    h.check_state(&rt);
+}
+
+#[test]
+fn withdrawable_balance_includes_vested_funds() {
+    let h = ActorHarness::new(PERIOD_OFFSET);
+    let rt = h.new_runtime();
+    rt.set_balance(BIG_BALANCE.clone());
+    h.construct_and_verify(&rt);
+
+    // Lock funds that vest in two steps.
+    let locked = TokenAmount::from_atto(1000);
+    let mut st = h.get_state(&rt);
+    st.add_locked_funds(
+        &rt.store,
+        *rt.epoch.borrow(),
+        &locked,
+        &VestSpec { initial_delay: 0, vest_period: 2, step_duration: 1, quantization: 1 },
+    )
+    .unwrap();
+    rt.replace_state(&st);
+
+    // Half has vested but is not yet unlocked in state.
+    rt.set_epoch(*rt.epoch.borrow() + 2);
+    let preview = h.get_withdrawable_balance(&rt);
+    assert_eq!(TokenAmount::from_atto(500), preview.newly_vested);
+    assert_eq!(TokenAmount::from_atto(500), preview.locked_funds);
+    assert_eq!(&*BIG_BALANCE - TokenAmount::from_atto(500), preview.withdrawable);
+    assert_eq!(
+        &preview.withdrawable - TokenAmount::from_atto(500),
+        h.get_available_balance(&rt).unwrap()
+    );
+    h.check_state(&rt);
+}
+
+#[test]
+fn withdrawable_balance_limited_to_beneficiary_quota() {
+    let mut h = ActorHarness::new(PERIOD_OFFSET);
+    let rt = h.new_runtime();
+    rt.set_balance(BIG_BALANCE.clone());
+    h.construct_and_verify(&rt);
+
+    let quota = &*ONE_PERCENT_BALANCE;
+    h.propose_approve_initial_beneficiary(
+        &rt,
+        Address::new_id(999),
+        BeneficiaryTerm::new(quota.clone(), TokenAmount::zero(), PERIOD_OFFSET + 100),
+    )
+    .unwrap();
+
+    let preview = h.get_withdrawable_balance(&rt);
+    assert_eq!(*quota, preview.withdrawable);
+    assert_eq!(Some(quota.clone()), preview.beneficiary_quota);
+    assert_eq!(*BIG_BALANCE, preview.available_balance);
+    h.check_state(&rt);
+}
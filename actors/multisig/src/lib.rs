@@ -8,6 +8,7 @@ use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_ipld_encoding::RawBytes;
 use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
 use fvm_shared::MethodNum;
@@ -46,8 +47,12 @@ pub enum Method {
     SwapSigner = 7,
     ChangeNumApprovalsThreshold = 8,
     LockBalance = 9,
+    ReconfigureSigners = 10,
+    SetExecutionDelays = 11,
     // Method numbers derived from FRC-0042 standards
     UniversalReceiverHook = frc42_dispatch::method_hash!("Receive"),
+    PruneExpiredExported = frc42_dispatch::method_hash!("PruneExpired"),
+    ListPendingTransactionsExported = frc42_dispatch::method_hash!("ListPendingTransactions"),
 }
 
 /// Multisig Actor
@@ -107,6 +112,7 @@ impl Actor {
             next_tx_id: Default::default(),
             start_epoch: Default::default(),
             unlock_duration: Default::default(),
+            execution_delays: Vec::new(),
         };
 
         if params.unlock_duration != 0 {
@@ -134,6 +140,18 @@ impl Actor {
             ));
         }
 
+        let curr_epoch = rt.curr_epoch();
+        if let Some(expiration) = params.expiration_epoch {
+            if expiration <= curr_epoch {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "proposal expiration {} must be after current epoch {}",
+                    expiration,
+                    curr_epoch
+                ));
+            }
+        }
+
         let (txn_id, txn) = rt.transaction(|st: &mut State, rt| {
             if !st.is_signer(&proposer) {
                 return Err(actor_error!(forbidden, "{} is not a signer", proposer));
@@ -154,6 +172,8 @@ impl Actor {
                 method: params.method,
                 params: params.params,
                 approved: Vec::new(),
+                expiration_epoch: params.expiration_epoch,
+                executable_epoch: None,
             };
 
             ptx.set(&t_id, txn.clone())?;
@@ -183,6 +203,9 @@ impl Actor {
             )?;
 
             let txn = get_transaction(rt, &ptx, params.id, params.proposal_hash)?;
+            if txn.is_expired(rt.curr_epoch()) {
+                return Err(actor_error!(forbidden, "transaction {} has expired", params.id));
+            }
 
             // Go implementation holds reference to state after transaction so state must be cloned
             // to match to handle possible exit code inconsistency
@@ -190,12 +213,14 @@ impl Actor {
         })?;
 
         let (applied, ret, code) = execute_transaction_if_approved(rt, &st, id, &txn)?;
-        if !applied {
+        let threshold_met = txn.approved.len() as u64 >= st.num_approvals_threshold;
+        if !applied && !threshold_met {
             // if the transaction hasn't already been approved, "process" the approval
             // and see if the transaction can be executed
             let (applied, ret, code) = Self::approve_transaction(rt, id, txn)?;
             Ok(ApproveReturn { applied, code, ret })
         } else {
+            // If approved but not applied, the transaction is awaiting its execution delay.
             Ok(ApproveReturn { applied, code, ret })
         }
     }
@@ -221,8 +246,13 @@ impl Actor {
                 actor_error!(not_found, "no such transaction {:?} to cancel", params.id)
             })?;
 
-            // Check to make sure transaction proposer is caller address
-            if tx.approved.first() != Some(&caller_addr) {
+            // Check to make sure transaction proposer is caller address, unless the transaction
+            // is awaiting its execution delay or has expired, when any signer may cancel it.
+            let curr_epoch = rt.curr_epoch();
+            if tx.approved.first() != Some(&caller_addr)
+                && !tx.is_delayed(curr_epoch)
+                && !tx.is_expired(curr_epoch)
+            {
                 return Err(actor_error!(forbidden; "Cannot cancel another signers transaction"));
             }
 
@@ -390,6 +420,157 @@ impl Actor {
         Ok(())
     }
 
+    /// Removes all expired pending transactions. Callable by anyone.
+    pub fn prune_expired(rt: &impl Runtime) -> Result<PruneExpiredReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let pruned =
+            rt.transaction(|st: &mut State, rt| st.prune_expired(rt.store(), rt.curr_epoch()))?;
+        Ok(PruneExpiredReturn { pruned })
+    }
+
+    /// Lists a page of the pending transactions. Callable by anyone.
+    pub fn list_pending_transactions(
+        rt: &impl Runtime,
+        params: ListPendingTransactionsParams,
+    ) -> Result<ListPendingTransactionsReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        params.request.validate::<Transaction>()?;
+        let st: State = rt.state()?;
+        let (entries, next_cursor) =
+            st.list_pending_txns(rt.store(), &params.request, |addr| rt.resolve_address(addr))?;
+        Ok(ListPendingTransactionsReturn { entries, next_cursor })
+    }
+
+    /// Multisig actor function to add and remove signers and change the approval threshold
+    /// in a single step, so that the wallet never passes through an intermediate configuration.
+    pub fn reconfigure_signers(
+        rt: &impl Runtime,
+        params: ReconfigureSignersParams,
+    ) -> Result<(), ActorError> {
+        let receiver = rt.message().receiver();
+        rt.validate_immediate_caller_is(std::iter::once(&receiver))?;
+
+        let mut to_remove = BTreeSet::new();
+        for signer in &params.remove {
+            let resolved = resolve_to_actor_id(rt, signer, false)?;
+            if !to_remove.insert(resolved) {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "duplicate signer to remove {}",
+                    signer
+                ));
+            }
+        }
+        let mut to_add = Vec::with_capacity(params.add.len());
+        for signer in &params.add {
+            let resolved = resolve_to_actor_id(rt, signer, true)?;
+            if to_remove.contains(&resolved) {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "cannot both add and remove signer {}",
+                    signer
+                ));
+            }
+            if to_add.contains(&resolved) {
+                return Err(actor_error!(illegal_argument, "duplicate signer to add {}", signer));
+            }
+            to_add.push(resolved);
+        }
+
+        rt.transaction(|st: &mut State, rt| {
+            for removed in &to_remove {
+                if !st.is_signer(&Address::new_id(*removed)) {
+                    return Err(actor_error!(forbidden, "{} is not a signer", removed));
+                }
+            }
+            for added in &to_add {
+                if st.is_signer(&Address::new_id(*added)) {
+                    return Err(actor_error!(forbidden, "{} is already a signer", added));
+                }
+            }
+
+            let signer_count = st.signers.len() - to_remove.len() + to_add.len();
+            if signer_count == 0 {
+                return Err(actor_error!(forbidden; "Cannot remove all signers"));
+            }
+            if signer_count > SIGNERS_MAX {
+                return Err(actor_error!(
+                    forbidden,
+                    "cannot have more than {} signers",
+                    SIGNERS_MAX
+                ));
+            }
+            if params.new_threshold == 0 || params.new_threshold > signer_count as u64 {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "threshold {} not supported with {} signers",
+                    params.new_threshold,
+                    signer_count
+                ));
+            }
+
+            // Remove signers and their approvals (retain preserves order of elements)
+            for removed in &to_remove {
+                st.purge_approvals(rt.store(), &Address::new_id(*removed))
+                    .context("failed to purge approvals of removed signer")?;
+            }
+            st.signers.retain(|s| !to_remove.iter().any(|id| *s == Address::new_id(*id)));
+
+            st.signers.extend(to_add.iter().map(|id| Address::new_id(*id)));
+            st.num_approvals_threshold = params.new_threshold;
+            Ok(())
+        })
+    }
+
+    /// Multisig actor function to set the delays between approval and execution of transactions
+    /// by value. The delays of transactions already awaiting execution are unchanged.
+    pub fn set_execution_delays(
+        rt: &impl Runtime,
+        params: SetExecutionDelaysParams,
+    ) -> Result<(), ActorError> {
+        let receiver = rt.message().receiver();
+        rt.validate_immediate_caller_is(std::iter::once(&receiver))?;
+
+        if params.delays.len() > EXECUTION_DELAYS_MAX {
+            return Err(actor_error!(
+                illegal_argument,
+                "cannot set more than {} execution delays",
+                EXECUTION_DELAYS_MAX
+            ));
+        }
+        for tier in &params.delays {
+            if tier.value_above.is_negative() {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "execution delay value {} must be non-negative",
+                    tier.value_above
+                ));
+            }
+            if tier.delay <= 0 {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "execution delay {} must be positive",
+                    tier.delay
+                ));
+            }
+        }
+        for pair in params.delays.windows(2) {
+            if pair[1].value_above <= pair[0].value_above || pair[1].delay <= pair[0].delay {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "execution delays must increase with value, {:?} follows {:?}",
+                    pair[1],
+                    pair[0]
+                ));
+            }
+        }
+
+        rt.transaction(|st: &mut State, _| {
+            st.execution_delays = params.delays;
+            Ok(())
+        })
+    }
+
     fn approve_transaction(
         rt: &impl Runtime,
         tx_id: TxnID,
@@ -460,7 +641,28 @@ fn execute_transaction_if_approved(
     let mut code = ExitCode::OK;
     let mut applied = false;
     let threshold_met = txn.approved.len() as u64 >= st.num_approvals_threshold;
-    if threshold_met {
+    if threshold_met && txn.executable_epoch.is_none() {
+        let delay = execution_delay(rt, st, txn);
+        if delay > 0 {
+            // Schedule execution after the delay, rather than executing now.
+            let executable_epoch = rt.curr_epoch() + delay;
+            rt.transaction(|st: &mut State, rt| {
+                let mut ptx = PendingTxnMap::load(
+                    rt.store(),
+                    &st.pending_txs,
+                    PENDING_TXN_CONFIG,
+                    "pending txns",
+                )?;
+                let mut txn = txn.clone();
+                txn.executable_epoch = Some(executable_epoch);
+                ptx.set(&txn_id, txn)?;
+                st.pending_txs = ptx.flush()?;
+                Ok(())
+            })?;
+            return Ok((applied, out, code));
+        }
+    }
+    if threshold_met && !txn.is_delayed(rt.curr_epoch()) {
         st.check_available(rt.current_balance(), &txn.value, rt.curr_epoch())?;
 
         match extract_send_result(rt.send_simple(
@@ -499,6 +701,20 @@ fn execute_transaction_if_approved(
     Ok((applied, out, code))
 }
 
+/// Returns the delay between approval and execution of a transaction. Transactions to the multisig
+/// itself, which may change its signers or delays, are subject to the longest delay.
+fn execution_delay(rt: &impl Runtime, st: &State, txn: &Transaction) -> ChainEpoch {
+    if st.execution_delays.is_empty() {
+        return 0;
+    }
+    let receiver = rt.message().receiver().id().unwrap();
+    if rt.resolve_address(&txn.to) == Some(receiver) {
+        st.max_execution_delay()
+    } else {
+        st.execution_delay(&txn.value)
+    }
+}
+
 fn get_transaction<'m, BS, RT>(
     rt: &RT,
     ptx: &'m PendingTxnMap<BS>,
@@ -532,15 +748,32 @@ where
 
 /// Computes a digest of a proposed transaction. This digest is used to confirm identity
 /// of the transaction associated with an ID, which might change under chain re-orgs.
+/// The digest of a transaction that expires also commits to its expiration, so that approvers
+/// know which expiration they're approving.
 pub fn compute_proposal_hash(txn: &Transaction, sys: &dyn Primitives) -> anyhow::Result<[u8; 32]> {
-    let proposal_hash = ProposalHashData {
-        requester: txn.approved.first(),
-        to: &txn.to,
-        value: &txn.value,
-        method: &txn.method,
-        params: &txn.params,
+    let data = match &txn.expiration_epoch {
+        None => serialize_vec(
+            &ProposalHashData {
+                requester: txn.approved.first(),
+                to: &txn.to,
+                value: &txn.value,
+                method: &txn.method,
+                params: &txn.params,
+            },
+            "proposal hash",
+        )?,
+        Some(expiration_epoch) => serialize_vec(
+            &ExpiringProposalHashData {
+                requester: txn.approved.first(),
+                to: &txn.to,
+                value: &txn.value,
+                method: &txn.method,
+                params: &txn.params,
+                expiration_epoch,
+            },
+            "proposal hash",
+        )?,
     };
-    let data = serialize_vec(&proposal_hash, "proposal hash")?;
     Ok(sys.hash_blake2b(&data))
 }
 
@@ -551,6 +784,11 @@ impl ActorCode for Actor {
         "Multisig"
     }
 
+    fil_actors_runtime::actor_info! {
+        interface_revisions: [1],
+        features: [],
+    }
+
     actor_dispatch! {
       Constructor => constructor,
       Propose => propose,
@@ -561,7 +799,11 @@ impl ActorCode for Actor {
       SwapSigner => swap_signer,
       ChangeNumApprovalsThreshold => change_num_approvals_threshold,
       LockBalance => lock_balance,
+      ReconfigureSigners => reconfigure_signers,
+      SetExecutionDelays => set_execution_delays,
       UniversalReceiverHook => universal_receiver_hook,
+      PruneExpiredExported => prune_expired,
+      ListPendingTransactionsExported => list_pending_transactions,
       _ => fallback,
     }
 }
@@ -9,12 +9,14 @@ use fvm_shared::bigint::BigInt;
 use fvm_shared::bigint::Integer;
 use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
+use fvm_shared::ActorID;
 use indexmap::IndexMap;
 use num_traits::Zero;
 
+use fil_actors_runtime::query::ListRequest;
 use fil_actors_runtime::{actor_error, ActorError, Config, Map2, DEFAULT_HAMT_CONFIG};
 
-use super::types::Transaction;
+use super::types::{ExecutionDelay, Transaction, TransactionRecord};
 use super::TxnID;
 
 pub type PendingTxnMap<BS> = Map2<BS, TxnID, Transaction>;
@@ -33,6 +35,9 @@ pub struct State {
     pub unlock_duration: ChainEpoch,
 
     pub pending_txs: Cid,
+
+    // Execution delays for approved transactions, in increasing order of value and delay
+    pub execution_delays: Vec<ExecutionDelay>,
 }
 
 impl State {
@@ -41,6 +46,21 @@ impl State {
         self.signers.contains(address)
     }
 
+    /// Returns the delay between approval and execution of a transaction sending `value`.
+    pub fn execution_delay(&self, value: &TokenAmount) -> ChainEpoch {
+        // Tiers are ordered by value, so the last applicable tier has the longest delay.
+        self.execution_delays
+            .iter()
+            .rev()
+            .find(|tier| value > &tier.value_above)
+            .map_or(0, |tier| tier.delay)
+    }
+
+    /// Returns the longest configured execution delay.
+    pub fn max_execution_delay(&self) -> ChainEpoch {
+        self.execution_delays.last().map_or(0, |tier| tier.delay)
+    }
+
     /// Set locked amount in multisig state.
     pub fn set_locked(
         &mut self,
@@ -107,6 +127,67 @@ impl State {
         Ok(())
     }
 
+    /// Removes all pending transactions that have expired as of an epoch.
+    /// Returns the IDs of the removed transactions.
+    pub fn prune_expired<BS: Blockstore>(
+        &mut self,
+        store: &BS,
+        curr_epoch: ChainEpoch,
+    ) -> Result<Vec<TxnID>, ActorError> {
+        let mut txns =
+            PendingTxnMap::load(store, &self.pending_txs, PENDING_TXN_CONFIG, "pending txns")?;
+
+        let mut expired = Vec::new();
+        txns.for_each(|tx_id, txn: &Transaction| {
+            if txn.is_expired(curr_epoch) {
+                expired.push(tx_id);
+            }
+            Ok(())
+        })?;
+
+        if !expired.is_empty() {
+            for tx_id in &expired {
+                txns.delete(tx_id)?;
+            }
+            self.pending_txs = txns.flush()?;
+        }
+        Ok(expired)
+    }
+
+    /// Lists the pending transactions matching a request's filter among the next page of
+    /// pending transactions, in storage order.
+    /// A cursor whose transaction has since been removed resumes from the transaction that
+    /// followed it. Recipients are resolved to ID addresses with `resolve` before matching
+    /// a filter on them.
+    /// Returns the matching transactions, and the ID at which to resume if any remain.
+    pub fn list_pending_txns<BS: Blockstore>(
+        &self,
+        store: &BS,
+        request: &ListRequest<TxnID>,
+        resolve: impl Fn(&Address) -> Option<ActorID>,
+    ) -> Result<(Vec<TransactionRecord>, Option<TxnID>), ActorError> {
+        let mut txns =
+            PendingTxnMap::load(store, &self.pending_txs, PENDING_TXN_CONFIG, "pending txns")?;
+        let filters_to = request.filter.iter().any(|f| f.field == "to");
+
+        let mut records = Vec::new();
+        let (_, next) = txns.for_each_ranged(
+            request.cursor.as_ref(),
+            Some(request.limit as usize),
+            |id, txn: &Transaction| {
+                let matches = match resolve_to(txn, filters_to, &resolve) {
+                    Some(resolved) => request.matches(&resolved),
+                    None => request.matches(txn),
+                };
+                if matches {
+                    records.push(TransactionRecord { id, transaction: txn.clone() });
+                }
+                Ok(())
+            },
+        )?;
+        Ok((records, next))
+    }
+
     pub(crate) fn check_available(
         &self,
         balance: TokenAmount,
@@ -149,3 +230,17 @@ impl State {
         Ok(())
     }
 }
+
+// Returns a copy of a transaction with its recipient resolved to an ID address, if the
+// recipient is to be filtered on and isn't already an ID address.
+fn resolve_to(
+    txn: &Transaction,
+    filters_to: bool,
+    resolve: &impl Fn(&Address) -> Option<ActorID>,
+) -> Option<Transaction> {
+    if !filters_to || txn.to.id().is_ok() {
+        return None;
+    }
+    let id = resolve(&txn.to)?;
+    Some(Transaction { to: Address::new_id(id), ..txn.clone() })
+}
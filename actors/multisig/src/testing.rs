@@ -5,7 +5,7 @@ use fvm_shared::address::Address;
 
 use fil_actors_runtime::MessageAccumulator;
 
-use crate::{PendingTxnMap, State, TxnID, PENDING_TXN_CONFIG, SIGNERS_MAX};
+use crate::{PendingTxnMap, State, TxnID, EXECUTION_DELAYS_MAX, PENDING_TXN_CONFIG, SIGNERS_MAX};
 
 pub struct StateSummary {
     pub pending_tx_count: u64,
@@ -46,6 +46,18 @@ pub fn check_state_invariants<BS: Blockstore>(
         );
     }
 
+    // assert invariants involving execution delays
+    acc.require(
+        state.execution_delays.len() <= EXECUTION_DELAYS_MAX,
+        format!("multisig has too many execution delays: {}", state.execution_delays.len()),
+    );
+    for pair in state.execution_delays.windows(2) {
+        acc.require(
+            pair[0].value_above < pair[1].value_above && pair[0].delay < pair[1].delay,
+            format!("execution delays out of order: {:?} then {:?}", pair[0], pair[1]),
+        );
+    }
+
     // create lookup to test transaction approvals are multisig signers
     let signers = HashSet::<&Address>::from_iter(state.signers.iter());
 
@@ -75,7 +87,8 @@ pub fn check_state_invariants<BS: Blockstore>(
                     );
                     seen_approvals.insert(approval);
                 });
-                acc.require((seen_approvals.len() as u64) < state.num_approvals_threshold,
+                // Approved transactions remain pending only while awaiting an execution delay.
+                acc.require((seen_approvals.len() as u64) < state.num_approvals_threshold || transaction.executable_epoch.is_some(),
                     format!("number of approvals ({}) meets the approvals threshold ({}), transaction should not be pending",
                     seen_approvals.len(), state.num_approvals_threshold));
 
@@ -12,12 +12,16 @@ use fvm_shared::error::ExitCode;
 use fvm_shared::MethodNum;
 use serde::{Deserialize, Serialize};
 
+use fil_actors_runtime::query::{Indexed, ListPage, ListRequest};
 use fil_actors_runtime::MapKey;
 
 /// SignersMax is the maximum number of signers allowed in a multisig. If more
 /// are required, please use a combining tree of multisigs.
 pub const SIGNERS_MAX: usize = 256;
 
+/// The maximum number of execution delay tiers a multisig may configure.
+pub const EXECUTION_DELAYS_MAX: usize = 16;
+
 /// Transaction ID type
 #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, Hash, Eq, PartialEq, PartialOrd)]
 #[serde(transparent)]
@@ -48,6 +52,49 @@ pub struct Transaction {
     pub params: RawBytes,
 
     pub approved: Vec<Address>,
+    /// Epoch from which the transaction can no longer be approved, and may be pruned.
+    pub expiration_epoch: Option<ChainEpoch>,
+    /// Epoch from which the transaction may be executed, set when it meets the approval threshold
+    /// if it's subject to an execution delay. A transaction that expires first is never executed.
+    pub executable_epoch: Option<ChainEpoch>,
+}
+
+impl Transaction {
+    /// Whether the transaction has expired as of an epoch.
+    pub fn is_expired(&self, epoch: ChainEpoch) -> bool {
+        matches!(self.expiration_epoch, Some(expiration) if epoch >= expiration)
+    }
+
+    /// Whether the transaction has been approved but is still awaiting its execution delay
+    /// as of an epoch.
+    pub fn is_delayed(&self, epoch: ChainEpoch) -> bool {
+        matches!(self.executable_epoch, Some(executable) if epoch < executable)
+    }
+}
+
+impl Indexed for Transaction {
+    const INDEXED_FIELDS: &'static [&'static str] =
+        &["to", "method", "approvals", "expiration-epoch", "executable-epoch"];
+
+    fn field(&self, name: &str) -> Option<i128> {
+        match name {
+            "to" => self.to.id().ok().map(i128::from),
+            "method" => Some(self.method.into()),
+            "approvals" => Some(self.approved.len() as i128),
+            "expiration-epoch" => self.expiration_epoch.map(i128::from),
+            "executable-epoch" => self.executable_epoch.map(i128::from),
+            _ => None,
+        }
+    }
+}
+
+/// A delay between a transaction meeting the approval threshold and its execution, applying to
+/// transactions sending more than some value. Any signer may cancel a transaction during its delay.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize_tuple, Deserialize_tuple)]
+pub struct ExecutionDelay {
+    /// Transactions sending more than this value are subject to the delay.
+    pub value_above: TokenAmount,
+    pub delay: ChainEpoch,
 }
 
 /// Data for a BLAKE2B-256 to be attached to methods referencing proposals via TXIDs.
@@ -65,6 +112,18 @@ pub struct ProposalHashData<'a> {
     pub params: &'a RawBytes,
 }
 
+/// Proposal hash data of a transaction that expires, which also commits to its expiration.
+/// The hash of a transaction without an expiration is computed from `ProposalHashData`.
+#[derive(Serialize_tuple, Debug)]
+pub struct ExpiringProposalHashData<'a> {
+    pub requester: Option<&'a Address>,
+    pub to: &'a Address,
+    pub value: &'a TokenAmount,
+    pub method: &'a MethodNum,
+    pub params: &'a RawBytes,
+    pub expiration_epoch: &'a ChainEpoch,
+}
+
 /// Constructor parameters for multisig actor.
 #[derive(Serialize_tuple, Deserialize_tuple)]
 pub struct ConstructorParams {
@@ -82,6 +141,10 @@ pub struct ProposeParams {
     pub value: TokenAmount,
     pub method: MethodNum,
     pub params: RawBytes,
+    /// Optional epoch from which the proposal can no longer be approved.
+    /// Must be in the future. May be omitted by callers predating expiring proposals.
+    #[serde(default)]
+    pub expiration_epoch: Option<ChainEpoch>,
 }
 
 /// Propose method call return.
@@ -122,6 +185,30 @@ pub struct ApproveReturn {
     pub ret: RawBytes,
 }
 
+/// Prune expired method call return.
+#[derive(Serialize_tuple, Deserialize_tuple)]
+pub struct PruneExpiredReturn {
+    /// IDs of the expired transactions that were removed.
+    pub pruned: Vec<TxnID>,
+}
+
+/// List pending transactions params.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct ListPendingTransactionsParams {
+    pub request: ListRequest<TxnID>,
+}
+
+/// A pending transaction with its ID.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize_tuple, Deserialize_tuple)]
+pub struct TransactionRecord {
+    pub id: TxnID,
+    pub transaction: Transaction,
+}
+
+/// List pending transactions return.
+pub type ListPendingTransactionsReturn = ListPage<TransactionRecord, TxnID>;
+
 /// Add signer params.
 #[derive(Serialize_tuple, Deserialize_tuple)]
 pub struct AddSignerParams {
@@ -149,6 +236,23 @@ pub struct ChangeNumApprovalsThresholdParams {
     pub new_threshold: u64,
 }
 
+/// Reconfigure signers method call params.
+/// Removals are applied before additions, and the new threshold must be met by the
+/// resulting signer set.
+#[derive(Serialize_tuple, Deserialize_tuple)]
+pub struct ReconfigureSignersParams {
+    pub add: Vec<Address>,
+    pub remove: Vec<Address>,
+    pub new_threshold: u64,
+}
+
+/// Set execution delays call params.
+/// Tiers must be in increasing order of both value and delay. An empty list removes all delays.
+#[derive(Serialize_tuple, Deserialize_tuple)]
+pub struct SetExecutionDelaysParams {
+    pub delays: Vec<ExecutionDelay>,
+}
+
 /// Lock balance call params.
 #[derive(Serialize_tuple, Deserialize_tuple)]
 pub struct LockBalanceParams {
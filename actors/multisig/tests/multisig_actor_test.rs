@@ -1,9 +1,11 @@
 use fil_actor_multisig::testing::check_state_invariants;
 use fil_actor_multisig::{
-    compute_proposal_hash, Actor as MultisigActor, ConstructorParams, Method, ProposeReturn, State,
-    Transaction, TxnID, TxnIDParams, SIGNERS_MAX,
+    compute_proposal_hash, Actor as MultisigActor, ApproveReturn, ConstructorParams,
+    ExecutionDelay, ListPendingTransactionsReturn, Method, PendingTxnMap, ProposeReturn, State,
+    Transaction, TxnID, TxnIDParams, PENDING_TXN_CONFIG, SIGNERS_MAX,
 };
 use fil_actors_runtime::cbor::serialize;
+use fil_actors_runtime::query::{FieldFilter, FilterOp, ListRequest};
 use fil_actors_runtime::runtime::Runtime;
 use fil_actors_runtime::test_utils::*;
 use fil_actors_runtime::FIRST_EXPORTED_METHOD_NUMBER;
@@ -574,6 +576,8 @@ fn test_simple_propose() {
         method: METHOD_SEND,
         params: RawBytes::default(),
         approved: vec![anne],
+        expiration_epoch: None,
+        executable_epoch: None,
     };
     let expect_txns = vec![(TxnID(0), txn0)];
     h.assert_transactions(&rt, expect_txns);
@@ -1144,6 +1148,8 @@ fn test_swap_signer_removes_approvals() {
                     method: METHOD_SEND,
                     params: RawBytes::default(),
                     approved: vec![bob],
+                    expiration_epoch: None,
+                    executable_epoch: None,
                 },
             ),
             (
@@ -1154,6 +1160,8 @@ fn test_swap_signer_removes_approvals() {
                     method: METHOD_SEND,
                     params: RawBytes::default(),
                     approved: vec![bob],
+                    expiration_epoch: None,
+                    executable_epoch: None,
                 },
             ),
         ],
@@ -1230,6 +1238,8 @@ fn test_remove_signer_removes_approvals() {
                     method: METHOD_SEND,
                     params: RawBytes::default(),
                     approved: vec![bob],
+                    expiration_epoch: None,
+                    executable_epoch: None,
                 },
             ),
             (
@@ -1240,6 +1250,8 @@ fn test_remove_signer_removes_approvals() {
                     method: METHOD_SEND,
                     params: RawBytes::default(),
                     approved: vec![bob],
+                    expiration_epoch: None,
+                    executable_epoch: None,
                 },
             ),
         ],
@@ -1273,6 +1285,203 @@ fn remove_signer_deletes_solo_proposals() {
 }
 
 // Approve
+// ReconfigureSigners
+#[test]
+fn test_reconfigure_signers() {
+    let msig = Address::new_id(100);
+    let anne = Address::new_id(101);
+    let bob = Address::new_id(102);
+    let bob_non_id = Address::new_bls(&[1u8; BLS_PUB_LEN]).unwrap();
+    let chuck = Address::new_id(103);
+    let darlene = Address::new_id(104);
+
+    struct TestCase<'a> {
+        #[allow(dead_code)]
+        desc: &'a str,
+
+        initial_signers: Vec<Address>,
+        initial_approvals: u64,
+        add: Vec<Address>,
+        remove: Vec<Address>,
+        new_threshold: u64,
+        expect_signers: Vec<Address>,
+        code: ExitCode,
+    }
+
+    let test_cases = vec![
+        TestCase {
+            desc: "replace two signers and raise threshold",
+            initial_signers: vec![anne, bob, chuck],
+            initial_approvals: 2,
+            add: vec![darlene],
+            remove: vec![bob, chuck],
+            new_threshold: 2,
+            expect_signers: vec![anne, darlene],
+            code: ExitCode::OK,
+        },
+        TestCase {
+            desc: "threshold may exceed the signer count before reconfiguration",
+            initial_signers: vec![anne],
+            initial_approvals: 1,
+            add: vec![bob, chuck],
+            remove: vec![],
+            new_threshold: 3,
+            expect_signers: vec![anne, bob, chuck],
+            code: ExitCode::OK,
+        },
+        TestCase {
+            desc: "remove signer by non-ID address",
+            initial_signers: vec![anne, bob],
+            initial_approvals: 2,
+            add: vec![],
+            remove: vec![bob_non_id],
+            new_threshold: 1,
+            expect_signers: vec![anne],
+            code: ExitCode::OK,
+        },
+        TestCase {
+            desc: "fail when threshold exceeds resulting signers",
+            initial_signers: vec![anne, bob, chuck],
+            initial_approvals: 2,
+            add: vec![],
+            remove: vec![chuck],
+            new_threshold: 3,
+            expect_signers: vec![],
+            code: ExitCode::USR_ILLEGAL_ARGUMENT,
+        },
+        TestCase {
+            desc: "fail with zero threshold",
+            initial_signers: vec![anne, bob],
+            initial_approvals: 1,
+            add: vec![chuck],
+            remove: vec![],
+            new_threshold: 0,
+            expect_signers: vec![],
+            code: ExitCode::USR_ILLEGAL_ARGUMENT,
+        },
+        TestCase {
+            desc: "fail to remove all signers",
+            initial_signers: vec![anne, bob],
+            initial_approvals: 1,
+            add: vec![],
+            remove: vec![anne, bob],
+            new_threshold: 1,
+            expect_signers: vec![],
+            code: ExitCode::USR_FORBIDDEN,
+        },
+        TestCase {
+            desc: "fail to remove a non-signer",
+            initial_signers: vec![anne, bob],
+            initial_approvals: 1,
+            add: vec![],
+            remove: vec![darlene],
+            new_threshold: 1,
+            expect_signers: vec![],
+            code: ExitCode::USR_FORBIDDEN,
+        },
+        TestCase {
+            desc: "fail to add an existing signer",
+            initial_signers: vec![anne, bob],
+            initial_approvals: 1,
+            add: vec![bob_non_id],
+            remove: vec![],
+            new_threshold: 1,
+            expect_signers: vec![],
+            code: ExitCode::USR_FORBIDDEN,
+        },
+        TestCase {
+            desc: "fail to both add and remove a signer",
+            initial_signers: vec![anne, bob],
+            initial_approvals: 1,
+            add: vec![bob],
+            remove: vec![bob_non_id],
+            new_threshold: 1,
+            expect_signers: vec![],
+            code: ExitCode::USR_ILLEGAL_ARGUMENT,
+        },
+        TestCase {
+            desc: "fail to add a signer twice",
+            initial_signers: vec![anne],
+            initial_approvals: 1,
+            add: vec![chuck, chuck],
+            remove: vec![],
+            new_threshold: 1,
+            expect_signers: vec![],
+            code: ExitCode::USR_ILLEGAL_ARGUMENT,
+        },
+    ];
+
+    for tc in test_cases {
+        let rt = construct_runtime(msig);
+        rt.id_addresses.borrow_mut().insert(bob_non_id, bob);
+        let h = util::ActorHarness::new();
+        h.construct_and_verify(&rt, tc.initial_approvals, 0, 0, tc.initial_signers);
+
+        rt.set_caller(*MULTISIG_ACTOR_CODE_ID, msig);
+        let ret = h.reconfigure_signers(&rt, tc.add, tc.remove, tc.new_threshold);
+        match tc.code {
+            ExitCode::OK => {
+                assert!(ret.unwrap().is_none());
+                let st: State = rt.get_state();
+                assert_eq!(tc.expect_signers, st.signers);
+                assert_eq!(tc.new_threshold, st.num_approvals_threshold);
+            }
+            _ => assert_eq!(
+                tc.code,
+                ret.expect_err("reconfigure signers return expected to be actor error").exit_code()
+            ),
+        };
+        check_state(&rt);
+    }
+}
+
+#[test]
+fn test_reconfigure_signers_removes_approvals() {
+    let msig = Address::new_id(100);
+    let anne = Address::new_id(101);
+    let bob = Address::new_id(102);
+    let chuck = Address::new_id(103);
+    let darlene = Address::new_id(104);
+
+    let rt = construct_runtime(msig);
+    let h = util::ActorHarness::new();
+    h.construct_and_verify(&rt, 3, 0, 0, vec![anne, bob, chuck]);
+
+    // anne proposes, bob approves; chuck proposes another
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+    let proposal_hash =
+        h.propose_ok(&rt, darlene, TokenAmount::zero(), METHOD_SEND, RawBytes::default());
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, bob);
+    h.approve(&rt, TxnID(0), proposal_hash).unwrap();
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, chuck);
+    h.propose_ok(&rt, darlene, TokenAmount::zero(), METHOD_SEND, RawBytes::default());
+
+    // replace anne and chuck with darlene, keeping the threshold at 2
+    rt.set_caller(*MULTISIG_ACTOR_CODE_ID, msig);
+    h.reconfigure_signers(&rt, vec![darlene], vec![anne, chuck], 2).unwrap();
+
+    let st: State = rt.get_state();
+    assert_eq!(vec![bob, darlene], st.signers);
+    assert_eq!(2, st.num_approvals_threshold);
+    // anne's approval is gone from the first transaction, chuck's transaction is gone entirely
+    h.assert_transactions(
+        &rt,
+        vec![(
+            TxnID(0),
+            Transaction {
+                to: darlene,
+                value: TokenAmount::zero(),
+                method: METHOD_SEND,
+                params: RawBytes::default(),
+                approved: vec![bob],
+                expiration_epoch: None,
+                executable_epoch: None,
+            },
+        )],
+    );
+    check_state(&rt);
+}
+
 mod approval_tests {
     use super::*;
     use fil_actor_multisig::ApproveReturn;
@@ -1305,6 +1514,8 @@ mod approval_tests {
             method: fake_method,
             params: fake_params.clone(),
             approved: vec![anne],
+            expiration_epoch: None,
+            executable_epoch: None,
         };
         h.assert_transactions(&rt, vec![(TxnID(0), expect_txn)]);
 
@@ -1391,6 +1602,8 @@ mod approval_tests {
                     method: fake_method,
                     params: fake_params.clone(),
                     approved: vec![anne],
+                    expiration_epoch: None,
+                    executable_epoch: None,
                 },
             )],
         );
@@ -1441,6 +1654,8 @@ mod approval_tests {
                     method: fake_method,
                     params: fake_params,
                     approved: vec![anne],
+                    expiration_epoch: None,
+                    executable_epoch: None,
                 },
             )],
         );
@@ -1478,6 +1693,8 @@ mod approval_tests {
                     method: fake_method,
                     params: fake_params,
                     approved: vec![anne],
+                    expiration_epoch: None,
+                    executable_epoch: None,
                 },
             )],
         );
@@ -1512,6 +1729,8 @@ mod approval_tests {
                 method: fake_method,
                 params: fake_params,
                 approved: vec![bob], //mismatch
+                expiration_epoch: None,
+                executable_epoch: None,
             },
             &rt,
         )
@@ -1550,6 +1769,8 @@ mod approval_tests {
             method: fake_method,
             params: fake_params.clone(),
             approved: vec![anne],
+            expiration_epoch: None,
+            executable_epoch: None,
         };
         h.assert_transactions(&rt, vec![(TxnID(0), expect_txn)]);
 
@@ -1647,6 +1868,8 @@ mod approval_tests {
                     method: fake_method,
                     params: fake_params,
                     approved: vec![anne],
+                    expiration_epoch: None,
+                    executable_epoch: None,
                 },
             )],
         );
@@ -1713,6 +1936,8 @@ mod approval_tests {
                     method: fake_method,
                     params: fake_params,
                     approved: vec![anne],
+                    expiration_epoch: None,
+                    executable_epoch: None,
                 },
             )],
         );
@@ -1948,6 +2173,8 @@ mod cancel_tests {
                     method: fake_method,
                     params: RawBytes::default(),
                     approved: vec![anne],
+                    expiration_epoch: None,
+                    executable_epoch: None,
                 },
             )],
         );
@@ -1989,6 +2216,8 @@ mod cancel_tests {
                     method: fake_method,
                     params: RawBytes::default(),
                     approved: vec![anne],
+                    expiration_epoch: None,
+                    executable_epoch: None,
                 },
             )],
         );
@@ -2031,6 +2260,8 @@ mod cancel_tests {
                     method: fake_method,
                     params: RawBytes::default(),
                     approved: vec![anne],
+                    expiration_epoch: None,
+                    executable_epoch: None,
                 },
             )],
         );
@@ -2083,6 +2314,8 @@ mod cancel_tests {
             method: fake_method,
             params: RawBytes::default(),
             approved: vec![bob], // anne's approval is gone
+            expiration_epoch: None,
+            executable_epoch: None,
         };
         let new_proposal_hash = compute_proposal_hash(&new_tx, &rt).unwrap();
         h.assert_transactions(&rt, vec![(TxnID(0), new_tx)]);
@@ -2485,3 +2718,586 @@ fn test_fallback() {
 
     rt.verify();
 }
+
+mod expiration_tests {
+    use super::*;
+    use fil_actor_multisig::ProposalHashData;
+    use fil_actors_runtime::cbor::serialize_vec;
+    use fil_actors_runtime::runtime::Primitives;
+
+    fn setup() -> (MockRuntime, util::ActorHarness, Address, Address) {
+        let msig = Address::new_id(1000);
+        let rt = construct_runtime(msig);
+        let h = util::ActorHarness::new();
+        let anne = Address::new_id(101);
+        let bob = Address::new_id(102);
+        h.construct_and_verify(&rt, 2, 0, 0, vec![anne, bob]);
+        (rt, h, anne, bob)
+    }
+
+    fn expiring_txn(
+        to: Address,
+        approver: Address,
+        expiration_epoch: Option<ChainEpoch>,
+    ) -> Transaction {
+        Transaction {
+            to,
+            value: TokenAmount::zero(),
+            method: METHOD_SEND,
+            params: RawBytes::default(),
+            approved: vec![approver],
+            expiration_epoch,
+            executable_epoch: None,
+        }
+    }
+
+    #[test]
+    fn propose_with_past_expiration_fails() {
+        let (rt, h, anne, _) = setup();
+        let chuck = Address::new_id(103);
+        rt.set_epoch(10);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+        expect_abort_contains_message(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            "must be after current epoch",
+            h.propose_with_expiration(
+                &rt,
+                chuck,
+                TokenAmount::zero(),
+                METHOD_SEND,
+                RawBytes::default(),
+                Some(10),
+            ),
+        );
+        rt.reset();
+        h.assert_transactions(&rt, vec![]);
+        check_state(&rt);
+    }
+
+    #[test]
+    fn approve_expired_transaction_fails() {
+        let (rt, h, anne, bob) = setup();
+        let chuck = Address::new_id(103);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+        h.propose_with_expiration(
+            &rt,
+            chuck,
+            TokenAmount::zero(),
+            METHOD_SEND,
+            RawBytes::default(),
+            Some(10),
+        )
+        .unwrap();
+        let proposal_hash =
+            compute_proposal_hash(&expiring_txn(chuck, anne, Some(10)), &rt).unwrap();
+
+        rt.set_epoch(10);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, bob);
+        expect_abort_contains_message(
+            ExitCode::USR_FORBIDDEN,
+            "has expired",
+            h.approve(&rt, TxnID(0), proposal_hash),
+        );
+        rt.reset();
+        h.assert_transactions(&rt, vec![(TxnID(0), expiring_txn(chuck, anne, Some(10)))]);
+        check_state(&rt);
+    }
+
+    #[test]
+    fn prune_removes_only_expired_transactions() {
+        let (rt, h, anne, _) = setup();
+        let chuck = Address::new_id(103);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+        for expiration in [Some(10), None, Some(20)] {
+            h.propose_with_expiration(
+                &rt,
+                chuck,
+                TokenAmount::zero(),
+                METHOD_SEND,
+                RawBytes::default(),
+                expiration,
+            )
+            .unwrap();
+        }
+
+        // Anyone can prune.
+        rt.set_epoch(15);
+        rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(1234));
+        assert_eq!(vec![TxnID(0)], h.prune_expired(&rt).unwrap());
+        h.assert_transactions(
+            &rt,
+            vec![
+                (TxnID(1), expiring_txn(chuck, anne, None)),
+                (TxnID(2), expiring_txn(chuck, anne, Some(20))),
+            ],
+        );
+
+        // Nothing further to prune.
+        assert!(h.prune_expired(&rt).unwrap().is_empty());
+        check_state(&rt);
+    }
+
+    #[test]
+    fn propose_leaves_expired_transactions() {
+        let (rt, h, anne, bob) = setup();
+        let chuck = Address::new_id(103);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+        h.propose_with_expiration(
+            &rt,
+            chuck,
+            TokenAmount::zero(),
+            METHOD_SEND,
+            RawBytes::default(),
+            Some(10),
+        )
+        .unwrap();
+
+        // Expired transactions are only removed when pruned or cancelled.
+        rt.set_epoch(10);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, bob);
+        h.propose_ok(&rt, chuck, TokenAmount::zero(), METHOD_SEND, RawBytes::default());
+        h.assert_transactions(
+            &rt,
+            vec![
+                (TxnID(0), expiring_txn(chuck, anne, Some(10))),
+                (TxnID(1), expiring_txn(chuck, bob, None)),
+            ],
+        );
+        check_state(&rt);
+    }
+
+    #[test]
+    fn any_signer_can_cancel_expired_transaction() {
+        let (rt, h, anne, bob) = setup();
+        let chuck = Address::new_id(103);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+        h.propose_with_expiration(
+            &rt,
+            chuck,
+            TokenAmount::zero(),
+            METHOD_SEND,
+            RawBytes::default(),
+            Some(10),
+        )
+        .unwrap();
+        let proposal_hash =
+            compute_proposal_hash(&expiring_txn(chuck, anne, Some(10)), &rt).unwrap();
+
+        rt.set_epoch(9);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, bob);
+        expect_abort_contains_message(
+            ExitCode::USR_FORBIDDEN,
+            "Cannot cancel another signers transaction",
+            h.cancel(&rt, TxnID(0), proposal_hash),
+        );
+        rt.reset();
+
+        rt.set_epoch(10);
+        h.cancel(&rt, TxnID(0), proposal_hash).unwrap();
+        h.assert_transactions(&rt, vec![]);
+        check_state(&rt);
+    }
+
+    #[test]
+    fn proposal_hash_commits_to_expiration() {
+        let (rt, h, anne, bob) = setup();
+        let chuck = Address::new_id(103);
+        let never = compute_proposal_hash(&expiring_txn(chuck, anne, None), &rt).unwrap();
+        let at_10 = compute_proposal_hash(&expiring_txn(chuck, anne, Some(10)), &rt).unwrap();
+        let at_20 = compute_proposal_hash(&expiring_txn(chuck, anne, Some(20)), &rt).unwrap();
+        assert_ne!(never, at_10);
+        assert_ne!(at_10, at_20);
+
+        // The hash of a transaction without an expiration is unchanged.
+        let legacy = ProposalHashData {
+            requester: Some(&anne),
+            to: &chuck,
+            value: &TokenAmount::zero(),
+            method: &METHOD_SEND,
+            params: &RawBytes::default(),
+        };
+        assert_eq!(rt.hash_blake2b(&serialize_vec(&legacy, "proposal hash").unwrap()), never);
+
+        // A transaction can't be approved with the hash of a different expiration.
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+        h.propose_with_expiration(
+            &rt,
+            chuck,
+            TokenAmount::zero(),
+            METHOD_SEND,
+            RawBytes::default(),
+            Some(10),
+        )
+        .unwrap();
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, bob);
+        expect_abort_contains_message(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            "hash does not match proposal params",
+            h.approve(&rt, TxnID(0), at_20),
+        );
+        rt.reset();
+        check_state(&rt);
+    }
+
+    #[test]
+    fn propose_accepts_params_without_expiration() {
+        // Propose params as encoded before proposals could expire.
+        #[derive(Serialize_tuple)]
+        struct LegacyProposeParams {
+            to: Address,
+            value: TokenAmount,
+            method: MethodNum,
+            params: RawBytes,
+        }
+
+        let (rt, h, anne, _) = setup();
+        let chuck = Address::new_id(103);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+        rt.expect_validate_caller_any();
+        let params = LegacyProposeParams {
+            to: chuck,
+            value: TokenAmount::zero(),
+            method: METHOD_SEND,
+            params: RawBytes::default(),
+        };
+        rt.call::<MultisigActor>(
+            Method::Propose as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        )
+        .unwrap();
+        rt.verify();
+        h.assert_transactions(&rt, vec![(TxnID(0), expiring_txn(chuck, anne, None))]);
+        check_state(&rt);
+    }
+}
+
+mod execution_delay_tests {
+    use super::*;
+
+    const MSIG: Address = Address::new_id(1000);
+    const ANNE: Address = Address::new_id(101);
+    const BOB: Address = Address::new_id(102);
+    const CHUCK: Address = Address::new_id(103);
+
+    fn setup() -> (MockRuntime, util::ActorHarness) {
+        let rt = construct_runtime(MSIG);
+        let h = util::ActorHarness::new();
+        h.construct_and_verify(&rt, 2, 0, 0, vec![ANNE, BOB]);
+        rt.set_caller(*MULTISIG_ACTOR_CODE_ID, MSIG);
+        h.set_execution_delays(
+            &rt,
+            vec![
+                ExecutionDelay { value_above: TokenAmount::from_whole(10), delay: 100 },
+                ExecutionDelay { value_above: TokenAmount::from_whole(100), delay: 1000 },
+            ],
+        )
+        .unwrap();
+        rt.set_balance(TokenAmount::from_whole(1000));
+        (rt, h)
+    }
+
+    fn txn(to: Address, value: TokenAmount, executable_epoch: Option<ChainEpoch>) -> Transaction {
+        Transaction {
+            to,
+            value,
+            method: METHOD_SEND,
+            params: RawBytes::default(),
+            approved: vec![ANNE, BOB],
+            expiration_epoch: None,
+            executable_epoch,
+        }
+    }
+
+    // Anne proposes and Bob approves a transfer to Chuck, returning the proposal hash.
+    fn propose_and_approve(
+        rt: &MockRuntime,
+        h: &util::ActorHarness,
+        value: &TokenAmount,
+    ) -> [u8; 32] {
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, ANNE);
+        let proposal_hash =
+            h.propose_ok(rt, CHUCK, value.clone(), METHOD_SEND, RawBytes::default());
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, BOB);
+        let ret = h.approve(rt, TxnID(0), proposal_hash).unwrap();
+        assert!(!ret.unwrap().deserialize::<ApproveReturn>().unwrap().applied);
+        proposal_hash
+    }
+
+    #[test]
+    fn transaction_below_tier_executes_immediately() {
+        let (rt, h) = setup();
+        let value = TokenAmount::from_whole(10);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, ANNE);
+        let proposal_hash =
+            h.propose_ok(&rt, CHUCK, value.clone(), METHOD_SEND, RawBytes::default());
+
+        rt.expect_send_simple(CHUCK, METHOD_SEND, None, value, None, ExitCode::OK);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, BOB);
+        h.approve_ok(&rt, TxnID(0), proposal_hash);
+        h.assert_transactions(&rt, vec![]);
+        check_state(&rt);
+    }
+
+    #[test]
+    fn delayed_transaction_executes_after_delay() {
+        let (rt, h) = setup();
+        rt.set_epoch(10);
+        let value = TokenAmount::from_whole(50);
+        let proposal_hash = propose_and_approve(&rt, &h, &value);
+        h.assert_transactions(&rt, vec![(TxnID(0), txn(CHUCK, value.clone(), Some(110)))]);
+        check_state(&rt);
+
+        // Approving again doesn't execute the transaction before the delay has passed.
+        rt.set_epoch(109);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, ANNE);
+        let ret = h.approve(&rt, TxnID(0), proposal_hash).unwrap();
+        assert!(!ret.unwrap().deserialize::<ApproveReturn>().unwrap().applied);
+        h.assert_transactions(&rt, vec![(TxnID(0), txn(CHUCK, value.clone(), Some(110)))]);
+
+        // Any approver may execute it after the delay.
+        rt.set_epoch(110);
+        rt.expect_send_simple(CHUCK, METHOD_SEND, None, value, None, ExitCode::OK);
+        h.approve_ok(&rt, TxnID(0), proposal_hash);
+        h.assert_transactions(&rt, vec![]);
+        check_state(&rt);
+    }
+
+    #[test]
+    fn higher_tier_has_longer_delay() {
+        let (rt, h) = setup();
+        let value = TokenAmount::from_whole(101);
+        propose_and_approve(&rt, &h, &value);
+        h.assert_transactions(&rt, vec![(TxnID(0), txn(CHUCK, value, Some(1000)))]);
+        check_state(&rt);
+    }
+
+    #[test]
+    fn transactions_to_self_have_longest_delay() {
+        let (rt, h) = setup();
+        let value = TokenAmount::zero();
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, ANNE);
+        let proposal_hash =
+            h.propose_ok(&rt, MSIG, value.clone(), METHOD_SEND, RawBytes::default());
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, BOB);
+        h.approve(&rt, TxnID(0), proposal_hash).unwrap();
+        h.assert_transactions(&rt, vec![(TxnID(0), txn(MSIG, value, Some(1000)))]);
+        check_state(&rt);
+    }
+
+    #[test]
+    fn any_signer_can_cancel_during_delay() {
+        let (rt, h) = setup();
+        let value = TokenAmount::from_whole(50);
+        let proposal_hash = propose_and_approve(&rt, &h, &value);
+
+        // Bob didn't propose the transaction, but can cancel it while it's delayed.
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, BOB);
+        h.cancel(&rt, TxnID(0), proposal_hash).unwrap();
+        h.assert_transactions(&rt, vec![]);
+        check_state(&rt);
+    }
+
+    #[test]
+    fn only_proposer_can_cancel_after_delay() {
+        let (rt, h) = setup();
+        let value = TokenAmount::from_whole(50);
+        let proposal_hash = propose_and_approve(&rt, &h, &value);
+
+        rt.set_epoch(100);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, BOB);
+        expect_abort(ExitCode::USR_FORBIDDEN, h.cancel(&rt, TxnID(0), proposal_hash));
+        rt.reset();
+
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, ANNE);
+        h.cancel(&rt, TxnID(0), proposal_hash).unwrap();
+        h.assert_transactions(&rt, vec![]);
+        check_state(&rt);
+    }
+
+    #[test]
+    fn set_execution_delays_checks_preconditions() {
+        let (rt, h) = setup();
+        let tier = |value: i64, delay: ChainEpoch| ExecutionDelay {
+            value_above: TokenAmount::from_whole(value),
+            delay,
+        };
+
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, ANNE);
+        expect_abort(ExitCode::USR_FORBIDDEN, h.set_execution_delays(&rt, vec![]));
+        rt.reset();
+
+        rt.set_caller(*MULTISIG_ACTOR_CODE_ID, MSIG);
+        for delays in [
+            vec![tier(-1, 10)],
+            vec![tier(1, 0)],
+            vec![tier(1, 10), tier(1, 20)],
+            vec![tier(1, 20), tier(2, 10)],
+        ] {
+            expect_abort(ExitCode::USR_ILLEGAL_ARGUMENT, h.set_execution_delays(&rt, delays));
+            rt.reset();
+        }
+
+        // Delays can be removed.
+        h.set_execution_delays(&rt, vec![]).unwrap();
+        let st: State = rt.get_state();
+        assert!(st.execution_delays.is_empty());
+        check_state(&rt);
+    }
+}
+
+mod list_pending_transactions_tests {
+    use super::*;
+
+    fn setup() -> (MockRuntime, util::ActorHarness, Address, Address) {
+        let msig = Address::new_id(1000);
+        let rt = construct_runtime(msig);
+        let h = util::ActorHarness::new();
+        let anne = Address::new_id(101);
+        let bob = Address::new_id(102);
+        h.construct_and_verify(&rt, 2, 0, 0, vec![anne, bob]);
+
+        let chuck = Address::new_id(103);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+        for expiration in [Some(10), None, Some(20)] {
+            h.propose_with_expiration(
+                &rt,
+                chuck,
+                TokenAmount::zero(),
+                METHOD_SEND,
+                RawBytes::default(),
+                expiration,
+            )
+            .unwrap();
+        }
+        rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(1234));
+        (rt, h, anne, chuck)
+    }
+
+    fn ids(page: &ListPendingTransactionsReturn) -> Vec<TxnID> {
+        let mut ids: Vec<TxnID> = page.entries.iter().map(|r| r.id).collect();
+        ids.sort_by_key(|id| id.0);
+        ids
+    }
+
+    #[test]
+    fn lists_all_pages() {
+        let (rt, h, anne, chuck) = setup();
+        let page = h.list_pending_transactions(&rt, ListRequest::first(10)).unwrap();
+        assert_eq!(vec![TxnID(0), TxnID(1), TxnID(2)], ids(&page));
+        assert_eq!(None, page.next_cursor);
+        let first = page.entries.iter().find(|r| r.id == TxnID(0)).unwrap();
+        assert_eq!(
+            Transaction {
+                to: chuck,
+                value: TokenAmount::zero(),
+                method: METHOD_SEND,
+                params: RawBytes::default(),
+                approved: vec![anne],
+                expiration_epoch: Some(10),
+                executable_epoch: None,
+            },
+            first.transaction
+        );
+
+        // Paging one transaction at a time visits each transaction once.
+        let mut request = ListRequest::first(1);
+        let mut listed = vec![];
+        loop {
+            let page = h.list_pending_transactions(&rt, request.clone()).unwrap();
+            assert_eq!(1, page.entries.len());
+            listed.extend(ids(&page));
+            if page.next_cursor.is_none() {
+                break;
+            }
+            request.cursor = page.next_cursor;
+        }
+        listed.sort_by_key(|id| id.0);
+        assert_eq!(vec![TxnID(0), TxnID(1), TxnID(2)], listed);
+        check_state(&rt);
+    }
+
+    #[test]
+    fn filters_on_expiration() {
+        let (rt, h, _, _) = setup();
+        // Transactions without an expiration don't match conditions on it.
+        let mut request = ListRequest::first(10);
+        request.filter.push(FieldFilter::new("expiration-epoch", FilterOp::Ge, 15));
+        let page = h.list_pending_transactions(&rt, request).unwrap();
+        assert_eq!(vec![TxnID(2)], ids(&page));
+
+        let mut request = ListRequest::first(10);
+        request.filter.push(FieldFilter::new("approvals", FilterOp::Eq, 1));
+        request.filter.push(FieldFilter::new("method", FilterOp::Eq, METHOD_SEND as i64));
+        let page = h.list_pending_transactions(&rt, request).unwrap();
+        assert_eq!(vec![TxnID(0), TxnID(1), TxnID(2)], ids(&page));
+        check_state(&rt);
+    }
+
+    #[test]
+    fn resumes_after_cursor_transaction_removed() {
+        let (rt, h, _, _) = setup();
+        let page = h.list_pending_transactions(&rt, ListRequest::first(1)).unwrap();
+        let cursor = page.next_cursor.unwrap();
+
+        // The transaction at the cursor is removed before the next page is listed.
+        let mut st: State = rt.get_state();
+        let mut txns =
+            PendingTxnMap::load(&rt.store, &st.pending_txs, PENDING_TXN_CONFIG, "pending").unwrap();
+        txns.delete(&cursor).unwrap();
+        st.pending_txs = txns.flush().unwrap();
+        rt.replace_state(&st);
+
+        let mut request = ListRequest::first(10);
+        request.cursor = Some(cursor);
+        let next = h.list_pending_transactions(&rt, request).unwrap();
+        assert_eq!(None, next.next_cursor);
+        let mut listed = ids(&page);
+        listed.extend(ids(&next));
+        listed.sort_by_key(|id| id.0);
+        let expected: Vec<TxnID> =
+            [TxnID(0), TxnID(1), TxnID(2)].into_iter().filter(|id| *id != cursor).collect();
+        assert_eq!(expected, listed);
+        check_state(&rt);
+    }
+
+    #[test]
+    fn filters_on_resolved_recipient() {
+        let (rt, h, anne, chuck) = setup();
+        let chuck_key = Address::new_bls(&[3; BLS_PUB_LEN]).unwrap();
+        rt.add_id_address(chuck_key, chuck);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, anne);
+        h.propose_with_expiration(
+            &rt,
+            chuck_key,
+            TokenAmount::zero(),
+            METHOD_SEND,
+            RawBytes::default(),
+            None,
+        )
+        .unwrap();
+        rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(1234));
+
+        // A transaction proposed to the recipient's key address matches its ID.
+        let mut request = ListRequest::first(10);
+        request.filter.push(FieldFilter::new("to", FilterOp::Eq, chuck.id().unwrap() as i64));
+        let page = h.list_pending_transactions(&rt, request).unwrap();
+        assert_eq!(vec![TxnID(0), TxnID(1), TxnID(2), TxnID(3)], ids(&page));
+        let proposed = page.entries.iter().find(|r| r.id == TxnID(3)).unwrap();
+        assert_eq!(chuck_key, proposed.transaction.to);
+        check_state(&rt);
+    }
+
+    #[test]
+    fn rejects_bad_requests() {
+        let (rt, h, _, _) = setup();
+        let mut request = ListRequest::first(10);
+        request.filter.push(FieldFilter::new("value", FilterOp::Gt, 0));
+        expect_abort_contains_message(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            "cannot filter on value",
+            h.list_pending_transactions(&rt, request),
+        );
+        rt.reset();
+        check_state(&rt);
+    }
+}
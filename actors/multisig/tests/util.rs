@@ -1,9 +1,12 @@
 use fil_actor_multisig::{
-    compute_proposal_hash, Actor, AddSignerParams, ApproveReturn, ConstructorParams, Method,
-    PendingTxnMap, ProposeParams, ProposeReturn, RemoveSignerParams, State, SwapSignerParams,
-    Transaction, TxnID, TxnIDParams, PENDING_TXN_CONFIG,
+    compute_proposal_hash, Actor, AddSignerParams, ApproveReturn, ConstructorParams,
+    ExecutionDelay, ListPendingTransactionsParams, ListPendingTransactionsReturn, Method,
+    PendingTxnMap, ProposeParams, ProposeReturn, PruneExpiredReturn, ReconfigureSignersParams,
+    RemoveSignerParams, SetExecutionDelaysParams, State, SwapSignerParams, Transaction, TxnID,
+    TxnIDParams, PENDING_TXN_CONFIG,
 };
 use fil_actor_multisig::{ChangeNumApprovalsThresholdParams, LockBalanceParams};
+use fil_actors_runtime::query::ListRequest;
 use fil_actors_runtime::test_utils::*;
 use fil_actors_runtime::ActorError;
 use fil_actors_runtime::INIT_ACTOR_ADDR;
@@ -91,6 +94,23 @@ impl ActorHarness {
         ret
     }
 
+    pub fn reconfigure_signers(
+        &self,
+        rt: &MockRuntime,
+        add: Vec<Address>,
+        remove: Vec<Address>,
+        new_threshold: u64,
+    ) -> Result<Option<IpldBlock>, ActorError> {
+        rt.expect_validate_caller_addr(vec![rt.receiver]);
+        let params = ReconfigureSignersParams { add, remove, new_threshold };
+        let ret = rt.call::<Actor>(
+            Method::ReconfigureSigners as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        );
+        rt.verify();
+        ret
+    }
+
     pub fn propose_ok(
         &self,
         rt: &MockRuntime,
@@ -102,7 +122,15 @@ impl ActorHarness {
         let ret = self.propose(rt, to, value.clone(), method, params.clone());
         ret.unwrap().unwrap().deserialize::<ProposeReturn>().unwrap();
         // compute proposal hash
-        let txn = Transaction { to, value, method, params, approved: vec![*rt.caller.borrow()] };
+        let txn = Transaction {
+            to,
+            value,
+            method,
+            params,
+            approved: vec![*rt.caller.borrow()],
+            expiration_epoch: None,
+            executable_epoch: None,
+        };
         compute_proposal_hash(&txn, rt).unwrap()
     }
 
@@ -122,9 +150,21 @@ impl ActorHarness {
         value: TokenAmount,
         method: MethodNum,
         params: RawBytes,
+    ) -> Result<Option<IpldBlock>, ActorError> {
+        self.propose_with_expiration(rt, to, value, method, params, None)
+    }
+
+    pub fn propose_with_expiration(
+        &self,
+        rt: &MockRuntime,
+        to: Address,
+        value: TokenAmount,
+        method: MethodNum,
+        params: RawBytes,
+        expiration_epoch: Option<ChainEpoch>,
     ) -> Result<Option<IpldBlock>, ActorError> {
         rt.expect_validate_caller_any();
-        let propose_params = ProposeParams { to, value, method, params };
+        let propose_params = ProposeParams { to, value, method, params, expiration_epoch };
         let ret = rt.call::<Actor>(
             Method::Propose as u64,
             IpldBlock::serialize_cbor(&propose_params).unwrap(),
@@ -167,6 +207,28 @@ impl ActorHarness {
         ret
     }
 
+    pub fn prune_expired(&self, rt: &MockRuntime) -> Result<Vec<TxnID>, ActorError> {
+        rt.expect_validate_caller_any();
+        let ret = rt.call::<Actor>(Method::PruneExpiredExported as u64, None);
+        rt.verify();
+        Ok(ret?.unwrap().deserialize::<PruneExpiredReturn>().unwrap().pruned)
+    }
+
+    pub fn list_pending_transactions(
+        &self,
+        rt: &MockRuntime,
+        request: ListRequest<TxnID>,
+    ) -> Result<ListPendingTransactionsReturn, ActorError> {
+        rt.expect_validate_caller_any();
+        let params = ListPendingTransactionsParams { request };
+        let ret = rt.call::<Actor>(
+            Method::ListPendingTransactionsExported as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        );
+        rt.verify();
+        Ok(ret?.unwrap().deserialize().unwrap())
+    }
+
     pub fn lock_balance(
         &self,
         rt: &MockRuntime,
@@ -200,6 +262,21 @@ impl ActorHarness {
         ret
     }
 
+    pub fn set_execution_delays(
+        &self,
+        rt: &MockRuntime,
+        delays: Vec<ExecutionDelay>,
+    ) -> Result<Option<IpldBlock>, ActorError> {
+        rt.expect_validate_caller_addr(vec![rt.receiver]);
+        let params = SetExecutionDelaysParams { delays };
+        let ret = rt.call::<Actor>(
+            Method::SetExecutionDelays as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        );
+        rt.verify();
+        ret
+    }
+
     pub fn assert_transactions(
         &self,
         rt: &MockRuntime,
@@ -40,6 +40,14 @@ pub enum Method {
     UpdateChannelState = 2,
     Settle = 3,
     Collect = 4,
+    UpdateChannelStateBatch = 5,
+    SettleAndCollect = 6,
+    // Method numbers derived from FRC-0042 standards
+    UpdateChannelStateExported = frc42_dispatch::method_hash!("UpdateChannelState"),
+    SettleExported = frc42_dispatch::method_hash!("Settle"),
+    CollectExported = frc42_dispatch::method_hash!("Collect"),
+    UpdateChannelStateBatchExported = frc42_dispatch::method_hash!("UpdateChannelStateBatch"),
+    SettleAndCollectExported = frc42_dispatch::method_hash!("SettleAndCollect"),
 }
 
 pub const ERR_CHANNEL_STATE_UPDATE_AFTER_SETTLED: ExitCode = ExitCode::new(32);
@@ -54,7 +62,9 @@ impl Actor {
         // behalf of the payer/payee.
         rt.validate_immediate_caller_type(std::iter::once(&Type::Init))?;
 
-        // Check both parties are capable of signing vouchers
+        // Resolve both parties, creating placeholders for undeployed delegated addresses.
+        // Vouchers are authenticated by calling AuthenticateMessage on the counterparty,
+        // so any actor implementing it (including EVM contracts) may be a party.
         let to = resolve_to_actor_id(rt, &params.to, true).map(Address::new_id)?;
         let from = resolve_to_actor_id(rt, &params.from, true).map(Address::new_id)?;
 
@@ -77,92 +87,50 @@ impl Actor {
 
         rt.validate_immediate_caller_is([st.from, st.to].iter())?;
         let signer = if rt.message().caller() == st.from { st.to } else { st.from };
-        let sv = params.sv;
 
-        // Pull signature from signed voucher
-        let sig = &sv
-            .signature
-            .as_ref()
-            .ok_or_else(|| actor_error!(illegal_argument, "voucher has no signature"))?
-            .bytes;
-
-        if st.settling_at != 0 && rt.curr_epoch() >= st.settling_at {
-            return Err(ActorError::unchecked(
-                ERR_CHANNEL_STATE_UPDATE_AFTER_SETTLED,
-                "no vouchers can be processed after settling at epoch".to_string(),
-            ));
-        }
-
-        if params.secret.len() > MAX_SECRET_SIZE {
-            return Err(actor_error!(illegal_argument, "secret must be at most 256 bytes long"));
-        }
-
-        // Generate unsigned bytes
-        let sv_bz = sv.signing_bytes().map_err(|e| {
-            ActorError::serialization(format!("failed to serialized SignedVoucher: {}", e))
-        })?;
+        verify_voucher(rt, &st, &signer, &params)?;
 
-        // Validate signature
+        rt.transaction(|st: &mut State, rt| {
+            let mut l_states = Array::load(&st.lane_states, rt.store()).map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load lane states")
+            })?;
 
-        if !extract_send_result(rt.send(
-            &signer,
-            ext::account::AUTHENTICATE_MESSAGE_METHOD,
-            IpldBlock::serialize_cbor(&ext::account::AuthenticateMessageParams {
-                signature: sig.to_vec(),
-                message: sv_bz,
-            })?,
-            TokenAmount::zero(),
-            None,
-            SendFlags::READ_ONLY,
-        ))
-        .and_then(deserialize_block)
-        .context("proposal authentication failed")?
-        {
-            return Err(actor_error!(illegal_argument, "voucher sig authentication failed"));
-        }
+            apply_voucher(st, &mut l_states, params.sv, &rt.current_balance())?;
 
-        let pch_addr = rt.message().receiver();
-        let svpch_id = rt.resolve_address(&sv.channel_addr).ok_or_else(|| {
-            actor_error!(
-                illegal_argument,
-                "voucher payment channel address {} does not resolve to an ID address",
-                sv.channel_addr
-            )
-        })?;
-        if pch_addr != Address::new_id(svpch_id) {
-            return Err(actor_error!(illegal_argument;
-                    "voucher payment channel address {} does not match receiver {}",
-                    svpch_id, pch_addr));
-        }
+            st.lane_states = l_states.flush().map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to save lanes")
+            })?;
+            Ok(())
+        })
+    }
 
-        if rt.curr_epoch() < sv.time_lock_min {
-            return Err(actor_error!(illegal_argument; "cannot use this voucher yet"));
-        }
+    /// Verifies and applies several vouchers, possibly across lanes, in one message.
+    /// Vouchers are applied in order, so a later voucher may supersede an earlier one on the
+    /// same lane. Either all vouchers are applied or none are.
+    pub fn update_channel_state_batch(
+        rt: &impl Runtime,
+        params: UpdateChannelStateBatchParams,
+    ) -> Result<(), ActorError> {
+        let st: State = rt.state()?;
 
-        if sv.time_lock_max != 0 && rt.curr_epoch() > sv.time_lock_max {
-            return Err(actor_error!(illegal_argument; "this voucher has expired"));
-        }
+        rt.validate_immediate_caller_is([st.from, st.to].iter())?;
+        let signer = if rt.message().caller() == st.from { st.to } else { st.from };
 
-        if sv.amount.is_negative() {
-            return Err(actor_error!(illegal_argument;
-                    "voucher amount must be non-negative, was {}", sv.amount));
+        if params.updates.is_empty() {
+            return Err(actor_error!(illegal_argument, "batch must contain at least one voucher"));
         }
-
-        if !sv.secret_pre_image.is_empty() {
-            let hashed_secret: &[u8] = &rt.hash_blake2b(&params.secret);
-            if hashed_secret != sv.secret_pre_image.as_slice() {
-                return Err(actor_error!(illegal_argument; "incorrect secret"));
-            }
+        if params.updates.len() > MAX_UPDATE_BATCH_SIZE {
+            return Err(actor_error!(
+                illegal_argument,
+                "batch of {} vouchers exceeds maximum of {}",
+                params.updates.len(),
+                MAX_UPDATE_BATCH_SIZE
+            ));
         }
 
-        if let Some(extra) = &sv.extra {
-            extract_send_result(rt.send_simple(
-                &extra.actor,
-                extra.method,
-                Some(IpldBlock { codec: CBOR, data: extra.data.to_vec() }),
-                TokenAmount::zero(),
-            ))
-            .map_err(|e| e.wrap("spend voucher verification failed"))?;
+        for (i, update) in params.updates.iter().enumerate() {
+            verify_voucher(rt, &st, &signer, update)
+                .map_err(|e| e.wrap(format!("invalid voucher at index {}", i)))?;
         }
 
         rt.transaction(|st: &mut State, rt| {
@@ -170,93 +138,12 @@ impl Actor {
                 e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load lane states")
             })?;
 
-            // Find the voucher lane, create and insert it in sorted order if necessary.
-            let lane_id = sv.lane;
-            let lane_state = find_lane(&l_states, lane_id)?;
-
-            let mut lane_state = if let Some(state) = lane_state {
-                if state.nonce >= sv.nonce {
-                    return Err(actor_error!(illegal_argument;
-                        "voucher has an outdated nonce, existing: {}, voucher: {}, cannot redeem",
-                        state.nonce, sv.nonce));
-                }
-                state.clone()
-            } else {
-                LaneState::default()
-            };
-
-            // The next section actually calculates the payment amounts to update
-            // the payment channel state
-            // 1. (optional) sum already redeemed value of all merging lanes
-            let mut redeemed_from_others = TokenAmount::zero();
-            for merge in sv.merges {
-                if merge.lane == sv.lane {
-                    return Err(actor_error!(illegal_argument;
-                        "voucher cannot merge lanes into it's own lane"));
-                }
-                let mut other_ls = find_lane(&l_states, merge.lane)?
-                    .ok_or_else(|| {
-                        actor_error!(illegal_argument;
-                        "voucher specifies invalid merge lane {}", merge.lane)
-                    })?
-                    .clone();
-
-                if other_ls.nonce >= merge.nonce {
-                    return Err(actor_error!(illegal_argument;
-                            "merged lane in voucher has outdated nonce, cannot redeem"));
-                }
-
-                redeemed_from_others += &other_ls.redeemed;
-                other_ls.nonce = merge.nonce;
-                l_states.set(merge.lane, other_ls).map_err(|e| {
-                    e.downcast_default(
-                        ExitCode::USR_ILLEGAL_STATE,
-                        format!("failed to store lane {}", merge.lane),
-                    )
-                })?;
-            }
-
-            // 2. To prevent double counting, remove already redeemed amounts (from
-            // voucher or other lanes) from the voucher amount
-            lane_state.nonce = sv.nonce;
-            let balance_delta = &sv.amount - (redeemed_from_others + &lane_state.redeemed);
-
-            // 3. set new redeemed value for merged-into lane
-            lane_state.redeemed = sv.amount;
-
-            // 4. check operation validity
-            let new_send_balance = balance_delta + &st.to_send;
-
-            if new_send_balance < TokenAmount::zero() {
-                return Err(actor_error!(illegal_argument;
-                    "voucher would leave channel balance negative"));
+            let balance = rt.current_balance();
+            for (i, update) in params.updates.into_iter().enumerate() {
+                apply_voucher(st, &mut l_states, update.sv, &balance)
+                    .map_err(|e| e.wrap(format!("failed to apply voucher at index {}", i)))?;
             }
 
-            if new_send_balance > rt.current_balance() {
-                return Err(actor_error!(illegal_argument;
-                    "not enough funds in channel to cover voucher"));
-            }
-
-            // 5. add new redemption ToSend
-            st.to_send = new_send_balance;
-
-            // update channel settlingAt and MinSettleHeight if delayed by voucher
-            if sv.min_settle_height != 0 {
-                if st.settling_at != 0 && st.settling_at < sv.min_settle_height {
-                    st.settling_at = sv.min_settle_height;
-                }
-                if st.min_settle_height < sv.min_settle_height {
-                    st.min_settle_height = sv.min_settle_height;
-                }
-            }
-
-            l_states.set(lane_id, lane_state).map_err(|e| {
-                e.downcast_default(
-                    ExitCode::USR_ILLEGAL_STATE,
-                    format!("failed to store lane {}", lane_id),
-                )
-            })?;
-
             st.lane_states = l_states.flush().map_err(|e| {
                 e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to save lanes")
             })?;
@@ -276,6 +163,7 @@ impl Actor {
             if st.settling_at < st.min_settle_height {
                 st.settling_at = st.min_settle_height;
             }
+            st.settlement_nonce += 1;
 
             Ok(())
         })
@@ -289,20 +177,288 @@ impl Actor {
             return Err(actor_error!(forbidden; "payment channel not settling or settled"));
         }
 
-        // send ToSend to `to`
-        extract_send_result(rt.send_simple(&st.to, METHOD_SEND, None, st.to_send))
-            .map_err(|e| e.wrap("Failed to send funds to `to` address"))?;
+        pay_out_and_delete(rt, &st)
+    }
 
-        // return remaining balance back to the "from" address.
-        extract_send_result(rt.send_simple(&st.from, METHOD_SEND, None, rt.current_balance()))
-            .map_err(|e| e.wrap("Failed to send funds to `from` address"))?;
+    /// Closes the channel immediately with the consent of both parties, paying out the
+    /// redeemed amount without waiting for the settlement delay or minimum settle height.
+    /// The caller must be one party and present the other party's signature over the params.
+    /// The signature is bound to the channel's current settlement nonce and expires after
+    /// the given epoch, so it can't be replayed against a later state of the channel.
+    pub fn settle_and_collect(
+        rt: &impl Runtime,
+        params: SettleAndCollectParams,
+    ) -> Result<(), ActorError> {
+        let st: State = rt.state()?;
 
-        rt.delete_actor()?;
+        rt.validate_immediate_caller_is([st.from, st.to].iter())?;
+        let signer = if rt.message().caller() == st.from { st.to } else { st.from };
 
-        Ok(())
+        let sig = &params
+            .signature
+            .as_ref()
+            .ok_or_else(|| actor_error!(illegal_argument, "settlement has no signature"))?
+            .bytes;
+
+        let bz = params.signing_bytes().map_err(|e| {
+            ActorError::serialization(format!("failed to serialize settlement: {}", e))
+        })?;
+        if !authenticate(rt, &signer, sig, bz)? {
+            return Err(actor_error!(illegal_argument, "settlement sig authentication failed"));
+        }
+
+        check_channel_addr(rt, &params.channel_addr)?;
+
+        if rt.curr_epoch() > params.expiry_epoch {
+            return Err(actor_error!(
+                illegal_argument,
+                "settlement expired at epoch {}",
+                params.expiry_epoch
+            ));
+        }
+
+        if params.nonce != st.settlement_nonce {
+            return Err(actor_error!(
+                illegal_argument,
+                "settlement nonce {} does not match channel nonce {}",
+                params.nonce,
+                st.settlement_nonce
+            ));
+        }
+
+        if params.to_send != st.to_send {
+            return Err(actor_error!(
+                illegal_argument,
+                "settlement amount {} does not match redeemed amount {}",
+                params.to_send,
+                st.to_send
+            ));
+        }
+
+        pay_out_and_delete(rt, &st)
     }
 }
 
+/// Checks everything about a voucher that doesn't depend on lane state,
+/// including its signature and any extra verification call.
+fn verify_voucher(
+    rt: &impl Runtime,
+    st: &State,
+    signer: &Address,
+    params: &UpdateChannelStateParams,
+) -> Result<(), ActorError> {
+    let sv = &params.sv;
+
+    // Pull signature from signed voucher
+    let sig = &sv
+        .signature
+        .as_ref()
+        .ok_or_else(|| actor_error!(illegal_argument, "voucher has no signature"))?
+        .bytes;
+
+    if st.settling_at != 0 && rt.curr_epoch() >= st.settling_at {
+        return Err(ActorError::unchecked(
+            ERR_CHANNEL_STATE_UPDATE_AFTER_SETTLED,
+            "no vouchers can be processed after settling at epoch".to_string(),
+        ));
+    }
+
+    if params.secret.len() > MAX_SECRET_SIZE {
+        return Err(actor_error!(illegal_argument, "secret must be at most 256 bytes long"));
+    }
+
+    // Generate unsigned bytes
+    let sv_bz = sv.signing_bytes().map_err(|e| {
+        ActorError::serialization(format!("failed to serialized SignedVoucher: {}", e))
+    })?;
+
+    // Validate signature
+    if !authenticate(rt, signer, sig, sv_bz)? {
+        return Err(actor_error!(illegal_argument, "voucher sig authentication failed"));
+    }
+
+    check_channel_addr(rt, &sv.channel_addr)?;
+
+    if rt.curr_epoch() < sv.time_lock_min {
+        return Err(actor_error!(illegal_argument; "cannot use this voucher yet"));
+    }
+
+    if sv.time_lock_max != 0 && rt.curr_epoch() > sv.time_lock_max {
+        return Err(actor_error!(illegal_argument; "this voucher has expired"));
+    }
+
+    if sv.amount.is_negative() {
+        return Err(actor_error!(illegal_argument;
+                "voucher amount must be non-negative, was {}", sv.amount));
+    }
+
+    if !sv.secret_pre_image.is_empty() {
+        let hashed_secret: &[u8] = &rt.hash_blake2b(&params.secret);
+        if hashed_secret != sv.secret_pre_image.as_slice() {
+            return Err(actor_error!(illegal_argument; "incorrect secret"));
+        }
+    }
+
+    if let Some(extra) = &sv.extra {
+        extract_send_result(rt.send_simple(
+            &extra.actor,
+            extra.method,
+            Some(IpldBlock { codec: CBOR, data: extra.data.to_vec() }),
+            TokenAmount::zero(),
+        ))
+        .map_err(|e| e.wrap("spend voucher verification failed"))?;
+    }
+    Ok(())
+}
+
+/// Applies a verified voucher to the lane states, updating the amount to send.
+fn apply_voucher<BS: Blockstore>(
+    st: &mut State,
+    l_states: &mut Array<LaneState, BS>,
+    sv: SignedVoucher,
+    balance: &TokenAmount,
+) -> Result<(), ActorError> {
+    // Find the voucher lane, create and insert it in sorted order if necessary.
+    let lane_id = sv.lane;
+    let lane_state = find_lane(l_states, lane_id)?;
+
+    let mut lane_state = if let Some(state) = lane_state {
+        if state.nonce >= sv.nonce {
+            return Err(actor_error!(illegal_argument;
+                "voucher has an outdated nonce, existing: {}, voucher: {}, cannot redeem",
+                state.nonce, sv.nonce));
+        }
+        state.clone()
+    } else {
+        LaneState::default()
+    };
+
+    // The next section actually calculates the payment amounts to update
+    // the payment channel state
+    // 1. (optional) sum already redeemed value of all merging lanes
+    let mut redeemed_from_others = TokenAmount::zero();
+    for merge in sv.merges {
+        if merge.lane == sv.lane {
+            return Err(actor_error!(illegal_argument;
+                "voucher cannot merge lanes into it's own lane"));
+        }
+        let mut other_ls = find_lane(l_states, merge.lane)?
+            .ok_or_else(|| {
+                actor_error!(illegal_argument;
+                "voucher specifies invalid merge lane {}", merge.lane)
+            })?
+            .clone();
+
+        if other_ls.nonce >= merge.nonce {
+            return Err(actor_error!(illegal_argument;
+                    "merged lane in voucher has outdated nonce, cannot redeem"));
+        }
+
+        redeemed_from_others += &other_ls.redeemed;
+        other_ls.nonce = merge.nonce;
+        l_states.set(merge.lane, other_ls).map_err(|e| {
+            e.downcast_default(
+                ExitCode::USR_ILLEGAL_STATE,
+                format!("failed to store lane {}", merge.lane),
+            )
+        })?;
+    }
+
+    // 2. To prevent double counting, remove already redeemed amounts (from
+    // voucher or other lanes) from the voucher amount
+    lane_state.nonce = sv.nonce;
+    let balance_delta = &sv.amount - (redeemed_from_others + &lane_state.redeemed);
+
+    // 3. set new redeemed value for merged-into lane
+    lane_state.redeemed = sv.amount;
+
+    // 4. check operation validity
+    let new_send_balance = balance_delta + &st.to_send;
+
+    if new_send_balance < TokenAmount::zero() {
+        return Err(actor_error!(illegal_argument;
+            "voucher would leave channel balance negative"));
+    }
+
+    if &new_send_balance > balance {
+        return Err(actor_error!(illegal_argument;
+            "not enough funds in channel to cover voucher"));
+    }
+
+    // 5. add new redemption ToSend
+    st.to_send = new_send_balance;
+    st.settlement_nonce += 1;
+
+    // update channel settlingAt and MinSettleHeight if delayed by voucher
+    if sv.min_settle_height != 0 {
+        if st.settling_at != 0 && st.settling_at < sv.min_settle_height {
+            st.settling_at = sv.min_settle_height;
+        }
+        if st.min_settle_height < sv.min_settle_height {
+            st.min_settle_height = sv.min_settle_height;
+        }
+    }
+
+    l_states.set(lane_id, lane_state).map_err(|e| {
+        e.downcast_default(ExitCode::USR_ILLEGAL_STATE, format!("failed to store lane {}", lane_id))
+    })
+}
+
+/// Asks the signer's account to authenticate a signature over a message.
+fn authenticate(
+    rt: &impl Runtime,
+    signer: &Address,
+    signature: &[u8],
+    message: Vec<u8>,
+) -> Result<bool, ActorError> {
+    extract_send_result(rt.send(
+        signer,
+        ext::account::AUTHENTICATE_MESSAGE_METHOD,
+        IpldBlock::serialize_cbor(&ext::account::AuthenticateMessageParams {
+            signature: signature.to_vec(),
+            message,
+        })?,
+        TokenAmount::zero(),
+        None,
+        SendFlags::READ_ONLY,
+    ))
+    .and_then(deserialize_block)
+    .context("proposal authentication failed")
+}
+
+/// Checks that a channel address given in signed params resolves to this actor.
+fn check_channel_addr(rt: &impl Runtime, channel_addr: &Address) -> Result<(), ActorError> {
+    let pch_addr = rt.message().receiver();
+    let svpch_id = rt.resolve_address(channel_addr).ok_or_else(|| {
+        actor_error!(
+            illegal_argument,
+            "voucher payment channel address {} does not resolve to an ID address",
+            channel_addr
+        )
+    })?;
+    if pch_addr != Address::new_id(svpch_id) {
+        return Err(actor_error!(illegal_argument;
+                "voucher payment channel address {} does not match receiver {}",
+                svpch_id, pch_addr));
+    }
+    Ok(())
+}
+
+/// Sends the redeemed amount to `to`, returns the remainder to `from` and deletes the channel.
+fn pay_out_and_delete(rt: &impl Runtime, st: &State) -> Result<(), ActorError> {
+    // send ToSend to `to`
+    extract_send_result(rt.send_simple(&st.to, METHOD_SEND, None, st.to_send.clone()))
+        .map_err(|e| e.wrap("Failed to send funds to `to` address"))?;
+
+    // return remaining balance back to the "from" address.
+    extract_send_result(rt.send_simple(&st.from, METHOD_SEND, None, rt.current_balance()))
+        .map_err(|e| e.wrap("Failed to send funds to `from` address"))?;
+
+    rt.delete_actor()?;
+
+    Ok(())
+}
+
 #[inline]
 fn find_lane<'a, BS>(
     ls: &'a Array<LaneState, BS>,
@@ -327,10 +483,22 @@ impl ActorCode for Actor {
         "PaymentChannel"
     }
 
+    fil_actors_runtime::actor_info! {
+        interface_revisions: [1],
+        features: [],
+    }
+
     actor_dispatch! {
         Constructor => constructor,
         UpdateChannelState => update_channel_state,
         Settle => settle,
         Collect => collect,
+        UpdateChannelStateBatch => update_channel_state_batch,
+        SettleAndCollect => settle_and_collect,
+        UpdateChannelStateExported => update_channel_state,
+        SettleExported => settle,
+        CollectExported => collect,
+        UpdateChannelStateBatchExported => update_channel_state_batch,
+        SettleAndCollectExported => settle_and_collect,
     }
 }
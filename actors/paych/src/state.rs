@@ -25,6 +25,9 @@ pub struct State {
     pub min_settle_height: ChainEpoch,
     /// Collections of lane states for the channel, maintained in ID order.
     pub lane_states: Cid, // AMT<LaneState>
+    /// Nonce a settlement must be signed over, advanced whenever a voucher is redeemed or
+    /// the channel starts settling.
+    pub settlement_nonce: u64,
 }
 
 impl State {
@@ -36,6 +39,7 @@ impl State {
             settling_at: 0,
             min_settle_height: 0,
             lane_states: empty_arr_cid,
+            settlement_nonce: 0,
         }
     }
 }
@@ -22,6 +22,9 @@ pub const MAX_SECRET_SIZE: usize = 256;
 
 pub const LANE_STATES_AMT_BITWIDTH: u32 = 3;
 
+/// Maximum number of vouchers that can be submitted in a single batch update.
+pub const MAX_UPDATE_BATCH_SIZE: usize = 256;
+
 /// Constructor parameters for payment channel actor
 #[derive(Serialize_tuple, Deserialize_tuple)]
 pub struct ConstructorParams {
@@ -126,3 +129,44 @@ impl From<SignedVoucher> for UpdateChannelStateParams {
         UpdateChannelStateParams { secret: vec![], sv }
     }
 }
+
+#[derive(Serialize_tuple, Deserialize_tuple)]
+pub struct UpdateChannelStateBatchParams {
+    pub updates: Vec<UpdateChannelStateParams>,
+}
+
+/// Parameters for closing a channel immediately with the consent of both parties.
+/// Signed by the party that is not submitting the message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct SettleAndCollectParams {
+    /// Address of the payment channel being closed
+    pub channel_addr: Address,
+    /// Amount to pay out to `to`, which must match the amount redeemed in the channel
+    pub to_send: TokenAmount,
+    /// The channel's settlement nonce, so the signature is spent by any later redemption
+    pub nonce: u64,
+    /// Last epoch at which the settlement may be submitted
+    pub expiry_epoch: ChainEpoch,
+    /// Counterparty's signature over the params (sign on none)
+    pub signature: Option<Signature>,
+}
+
+impl SettleAndCollectParams {
+    pub fn signing_bytes(&self) -> Result<Vec<u8>, Error> {
+        #[derive(Serialize_tuple)]
+        struct SettleAndCollectSer<'a> {
+            pub channel_addr: &'a Address,
+            pub to_send: &'a TokenAmount,
+            pub nonce: u64,
+            pub expiry_epoch: ChainEpoch,
+            pub signature: (),
+        }
+        to_vec(&SettleAndCollectSer {
+            channel_addr: &self.channel_addr,
+            to_send: &self.to_send,
+            nonce: self.nonce,
+            expiry_epoch: self.expiry_epoch,
+            signature: (),
+        })
+    }
+}
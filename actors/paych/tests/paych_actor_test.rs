@@ -10,7 +10,8 @@ use fil_actor_paych::ext::account::{AuthenticateMessageParams, AUTHENTICATE_MESS
 use fil_actor_paych::testing::check_state_invariants;
 use fil_actor_paych::{
     Actor as PaychActor, ConstructorParams, LaneState, Merge, Method, ModVerifyParams,
-    SignedVoucher, State as PState, UpdateChannelStateParams, MAX_LANE, SETTLE_DELAY,
+    SettleAndCollectParams, SignedVoucher, State as PState, UpdateChannelStateBatchParams,
+    UpdateChannelStateParams, MAX_LANE, MAX_UPDATE_BATCH_SIZE, SETTLE_DELAY,
 };
 
 use fil_actors_runtime::runtime::builtins::Type;
@@ -463,6 +464,7 @@ mod update_channel_state_redeem {
             settling_at: state.settling_at,
             min_settle_height: state.min_settle_height,
             lane_states: construct_lane_state_amt(&rt, vec![exp_ls]),
+            settlement_nonce: state.settlement_nonce + 1,
         };
         verify_state(&rt, Some(1), exp_state);
     }
@@ -584,6 +586,7 @@ mod merge_tests {
         let redeemed = &merge_from.redeemed + &merge_to.redeemed;
         let exp_delta = &sv.amount - &redeemed;
         state.to_send = exp_delta + &state.to_send;
+        state.settlement_nonce += 1;
 
         state.lane_states = construct_lane_state_amt(
             &rt,
@@ -1022,6 +1025,403 @@ mod actor_collect {
     }
 }
 
+mod update_channel_state_batch {
+    use super::*;
+
+    fn voucher(template: &SignedVoucher, lane: u64, nonce: u64, amount: u64) -> SignedVoucher {
+        SignedVoucher { lane, nonce, amount: TokenAmount::from_atto(amount), ..template.clone() }
+    }
+
+    fn batch_params(vouchers: &[SignedVoucher]) -> Option<IpldBlock> {
+        let updates = vouchers.iter().cloned().map(UpdateChannelStateParams::from).collect();
+        IpldBlock::serialize_cbor(&UpdateChannelStateBatchParams { updates }).unwrap()
+    }
+
+    #[test]
+    fn redeem_vouchers_across_lanes() {
+        let (rt, sv) = require_create_channel_with_lanes(1);
+        let state: PState = rt.get_state();
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, state.from);
+
+        // Update the existing lane twice and open two new lanes.
+        let vouchers = vec![
+            voucher(&sv, 0, 2, 10),
+            voucher(&sv, 1, 1, 20),
+            voucher(&sv, 0, 3, 15),
+            voucher(&sv, 2, 1, 30),
+        ];
+        rt.expect_validate_caller_addr(vec![state.from, state.to]);
+        for v in &vouchers {
+            expect_authenticate_message(&rt, state.to, v.clone(), ExitCode::OK);
+        }
+        call(&rt, Method::UpdateChannelStateBatch as u64, batch_params(&vouchers));
+        rt.verify();
+
+        let state: PState = rt.get_state();
+        assert_lane_states_length(&rt, &state.lane_states, 3);
+        assert_eq!(
+            LaneState { redeemed: TokenAmount::from_atto(15), nonce: 3 },
+            get_lane_state(&rt, &state.lane_states, 0)
+        );
+        assert_eq!(
+            LaneState { redeemed: TokenAmount::from_atto(20), nonce: 1 },
+            get_lane_state(&rt, &state.lane_states, 1)
+        );
+        assert_eq!(
+            LaneState { redeemed: TokenAmount::from_atto(30), nonce: 1 },
+            get_lane_state(&rt, &state.lane_states, 2)
+        );
+        assert_eq!(TokenAmount::from_atto(65), state.to_send);
+        check_state(&rt);
+    }
+
+    #[test]
+    fn batch_is_atomic() {
+        let (rt, sv) = require_create_channel_with_lanes(1);
+        let state: PState = rt.get_state();
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, state.from);
+
+        // The second voucher reuses the nonce of the first, so neither is applied.
+        let vouchers = vec![voucher(&sv, 1, 1, 20), voucher(&sv, 1, 1, 25)];
+        rt.expect_validate_caller_addr(vec![state.from, state.to]);
+        for v in &vouchers {
+            expect_authenticate_message(&rt, state.to, v.clone(), ExitCode::OK);
+        }
+        expect_abort(
+            &rt,
+            Method::UpdateChannelStateBatch as u64,
+            batch_params(&vouchers),
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+        );
+        rt.verify();
+
+        let after: PState = rt.get_state();
+        assert_eq!(state.lane_states, after.lane_states);
+        assert_eq!(state.to_send, after.to_send);
+        check_state(&rt);
+    }
+
+    #[test]
+    fn rejects_invalid_signature() {
+        let (rt, sv) = require_create_channel_with_lanes(1);
+        let state: PState = rt.get_state();
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, state.from);
+
+        let vouchers = vec![voucher(&sv, 1, 1, 20), voucher(&sv, 2, 1, 20)];
+        rt.expect_validate_caller_addr(vec![state.from, state.to]);
+        expect_authenticate_message(&rt, state.to, vouchers[0].clone(), ExitCode::OK);
+        expect_authenticate_message(
+            &rt,
+            state.to,
+            vouchers[1].clone(),
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+        );
+        expect_abort(
+            &rt,
+            Method::UpdateChannelStateBatch as u64,
+            batch_params(&vouchers),
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+        );
+        rt.verify();
+        check_state(&rt);
+    }
+
+    #[test]
+    fn rejects_empty_or_oversized_batch() {
+        let (rt, sv) = require_create_channel_with_lanes(1);
+        let state: PState = rt.get_state();
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, state.from);
+
+        rt.expect_validate_caller_addr(vec![state.from, state.to]);
+        expect_abort(
+            &rt,
+            Method::UpdateChannelStateBatch as u64,
+            batch_params(&[]),
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+        );
+
+        let vouchers: Vec<_> =
+            (0..=MAX_UPDATE_BATCH_SIZE as u64).map(|i| voucher(&sv, i + 1, 1, 1)).collect();
+        rt.expect_validate_caller_addr(vec![state.from, state.to]);
+        expect_abort(
+            &rt,
+            Method::UpdateChannelStateBatch as u64,
+            batch_params(&vouchers),
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+        );
+        rt.verify();
+    }
+}
+
+mod settle_and_collect {
+    use fvm_shared::METHOD_SEND;
+
+    use super::*;
+
+    fn settlement(rt: &MockRuntime) -> SettleAndCollectParams {
+        let state: PState = rt.get_state();
+        SettleAndCollectParams {
+            channel_addr: Address::new_id(PAYCH_ID),
+            to_send: state.to_send,
+            nonce: state.settlement_nonce,
+            expiry_epoch: *rt.epoch.borrow(),
+            signature: Some(Signature::new_bls(vec![7, 6, 5, 4, 3, 2, 1, 0])),
+        }
+    }
+
+    // Expects a settlement to be authenticated by the payee and rejected.
+    fn expect_settlement_rejected(rt: &MockRuntime, params: &SettleAndCollectParams) {
+        let state: PState = rt.get_state();
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, state.from);
+        rt.expect_validate_caller_addr(vec![state.from, state.to]);
+        expect_authenticate_settlement(rt, state.to, params, ExitCode::OK);
+        expect_abort(
+            rt,
+            Method::SettleAndCollect as u64,
+            IpldBlock::serialize_cbor(params).unwrap(),
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+        );
+        rt.verify();
+        assert!(!rt.is_deleted());
+        check_state(rt);
+    }
+
+    fn expect_authenticate_settlement(
+        rt: &MockRuntime,
+        signer: Address,
+        params: &SettleAndCollectParams,
+        exp_exit_code: ExitCode,
+    ) {
+        rt.expect_send(
+            signer,
+            AUTHENTICATE_MESSAGE_METHOD,
+            IpldBlock::serialize_cbor(&AuthenticateMessageParams {
+                signature: params.signature.clone().unwrap().bytes,
+                message: params.signing_bytes().unwrap(),
+            })
+            .unwrap(),
+            TokenAmount::zero(),
+            None,
+            SendFlags::READ_ONLY,
+            exp_exit_code.is_success().then(|| AUTHENTICATE_MESSAGE_RESPONSE.clone()).flatten(),
+            exp_exit_code,
+            None,
+        )
+    }
+
+    #[test]
+    fn closes_without_settlement_delay() {
+        let (rt, mut sv) = require_create_channel_with_lanes(1);
+        let state: PState = rt.get_state();
+
+        // A voucher pushing out the min settle height doesn't hold up a cooperative close.
+        sv.min_settle_height = *rt.epoch.borrow() + 10 * SETTLE_DELAY;
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, state.from);
+        rt.expect_validate_caller_addr(vec![state.from, state.to]);
+        expect_authenticate_message(&rt, state.to, sv.clone(), ExitCode::OK);
+        call(
+            &rt,
+            Method::UpdateChannelState as u64,
+            IpldBlock::serialize_cbor(&UpdateChannelStateParams::from(sv)).unwrap(),
+        );
+        rt.verify();
+
+        // The payee closes the channel with the payer's signature.
+        let state: PState = rt.get_state();
+        let params = settlement(&rt);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, state.to);
+        rt.expect_validate_caller_addr(vec![state.from, state.to]);
+        expect_authenticate_settlement(&rt, state.from, &params, ExitCode::OK);
+        rt.expect_send_simple(
+            state.to,
+            METHOD_SEND,
+            None,
+            state.to_send.clone(),
+            None,
+            ExitCode::OK,
+        );
+        rt.expect_send_simple(
+            state.from,
+            METHOD_SEND,
+            None,
+            &*rt.balance.borrow() - &state.to_send,
+            None,
+            ExitCode::OK,
+        );
+        rt.expect_delete_actor();
+        let res =
+            call(&rt, Method::SettleAndCollect as u64, IpldBlock::serialize_cbor(&params).unwrap());
+        assert!(res.is_none());
+        rt.verify();
+        assert!(rt.is_deleted());
+    }
+
+    #[test]
+    fn rejects_mismatched_amount() {
+        let (rt, _sv) = require_create_channel_with_lanes(1);
+        let mut params = settlement(&rt);
+        params.to_send += TokenAmount::from_atto(1);
+        expect_settlement_rejected(&rt, &params);
+    }
+
+    #[test]
+    fn rejects_expired_settlement() {
+        let (rt, _sv) = require_create_channel_with_lanes(1);
+        let params = settlement(&rt);
+        rt.set_epoch(params.expiry_epoch + 1);
+        expect_settlement_rejected(&rt, &params);
+    }
+
+    #[test]
+    fn rejects_settlement_signed_before_later_redemption() {
+        let (rt, mut sv) = require_create_channel_with_lanes(1);
+        let stale = settlement(&rt);
+
+        // Redeeming a voucher advances the nonce, spending the settlement signed before it.
+        let state: PState = rt.get_state();
+        sv.amount += TokenAmount::from_atto(1);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, state.to);
+        rt.expect_validate_caller_addr(vec![state.from, state.to]);
+        expect_authenticate_message(&rt, state.from, sv.clone(), ExitCode::OK);
+        call(
+            &rt,
+            Method::UpdateChannelState as u64,
+            IpldBlock::serialize_cbor(&UpdateChannelStateParams::from(sv)).unwrap(),
+        );
+        rt.verify();
+        let state: PState = rt.get_state();
+        assert_eq!(stale.nonce + 1, state.settlement_nonce);
+
+        // Even for the amount now redeemed, the old signature no longer applies.
+        let params = SettleAndCollectParams { to_send: state.to_send, ..stale };
+        expect_settlement_rejected(&rt, &params);
+    }
+
+    #[test]
+    fn rejects_settlement_signed_before_settling() {
+        let (rt, _sv) = require_create_channel_with_lanes(1);
+        let stale = settlement(&rt);
+
+        let state: PState = rt.get_state();
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, state.from);
+        rt.expect_validate_caller_addr(vec![state.from, state.to]);
+        call(&rt, Method::Settle as u64, None);
+        rt.verify();
+
+        expect_settlement_rejected(&rt, &stale);
+    }
+
+    #[test]
+    fn rejects_missing_or_invalid_signature() {
+        let (rt, _sv) = require_create_channel_with_lanes(1);
+        let state: PState = rt.get_state();
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, state.from);
+
+        let mut params = settlement(&rt);
+        params.signature = None;
+        rt.expect_validate_caller_addr(vec![state.from, state.to]);
+        expect_abort(
+            &rt,
+            Method::SettleAndCollect as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+        );
+
+        let params = settlement(&rt);
+        rt.expect_validate_caller_addr(vec![state.from, state.to]);
+        expect_authenticate_settlement(&rt, state.to, &params, ExitCode::USR_ILLEGAL_ARGUMENT);
+        expect_abort(
+            &rt,
+            Method::SettleAndCollect as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+        );
+        rt.verify();
+        assert!(!rt.is_deleted());
+    }
+}
+
+mod contract_parties {
+    use super::*;
+
+    // Creates a channel from an EVM contract payer to an account payee.
+    fn construct_runtime() -> MockRuntime {
+        let payer_addr = Address::new_id(PAYER_ID);
+        let payee_addr = Address::new_id(PAYEE_ID);
+        let mut actor_code_cids = HashMap::default();
+        actor_code_cids.insert(payer_addr, *EVM_ACTOR_CODE_ID);
+        actor_code_cids.insert(payee_addr, *ACCOUNT_ACTOR_CODE_ID);
+
+        let rt = MockRuntime {
+            receiver: Address::new_id(PAYCH_ID),
+            caller: RefCell::new(INIT_ACTOR_ADDR),
+            caller_type: RefCell::new(*INIT_ACTOR_CODE_ID),
+            actor_code_cids: RefCell::new(actor_code_cids),
+            balance: RefCell::new(TokenAmount::from_atto(100_000)),
+            epoch: RefCell::new(2),
+            ..Default::default()
+        };
+        construct_and_verify(&rt, payer_addr, payee_addr);
+        rt
+    }
+
+    fn voucher() -> SignedVoucher {
+        SignedVoucher {
+            time_lock_min: 0,
+            time_lock_max: i64::MAX,
+            lane: 0,
+            nonce: 1,
+            amount: TokenAmount::from_atto(10),
+            signature: Some(Signature::new_secp256k1(vec![7; 65])),
+            secret_pre_image: Default::default(),
+            channel_addr: Address::new_id(PAYCH_ID),
+            extra: Default::default(),
+            min_settle_height: Default::default(),
+            merges: Default::default(),
+        }
+    }
+
+    #[test]
+    fn redeem_voucher_authenticated_by_contract() {
+        let rt = construct_runtime();
+        let payer_addr = Address::new_id(PAYER_ID);
+        let payee_addr = Address::new_id(PAYEE_ID);
+        let sv = voucher();
+
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, payee_addr);
+        rt.expect_validate_caller_addr(vec![payer_addr, payee_addr]);
+        expect_authenticate_message(&rt, payer_addr, sv.clone(), ExitCode::OK);
+        call(
+            &rt,
+            Method::UpdateChannelStateExported as u64,
+            IpldBlock::serialize_cbor(&UpdateChannelStateParams::from(sv)).unwrap(),
+        );
+        rt.verify();
+
+        let state: PState = rt.get_state();
+        assert_eq!(TokenAmount::from_atto(10), state.to_send);
+        check_state(&rt);
+    }
+
+    #[test]
+    fn contract_uses_exported_methods() {
+        let rt = construct_runtime();
+        let payer_addr = Address::new_id(PAYER_ID);
+        let payee_addr = Address::new_id(PAYEE_ID);
+        rt.set_caller(*EVM_ACTOR_CODE_ID, payer_addr);
+
+        expect_abort(&rt, Method::Settle as u64, None, ExitCode::USR_FORBIDDEN);
+
+        rt.expect_validate_caller_addr(vec![payer_addr, payee_addr]);
+        call(&rt, Method::SettleExported as u64, None);
+        rt.verify();
+
+        let state: PState = rt.get_state();
+        assert_eq!(2 + SETTLE_DELAY, state.settling_at);
+        check_state(&rt);
+    }
+}
+
 fn require_create_channel_with_lanes(num_lanes: u64) -> (MockRuntime, SignedVoucher) {
     let paych_addr = Address::new_id(100);
     let payer_addr = Address::new_id(PAYER_ID);
@@ -1121,6 +1521,7 @@ fn verify_state(rt: &MockRuntime, exp_lanes: Option<u64>, expected_state: PState
     assert_eq!(expected_state.min_settle_height, state.min_settle_height);
     assert_eq!(expected_state.settling_at, state.settling_at);
     assert_eq!(expected_state.to_send, state.to_send);
+    assert_eq!(expected_state.settlement_nonce, state.settlement_nonce);
     if let Some(exp_lanes) = exp_lanes {
         assert_lane_states_length(rt, &state.lane_states, exp_lanes);
         assert_eq!(expected_state.lane_states, state.lane_states);
@@ -0,0 +1,162 @@
+//! Compares the cost of enrolling and popping cron events with the epoch-keyed AMT queue
+//! against the HAMT[ChainEpoch]AMT[CronEvent] multimap it replaced.
+//!
+//! Each enrollment loads the queue, adds one event and flushes it, as when miners enroll
+//! in separate messages, and each epoch's cron tick pops its due events and flushes once.
+//! Blockstore reads and writes are reported alongside running time, as they dominate the
+//! gas charged on chain.
+//!
+//! Run with `cargo bench -p fil_actor_power --bench cron_event_queue`.
+
+use std::time::{Duration, Instant};
+
+use cid::Cid;
+use fil_actor_power::{epoch_key, CronEvent, CronEventQueue};
+use fil_actors_runtime::test_blockstores::{BSStats, MemoryBlockstore};
+use fil_actors_runtime::Multimap;
+use fvm_ipld_encoding::RawBytes;
+use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
+
+// Bit widths of the multimap previously used as the queue.
+const LEGACY_HAMT_BITWIDTH: u32 = 6;
+const LEGACY_AMT_BITWIDTH: u32 = 6;
+
+// Miners enroll in the last epoch of a deadline, spread over this many deadlines.
+const DEADLINES: i64 = 48;
+const DEADLINE_EPOCHS: i64 = 60;
+
+struct Scenario {
+    name: &'static str,
+    miners: u64,
+    deadlines: i64,
+}
+
+const SCENARIOS: &[Scenario] = &[
+    Scenario { name: "one deadline, 10 miners", miners: 10, deadlines: 1 },
+    Scenario { name: "one deadline, 100 miners", miners: 100, deadlines: 1 },
+    Scenario { name: "one deadline, 1000 miners", miners: 1000, deadlines: 1 },
+    Scenario { name: "all deadlines, 1000 miners", miners: 1000, deadlines: DEADLINES },
+];
+
+struct Measurement {
+    stats: BSStats,
+    elapsed: Duration,
+}
+
+fn enrollments(scenario: &Scenario) -> Vec<(ChainEpoch, CronEvent)> {
+    (0..scenario.miners)
+        .map(|m| {
+            let deadline = m as i64 % scenario.deadlines;
+            let epoch = (deadline + 1) * DEADLINE_EPOCHS - 1;
+            let event = CronEvent {
+                miner_addr: Address::new_id(1000 + m),
+                callback_payload: RawBytes::new(vec![0; 8]),
+            };
+            (epoch, event)
+        })
+        .collect()
+}
+
+fn last_epoch(scenario: &Scenario) -> ChainEpoch {
+    scenario.deadlines * DEADLINE_EPOCHS
+}
+
+fn measure(store: &MemoryBlockstore, f: impl FnOnce()) -> Measurement {
+    let before = *store.stats.borrow();
+    let start = Instant::now();
+    f();
+    let elapsed = start.elapsed();
+    let after = *store.stats.borrow();
+    Measurement {
+        stats: BSStats {
+            r: after.r - before.r,
+            w: after.w - before.w,
+            br: after.br - before.br,
+            bw: after.bw - before.bw,
+        },
+        elapsed,
+    }
+}
+
+fn bench_queue(scenario: &Scenario) -> (Measurement, Measurement) {
+    let store = MemoryBlockstore::new();
+    let mut root = CronEventQueue::new_empty(&store).unwrap();
+
+    let enroll = measure(&store, || {
+        for (epoch, event) in enrollments(scenario) {
+            let mut queue = CronEventQueue::new(&store, &root).unwrap();
+            queue.enqueue(epoch, event).unwrap();
+            root = queue.flush().unwrap();
+        }
+    });
+    let pop = measure(&store, || {
+        let mut popped = 0;
+        for epoch in 0..=last_epoch(scenario) {
+            let mut queue = CronEventQueue::new(&store, &root).unwrap();
+            popped += queue.pop_until(epoch).unwrap().len();
+            root = queue.flush().unwrap();
+        }
+        assert_eq!(scenario.miners as usize, popped);
+    });
+    (enroll, pop)
+}
+
+fn bench_legacy(scenario: &Scenario) -> (Measurement, Measurement) {
+    let store = MemoryBlockstore::new();
+    let mut root: Cid =
+        Multimap::new(&store, LEGACY_HAMT_BITWIDTH, LEGACY_AMT_BITWIDTH).root().unwrap();
+
+    let enroll = measure(&store, || {
+        for (epoch, event) in enrollments(scenario) {
+            let mut mmap =
+                Multimap::from_root(&store, &root, LEGACY_HAMT_BITWIDTH, LEGACY_AMT_BITWIDTH)
+                    .unwrap();
+            mmap.add(epoch_key(epoch), event).unwrap();
+            root = mmap.root().unwrap();
+        }
+    });
+    let pop = measure(&store, || {
+        let mut popped = 0;
+        for epoch in 0..=last_epoch(scenario) {
+            let mut mmap =
+                Multimap::from_root(&store, &root, LEGACY_HAMT_BITWIDTH, LEGACY_AMT_BITWIDTH)
+                    .unwrap();
+            let mut events = Vec::new();
+            mmap.for_each(&epoch_key(epoch), |_, event: &CronEvent| {
+                events.push(event.clone());
+                Ok(())
+            })
+            .unwrap();
+            if !events.is_empty() {
+                mmap.remove_all(&epoch_key(epoch)).unwrap();
+            }
+            popped += events.len();
+            root = mmap.root().unwrap();
+        }
+        assert_eq!(scenario.miners as usize, popped);
+    });
+    (enroll, pop)
+}
+
+fn report(scenario: &str, structure: &str, phase: &str, m: &Measurement) {
+    println!(
+        "{:<28} {:<8} {:<7} {:>8} {:>8} {:>12} {:>12} {:>10.2?}",
+        scenario, structure, phase, m.stats.r, m.stats.w, m.stats.br, m.stats.bw, m.elapsed
+    );
+}
+
+fn main() {
+    println!(
+        "{:<28} {:<8} {:<7} {:>8} {:>8} {:>12} {:>12} {:>10}",
+        "scenario", "queue", "phase", "reads", "writes", "bytes read", "bytes written", "time"
+    );
+    for scenario in SCENARIOS {
+        let (enroll, pop) = bench_legacy(scenario);
+        report(scenario.name, "multimap", "enroll", &enroll);
+        report(scenario.name, "multimap", "pop", &pop);
+        let (enroll, pop) = bench_queue(scenario);
+        report(scenario.name, "amt", "enroll", &enroll);
+        report(scenario.name, "amt", "pop", &pop);
+    }
+}
@@ -0,0 +1,83 @@
+// Copyright 2019-2022 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::convert::TryInto;
+
+use cid::Cid;
+use fil_actors_runtime::{ActorDowncast, Array};
+use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::tuple::*;
+use fvm_shared::clock::ChainEpoch;
+
+use super::CronEvent;
+
+pub const CRON_QUEUE_AMT_BITWIDTH: u32 = 6;
+
+/// All cron events enrolled for a single epoch, packed into one queue entry.
+#[derive(Clone, Debug, Default, Serialize_tuple, Deserialize_tuple)]
+pub struct CronEventBatch {
+    pub events: Vec<CronEvent>,
+}
+
+/// Wrapper for working with an AMT[ChainEpoch]CronEventBatch functioning as a queue of cron events.
+/// Events for the same epoch are packed into a single batch, so that enrolling an event touches
+/// a single entry and popping due events is a single ordered traversal of the AMT.
+/// Changes are only written to the store when the queue is flushed.
+pub struct CronEventQueue<'db, BS> {
+    pub amt: Array<'db, CronEventBatch, BS>,
+}
+
+impl<'db, BS: Blockstore> CronEventQueue<'db, BS> {
+    pub fn new(store: &'db BS, root: &Cid) -> anyhow::Result<Self> {
+        Ok(Self { amt: Array::load(root, store)? })
+    }
+
+    /// Creates an empty queue and returns its root.
+    pub fn new_empty(store: &'db BS) -> anyhow::Result<Cid> {
+        let mut amt =
+            Array::<CronEventBatch, BS>::new_with_bit_width(store, CRON_QUEUE_AMT_BITWIDTH);
+        Ok(amt.flush()?)
+    }
+
+    /// Appends an event to the batch for an epoch.
+    pub fn enqueue(&mut self, epoch: ChainEpoch, event: CronEvent) -> anyhow::Result<()> {
+        let key: u64 = epoch.try_into()?;
+        let mut batch = self
+            .amt
+            .get(key)
+            .map_err(|e| e.downcast_wrap(format!("failed to lookup cron events at {}", epoch)))?
+            .cloned()
+            .unwrap_or_default();
+        batch.events.push(event);
+        self.amt
+            .set(key, batch)
+            .map_err(|e| e.downcast_wrap(format!("failed to set cron events at {}", epoch)))?;
+        Ok(())
+    }
+
+    /// Removes and returns all events enrolled for epochs up to and including some epoch,
+    /// in epoch order and then enrollment order.
+    pub fn pop_until(&mut self, until: ChainEpoch) -> anyhow::Result<Vec<CronEvent>> {
+        let mut events = Vec::new();
+        let mut popped_keys = Vec::<u64>::new();
+
+        self.amt.for_each_while(|epoch, batch| {
+            if epoch as ChainEpoch > until {
+                return Ok(false);
+            }
+            popped_keys.push(epoch);
+            events.extend(batch.events.iter().cloned());
+            Ok(true)
+        })?;
+
+        if !popped_keys.is_empty() {
+            self.amt.batch_delete(popped_keys, true)?;
+        }
+        Ok(events)
+    }
+
+    /// Writes any pending changes to the store and returns the new root.
+    pub fn flush(&mut self) -> anyhow::Result<Cid> {
+        Ok(self.amt.flush()?)
+    }
+}
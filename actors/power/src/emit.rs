@@ -0,0 +1,48 @@
+use fil_actors_runtime::runtime::Runtime;
+use fil_actors_runtime::{ActorError, EventBuilder};
+use fvm_shared::bigint::bigint_ser::BigIntSer;
+use fvm_shared::sector::StoragePower;
+use fvm_shared::ActorID;
+
+use crate::{MinerChanges, PowerTotals};
+
+/// Indicates the aggregate power totals have been recomputed from claims.
+pub fn aggregates_recomputed(rt: &impl Runtime, totals: &PowerTotals) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("power-aggregates-recomputed")
+            .field("raw-byte-power", &BigIntSer(&totals.raw_byte_power))
+            .field("quality-adj-power", &BigIntSer(&totals.quality_adj_power))
+            .field("miner-count", &totals.miner_count)
+            .field("miner-above-min-power-count", &totals.miner_above_min_power_count)
+            .build()?,
+    )
+}
+
+/// Summarises the change in network power since the previous cron tick, along with the miners
+/// created and the miners crossing the consensus minimum power in either direction.
+pub fn epoch_power_summary(
+    rt: &impl Runtime,
+    raw_byte_delta: &StoragePower,
+    qa_delta: &StoragePower,
+    changes: &MinerChanges,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("power-epoch-summary")
+            .field("raw-byte-power-delta", &BigIntSer(raw_byte_delta))
+            .field("quality-adj-power-delta", &BigIntSer(qa_delta))
+            .field("new-miners", &changes.created)
+            .field("miners-reached-min-power", &changes.reached_min_power)
+            .field("miners-fell-below-min-power", &changes.fell_below_min_power)
+            .build()?,
+    )
+}
+
+/// Indicates a miner's power claim has been removed after its deferred cron callback failed.
+/// The miner forfeits its power rather than funds, so this is not a penalty-assessed event.
+pub fn claim_forfeited(rt: &impl Runtime, miner: ActorID) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new().typ("miner-claim-forfeited").field_indexed("miner", &miner).build()?,
+    )
+}
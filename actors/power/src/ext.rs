@@ -10,11 +10,16 @@ use num_derive::FromPrimitive;
 
 use fil_actors_runtime::reward::FilterEstimate;
 
+pub mod account {
+    pub const PUBKEY_ADDRESS_METHOD: u64 = 2;
+}
+
 pub mod init {
     use super::*;
     use fvm_ipld_encoding::RawBytes;
 
     pub const EXEC_METHOD: u64 = 2;
+    pub const EXEC_DELEGATED_METHOD: u64 = 4;
 
     /// Init actor Exec Params
     #[derive(Serialize_tuple, Deserialize_tuple)]
@@ -31,6 +36,25 @@ pub mod init {
         /// Reorg safe address for actor
         pub robust_address: Address,
     }
+
+    /// Init actor ExecDelegated Params
+    #[derive(Serialize_tuple, Deserialize_tuple)]
+    pub struct ExecDelegatedParams {
+        pub code_cid: Cid,
+        pub constructor_params: RawBytes,
+        pub subaddress: RawBytes,
+    }
+
+    /// Init actor ExecDelegated Return value
+    #[derive(Serialize_tuple, Deserialize_tuple)]
+    pub struct ExecDelegatedReturn {
+        /// ID based address for created actor
+        pub id_address: Address,
+        /// Reorg safe address for actor
+        pub robust_address: Address,
+        /// Delegated (f4) address for actor
+        pub delegated_address: Address,
+    }
 }
 
 pub mod miner {
@@ -5,18 +5,20 @@ use std::collections::BTreeSet;
 use std::convert::TryInto;
 
 use anyhow::anyhow;
+use fil_actors_runtime::reward::math::PRECISION;
 use fil_actors_runtime::reward::ThisEpochRewardReturn;
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_ipld_encoding::RawBytes;
 use fvm_shared::address::Address;
 use fvm_shared::bigint::bigint_ser::BigIntSer;
+use fvm_shared::bigint::BigInt;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
-use fvm_shared::sector::SealVerifyInfo;
+use fvm_shared::sector::{RegisteredPoStProof, SealVerifyInfo};
 use fvm_shared::{MethodNum, HAMT_BIT_WIDTH, METHOD_CONSTRUCTOR};
 use log::{debug, error};
 use num_derive::FromPrimitive;
-use num_traits::Zero;
+use num_traits::{Signed, Zero};
 
 use ext::init;
 use fil_actors_runtime::runtime::builtins::Type;
@@ -26,6 +28,7 @@ use fil_actors_runtime::{
     Multimap, CRON_ACTOR_ADDR, INIT_ACTOR_ADDR, REWARD_ACTOR_ADDR, SYSTEM_ACTOR_ADDR,
 };
 
+pub use self::cron_queue::*;
 pub use self::policy::*;
 pub use self::state::*;
 pub use self::types::*;
@@ -33,6 +36,8 @@ pub use self::types::*;
 #[cfg(feature = "fil-actor")]
 fil_actors_runtime::wasm_trampoline!(Actor);
 
+mod cron_queue;
+mod emit;
 #[doc(hidden)]
 pub mod ext;
 mod policy;
@@ -63,12 +68,16 @@ pub enum Method {
     // OnConsensusFault = 7,
     SubmitPoRepForBulkVerify = 8,
     CurrentTotalPower = 9,
+    RecomputeAggregates = 10,
     // Method numbers derived from FRC-0042 standards
     CreateMinerExported = frc42_dispatch::method_hash!("CreateMiner"),
     NetworkRawPowerExported = frc42_dispatch::method_hash!("NetworkRawPower"),
     MinerRawPowerExported = frc42_dispatch::method_hash!("MinerRawPower"),
     MinerCountExported = frc42_dispatch::method_hash!("MinerCount"),
     MinerConsensusCountExported = frc42_dispatch::method_hash!("MinerConsensusCount"),
+    NetworkStatsExported = frc42_dispatch::method_hash!("NetworkStats"),
+    GetPledgeRatiosExported = frc42_dispatch::method_hash!("GetPledgeRatios"),
+    CreateMinerDelegatedExported = frc42_dispatch::method_hash!("CreateMinerDelegated"),
 }
 
 pub const ERR_TOO_MANY_PROVE_COMMITS: ExitCode = ExitCode::new(32);
@@ -95,15 +104,6 @@ impl Actor {
         rt.validate_immediate_caller_accept_any()?;
         let value = rt.message().value_received();
 
-        let constructor_params = RawBytes::serialize(ext::miner::MinerConstructorParams {
-            owner: params.owner,
-            worker: params.worker,
-            window_post_proof_type: params.window_post_proof_type,
-            peer_id: params.peer,
-            multi_addresses: params.multiaddrs,
-            control_addresses: Default::default(),
-        })?;
-
         let miner_actor_code_cid = rt.get_code_cid_for_type(Type::Miner);
         let ext::init::ExecReturn { id_address, robust_address } =
             deserialize_block(extract_send_result(rt.send_simple(
@@ -111,38 +111,47 @@ impl Actor {
                 ext::init::EXEC_METHOD,
                 IpldBlock::serialize_cbor(&init::ExecParams {
                     code_cid: miner_actor_code_cid,
-                    constructor_params,
+                    constructor_params: miner_constructor_params(&params)?,
                 })?,
                 value,
             ))?)?;
 
-        let window_post_proof_type = params.window_post_proof_type;
-        rt.transaction(|st: &mut State, rt| {
-            let mut claims = st.load_claims(rt.store())?;
-            set_claim(
-                &mut claims,
-                &id_address,
-                Claim {
-                    window_post_proof_type,
-                    quality_adj_power: Default::default(),
-                    raw_byte_power: Default::default(),
-                },
-            )?;
-            st.miner_count += 1;
+        register_new_miner(rt, &id_address, params.window_post_proof_type)?;
+        Ok(CreateMinerReturn { id_address, robust_address })
+    }
 
-            st.update_stats_for_new_miner(rt.policy(), window_post_proof_type).map_err(|e| {
-                actor_error!(
-                    illegal_state,
-                    "failed to update power stats for new miner {}: {}",
-                    &id_address,
-                    e
-                )
-            })?;
+    /// Creates a miner with an f4 address in the power actor's namespace, derived from the caller's
+    /// address and a salt, as well as the usual ID and robust addresses.
+    /// Unlike the ID address, the f4 address doesn't depend on the order in which actors are
+    /// created, so can be computed before the miner exists.
+    /// The caller's address is its own f4 address or, for an account, its public key address,
+    /// since its ID address may change in a re-org.
+    fn create_miner_delegated(
+        rt: &impl Runtime,
+        params: CreateMinerDelegatedParams,
+    ) -> Result<CreateMinerDelegatedReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let value = rt.message().value_received();
 
-            st.save_claims(&mut claims)?;
-            Ok(())
-        })?;
-        Ok(CreateMinerReturn { id_address, robust_address })
+        let mut preimage = stable_caller_address(rt)?.to_bytes();
+        preimage.extend_from_slice(&params.salt);
+        let subaddress = rt.hash_blake2b(&preimage);
+
+        let miner_actor_code_cid = rt.get_code_cid_for_type(Type::Miner);
+        let ext::init::ExecDelegatedReturn { id_address, robust_address, delegated_address } =
+            deserialize_block(extract_send_result(rt.send_simple(
+                &INIT_ACTOR_ADDR,
+                ext::init::EXEC_DELEGATED_METHOD,
+                IpldBlock::serialize_cbor(&init::ExecDelegatedParams {
+                    code_cid: miner_actor_code_cid,
+                    constructor_params: miner_constructor_params(&params.miner)?,
+                    subaddress: subaddress.to_vec().into(),
+                })?,
+                value,
+            ))?)?;
+
+        register_new_miner(rt, &id_address, params.miner.window_post_proof_type)?;
+        Ok(CreateMinerDelegatedReturn { id_address, robust_address, delegated_address })
     }
 
     /// Adds or removes claimed power for the calling actor.
@@ -188,21 +197,16 @@ impl Actor {
         }
 
         rt.transaction(|st: &mut State, rt| {
-            let mut events = Multimap::from_root(
-                rt.store(),
-                &st.cron_event_queue,
-                CRON_QUEUE_HAMT_BITWIDTH,
-                CRON_QUEUE_AMT_BITWIDTH,
-            )
-            .map_err(|e| {
-                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load cron events")
-            })?;
+            let mut events =
+                CronEventQueue::new(rt.store(), &st.cron_event_queue).map_err(|e| {
+                    e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load cron events")
+                })?;
 
             st.append_cron_event(&mut events, params.event_epoch, miner_event).map_err(|e| {
                 e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to enroll cron event")
             })?;
 
-            st.cron_event_queue = events.root().map_err(|e| {
+            st.cron_event_queue = events.flush().map_err(|e| {
                 e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to flush cron events")
             })?;
             Ok(())
@@ -228,16 +232,26 @@ impl Actor {
         }
         Self::process_deferred_cron_events(rt, rewret)?;
 
-        let this_epoch_raw_byte_power = rt.transaction(|st: &mut State, _| {
-            let (raw_byte_power, qa_power) = st.current_total_power();
-            st.this_epoch_pledge_collateral = st.total_pledge_collateral.clone();
-            st.this_epoch_quality_adj_power = qa_power;
-            st.this_epoch_raw_byte_power = raw_byte_power;
-            // Can assume delta is one since cron is invoked every epoch.
-            st.update_smoothed_estimate(1);
-
-            Ok(IpldBlock::serialize_cbor(&BigIntSer(&st.this_epoch_raw_byte_power))?)
-        })?;
+        let (this_epoch_raw_byte_power, raw_byte_delta, qa_delta, miner_changes) =
+            rt.transaction(|st: &mut State, _| {
+                let (raw_byte_power, qa_power) = st.current_total_power();
+                let raw_byte_delta = &raw_byte_power - &st.this_epoch_raw_byte_power;
+                let qa_delta = &qa_power - &st.this_epoch_quality_adj_power;
+                st.this_epoch_pledge_collateral = st.total_pledge_collateral.clone();
+                st.record_pledge_flows();
+                st.this_epoch_quality_adj_power = qa_power;
+                st.this_epoch_raw_byte_power = raw_byte_power;
+                // Can assume delta is one since cron is invoked every epoch.
+                st.update_smoothed_estimate(1);
+
+                Ok((
+                    IpldBlock::serialize_cbor(&BigIntSer(&st.this_epoch_raw_byte_power))?,
+                    raw_byte_delta,
+                    qa_delta,
+                    std::mem::take(&mut st.miner_changes),
+                ))
+            })?;
+        emit::epoch_power_summary(rt, &raw_byte_delta, &qa_delta, &miner_changes)?;
 
         // Update network KPA in reward actor
         extract_send_result(rt.send_simple(
@@ -393,6 +407,80 @@ impl Actor {
         Ok(MinerConsensusCountReturn { miner_consensus_count: st.miner_above_min_power_count })
     }
 
+    /// Returns the aggregate power and pledge statistics of the network.
+    /// The "total" values are updated eagerly as messages are executed, while the "this epoch"
+    /// values and the smoothed quality-adjusted power estimate are static over an epoch.
+    fn network_stats(rt: &impl Runtime) -> Result<NetworkStatsReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let st: State = rt.state()?;
+
+        Ok(NetworkStatsReturn {
+            total_raw_byte_power: st.total_raw_byte_power,
+            total_bytes_committed: st.total_bytes_committed,
+            total_quality_adj_power: st.total_quality_adj_power,
+            total_qa_bytes_committed: st.total_qa_bytes_committed,
+            total_pledge_collateral: st.total_pledge_collateral,
+            this_epoch_raw_byte_power: st.this_epoch_raw_byte_power,
+            this_epoch_quality_adj_power: st.this_epoch_quality_adj_power,
+            this_epoch_pledge_collateral: st.this_epoch_pledge_collateral,
+            this_epoch_qa_power_smoothed: st.this_epoch_qa_power_smoothed,
+            miner_count: st.miner_count,
+            miner_above_min_power_count: st.miner_above_min_power_count,
+        })
+    }
+
+    /// Returns the network's pledge collateral relative to its quality-adjusted power and to the
+    /// circulating supply, along with the pledge added and released by miners during the last epoch.
+    /// The ratios are Q.128 fixed-point values computed from the "this epoch" pledge and power,
+    /// which are static over an epoch, and are zero if the denominator is zero.
+    fn get_pledge_ratios(rt: &impl Runtime) -> Result<GetPledgeRatiosReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let st: State = rt.state()?;
+
+        let pledge = st.this_epoch_pledge_collateral.atto();
+        let ratio = |denominator: &BigInt| {
+            if denominator.is_positive() {
+                (pledge << PRECISION) / denominator
+            } else {
+                BigInt::zero()
+            }
+        };
+        Ok(GetPledgeRatiosReturn {
+            pledge_per_qa_power: ratio(&st.this_epoch_quality_adj_power),
+            pledge_per_circulating_supply: ratio(rt.total_fil_circ_supply().atto()),
+            this_epoch_pledge_added: st.this_epoch_pledge_added,
+            this_epoch_pledge_released: st.this_epoch_pledge_released,
+        })
+    }
+
+    /// Rebuilds the aggregate power totals and miner counts by folding over all claims,
+    /// repairing any drift from the incrementally maintained values.
+    /// At most `max_claims` claims are folded per invocation; the recomputation resumes
+    /// where it left off on the next invocation, and completes with an event.
+    /// May only be invoked by the system actor.
+    fn recompute_aggregates(
+        rt: &impl Runtime,
+        params: RecomputeAggregatesParams,
+    ) -> Result<RecomputeAggregatesReturn, ActorError> {
+        rt.validate_immediate_caller_is(std::iter::once(&SYSTEM_ACTOR_ADDR))?;
+        if params.max_claims == 0 {
+            return Err(actor_error!(illegal_argument, "max claims must be positive"));
+        }
+
+        let (claims_processed, cursor, totals) = rt.transaction(|st: &mut State, rt| {
+            let (processed, totals) =
+                st.recompute_aggregates(rt.policy(), rt.store(), params.max_claims)?;
+            let cursor = st.aggregate_recompute.as_ref().and_then(|r| r.cursor);
+            Ok((processed, cursor, totals))
+        })?;
+
+        let completed = totals.is_some();
+        if let Some(totals) = totals {
+            emit::aggregates_recomputed(rt, &totals)?;
+        }
+        Ok(RecomputeAggregatesReturn { claims_processed, cursor, completed })
+    }
+
     fn process_batch_proof_verifies(
         rt: &impl Runtime,
         rewret: &ThisEpochRewardReturn,
@@ -534,48 +622,30 @@ impl Actor {
         let mut cron_events = Vec::new();
         let st: State = rt.state()?;
         rt.transaction(|st: &mut State, rt| {
-            let mut events = Multimap::from_root(
-                rt.store(),
-                &st.cron_event_queue,
-                CRON_QUEUE_HAMT_BITWIDTH,
-                CRON_QUEUE_AMT_BITWIDTH,
-            )
-            .map_err(|e| {
-                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load cron events")
+            let mut events =
+                CronEventQueue::new(rt.store(), &st.cron_event_queue).map_err(|e| {
+                    e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load cron events")
+                })?;
+
+            let due_events = events.pop_until(rt_epoch).map_err(|e| {
+                e.downcast_default(
+                    ExitCode::USR_ILLEGAL_STATE,
+                    format!("failed to pop cron events until {}", rt_epoch),
+                )
             })?;
 
             let claims = st.load_claims(rt.store())?;
-            for epoch in st.first_cron_epoch..=rt_epoch {
-                let epoch_events = load_cron_events(&events, epoch).map_err(|e| {
-                    e.downcast_default(
-                        ExitCode::USR_ILLEGAL_STATE,
-                        format!("failed to load cron events at {}", epoch),
-                    )
-                })?;
-
-                if epoch_events.is_empty() {
+            for evt in due_events.into_iter() {
+                let miner_has_claim = claims.contains_key(&evt.miner_addr)?;
+                if !miner_has_claim {
+                    debug!("skipping cron event for unknown miner: {}", evt.miner_addr);
                     continue;
                 }
-
-                for evt in epoch_events.into_iter() {
-                    let miner_has_claim = claims.contains_key(&evt.miner_addr)?;
-                    if !miner_has_claim {
-                        debug!("skipping cron event for unknown miner: {}", evt.miner_addr);
-                        continue;
-                    }
-                    cron_events.push(evt);
-                }
-
-                events.remove_all(&epoch_key(epoch)).map_err(|e| {
-                    e.downcast_default(
-                        ExitCode::USR_ILLEGAL_STATE,
-                        format!("failed to clear cron events at {}", epoch),
-                    )
-                })?;
+                cron_events.push(evt);
             }
 
             st.first_cron_epoch = rt_epoch + 1;
-            st.cron_event_queue = events.root().map_err(|e| {
+            st.cron_event_queue = events.flush().map_err(|e| {
                 e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to flush events")
             })?;
 
@@ -619,7 +689,8 @@ impl Actor {
                         );
                         continue;
                     }
-                    st.miner_count -= 1
+                    st.miner_count -= 1;
+                    emit::claim_forfeited(rt, miner_addr.id().unwrap())?;
                 }
                 st.save_claims(&mut claims)?;
                 Ok(())
@@ -629,6 +700,76 @@ impl Actor {
     }
 }
 
+/// Returns an address of the caller that doesn't change in a re-org: its delegated address if it
+/// has one, or else the public key address of an account.
+fn stable_caller_address(rt: &impl Runtime) -> Result<Address, ActorError> {
+    let caller = rt.message().caller();
+    let caller_id = caller.id().unwrap();
+    if let Some(delegated) = rt.lookup_delegated_address(caller_id) {
+        return Ok(delegated);
+    }
+    let is_account = rt
+        .get_actor_code_cid(&caller_id)
+        .map_or(false, |code| rt.resolve_builtin_actor_type_checked(&code).is_native_account());
+    if !is_account {
+        return Err(actor_error!(
+            forbidden,
+            "caller {} has no re-org stable address to derive a delegated address from",
+            caller
+        ));
+    }
+    deserialize_block(extract_send_result(rt.send_simple(
+        &caller,
+        ext::account::PUBKEY_ADDRESS_METHOD,
+        None,
+        TokenAmount::zero(),
+    ))?)
+}
+
+fn miner_constructor_params(params: &CreateMinerParams) -> Result<RawBytes, ActorError> {
+    Ok(RawBytes::serialize(ext::miner::MinerConstructorParams {
+        owner: params.owner,
+        worker: params.worker,
+        window_post_proof_type: params.window_post_proof_type,
+        peer_id: params.peer.clone(),
+        multi_addresses: params.multiaddrs.clone(),
+        control_addresses: Default::default(),
+    })?)
+}
+
+// Records a zero-power claim for a newly created miner.
+fn register_new_miner(
+    rt: &impl Runtime,
+    id_address: &Address,
+    window_post_proof_type: RegisteredPoStProof,
+) -> Result<(), ActorError> {
+    rt.transaction(|st: &mut State, rt| {
+        let mut claims = st.load_claims(rt.store())?;
+        set_claim(
+            &mut claims,
+            id_address,
+            Claim {
+                window_post_proof_type,
+                quality_adj_power: Default::default(),
+                raw_byte_power: Default::default(),
+            },
+        )?;
+        st.miner_count += 1;
+
+        st.update_stats_for_new_miner(rt.policy(), window_post_proof_type).map_err(|e| {
+            actor_error!(
+                illegal_state,
+                "failed to update power stats for new miner {}: {}",
+                id_address,
+                e
+            )
+        })?;
+
+        st.save_claims(&mut claims)?;
+        Ok(())
+    })
+}
+
 impl ActorCode for Actor {
     type Methods = Method;
 
@@ -636,6 +777,11 @@ impl ActorCode for Actor {
         "StoragePower"
     }
 
+    fil_actors_runtime::actor_info! {
+        interface_revisions: [1],
+        features: [],
+    }
+
     actor_dispatch! {
         Constructor => constructor,
         CreateMiner|CreateMinerExported => create_miner,
@@ -645,9 +791,13 @@ impl ActorCode for Actor {
         UpdatePledgeTotal => update_pledge_total,
         SubmitPoRepForBulkVerify => submit_porep_for_bulk_verify,
         CurrentTotalPower => current_total_power,
+        RecomputeAggregates => recompute_aggregates,
         NetworkRawPowerExported => network_raw_power,
         MinerRawPowerExported => miner_raw_power,
         MinerCountExported => miner_count,
         MinerConsensusCountExported => miner_consensus_count,
+        NetworkStatsExported => network_stats,
+        GetPledgeRatiosExported => get_pledge_ratios,
+        CreateMinerDelegatedExported => create_miner_delegated,
     }
 }
@@ -25,11 +25,11 @@ use fil_actors_runtime::builtin::reward::smooth::{
 };
 use fil_actors_runtime::runtime::Policy;
 use fil_actors_runtime::{
-    actor_error, ActorContext, ActorDowncast, ActorError, AsActorError, Config, Map2, Multimap,
+    actor_error, ActorContext, ActorDowncast, ActorError, AsActorError, Config, Map2,
     DEFAULT_HAMT_CONFIG,
 };
 
-use super::CONSENSUS_MINER_MIN_MINERS;
+use super::{CronEventQueue, CONSENSUS_MINER_MIN_MINERS};
 
 lazy_static! {
     /// genesis power in bytes = 750,000 GiB
@@ -38,8 +38,6 @@ lazy_static! {
     pub static ref INITIAL_QA_POWER_ESTIMATE_VELOCITY: StoragePower = StoragePower::from(3_840) * (1 << 30);
 }
 
-pub const CRON_QUEUE_HAMT_BITWIDTH: u32 = 6;
-pub const CRON_QUEUE_AMT_BITWIDTH: u32 = 6;
 pub const PROOF_VALIDATION_BATCH_AMT_BITWIDTH: u32 = 4;
 
 pub type ClaimsMap<BS> = Map2<BS, Address, Claim>;
@@ -70,7 +68,7 @@ pub struct State {
     pub miner_above_min_power_count: i64,
 
     /// A queue of events to be triggered by cron, indexed by epoch.
-    pub cron_event_queue: Cid, // Multimap, (HAMT[ChainEpoch]AMT[CronEvent]
+    pub cron_event_queue: Cid, // AMT[ChainEpoch]CronEventBatch
 
     /// First epoch in which a cron task may be stored. Cron will iterate every epoch between this
     /// and the current epoch inclusively to find tasks to execute.
@@ -80,16 +78,108 @@ pub struct State {
     pub claims: Cid, // Map, HAMT[address]Claim
 
     pub proof_validation_batch: Option<Cid>,
+
+    /// Progress of an in-flight recomputation of the aggregate power totals from claims, if any.
+    pub aggregate_recompute: Option<AggregateRecompute>,
+
+    /// Pledge added and released by miners since the last cron tick.
+    pub pledge_added: TokenAmount,
+    pub pledge_released: TokenAmount,
+    /// Pledge added and released by miners during the last epoch, recorded at cron.
+    pub this_epoch_pledge_added: TokenAmount,
+    pub this_epoch_pledge_released: TokenAmount,
+
+    /// Changes to the set of miners since the last cron tick.
+    pub miner_changes: MinerChanges,
+}
+
+/// Counts of miners created and of miners crossing the consensus minimum power.
+#[derive(Default, Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct MinerChanges {
+    pub created: u64,
+    pub reached_min_power: u64,
+    pub fell_below_min_power: u64,
+}
+
+/// Aggregate power totals and miner counts, as derived from claims.
+#[derive(Default, Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct PowerTotals {
+    #[serde(with = "bigint_ser")]
+    pub raw_byte_power: StoragePower,
+    #[serde(with = "bigint_ser")]
+    pub bytes_committed: StoragePower,
+    #[serde(with = "bigint_ser")]
+    pub quality_adj_power: StoragePower,
+    #[serde(with = "bigint_ser")]
+    pub qa_bytes_committed: StoragePower,
+    pub miner_count: i64,
+    pub miner_above_min_power_count: i64,
+}
+
+impl PowerTotals {
+    /// Adds a single miner's claim to the totals.
+    fn add_claim(&mut self, policy: &Policy, claim: &Claim) -> anyhow::Result<()> {
+        self.miner_count += 1;
+        self.bytes_committed += &claim.raw_byte_power;
+        self.qa_bytes_committed += &claim.quality_adj_power;
+        let min_power = consensus_miner_min_power(policy, claim.window_post_proof_type)?;
+        if claim.raw_byte_power >= min_power {
+            self.miner_above_min_power_count += 1;
+            self.raw_byte_power += &claim.raw_byte_power;
+            self.quality_adj_power += &claim.quality_adj_power;
+        }
+        Ok(())
+    }
+
+    /// Returns these totals shifted by the change from `from` to `to`.
+    fn rebase(self, from: &PowerTotals, to: &PowerTotals) -> PowerTotals {
+        PowerTotals {
+            raw_byte_power: self.raw_byte_power + &to.raw_byte_power - &from.raw_byte_power,
+            bytes_committed: self.bytes_committed + &to.bytes_committed - &from.bytes_committed,
+            quality_adj_power: self.quality_adj_power + &to.quality_adj_power
+                - &from.quality_adj_power,
+            qa_bytes_committed: self.qa_bytes_committed + &to.qa_bytes_committed
+                - &from.qa_bytes_committed,
+            miner_count: self.miner_count + to.miner_count - from.miner_count,
+            miner_above_min_power_count: self.miner_above_min_power_count
+                + to.miner_above_min_power_count
+                - from.miner_above_min_power_count,
+        }
+    }
+
+    fn is_negative(&self) -> bool {
+        self.raw_byte_power.is_negative()
+            || self.bytes_committed.is_negative()
+            || self.quality_adj_power.is_negative()
+            || self.qa_bytes_committed.is_negative()
+            || self.miner_count < 0
+            || self.miner_above_min_power_count < 0
+    }
+}
+
+/// A recomputation of the aggregate totals that may span many epochs.
+/// Claims are folded from a snapshot of the claims map, so that claims updated while the
+/// recomputation is in flight are accounted for by the incremental updates to the live totals
+/// since the snapshot was taken.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct AggregateRecompute {
+    /// Root of the claims map at the time the recomputation started.
+    pub claims: Cid,
+    /// Key of the next claim to fold, or None if no claims have yet been folded.
+    pub cursor: Option<Address>,
+    /// The recorded totals at the time the recomputation started.
+    pub snapshot_totals: PowerTotals,
+    /// Totals of the claims folded so far.
+    pub folded_totals: PowerTotals,
 }
 
 impl State {
     pub fn new<BS: Blockstore>(store: &BS) -> anyhow::Result<State> {
         let empty_claims = ClaimsMap::empty(store, CLAIMS_CONFIG, "empty").flush()?;
-        let empty_mmap = Multimap::new(store, CRON_QUEUE_HAMT_BITWIDTH, CRON_QUEUE_AMT_BITWIDTH)
-            .root()
-            .context_code(ExitCode::USR_ILLEGAL_STATE, "Failed to get empty multimap cid")?;
+        let empty_cron_queue = CronEventQueue::new_empty(store)
+            .context_code(ExitCode::USR_ILLEGAL_STATE, "Failed to get empty cron queue cid")?;
         Ok(State {
-            cron_event_queue: empty_mmap,
+            cron_event_queue: empty_cron_queue,
             claims: empty_claims,
             this_epoch_qa_power_smoothed: FilterEstimate::new(
                 INITIAL_QA_POWER_ESTIMATE_POSITION.clone(),
@@ -103,6 +193,86 @@ impl State {
         self.total_pledge_collateral
     }
 
+    /// Returns the recorded aggregate power totals and miner counts.
+    pub fn power_totals(&self) -> PowerTotals {
+        PowerTotals {
+            raw_byte_power: self.total_raw_byte_power.clone(),
+            bytes_committed: self.total_bytes_committed.clone(),
+            quality_adj_power: self.total_quality_adj_power.clone(),
+            qa_bytes_committed: self.total_qa_bytes_committed.clone(),
+            miner_count: self.miner_count,
+            miner_above_min_power_count: self.miner_above_min_power_count,
+        }
+    }
+
+    fn set_power_totals(&mut self, totals: PowerTotals) {
+        self.total_raw_byte_power = totals.raw_byte_power;
+        self.total_bytes_committed = totals.bytes_committed;
+        self.total_quality_adj_power = totals.quality_adj_power;
+        self.total_qa_bytes_committed = totals.qa_bytes_committed;
+        self.miner_count = totals.miner_count;
+        self.miner_above_min_power_count = totals.miner_above_min_power_count;
+    }
+
+    /// Folds up to `max_claims` claims into the recomputation of the aggregate totals,
+    /// starting a new recomputation from the current claims if none is in flight.
+    /// Returns the number of claims folded, and the new totals if the recomputation completed.
+    pub(super) fn recompute_aggregates<BS: Blockstore>(
+        &mut self,
+        policy: &Policy,
+        store: &BS,
+        max_claims: u64,
+    ) -> Result<(u64, Option<PowerTotals>), ActorError> {
+        let mut recompute = self.aggregate_recompute.take().unwrap_or_else(|| AggregateRecompute {
+            claims: self.claims,
+            cursor: None,
+            snapshot_totals: self.power_totals(),
+            folded_totals: PowerTotals::default(),
+        });
+
+        // The snapshot isn't modified while the recomputation is in flight, so the cursor is
+        // always present in it.
+        let mut claims =
+            ClaimsMap::load(store, &recompute.claims, CLAIMS_CONFIG, "claims snapshot")?;
+        let (traversed, next) = claims.for_each_ranged(
+            recompute.cursor.as_ref(),
+            Some(max_claims as usize),
+            |miner, claim| {
+                recompute
+                    .folded_totals
+                    .add_claim(policy, claim)
+                    .with_context_code(ExitCode::USR_ILLEGAL_STATE, || {
+                        format!("failed to fold claim for miner {}", miner)
+                    })
+            },
+        )?;
+
+        if next.is_some() {
+            recompute.cursor = next;
+            self.aggregate_recompute = Some(recompute);
+            return Ok((traversed as u64, None));
+        }
+
+        let totals =
+            recompute.folded_totals.rebase(&recompute.snapshot_totals, &self.power_totals());
+        if totals.is_negative() {
+            return Err(actor_error!(
+                illegal_state,
+                "recomputed aggregate totals are negative: {:?}",
+                totals
+            ));
+        }
+        // Miners found above or below the minimum by the recomputation cross it now.
+        let above_min_delta = totals.miner_above_min_power_count - self.miner_above_min_power_count;
+        if above_min_delta > 0 {
+            self.miner_changes.reached_min_power += above_min_delta as u64;
+        } else {
+            self.miner_changes.fell_below_min_power += above_min_delta.unsigned_abs();
+        }
+        self.set_power_totals(totals.clone());
+        Ok((traversed as u64, Some(totals)))
+    }
+
     /// Checks power actor state for if miner meets minimum consensus power.
     pub fn miner_nominal_power_meets_consensus_minimum<BS: Blockstore>(
         &self,
@@ -174,11 +344,13 @@ impl State {
         if prev_below && !still_below {
             // Just passed min miner size
             self.miner_above_min_power_count += 1;
+            self.miner_changes.reached_min_power += 1;
             self.total_quality_adj_power += &new_claim.quality_adj_power;
             self.total_raw_byte_power += &new_claim.raw_byte_power;
         } else if !prev_below && still_below {
             // just went below min miner size
             self.miner_above_min_power_count -= 1;
+            self.miner_changes.fell_below_min_power += 1;
             self.total_quality_adj_power = self
                 .total_quality_adj_power
                 .checked_sub(&old_claim.quality_adj_power)
@@ -231,12 +403,24 @@ impl State {
     }
 
     pub(super) fn add_pledge_total(&mut self, amount: TokenAmount) {
+        if amount.is_positive() {
+            self.pledge_added += &amount;
+        } else {
+            self.pledge_released -= &amount;
+        }
         self.total_pledge_collateral += amount;
     }
 
+    /// Records the pledge added and released since the last cron tick as this epoch's flows,
+    /// and resets the accumulators.
+    pub(super) fn record_pledge_flows(&mut self) {
+        self.this_epoch_pledge_added = std::mem::take(&mut self.pledge_added);
+        self.this_epoch_pledge_released = std::mem::take(&mut self.pledge_released);
+    }
+
     pub(super) fn append_cron_event<BS: Blockstore>(
         &mut self,
-        events: &mut Multimap<BS>,
+        events: &mut CronEventQueue<BS>,
         epoch: ChainEpoch,
         event: CronEvent,
     ) -> anyhow::Result<()> {
@@ -244,7 +428,7 @@ impl State {
             self.first_cron_epoch = epoch;
         }
 
-        events.add(epoch_key(epoch), event).map_err(|e| {
+        events.enqueue(epoch, event).map_err(|e| {
             e.downcast_wrap(format!("failed to store cron event at epoch {}", epoch))
         })?;
         Ok(())
@@ -268,7 +452,7 @@ impl State {
             filter_qa_power.next_estimate(&self.this_epoch_quality_adj_power, delta);
     }
 
-    /// Update stats on new miner creation. This is currently just used to update the miner count
+    /// Update stats on new miner creation. This records the new miner, and updates the miner count
     /// when new added miner starts above the minimum.
     pub(super) fn update_stats_for_new_miner(
         &mut self,
@@ -277,8 +461,10 @@ impl State {
     ) -> anyhow::Result<()> {
         let min_power = consensus_miner_min_power(policy, window_post_proof)?;
 
+        self.miner_changes.created += 1;
         if !min_power.is_positive() {
             self.miner_above_min_power_count += 1;
+            self.miner_changes.reached_min_power += 1;
         }
         Ok(())
     }
@@ -336,20 +522,6 @@ impl State {
     }
 }
 
-pub(super) fn load_cron_events<BS: Blockstore>(
-    mmap: &Multimap<BS>,
-    epoch: ChainEpoch,
-) -> anyhow::Result<Vec<CronEvent>> {
-    let mut events = Vec::new();
-
-    mmap.for_each(&epoch_key(epoch), |_, v: &CronEvent| {
-        events.push(v.clone());
-        Ok(())
-    })?;
-
-    Ok(events)
-}
-
 pub fn set_claim<BS: Blockstore>(
     claims: &mut ClaimsMap<BS>,
     a: &Address,
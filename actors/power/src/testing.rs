@@ -10,12 +10,11 @@ use fvm_shared::{
 };
 use num_traits::{Signed, Zero};
 
-use fil_actors_runtime::{parse_uint_key, runtime::Policy, MessageAccumulator, Multimap};
+use fil_actors_runtime::{runtime::Policy, MessageAccumulator, Multimap};
 
 use crate::{
-    consensus_miner_min_power, Claim, ClaimsMap, CronEvent, State, CLAIMS_CONFIG,
-    CRON_QUEUE_AMT_BITWIDTH, CRON_QUEUE_HAMT_BITWIDTH, MAX_MINER_PROVE_COMMITS_PER_EPOCH,
-    PROOF_VALIDATION_BATCH_AMT_BITWIDTH,
+    consensus_miner_min_power, Claim, ClaimsMap, CronEventQueue, State, CLAIMS_CONFIG,
+    MAX_MINER_PROVE_COMMITS_PER_EPOCH, PROOF_VALIDATION_BATCH_AMT_BITWIDTH,
 };
 
 pub struct MinerCronEvent {
@@ -23,6 +22,12 @@ pub struct MinerCronEvent {
     pub payload: RawBytes,
 }
 
+// Bounds on sector quality, mirroring the miner actor's verified deal weight and duration
+// multipliers.
+const MAX_SECTOR_QUALITY: i64 = 10;
+const MAX_DURATION_MULTIPLIER_NUM: i64 = 3;
+const MAX_DURATION_MULTIPLIER_DENOM: i64 = 2;
+
 type CronEventsByAddress = HashMap<Address, Vec<MinerCronEvent>>;
 type ClaimsByAddress = HashMap<Address, Claim>;
 type ProofsByAddress = HashMap<Address, SealVerifyInfo>;
@@ -102,22 +107,10 @@ fn check_cron_invariants<BS: Blockstore>(
 ) -> CronEventsByAddress {
     let mut cron_events_by_address = CronEventsByAddress::new();
 
-    match Multimap::from_root(
-        store,
-        &state.cron_event_queue,
-        CRON_QUEUE_HAMT_BITWIDTH,
-        CRON_QUEUE_AMT_BITWIDTH,
-    ) {
+    match CronEventQueue::new(store, &state.cron_event_queue) {
         Ok(queue) => {
-            let ret = queue.for_all::<_, CronEvent>(|key, events| {
-                let epoch = match parse_uint_key(key) {
-                    Ok(key) => key,
-                    Err(e) => {
-                        acc.add(format!("non-int key in cron array: {e}"));
-                        return Ok(());
-                    }
-                } as i64;
-
+            let ret = queue.amt.for_each(|epoch, batch| {
+                let epoch = epoch as ChainEpoch;
                 acc.require(
                     epoch >= state.first_cron_epoch,
                     format!(
@@ -125,16 +118,17 @@ fn check_cron_invariants<BS: Blockstore>(
                         state.first_cron_epoch
                     ),
                 );
-                events
-                    .for_each(|_, event| {
-                        cron_events_by_address.entry(event.miner_addr).or_default().push(
-                            MinerCronEvent { epoch, payload: event.callback_payload.clone() },
-                        );
-                        Ok(())
-                    })
-                    .map_err(|e| {
-                        anyhow::anyhow!("error iterating cron events for epoch {}: {}", epoch, e)
-                    })
+                acc.require(
+                    !batch.events.is_empty(),
+                    format!("empty cron event batch at epoch {epoch}"),
+                );
+                for event in &batch.events {
+                    cron_events_by_address
+                        .entry(event.miner_addr)
+                        .or_default()
+                        .push(MinerCronEvent { epoch, payload: event.callback_payload.clone() });
+                }
+                Ok(())
             });
             acc.require_no_error(ret, "error iterating cron tasks");
         }
@@ -164,6 +158,22 @@ fn check_claims_invariants<BS: Blockstore>(
                 committed_raw_power += &claim.raw_byte_power;
                 committed_qa_power += &claim.quality_adj_power;
 
+                // Sector quality ranges from one to that of a sector full of verified deals,
+                // further scaled by the maximum duration multiplier when it's enabled.
+                let mut max_qa_power = &claim.raw_byte_power * MAX_SECTOR_QUALITY;
+                if policy.sector_duration_multiplier_enabled {
+                    max_qa_power =
+                        max_qa_power * MAX_DURATION_MULTIPLIER_NUM / MAX_DURATION_MULTIPLIER_DENOM;
+                }
+                acc.require(
+                    claim.quality_adj_power >= claim.raw_byte_power
+                        && claim.quality_adj_power <= max_qa_power,
+                    format!(
+                        "claim for miner {address} has qa power {} out of range for raw power {}",
+                        claim.quality_adj_power, claim.raw_byte_power
+                    ),
+                );
+
                 let min_power =
                     match consensus_miner_min_power(policy, claim.window_post_proof_type) {
                         Ok(power) => power,
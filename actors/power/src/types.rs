@@ -5,7 +5,7 @@ use fil_actors_runtime::reward::FilterEstimate;
 use fvm_ipld_encoding::tuple::*;
 use fvm_ipld_encoding::{strict_bytes, BytesDe, RawBytes};
 use fvm_shared::address::Address;
-use fvm_shared::bigint::bigint_ser;
+use fvm_shared::bigint::{bigint_ser, BigInt};
 use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::sector::{RegisteredPoStProof, SealVerifyInfo, StoragePower};
@@ -40,6 +40,24 @@ pub struct CreateMinerReturn {
     pub robust_address: Address,
 }
 
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct CreateMinerDelegatedParams {
+    pub miner: CreateMinerParams,
+    /// Salt from which, with the caller's address, the miner's f4 subaddress is derived.
+    #[serde(with = "strict_bytes")]
+    pub salt: Vec<u8>,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct CreateMinerDelegatedReturn {
+    /// Canonical ID-based address for the actor.
+    pub id_address: Address,
+    /// Re-org safe address for created actor.
+    pub robust_address: Address,
+    /// Deterministic f4 address for created actor, in the power actor's namespace.
+    pub delegated_address: Address,
+}
+
 #[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
 pub struct UpdateClaimedPowerParams {
     #[serde(with = "bigint_ser")]
@@ -107,3 +125,53 @@ pub struct MinerCountReturn {
 pub struct MinerConsensusCountReturn {
     pub miner_consensus_count: i64,
 }
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct NetworkStatsReturn {
+    #[serde(with = "bigint_ser")]
+    pub total_raw_byte_power: StoragePower,
+    #[serde(with = "bigint_ser")]
+    pub total_bytes_committed: StoragePower,
+    #[serde(with = "bigint_ser")]
+    pub total_quality_adj_power: StoragePower,
+    #[serde(with = "bigint_ser")]
+    pub total_qa_bytes_committed: StoragePower,
+    pub total_pledge_collateral: TokenAmount,
+    #[serde(with = "bigint_ser")]
+    pub this_epoch_raw_byte_power: StoragePower,
+    #[serde(with = "bigint_ser")]
+    pub this_epoch_quality_adj_power: StoragePower,
+    pub this_epoch_pledge_collateral: TokenAmount,
+    pub this_epoch_qa_power_smoothed: FilterEstimate,
+    pub miner_count: i64,
+    pub miner_above_min_power_count: i64,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct GetPledgeRatiosReturn {
+    /// Pledge collateral per byte of quality-adjusted power, in Q.128.
+    #[serde(with = "bigint_ser")]
+    pub pledge_per_qa_power: BigInt,
+    /// Pledge collateral as a fraction of the circulating supply, in Q.128.
+    #[serde(with = "bigint_ser")]
+    pub pledge_per_circulating_supply: BigInt,
+    pub this_epoch_pledge_added: TokenAmount,
+    pub this_epoch_pledge_released: TokenAmount,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct RecomputeAggregatesParams {
+    /// Maximum number of claims to fold in this invocation.
+    pub max_claims: u64,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Eq, PartialEq)]
+pub struct RecomputeAggregatesReturn {
+    /// Number of claims folded in this invocation.
+    pub claims_processed: u64,
+    /// Key of the next claim to fold, if the recomputation is still in flight.
+    pub cursor: Option<Address>,
+    /// Whether the recomputation completed and the aggregate totals were replaced.
+    pub completed: bool,
+}
@@ -0,0 +1,109 @@
+use fil_actor_power::{epoch_key, CronEvent, CronEventQueue};
+use fil_actors_runtime::test_blockstores::MemoryBlockstore;
+use fil_actors_runtime::Multimap;
+use fvm_ipld_encoding::RawBytes;
+use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
+
+// Bit widths of the HAMT[ChainEpoch]AMT[CronEvent] multimap previously used as the queue.
+const LEGACY_HAMT_BITWIDTH: u32 = 6;
+const LEGACY_AMT_BITWIDTH: u32 = 6;
+
+fn event(miner: u64, payload: u8) -> CronEvent {
+    CronEvent { miner_addr: Address::new_id(miner), callback_payload: RawBytes::new(vec![payload]) }
+}
+
+// Events enrolled out of epoch order, with several miners sharing an epoch.
+fn enrollments() -> Vec<(ChainEpoch, CronEvent)> {
+    vec![
+        (10, event(101, 1)),
+        (4, event(102, 2)),
+        (10, event(103, 3)),
+        (7, event(101, 4)),
+        (4, event(104, 5)),
+        (2880, event(105, 6)),
+        (10, event(102, 7)),
+        (11, event(101, 8)),
+    ]
+}
+
+fn legacy_pop(
+    mmap: &mut Multimap<MemoryBlockstore>,
+    from: ChainEpoch,
+    until: ChainEpoch,
+) -> Vec<CronEvent> {
+    let mut events = Vec::new();
+    for epoch in from..=until {
+        let count = events.len();
+        mmap.for_each(&epoch_key(epoch), |_, v: &CronEvent| {
+            events.push(v.clone());
+            Ok(())
+        })
+        .unwrap();
+        // Removing a key that was never enrolled is an error.
+        if events.len() > count {
+            mmap.remove_all(&epoch_key(epoch)).unwrap();
+        }
+    }
+    events
+}
+
+#[test]
+fn pops_events_in_legacy_order() {
+    let store = MemoryBlockstore::new();
+    let root = CronEventQueue::new_empty(&store).unwrap();
+    let mut queue = CronEventQueue::new(&store, &root).unwrap();
+
+    let legacy_store = MemoryBlockstore::new();
+    let mut legacy = Multimap::new(&legacy_store, LEGACY_HAMT_BITWIDTH, LEGACY_AMT_BITWIDTH);
+
+    for (epoch, evt) in enrollments() {
+        queue.enqueue(epoch, evt.clone()).unwrap();
+        legacy.add(epoch_key(epoch), evt).unwrap();
+    }
+
+    let mut first_epoch = 0;
+    for until in [3, 4, 10, 100, 3000] {
+        let popped = queue.pop_until(until).unwrap();
+        let expected = legacy_pop(&mut legacy, first_epoch, until);
+        assert_eq!(expected.len(), popped.len(), "popped count differs until {}", until);
+        for (e, p) in expected.iter().zip(popped.iter()) {
+            assert_eq!(e.miner_addr, p.miner_addr);
+            assert_eq!(e.callback_payload, p.callback_payload);
+        }
+        first_epoch = until + 1;
+    }
+    assert_eq!(0, queue.amt.count());
+}
+
+#[test]
+fn queue_survives_flush_and_reload() {
+    let store = MemoryBlockstore::new();
+    let mut root = CronEventQueue::new_empty(&store).unwrap();
+
+    // Each enrollment is flushed, as when enrolled in separate messages.
+    for (epoch, evt) in enrollments() {
+        let mut queue = CronEventQueue::new(&store, &root).unwrap();
+        queue.enqueue(epoch, evt).unwrap();
+        root = queue.flush().unwrap();
+    }
+
+    let mut queue = CronEventQueue::new(&store, &root).unwrap();
+    // One batch per distinct epoch.
+    assert_eq!(5, queue.amt.count());
+    let popped = queue.pop_until(10).unwrap();
+    let payloads: Vec<_> = popped.iter().map(|e| e.callback_payload.to_vec()).collect();
+    assert_eq!(vec![vec![2], vec![5], vec![4], vec![1], vec![3], vec![7]], payloads);
+    root = queue.flush().unwrap();
+
+    let queue = CronEventQueue::new(&store, &root).unwrap();
+    assert_eq!(2, queue.amt.count());
+}
+
+#[test]
+fn rejects_negative_epoch() {
+    let store = MemoryBlockstore::new();
+    let root = CronEventQueue::new_empty(&store).unwrap();
+    let mut queue = CronEventQueue::new(&store, &root).unwrap();
+    assert!(queue.enqueue(-1, event(101, 1)).is_err());
+}
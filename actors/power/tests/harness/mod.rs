@@ -27,15 +27,14 @@ use fil_actor_power::ext::miner::CONFIRM_SECTOR_PROOFS_VALID_METHOD;
 use fil_actor_power::ext::reward::Method::ThisEpochReward;
 use fil_actor_power::ext::reward::UPDATE_NETWORK_KPI;
 use fil_actor_power::testing::check_state_invariants;
-use fil_actor_power::EnrollCronEventParams;
-use fil_actor_power::CRON_QUEUE_AMT_BITWIDTH;
-use fil_actor_power::CRON_QUEUE_HAMT_BITWIDTH;
-use fil_actor_power::{epoch_key, MinerCountReturn};
 use fil_actor_power::{
     ext, Claim, CreateMinerParams, CreateMinerReturn, CurrentTotalPowerReturn, Method, State,
     UpdateClaimedPowerParams,
 };
-use fil_actor_power::{CronEvent, MinerConsensusCountReturn};
+use fil_actor_power::{CronEvent, MinerChanges, MinerConsensusCountReturn};
+use fil_actor_power::{CronEventQueue, MinerCountReturn};
+use fil_actor_power::{EnrollCronEventParams, GetPledgeRatiosReturn};
+use fil_actor_power::{PowerTotals, RecomputeAggregatesParams, RecomputeAggregatesReturn};
 use fil_actors_runtime::builtin::reward::{FilterEstimate, ThisEpochRewardReturn};
 use fil_actors_runtime::runtime::builtins::Type;
 use fil_actors_runtime::runtime::Runtime;
@@ -46,9 +45,9 @@ use fil_actors_runtime::test_utils::{
 };
 use fil_actors_runtime::REWARD_ACTOR_ADDR;
 use fil_actors_runtime::{
-    ActorError, INIT_ACTOR_ADDR, STORAGE_POWER_ACTOR_ADDR, SYSTEM_ACTOR_ADDR,
+    ActorError, EventBuilder, INIT_ACTOR_ADDR, STORAGE_POWER_ACTOR_ADDR, SYSTEM_ACTOR_ADDR,
 };
-use fil_actors_runtime::{Map2, MapKey, Multimap};
+use fil_actors_runtime::{Map2, MapKey};
 use fil_actors_runtime::{CRON_ACTOR_ADDR, DEFAULT_HAMT_CONFIG};
 
 use crate::PowerActor;
@@ -119,7 +118,7 @@ impl Harness {
         assert_eq!(0, st.miner_above_min_power_count);
 
         verify_empty_map(rt, st.claims);
-        verify_empty_map(rt, st.cron_event_queue);
+        verify_empty_queue(rt, st.cron_event_queue);
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -257,23 +256,10 @@ impl Harness {
 
     pub fn get_enrolled_cron_ticks(&self, rt: &MockRuntime, epoch: ChainEpoch) -> Vec<CronEvent> {
         let state: State = rt.get_state();
-        let events_map = Multimap::from_root(
-            &rt.store,
-            &state.cron_event_queue,
-            CRON_QUEUE_HAMT_BITWIDTH,
-            CRON_QUEUE_AMT_BITWIDTH,
-        )
-        .expect("failed to load cron events");
-
-        let mut events: Vec<CronEvent> = Vec::new();
-        events_map
-            .for_each::<_, CronEvent>(&epoch_key(epoch), |_, v| {
-                events.push(v.to_owned());
-                Ok(())
-            })
-            .unwrap();
+        let queue = CronEventQueue::new(&rt.store, &state.cron_event_queue)
+            .expect("failed to load cron events");
 
-        events
+        queue.amt.get(epoch as u64).unwrap().map(|batch| batch.events.clone()).unwrap_or_default()
     }
 
     pub fn check_state(&self, rt: &MockRuntime) {
@@ -378,6 +364,62 @@ impl Harness {
         assert_eq!(count, ret.miner_consensus_count);
     }
 
+    pub fn get_pledge_ratios(&self, rt: &MockRuntime) -> GetPledgeRatiosReturn {
+        rt.expect_validate_caller_any();
+        let ret = rt
+            .call::<PowerActor>(Method::GetPledgeRatiosExported as MethodNum, None)
+            .unwrap()
+            .unwrap()
+            .deserialize()
+            .unwrap();
+        rt.verify();
+        ret
+    }
+
+    /// Folds up to `max_claims` claims into the aggregate recomputation.
+    /// If `expect_completed` is provided, expects the recomputation to complete with those totals.
+    pub fn recompute_aggregates(
+        &self,
+        rt: &MockRuntime,
+        max_claims: u64,
+        expect_completed: Option<&PowerTotals>,
+    ) -> RecomputeAggregatesReturn {
+        rt.set_caller(*SYSTEM_ACTOR_CODE_ID, SYSTEM_ACTOR_ADDR);
+        rt.expect_validate_caller_addr(vec![SYSTEM_ACTOR_ADDR]);
+        if let Some(totals) = expect_completed {
+            rt.expect_emitted_event(
+                EventBuilder::new()
+                    .typ("power-aggregates-recomputed")
+                    .field("raw-byte-power", &BigIntSer(&totals.raw_byte_power))
+                    .field("quality-adj-power", &BigIntSer(&totals.quality_adj_power))
+                    .field("miner-count", &totals.miner_count)
+                    .field("miner-above-min-power-count", &totals.miner_above_min_power_count)
+                    .build()
+                    .unwrap(),
+            );
+        }
+        let ret: RecomputeAggregatesReturn = rt
+            .call::<PowerActor>(
+                Method::RecomputeAggregates as MethodNum,
+                IpldBlock::serialize_cbor(&RecomputeAggregatesParams { max_claims }).unwrap(),
+            )
+            .unwrap()
+            .unwrap()
+            .deserialize()
+            .unwrap();
+        rt.verify();
+
+        assert_eq!(expect_completed.is_some(), ret.completed);
+        let st: State = rt.get_state();
+        if let Some(totals) = expect_completed {
+            assert_eq!(totals, &st.power_totals());
+            assert!(st.aggregate_recompute.is_none());
+        } else {
+            assert_eq!(ret.cursor, st.aggregate_recompute.unwrap().cursor);
+        }
+        ret
+    }
+
     pub fn expect_query_network_info(&self, rt: &MockRuntime) {
         let current_reward = ThisEpochRewardReturn {
             this_epoch_baseline_power: self.this_epoch_baseline_power.clone(),
@@ -394,6 +436,38 @@ impl Harness {
         );
     }
 
+    pub fn expect_epoch_power_summary(
+        &self,
+        rt: &MockRuntime,
+        raw_byte_delta: &StoragePower,
+        qa_delta: &StoragePower,
+        changes: &MinerChanges,
+    ) {
+        rt.expect_emitted_event(
+            EventBuilder::new()
+                .typ("power-epoch-summary")
+                .field("raw-byte-power-delta", &BigIntSer(raw_byte_delta))
+                .field("quality-adj-power-delta", &BigIntSer(qa_delta))
+                .field("new-miners", &changes.created)
+                .field("miners-reached-min-power", &changes.reached_min_power)
+                .field("miners-fell-below-min-power", &changes.fell_below_min_power)
+                .build()
+                .unwrap(),
+        );
+    }
+
+    /// Expects the power summary event for a cron tick that doesn't change any claims.
+    pub fn expect_pending_epoch_power_summary(&self, rt: &MockRuntime) {
+        let st: State = rt.get_state();
+        let (raw_byte_power, qa_power) = st.current_total_power();
+        self.expect_epoch_power_summary(
+            rt,
+            &(raw_byte_power - &st.this_epoch_raw_byte_power),
+            &(qa_power - &st.this_epoch_quality_adj_power),
+            &st.miner_changes,
+        );
+    }
+
     pub fn on_epoch_tick_end(
         &self,
         rt: &MockRuntime,
@@ -403,6 +477,7 @@ impl Harness {
         infos: Vec<SealVerifyInfo>,
     ) {
         self.expect_query_network_info(rt);
+        self.expect_pending_epoch_power_summary(rt);
 
         let state: State = rt.get_state();
 
@@ -493,6 +568,11 @@ where
     Ok(ret_keys)
 }
 
+pub fn verify_empty_queue(rt: &MockRuntime, root: Cid) {
+    let queue = CronEventQueue::new(&rt.store, &root).unwrap();
+    assert_eq!(0, queue.amt.count(), "expected no cron events");
+}
+
 pub fn verify_empty_map(rt: &MockRuntime, key: Cid) {
     let map =
         Map2::<_, Vec<u8>, Vec<u8>>::load(&rt.store, &key, DEFAULT_HAMT_CONFIG, "empty?").unwrap();
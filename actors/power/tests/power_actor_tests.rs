@@ -1,11 +1,16 @@
-use fil_actor_power::ext::init::{ExecParams, EXEC_METHOD};
+use fil_actor_power::ext::account::PUBKEY_ADDRESS_METHOD;
+use fil_actor_power::ext::init::{
+    ExecDelegatedParams, ExecDelegatedReturn, ExecParams, EXEC_DELEGATED_METHOD, EXEC_METHOD,
+};
 use fil_actor_power::ext::miner::MinerConstructorParams;
 use fil_actors_runtime::runtime::builtins::Type;
+use fil_actors_runtime::runtime::Primitives;
 use fil_actors_runtime::test_utils::{
-    expect_abort, expect_abort_contains_message, ACCOUNT_ACTOR_CODE_ID, EVM_ACTOR_CODE_ID,
-    MINER_ACTOR_CODE_ID, SYSTEM_ACTOR_CODE_ID,
+    expect_abort, expect_abort_contains_message, make_identity_cid, MockRuntime,
+    ACCOUNT_ACTOR_CODE_ID, EVM_ACTOR_CODE_ID, MINER_ACTOR_CODE_ID, MULTISIG_ACTOR_CODE_ID,
+    SYSTEM_ACTOR_CODE_ID,
 };
-use fil_actors_runtime::{runtime::Policy, INIT_ACTOR_ADDR};
+use fil_actors_runtime::{runtime::Policy, INIT_ACTOR_ADDR, STORAGE_POWER_ACTOR_ID};
 use fvm_ipld_encoding::{BytesDe, RawBytes};
 use fvm_shared::address::Address;
 use fvm_shared::bigint::bigint_ser::BigIntSer;
@@ -18,9 +23,10 @@ use num_traits::Zero;
 use std::ops::Neg;
 
 use fil_actor_power::{
-    consensus_miner_min_power, Actor as PowerActor, Actor, CreateMinerParams, CreateMinerReturn,
-    EnrollCronEventParams, Method, MinerRawPowerParams, MinerRawPowerReturn, NetworkRawPowerReturn,
-    State, UpdateClaimedPowerParams, CONSENSUS_MINER_MIN_MINERS,
+    consensus_miner_min_power, Actor as PowerActor, Actor, CreateMinerDelegatedParams,
+    CreateMinerDelegatedReturn, CreateMinerParams, CreateMinerReturn, EnrollCronEventParams,
+    Method, MinerChanges, MinerRawPowerParams, MinerRawPowerReturn, NetworkRawPowerReturn,
+    NetworkStatsReturn, State, UpdateClaimedPowerParams, CONSENSUS_MINER_MIN_MINERS,
 };
 
 use fvm_ipld_encoding::ipld_block::IpldBlock;
@@ -75,7 +81,161 @@ fn create_miner() {
     assert_eq!(TokenAmount::zero(), st.total_pledge_collateral);
     assert_eq!(0, st.miner_above_min_power_count);
 
-    verify_empty_map(&rt, st.cron_event_queue);
+    verify_empty_queue(&rt, st.cron_event_queue);
+    h.check_state(&rt);
+}
+
+fn create_miner_delegated_params() -> CreateMinerDelegatedParams {
+    CreateMinerDelegatedParams {
+        miner: CreateMinerParams {
+            owner: *OWNER,
+            worker: *OWNER,
+            window_post_proof_type: RegisteredPoStProof::StackedDRGWindow32GiBV1P1,
+            peer: "miner".as_bytes().to_vec(),
+            multiaddrs: vec![BytesDe("multiaddr".as_bytes().to_vec())],
+        },
+        salt: b"salt".to_vec(),
+    }
+}
+
+// Expects the miner to be created with a subaddress committing to the caller's stable address
+// and the salt, and returns the miner's delegated address.
+fn expect_exec_delegated(
+    rt: &MockRuntime,
+    params: &CreateMinerDelegatedParams,
+    caller: &Address,
+) -> Address {
+    let subaddress = rt.hash_blake2b(&[caller.to_bytes(), params.salt.clone()].concat());
+    let delegated_address = Address::new_delegated(STORAGE_POWER_ACTOR_ID, &subaddress).unwrap();
+    rt.expect_send_simple(
+        INIT_ACTOR_ADDR,
+        EXEC_DELEGATED_METHOD,
+        IpldBlock::serialize_cbor(&ExecDelegatedParams {
+            code_cid: *MINER_ACTOR_CODE_ID,
+            constructor_params: RawBytes::serialize(MinerConstructorParams {
+                owner: *OWNER,
+                worker: *OWNER,
+                window_post_proof_type: RegisteredPoStProof::StackedDRGWindow32GiBV1P1,
+                peer_id: params.miner.peer.clone(),
+                multi_addresses: params.miner.multiaddrs.clone(),
+                control_addresses: Default::default(),
+            })
+            .unwrap(),
+            subaddress: subaddress.to_vec().into(),
+        })
+        .unwrap(),
+        TokenAmount::from_atto(10),
+        IpldBlock::serialize_cbor(&ExecDelegatedReturn {
+            id_address: *MINER,
+            robust_address: *ACTOR,
+            delegated_address,
+        })
+        .unwrap(),
+        ExitCode::OK,
+    );
+    delegated_address
+}
+
+#[test]
+fn create_miner_delegated() {
+    let (h, rt) = setup();
+
+    let params = create_miner_delegated_params();
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, *OWNER);
+    rt.set_received(TokenAmount::from_atto(10));
+    rt.set_balance(TokenAmount::from_atto(10));
+    rt.expect_validate_caller_any();
+
+    // The subaddress commits to the account's public key, rather than its ID.
+    let pubkey = Address::new_secp256k1(&[7; 65]).unwrap();
+    rt.expect_send_simple(
+        *OWNER,
+        PUBKEY_ADDRESS_METHOD,
+        None,
+        TokenAmount::zero(),
+        IpldBlock::serialize_cbor(&pubkey).unwrap(),
+        ExitCode::OK,
+    );
+    let delegated_address = expect_exec_delegated(&rt, &params, &pubkey);
+
+    let ret: CreateMinerDelegatedReturn = rt
+        .call::<PowerActor>(
+            Method::CreateMinerDelegatedExported as MethodNum,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        )
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    assert_eq!(
+        CreateMinerDelegatedReturn {
+            id_address: *MINER,
+            robust_address: *ACTOR,
+            delegated_address
+        },
+        ret
+    );
+
+    // The miner is registered as usual.
+    let st: State = rt.get_state();
+    let claim = h.get_claim(&rt, &MINER).unwrap();
+    assert_eq!(RegisteredPoStProof::StackedDRGWindow32GiBV1P1, claim.window_post_proof_type);
+    assert_eq!(1, st.miner_count);
+    h.check_state(&rt);
+}
+
+#[test]
+fn create_miner_delegated_from_delegated_caller() {
+    let (h, rt) = setup();
+
+    // A contract's subaddress commits to its own f4 address.
+    let params = create_miner_delegated_params();
+    let caller = Address::new_id(1234);
+    let caller_f4 = Address::new_delegated(10, &[0xab; 20]).unwrap();
+    rt.set_caller(*EVM_ACTOR_CODE_ID, caller);
+    rt.set_delegated_address(caller.id().unwrap(), caller_f4);
+    rt.set_received(TokenAmount::from_atto(10));
+    rt.set_balance(TokenAmount::from_atto(10));
+    rt.expect_validate_caller_any();
+    let delegated_address = expect_exec_delegated(&rt, &params, &caller_f4);
+
+    let ret: CreateMinerDelegatedReturn = rt
+        .call::<PowerActor>(
+            Method::CreateMinerDelegatedExported as MethodNum,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        )
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+    assert_eq!(delegated_address, ret.delegated_address);
+    h.check_state(&rt);
+}
+
+#[test]
+fn create_miner_delegated_requires_stable_caller_address() {
+    let (h, rt) = setup();
+
+    // A multisig, or an actor of unknown code, has no address other than its ID from which to
+    // derive the subaddress.
+    let params = create_miner_delegated_params();
+    for code in [*MULTISIG_ACTOR_CODE_ID, make_identity_cid(b"unknown")] {
+        rt.set_caller(code, Address::new_id(1234));
+        rt.set_received(TokenAmount::from_atto(10));
+        rt.set_balance(TokenAmount::from_atto(10));
+        rt.expect_validate_caller_any();
+        expect_abort_contains_message(
+            ExitCode::USR_FORBIDDEN,
+            "no re-org stable address",
+            rt.call::<PowerActor>(
+                Method::CreateMinerDelegatedExported as MethodNum,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            ),
+        );
+        rt.verify();
+    }
     h.check_state(&rt);
 }
 
@@ -611,6 +771,61 @@ fn get_network_and_miner_power() {
     h.check_state(&rt);
 }
 
+#[test]
+fn get_network_stats() {
+    let power_unit = &consensus_miner_min_power(
+        &Policy::default(),
+        RegisteredPoStProof::StackedDRGWindow32GiBV1P1,
+    )
+    .unwrap();
+    let pledge = TokenAmount::from_atto(1_000_000);
+
+    let (mut h, rt) = setup();
+
+    h.create_miner_basic(&rt, *OWNER, *OWNER, MINER1).unwrap();
+    h.create_miner_basic(&rt, *OWNER, *OWNER, MINER2).unwrap();
+    h.update_claimed_power(&rt, MINER1, power_unit, &(power_unit * 2));
+    h.update_pledge_total(&rt, MINER1, &pledge);
+
+    // manually update state in lieu of cron running
+    let mut state: State = rt.get_state();
+    state.this_epoch_raw_byte_power = power_unit.clone();
+    state.this_epoch_quality_adj_power = power_unit * 2;
+    state.this_epoch_pledge_collateral = pledge.clone();
+    rt.replace_state(&state);
+
+    // set caller to not-builtin
+    rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(1234));
+
+    rt.expect_validate_caller_any();
+    let stats: NetworkStatsReturn = rt
+        .call::<Actor>(Method::NetworkStatsExported as u64, None)
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    rt.verify();
+
+    assert_eq!(
+        NetworkStatsReturn {
+            total_raw_byte_power: power_unit.clone(),
+            total_bytes_committed: power_unit.clone(),
+            total_quality_adj_power: power_unit * 2,
+            total_qa_bytes_committed: power_unit * 2,
+            total_pledge_collateral: pledge.clone(),
+            this_epoch_raw_byte_power: power_unit.clone(),
+            this_epoch_quality_adj_power: power_unit * 2,
+            this_epoch_pledge_collateral: pledge,
+            this_epoch_qa_power_smoothed: state.this_epoch_qa_power_smoothed,
+            miner_count: 2,
+            miner_above_min_power_count: 1,
+        },
+        stats
+    );
+
+    h.check_state(&rt);
+}
+
 #[test]
 fn given_no_miner_claim_update_pledge_total_should_abort() {
     let (mut h, rt) = setup();
@@ -644,7 +859,11 @@ mod cron_tests {
         miner::{DeferredCronEventParams, ON_DEFERRED_CRON_EVENT_METHOD},
         reward::UPDATE_NETWORK_KPI,
     };
-    use fil_actors_runtime::{test_utils::CRON_ACTOR_CODE_ID, CRON_ACTOR_ADDR, REWARD_ACTOR_ADDR};
+    use fil_actor_power::GetPledgeRatiosReturn;
+    use fil_actors_runtime::reward::math::PRECISION;
+    use fil_actors_runtime::{
+        test_utils::CRON_ACTOR_CODE_ID, EventBuilder, CRON_ACTOR_ADDR, REWARD_ACTOR_ADDR,
+    };
     use fvm_shared::bigint::BigInt;
 
     const OWNER: Address = Address::new_id(103);
@@ -670,6 +889,8 @@ mod cron_tests {
         rt.set_caller(*CRON_ACTOR_CODE_ID, CRON_ACTOR_ADDR);
         rt.expect_batch_verify_seals(Vec::new(), Ok(Vec::new()));
 
+        h.expect_pending_epoch_power_summary(&rt);
+
         rt.call::<PowerActor>(Method::OnEpochTickEnd as u64, None).unwrap();
 
         rt.verify();
@@ -716,6 +937,115 @@ mod cron_tests {
         h.check_state(&rt);
     }
 
+    #[test]
+    fn pledge_ratios_and_flows_recorded_at_cron() {
+        let (mut h, rt) = setup();
+        let power_unit = consensus_miner_min_power(
+            &Policy::default(),
+            RegisteredPoStProof::StackedDRGWindow2KiBV1P1,
+        )
+        .unwrap();
+
+        let miners: Vec<_> = (101..105).map(Address::new_id).collect();
+        for miner in &miners {
+            h.create_miner_basic(&rt, OWNER, OWNER, *miner).unwrap();
+            h.update_claimed_power(&rt, *miner, &power_unit, &power_unit);
+        }
+        let expected_power: BigInt = power_unit * 4u8;
+
+        h.update_pledge_total(&rt, miners[0], &TokenAmount::from_atto(100));
+        h.update_pledge_total(&rt, miners[1], &TokenAmount::from_atto(50));
+        h.update_pledge_total(&rt, miners[2], &TokenAmount::from_atto(-30));
+        h.on_epoch_tick_end(&rt, 0, &expected_power, Vec::new(), Vec::new());
+
+        let circ_supply = TokenAmount::from_atto(1_000_000);
+        rt.set_circulating_supply(circ_supply.clone());
+        let ratios = h.get_pledge_ratios(&rt);
+        assert_eq!(
+            GetPledgeRatiosReturn {
+                pledge_per_qa_power: (BigInt::from(120) << PRECISION) / &expected_power,
+                pledge_per_circulating_supply: (BigInt::from(120) << PRECISION)
+                    / circ_supply.atto(),
+                this_epoch_pledge_added: TokenAmount::from_atto(150),
+                this_epoch_pledge_released: TokenAmount::from_atto(30),
+            },
+            ratios
+        );
+
+        // Flows are reset each epoch, while the ratios carry over.
+        h.on_epoch_tick_end(&rt, 1, &expected_power, Vec::new(), Vec::new());
+        let next = h.get_pledge_ratios(&rt);
+        assert_eq!(ratios.pledge_per_qa_power, next.pledge_per_qa_power);
+        assert!(next.this_epoch_pledge_added.is_zero());
+        assert!(next.this_epoch_pledge_released.is_zero());
+
+        // Ratios are zero without circulating supply.
+        rt.set_circulating_supply(TokenAmount::zero());
+        assert!(h.get_pledge_ratios(&rt).pledge_per_circulating_supply.is_zero());
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn power_summary_recorded_at_cron() {
+        let (mut h, rt) = setup();
+        let power_unit = consensus_miner_min_power(
+            &Policy::default(),
+            RegisteredPoStProof::StackedDRGWindow2KiBV1P1,
+        )
+        .unwrap();
+
+        let miners: Vec<_> = (101..105).map(Address::new_id).collect();
+        for miner in &miners {
+            h.create_miner_basic(&rt, OWNER, OWNER, *miner).unwrap();
+            h.update_claimed_power(&rt, *miner, &power_unit, &(&power_unit * 2));
+        }
+        let st: State = rt.get_state();
+        assert_eq!(
+            MinerChanges { created: 4, reached_min_power: 4, fell_below_min_power: 0 },
+            st.miner_changes
+        );
+
+        let expected_power: BigInt = &power_unit * 4u8;
+        h.expect_epoch_power_summary(
+            &rt,
+            &expected_power,
+            &(&expected_power * 2),
+            &st.miner_changes,
+        );
+        h.expect_query_network_info(&rt);
+        rt.expect_batch_verify_seals(Vec::new(), Ok(Vec::new()));
+        rt.expect_send_simple(
+            REWARD_ACTOR_ADDR,
+            UPDATE_NETWORK_KPI,
+            IpldBlock::serialize_cbor(&BigIntSer(&expected_power)).unwrap(),
+            TokenAmount::zero(),
+            None,
+            ExitCode::OK,
+        );
+        rt.expect_validate_caller_addr(vec![CRON_ACTOR_ADDR]);
+        rt.set_caller(*CRON_ACTOR_CODE_ID, CRON_ACTOR_ADDR);
+        rt.call::<PowerActor>(Method::OnEpochTickEnd as u64, None).unwrap();
+        rt.verify();
+
+        // The changes are reset each epoch.
+        let st: State = rt.get_state();
+        assert_eq!(MinerChanges::default(), st.miner_changes);
+
+        // One miner dropping below the minimum brings the network below the minimum number of
+        // miners, so the power of all miners counts again.
+        h.update_claimed_power(&rt, miners[0], &power_unit.clone().neg(), &(&power_unit * 2).neg());
+        let st: State = rt.get_state();
+        assert_eq!(
+            MinerChanges { created: 0, reached_min_power: 0, fell_below_min_power: 1 },
+            st.miner_changes
+        );
+        let (raw_byte_power, qa_power) = st.current_total_power();
+        assert_eq!(&power_unit * 3u8, raw_byte_power);
+        assert_eq!(&power_unit * 6u8, qa_power);
+        h.on_epoch_tick_end(&rt, 1, &raw_byte_power, Vec::new(), Vec::new());
+        h.check_state(&rt);
+    }
+
     #[test]
     fn event_scheduled_in_null_round_called_next_round() {
         let (mut h, rt) = setup();
@@ -780,6 +1110,7 @@ mod cron_tests {
         );
         rt.set_caller(*CRON_ACTOR_CODE_ID, CRON_ACTOR_ADDR);
         rt.expect_batch_verify_seals(Vec::new(), Ok(Vec::new()));
+        h.expect_pending_epoch_power_summary(&rt);
         rt.call::<PowerActor>(Method::OnEpochTickEnd as u64, None).unwrap();
 
         rt.verify();
@@ -810,6 +1141,8 @@ mod cron_tests {
 
         rt.expect_batch_verify_seals(Vec::new(), Ok(Vec::new()));
 
+        h.expect_pending_epoch_power_summary(&rt);
+
         rt.call::<PowerActor>(Method::OnEpochTickEnd as u64, None).unwrap();
         rt.verify();
 
@@ -848,13 +1181,15 @@ mod cron_tests {
         rt.set_caller(*CRON_ACTOR_CODE_ID, CRON_ACTOR_ADDR);
         rt.expect_batch_verify_seals(Vec::new(), Ok(Vec::new()));
 
+        h.expect_pending_epoch_power_summary(&rt);
+
         rt.call::<PowerActor>(Method::OnEpochTickEnd as u64, None).unwrap();
         rt.verify();
 
         // assert used cron events are cleaned up
         let state: State = rt.get_state();
 
-        verify_empty_map(&rt, state.cron_event_queue);
+        verify_empty_queue(&rt, state.cron_event_queue);
         h.check_state(&rt);
     }
 
@@ -923,6 +1258,7 @@ mod cron_tests {
             ExitCode::OK,
         );
         rt.set_caller(*CRON_ACTOR_CODE_ID, CRON_ACTOR_ADDR);
+        h.expect_pending_epoch_power_summary(&rt);
         rt.call::<PowerActor>(Method::OnEpochTickEnd as u64, None).unwrap();
         rt.verify();
 
@@ -999,6 +1335,15 @@ mod cron_tests {
             None,
             ExitCode::OK,
         );
+        // the failed miner forfeits its claim
+        rt.expect_emitted_event(
+            EventBuilder::new()
+                .typ("miner-claim-forfeited")
+                .field_indexed("miner", &miner1.id().unwrap())
+                .build()
+                .unwrap(),
+        );
+        h.expect_pending_epoch_power_summary(&rt);
         rt.call::<PowerActor>(Method::OnEpochTickEnd as u64, None).unwrap();
         rt.verify();
 
@@ -1029,6 +1374,8 @@ mod cron_tests {
         rt.set_caller(*CRON_ACTOR_CODE_ID, CRON_ACTOR_ADDR);
         rt.expect_batch_verify_seals(Vec::new(), Ok(Vec::new()));
 
+        h.expect_pending_epoch_power_summary(&rt);
+
         rt.call::<PowerActor>(Method::OnEpochTickEnd as u64, None).unwrap();
         rt.verify();
         h.check_state(&rt);
@@ -1278,6 +1625,8 @@ mod cron_batch_proof_verifies_tests {
         rt.set_epoch(0);
         rt.set_caller(*CRON_ACTOR_CODE_ID, CRON_ACTOR_ADDR);
 
+        h.expect_pending_epoch_power_summary(&rt);
+
         rt.call::<PowerActor>(Method::OnEpochTickEnd as u64, None).unwrap();
 
         rt.verify();
@@ -1311,6 +1660,8 @@ mod cron_batch_proof_verifies_tests {
         rt.set_epoch(0);
         rt.set_caller(*CRON_ACTOR_CODE_ID, CRON_ACTOR_ADDR);
 
+        h.expect_pending_epoch_power_summary(&rt);
+
         rt.call::<PowerActor>(Method::OnEpochTickEnd as u64, None).unwrap();
         rt.verify();
         h.check_state(&rt);
@@ -1503,3 +1854,161 @@ fn create_miner_restricted_correctly() {
 
     h.check_state(&rt);
 }
+
+mod recompute_aggregates_tests {
+    use super::*;
+
+    use fil_actor_power::{PowerTotals, RecomputeAggregatesParams};
+    use fil_actors_runtime::test_utils::MockRuntime;
+    use fil_actors_runtime::SYSTEM_ACTOR_ADDR;
+
+    // Creates five miners, four of which are above the consensus minimum power.
+    fn setup_miners() -> (Harness, MockRuntime) {
+        let (mut h, rt) = setup();
+        let power_unit =
+            &consensus_miner_min_power(&Policy::default(), h.window_post_proof).unwrap();
+        let small_power_unit = &StoragePower::from(1_000_000);
+
+        for miner in [MINER1, MINER2, MINER3, MINER4, MINER5] {
+            h.create_miner_basic(&rt, *OWNER, *OWNER, miner).unwrap();
+        }
+        h.update_claimed_power(&rt, MINER1, small_power_unit, &(small_power_unit * 10));
+        for miner in [MINER2, MINER3, MINER4, MINER5] {
+            h.update_claimed_power(&rt, miner, power_unit, &(power_unit * 10));
+        }
+        (h, rt)
+    }
+
+    // Introduces drift into the recorded aggregates and returns the drift applied.
+    fn corrupt_aggregates(rt: &MockRuntime) -> PowerTotals {
+        let drift = PowerTotals {
+            raw_byte_power: StoragePower::from(1),
+            quality_adj_power: StoragePower::from(10),
+            miner_count: 1,
+            miner_above_min_power_count: 1,
+            ..Default::default()
+        };
+        let mut st: State = rt.get_state();
+        st.total_raw_byte_power += &drift.raw_byte_power;
+        st.total_quality_adj_power += &drift.quality_adj_power;
+        st.miner_count += drift.miner_count;
+        st.miner_above_min_power_count += drift.miner_above_min_power_count;
+        rt.replace_state(&st);
+        drift
+    }
+
+    fn without_drift(rt: &MockRuntime, drift: &PowerTotals) -> PowerTotals {
+        let totals = rt.get_state::<State>().power_totals();
+        PowerTotals {
+            raw_byte_power: totals.raw_byte_power - &drift.raw_byte_power,
+            bytes_committed: totals.bytes_committed - &drift.bytes_committed,
+            quality_adj_power: totals.quality_adj_power - &drift.quality_adj_power,
+            qa_bytes_committed: totals.qa_bytes_committed - &drift.qa_bytes_committed,
+            miner_count: totals.miner_count - drift.miner_count,
+            miner_above_min_power_count: totals.miner_above_min_power_count
+                - drift.miner_above_min_power_count,
+        }
+    }
+
+    #[test]
+    fn repairs_drift_across_invocations() {
+        let (h, rt) = setup_miners();
+        let expected = rt.get_state::<State>().power_totals();
+        corrupt_aggregates(&rt);
+
+        let ret = h.recompute_aggregates(&rt, 2, None);
+        assert_eq!(2, ret.claims_processed);
+        assert!(ret.cursor.is_some());
+        let ret = h.recompute_aggregates(&rt, 2, None);
+        assert_eq!(2, ret.claims_processed);
+        let ret = h.recompute_aggregates(&rt, 2, Some(&expected));
+        assert_eq!(1, ret.claims_processed);
+        assert_eq!(None, ret.cursor);
+        h.check_state(&rt);
+
+        // A subsequent invocation starts a new recomputation.
+        h.recompute_aggregates(&rt, 10, Some(&expected));
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn accounts_for_claims_updated_while_in_flight() {
+        let (mut h, rt) = setup_miners();
+        let drift = corrupt_aggregates(&rt);
+
+        h.recompute_aggregates(&rt, 1, None);
+
+        // Push the small miner above the minimum, and add a new miner.
+        let power_unit =
+            &consensus_miner_min_power(&Policy::default(), h.window_post_proof).unwrap();
+        h.update_claimed_power(&rt, MINER1, power_unit, power_unit);
+        h.create_miner_basic(&rt, *OWNER, *OWNER, Address::new_id(116)).unwrap();
+
+        let expected = without_drift(&rt, &drift);
+        h.recompute_aggregates(&rt, 10, Some(&expected));
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn records_min_power_crossings_in_miner_changes() {
+        let (h, rt) = setup_miners();
+        let expected = rt.get_state::<State>().power_totals();
+        let before = rt.get_state::<State>().miner_changes;
+
+        // Recomputing over-counted miners records them as falling below the minimum.
+        corrupt_aggregates(&rt);
+        h.recompute_aggregates(&rt, 10, Some(&expected));
+        let st: State = rt.get_state();
+        assert_eq!(
+            MinerChanges { fell_below_min_power: before.fell_below_min_power + 1, ..before },
+            st.miner_changes
+        );
+
+        // Recomputing under-counted miners records them as reaching the minimum.
+        let mut st: State = rt.get_state();
+        st.miner_above_min_power_count -= 2;
+        rt.replace_state(&st);
+        h.recompute_aggregates(&rt, 10, Some(&expected));
+        let st: State = rt.get_state();
+        assert_eq!(
+            MinerChanges {
+                reached_min_power: before.reached_min_power + 2,
+                fell_below_min_power: before.fell_below_min_power + 1,
+                ..before
+            },
+            st.miner_changes
+        );
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn rejects_non_system_caller() {
+        let (_, rt) = setup_miners();
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, *OWNER);
+        rt.expect_validate_caller_addr(vec![SYSTEM_ACTOR_ADDR]);
+        expect_abort(
+            ExitCode::USR_FORBIDDEN,
+            rt.call::<PowerActor>(
+                Method::RecomputeAggregates as MethodNum,
+                IpldBlock::serialize_cbor(&RecomputeAggregatesParams { max_claims: 1 }).unwrap(),
+            ),
+        );
+        rt.verify();
+    }
+
+    #[test]
+    fn rejects_zero_max_claims() {
+        let (_, rt) = setup_miners();
+        rt.set_caller(*SYSTEM_ACTOR_CODE_ID, SYSTEM_ACTOR_ADDR);
+        rt.expect_validate_caller_addr(vec![SYSTEM_ACTOR_ADDR]);
+        expect_abort_contains_message(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            "max claims must be positive",
+            rt.call::<PowerActor>(
+                Method::RecomputeAggregates as MethodNum,
+                IpldBlock::serialize_cbor(&RecomputeAggregatesParams { max_claims: 0 }).unwrap(),
+            ),
+        );
+        rt.verify();
+    }
+}
@@ -0,0 +1,26 @@
+// A namespace for helpers that build and emit reward actor events.
+
+use fil_actors_runtime::runtime::Runtime;
+use fil_actors_runtime::{ActorError, EventBuilder};
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::ActorID;
+
+/// Indicates a block reward was reduced to keep the epoch's emission within its ceiling.
+/// The withheld amount remains in the reward actor.
+pub fn emission_capped(
+    rt: &impl Runtime,
+    miner: ActorID,
+    requested: &TokenAmount,
+    paid: &TokenAmount,
+    ceiling: &TokenAmount,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("reward-emission-capped")
+            .field_indexed("miner", &miner)
+            .field("requested", requested)
+            .field("paid", paid)
+            .field("ceiling", ceiling)
+            .build()?,
+    )
+}
@@ -1,6 +1,25 @@
 use fvm_ipld_encoding::tuple::*;
 use fvm_shared::econ::TokenAmount;
 
+pub mod power {
+    use super::*;
+    use fil_actors_runtime::reward::FilterEstimate;
+    use fvm_shared::bigint::bigint_ser;
+    use fvm_shared::sector::StoragePower;
+
+    pub const CURRENT_TOTAL_POWER_METHOD: u64 = 9;
+
+    #[derive(Debug, Serialize_tuple, Deserialize_tuple)]
+    pub struct CurrentTotalPowerReturn {
+        #[serde(with = "bigint_ser")]
+        pub raw_byte_power: StoragePower,
+        #[serde(with = "bigint_ser")]
+        pub quality_adj_power: StoragePower,
+        pub pledge_collateral: TokenAmount,
+        pub quality_adj_power_smoothed: FilterEstimate,
+    }
+}
+
 pub mod miner {
     use super::*;
 
@@ -1,26 +1,31 @@
 // Copyright 2019-2022 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use fil_actors_runtime::reward::expected_reward_for_power;
 use fil_actors_runtime::runtime::{ActorCode, Runtime};
 use fil_actors_runtime::{
-    actor_dispatch, actor_error, extract_send_result, ActorError, BURNT_FUNDS_ACTOR_ADDR,
-    EXPECTED_LEADERS_PER_EPOCH, STORAGE_POWER_ACTOR_ADDR, SYSTEM_ACTOR_ADDR,
+    actor_dispatch, actor_error, deserialize_block, extract_send_result, ActorError,
+    BURNT_FUNDS_ACTOR_ADDR, EXPECTED_LEADERS_PER_EPOCH, STORAGE_POWER_ACTOR_ADDR,
+    SYSTEM_ACTOR_ADDR,
 };
 
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_shared::address::Address;
 use fvm_shared::econ::TokenAmount;
+use fvm_shared::sys::SendFlags;
 use fvm_shared::{METHOD_CONSTRUCTOR, METHOD_SEND};
 use log::{error, warn};
 use num_derive::FromPrimitive;
+use num_traits::{Signed, Zero};
 
 pub use self::logic::*;
-pub use self::state::{Reward, State, VestingFunction};
+pub use self::state::{Reward, State, TreasurySplit, VestingFunction};
 pub use self::types::*;
 
 #[cfg(feature = "fil-actor")]
 fil_actors_runtime::wasm_trampoline!(Actor);
 
+mod emit;
 pub(crate) mod expneg;
 mod logic;
 mod state;
@@ -36,6 +41,13 @@ pub mod ext;
 /// PenaltyMultiplier is the factor miner penalties are scaled up by
 pub const PENALTY_MULTIPLIER: u64 = 3;
 
+/// Denominator of the treasury's share of block rewards, which is expressed in basis points.
+pub const TREASURY_SHARE_DENOMINATOR: u64 = 10_000;
+
+/// Denominator of the per-epoch emission ceiling, which is expressed in parts per billion
+/// of the circulating supply.
+pub const EMISSION_CEILING_DENOMINATOR: u64 = 1_000_000_000;
+
 /// Reward actor methods available
 #[derive(FromPrimitive)]
 #[repr(u64)]
@@ -44,6 +56,9 @@ pub enum Method {
     AwardBlockReward = 2,
     ThisEpochReward = 3,
     UpdateNetworkKPI = 4,
+    SetTreasurySplit = 5,
+    // Method numbers derived from FRC-0042 standards
+    ProjectedRewardExported = frc42_dispatch::method_hash!("ProjectedReward"),
 }
 
 /// Reward Actor
@@ -72,6 +87,8 @@ impl Actor {
     ///
     /// The reward is reduced before the residual is credited to the block producer, by:
     /// - a penalty amount, provided as a parameter, which is burnt,
+    /// - any block reward beyond the policy's per-epoch emission ceiling, if one is set,
+    ///   which is withheld in the reward actor rather than failing the block.
     fn award_block_reward(
         rt: &impl Runtime,
         params: AwardBlockRewardParams,
@@ -106,30 +123,69 @@ impl Actor {
 
         let penalty: TokenAmount = &params.penalty * PENALTY_MULTIPLIER;
 
-        let total_reward = rt.transaction(|st: &mut State, rt| {
-            let mut block_reward: TokenAmount =
-                (&st.this_epoch_reward * params.win_count).div_floor(EXPECTED_LEADERS_PER_EPOCH);
-            let mut total_reward = &params.gas_reward + &block_reward;
-            let curr_balance = rt.current_balance();
-            if total_reward > curr_balance {
-                warn!(
-                    "reward actor balance {} below totalReward expected {},\
+        let (mut total_reward, treasury_reward, capped) =
+            rt.transaction(|st: &mut State, rt| {
+                let mut block_reward: TokenAmount = (&st.this_epoch_reward * params.win_count)
+                    .div_floor(EXPECTED_LEADERS_PER_EPOCH);
+                let mut total_reward = &params.gas_reward + &block_reward;
+                let curr_balance = rt.current_balance();
+                if total_reward > curr_balance {
+                    warn!(
+                        "reward actor balance {} below totalReward expected {},\
                     paying out rest of balance",
-                    curr_balance, total_reward
-                );
-                total_reward = curr_balance;
-                block_reward = &total_reward - &params.gas_reward;
-                if block_reward.is_negative() {
-                    return Err(actor_error!(
-                        illegal_state,
-                        "programming error, block reward {} below zero",
-                        block_reward
-                    ));
+                        curr_balance, total_reward
+                    );
+                    total_reward = curr_balance;
+                    block_reward = &total_reward - &params.gas_reward;
+                    if block_reward.is_negative() {
+                        return Err(actor_error!(
+                            illegal_state,
+                            "programming error, block reward {} below zero",
+                            block_reward
+                        ));
+                    }
                 }
-            }
-            st.total_storage_power_reward += block_reward;
-            Ok(total_reward)
-        })?;
+                // Withhold any block reward beyond the epoch's emission ceiling, if one is configured.
+                // Gas rewards are not minted, so are not subject to the ceiling.
+                let curr_epoch = rt.curr_epoch();
+                if st.emission_epoch != curr_epoch {
+                    st.emission_epoch = curr_epoch;
+                    st.epoch_emission = TokenAmount::zero();
+                }
+                let mut capped = None;
+                let ceiling_ppb = rt.policy().max_epoch_reward_emission_ppb;
+                if ceiling_ppb > 0 {
+                    let ceiling = (rt.total_fil_circ_supply() * ceiling_ppb)
+                        .div_floor(EMISSION_CEILING_DENOMINATOR);
+                    let allowed = std::cmp::max(&ceiling - &st.epoch_emission, TokenAmount::zero());
+                    if block_reward > allowed {
+                        warn!(
+                            "block reward {} exceeds remaining epoch emission {} of ceiling {}",
+                            block_reward, allowed, ceiling
+                        );
+                        total_reward -= &block_reward - &allowed;
+                        capped = Some((block_reward, allowed.clone(), ceiling));
+                        block_reward = allowed;
+                    }
+                }
+                st.epoch_emission += &block_reward;
+
+                // The treasury's share is split from the block reward, leaving gas rewards to the miner.
+                let treasury_reward = st.treasury_split.as_ref().map(|split| {
+                    let amount =
+                        (&block_reward * split.share_bps).div_floor(TREASURY_SHARE_DENOMINATOR);
+                    (split.address, amount)
+                });
+                if let Some((_, amount)) = &treasury_reward {
+                    st.total_treasury_reward += amount;
+                }
+                st.total_storage_power_reward += block_reward;
+                Ok((total_reward, treasury_reward, capped))
+            })?;
+
+        if let Some((requested, paid, ceiling)) = capped {
+            emit::emission_capped(rt, miner_id, &requested, &paid, &ceiling)?;
+        }
 
         // * Go implementation added this and removed capping it -- this could potentially panic
         // * as they treat panics as an exit code. Revisit this.
@@ -142,6 +198,33 @@ impl Actor {
             ));
         }
 
+        if let Some((treasury, amount)) = treasury_reward.filter(|(_, a)| a.is_positive()) {
+            total_reward -= &amount;
+            // if this fails, we can assume the treasury is responsible and avoid failing here.
+            let res =
+                extract_send_result(rt.send_simple(&treasury, METHOD_SEND, None, amount.clone()));
+            if let Err(e) = res {
+                error!(
+                    "failed to send treasury reward {} to {}, code: {:?}",
+                    amount,
+                    treasury,
+                    e.exit_code()
+                );
+                let res = extract_send_result(rt.send_simple(
+                    &BURNT_FUNDS_ACTOR_ADDR,
+                    METHOD_SEND,
+                    None,
+                    amount,
+                ));
+                if let Err(e) = res {
+                    error!(
+                        "failed to send unsent treasury reward to the burnt funds actor, code: {:?}",
+                        e.exit_code()
+                    );
+                }
+            }
+        }
+
         // if this fails, we can assume the miner is responsible and avoid failing here.
         let reward_params = ext::miner::ApplyRewardParams { reward: total_reward.clone(), penalty };
         let res = extract_send_result(rt.send_simple(
@@ -173,6 +256,45 @@ impl Actor {
         Ok(())
     }
 
+    /// Configures the share of block rewards sent to a treasury, or removes it.
+    /// Only the governor can call this method, and a network without a governor
+    /// has no treasury split.
+    fn set_treasury_split(
+        rt: &impl Runtime,
+        params: SetTreasurySplitParams,
+    ) -> Result<(), ActorError> {
+        let st: State = rt.state()?;
+        let governor =
+            st.governor.ok_or_else(|| actor_error!(forbidden, "treasury split is not governed"))?;
+        rt.validate_immediate_caller_is(std::iter::once(&governor))?;
+
+        let split = match params.split {
+            Some(split) => {
+                if split.share_bps > TREASURY_SHARE_DENOMINATOR {
+                    return Err(actor_error!(
+                        illegal_argument,
+                        "treasury share {} exceeds {} basis points",
+                        split.share_bps,
+                        TREASURY_SHARE_DENOMINATOR
+                    ));
+                }
+                let treasury_id = rt.resolve_address(&split.address).ok_or_else(|| {
+                    actor_error!(illegal_argument, "failed to resolve treasury {}", split.address)
+                })?;
+                Some(TreasurySplit {
+                    address: Address::new_id(treasury_id),
+                    share_bps: split.share_bps,
+                })
+            }
+            None => None,
+        };
+
+        rt.transaction(|st: &mut State, _| {
+            st.treasury_split = split;
+            Ok(())
+        })
+    }
+
     /// The award value used for the current epoch, updated at the end of an epoch
     /// through cron tick.  In the case previous epochs were null blocks this
     /// is the reward value as calculated at the last non-null epoch.
@@ -185,6 +307,45 @@ impl Actor {
         })
     }
 
+    /// Returns the projected block reward, BR(t), that some quality-adjusted power would earn
+    /// over a duration, from the current smoothed estimates of the epoch reward and
+    /// the network's quality-adjusted power.
+    fn projected_reward(
+        rt: &impl Runtime,
+        params: ProjectedRewardParams,
+    ) -> Result<ProjectedRewardReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        if params.qa_power.is_negative() {
+            return Err(actor_error!(illegal_argument, "negative qa power {}", params.qa_power));
+        }
+        if params.duration_epochs < 0 {
+            return Err(actor_error!(
+                illegal_argument,
+                "negative projection duration {}",
+                params.duration_epochs
+            ));
+        }
+
+        let power: ext::power::CurrentTotalPowerReturn =
+            deserialize_block(extract_send_result(rt.send(
+                &STORAGE_POWER_ACTOR_ADDR,
+                ext::power::CURRENT_TOTAL_POWER_METHOD,
+                None,
+                TokenAmount::zero(),
+                None,
+                SendFlags::READ_ONLY,
+            ))?)?;
+
+        let st: State = rt.state()?;
+        let reward = expected_reward_for_power(
+            &st.this_epoch_reward_smoothed,
+            &power.quality_adj_power_smoothed,
+            &params.qa_power,
+            params.duration_epochs,
+        );
+        Ok(ProjectedRewardReturn { reward })
+    }
+
     /// Called at the end of each epoch by the power actor (in turn by its cron hook).
     /// This is only invoked for non-empty tipsets, but catches up any number of null
     /// epochs to compute the next epoch reward.
@@ -222,10 +383,17 @@ impl ActorCode for Actor {
         "Reward"
     }
 
+    fil_actors_runtime::actor_info! {
+        interface_revisions: [1],
+        features: [],
+    }
+
     actor_dispatch! {
         Constructor => constructor,
         AwardBlockReward => award_block_reward,
         ThisEpochReward => this_epoch_reward,
         UpdateNetworkKPI => update_network_kpi,
+        SetTreasurySplit => set_treasury_split,
+        ProjectedRewardExported => projected_reward,
     }
 }
@@ -3,6 +3,7 @@
 
 use fvm_ipld_encoding::repr::*;
 use fvm_ipld_encoding::tuple::*;
+use fvm_shared::address::Address;
 use fvm_shared::bigint::bigint_ser;
 use fvm_shared::bigint::BigInt;
 use fvm_shared::clock::{ChainEpoch, EPOCH_UNDEFINED};
@@ -74,6 +75,29 @@ pub struct State {
     // into a code constant in a subsequent upgrade.
     pub simple_total: TokenAmount,
     pub baseline_total: TokenAmount,
+
+    /// Address permitted to configure the treasury split, chosen at genesis.
+    /// Without a governor, the split can only be changed by a migration.
+    pub governor: Option<Address>,
+    /// Share of block rewards sent to a treasury, rather than to the block producer.
+    pub treasury_split: Option<TreasurySplit>,
+    /// Tracks the total FIL sent to the treasury, which is included in the
+    /// total_storage_power_reward.
+    pub total_treasury_reward: TokenAmount,
+
+    /// The epoch in which epoch_emission was paid out.
+    pub emission_epoch: ChainEpoch,
+    /// Block reward paid out so far in emission_epoch, counted against the emission ceiling.
+    pub epoch_emission: TokenAmount,
+}
+
+/// A share of each block reward sent to a treasury.
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, PartialEq, Eq)]
+pub struct TreasurySplit {
+    /// ID address of the treasury.
+    pub address: Address,
+    /// The treasury's share of block rewards, in basis points.
+    pub share_bps: u64,
 }
 
 impl State {
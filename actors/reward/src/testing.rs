@@ -1,6 +1,6 @@
-use crate::{baseline_power_from_prev, State};
+use crate::{baseline_power_from_prev, State, TREASURY_SHARE_DENOMINATOR};
 use fil_actors_runtime::MessageAccumulator;
-use fvm_shared::{clock::ChainEpoch, econ::TokenAmount};
+use fvm_shared::{address::Protocol, clock::ChainEpoch, econ::TokenAmount};
 use num_traits::Signed;
 
 #[derive(Default)]
@@ -24,6 +24,36 @@ pub fn check_state_invariants(
         ),
     );
 
+    // The treasury's rewards are minted as part of the storage power reward.
+    acc.require(
+        !state.total_treasury_reward.is_negative()
+            && state.total_treasury_reward <= state.total_storage_power_reward,
+        format!(
+            "treasury reward {} outside of storage power reward {}",
+            state.total_treasury_reward, state.total_storage_power_reward
+        ),
+    );
+    acc.require(
+        !state.epoch_emission.is_negative(),
+        format!("negative epoch emission {}", state.epoch_emission),
+    );
+    if let Some(split) = &state.treasury_split {
+        acc.require(
+            split.share_bps <= TREASURY_SHARE_DENOMINATOR,
+            format!("treasury share {} exceeds {}", split.share_bps, TREASURY_SHARE_DENOMINATOR),
+        );
+        acc.require(
+            split.address.protocol() == Protocol::ID,
+            format!("treasury address {} is not an ID address", split.address),
+        );
+    }
+    if let Some(governor) = &state.governor {
+        acc.require(
+            governor.protocol() == Protocol::ID,
+            format!("governor address {} is not an ID address", governor),
+        );
+    }
+
     acc.require(
         state.epoch == prior_epoch + 1,
         format!(
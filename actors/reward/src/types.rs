@@ -3,8 +3,12 @@
 
 use fvm_ipld_encoding::tuple::*;
 use fvm_shared::address::Address;
-use fvm_shared::bigint::bigint_ser::BigIntDe;
+use fvm_shared::bigint::bigint_ser::{self, BigIntDe};
+use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
+use fvm_shared::sector::StoragePower;
+
+use crate::TreasurySplit;
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
 #[serde(transparent)]
@@ -27,3 +31,22 @@ pub use fil_actors_runtime::builtin::reward::ThisEpochRewardReturn;
 pub struct UpdateNetworkKPIParams {
     pub curr_realized_power: Option<BigIntDe>,
 }
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ProjectedRewardParams {
+    #[serde(with = "bigint_ser")]
+    pub qa_power: StoragePower,
+    pub duration_epochs: ChainEpoch,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct ProjectedRewardReturn {
+    pub reward: TokenAmount,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct SetTreasurySplitParams {
+    /// The new treasury split, or None to send all block rewards to block producers.
+    pub split: Option<TreasurySplit>,
+}
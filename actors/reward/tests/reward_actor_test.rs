@@ -4,9 +4,11 @@
 use std::cell::RefCell;
 
 use fil_actor_reward::{
-    ext, Actor as RewardActor, AwardBlockRewardParams, Method, State, ThisEpochRewardReturn,
-    BASELINE_INITIAL_VALUE, PENALTY_MULTIPLIER,
+    ext, Actor as RewardActor, AwardBlockRewardParams, Method, ProjectedRewardParams,
+    ProjectedRewardReturn, State, ThisEpochRewardReturn, BASELINE_INITIAL_VALUE,
+    PENALTY_MULTIPLIER, TREASURY_SHARE_DENOMINATOR,
 };
+use fil_actors_runtime::reward::{expected_reward_for_power, FilterEstimate};
 use fil_actors_runtime::test_utils::*;
 use fil_actors_runtime::EXPECTED_LEADERS_PER_EPOCH;
 use fil_actors_runtime::{
@@ -314,6 +316,348 @@ mod test_this_epoch_reward {
     }
 }
 
+mod test_projected_reward {
+    use fvm_shared::sys::SendFlags;
+    use num::Zero;
+
+    use super::*;
+
+    #[test]
+    fn projects_reward_from_smoothed_estimates() {
+        let rt = construct_and_verify(&StoragePower::from(1));
+        let state: State = rt.get_state();
+
+        let network_qa_power = FilterEstimate::new(StoragePower::from(1_i64 << 50), Zero::zero());
+        let qa_power = StoragePower::from(32_i64 << 30);
+        let duration = 20 * 2880;
+
+        rt.set_caller(*EVM_ACTOR_CODE_ID, Address::new_id(1234));
+        let ret = projected_reward(&rt, &network_qa_power, &qa_power, duration).unwrap();
+        assert!(ret.reward.is_positive());
+        assert_eq!(
+            expected_reward_for_power(
+                &state.this_epoch_reward_smoothed,
+                &network_qa_power,
+                &qa_power,
+                duration
+            ),
+            ret.reward
+        );
+
+        // Reward grows with power.
+        let double_ret =
+            projected_reward(&rt, &network_qa_power, &(&qa_power * 2), duration).unwrap();
+        assert!(double_ret.reward > ret.reward);
+    }
+
+    #[test]
+    fn zero_network_power_projects_epoch_reward() {
+        let rt = construct_and_verify(&StoragePower::from(1));
+        let state: State = rt.get_state();
+
+        let ret =
+            projected_reward(&rt, &FilterEstimate::default(), &StoragePower::from(1), 10).unwrap();
+        assert_eq!(TokenAmount::from_atto(state.this_epoch_reward_smoothed.estimate()), ret.reward);
+    }
+
+    #[test]
+    fn rejects_negative_inputs() {
+        let rt = construct_and_verify(&StoragePower::from(1));
+        for (qa_power, duration) in [(StoragePower::from(-1), 10), (StoragePower::from(1), -1)] {
+            rt.expect_validate_caller_any();
+            let params = IpldBlock::serialize_cbor(&ProjectedRewardParams {
+                qa_power,
+                duration_epochs: duration,
+            })
+            .unwrap();
+            expect_abort(
+                ExitCode::USR_ILLEGAL_ARGUMENT,
+                rt.call::<RewardActor>(Method::ProjectedRewardExported as u64, params),
+            );
+            rt.verify();
+        }
+    }
+
+    fn projected_reward(
+        rt: &MockRuntime,
+        network_qa_power: &FilterEstimate,
+        qa_power: &StoragePower,
+        duration: ChainEpoch,
+    ) -> Result<ProjectedRewardReturn, ActorError> {
+        rt.expect_validate_caller_any();
+        rt.expect_send(
+            STORAGE_POWER_ACTOR_ADDR,
+            ext::power::CURRENT_TOTAL_POWER_METHOD,
+            None,
+            TokenAmount::zero(),
+            None,
+            SendFlags::READ_ONLY,
+            IpldBlock::serialize_cbor(&ext::power::CurrentTotalPowerReturn {
+                raw_byte_power: Zero::zero(),
+                quality_adj_power: Zero::zero(),
+                pledge_collateral: TokenAmount::zero(),
+                quality_adj_power_smoothed: network_qa_power.clone(),
+            })
+            .unwrap(),
+            ExitCode::OK,
+            None,
+        );
+        let params = IpldBlock::serialize_cbor(&ProjectedRewardParams {
+            qa_power: qa_power.clone(),
+            duration_epochs: duration,
+        })
+        .unwrap();
+        let ret = rt.call::<RewardActor>(Method::ProjectedRewardExported as u64, params)?;
+        rt.verify();
+        Ok(ret.unwrap().deserialize().unwrap())
+    }
+}
+
+mod test_treasury_split {
+    use fil_actor_reward::{SetTreasurySplitParams, TreasurySplit};
+    use num::Zero;
+
+    use super::*;
+
+    lazy_static! {
+        static ref GOVERNOR: Address = Address::new_id(2000);
+        static ref TREASURY: Address = Address::new_id(3000);
+    }
+
+    #[test]
+    fn only_governor_can_set_split() {
+        let rt = construct_and_verify(&StoragePower::from(1));
+        let split = TreasurySplit { address: *TREASURY, share_bps: 1000 };
+
+        // Without a governor, there's no split to configure.
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, *GOVERNOR);
+        expect_abort(ExitCode::USR_FORBIDDEN, set_treasury_split(&rt, Some(split.clone())));
+        rt.reset();
+
+        set_governor(&rt);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, Address::new_id(2001));
+        rt.expect_validate_caller_addr(vec![*GOVERNOR]);
+        expect_abort(ExitCode::USR_FORBIDDEN, set_treasury_split(&rt, Some(split.clone())));
+        rt.verify();
+
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, *GOVERNOR);
+        rt.expect_validate_caller_addr(vec![*GOVERNOR]);
+        set_treasury_split(&rt, Some(split.clone())).unwrap();
+        rt.verify();
+        assert_eq!(Some(split), rt.get_state::<State>().treasury_split);
+
+        rt.expect_validate_caller_addr(vec![*GOVERNOR]);
+        set_treasury_split(&rt, None).unwrap();
+        rt.verify();
+        assert_eq!(None, rt.get_state::<State>().treasury_split);
+    }
+
+    #[test]
+    fn rejects_invalid_split() {
+        let rt = construct_and_verify(&StoragePower::from(1));
+        set_governor(&rt);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, *GOVERNOR);
+
+        for split in [
+            TreasurySplit { address: *TREASURY, share_bps: TREASURY_SHARE_DENOMINATOR + 1 },
+            TreasurySplit { address: Address::new_actor(b"treasury"), share_bps: 1000 },
+        ] {
+            rt.expect_validate_caller_addr(vec![*GOVERNOR]);
+            expect_abort(ExitCode::USR_ILLEGAL_ARGUMENT, set_treasury_split(&rt, Some(split)));
+            rt.verify();
+        }
+    }
+
+    #[test]
+    fn splits_block_reward_with_treasury() {
+        let rt = construct_and_verify(&StoragePower::from(1));
+        let mut state: State = rt.get_state();
+        state.this_epoch_reward = TokenAmount::from_atto(5000);
+        state.treasury_split = Some(TreasurySplit { address: *TREASURY, share_bps: 2500 });
+        rt.replace_state(&state);
+        rt.set_balance(TokenAmount::from_atto(10_000));
+
+        // A quarter of the block reward goes to the treasury, and the rest with the gas reward
+        // to the miner.
+        let block_reward = TokenAmount::from_atto(1000);
+        let gas_reward = TokenAmount::from_atto(200);
+        rt.expect_validate_caller_addr(vec![SYSTEM_ACTOR_ADDR]);
+        rt.expect_send_simple(
+            *TREASURY,
+            METHOD_SEND,
+            None,
+            TokenAmount::from_atto(250),
+            None,
+            ExitCode::OK,
+        );
+        let miner_reward = TokenAmount::from_atto(750) + &gas_reward;
+        rt.expect_send_simple(
+            *WINNER,
+            ext::miner::APPLY_REWARDS_METHOD,
+            IpldBlock::serialize_cbor(&ext::miner::ApplyRewardParams {
+                reward: miner_reward.clone(),
+                penalty: TokenAmount::zero(),
+            })
+            .unwrap(),
+            miner_reward,
+            None,
+            ExitCode::OK,
+        );
+        award(&rt, gas_reward);
+        rt.verify();
+
+        // The treasury's share is accounted for as minted storage power reward.
+        let state: State = rt.get_state();
+        assert_eq!(block_reward, state.total_storage_power_reward);
+        assert_eq!(TokenAmount::from_atto(250), state.total_treasury_reward);
+    }
+
+    #[test]
+    fn treasury_reward_is_burnt_if_send_fails() {
+        let rt = construct_and_verify(&StoragePower::from(1));
+        let mut state: State = rt.get_state();
+        state.this_epoch_reward = TokenAmount::from_atto(5000);
+        state.treasury_split = Some(TreasurySplit { address: *TREASURY, share_bps: 2500 });
+        rt.replace_state(&state);
+        rt.set_balance(TokenAmount::from_atto(10_000));
+
+        rt.expect_validate_caller_addr(vec![SYSTEM_ACTOR_ADDR]);
+        let treasury_reward = TokenAmount::from_atto(250);
+        rt.expect_send_simple(
+            *TREASURY,
+            METHOD_SEND,
+            None,
+            treasury_reward.clone(),
+            None,
+            ExitCode::USR_FORBIDDEN,
+        );
+        rt.expect_send_simple(
+            BURNT_FUNDS_ACTOR_ADDR,
+            METHOD_SEND,
+            None,
+            treasury_reward,
+            None,
+            ExitCode::OK,
+        );
+        let miner_reward = TokenAmount::from_atto(750);
+        rt.expect_send_simple(
+            *WINNER,
+            ext::miner::APPLY_REWARDS_METHOD,
+            IpldBlock::serialize_cbor(&ext::miner::ApplyRewardParams {
+                reward: miner_reward.clone(),
+                penalty: TokenAmount::zero(),
+            })
+            .unwrap(),
+            miner_reward,
+            None,
+            ExitCode::OK,
+        );
+        award(&rt, TokenAmount::zero());
+        rt.verify();
+    }
+
+    fn set_governor(rt: &MockRuntime) {
+        let mut state: State = rt.get_state();
+        state.governor = Some(*GOVERNOR);
+        rt.replace_state(&state);
+    }
+
+    fn set_treasury_split(
+        rt: &MockRuntime,
+        split: Option<TreasurySplit>,
+    ) -> Result<Option<IpldBlock>, ActorError> {
+        rt.call::<RewardActor>(
+            Method::SetTreasurySplit as u64,
+            IpldBlock::serialize_cbor(&SetTreasurySplitParams { split }).unwrap(),
+        )
+    }
+
+    fn award(rt: &MockRuntime, gas_reward: TokenAmount) {
+        rt.set_caller(*SYSTEM_ACTOR_CODE_ID, SYSTEM_ACTOR_ADDR);
+        let params = IpldBlock::serialize_cbor(&AwardBlockRewardParams {
+            miner: *WINNER,
+            penalty: TokenAmount::zero(),
+            gas_reward,
+            win_count: 1,
+        })
+        .unwrap();
+        rt.call::<RewardActor>(Method::AwardBlockReward as u64, params).unwrap();
+    }
+}
+
+mod test_emission_ceiling {
+    use fil_actors_runtime::EventBuilder;
+    use num::Zero;
+
+    use super::*;
+
+    #[test]
+    fn withholds_block_reward_beyond_epoch_ceiling() {
+        let mut rt = construct_and_verify(&StoragePower::from(1));
+        // A ceiling of 0.1% of circulating supply allows 1500 per epoch.
+        rt.policy.max_epoch_reward_emission_ppb = 1_000_000;
+        rt.set_circulating_supply(TokenAmount::from_atto(1_500_000));
+        let mut state: State = rt.get_state();
+        state.this_epoch_reward = TokenAmount::from_atto(5000);
+        rt.replace_state(&state);
+        rt.set_balance(TokenAmount::from_atto(10_000));
+        rt.set_epoch(10);
+
+        // The first block reward fits within the ceiling.
+        award(&rt, TokenAmount::from_atto(1000), None);
+
+        // The second is clamped to what remains, but gas rewards are still paid in full.
+        let gas_reward = TokenAmount::from_atto(100);
+        award(&rt, gas_reward.clone(), Some(TokenAmount::from_atto(500)));
+
+        // Further blocks in the epoch earn only their gas reward.
+        award(&rt, gas_reward, Some(TokenAmount::zero()));
+
+        let state: State = rt.get_state();
+        assert_eq!(TokenAmount::from_atto(1500), state.total_storage_power_reward);
+        assert_eq!(TokenAmount::from_atto(1500), state.epoch_emission);
+
+        // The ceiling resets in the next epoch.
+        rt.set_epoch(11);
+        award(&rt, TokenAmount::from_atto(1000), None);
+        let state: State = rt.get_state();
+        assert_eq!(TokenAmount::from_atto(1000), state.epoch_emission);
+        assert_eq!(11, state.emission_epoch);
+    }
+
+    // Awards a block reward of 1000 plus gas. If `capped` is set, expects the block reward to be
+    // clamped to it.
+    fn award(rt: &MockRuntime, gas_reward: TokenAmount, capped: Option<TokenAmount>) {
+        let block_reward = TokenAmount::from_atto(1000);
+        let paid = match &capped {
+            Some(paid) => {
+                rt.expect_emitted_event(
+                    EventBuilder::new()
+                        .typ("reward-emission-capped")
+                        .field_indexed("miner", &WINNER.id().unwrap())
+                        .field("requested", &block_reward)
+                        .field("paid", paid)
+                        .field("ceiling", &TokenAmount::from_atto(1500))
+                        .build()
+                        .unwrap(),
+                );
+                paid.clone()
+            }
+            None => block_reward,
+        };
+        rt.set_caller(*SYSTEM_ACTOR_CODE_ID, SYSTEM_ACTOR_ADDR);
+        award_block_reward(
+            rt,
+            *WINNER,
+            TokenAmount::zero(),
+            gas_reward.clone(),
+            1,
+            paid + gas_reward,
+        )
+        .unwrap();
+    }
+}
+
 #[test]
 fn test_successive_kpi_updates() {
     let power = StoragePower::from_i128(1 << 50).unwrap();
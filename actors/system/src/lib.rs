@@ -71,6 +71,11 @@ impl ActorCode for Actor {
         "System"
     }
 
+    fil_actors_runtime::actor_info! {
+        interface_revisions: [1],
+        features: [],
+    }
+
     actor_dispatch! {
         Constructor => constructor,
     }
@@ -36,6 +36,21 @@ pub fn allocation(
     )
 }
 
+/// Indicates an allocation has been updated (e.g. with a new provider).
+pub fn allocation_updated(
+    rt: &impl Runtime,
+    id: AllocationID,
+    client: ActorID,
+    provider: ActorID,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("allocation-updated")
+            .with_parties(id, client, provider)
+            .build()?,
+    )
+}
+
 /// Indicates an expired allocation has been removed.
 pub fn allocation_removed(
     rt: &impl Runtime,
@@ -51,6 +66,24 @@ pub fn allocation_removed(
     )
 }
 
+/// Indicates expired allocations made by a client have been removed,
+/// and their datacap returned to the client.
+pub fn expired_allocations_removed(
+    rt: &impl Runtime,
+    client: ActorID,
+    allocations: u64,
+    datacap: &DataCap,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ("expired-allocations-removed")
+            .field_indexed("client", &client)
+            .field("allocations", &allocations)
+            .field("datacap", &BigIntSer(datacap))
+            .build()?,
+    )
+}
+
 /// Indicates an allocation has been claimed.
 pub fn claim(
     rt: &impl Runtime,
@@ -52,6 +52,38 @@ where
     Ok(found_ids)
 }
 
+// Finds the items in a collection that have expired, across all owners, examining at most
+// `limit` items in storage order from a cursor of an owner and (optionally) a key of that owner.
+// Returns those items' owners and keys, and the cursor at which to resume if any items remain.
+pub fn find_expired_from<T, BS>(
+    collection: &mut MapMap<BS, T, ActorID, u64>,
+    cursor: Option<(ActorID, Option<u64>)>,
+    limit: usize,
+    curr_epoch: ChainEpoch,
+) -> Result<(Vec<(ActorID, u64)>, Option<(ActorID, Option<u64>)>), ActorError>
+where
+    T: Expires + Serialize + DeserializeOwned + Clone + PartialEq,
+    BS: Blockstore,
+{
+    let parse = |k: &[u8]| {
+        parse_uint_key(k).context_code(ExitCode::USR_ILLEGAL_STATE, "failed to parse uint key")
+    };
+    let mut found = Vec::<(ActorID, u64)>::new();
+    let (_, next) = collection
+        .for_each_ranged(cursor, limit, |owner_key, key, record| {
+            if curr_epoch >= record.expiration() {
+                found.push((parse(owner_key)?, parse(key)?));
+            }
+            Ok(())
+        })
+        .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to iterate over allocations/claims")?;
+    let next = match next {
+        Some((owner_key, key)) => Some((parse(&owner_key)?, key.map(|k| parse(&k)).transpose()?)),
+        None => None,
+    };
+    Ok((found, next))
+}
+
 // Checks each candidate item from the collection for expiration.
 // Returns a batch return with OK for expired items, and FORBIDDEN for non-expired.
 pub fn check_expired<T, BS>(
@@ -44,3 +44,35 @@ pub mod datacap {
         pub amount: TokenAmount,
     }
 }
+
+pub mod miner {
+    use super::*;
+    use fvm_shared::sector::SectorNumber;
+
+    pub const ON_VERIFIED_CLAIMS_EXPIRED_METHOD: u64 = 38;
+    pub const IS_CONTROLLING_ADDRESS_EXPORTED: u64 =
+        frc42_dispatch::method_hash!("IsControllingAddress");
+
+    #[derive(Serialize_tuple, Deserialize_tuple)]
+    #[serde(transparent)]
+    pub struct IsControllingAddressParam {
+        pub address: Address,
+    }
+
+    #[derive(Serialize_tuple, Deserialize_tuple)]
+    #[serde(transparent)]
+    pub struct IsControllingAddressReturn {
+        pub is_controlling: bool,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+    pub struct OnVerifiedClaimsExpiredParams {
+        pub sectors: Vec<SectorExpiredClaimSpace>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+    pub struct SectorExpiredClaimSpace {
+        pub sector_number: SectorNumber,
+        pub space: u64,
+    }
+}
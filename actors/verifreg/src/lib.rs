@@ -1,6 +1,8 @@
 // Copyright 2019-2022 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use std::collections::BTreeMap;
+
 use frc46_token::receiver::{FRC46TokenReceived, FRC46_TOKEN_TYPE};
 use frc46_token::token::types::{BurnParams, TransferParams};
 use frc46_token::token::TOKEN_PRECISION;
@@ -11,8 +13,10 @@ use fvm_ipld_encoding::RawBytes;
 use fvm_shared::address::Address;
 use fvm_shared::bigint::BigInt;
 use fvm_shared::clock::ChainEpoch;
+use fvm_shared::crypto::signature::Signature;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
+use fvm_shared::sector::SectorNumber;
 use fvm_shared::sys::SendFlags;
 use fvm_shared::{ActorID, METHOD_CONSTRUCTOR};
 use log::info;
@@ -20,12 +24,13 @@ use num_derive::FromPrimitive;
 use num_traits::{Signed, Zero};
 
 use fil_actors_runtime::cbor::deserialize;
+use fil_actors_runtime::query::MAX_LIST_LIMIT;
 use fil_actors_runtime::runtime::builtins::Type;
 use fil_actors_runtime::runtime::{ActorCode, Policy, Runtime};
 use fil_actors_runtime::{
     actor_dispatch, actor_error, deserialize_block, extract_send_result, resolve_to_actor_id,
-    ActorError, BatchReturn, DATACAP_TOKEN_ACTOR_ADDR, STORAGE_MARKET_ACTOR_ADDR,
-    SYSTEM_ACTOR_ADDR, VERIFIED_REGISTRY_ACTOR_ADDR,
+    verify_key_signatures, ActorError, BatchReturn, MapMap, DATACAP_TOKEN_ACTOR_ADDR,
+    STORAGE_MARKET_ACTOR_ADDR, SYSTEM_ACTOR_ADDR, VERIFIED_REGISTRY_ACTOR_ADDR,
 };
 use fil_actors_runtime::{ActorContext, AsActorError, BatchReturnGen};
 
@@ -72,6 +77,13 @@ pub enum Method {
     GetClaimsExported = frc42_dispatch::method_hash!("GetClaims"),
     ExtendClaimTermsExported = frc42_dispatch::method_hash!("ExtendClaimTerms"),
     RemoveExpiredClaimsExported = frc42_dispatch::method_hash!("RemoveExpiredClaims"),
+    ListAllocationsForClientExported = frc42_dispatch::method_hash!("ListAllocationsForClient"),
+    ListClaimsForProviderExported = frc42_dispatch::method_hash!("ListClaimsForProvider"),
+    ExtendClaimTermsBatchExported = frc42_dispatch::method_hash!("ExtendClaimTermsBatch"),
+    RetargetAllocationExported = frc42_dispatch::method_hash!("RetargetAllocation"),
+    RemoveExpiredAllocationsWithReceiptExported =
+        frc42_dispatch::method_hash!("RemoveExpiredAllocationsWithReceipt"),
+    SweepExpiredAllocationsExported = frc42_dispatch::method_hash!("SweepExpiredAllocations"),
     UniversalReceiverHook = frc42_dispatch::method_hash!("Receive"),
 }
 
@@ -275,20 +287,33 @@ impl Actor {
         // re-entrant calls do anything funny.
         //
         // If this fails, we'll revert and the proposals will be restored.
-        remove_data_cap_request_is_valid(
-            rt,
-            &params.verifier_request_1,
-            verifier_1_id,
-            &params.data_cap_amount_to_remove,
-            client,
-        )?;
-        remove_data_cap_request_is_valid(
+        let requests = [
+            (
+                &params.verifier_request_1,
+                remove_data_cap_payload(verifier_1_id, &params.data_cap_amount_to_remove, client)?,
+            ),
+            (
+                &params.verifier_request_2,
+                remove_data_cap_payload(verifier_2_id, &params.data_cap_amount_to_remove, client)?,
+            ),
+        ];
+        // Requests from verifiers with key addresses are verified with a single batched signature
+        // verification, and the rest with `AuthenticateMessage`.
+        let signatures_valid = verify_key_signatures(
             rt,
-            &params.verifier_request_2,
-            verifier_2_id,
-            &params.data_cap_amount_to_remove,
-            client,
+            requests.iter().map(|(request, payload)| {
+                (request.verifier, request.signature.bytes.clone(), payload.clone())
+            }),
         )?;
+        for ((request, payload), signature_valid) in requests.into_iter().zip(signatures_valid) {
+            match signature_valid {
+                Some(true) => {}
+                Some(false) => {
+                    return Err(actor_error!(illegal_argument, "proposal signature invalid"))
+                }
+                None => remove_data_cap_request_is_valid(rt, request, payload)?,
+            }
+        }
 
         // Burn the client's data cap tokens.
         let balance = balance(rt, &client).context("failed to fetch balance")?;
@@ -309,66 +334,72 @@ impl Actor {
         rt: &impl Runtime,
         params: RemoveExpiredAllocationsParams,
     ) -> Result<RemoveExpiredAllocationsReturn, ActorError> {
-        // Since the allocations are expired, this is safe to be called by anyone.
+        Ok(remove_expired_allocations_for_client(rt, params)?.0)
+    }
+
+    // Removes expired allocations as RemoveExpiredAllocations does, also returning a receipt
+    // of the allocations removed for each provider.
+    pub fn remove_expired_allocations_with_receipt(
+        rt: &impl Runtime,
+        params: RemoveExpiredAllocationsParams,
+    ) -> Result<RemoveExpiredAllocationsWithReceiptReturn, ActorError> {
+        let (removal, receipt) = remove_expired_allocations_for_client(rt, params)?;
+        Ok(RemoveExpiredAllocationsWithReceiptReturn { removal, receipt })
+    }
+
+    // Removes the expired allocations of any clients among at most `limit` allocations,
+    // examined in storage order from a cursor, and transfers their DataCap tokens back to
+    // the clients.
+    // This can be called by anyone, without knowing which allocations have expired. A sweep
+    // of all allocations continues from the returned cursor until it returns none.
+    pub fn sweep_expired_allocations(
+        rt: &impl Runtime,
+        params: SweepExpiredAllocationsParams,
+    ) -> Result<SweepExpiredAllocationsReturn, ActorError> {
         rt.validate_immediate_caller_accept_any()?;
+        if params.limit == 0 || params.limit > MAX_LIST_LIMIT {
+            return Err(actor_error!(
+                illegal_argument,
+                "sweep limit {} must be between 1 and {}",
+                params.limit,
+                MAX_LIST_LIMIT
+            ));
+        }
         let curr_epoch = rt.curr_epoch();
-        let mut batch_ret = BatchReturn::empty();
-        let mut considered = Vec::<ClaimID>::new();
-        let mut recovered_datacap = DataCap::zero();
-        let recovered_datacap = rt
+        let (removed, receipt, next_cursor) = rt
             .transaction(|st: &mut State, rt| {
                 let mut allocs = st.load_allocs(rt.store())?;
-
-                let to_remove: Vec<&AllocationID>;
-                if params.allocation_ids.is_empty() {
-                    // Find all expired allocations for the client.
-                    considered = expiration::find_expired(&mut allocs, params.client, curr_epoch)?;
-                    batch_ret = BatchReturn::ok(considered.len() as u32);
-                    to_remove = considered.iter().collect();
-                } else {
-                    considered = params.allocation_ids.clone();
-                    batch_ret = expiration::check_expired(
-                        &mut allocs,
-                        &params.allocation_ids,
-                        params.client,
-                        curr_epoch,
-                    )?;
-                    to_remove = batch_ret.successes(&params.allocation_ids);
-                }
-
-                for id in to_remove {
-                    let existing = allocs
-                        .remove(params.client, *id)
-                        .context_code(
-                            ExitCode::USR_ILLEGAL_STATE,
-                            format!("failed to remove allocation {}", id),
-                        )?
-                        .unwrap(); // Unwrapping here as both paths to here should ensure the allocation exists.
-
-                    emit::allocation_removed(rt, *id, existing.client, existing.provider)?;
-
-                    // Unwrapping here as both paths to here should ensure the allocation exists.
-                    recovered_datacap += existing.size.0;
-                }
-
+                let (found, next) = expiration::find_expired_from(
+                    &mut allocs,
+                    params.cursor.as_ref().map(|c| (c.client, c.allocation)),
+                    params.limit as usize,
+                    curr_epoch,
+                )?;
+                let receipt = remove_allocations(rt, &mut allocs, found.iter().copied())?;
                 st.save_allocs(&mut allocs)?;
-                Ok(recovered_datacap)
+                let removed = found.into_iter().map(|(_, id)| id).collect::<Vec<_>>();
+                let next_cursor =
+                    next.map(|(client, allocation)| SweepCursor { client, allocation });
+                Ok((removed, receipt, next_cursor))
             })
             .context("state transaction failed")?;
 
-        // Transfer the recovered datacap back to the client.
-        transfer(rt, params.client, &recovered_datacap).with_context(|| {
-            format!(
-                "failed to transfer recovered datacap {} back to client {}",
-                &recovered_datacap, params.client
-            )
-        })?;
+        for returned in &receipt.clients {
+            transfer(rt, returned.client, &returned.datacap).with_context(|| {
+                format!(
+                    "failed to transfer recovered datacap {} back to client {}",
+                    &returned.datacap, returned.client
+                )
+            })?;
+            emit::expired_allocations_removed(
+                rt,
+                returned.client,
+                returned.allocations,
+                &returned.datacap,
+            )?;
+        }
 
-        Ok(RemoveExpiredAllocationsReturn {
-            considered,
-            results: batch_ret,
-            datacap_recovered: recovered_datacap,
-        })
+        Ok(SweepExpiredAllocationsReturn { removed, receipt, next_cursor })
     }
 
     /// Called by storage provider actor to claim allocations for data provably committed to storage.
@@ -578,6 +609,140 @@ impl Actor {
         Ok(batch_gen.gen())
     }
 
+    // Extends the maximum terms of many claims, potentially of different providers, spending
+    // the caller's datacap only for the added term.
+    // As when spending datacap through the token receiver hook, any datacap holder may
+    // extend any claim, and the claim's client is unchanged.
+    // Each extension succeeds or fails independently. The datacap for the successful
+    // extensions is destroyed from the caller's balance, failing the whole call if insufficient.
+    pub fn extend_claim_terms_batch(
+        rt: &impl Runtime,
+        params: ExtendClaimTermsBatchParams,
+    ) -> Result<ExtendClaimTermsBatchReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let caller = rt.message().caller();
+        let curr_epoch = rt.curr_epoch();
+        let policy = rt.policy();
+
+        let st: State = rt.state()?;
+        let mut claims = st.load_claims(rt.store())?;
+        let mut batch_gen = BatchReturnGen::new(params.extensions.len());
+        let mut updated_claims = Vec::<(ClaimID, Claim)>::new();
+        let mut datacap_spent = DataCap::zero();
+        for req in &params.extensions {
+            let claim = match state::get_claim(&mut claims, req.provider, req.claim)? {
+                Some(claim) => claim.clone(),
+                None => {
+                    batch_gen.add_fail(ExitCode::USR_NOT_FOUND);
+                    info!("no claim {} for provider {}", req.claim, req.provider);
+                    continue;
+                }
+            };
+            if let Err(e) = validate_claim_extension(req, &claim, policy, curr_epoch) {
+                batch_gen.add_fail(e.exit_code());
+                info!("{}", e.msg());
+                continue;
+            }
+
+            datacap_spent += extension_datacap(&claim, req.term_max, policy);
+            let new_claim = Claim { term_max: req.term_max, ..claim };
+            // Update the working copy so that repeated requests for a claim compound.
+            claims
+                .put(req.provider, req.claim, new_claim.clone())
+                .context_code(ExitCode::USR_ILLEGAL_STATE, "HAMT put failure storing new claims")?;
+            updated_claims.push((req.claim, new_claim));
+            batch_gen.add_success();
+        }
+
+        destroy(rt, &caller, &datacap_spent)?;
+
+        rt.transaction(|st: &mut State, rt| {
+            st.put_claims(rt.store(), updated_claims.clone())?;
+            for (id, claim) in updated_claims {
+                emit::claim_updated(rt, id, claim.client, claim.provider)?;
+            }
+            Ok(())
+        })
+        .context("state transaction failed")?;
+
+        Ok(ExtendClaimTermsBatchReturn { results: batch_gen.gen(), datacap_spent })
+    }
+
+    // Changes the provider that may claim one of the caller's unexpired allocations.
+    // The allocation's data, size, terms and expiration are unchanged, and no datacap moves.
+    // The current provider must approve, since it may already have published a deal or
+    // committed a sector for the allocation.
+    pub fn retarget_allocation(
+        rt: &impl Runtime,
+        params: RetargetAllocationParams,
+    ) -> Result<(), ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let client = rt.message().caller().id().unwrap();
+        // Require the new provider to be a miner actor, as for new allocations.
+        check_miner_id(rt, params.new_provider)?;
+
+        let st: State = rt.state()?;
+        let mut allocs = st.load_allocs(rt.store())?;
+        let alloc = state::get_allocation(&mut allocs, client, params.allocation_id)?
+            .ok_or_else(|| {
+                actor_error!(
+                    not_found,
+                    "no allocation {} for client {}",
+                    params.allocation_id,
+                    client
+                )
+            })?
+            .clone();
+        if alloc.expiration < rt.curr_epoch() {
+            return Err(actor_error!(
+                forbidden,
+                "allocation {} expired at {}",
+                params.allocation_id,
+                alloc.expiration
+            ));
+        }
+        if alloc.provider == params.new_provider {
+            return Err(actor_error!(
+                illegal_argument,
+                "allocation {} is already for provider {}",
+                params.allocation_id,
+                params.new_provider
+            ));
+        }
+
+        let approval = &params.provider_approval;
+        if approval.expiration < rt.curr_epoch() {
+            return Err(actor_error!(
+                forbidden,
+                "provider approval expired at {}",
+                approval.expiration
+            ));
+        }
+        validate_signer_controls_provider(rt, &approval.signer, alloc.provider)?;
+        let payload = retarget_allocation_payload(&RetargetAllocationProposal {
+            client,
+            allocation_id: params.allocation_id,
+            provider: alloc.provider,
+            new_provider: params.new_provider,
+            expiration: approval.expiration,
+        })?;
+        authenticate_message(rt, &approval.signer, &approval.signature, payload)
+            .context("provider approval authentication failed")?;
+
+        // Authentication is read-only, so the allocation can't have changed.
+        let new_alloc = Allocation { provider: params.new_provider, ..alloc };
+        rt.transaction(|st: &mut State, rt| {
+            let mut allocs = st.load_allocs(rt.store())?;
+            allocs
+                .put(client, params.allocation_id, new_alloc)
+                .context_code(ExitCode::USR_ILLEGAL_STATE, "HAMT put failure storing allocation")?;
+            st.save_allocs(&mut allocs)?;
+            emit::allocation_updated(rt, params.allocation_id, client, params.new_provider)
+        })
+        .context("state transaction failed")?;
+        Ok(())
+    }
+
     // A claim may be removed after its maximum term has elapsed (by anyone).
     // If no claims are specified, all eligible claims are removed.
     pub fn remove_expired_claims(
@@ -589,6 +754,7 @@ impl Actor {
         let curr_epoch = rt.curr_epoch();
         let mut batch_ret = BatchReturn::empty();
         let mut considered = Vec::<ClaimID>::new();
+        let mut expired_space_by_sector = BTreeMap::<SectorNumber, u64>::new();
         rt.transaction(|st: &mut State, rt| {
             let mut claims = st.load_claims(rt.store())?;
             let to_remove: Vec<&ClaimID>;
@@ -616,6 +782,7 @@ impl Actor {
                         format!("failed to remove claim {}", id),
                     )?
                     .unwrap();
+                *expired_space_by_sector.entry(removed.sector).or_default() += removed.size.0;
 
                 emit::claim_removed(rt, *id, removed.client, removed.provider)?;
             }
@@ -625,9 +792,70 @@ impl Actor {
         })
         .context("state transaction failed")?;
 
+        // Notify the provider, which removes the claims' verified power from any sectors
+        // that outlive them. The notification is best-effort: the claims are removed even if
+        // the provider fails to handle it, so that a provider cannot keep them alive.
+        if !expired_space_by_sector.is_empty() {
+            let sectors = expired_space_by_sector
+                .into_iter()
+                .map(|(sector_number, space)| ext::miner::SectorExpiredClaimSpace {
+                    sector_number,
+                    space,
+                })
+                .collect();
+            let res = extract_send_result(rt.send_simple(
+                &Address::new_id(params.provider),
+                ext::miner::ON_VERIFIED_CLAIMS_EXPIRED_METHOD,
+                IpldBlock::serialize_cbor(&ext::miner::OnVerifiedClaimsExpiredParams { sectors })?,
+                TokenAmount::zero(),
+            ));
+            if let Err(e) = res {
+                log::warn!(
+                    "failed to notify provider {} of expired claims: {}",
+                    params.provider,
+                    e
+                );
+            }
+        }
+
         Ok(RemoveExpiredClaimsReturn { considered, results: batch_ret })
     }
 
+    // Lists a page of a client's allocations, in storage order.
+    // Pass the returned cursor back to fetch the following page.
+    pub fn list_allocations_for_client(
+        rt: &impl Runtime,
+        params: ListAllocationsForClientParams,
+    ) -> Result<ListAllocationsForClientReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        params.request.validate::<Allocation>()?;
+        let st: State = rt.state()?;
+        let mut allocs = st.load_allocs(rt.store())?;
+        let (records, next_cursor) =
+            state::list_records(&mut allocs, params.client, &params.request)?;
+        let entries = records
+            .into_iter()
+            .map(|(id, allocation)| AllocationRecord { id, allocation })
+            .collect();
+        Ok(ListAllocationsForClientReturn { entries, next_cursor })
+    }
+
+    // Lists a page of a provider's claims, in storage order.
+    // Pass the returned cursor back to fetch the following page.
+    pub fn list_claims_for_provider(
+        rt: &impl Runtime,
+        params: ListClaimsForProviderParams,
+    ) -> Result<ListClaimsForProviderReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        params.request.validate::<Claim>()?;
+        let st: State = rt.state()?;
+        let mut claims = st.load_claims(rt.store())?;
+        let (records, next_cursor) =
+            state::list_records(&mut claims, params.provider, &params.request)?;
+        let entries = records.into_iter().map(|(id, claim)| ClaimRecord { id, claim }).collect();
+        Ok(ListClaimsForProviderReturn { entries, next_cursor })
+    }
+
     // Receives data cap tokens (only) and creates allocations according to one or more
     // allocation requests specified in the transfer's operator data.
     // The token amount received must exactly correspond to the sum of the requested allocation sizes.
@@ -688,9 +916,10 @@ impl Actor {
             validate_claim_extension(req, claim, policy, curr_epoch)?;
             // The claim's client is not changed to be the address of the token sender.
             // It remains the original allocation client.
+            let cost = extension_datacap(claim, req.term_max, policy);
             updated_claims.push((req.claim, Claim { term_max: req.term_max, ..*claim }));
-            datacap_total += DataCap::from(claim.size.0);
-            extension_total += DataCap::from(claim.size.0);
+            datacap_total += &cost;
+            extension_total += cost;
         }
 
         // Allocation size must match the tokens received exactly (we don't return change).
@@ -814,6 +1043,116 @@ fn destroy(rt: &impl Runtime, owner: &Address, amount: &DataCap) -> Result<(), A
     Ok(())
 }
 
+// Removes the expired allocations of a client, or those specified, and transfers their DataCap
+// tokens back to the client.
+// Returns the result along with a receipt of the allocations removed.
+fn remove_expired_allocations_for_client(
+    rt: &impl Runtime,
+    params: RemoveExpiredAllocationsParams,
+) -> Result<(RemoveExpiredAllocationsReturn, RemovalReceipt), ActorError> {
+    // Since the allocations are expired, this is safe to be called by anyone.
+    rt.validate_immediate_caller_accept_any()?;
+    let curr_epoch = rt.curr_epoch();
+    let mut batch_ret = BatchReturn::empty();
+    let mut considered = Vec::<ClaimID>::new();
+    let receipt = rt
+        .transaction(|st: &mut State, rt| {
+            let mut allocs = st.load_allocs(rt.store())?;
+
+            let to_remove: Vec<&AllocationID>;
+            if params.allocation_ids.is_empty() {
+                // Find all expired allocations for the client.
+                considered = expiration::find_expired(&mut allocs, params.client, curr_epoch)?;
+                batch_ret = BatchReturn::ok(considered.len() as u32);
+                to_remove = considered.iter().collect();
+            } else {
+                considered = params.allocation_ids.clone();
+                batch_ret = expiration::check_expired(
+                    &mut allocs,
+                    &params.allocation_ids,
+                    params.client,
+                    curr_epoch,
+                )?;
+                to_remove = batch_ret.successes(&params.allocation_ids);
+            }
+
+            // Both paths to here ensure the allocations exist.
+            let receipt = remove_allocations(
+                rt,
+                &mut allocs,
+                to_remove.into_iter().map(|id| (params.client, *id)),
+            )?;
+            st.save_allocs(&mut allocs)?;
+            Ok(receipt)
+        })
+        .context("state transaction failed")?;
+
+    // Transfer the recovered datacap back to the client.
+    let recovered_datacap = receipt.clients.first().map(|c| c.datacap.clone()).unwrap_or_default();
+    transfer(rt, params.client, &recovered_datacap).with_context(|| {
+        format!(
+            "failed to transfer recovered datacap {} back to client {}",
+            &recovered_datacap, params.client
+        )
+    })?;
+    for returned in &receipt.clients {
+        emit::expired_allocations_removed(
+            rt,
+            returned.client,
+            returned.allocations,
+            &returned.datacap,
+        )?;
+    }
+
+    let ret = RemoveExpiredAllocationsReturn {
+        considered,
+        results: batch_ret,
+        datacap_recovered: recovered_datacap,
+    };
+    Ok((ret, receipt))
+}
+
+// Removes allocations, which must exist, emitting an event for each.
+// Returns a receipt totalling the datacap to be returned to each client.
+fn remove_allocations<BS: Blockstore>(
+    rt: &impl Runtime,
+    allocs: &mut MapMap<BS, Allocation, ActorID, AllocationID>,
+    to_remove: impl IntoIterator<Item = (ActorID, AllocationID)>,
+) -> Result<RemovalReceipt, ActorError> {
+    let mut by_client = BTreeMap::<ActorID, (u64, DataCap)>::new();
+    let mut by_provider = BTreeMap::<ActorID, u64>::new();
+    for (client, id) in to_remove {
+        let existing = allocs
+            .remove(client, id)
+            .context_code(
+                ExitCode::USR_ILLEGAL_STATE,
+                format!("failed to remove allocation {}", id),
+            )?
+            .ok_or_else(|| actor_error!(illegal_state, "allocation {} not found", id))?;
+
+        emit::allocation_removed(rt, id, existing.client, existing.provider)?;
+
+        let returned = by_client.entry(client).or_insert_with(|| (0, DataCap::zero()));
+        returned.0 += 1;
+        returned.1 += existing.size.0;
+        *by_provider.entry(existing.provider).or_default() += 1;
+    }
+    Ok(RemovalReceipt {
+        clients: by_client
+            .into_iter()
+            .map(|(client, (allocations, datacap))| ClientDataCapReturned {
+                client,
+                allocations,
+                datacap,
+            })
+            .collect(),
+        providers: by_provider
+            .into_iter()
+            .map(|(provider, allocations)| ProviderAllocationsRemoved { provider, allocations })
+            .collect(),
+    })
+}
+
 // Invokes transfer on a data cap token actor for whole units of data cap.
 fn transfer(rt: &impl Runtime, to: ActorID, amount: &DataCap) -> Result<(), ActorError> {
     let token_amt = datacap_to_tokens(amount);
@@ -865,13 +1204,12 @@ where
     Ok(curr_id)
 }
 
-fn remove_data_cap_request_is_valid(
-    rt: &impl Runtime,
-    request: &RemoveDataCapRequest,
+// Returns the payload a verifier signs to request removal of a client's data cap.
+fn remove_data_cap_payload(
     id: RemoveDataCapProposalID,
     to_remove: &DataCap,
     client: Address,
-) -> Result<(), ActorError> {
+) -> Result<Vec<u8>, ActorError> {
     let proposal = RemoveDataCapProposal {
         removal_proposal_id: id,
         data_cap_amount: to_remove.clone(),
@@ -883,28 +1221,80 @@ fn remove_data_cap_request_is_valid(
                 serialization; "failed to marshal remove datacap request: {}", e)
     })?;
 
-    let payload = [SIGNATURE_DOMAIN_SEPARATION_REMOVE_DATA_CAP, b.bytes()].concat();
+    Ok([SIGNATURE_DOMAIN_SEPARATION_REMOVE_DATA_CAP, b.bytes()].concat())
+}
 
+fn remove_data_cap_request_is_valid(
+    rt: &impl Runtime,
+    request: &RemoveDataCapRequest,
+    payload: Vec<u8>,
+) -> Result<(), ActorError> {
+    authenticate_message(rt, &request.verifier, &request.signature, payload)
+        .context("proposal authentication failed")
+}
+
+// Returns the payload a provider signs to approve retargeting one of its allocations.
+fn retarget_allocation_payload(
+    proposal: &RetargetAllocationProposal,
+) -> Result<Vec<u8>, ActorError> {
+    let b = RawBytes::serialize(proposal).map_err(
+        |e| actor_error!(serialization; "failed to marshal retarget allocation proposal: {}", e),
+    )?;
+    Ok([SIGNATURE_DOMAIN_SEPARATION_RETARGET_ALLOCATION, b.bytes()].concat())
+}
+
+// Checks that the signer authenticates the payload, through its AuthenticateMessage method.
+fn authenticate_message(
+    rt: &impl Runtime,
+    signer: &Address,
+    signature: &Signature,
+    payload: Vec<u8>,
+) -> Result<(), ActorError> {
     if !extract_send_result(rt.send(
-        &request.verifier,
+        signer,
         ext::account::AUTHENTICATE_MESSAGE_METHOD,
         IpldBlock::serialize_cbor(&ext::account::AuthenticateMessageParams {
-            signature: request.signature.bytes.clone(),
+            signature: signature.bytes.clone(),
             message: payload,
         })?,
         TokenAmount::zero(),
         None,
         SendFlags::READ_ONLY,
     ))
-    .and_then(deserialize_block)
-    .context("proposal authentication failed")?
+    .and_then(deserialize_block)?
     {
-        Err(actor_error!(illegal_argument, "proposal authentication failed"))
+        Err(actor_error!(illegal_argument, "signature authentication failed"))
     } else {
         Ok(())
     }
 }
 
+// Checks that an address is the owner, worker or a control address of a miner.
+fn validate_signer_controls_provider(
+    rt: &impl Runtime,
+    signer: &Address,
+    provider: ActorID,
+) -> Result<(), ActorError> {
+    let status: ext::miner::IsControllingAddressReturn =
+        deserialize_block(extract_send_result(rt.send(
+            &Address::new_id(provider),
+            ext::miner::IS_CONTROLLING_ADDRESS_EXPORTED,
+            IpldBlock::serialize_cbor(&ext::miner::IsControllingAddressParam { address: *signer })?,
+            TokenAmount::zero(),
+            None,
+            SendFlags::READ_ONLY,
+        ))?)?;
+    if !status.is_controlling {
+        return Err(actor_error!(
+            forbidden,
+            "{} is not the owner, worker or a control address of provider {}",
+            signer,
+            provider
+        ));
+    }
+    Ok(())
+}
+
 // Deserializes and validates a receiver hook payload, expecting only an FRC-46 transfer.
 fn validate_tokens_received(
     params: &UniversalReceiverParams,
@@ -1045,6 +1435,16 @@ fn validate_claim_extension(
     Ok(())
 }
 
+/// Computes the datacap spent to extend a claim's maximum term to `term_max`, whether through
+/// ExtendClaimTermsBatch or by transferring datacap with extension requests.
+/// This is the claim's size pro-rated by the added term over the maximum allocation term,
+/// rounded up, so extending by the maximum term costs the full size.
+pub fn extension_datacap(claim: &Claim, term_max: ChainEpoch, policy: &Policy) -> DataCap {
+    let term_delta = term_max - claim.term_max;
+    let max_term = policy.maximum_verified_allocation_term;
+    (DataCap::from(claim.size.0) * term_delta + max_term - 1) / max_term
+}
+
 // Checks that an address corresponsds to a miner actor.
 fn check_miner_id(rt: &impl Runtime, id: ActorID) -> Result<(), ActorError> {
     let code_cid =
@@ -1052,12 +1452,8 @@ fn check_miner_id(rt: &impl Runtime, id: ActorID) -> Result<(), ActorError> {
             format!("no code CID for provider {}", id)
         })?;
 
-    let provider_type = rt
-        .resolve_builtin_actor_type(&code_cid)
-        .with_context_code(ExitCode::USR_ILLEGAL_ARGUMENT, || {
-            format!("provider code {} must be built-in miner actor", code_cid)
-        })?;
-    if provider_type != Type::Miner {
+    let provider_type = rt.resolve_builtin_actor_type_checked(&code_cid);
+    if !provider_type.is_storage_provider() {
         return Err(actor_error!(
             illegal_argument,
             "allocation provider {} must be a miner actor, was {:?}",
@@ -1092,6 +1488,11 @@ impl ActorCode for Actor {
         "VerifiedRegistry"
     }
 
+    fil_actors_runtime::actor_info! {
+        interface_revisions: [1],
+        features: [],
+    }
+
     actor_dispatch! {
         Constructor => constructor,
         AddVerifier => add_verifier,
@@ -1103,6 +1504,12 @@ impl ActorCode for Actor {
         GetClaims|GetClaimsExported => get_claims,
         ExtendClaimTerms|ExtendClaimTermsExported => extend_claim_terms,
         RemoveExpiredClaims|RemoveExpiredClaimsExported => remove_expired_claims,
+        ListAllocationsForClientExported => list_allocations_for_client,
+        ListClaimsForProviderExported => list_claims_for_provider,
+        ExtendClaimTermsBatchExported => extend_claim_terms_batch,
+        RetargetAllocationExported => retarget_allocation,
+        RemoveExpiredAllocationsWithReceiptExported => remove_expired_allocations_with_receipt,
+        SweepExpiredAllocationsExported => sweep_expired_allocations,
         UniversalReceiverHook => universal_receiver_hook,
     }
 }
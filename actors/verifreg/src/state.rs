@@ -12,9 +12,13 @@ use fvm_shared::piece::PaddedPieceSize;
 use fvm_shared::sector::SectorNumber;
 use fvm_shared::{ActorID, HAMT_BIT_WIDTH};
 
+use fil_actors_runtime::query::{Indexed, ListRequest};
 use fil_actors_runtime::{
-    actor_error, ActorError, AsActorError, Config, Map2, MapMap, DEFAULT_HAMT_CONFIG,
+    actor_error, parse_uint_key, ActorError, AsActorError, Config, Map2, MapMap,
+    DEFAULT_HAMT_CONFIG,
 };
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 
 use crate::{AddrPairKey, AllocationID, ClaimID};
 use crate::{DataCap, RemoveDataCapProposalID};
@@ -237,6 +241,39 @@ pub struct Allocation {
     pub expiration: ChainEpoch,
 }
 
+impl Indexed for Claim {
+    const INDEXED_FIELDS: &'static [&'static str] =
+        &["client", "size", "term-min", "term-max", "term-start", "sector"];
+
+    fn field(&self, name: &str) -> Option<i128> {
+        match name {
+            "client" => Some(self.client.into()),
+            "size" => Some(self.size.0.into()),
+            "term-min" => Some(self.term_min.into()),
+            "term-max" => Some(self.term_max.into()),
+            "term-start" => Some(self.term_start.into()),
+            "sector" => Some(self.sector.into()),
+            _ => None,
+        }
+    }
+}
+
+impl Indexed for Allocation {
+    const INDEXED_FIELDS: &'static [&'static str] =
+        &["provider", "size", "term-min", "term-max", "expiration"];
+
+    fn field(&self, name: &str) -> Option<i128> {
+        match name {
+            "provider" => Some(self.provider.into()),
+            "size" => Some(self.size.0.into()),
+            "term-min" => Some(self.term_min.into()),
+            "term-max" => Some(self.term_max.into()),
+            "expiration" => Some(self.expiration.into()),
+            _ => None,
+        }
+    }
+}
+
 pub fn get_allocation<'a, BS>(
     allocations: &'a mut MapMap<BS, Allocation, ActorID, AllocationID>,
     client: ActorID,
@@ -262,3 +299,34 @@ where
         .get(provider, id)
         .context_code(ExitCode::USR_ILLEGAL_STATE, "HAMT lookup failure getting claim")
 }
+
+// Lists the records matching a request's filter among the next page of records in a collection
+// for some owner, in storage order.
+// Returns the records with their keys, and the key at which to resume if any records remain.
+// A cursor whose record has since been removed resumes from the record that followed it.
+pub fn list_records<T, BS>(
+    collection: &mut MapMap<BS, T, ActorID, u64>,
+    owner: ActorID,
+    request: &ListRequest<u64>,
+) -> Result<(Vec<(u64, T)>, Option<u64>), ActorError>
+where
+    T: Serialize + DeserializeOwned + Clone + PartialEq + Indexed,
+    BS: Blockstore,
+{
+    let mut records = Vec::new();
+    let (_, next) = collection
+        .for_each_in_ranged(owner, request.cursor, Some(request.limit as usize), |key, record| {
+            if request.matches(record) {
+                let id = parse_uint_key(key)
+                    .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to parse uint key")?;
+                records.push((id, record.clone()));
+            }
+            Ok(())
+        })
+        .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to iterate over allocations/claims")?;
+    let next = next
+        .map(|k| parse_uint_key(&k))
+        .transpose()
+        .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to parse uint key")?;
+    Ok((records, next))
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 use cid::Cid;
+use fil_actors_runtime::query::{ListPage, ListRequest};
 use fil_actors_runtime::{BatchReturn, MapKey};
 use fvm_ipld_encoding::tuple::*;
 use fvm_shared::address::Address;
@@ -14,7 +15,7 @@ use fvm_shared::sector::StoragePower;
 use fvm_shared::ActorID;
 use std::fmt::{Debug, Formatter};
 
-use crate::Claim;
+use crate::{Allocation, Claim};
 
 pub type AllocationID = u64;
 pub type ClaimID = u64;
@@ -47,6 +48,7 @@ pub struct RemoveVerifierParams {
 pub type DataCap = StoragePower;
 
 pub const SIGNATURE_DOMAIN_SEPARATION_REMOVE_DATA_CAP: &[u8] = b"fil_removedatacap:";
+pub const SIGNATURE_DOMAIN_SEPARATION_RETARGET_ALLOCATION: &[u8] = b"fil_retargetallocation:";
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
 pub struct RemoveDataCapParams {
@@ -133,6 +135,65 @@ pub struct RemoveExpiredAllocationsReturn {
     pub datacap_recovered: DataCap,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct RemoveExpiredAllocationsWithReceiptReturn {
+    // The result, as returned by RemoveExpiredAllocations.
+    pub removal: RemoveExpiredAllocationsReturn,
+    // The work done removing the allocations.
+    pub receipt: RemovalReceipt,
+}
+
+/// A position in a sweep over the allocations of every client, in storage order.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct SweepCursor {
+    pub client: ActorID,
+    // Allocation of the client at which to resume, or None to start from the client's first.
+    pub allocation: Option<AllocationID>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct SweepExpiredAllocationsParams {
+    // Position at which to resume a previous sweep, or None to start from the beginning.
+    pub cursor: Option<SweepCursor>,
+    // Maximum number of allocations to examine, whether or not they've expired.
+    pub limit: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct SweepExpiredAllocationsReturn {
+    // Ids of the expired allocations removed, in the order examined.
+    pub removed: Vec<AllocationID>,
+    // The work done removing the allocations.
+    pub receipt: RemovalReceipt,
+    // Position at which to continue the sweep, or None if it reached the end.
+    pub next_cursor: Option<SweepCursor>,
+}
+
+/// A summary of the expired allocations removed by a call, for those paying for cleanup.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct RemovalReceipt {
+    // DataCap returned to each client, in client ID order.
+    pub clients: Vec<ClientDataCapReturned>,
+    // Allocations removed for each provider, in provider ID order.
+    pub providers: Vec<ProviderAllocationsRemoved>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ClientDataCapReturned {
+    pub client: ActorID,
+    // Number of the client's allocations removed.
+    pub allocations: u64,
+    #[serde(with = "bigint_ser")]
+    pub datacap: DataCap,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ProviderAllocationsRemoved {
+    pub provider: ActorID,
+    // Number of allocations to the provider removed.
+    pub allocations: u64,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
 pub struct SectorAllocationClaims {
     pub sector: SectorNumber,
@@ -187,6 +248,21 @@ pub struct ExtendClaimTermsParams {
 
 pub type ExtendClaimTermsReturn = BatchReturn;
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ExtendClaimTermsBatchParams {
+    // Claims to extend, which may belong to any providers.
+    pub extensions: Vec<ClaimExtensionRequest>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ExtendClaimTermsBatchReturn {
+    // Result for each extension request.
+    pub results: BatchReturn,
+    // The amount of the caller's datacap spent on the successful extensions.
+    #[serde(with = "bigint_ser")]
+    pub datacap_spent: DataCap,
+}
+
 //
 // Receiver hook payload
 //
@@ -258,3 +334,61 @@ pub struct RemoveExpiredClaimsReturn {
     // Results for each processed claim.
     pub results: BatchReturn,
 }
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct RetargetAllocationParams {
+    // The caller's allocation to retarget.
+    pub allocation_id: AllocationID,
+    // The miner actor that may claim the allocation instead.
+    pub new_provider: ActorID,
+    // The current provider's consent to give up the allocation.
+    pub provider_approval: RetargetAllocationApproval,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct RetargetAllocationApproval {
+    // The owner, worker or a control address of the current provider.
+    pub signer: Address,
+    // The signer's signature over the domain-separated RetargetAllocationProposal.
+    pub signature: Signature,
+    // The last epoch at which the approval may be used.
+    pub expiration: ChainEpoch,
+}
+
+// The payload signed by the current provider of an allocation to approve its retargeting.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct RetargetAllocationProposal {
+    pub client: ActorID,
+    pub allocation_id: AllocationID,
+    pub provider: ActorID,
+    pub new_provider: ActorID,
+    pub expiration: ChainEpoch,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ListAllocationsForClientParams {
+    pub client: ActorID,
+    pub request: ListRequest<AllocationID>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct AllocationRecord {
+    pub id: AllocationID,
+    pub allocation: Allocation,
+}
+
+pub type ListAllocationsForClientReturn = ListPage<AllocationRecord, AllocationID>;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ListClaimsForProviderParams {
+    pub provider: ActorID,
+    pub request: ListRequest<ClaimID>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ClaimRecord {
+    pub id: ClaimID,
+    pub claim: Claim,
+}
+
+pub type ListClaimsForProviderReturn = ListPage<ClaimRecord, ClaimID>;
@@ -1,5 +1,5 @@
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use frc46_token::receiver::{FRC46TokenReceived, FRC46_TOKEN_TYPE};
 use frc46_token::token::types::{BurnParams, BurnReturn, TransferParams};
@@ -10,6 +10,7 @@ use fvm_ipld_encoding::RawBytes;
 use fvm_shared::address::Address;
 use fvm_shared::bigint::bigint_ser::BigIntSer;
 use fvm_shared::clock::ChainEpoch;
+use fvm_shared::crypto::signature::Signature;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
 use fvm_shared::piece::PaddedPieceSize;
@@ -24,11 +25,18 @@ use fil_actor_verifreg::{
     ext, Actor as VerifregActor, AddVerifiedClientParams, AddVerifierParams, Allocation,
     AllocationClaim, AllocationID, AllocationRequest, AllocationRequests, AllocationsResponse,
     Claim, ClaimAllocationsParams, ClaimAllocationsReturn, ClaimExtensionRequest, ClaimID, DataCap,
-    ExtendClaimTermsParams, ExtendClaimTermsReturn, GetClaimsParams, GetClaimsReturn, Method,
-    RemoveExpiredAllocationsParams, RemoveExpiredAllocationsReturn, RemoveExpiredClaimsParams,
-    RemoveExpiredClaimsReturn, SectorAllocationClaims, State,
+    ExtendClaimTermsBatchParams, ExtendClaimTermsBatchReturn, ExtendClaimTermsParams,
+    ExtendClaimTermsReturn, GetClaimsParams, GetClaimsReturn, ListAllocationsForClientParams,
+    ListAllocationsForClientReturn, ListClaimsForProviderParams, ListClaimsForProviderReturn,
+    Method, RemoveExpiredAllocationsParams, RemoveExpiredAllocationsReturn,
+    RemoveExpiredAllocationsWithReceiptReturn, RemoveExpiredClaimsParams,
+    RemoveExpiredClaimsReturn, RetargetAllocationApproval, RetargetAllocationParams,
+    RetargetAllocationProposal, SectorAllocationClaims, State, SweepCursor,
+    SweepExpiredAllocationsParams, SweepExpiredAllocationsReturn,
+    SIGNATURE_DOMAIN_SEPARATION_RETARGET_ALLOCATION,
 };
 use fil_actors_runtime::cbor::serialize;
+use fil_actors_runtime::query::ListRequest;
 use fil_actors_runtime::runtime::builtins::Type;
 use fil_actors_runtime::runtime::policy_constants::{
     MAXIMUM_VERIFIED_ALLOCATION_TERM, MINIMUM_VERIFIED_ALLOCATION_TERM,
@@ -36,7 +44,7 @@ use fil_actors_runtime::runtime::policy_constants::{
 use fil_actors_runtime::runtime::Runtime;
 use fil_actors_runtime::test_utils::*;
 use fil_actors_runtime::{
-    ActorError, AsActorError, BatchReturn, EventBuilder, DATACAP_TOKEN_ACTOR_ADDR,
+    parse_uint_key, ActorError, AsActorError, BatchReturn, EventBuilder, DATACAP_TOKEN_ACTOR_ADDR,
     STORAGE_MARKET_ACTOR_ADDR, SYSTEM_ACTOR_ADDR, VERIFIED_REGISTRY_ACTOR_ADDR,
 };
 
@@ -48,6 +56,8 @@ const TEST_CLIENT_ADDR: u64 = 301;
 const TEST_CLIENT2_ADDR: u64 = 302;
 const TEST_CLIENT3_ADDR: u64 = 303;
 const TEST_CLIENT4_ADDR: u64 = 304;
+// A controlling address of the provider in retargeted allocations.
+pub const PROVIDER_SIGNER: Address = Address::new_id(401);
 
 pub fn new_runtime() -> MockRuntime {
     let test_verifier_addr = Address::new_id(TEST_VERIFIER_ADDR);
@@ -334,14 +344,65 @@ impl Harness {
         allocation_ids: Vec<AllocationID>,
         expect_removed: Vec<(AllocationID, Allocation)>,
     ) -> Result<RemoveExpiredAllocationsReturn, ActorError> {
+        self.expect_remove_expired_allocations(rt, client, expect_removed);
+        let params = RemoveExpiredAllocationsParams { client, allocation_ids };
+        let ret = rt
+            .call::<VerifregActor>(
+                Method::RemoveExpiredAllocations as MethodNum,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            )?
+            .unwrap()
+            .deserialize()
+            .expect("failed to deserialize remove expired allocations return");
+        rt.verify();
+        Ok(ret)
+    }
+
+    // Invokes the RemoveExpiredAllocationsWithReceipt actor method.
+    pub fn remove_expired_allocations_with_receipt(
+        &self,
+        rt: &MockRuntime,
+        client: ActorID,
+        allocation_ids: Vec<AllocationID>,
+        expect_removed: Vec<(AllocationID, Allocation)>,
+    ) -> Result<RemoveExpiredAllocationsWithReceiptReturn, ActorError> {
+        self.expect_remove_expired_allocations(rt, client, expect_removed);
+        let params = RemoveExpiredAllocationsParams { client, allocation_ids };
+        let ret = rt
+            .call::<VerifregActor>(
+                Method::RemoveExpiredAllocationsWithReceiptExported as MethodNum,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            )?
+            .unwrap()
+            .deserialize()
+            .expect("failed to deserialize remove expired allocations with receipt return");
+        rt.verify();
+        Ok(ret)
+    }
+
+    fn expect_remove_expired_allocations(
+        &self,
+        rt: &MockRuntime,
+        client: ActorID,
+        expect_removed: Vec<(AllocationID, Allocation)>,
+    ) {
         rt.expect_validate_caller_any();
 
         let mut expected_datacap = 0u64;
+        let expected_count = expect_removed.len() as u64;
         for (id, alloc) in expect_removed {
             expected_datacap += alloc.size.0;
 
             expect_emitted(rt, "allocation-removed", &id, alloc.client, alloc.provider);
         }
+        if expected_count > 0 {
+            expect_expired_allocations_removed(
+                rt,
+                client,
+                expected_count,
+                &DataCap::from(expected_datacap),
+            );
+        }
         rt.expect_send_simple(
             DATACAP_TOKEN_ACTOR_ADDR,
             ext::datacap::Method::Transfer as MethodNum,
@@ -355,20 +416,71 @@ impl Harness {
             None,
             ExitCode::OK,
         );
+    }
 
-        let params = RemoveExpiredAllocationsParams { client, allocation_ids };
-        let ret = rt
+    // Invokes the SweepExpiredAllocations actor method.
+    // The expected removals are given in the order they're expected to be examined.
+    pub fn sweep_expired_allocations(
+        &self,
+        rt: &MockRuntime,
+        cursor: Option<SweepCursor>,
+        limit: u64,
+        expect_removed: Vec<(AllocationID, Allocation)>,
+    ) -> Result<SweepExpiredAllocationsReturn, ActorError> {
+        rt.expect_validate_caller_any();
+
+        let mut expected_by_client = BTreeMap::<ActorID, (u64, u64)>::new();
+        for (id, alloc) in &expect_removed {
+            expect_emitted(rt, "allocation-removed", id, alloc.client, alloc.provider);
+            let returned = expected_by_client.entry(alloc.client).or_default();
+            returned.0 += 1;
+            returned.1 += alloc.size.0;
+        }
+        for (client, (count, datacap)) in expected_by_client {
+            rt.expect_send_simple(
+                DATACAP_TOKEN_ACTOR_ADDR,
+                ext::datacap::Method::Transfer as MethodNum,
+                IpldBlock::serialize_cbor(&TransferParams {
+                    to: Address::new_id(client),
+                    amount: TokenAmount::from_whole(datacap.to_i64().unwrap()),
+                    operator_data: RawBytes::default(),
+                })
+                .unwrap(),
+                TokenAmount::zero(),
+                None,
+                ExitCode::OK,
+            );
+            expect_expired_allocations_removed(rt, client, count, &DataCap::from(datacap));
+        }
+
+        let params = SweepExpiredAllocationsParams { cursor, limit };
+        let ret: SweepExpiredAllocationsReturn = rt
             .call::<VerifregActor>(
-                Method::RemoveExpiredAllocations as MethodNum,
+                Method::SweepExpiredAllocationsExported as MethodNum,
                 IpldBlock::serialize_cbor(&params).unwrap(),
             )?
             .unwrap()
             .deserialize()
-            .expect("failed to deserialize remove expired allocations return");
+            .expect("failed to deserialize sweep expired allocations return");
         rt.verify();
+        assert_eq!(expect_removed.iter().map(|(id, _)| *id).collect::<Vec<_>>(), ret.removed);
         Ok(ret)
     }
 
+    // Returns every allocation, in the order a sweep examines them.
+    pub fn allocations_in_sweep_order(&self, rt: &MockRuntime) -> Vec<(AllocationID, Allocation)> {
+        let st: State = rt.get_state();
+        let mut allocs = st.load_allocs(rt.store()).unwrap();
+        let mut found = Vec::new();
+        allocs
+            .for_each_ranged(None, usize::MAX, |_, key, alloc| {
+                found.push((parse_uint_key(key).unwrap(), alloc.clone()));
+                Ok(())
+            })
+            .unwrap();
+        found
+    }
+
     // Invokes the RemoveExpiredClaims actor method.
     pub fn remove_expired_claims(
         &self,
@@ -376,11 +488,44 @@ impl Harness {
         provider: ActorID,
         claim_ids: Vec<ClaimID>,
         expect_removed: Vec<(ClaimID, Claim)>,
+    ) -> Result<RemoveExpiredClaimsReturn, ActorError> {
+        self.remove_expired_claims_notified(rt, provider, claim_ids, expect_removed, ExitCode::OK)
+    }
+
+    // Invokes the RemoveExpiredClaims actor method, with the provider's handling of the
+    // notification of expired claims exiting with `notify_exit`.
+    pub fn remove_expired_claims_notified(
+        &self,
+        rt: &MockRuntime,
+        provider: ActorID,
+        claim_ids: Vec<ClaimID>,
+        expect_removed: Vec<(ClaimID, Claim)>,
+        notify_exit: ExitCode,
     ) -> Result<RemoveExpiredClaimsReturn, ActorError> {
         rt.expect_validate_caller_any();
 
+        let mut expired_space_by_sector = BTreeMap::<SectorNumber, u64>::new();
         for (id, claim) in expect_removed {
             expect_emitted(rt, "claim-removed", &id, claim.client, claim.provider);
+            *expired_space_by_sector.entry(claim.sector).or_default() += claim.size.0;
+        }
+        if !expired_space_by_sector.is_empty() {
+            let sectors = expired_space_by_sector
+                .into_iter()
+                .map(|(sector_number, space)| ext::miner::SectorExpiredClaimSpace {
+                    sector_number,
+                    space,
+                })
+                .collect();
+            rt.expect_send_simple(
+                Address::new_id(provider),
+                ext::miner::ON_VERIFIED_CLAIMS_EXPIRED_METHOD,
+                IpldBlock::serialize_cbor(&ext::miner::OnVerifiedClaimsExpiredParams { sectors })
+                    .unwrap(),
+                TokenAmount::zero(),
+                None,
+                notify_exit,
+            );
         }
         let params = RemoveExpiredClaimsParams { provider, claim_ids };
         let ret = rt
@@ -491,6 +636,189 @@ impl Harness {
         Ok(ret)
     }
 
+    // Invokes ExtendClaimTermsBatch as a client, expecting the client's datacap to be destroyed.
+    pub fn extend_claim_terms_batch(
+        &self,
+        rt: &MockRuntime,
+        client: ActorID,
+        extensions: Vec<ClaimExtensionRequest>,
+        expect_spent: u64,
+        expect_updated: Vec<(ClaimID, Claim)>,
+    ) -> Result<ExtendClaimTermsBatchReturn, ActorError> {
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, Address::new_id(client));
+        rt.expect_validate_caller_any();
+        if expect_spent > 0 {
+            rt.expect_send_simple(
+                DATACAP_TOKEN_ACTOR_ADDR,
+                ext::datacap::Method::Destroy as MethodNum,
+                IpldBlock::serialize_cbor(&ext::datacap::DestroyParams {
+                    owner: Address::new_id(client),
+                    amount: TokenAmount::from_whole(expect_spent),
+                })
+                .unwrap(),
+                TokenAmount::zero(),
+                IpldBlock::serialize_cbor(&BurnReturn { balance: TokenAmount::zero() }).unwrap(),
+                ExitCode::OK,
+            );
+        }
+        for (id, claim) in expect_updated.iter() {
+            expect_emitted(rt, "claim-updated", id, claim.client, claim.provider);
+        }
+
+        let params = ExtendClaimTermsBatchParams { extensions };
+        let ret: ExtendClaimTermsBatchReturn = rt
+            .call::<VerifregActor>(
+                Method::ExtendClaimTermsBatchExported as MethodNum,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            )?
+            .unwrap()
+            .deserialize()
+            .expect("failed to deserialize extend claim terms batch return");
+        rt.verify();
+        assert_eq!(DataCap::from(expect_spent), ret.datacap_spent);
+        Ok(ret)
+    }
+
+    // Retargets an allocation from its current provider, which approves through a signer
+    // that controls it.
+    pub fn retarget_allocation(
+        &self,
+        rt: &MockRuntime,
+        client: ActorID,
+        allocation_id: AllocationID,
+        provider: ActorID,
+        new_provider: ActorID,
+    ) -> Result<(), ActorError> {
+        self.retarget_allocation_approved(
+            rt,
+            client,
+            allocation_id,
+            provider,
+            new_provider,
+            true,
+            true,
+        )
+    }
+
+    // Retargets an allocation, with the provider's approval signed by PROVIDER_SIGNER.
+    // The provider reports whether the signer controls it, and the signer whether the
+    // approval is authentic; approvals are only checked for allocations that may be retargeted.
+    #[allow(clippy::too_many_arguments)]
+    pub fn retarget_allocation_approved(
+        &self,
+        rt: &MockRuntime,
+        client: ActorID,
+        allocation_id: AllocationID,
+        provider: ActorID,
+        new_provider: ActorID,
+        is_controlling: bool,
+        authentic: bool,
+    ) -> Result<(), ActorError> {
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, Address::new_id(client));
+        rt.expect_validate_caller_any();
+        let approval = RetargetAllocationApproval {
+            signer: PROVIDER_SIGNER,
+            signature: Signature::new_secp256k1(b"approval".to_vec()),
+            expiration: *rt.epoch.borrow(),
+        };
+        rt.expect_send(
+            Address::new_id(provider),
+            ext::miner::IS_CONTROLLING_ADDRESS_EXPORTED,
+            IpldBlock::serialize_cbor(&ext::miner::IsControllingAddressParam {
+                address: PROVIDER_SIGNER,
+            })
+            .unwrap(),
+            TokenAmount::zero(),
+            None,
+            SendFlags::READ_ONLY,
+            IpldBlock::serialize_cbor(&ext::miner::IsControllingAddressReturn { is_controlling })
+                .unwrap(),
+            ExitCode::OK,
+            None,
+        );
+        if is_controlling {
+            let proposal = RetargetAllocationProposal {
+                client,
+                allocation_id,
+                provider,
+                new_provider,
+                expiration: approval.expiration,
+            };
+            let payload = [
+                SIGNATURE_DOMAIN_SEPARATION_RETARGET_ALLOCATION,
+                RawBytes::serialize(proposal).unwrap().bytes(),
+            ]
+            .concat();
+            rt.expect_send(
+                PROVIDER_SIGNER,
+                ext::account::AUTHENTICATE_MESSAGE_METHOD,
+                IpldBlock::serialize_cbor(&ext::account::AuthenticateMessageParams {
+                    signature: approval.signature.bytes.clone(),
+                    message: payload,
+                })
+                .unwrap(),
+                TokenAmount::zero(),
+                None,
+                SendFlags::READ_ONLY,
+                IpldBlock::serialize_cbor(&authentic).unwrap(),
+                ExitCode::OK,
+                None,
+            );
+        }
+        if is_controlling && authentic {
+            expect_emitted(rt, "allocation-updated", &allocation_id, client, new_provider);
+        }
+
+        let params =
+            RetargetAllocationParams { allocation_id, new_provider, provider_approval: approval };
+        rt.call::<VerifregActor>(
+            Method::RetargetAllocationExported as MethodNum,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        )?;
+        rt.verify();
+        Ok(())
+    }
+
+    pub fn list_allocations_for_client(
+        &self,
+        rt: &MockRuntime,
+        client: ActorID,
+        request: ListRequest<AllocationID>,
+    ) -> Result<ListAllocationsForClientReturn, ActorError> {
+        rt.expect_validate_caller_any();
+        let params = ListAllocationsForClientParams { client, request };
+        let ret = rt
+            .call::<VerifregActor>(
+                Method::ListAllocationsForClientExported as MethodNum,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            )?
+            .unwrap()
+            .deserialize()
+            .expect("failed to deserialize list allocations return");
+        rt.verify();
+        Ok(ret)
+    }
+
+    pub fn list_claims_for_provider(
+        &self,
+        rt: &MockRuntime,
+        provider: ActorID,
+        request: ListRequest<ClaimID>,
+    ) -> Result<ListClaimsForProviderReturn, ActorError> {
+        rt.expect_validate_caller_any();
+        let params = ListClaimsForProviderParams { provider, request };
+        let ret = rt
+            .call::<VerifregActor>(
+                Method::ListClaimsForProviderExported as MethodNum,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            )?
+            .unwrap()
+            .deserialize()
+            .expect("failed to deserialize list claims return");
+        rt.verify();
+        Ok(ret)
+    }
+
     pub fn extend_claim_terms(
         &self,
         rt: &MockRuntime,
@@ -551,6 +879,23 @@ pub fn expect_emitted(rt: &MockRuntime, typ: &str, id: &u64, client: ActorID, pr
     );
 }
 
+pub fn expect_expired_allocations_removed(
+    rt: &MockRuntime,
+    client: ActorID,
+    allocations: u64,
+    datacap: &DataCap,
+) {
+    rt.expect_emitted_event(
+        EventBuilder::new()
+            .typ("expired-allocations-removed")
+            .field_indexed("client", &client)
+            .field("allocations", &allocations)
+            .field("datacap", &BigIntSer(datacap))
+            .build()
+            .unwrap(),
+    );
+}
+
 pub fn make_extension_req(
     provider: ActorID,
     claim: ClaimID,
@@ -522,16 +522,21 @@ mod allocs_claims {
     use cid::Cid;
     use fvm_ipld_encoding::ipld_block::IpldBlock;
     use fvm_shared::bigint::BigInt;
+    use fvm_shared::crypto::signature::Signature;
+    use fvm_shared::econ::TokenAmount;
     use fvm_shared::error::ExitCode;
     use fvm_shared::piece::PaddedPieceSize;
     use fvm_shared::{ActorID, MethodNum};
     use num_traits::Zero;
 
     use fil_actor_verifreg::{
-        Actor, AllocationID, ClaimTerm, DataCap, ExtendClaimTermsParams, GetClaimsParams, Method,
-        State,
+        ext, Actor, Allocation, AllocationID, ClaimID, ClaimTerm, ClientDataCapReturned, DataCap,
+        ExtendClaimTermsBatchParams, ExtendClaimTermsParams, GetClaimsParams, Method,
+        ProviderAllocationsRemoved, RemovalReceipt, RetargetAllocationApproval,
+        RetargetAllocationParams, State, SweepCursor, SweepExpiredAllocationsParams,
     };
-    use fil_actor_verifreg::{Claim, ExtendClaimTermsReturn};
+    use fil_actor_verifreg::{Claim, ClaimExtensionRequest, ExtendClaimTermsReturn};
+    use fil_actors_runtime::query::{FieldFilter, FilterOp, ListRequest, MAX_LIST_LIMIT};
     use fil_actors_runtime::runtime::policy_constants::{
         MAXIMUM_VERIFIED_ALLOCATION_TERM, MINIMUM_VERIFIED_ALLOCATION_SIZE,
         MINIMUM_VERIFIED_ALLOCATION_TERM,
@@ -539,7 +544,7 @@ mod allocs_claims {
     use fil_actors_runtime::test_utils::{
         expect_abort, expect_abort_contains_message, ACCOUNT_ACTOR_CODE_ID, EVM_ACTOR_CODE_ID,
     };
-    use fil_actors_runtime::FailCode;
+    use fil_actors_runtime::{FailCode, DATACAP_TOKEN_ACTOR_ADDR};
     use harness::*;
 
     use crate::*;
@@ -573,6 +578,14 @@ mod allocs_claims {
         assert_eq!(vec![1, 2], ret.considered);
         assert_eq!(vec![ExitCode::USR_FORBIDDEN, ExitCode::USR_FORBIDDEN], ret.results.codes());
         assert_eq!(DataCap::zero(), ret.datacap_recovered);
+        let ret = h
+            .remove_expired_allocations_with_receipt(&rt, CLIENT1, vec![id1, id2], vec![])
+            .unwrap();
+        assert_eq!(
+            vec![ExitCode::USR_FORBIDDEN, ExitCode::USR_FORBIDDEN],
+            ret.removal.results.codes()
+        );
+        assert_eq!(RemovalReceipt::default(), ret.receipt);
 
         // Can't remove with wrong client ID
         rt.set_epoch(200);
@@ -599,10 +612,23 @@ mod allocs_claims {
 
         // Reset state and show we can remove two at once.
         rt.replace_state(&state_with_allocs);
-        let ret = h.remove_expired_allocations(&rt, CLIENT1, vec![id1, id2], expect_both).unwrap();
-        assert_eq!(vec![1, 2], ret.considered);
-        assert_eq!(vec![ExitCode::OK, ExitCode::OK], ret.results.codes());
-        assert_eq!(DataCap::from(total_size), ret.datacap_recovered);
+        let ret = h
+            .remove_expired_allocations_with_receipt(&rt, CLIENT1, vec![id1, id2], expect_both)
+            .unwrap();
+        assert_eq!(vec![1, 2], ret.removal.considered);
+        assert_eq!(vec![ExitCode::OK, ExitCode::OK], ret.removal.results.codes());
+        assert_eq!(DataCap::from(total_size), ret.removal.datacap_recovered);
+        assert_eq!(
+            RemovalReceipt {
+                clients: vec![ClientDataCapReturned {
+                    client: CLIENT1,
+                    allocations: 2,
+                    datacap: DataCap::from(total_size),
+                }],
+                providers: vec![ProviderAllocationsRemoved { provider: PROVIDER1, allocations: 2 }],
+            },
+            ret.receipt
+        );
 
         // Reset state and show that only what was asked for is removed.
         rt.replace_state(&state_with_allocs);
@@ -650,6 +676,113 @@ mod allocs_claims {
         h.check_state(&rt);
     }
 
+    #[test]
+    fn sweep_expired_allocs() {
+        let (h, rt) = new_harness();
+
+        let mut alloc1 = make_alloc("1", CLIENT1, PROVIDER1, ALLOC_SIZE);
+        alloc1.expiration = 200;
+        let mut alloc2 = make_alloc("2", CLIENT2, PROVIDER1, ALLOC_SIZE * 2);
+        alloc2.expiration = 100;
+        let mut alloc3 = make_alloc("3", CLIENT1, PROVIDER2, ALLOC_SIZE * 3);
+        alloc3.expiration = 100;
+        let mut alloc4 = make_alloc("4", CLIENT1, PROVIDER2, ALLOC_SIZE);
+        alloc4.expiration = 300;
+        for alloc in [&alloc1, &alloc2, &alloc3, &alloc4] {
+            h.create_alloc(&rt, alloc).unwrap();
+        }
+        let state_with_allocs: State = rt.get_state();
+        let order = h.allocations_in_sweep_order(&rt);
+        assert_eq!(4, order.len());
+        let expired_among = |examined: &[(AllocationID, Allocation)]| {
+            examined.iter().filter(|(_, a)| a.expiration <= 200).cloned().collect::<Vec<_>>()
+        };
+
+        // Nothing is removed before any allocation expires.
+        rt.set_epoch(99);
+        let ret = h.sweep_expired_allocations(&rt, None, 10, vec![]).unwrap();
+        assert_eq!(RemovalReceipt::default(), ret.receipt);
+        assert_eq!(None, ret.next_cursor);
+
+        // A sweep of all allocations removes the expired ones, across clients.
+        rt.set_epoch(200);
+        let ret = h.sweep_expired_allocations(&rt, None, 10, expired_among(&order)).unwrap();
+        assert_eq!(
+            RemovalReceipt {
+                clients: vec![
+                    ClientDataCapReturned {
+                        client: CLIENT1,
+                        allocations: 2,
+                        datacap: DataCap::from(alloc1.size.0 + alloc3.size.0),
+                    },
+                    ClientDataCapReturned {
+                        client: CLIENT2,
+                        allocations: 1,
+                        datacap: DataCap::from(alloc2.size.0),
+                    },
+                ],
+                providers: vec![
+                    ProviderAllocationsRemoved { provider: PROVIDER1, allocations: 2 },
+                    ProviderAllocationsRemoved { provider: PROVIDER2, allocations: 1 },
+                ],
+            },
+            ret.receipt
+        );
+        assert_eq!(None, ret.next_cursor);
+        assert_eq!(
+            vec![alloc4],
+            h.allocations_in_sweep_order(&rt).into_iter().map(|(_, a)| a).collect::<Vec<_>>()
+        );
+        h.check_state(&rt);
+
+        // A sweep a page at a time examines only the limit, resuming from the cursor.
+        for limit in 1..=4 {
+            rt.replace_state(&state_with_allocs);
+            let mut cursor = None;
+            for page in order.chunks(limit) {
+                let ret = h
+                    .sweep_expired_allocations(&rt, cursor, limit as u64, expired_among(page))
+                    .unwrap();
+                cursor = ret.next_cursor;
+                if page.last() == order.last() {
+                    assert_eq!(None, cursor);
+                } else {
+                    assert!(cursor.is_some());
+                }
+            }
+            assert_eq!(1, h.allocations_in_sweep_order(&rt).len());
+        }
+
+        // A cursor at an allocation that has since been removed resumes from the one after it.
+        rt.replace_state(&state_with_allocs);
+        rt.set_epoch(300);
+        let ret = h.sweep_expired_allocations(&rt, None, 1, order[..1].to_vec()).unwrap();
+        let (id, alloc) = order[1].clone();
+        h.remove_expired_allocations(&rt, alloc.client, vec![id], vec![(id, alloc)]).unwrap();
+        let ret =
+            h.sweep_expired_allocations(&rt, ret.next_cursor, 10, order[2..].to_vec()).unwrap();
+        assert_eq!(None, ret.next_cursor);
+        assert!(h.allocations_in_sweep_order(&rt).is_empty());
+
+        // The number to examine must be bounded.
+        for limit in [0, MAX_LIST_LIMIT + 1] {
+            rt.expect_validate_caller_any();
+            expect_abort_contains_message(
+                ExitCode::USR_ILLEGAL_ARGUMENT,
+                "sweep limit",
+                rt.call::<Actor>(
+                    Method::SweepExpiredAllocationsExported as MethodNum,
+                    IpldBlock::serialize_cbor(&SweepExpiredAllocationsParams {
+                        cursor: None,
+                        limit,
+                    })
+                    .unwrap(),
+                ),
+            );
+            rt.reset();
+        }
+    }
+
     #[test]
     fn claim_allocs() {
         let (h, rt) = new_harness();
@@ -879,6 +1012,300 @@ mod allocs_claims {
         h.check_state(&rt);
     }
 
+    #[test]
+    fn list_allocations_for_client() {
+        let (h, rt) = new_harness();
+        let mut expected = Vec::new();
+        for i in 0..5 {
+            let alloc = make_alloc(&i.to_string(), CLIENT1, PROVIDER1, ALLOC_SIZE);
+            expected.push((h.create_alloc(&rt, &alloc).unwrap(), alloc));
+        }
+        let other = make_alloc("other", CLIENT2, PROVIDER1, ALLOC_SIZE);
+        h.create_alloc(&rt, &other).unwrap();
+
+        // Page through the client's allocations two at a time.
+        let mut listed = Vec::new();
+        let mut cursor = None;
+        let mut pages = 0;
+        loop {
+            let request = ListRequest { cursor, limit: 2, filter: vec![] };
+            let ret = h.list_allocations_for_client(&rt, CLIENT1, request).unwrap();
+            assert!(ret.entries.len() <= 2);
+            listed.extend(ret.entries.into_iter().map(|r| (r.id, r.allocation)));
+            pages += 1;
+            cursor = ret.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        assert_eq!(3, pages);
+        listed.sort_by_key(|(id, _)| *id);
+        assert_eq!(expected, listed);
+
+        // A single page can hold everything.
+        let ret = h.list_allocations_for_client(&rt, CLIENT1, ListRequest::first(10)).unwrap();
+        assert_eq!(5, ret.entries.len());
+        assert_eq!(None, ret.next_cursor);
+
+        // A client with no allocations has an empty listing.
+        let ret = h.list_allocations_for_client(&rt, 103, ListRequest::first(10)).unwrap();
+        assert!(ret.entries.is_empty());
+        assert_eq!(None, ret.next_cursor);
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn retarget_allocation() {
+        let (h, rt) = new_harness();
+        add_miner(&rt, PROVIDER1);
+        add_miner(&rt, PROVIDER2);
+        let mut alloc = make_alloc("1", CLIENT1, PROVIDER1, ALLOC_SIZE);
+        alloc.expiration = 100;
+        let id1 = h.create_alloc(&rt, &alloc).unwrap();
+        let id2 = h.create_alloc(&rt, &make_alloc("2", CLIENT2, PROVIDER1, ALLOC_SIZE)).unwrap();
+
+        // Only the provider changes.
+        h.retarget_allocation(&rt, CLIENT1, id1, PROVIDER1, PROVIDER2).unwrap();
+        let expected = Allocation { provider: PROVIDER2, ..alloc.clone() };
+        assert_eq!(Some(expected), h.load_alloc(&rt, CLIENT1, id1));
+
+        // The allocation can be moved back, with the approval of the provider now holding it.
+        h.retarget_allocation(&rt, CLIENT1, id1, PROVIDER2, PROVIDER1).unwrap();
+        assert_eq!(Some(alloc.clone()), h.load_alloc(&rt, CLIENT1, id1));
+
+        // Already for the provider.
+        expect_abort_contains_message(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            "already for provider",
+            h.retarget_allocation(&rt, CLIENT1, id1, PROVIDER1, PROVIDER1),
+        );
+        rt.reset();
+
+        // Another client's allocation.
+        expect_abort(
+            ExitCode::USR_NOT_FOUND,
+            h.retarget_allocation(&rt, CLIENT1, id2, PROVIDER1, PROVIDER2),
+        );
+        rt.reset();
+
+        // New provider isn't a miner.
+        rt.set_address_actor_type(Address::new_id(PROVIDER2), *ACCOUNT_ACTOR_CODE_ID);
+        expect_abort(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            h.retarget_allocation(&rt, CLIENT2, id2, PROVIDER1, PROVIDER2),
+        );
+        rt.reset();
+        add_miner(&rt, PROVIDER2);
+
+        // Expired.
+        rt.set_epoch(101);
+        expect_abort(
+            ExitCode::USR_FORBIDDEN,
+            h.retarget_allocation(&rt, CLIENT1, id1, PROVIDER1, PROVIDER2),
+        );
+        rt.reset();
+        assert_eq!(Some(alloc), h.load_alloc(&rt, CLIENT1, id1));
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn retarget_allocation_requires_provider_approval() {
+        let (h, rt) = new_harness();
+        add_miner(&rt, PROVIDER1);
+        add_miner(&rt, PROVIDER2);
+        let alloc = make_alloc("1", CLIENT1, PROVIDER1, ALLOC_SIZE);
+        let id = h.create_alloc(&rt, &alloc).unwrap();
+
+        // The signer doesn't control the provider.
+        expect_abort_contains_message(
+            ExitCode::USR_FORBIDDEN,
+            "is not the owner, worker or a control address",
+            h.retarget_allocation_approved(&rt, CLIENT1, id, PROVIDER1, PROVIDER2, false, true),
+        );
+        rt.reset();
+
+        // The signer doesn't authenticate the approval.
+        expect_abort_contains_message(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            "provider approval authentication failed",
+            h.retarget_allocation_approved(&rt, CLIENT1, id, PROVIDER1, PROVIDER2, true, false),
+        );
+        rt.reset();
+
+        // An expired approval is rejected without checking it.
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, Address::new_id(CLIENT1));
+        rt.expect_validate_caller_any();
+        let params = RetargetAllocationParams {
+            allocation_id: id,
+            new_provider: PROVIDER2,
+            provider_approval: RetargetAllocationApproval {
+                signer: PROVIDER_SIGNER,
+                signature: Signature::new_secp256k1(b"approval".to_vec()),
+                expiration: *rt.epoch.borrow() - 1,
+            },
+        };
+        expect_abort_contains_message(
+            ExitCode::USR_FORBIDDEN,
+            "provider approval expired",
+            rt.call::<Actor>(
+                Method::RetargetAllocationExported as MethodNum,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            ),
+        );
+        rt.reset();
+
+        assert_eq!(Some(alloc), h.load_alloc(&rt, CLIENT1, id));
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn list_claims_for_provider() {
+        let (h, rt) = new_harness();
+        let min_term = MINIMUM_VERIFIED_ALLOCATION_TERM;
+        let max_term = min_term + 1000;
+        let mut expected = Vec::new();
+        for i in 0..3 {
+            let claim = make_claim(
+                &i.to_string(),
+                CLIENT1,
+                PROVIDER1,
+                ALLOC_SIZE,
+                min_term,
+                max_term,
+                0,
+                0,
+            );
+            expected.push((h.create_claim(&rt, &claim).unwrap(), claim));
+        }
+        let other = make_claim("other", CLIENT1, PROVIDER2, ALLOC_SIZE, min_term, max_term, 0, 0);
+        h.create_claim(&rt, &other).unwrap();
+
+        let first = h.list_claims_for_provider(&rt, PROVIDER1, ListRequest::first(2)).unwrap();
+        assert_eq!(2, first.entries.len());
+        let cursor = first.next_cursor.expect("expected a further page");
+        let request = ListRequest { cursor: Some(cursor), limit: 2, filter: vec![] };
+        let second = h.list_claims_for_provider(&rt, PROVIDER1, request).unwrap();
+        assert_eq!(1, second.entries.len());
+        assert_eq!(cursor, second.entries[0].id);
+        assert_eq!(None, second.next_cursor);
+
+        let mut listed: Vec<(ClaimID, Claim)> =
+            first.entries.into_iter().chain(second.entries).map(|r| (r.id, r.claim)).collect();
+        listed.sort_by_key(|(id, _)| *id);
+        assert_eq!(expected, listed);
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn list_claims_with_filter() {
+        let (h, rt) = new_harness();
+        let min_term = MINIMUM_VERIFIED_ALLOCATION_TERM;
+        let max_term = min_term + 1000;
+        let mut expected = Vec::new();
+        for sector in 0..4 {
+            let claim = make_claim(
+                &sector.to_string(),
+                CLIENT1,
+                PROVIDER1,
+                ALLOC_SIZE,
+                min_term,
+                max_term,
+                0,
+                sector,
+            );
+            let id = h.create_claim(&rt, &claim).unwrap();
+            if sector >= 2 {
+                expected.push((id, claim));
+            }
+        }
+
+        // The filter applies within each page, so pages may hold fewer entries than the limit.
+        let mut listed = Vec::new();
+        let mut cursor = None;
+        loop {
+            let request = ListRequest {
+                cursor,
+                limit: 1,
+                filter: vec![FieldFilter::new("sector", FilterOp::Ge, 2)],
+            };
+            let ret = h.list_claims_for_provider(&rt, PROVIDER1, request).unwrap();
+            listed.extend(ret.entries.into_iter().map(|r| (r.id, r.claim)));
+            cursor = ret.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        listed.sort_by_key(|(id, _)| *id);
+        assert_eq!(expected, listed);
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn list_resumes_after_removed_cursor() {
+        let (h, rt) = new_harness();
+        let mut expected = Vec::new();
+        for i in 0..5 {
+            let alloc = make_alloc(&i.to_string(), CLIENT1, PROVIDER1, ALLOC_SIZE);
+            expected.push((h.create_alloc(&rt, &alloc).unwrap(), alloc));
+        }
+        let first = h.list_allocations_for_client(&rt, CLIENT1, ListRequest::first(2)).unwrap();
+        let cursor = first.next_cursor.unwrap();
+
+        // The allocation at the cursor is removed before the next page is requested.
+        let mut st: State = rt.get_state();
+        let mut allocs = st.load_allocs(rt.store()).unwrap();
+        allocs.remove(CLIENT1, cursor).unwrap().unwrap();
+        st.allocations = allocs.flush().unwrap();
+        rt.replace_state(&st);
+
+        // The listing resumes from the allocation after it, and lists every other allocation.
+        let request = ListRequest { cursor: Some(cursor), limit: 10, filter: vec![] };
+        let rest = h.list_allocations_for_client(&rt, CLIENT1, request).unwrap();
+        assert_eq!(None, rest.next_cursor);
+        let mut listed: Vec<_> =
+            first.entries.into_iter().chain(rest.entries).map(|r| (r.id, r.allocation)).collect();
+        listed.sort_by_key(|(id, _)| *id);
+        expected.retain(|(id, _)| *id != cursor);
+        assert_eq!(expected, listed);
+
+        // A cursor for a client without allocations lists nothing.
+        let request = ListRequest { cursor: Some(cursor), limit: 10, filter: vec![] };
+        let ret = h.list_allocations_for_client(&rt, CLIENT2, request).unwrap();
+        assert!(ret.entries.is_empty());
+        assert_eq!(None, ret.next_cursor);
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn list_rejects_bad_page_requests() {
+        let (h, rt) = new_harness();
+        let alloc = make_alloc("1", CLIENT1, PROVIDER1, ALLOC_SIZE);
+        h.create_alloc(&rt, &alloc).unwrap();
+
+        expect_abort_contains_message(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            "page limit 0",
+            h.list_allocations_for_client(&rt, CLIENT1, ListRequest::first(0)),
+        );
+        rt.reset();
+        expect_abort_contains_message(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            "page limit",
+            h.list_claims_for_provider(&rt, PROVIDER1, ListRequest::first(MAX_LIST_LIMIT + 1)),
+        );
+        rt.reset();
+        // Filters may only refer to indexed fields.
+        let mut request = ListRequest::first(10);
+        request.filter.push(FieldFilter::new("data", FilterOp::Eq, 0));
+        expect_abort_contains_message(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            "cannot filter on data",
+            h.list_allocations_for_client(&rt, CLIENT1, request),
+        );
+        rt.reset();
+        h.check_state(&rt);
+    }
+
     #[test]
     fn extend_claims_basic() {
         let (h, rt) = new_harness();
@@ -919,6 +1346,150 @@ mod allocs_claims {
         h.check_state(&rt);
     }
 
+    #[test]
+    fn extend_claims_batch_across_providers() {
+        let (h, rt) = new_harness();
+        let size = MINIMUM_VERIFIED_ALLOCATION_SIZE as u64;
+        let min_term = MINIMUM_VERIFIED_ALLOCATION_TERM;
+        let max_term = min_term + 1000;
+        let max_extension = MAXIMUM_VERIFIED_ALLOCATION_TERM;
+
+        let claim1 = make_claim("1", CLIENT1, PROVIDER1, size, min_term, max_term, 0, 0);
+        let claim2 = make_claim("2", CLIENT2, PROVIDER1, size, min_term, max_term, 0, 0);
+        let claim3 = make_claim("3", CLIENT1, PROVIDER2, size, min_term, max_term, 0, 0);
+        let id1 = h.create_claim(&rt, &claim1).unwrap();
+        let id2 = h.create_claim(&rt, &claim2).unwrap();
+        let id3 = h.create_claim(&rt, &claim3).unwrap();
+        // The last epoch before the claims expire.
+        rt.set_epoch(max_term);
+
+        let extensions = vec![
+            // A full-term extension costs the claim's size.
+            ClaimExtensionRequest {
+                provider: PROVIDER1,
+                claim: id1,
+                term_max: max_term + max_extension,
+            },
+            // Another client's claim may be extended, for a pro-rated cost.
+            ClaimExtensionRequest {
+                provider: PROVIDER1,
+                claim: id2,
+                term_max: max_term + max_extension / 2,
+            },
+            // Costs are rounded up.
+            ClaimExtensionRequest { provider: PROVIDER2, claim: id3, term_max: max_term + 1 },
+            // Failures don't prevent the other extensions.
+            ClaimExtensionRequest { provider: PROVIDER2, claim: id1, term_max: max_term + 1 },
+            ClaimExtensionRequest { provider: PROVIDER1, claim: id1, term_max: max_term - 1 },
+        ];
+        let new_claim1 = Claim { term_max: max_term + max_extension, ..claim1 };
+        let new_claim2 = Claim { term_max: max_term + max_extension / 2, ..claim2 };
+        let new_claim3 = Claim { term_max: max_term + 1, ..claim3 };
+        let ret = h
+            .extend_claim_terms_batch(
+                &rt,
+                CLIENT1,
+                extensions,
+                size + size / 2 + 1,
+                vec![
+                    (id1, new_claim1.clone()),
+                    (id2, new_claim2.clone()),
+                    (id3, new_claim3.clone()),
+                ],
+            )
+            .unwrap();
+        assert_eq!(
+            vec![
+                ExitCode::OK,
+                ExitCode::OK,
+                ExitCode::OK,
+                ExitCode::USR_NOT_FOUND,
+                ExitCode::USR_ILLEGAL_ARGUMENT
+            ],
+            ret.results.codes()
+        );
+
+        // Claims keep their original clients.
+        assert_claim(&rt, PROVIDER1, id1, &new_claim1);
+        assert_claim(&rt, PROVIDER1, id2, &new_claim2);
+        assert_claim(&rt, PROVIDER2, id3, &new_claim3);
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn extend_claims_batch_edge_cases() {
+        let (h, rt) = new_harness();
+        let size = MINIMUM_VERIFIED_ALLOCATION_SIZE as u64;
+        let min_term = MINIMUM_VERIFIED_ALLOCATION_TERM;
+        let max_term = min_term + 1000;
+        let claim = make_claim("1", CLIENT1, PROVIDER1, size, min_term, max_term, 0, 0);
+
+        // Repeated extensions of a claim compound, each paying for its own increment.
+        {
+            let id = h.create_claim(&rt, &claim).unwrap();
+            let extensions = vec![
+                ClaimExtensionRequest { provider: PROVIDER1, claim: id, term_max: max_term + 1 },
+                ClaimExtensionRequest { provider: PROVIDER1, claim: id, term_max: max_term + 2 },
+            ];
+            let expected = vec![
+                (id, Claim { term_max: max_term + 1, ..claim }),
+                (id, Claim { term_max: max_term + 2, ..claim }),
+            ];
+            let ret = h.extend_claim_terms_batch(&rt, CLIENT1, extensions, 2, expected).unwrap();
+            assert_eq!(vec![ExitCode::OK, ExitCode::OK], ret.results.codes());
+            assert_claim(&rt, PROVIDER1, id, &Claim { term_max: max_term + 2, ..claim });
+        }
+        // Nothing is spent if no extension succeeds, and expired claims can't be extended.
+        {
+            let id = h.create_claim(&rt, &claim).unwrap();
+            rt.set_epoch(max_term + 1);
+            let extensions = vec![ClaimExtensionRequest {
+                provider: PROVIDER1,
+                claim: id,
+                term_max: max_term + 1,
+            }];
+            let ret = h.extend_claim_terms_batch(&rt, CLIENT1, extensions, 0, vec![]).unwrap();
+            assert_eq!(vec![ExitCode::USR_FORBIDDEN], ret.results.codes());
+            assert_claim(&rt, PROVIDER1, id, &claim);
+        }
+        // The whole batch fails if the caller has insufficient datacap.
+        {
+            rt.set_epoch(0);
+            let id = h.create_claim(&rt, &claim).unwrap();
+            rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, Address::new_id(CLIENT2));
+            rt.expect_validate_caller_any();
+            rt.expect_send_simple(
+                DATACAP_TOKEN_ACTOR_ADDR,
+                ext::datacap::Method::Destroy as MethodNum,
+                IpldBlock::serialize_cbor(&ext::datacap::DestroyParams {
+                    owner: Address::new_id(CLIENT2),
+                    amount: TokenAmount::from_whole(1),
+                })
+                .unwrap(),
+                TokenAmount::zero(),
+                None,
+                ExitCode::USR_INSUFFICIENT_FUNDS,
+            );
+            let params = ExtendClaimTermsBatchParams {
+                extensions: vec![ClaimExtensionRequest {
+                    provider: PROVIDER1,
+                    claim: id,
+                    term_max: max_term + 1,
+                }],
+            };
+            expect_abort(
+                ExitCode::USR_INSUFFICIENT_FUNDS,
+                rt.call::<Actor>(
+                    Method::ExtendClaimTermsBatchExported as MethodNum,
+                    IpldBlock::serialize_cbor(&params).unwrap(),
+                ),
+            );
+            rt.verify();
+            assert_claim(&rt, PROVIDER1, id, &claim);
+        }
+        h.check_state(&rt);
+    }
+
     #[test]
     fn extend_claims_edge_cases() {
         let (h, rt) = new_harness();
@@ -1086,6 +1657,28 @@ mod allocs_claims {
         h.check_state(&rt);
     }
 
+    #[test]
+    fn expire_claims_when_provider_notification_fails() {
+        let (h, rt) = new_harness();
+        let term_min = MINIMUM_VERIFIED_ALLOCATION_TERM;
+        let claim = make_claim("1", CLIENT1, PROVIDER1, ALLOC_SIZE, term_min, term_min, 0, 0);
+        let id = h.create_claim(&rt, &claim).unwrap();
+
+        rt.set_epoch(term_min);
+        let ret = h
+            .remove_expired_claims_notified(
+                &rt,
+                PROVIDER1,
+                vec![id],
+                vec![(id, claim)],
+                ExitCode::USR_UNHANDLED_MESSAGE,
+            )
+            .unwrap();
+        assert_eq!(vec![ExitCode::OK], ret.results.codes());
+        assert!(h.load_claim(&rt, PROVIDER1, id).is_none());
+        h.check_state(&rt);
+    }
+
     #[test]
     fn claims_restricted_correctly() {
         let (h, rt) = new_harness();
@@ -1179,6 +1772,12 @@ mod datacap {
     const SIZE: u64 = MINIMUM_VERIFIED_ALLOCATION_SIZE as u64;
     const BATCH_EMPTY: BatchReturn = BatchReturn::empty();
 
+    // The datacap spent extending the term of a claim of `size` by `term_delta` epochs.
+    fn extension_cost(size: u64, term_delta: i64) -> u64 {
+        let max_term = MAXIMUM_VERIFIED_ALLOCATION_TERM as u64;
+        (size * term_delta as u64 + max_term - 1) / max_term
+    }
+
     #[test]
     fn receive_tokens_make_allocs() {
         let (h, rt) = new_harness();
@@ -1246,9 +1845,10 @@ mod datacap {
             make_extension_req(PROVIDER1, cid1, term_max + 1000),
             make_extension_req(PROVIDER2, cid2, term_max + 2000),
         ];
-        // Client1 extends both claims
-        let payload = make_receiver_hook_token_payload(CLIENT1, vec![], reqs, SIZE * 3);
-        h.receive_tokens(&rt, payload, BATCH_EMPTY, BatchReturn::ok(2), vec![], SIZE * 3).unwrap();
+        // Client1 extends both claims, paying for the added terms.
+        let cost = extension_cost(SIZE, 1000) + extension_cost(SIZE * 2, 2000);
+        let payload = make_receiver_hook_token_payload(CLIENT1, vec![], reqs, cost);
+        h.receive_tokens(&rt, payload, BATCH_EMPTY, BatchReturn::ok(2), vec![], cost).unwrap();
 
         // Verify claims in state.
         assert_claim(&rt, PROVIDER1, cid1, &Claim { term_max: term_max + 1000, ..claim1 });
@@ -1283,17 +1883,16 @@ mod datacap {
         ];
 
         // CLIENT1 makes two new allocations and extends two existing claims.
-        let payload =
-            make_receiver_hook_token_payload(CLIENT1, alloc_reqs.clone(), ext_reqs, SIZE * 6);
-        h.receive_tokens(
-            &rt,
-            payload,
-            BatchReturn::ok(2),
-            BatchReturn::ok(2),
-            vec![3, 4],
-            claim1.size.0 + claim2.size.0,
-        )
-        .unwrap();
+        // Only the datacap for the extensions is burnt.
+        let cost = extension_cost(claim1.size.0, 1000) + extension_cost(claim2.size.0, 2000);
+        let payload = make_receiver_hook_token_payload(
+            CLIENT1,
+            alloc_reqs.clone(),
+            ext_reqs,
+            SIZE * 3 + cost,
+        );
+        h.receive_tokens(&rt, payload, BatchReturn::ok(2), BatchReturn::ok(2), vec![3, 4], cost)
+            .unwrap();
 
         // Verify state.
         assert_allocation(&rt, CLIENT1, 3, &alloc_from_req(CLIENT1, &alloc_reqs[0]));
@@ -1375,17 +1974,20 @@ mod datacap {
     fn receive_alloc_requires_miner_actor() {
         let (h, rt) = new_harness();
         let provider1 = Address::new_id(PROVIDER1);
-        rt.set_address_actor_type(provider1, *ACCOUNT_ACTOR_CODE_ID);
+        // Neither built-in actors of other types nor actors of unknown code may be providers.
+        for code in [*ACCOUNT_ACTOR_CODE_ID, make_identity_cid(b"unknown")] {
+            rt.set_address_actor_type(provider1, code);
 
-        let reqs = vec![make_alloc_req(&rt, PROVIDER1, SIZE)];
-        let payload = make_receiver_hook_token_payload(CLIENT1, reqs, vec![], SIZE);
-        expect_abort_contains_message(
-            ExitCode::USR_ILLEGAL_ARGUMENT,
-            format!("allocation provider {} must be a miner actor", provider1.id().unwrap())
-                .as_str(),
-            h.receive_tokens(&rt, payload, BatchReturn::ok(1), BATCH_EMPTY, vec![1], 0),
-        );
-        rt.reset();
+            let reqs = vec![make_alloc_req(&rt, PROVIDER1, SIZE)];
+            let payload = make_receiver_hook_token_payload(CLIENT1, reqs, vec![], SIZE);
+            expect_abort_contains_message(
+                ExitCode::USR_ILLEGAL_ARGUMENT,
+                format!("allocation provider {} must be a miner actor", provider1.id().unwrap())
+                    .as_str(),
+                h.receive_tokens(&rt, payload, BatchReturn::ok(1), BATCH_EMPTY, vec![1], 0),
+            );
+            rt.reset();
+        }
         h.check_state(&rt);
     }
 
@@ -1508,8 +2110,9 @@ mod datacap {
             rt.reset();
             // But just on the limit is allowed
             let reqs = vec![make_extension_req(PROVIDER1, cid1, max_allowed_term)];
-            let payload = make_receiver_hook_token_payload(CLIENT1, vec![], reqs, SIZE);
-            h.receive_tokens(&rt, payload, BATCH_EMPTY, BatchReturn::ok(1), vec![], SIZE).unwrap();
+            let cost = extension_cost(SIZE, max_allowed_term - term_max);
+            let payload = make_receiver_hook_token_payload(CLIENT1, vec![], reqs, cost);
+            h.receive_tokens(&rt, payload, BATCH_EMPTY, BatchReturn::ok(1), vec![], cost).unwrap();
             h.check_state(&rt);
             rt.reset();
         }
@@ -1532,8 +2135,9 @@ mod datacap {
             let new_term = epoch - term_start + MAXIMUM_VERIFIED_ALLOCATION_TERM; // Can get full max term now
             rt.set_epoch(epoch);
             let reqs = vec![make_extension_req(PROVIDER1, cid1, new_term)];
-            let payload = make_receiver_hook_token_payload(CLIENT1, vec![], reqs, SIZE);
-            h.receive_tokens(&rt, payload, BATCH_EMPTY, BatchReturn::ok(1), vec![], SIZE).unwrap();
+            let cost = extension_cost(SIZE, new_term - term_max);
+            let payload = make_receiver_hook_token_payload(CLIENT1, vec![], reqs, cost);
+            h.receive_tokens(&rt, payload, BATCH_EMPTY, BatchReturn::ok(1), vec![], cost).unwrap();
             h.check_state(&rt);
             rt.reset();
         }
@@ -1558,10 +2162,10 @@ mod datacap {
                 h.receive_tokens(&rt, payload, BATCH_EMPTY, BATCH_EMPTY, vec![], 0),
             );
             rt.reset();
-            // But extension by just 1 epoch is allowed
+            // But extension by just 1 epoch is allowed, for a rounded up cost
             let reqs = vec![make_extension_req(PROVIDER1, cid1, term_max + 1)];
-            let payload = make_receiver_hook_token_payload(CLIENT1, vec![], reqs, SIZE);
-            h.receive_tokens(&rt, payload, BATCH_EMPTY, BatchReturn::ok(1), vec![], SIZE).unwrap();
+            let payload = make_receiver_hook_token_payload(CLIENT1, vec![], reqs, 1);
+            h.receive_tokens(&rt, payload, BATCH_EMPTY, BatchReturn::ok(1), vec![], 1).unwrap();
             h.check_state(&rt);
             rt.reset();
         }
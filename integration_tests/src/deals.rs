@@ -1,6 +1,6 @@
 use fil_actor_market::{
-    ClientDealProposal, DealProposal, Label, Method as MarketMethod, PublishStorageDealsParams,
-    PublishStorageDealsReturn,
+    ClientDealProposal, DealProposal, Label, Method as MarketMethod, ProposalAuthScheme,
+    PublishStorageDealsParams, PublishStorageDealsReturn,
 };
 use fil_actors_runtime::cbor::serialize;
 use fil_actors_runtime::runtime::Policy;
@@ -139,6 +139,7 @@ fn publish_deal_params(proposals: &[DealProposal]) -> PublishStorageDealsParams
                 sig_type: SignatureType::BLS,
                 bytes: serialize(deal, "serializing deal proposal").unwrap().to_vec(),
             },
+            auth_scheme: ProposalAuthScheme::Cbor,
         })
         .collect();
     PublishStorageDealsParams { deals: params_deals }
@@ -6,10 +6,11 @@ use fvm_ipld_bitfield::BitField;
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_ipld_encoding::RawBytes;
 use fvm_shared::address::Address;
+use fvm_shared::bigint::bigint_ser::BigIntSer;
 use fvm_shared::clock::ChainEpoch;
 use fvm_shared::deal::DealID;
 use fvm_shared::econ::TokenAmount;
-use fvm_shared::sector::{RegisteredSealProof, SectorNumber};
+use fvm_shared::sector::{RegisteredSealProof, SectorNumber, StoragePower};
 use fvm_shared::{ActorID, METHOD_SEND};
 use num_traits::Zero;
 
@@ -21,13 +22,13 @@ use fil_actor_market::{
 };
 use fil_actor_miner::ext::verifreg::ClaimID;
 use fil_actor_miner::{IsControllingAddressParam, PowerPair};
-use fil_actor_power::{UpdateClaimedPowerParams, UpdatePledgeTotalParams};
+use fil_actor_power::{MinerChanges, UpdateClaimedPowerParams, UpdatePledgeTotalParams};
 use fil_actor_verifreg::GetClaimsParams;
 use fil_actors_runtime::{
-    EventBuilder, BURNT_FUNDS_ACTOR_ADDR, DATACAP_TOKEN_ACTOR_ADDR, DATACAP_TOKEN_ACTOR_ID,
-    REWARD_ACTOR_ADDR, STORAGE_MARKET_ACTOR_ADDR, STORAGE_MARKET_ACTOR_ID,
-    STORAGE_POWER_ACTOR_ADDR, STORAGE_POWER_ACTOR_ID, VERIFIED_REGISTRY_ACTOR_ADDR,
-    VERIFIED_REGISTRY_ACTOR_ID,
+    EventBuilder, PenaltyCategory, BURNT_FUNDS_ACTOR_ADDR, DATACAP_TOKEN_ACTOR_ADDR,
+    DATACAP_TOKEN_ACTOR_ID, PENALTY_ASSESSED_EVENT, REWARD_ACTOR_ADDR, STORAGE_MARKET_ACTOR_ADDR,
+    STORAGE_MARKET_ACTOR_ID, STORAGE_POWER_ACTOR_ADDR, STORAGE_POWER_ACTOR_ID,
+    VERIFIED_REGISTRY_ACTOR_ADDR, VERIFIED_REGISTRY_ACTOR_ID,
 };
 
 use vm_api::trace::{EmittedEvent, ExpectInvocation};
@@ -83,18 +84,27 @@ impl Expect {
         epoch: ChainEpoch,
         sectors: Vec<SectorNumber>,
         deals: Vec<(DealID, ActorID)>,
+        slashed: &TokenAmount,
     ) -> ExpectInvocation {
         let bf = BitField::try_from_bits(sectors).unwrap();
         let params =
             IpldBlock::serialize_cbor(&OnMinerSectorsTerminateParams { epoch, sectors: bf })
                 .unwrap();
 
-        let events: Vec<EmittedEvent> = deals
+        let mut events: Vec<EmittedEvent> = deals
             .into_iter()
             .map(|(deal_id, client)| {
                 Expect::build_market_event("deal-terminated", deal_id, client, from)
             })
             .collect();
+        events.push(Expect::build_penalty_event(
+            STORAGE_MARKET_ACTOR_ID,
+            from,
+            PenaltyCategory::DealTermination,
+            slashed,
+            slashed,
+            &TokenAmount::zero(),
+        ));
 
         ExpectInvocation {
             from,
@@ -102,7 +112,7 @@ impl Expect {
             method: fil_actor_market::Method::OnMinerSectorsTerminate as u64,
             params: Some(params),
             value: Some(TokenAmount::zero()),
-            subinvocs: Some(vec![Expect::burn(STORAGE_MARKET_ACTOR_ID, None)]),
+            subinvocs: Some(vec![Expect::burn(STORAGE_MARKET_ACTOR_ID, Some(slashed.clone()))]),
             events,
             ..Default::default()
         }
@@ -419,6 +429,48 @@ impl Expect {
         }
     }
 
+    pub fn build_power_summary_event(
+        raw_byte_delta: &StoragePower,
+        qa_delta: &StoragePower,
+        changes: &MinerChanges,
+    ) -> EmittedEvent {
+        EmittedEvent {
+            emitter: STORAGE_POWER_ACTOR_ID,
+            event: EventBuilder::new()
+                .typ("power-epoch-summary")
+                .field("raw-byte-power-delta", &BigIntSer(raw_byte_delta))
+                .field("quality-adj-power-delta", &BigIntSer(qa_delta))
+                .field("new-miners", &changes.created)
+                .field("miners-reached-min-power", &changes.reached_min_power)
+                .field("miners-fell-below-min-power", &changes.fell_below_min_power)
+                .build()
+                .unwrap(),
+        }
+    }
+
+    pub fn build_penalty_event(
+        emitter: ActorID,
+        subject: ActorID,
+        category: PenaltyCategory,
+        amount: &TokenAmount,
+        burned: &TokenAmount,
+        withheld: &TokenAmount,
+    ) -> EmittedEvent {
+        EmittedEvent {
+            emitter,
+            event: EventBuilder::new()
+                .typ(PENALTY_ASSESSED_EVENT)
+                .field("actor", &emitter)
+                .field_indexed("subject", &subject)
+                .field_indexed("category", category.as_str())
+                .field("amount", amount)
+                .field("burned", burned)
+                .field("withheld", withheld)
+                .build()
+                .unwrap(),
+        }
+    }
+
     pub fn build_sector_activation_event(
         typ: &str,
         miner_id: ActorID,
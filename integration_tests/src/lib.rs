@@ -24,8 +24,6 @@ pub const TEST_VERIFREG_ROOT_ADDR: Address = Address::new_id(TEST_VERIFREG_ROOT_
 
 // account actor seeding funds created by new_with_singletons
 pub const FAUCET_ROOT_KEY: &[u8] = &[153; fvm_shared::address::BLS_PUB_LEN];
-pub const TEST_FAUCET_ADDR: Address = Address::new_id(FIRST_NON_SINGLETON_ADDR + 2);
-pub const FIRST_TEST_USER_ADDR: ActorID = FIRST_NON_SINGLETON_ADDR + 3;
 
 // static values for predictable testing
 pub const TEST_VM_RAND_ARRAY: [u8; 32] = [
@@ -0,0 +1,64 @@
+use export_macro::vm_test;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::sector::RegisteredSealProof;
+
+use crate::util::{
+    advance_by_deadline_to_index, assert_invariants, create_accounts, create_miner, deadline_state,
+    miner_dline_info, miner_power, precommit_sectors_v2, AdversarialMinerState,
+};
+use fil_actor_miner::PowerPair;
+use fil_actors_runtime::runtime::Policy;
+use vm_api::util::DynBlockstore;
+use vm_api::VM;
+
+#[vm_test]
+pub fn adversarial_deadline_cron_test(v: &dyn VM) {
+    let policy = Policy::default();
+    let addrs = create_accounts(v, 1, &TokenAmount::from_whole(10_000));
+    let seal_proof = RegisteredSealProof::StackedDRG32GiBV1P1;
+    let (owner, worker) = (addrs[0], addrs[0]);
+    let (miner_id, _) = create_miner(
+        v,
+        &owner,
+        &worker,
+        seal_proof.registered_window_post_proof().unwrap(),
+        &TokenAmount::from_whole(10_000),
+    );
+    v.set_epoch(200);
+
+    // Pre-commit a sector through the normal flow to enrol the miner's deadline cron.
+    precommit_sectors_v2(v, 1, 1, vec![], &worker, &miner_id, seal_proof, 100, true, None);
+
+    // Install many sparse partitions with fragmented sector numbers and long expiration queues
+    // into a deadline that isn't open yet.
+    let partitions = 200;
+    let sectors_per_partition = 4;
+    let dline_idx = (miner_dline_info(v, &miner_id).index + 2) % policy.wpost_period_deadlines;
+    let installed = AdversarialMinerState::new(v, &miner_id, seal_proof, 1_000)
+        .sector_number_stride(2)
+        .sectors_per_partition(sectors_per_partition)
+        .expiration_spread(16)
+        .install_deadline(dline_idx, partitions);
+    assert_eq!(partitions * sectors_per_partition, installed.len() as u64);
+
+    let store = DynBlockstore::wrap(v.blockstore());
+    let deadline = deadline_state(v, &miner_id, dline_idx);
+    assert_eq!(partitions, deadline.partitions_amt(&store).unwrap().count());
+    assert_eq!(installed.len() as u64, deadline.live_sectors);
+    assert_invariants(v, &policy, None);
+
+    // Nothing was proven, so cron marks every installed sector faulty at the deadline's end
+    // without changing the miner's claimed power.
+    advance_by_deadline_to_index(v, &miner_id, (dline_idx + 1) % policy.wpost_period_deadlines);
+    let deadline = deadline_state(v, &miner_id, dline_idx);
+    deadline
+        .for_each(&store, |_, partition| {
+            assert_eq!(partition.sectors, partition.faults);
+            assert!(partition.unproven.is_empty());
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(PowerPair::zero(), miner_power(v, &miner_id));
+    assert!(!deadline.faulty_power.is_zero());
+    assert_invariants(v, &policy, None);
+}
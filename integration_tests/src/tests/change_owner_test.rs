@@ -37,11 +37,11 @@ pub fn change_owner_success_test(v: &dyn VM) {
     );
     change_owner_address(v, &owner, &miner_id, &new_owner);
     let minfo = miner_info(v, &miner_id);
-    assert_eq!(new_owner, minfo.pending_owner_address.unwrap());
+    assert_eq!(new_owner, minfo.pending_owner_change().unwrap().new_owner);
 
     change_owner_address(v, &new_owner, &miner_id, &new_owner);
     let minfo = miner_info(v, &miner_id);
-    assert!(minfo.pending_owner_address.is_none());
+    assert!(minfo.pending_owner_change().is_none());
     assert_eq!(new_owner, minfo.owner);
     assert_eq!(new_owner, minfo.beneficiary);
 
@@ -81,7 +81,7 @@ pub fn keep_beneficiary_when_owner_changed_test(v: &dyn VM) {
     change_owner_address(v, &owner, &miner_id, &new_owner);
     change_owner_address(v, &new_owner, &miner_id, &new_owner);
     let minfo = miner_info(v, &miner_id);
-    assert!(minfo.pending_owner_address.is_none());
+    assert!(minfo.pending_owner_change().is_none());
     assert_eq!(new_owner, minfo.owner);
     assert_eq!(beneficiary, minfo.beneficiary);
 
@@ -152,7 +152,7 @@ pub fn change_owner_fail_test(v: &dyn VM) {
     //confirm owner proposal
     change_owner_address(v, &addr, &miner_id, &addr);
     let minfo = miner_info(v, &miner_id);
-    assert!(minfo.pending_owner_address.is_none());
+    assert!(minfo.pending_owner_change().is_none());
     assert_eq!(addr, minfo.owner);
     assert_eq!(addr, minfo.beneficiary);
 
@@ -8,7 +8,7 @@ use fvm_shared::bigint::Zero;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
 use fvm_shared::randomness::Randomness;
-use fvm_shared::sector::{PoStProof, RegisteredSealProof, SectorNumber};
+use fvm_shared::sector::{PoStProof, RegisteredSealProof, SectorNumber, StoragePower};
 
 use crate::expects::Expect;
 use crate::util::{
@@ -24,11 +24,11 @@ use fil_actor_miner::{
     PoStPartition, ProveCommitAggregateParams, ProveCommitSectorParams, State as MinerState,
     SubmitWindowedPoStParams,
 };
-use fil_actor_power::{Method as PowerMethod, State as PowerState};
+use fil_actor_power::{Method as PowerMethod, MinerChanges, State as PowerState};
 use fil_actors_runtime::runtime::Policy;
 use fil_actors_runtime::{
-    CRON_ACTOR_ADDR, CRON_ACTOR_ID, STORAGE_MARKET_ACTOR_ADDR, STORAGE_POWER_ACTOR_ADDR,
-    STORAGE_POWER_ACTOR_ID,
+    PenaltyCategory, CRON_ACTOR_ADDR, CRON_ACTOR_ID, STORAGE_MARKET_ACTOR_ADDR,
+    STORAGE_POWER_ACTOR_ADDR, STORAGE_POWER_ACTOR_ID,
 };
 use vm_api::trace::{EmittedEvent, ExpectInvocation};
 use vm_api::util::{apply_code, apply_ok, get_state, DynBlockstore};
@@ -138,6 +138,12 @@ fn setup(v: &dyn VM) -> (MinerInfo, SectorInfo) {
                     },
                     Expect::reward_update_kpi(),
                 ]),
+                // No power was proven or lost since the last tick.
+                events: vec![Expect::build_power_summary_event(
+                    &StoragePower::zero(),
+                    &StoragePower::zero(),
+                    &MinerChanges::default(),
+                )],
                 ..Default::default()
             },
             ExpectInvocation {
@@ -214,6 +220,7 @@ pub fn skip_sector_test(v: &dyn VM) {
         }],
         chain_commit_epoch: sector_info.deadline_info.challenge,
         chain_commit_rand: Randomness(TEST_VM_RAND_ARRAY.into()),
+        auto_recover_faults: false,
     };
 
     // PoSt is rejected for skipping all sectors.
@@ -269,6 +276,12 @@ pub fn missed_first_post_deadline_test(v: &dyn VM) {
                     },
                     Expect::reward_update_kpi(),
                 ]),
+                // No power was proven or lost since the last tick.
+                events: vec![Expect::build_power_summary_event(
+                    &StoragePower::zero(),
+                    &StoragePower::zero(),
+                    &MinerChanges::default(),
+                )],
                 ..Default::default()
             },
             ExpectInvocation {
@@ -366,13 +379,30 @@ pub fn overdue_precommit_test(v: &dyn VM) {
                         method: MinerMethod::OnDeferredCronEvent as u64,
                         subinvocs: Some(vec![
                             // The call to burnt funds indicates the overdue precommit has been penalized
-                            Expect::burn(id_addr.id().unwrap(), Some(precommit.pre_commit_deposit)),
+                            Expect::burn(
+                                id_addr.id().unwrap(),
+                                Some(precommit.pre_commit_deposit.clone()),
+                            ),
                             // No re-enrollment of cron because burning of PCD discontinues miner cron scheduling
                         ]),
+                        events: vec![Expect::build_penalty_event(
+                            id_addr.id().unwrap(),
+                            id_addr.id().unwrap(),
+                            PenaltyCategory::PreCommitExpiry,
+                            &precommit.pre_commit_deposit,
+                            &precommit.pre_commit_deposit,
+                            &TokenAmount::zero(),
+                        )],
                         ..Default::default()
                     },
                     Expect::reward_update_kpi(),
                 ]),
+                // No power was proven or lost since the last tick.
+                events: vec![Expect::build_power_summary_event(
+                    &StoragePower::zero(),
+                    &StoragePower::zero(),
+                    &MinerChanges::default(),
+                )],
                 ..Default::default()
             },
             ExpectInvocation {
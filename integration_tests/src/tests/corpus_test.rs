@@ -0,0 +1,73 @@
+use export_macro::vm_test;
+use fil_actor_market::State as MarketState;
+use fil_actor_verifreg::State as VerifregState;
+use fil_actors_runtime::runtime::Policy;
+use fil_actors_runtime::{STORAGE_MARKET_ACTOR_ADDR, VERIFIED_REGISTRY_ACTOR_ADDR};
+use num_traits::Signed;
+use vm_api::util::{get_state, DynBlockstore};
+use vm_api::VM;
+
+use crate::util::{assert_invariants, sector_info, verifreg_list_claims, StateCorpus};
+
+#[vm_test]
+pub fn state_corpus_test(v: &dyn VM) {
+    let corpus = StateCorpus::new(3, 20, 10).clients(4).activate(6, 4).cc_sectors(1).build(v);
+    assert_eq!(3, corpus.miners.len());
+    assert_eq!(4, corpus.clients.len());
+    assert_eq!(20, corpus.deals.len());
+    assert_eq!(10, corpus.allocations.len());
+    assert_eq!(corpus.deals[..6], corpus.activated_deals[..]);
+    assert_eq!(corpus.allocations[..4], corpus.claims[..]);
+
+    // Each miner onboarded a sector per activated deal, then per claim, then one CC sector.
+    assert_eq!(vec![vec![0, 1, 2, 3, 4], vec![0, 1, 2, 3], vec![0, 1, 2, 3]], corpus.sectors);
+    for (miner, sectors) in corpus.miners.iter().zip(&corpus.sectors) {
+        for sector_number in sectors {
+            assert_eq!(*sector_number, sector_info(v, miner, *sector_number).sector_number);
+        }
+    }
+
+    // The leading deals are active in their providers' sectors, the rest await activation.
+    let store = DynBlockstore::wrap(v.blockstore());
+    let market: MarketState = get_state(v, &STORAGE_MARKET_ACTOR_ADDR).unwrap();
+    for (i, deal_id) in corpus.deals.iter().enumerate() {
+        let proposal = market.get_proposal(&store, *deal_id).unwrap();
+        assert_eq!(corpus.miners[i % 3], proposal.provider);
+        assert_eq!(corpus.clients[i % 4], proposal.client);
+        let state = market.find_deal_state(&store, *deal_id).unwrap();
+        if i < 6 {
+            let state = state.unwrap();
+            assert_eq!((i / 3) as u64, state.sector_number);
+            let sector = sector_info(v, &proposal.provider, state.sector_number);
+            assert_eq!(sector.activation, state.sector_start_epoch);
+        } else {
+            assert!(state.is_none());
+        }
+    }
+
+    // The leading allocations were claimed by sectors following their providers' deal sectors,
+    // and the rest remain allocated, backed by datacap held by the registry.
+    for (i, claim_id) in corpus.claims.iter().enumerate() {
+        let provider = corpus.miners[i % 3];
+        let claim = verifreg_list_claims(v, provider.id().unwrap()).remove(claim_id).unwrap();
+        assert_eq!(corpus.clients[i % 4].id().unwrap(), claim.client);
+        assert_eq!(2 + (i / 3) as u64, claim.sector);
+        let sector = sector_info(v, &provider, claim.sector);
+        assert!(sector.verified_deal_weight.is_positive());
+    }
+    let verifreg: VerifregState = get_state(v, &VERIFIED_REGISTRY_ACTOR_ADDR).unwrap();
+    assert_eq!(11, verifreg.next_allocation_id);
+    let mut allocations = verifreg.load_allocs(&store).unwrap();
+    let mut allocation_count = 0;
+    for client in &corpus.clients {
+        allocations
+            .for_each_in(client.id().unwrap(), |_, _| {
+                allocation_count += 1;
+                Ok(())
+            })
+            .unwrap();
+    }
+    assert_eq!(6, allocation_count);
+
+    assert_invariants(v, &Policy::default(), None);
+}
@@ -22,8 +22,7 @@ use std::sync::Arc;
 use vm_api::util::{apply_ok, serialize_ok};
 use vm_api::VM;
 
-use crate::util::create_accounts;
-use crate::TEST_FAUCET_ADDR;
+use crate::util::{create_accounts, faucet};
 
 // Generate a statically typed interface for the contracts.
 abigen!(Recursive, "../actors/evm/tests/contracts/Recursive.abi");
@@ -48,7 +47,7 @@ pub fn evm_eth_create_external_test(v: &dyn VM) {
     let eth_addr = Address::new_delegated(EAM_ACTOR_ID, &eth_bits).unwrap();
     apply_ok(
         v,
-        &TEST_FAUCET_ADDR,
+        &faucet(v),
         &eth_addr,
         &TokenAmount::from_whole(10_000),
         METHOD_SEND,
@@ -339,6 +338,97 @@ pub fn evm_empty_initcode_test(v: &dyn VM) {
     );
 }
 
+#[vm_test]
+pub fn evm_create_deterministic_test(v: &dyn VM) {
+    // create the EthAccount
+    let eth_bits = hex_literal::hex!("FEEDFACECAFEBEEF000000000000000000000000");
+    let eth_addr = Address::new_delegated(EAM_ACTOR_ID, &eth_bits).unwrap();
+    apply_ok(
+        v,
+        &faucet(v),
+        &eth_addr,
+        &TokenAmount::from_whole(10_000),
+        METHOD_SEND,
+        None::<RawBytes>,
+    );
+
+    let account = v.resolve_id_address(&eth_addr).unwrap();
+
+    let mut actor = v.actor(&account).unwrap();
+    actor.code = *ETHACCOUNT_ACTOR_CODE_ID;
+    v.set_actor(&account, actor);
+
+    let create = |from: &Address, initcode: Vec<u8>, salt: [u8; 32]| {
+        v.execute_message(
+            from,
+            &EAM_ACTOR_ADDR,
+            &TokenAmount::zero(),
+            fil_actor_eam::Method::CreateDeterministic as u64,
+            Some(serialize_ok(&fil_actor_eam::CreateDeterministicParams { initcode, salt })),
+        )
+        .unwrap()
+    };
+
+    let bytecode =
+        hex::decode(include_str!("../../../actors/evm/tests/contracts/Recursive.hex")).unwrap();
+    let salt = [0x42; 32];
+    let create_result = create(&account, bytecode, salt);
+    assert!(
+        create_result.code.is_success(),
+        "failed to create the new actor {}",
+        create_result.message
+    );
+    let create_return: fil_actor_eam::CreateDeterministicReturn =
+        create_result.ret.unwrap().deserialize().expect("failed to decode results");
+
+    // The contract is deployed where a CREATE3 factory would deploy it on other chains.
+    let proxy = ethers::utils::get_create2_address(
+        EthAddress::from(eth_bits),
+        salt.to_vec(),
+        hex_literal::hex!("67363d3d37363d34f03d5260086018f3").to_vec(),
+    );
+    let expected = ethers::utils::get_contract_address(proxy, 1u64);
+    assert_eq!(expected.0, create_return.eth_address.0);
+
+    // and works
+    let (client, _mock) = Provider::mocked();
+    let contract = Recursive::new(expected, Arc::new(client));
+    let contract_params = contract.enter().calldata().expect("should serialize");
+    let call_result = v
+        .execute_message(
+            &account,
+            &create_return.robust_address.unwrap(),
+            &TokenAmount::zero(),
+            fil_actor_evm::Method::InvokeContract as u64,
+            Some(serialize_ok(&ContractParams(contract_params.to_vec()))),
+        )
+        .unwrap();
+    assert!(call_result.code.is_success(), "failed to call the new actor {}", call_result.message);
+
+    // The address doesn't depend on the initcode, so other code can't be deployed with the same
+    // salt.
+    let create_result = create(&account, vec![], salt);
+    assert!(!create_result.code.is_success(), "deployed over an existing contract");
+
+    // But it can be with another salt, or by another deployer.
+    let other_salt = create(&account, vec![], [0x43; 32]);
+    assert!(other_salt.code.is_success(), "failed to create the new actor {}", other_salt.message);
+    let other_salt: fil_actor_eam::CreateDeterministicReturn =
+        other_salt.ret.unwrap().deserialize().expect("failed to decode results");
+    assert_ne!(create_return.eth_address, other_salt.eth_address);
+
+    let other_account = create_accounts(v, 1, &TokenAmount::from_whole(10_000))[0];
+    let other_deployer = create(&other_account, vec![], salt);
+    assert!(
+        other_deployer.code.is_success(),
+        "failed to create the new actor {}",
+        other_deployer.message
+    );
+    let other_deployer: fil_actor_eam::CreateDeterministicReturn =
+        other_deployer.ret.unwrap().deserialize().expect("failed to decode results");
+    assert_ne!(create_return.eth_address, other_deployer.eth_address);
+}
+
 #[vm_test]
 #[allow(non_snake_case)]
 pub fn evm_staticcall_test(v: &dyn VM) {
@@ -8,7 +8,10 @@ use fvm_shared::{address::Address, econ::TokenAmount, error::ExitCode, METHOD_SE
 use num_traits::Zero;
 use vm_api::{builtin::Type, util::serialize_ok, VM};
 
-use crate::{FIRST_TEST_USER_ADDR, TEST_FAUCET_ADDR};
+use crate::util::{
+    create_accounts_from_id, create_accounts_seeded, create_placeholder_with_id, faucet,
+    next_actor_id,
+};
 
 fn assert_placeholder_actor(exp_bal: TokenAmount, v: &dyn VM, addr: Address) {
     let act = v.actor(&addr).unwrap();
@@ -22,19 +25,12 @@ pub fn placeholder_deploy_test(v: &dyn VM) {
     // Create a placeholder.
     let subaddr = b"foobar";
     let addr = Address::new_delegated(EAM_ACTOR_ID, subaddr).unwrap();
-    assert!(
-        v.execute_message(
-            &TEST_FAUCET_ADDR,
-            &addr,
-            &TokenAmount::from_atto(42u8),
-            METHOD_SEND,
-            None,
-        )
+    let expect_id_addr = Address::new_id(next_actor_id(v));
+    assert!(v
+        .execute_message(&faucet(v), &addr, &TokenAmount::from_atto(42u8), METHOD_SEND, None)
         .unwrap()
         .code
-        .is_success()
-    );
-    let expect_id_addr = Address::new_id(FIRST_TEST_USER_ADDR);
+        .is_success());
     assert_placeholder_actor(TokenAmount::from_atto(42u8), v, expect_id_addr);
 
     // Make sure we assigned the right f4 address.
@@ -83,3 +79,19 @@ pub fn placeholder_deploy_test(v: &dyn VM) {
     let msig_ctor_res = deploy();
     assert_eq!(ExitCode::USR_FORBIDDEN, msig_ctor_res.code);
 }
+
+#[vm_test]
+pub fn deterministic_id_allocation_test(v: &dyn VM) {
+    // Accounts are allocated sequential IDs from the one given.
+    let accounts = create_accounts_from_id(v, 5000, 2, &TokenAmount::from_whole(1));
+    assert_eq!(vec![Address::new_id(5000), Address::new_id(5001)], accounts);
+
+    // A placeholder is created at the given ID for its delegated address.
+    let addr = Address::new_delegated(EAM_ACTOR_ID, b"foobar").unwrap();
+    let id_addr = create_placeholder_with_id(v, 6000, &addr, &TokenAmount::from_atto(42u8));
+    assert_placeholder_actor(TokenAmount::from_atto(42u8), v, id_addr);
+
+    // Allocation continues from there.
+    let accounts = create_accounts_seeded(v, 1, &TokenAmount::zero(), 1, &faucet(v));
+    assert_eq!(vec![Address::new_id(6001)], accounts);
+}
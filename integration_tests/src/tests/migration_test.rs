@@ -0,0 +1,641 @@
+use cid::multihash::Code;
+use export_macro::vm_test;
+use fil_actor_cron::{Entry as CronEntry, State as CronState};
+use fil_actor_init::{ExecParams, ExecReturn, Method as InitMethod};
+use fil_actor_market::{
+    CancelPublishedDealParams, CancelPublishedDealReturn, Method as MarketMethod,
+    State as MarketState,
+};
+use fil_actor_miner::{
+    max_prove_commit_duration, Method as MinerMethod, PendingOwnerChange, State as MinerState,
+};
+use fil_actor_multisig::{
+    compute_proposal_hash, ApproveReturn, ExecutionDelay, Method as MsigMethod, PendingTxnMap,
+    ProposeParams, ProposeReturn, SetExecutionDelaysParams, State as MsigState, Transaction, TxnID,
+    TxnIDParams, PENDING_TXN_CONFIG,
+};
+use fil_actor_paych::{ConstructorParams as PaychConstructorParams, State as PaychState};
+use fil_actor_power::{
+    epoch_key, CronEventQueue, GetPledgeRatiosReturn, Method as PowerMethod, MinerChanges,
+    State as PowerState, UpdatePledgeTotalParams,
+};
+use fil_actor_reward::{AwardBlockRewardParams, Method as RewardMethod, State as RewardState};
+use fil_actors_runtime::cbor::serialize;
+use fil_actors_runtime::runtime::Policy;
+use fil_actors_runtime::test_utils::PAYCH_ACTOR_CODE_ID;
+use fil_actors_runtime::{
+    Multimap, CRON_ACTOR_ADDR, EXPECTED_LEADERS_PER_EPOCH, INIT_ACTOR_ADDR, REWARD_ACTOR_ADDR,
+    STORAGE_MARKET_ACTOR_ADDR, STORAGE_POWER_ACTOR_ADDR, SYSTEM_ACTOR_ADDR,
+};
+use fil_builtin_actors_state::migration::cron::{
+    EntryV12 as CronEntryV12, StateV12 as CronStateV12,
+};
+use fil_builtin_actors_state::migration::market::StateV12 as MarketStateV12;
+use fil_builtin_actors_state::migration::miner::{MinerInfoV12, StateV12 as MinerStateV12};
+use fil_builtin_actors_state::migration::multisig::{
+    PendingTxnMapV12, StateV12 as MsigStateV12, TransactionV12,
+};
+use fil_builtin_actors_state::migration::paych::StateV12 as PaychStateV12;
+use fil_builtin_actors_state::migration::power::{
+    StateV12 as PowerStateV12, CRON_QUEUE_AMT_BITWIDTH_V12, CRON_QUEUE_HAMT_BITWIDTH_V12,
+};
+use fil_builtin_actors_state::migration::reward::StateV12 as RewardStateV12;
+use fvm_ipld_encoding::{CborStore, RawBytes};
+use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
+use fvm_shared::sector::{RegisteredPoStProof, RegisteredSealProof};
+use fvm_shared::METHOD_SEND;
+use num_traits::Zero;
+use vm_api::util::{apply_code, apply_ok, get_state, DynBlockstore};
+use vm_api::VM;
+
+use super::multisig_test::create_msig;
+use crate::deals::{DealBatcher, DealOptions};
+use crate::util::{
+    assert_invariants, create_accounts, create_miner, cron_tick, market_add_balance,
+    migrate_from_v12, miner_info, precommit_meta_data_from_deals, precommit_sectors_v2, put_state,
+};
+
+#[vm_test]
+pub fn migrate_cron_state_test(v: &dyn VM) {
+    let receivers = [
+        (STORAGE_POWER_ACTOR_ADDR, PowerMethod::OnEpochTickEnd as u64),
+        (STORAGE_MARKET_ACTOR_ADDR, MarketMethod::CronTick as u64),
+    ];
+    let entries = receivers
+        .iter()
+        .map(|(receiver, method_num)| CronEntryV12 { receiver: *receiver, method_num: *method_num })
+        .collect();
+    put_state(v, &CRON_ACTOR_ADDR, &CronStateV12 { entries });
+
+    migrate_from_v12(v, &[CRON_ACTOR_ADDR]);
+
+    // Entries keep their order, and are called as they were before.
+    let st: CronState = get_state(v, &CRON_ACTOR_ADDR).unwrap();
+    let expected: Vec<CronEntry> = receivers
+        .iter()
+        .map(|(receiver, method_num)| CronEntry {
+            receiver: *receiver,
+            method_num: *method_num,
+            priority: 0,
+            gas_limit: None,
+            disabled: false,
+        })
+        .collect();
+    assert_eq!(expected, st.entries);
+    // No actors are registered to be called in rotation.
+    let store = DynBlockstore::wrap(v.blockstore());
+    assert!(st.load_registered(&store).unwrap().is_empty());
+    assert_eq!(None, st.registered_cursor);
+    cron_tick(v);
+    assert_invariants(v, &Policy::default(), None);
+}
+
+#[vm_test]
+pub fn migrate_power_state_test(v: &dyn VM) {
+    let addrs = create_accounts(v, 1, &TokenAmount::from_whole(10_000));
+    let (miner, _) = create_miner(
+        v,
+        &addrs[0],
+        &addrs[0],
+        RegisteredPoStProof::StackedDRGWindow32GiBV1P1,
+        &TokenAmount::from_whole(1_000),
+    );
+    cron_tick(v);
+    let st: PowerState = get_state(v, &STORAGE_POWER_ACTOR_ADDR).unwrap();
+
+    // Write the state in the v12 layout, with the miner's cron event in a multimap.
+    let store = DynBlockstore::wrap(v.blockstore());
+    let mut legacy =
+        Multimap::new(&store, CRON_QUEUE_HAMT_BITWIDTH_V12, CRON_QUEUE_AMT_BITWIDTH_V12);
+    let queue = CronEventQueue::new(&store, &st.cron_event_queue).unwrap();
+    let mut enrolled = 0;
+    queue
+        .amt
+        .for_each(|epoch, batch| {
+            for event in &batch.events {
+                legacy.add(epoch_key(epoch as ChainEpoch), event.clone())?;
+                enrolled += 1;
+            }
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(1, enrolled);
+    let old = PowerStateV12 {
+        total_raw_byte_power: st.total_raw_byte_power.clone(),
+        total_bytes_committed: st.total_bytes_committed.clone(),
+        total_quality_adj_power: st.total_quality_adj_power.clone(),
+        total_qa_bytes_committed: st.total_qa_bytes_committed.clone(),
+        total_pledge_collateral: st.total_pledge_collateral.clone(),
+        this_epoch_raw_byte_power: st.this_epoch_raw_byte_power.clone(),
+        this_epoch_quality_adj_power: st.this_epoch_quality_adj_power.clone(),
+        this_epoch_pledge_collateral: st.this_epoch_pledge_collateral.clone(),
+        this_epoch_qa_power_smoothed: st.this_epoch_qa_power_smoothed.clone(),
+        miner_count: st.miner_count,
+        miner_above_min_power_count: st.miner_above_min_power_count,
+        cron_event_queue: legacy.root().unwrap(),
+        first_cron_epoch: st.first_cron_epoch,
+        claims: st.claims,
+        proof_validation_batch: st.proof_validation_batch,
+    };
+    let pledge_ratios_before = pledge_ratios(v);
+    put_state(v, &STORAGE_POWER_ACTOR_ADDR, &old);
+
+    migrate_from_v12(v, &[STORAGE_POWER_ACTOR_ADDR]);
+
+    // The queue holds the same events, so has the same root.
+    let migrated: PowerState = get_state(v, &STORAGE_POWER_ACTOR_ADDR).unwrap();
+    assert_eq!(st.cron_event_queue, migrated.cron_event_queue);
+    assert_eq!(st.first_cron_epoch, migrated.first_cron_epoch);
+    assert_eq!(st.claims, migrated.claims);
+    assert_eq!(st.power_totals(), migrated.power_totals());
+    assert_eq!(st.total_pledge_collateral, migrated.total_pledge_collateral);
+    assert_eq!(None, migrated.aggregate_recompute);
+    assert!(migrated.pledge_added.is_zero());
+    assert!(migrated.pledge_released.is_zero());
+    assert!(migrated.this_epoch_pledge_added.is_zero());
+    assert!(migrated.this_epoch_pledge_released.is_zero());
+    assert_eq!(MinerChanges::default(), migrated.miner_changes);
+    assert_eq!(pledge_ratios(v), pledge_ratios_before);
+
+    // Pledge flows are recorded from the migrated state, and reported after the next cron tick.
+    let pledge = TokenAmount::from_whole(5);
+    update_pledge_total(v, &miner, pledge.clone());
+    cron_tick(v);
+    let ratios = pledge_ratios(v);
+    assert_eq!(pledge, ratios.this_epoch_pledge_added);
+    assert!(ratios.this_epoch_pledge_released.is_zero());
+
+    update_pledge_total(v, &miner, -pledge.clone());
+    cron_tick(v);
+    let ratios = pledge_ratios(v);
+    assert!(ratios.this_epoch_pledge_added.is_zero());
+    assert_eq!(pledge, ratios.this_epoch_pledge_released);
+
+    // Miner changes are recorded from the migrated state, and cleared by the next cron tick.
+    create_miner(
+        v,
+        &addrs[0],
+        &addrs[0],
+        RegisteredPoStProof::StackedDRGWindow32GiBV1P1,
+        &TokenAmount::from_whole(1_000),
+    );
+    let st: PowerState = get_state(v, &STORAGE_POWER_ACTOR_ADDR).unwrap();
+    assert_eq!(MinerChanges { created: 1, ..Default::default() }, st.miner_changes);
+    cron_tick(v);
+    let st: PowerState = get_state(v, &STORAGE_POWER_ACTOR_ADDR).unwrap();
+    assert_eq!(MinerChanges::default(), st.miner_changes);
+    assert_invariants(v, &Policy::default(), None);
+}
+
+fn pledge_ratios(v: &dyn VM) -> GetPledgeRatiosReturn {
+    apply_ok(
+        v,
+        &SYSTEM_ACTOR_ADDR,
+        &STORAGE_POWER_ACTOR_ADDR,
+        &TokenAmount::zero(),
+        PowerMethod::GetPledgeRatiosExported as u64,
+        None::<RawBytes>,
+    )
+    .deserialize()
+    .unwrap()
+}
+
+fn update_pledge_total(v: &dyn VM, miner: &Address, pledge_delta: TokenAmount) {
+    apply_ok(
+        v,
+        miner,
+        &STORAGE_POWER_ACTOR_ADDR,
+        &TokenAmount::zero(),
+        PowerMethod::UpdatePledgeTotal as u64,
+        Some(UpdatePledgeTotalParams { pledge_delta }),
+    );
+}
+
+#[vm_test]
+pub fn migrate_multisig_state_test(v: &dyn VM) {
+    let addrs = create_accounts(v, 2, &TokenAmount::from_whole(10_000));
+    let (alice, bob) = (addrs[0], addrs[1]);
+    let msig = create_msig(v, &addrs, 2);
+    let value = TokenAmount::from_nano(3);
+    let propose_params = ProposeParams {
+        to: SYSTEM_ACTOR_ADDR,
+        value: value.clone(),
+        method: METHOD_SEND,
+        params: RawBytes::default(),
+        expiration_epoch: None,
+    };
+    apply_ok(v, &alice, &msig, &value, MsigMethod::Propose as u64, Some(propose_params));
+
+    // Write the state and its pending transaction in the v12 layout.
+    let st: MsigState = get_state(v, &msig).unwrap();
+    let store = DynBlockstore::wrap(v.blockstore());
+    let txns =
+        PendingTxnMap::load(&store, &st.pending_txs, PENDING_TXN_CONFIG, "pending txns").unwrap();
+    let mut old_txns = PendingTxnMapV12::empty(&store, PENDING_TXN_CONFIG, "pending txns");
+    txns.for_each(|id, txn| {
+        let old_txn = TransactionV12 {
+            to: txn.to,
+            value: txn.value.clone(),
+            method: txn.method,
+            params: txn.params.clone(),
+            approved: txn.approved.clone(),
+        };
+        old_txns.set(&id, old_txn)?;
+        Ok(())
+    })
+    .unwrap();
+    let old = MsigStateV12 {
+        signers: st.signers.clone(),
+        num_approvals_threshold: st.num_approvals_threshold,
+        next_tx_id: st.next_tx_id,
+        initial_balance: st.initial_balance.clone(),
+        start_epoch: st.start_epoch,
+        unlock_duration: st.unlock_duration,
+        pending_txs: old_txns.flush().unwrap(),
+    };
+    put_state(v, &msig, &old);
+
+    migrate_from_v12(v, &[msig]);
+
+    let migrated: MsigState = get_state(v, &msig).unwrap();
+    assert_eq!(st.pending_txs, migrated.pending_txs);
+    assert!(migrated.execution_delays.is_empty());
+
+    // The pending transaction is approved with its original proposal hash, and executed.
+    let txn = Transaction {
+        to: SYSTEM_ACTOR_ADDR,
+        value,
+        method: METHOD_SEND,
+        params: RawBytes::default(),
+        approved: vec![alice],
+        expiration_epoch: None,
+        executable_epoch: None,
+    };
+    let proposal_hash = compute_proposal_hash(&txn, v.primitives()).unwrap().to_vec();
+    let ret: ApproveReturn = apply_ok(
+        v,
+        &bob,
+        &msig,
+        &TokenAmount::zero(),
+        MsigMethod::Approve as u64,
+        Some(TxnIDParams { id: TxnID(0), proposal_hash }),
+    )
+    .deserialize()
+    .unwrap();
+    assert!(ret.applied);
+
+    // The migrated wallet can configure execution delays, which apply to later transactions.
+    let delay = 10;
+    let delays = SetExecutionDelaysParams {
+        delays: vec![ExecutionDelay { value_above: TokenAmount::zero(), delay }],
+    };
+    let propose_params = ProposeParams {
+        to: msig,
+        value: TokenAmount::zero(),
+        method: MsigMethod::SetExecutionDelays as u64,
+        params: serialize(&delays, "execution delays").unwrap(),
+        expiration_epoch: None,
+    };
+    apply_ok(
+        v,
+        &alice,
+        &msig,
+        &TokenAmount::zero(),
+        MsigMethod::Propose as u64,
+        Some(propose_params),
+    );
+    let ret: ApproveReturn = approve(v, &bob, &msig, TxnID(1));
+    assert!(ret.applied);
+
+    let propose_params = ProposeParams {
+        to: SYSTEM_ACTOR_ADDR,
+        value: TokenAmount::from_nano(1),
+        method: METHOD_SEND,
+        params: RawBytes::default(),
+        expiration_epoch: None,
+    };
+    let ret: ProposeReturn = apply_ok(
+        v,
+        &alice,
+        &msig,
+        &TokenAmount::zero(),
+        MsigMethod::Propose as u64,
+        Some(propose_params),
+    )
+    .deserialize()
+    .unwrap();
+    let ret: ApproveReturn = approve(v, &bob, &msig, ret.txn_id);
+    assert!(!ret.applied);
+    let st: MsigState = get_state(v, &msig).unwrap();
+    let txns =
+        PendingTxnMap::load(&store, &st.pending_txs, PENDING_TXN_CONFIG, "pending txns").unwrap();
+    let txn = txns.get(&TxnID(2)).unwrap().unwrap();
+    assert_eq!(Some(v.epoch() + delay), txn.executable_epoch);
+    assert_invariants(v, &Policy::default(), None);
+}
+
+fn approve(v: &dyn VM, approver: &Address, msig: &Address, id: TxnID) -> ApproveReturn {
+    let st: MsigState = get_state(v, msig).unwrap();
+    let store = DynBlockstore::wrap(v.blockstore());
+    let txns =
+        PendingTxnMap::load(&store, &st.pending_txs, PENDING_TXN_CONFIG, "pending txns").unwrap();
+    let txn = txns.get(&id).unwrap().unwrap();
+    let proposal_hash = compute_proposal_hash(txn, v.primitives()).unwrap().to_vec();
+    apply_ok(
+        v,
+        approver,
+        msig,
+        &TokenAmount::zero(),
+        MsigMethod::Approve as u64,
+        Some(TxnIDParams { id, proposal_hash }),
+    )
+    .deserialize()
+    .unwrap()
+}
+
+#[vm_test]
+pub fn migrate_paych_state_test(v: &dyn VM) {
+    let addrs = create_accounts(v, 2, &TokenAmount::from_whole(10_000));
+    let (payer, payee) = (addrs[0], addrs[1]);
+    let ctor_params =
+        serialize(&PaychConstructorParams { from: payer, to: payee }, "paych ctor params").unwrap();
+    let ret: ExecReturn = apply_ok(
+        v,
+        &payer,
+        &INIT_ACTOR_ADDR,
+        &TokenAmount::from_whole(100),
+        InitMethod::Exec as u64,
+        Some(ExecParams { code_cid: *PAYCH_ACTOR_CODE_ID, constructor_params: ctor_params }),
+    )
+    .deserialize()
+    .unwrap();
+    let paych = ret.id_address;
+
+    // Write the state in the v12 layout.
+    let st: PaychState = get_state(v, &paych).unwrap();
+    let old = PaychStateV12 {
+        from: st.from,
+        to: st.to,
+        to_send: st.to_send.clone(),
+        settling_at: st.settling_at,
+        min_settle_height: st.min_settle_height,
+        lane_states: st.lane_states,
+    };
+    put_state(v, &paych, &old);
+
+    migrate_from_v12(v, &[paych]);
+
+    let migrated: PaychState = get_state(v, &paych).unwrap();
+    assert_eq!((st.from, st.to), (migrated.from, migrated.to));
+    assert_eq!(st.to_send, migrated.to_send);
+    assert_eq!(st.lane_states, migrated.lane_states);
+    assert_eq!(0, migrated.settlement_nonce);
+    assert_invariants(v, &Policy::default(), None);
+}
+
+#[vm_test]
+pub fn migrate_market_state_test(v: &dyn VM) {
+    let policy = Policy::default();
+    let seal_proof = RegisteredSealProof::StackedDRG32GiBV1P1;
+    let addrs = create_accounts(v, 2, &TokenAmount::from_whole(10_000));
+    let (owner, client) = (addrs[0], addrs[1]);
+    let (miner, _) = create_miner(
+        v,
+        &owner,
+        &owner,
+        seal_proof.registered_window_post_proof().unwrap(),
+        &TokenAmount::from_whole(1_000),
+    );
+    market_add_balance(v, &client, &client, &TokenAmount::from_whole(100));
+    market_add_balance(v, &owner, &miner, &TokenAmount::from_whole(100));
+
+    let deal_start = v.epoch() + max_prove_commit_duration(&policy, seal_proof).unwrap();
+    let mut batcher = DealBatcher::new(v, DealOptions { deal_start, ..DealOptions::default() });
+    batcher.stage(client, miner);
+    batcher.stage(client, miner);
+    let deals = batcher.publish_ok(owner).ids;
+    let proposal = batcher.proposals()[1].clone();
+
+    // Write the state in the v12 layout.
+    let st: MarketState = get_state(v, &STORAGE_MARKET_ACTOR_ADDR).unwrap();
+    let old = MarketStateV12 {
+        proposals: st.proposals,
+        states: st.states,
+        pending_proposals: st.pending_proposals,
+        escrow_table: st.escrow_table,
+        locked_table: st.locked_table,
+        next_id: st.next_id,
+        deal_ops_by_epoch: st.deal_ops_by_epoch,
+        last_cron: st.last_cron,
+        total_client_locked_collateral: st.total_client_locked_collateral.clone(),
+        total_provider_locked_collateral: st.total_provider_locked_collateral.clone(),
+        total_client_storage_fee: st.total_client_storage_fee.clone(),
+        pending_deal_allocation_ids: st.pending_deal_allocation_ids,
+        provider_sectors: st.provider_sectors,
+    };
+    put_state(v, &STORAGE_MARKET_ACTOR_ADDR, &old);
+
+    migrate_from_v12(v, &[STORAGE_MARKET_ACTOR_ADDR]);
+
+    let migrated: MarketState = get_state(v, &STORAGE_MARKET_ACTOR_ADDR).unwrap();
+    assert_eq!(st.proposals, migrated.proposals);
+    assert_eq!(st.pending_proposals, migrated.pending_proposals);
+    assert_eq!((st.escrow_table, st.locked_table), (migrated.escrow_table, migrated.locked_table));
+    assert_eq!(st.deal_ops_by_epoch, migrated.deal_ops_by_epoch);
+    assert_eq!(st.get_total_locked(), migrated.get_total_locked());
+    // The new collections are empty, as in a newly constructed state.
+    let empty = MarketState::new(&DynBlockstore::wrap(v.blockstore())).unwrap();
+    assert_eq!(empty.client_deal_stats, migrated.client_deal_stats);
+    assert_eq!(empty.pending_deal_terminations, migrated.pending_deal_terminations);
+    assert_eq!(empty.activation_extensions, migrated.activation_extensions);
+    assert_eq!(empty.precommitted_deals, migrated.precommitted_deals);
+
+    // A deal verified for a pre-committed sector after the migration can no longer be cancelled.
+    precommit_sectors_v2(
+        v,
+        1,
+        1,
+        vec![precommit_meta_data_from_deals(v, &deals[..1], seal_proof)],
+        &owner,
+        &miner,
+        seal_proof,
+        0,
+        true,
+        None,
+    );
+    apply_code(
+        v,
+        &client,
+        &STORAGE_MARKET_ACTOR_ADDR,
+        &TokenAmount::zero(),
+        MarketMethod::CancelPublishedDealExported as u64,
+        Some(CancelPublishedDealParams { deal_id: deals[0] }),
+        ExitCode::USR_FORBIDDEN,
+    );
+
+    // The other deal is cancelled, paying the provider the minimum fee.
+    let ret: CancelPublishedDealReturn = apply_ok(
+        v,
+        &client,
+        &STORAGE_MARKET_ACTOR_ADDR,
+        &TokenAmount::zero(),
+        MarketMethod::CancelPublishedDealExported as u64,
+        Some(CancelPublishedDealParams { deal_id: deals[1] }),
+    )
+    .deserialize()
+    .unwrap();
+    let min_fee = (&proposal.provider_collateral * policy.deal_cancellation_min_fee_num)
+        .div_floor(policy.deal_cancellation_min_fee_denom);
+    assert_eq!(min_fee, ret.provider_fee);
+    assert_eq!(proposal.client_balance_requirement() - &min_fee, ret.client_refund);
+    assert_invariants(v, &policy, None);
+}
+
+#[vm_test]
+pub fn migrate_miner_state_test(v: &dyn VM) {
+    let addrs = create_accounts(v, 2, &TokenAmount::from_whole(10_000));
+    let (owner, new_owner) = (addrs[0], addrs[1]);
+    let (miner, _) = create_miner(
+        v,
+        &owner,
+        &owner,
+        RegisteredPoStProof::StackedDRGWindow32GiBV1P1,
+        &TokenAmount::from_whole(1_000),
+    );
+
+    // Write the state and info in the v12 layouts, with an owner change pending.
+    let st: MinerState = get_state(v, &miner).unwrap();
+    let info = miner_info(v, &miner);
+    let old_info = MinerInfoV12 {
+        owner: info.owner,
+        worker: info.worker,
+        control_addresses: info.control_addresses.clone(),
+        pending_worker_key: None,
+        peer_id: info.peer_id.clone(),
+        multi_address: info.multi_address.clone(),
+        window_post_proof_type: info.window_post_proof_type,
+        sector_size: info.sector_size,
+        window_post_partition_sectors: info.window_post_partition_sectors,
+        consensus_fault_elapsed: info.consensus_fault_elapsed,
+        pending_owner_address: Some(new_owner),
+        beneficiary: info.beneficiary,
+        beneficiary_term: info.beneficiary_term.clone(),
+        pending_beneficiary_term: None,
+    };
+    let old_info =
+        DynBlockstore::wrap(v.blockstore()).put_cbor(&old_info, Code::Blake2b256).unwrap();
+    let old = MinerStateV12 {
+        info: old_info,
+        pre_commit_deposits: st.pre_commit_deposits.clone(),
+        locked_funds: st.locked_funds.clone(),
+        vesting_funds: st.vesting_funds,
+        fee_debt: st.fee_debt.clone(),
+        initial_pledge: st.initial_pledge.clone(),
+        pre_committed_sectors: st.pre_committed_sectors,
+        pre_committed_sectors_cleanup: st.pre_committed_sectors_cleanup,
+        allocated_sectors: st.allocated_sectors,
+        sectors: st.sectors,
+        proving_period_start: st.proving_period_start,
+        current_deadline: st.current_deadline,
+        deadlines: st.deadlines,
+        early_terminations: st.early_terminations.clone(),
+        deadline_cron_active: st.deadline_cron_active,
+    };
+    put_state(v, &miner, &old);
+
+    migrate_from_v12(v, &[miner]);
+
+    // The migrated state records no expired claim space, as in a new miner.
+    let migrated: MinerState = get_state(v, &miner).unwrap();
+    assert_eq!(st.sectors, migrated.sectors);
+    assert_eq!(st.deadlines, migrated.deadlines);
+    assert_eq!(st.proving_period_start, migrated.proving_period_start);
+    assert_eq!(st.expired_claim_space, migrated.expired_claim_space);
+
+    // The pending owner change may be confirmed for the usual period after the upgrade,
+    // and no provers are authorized.
+    let policy = Policy::default();
+    let migrated_info = miner_info(v, &miner);
+    assert_eq!(info.owner, migrated_info.owner);
+    assert_eq!(info.beneficiary_term, migrated_info.beneficiary_term);
+    assert_eq!(
+        Some(PendingOwnerChange { new_owner, expiration: v.epoch() + policy.owner_change_expiry }),
+        migrated_info.pending_owner_change()
+    );
+    assert!(migrated_info.prover_authorizations.is_empty());
+    apply_ok(
+        v,
+        &new_owner,
+        &miner,
+        &TokenAmount::zero(),
+        MinerMethod::ChangeOwnerAddress as u64,
+        Some(new_owner),
+    );
+    assert_eq!(new_owner, miner_info(v, &miner).owner);
+    assert_invariants(v, &Policy::default(), None);
+}
+
+#[vm_test]
+pub fn migrate_reward_state_test(v: &dyn VM) {
+    let addrs = create_accounts(v, 1, &TokenAmount::from_whole(10_000));
+    let (miner, _) = create_miner(
+        v,
+        &addrs[0],
+        &addrs[0],
+        RegisteredPoStProof::StackedDRGWindow32GiBV1P1,
+        &TokenAmount::from_whole(1_000),
+    );
+
+    // Write the state in the v12 layout.
+    let st: RewardState = get_state(v, &REWARD_ACTOR_ADDR).unwrap();
+    let old = RewardStateV12 {
+        cumsum_baseline: st.cumsum_baseline.clone(),
+        cumsum_realized: st.cumsum_realized.clone(),
+        effective_network_time: st.effective_network_time,
+        effective_baseline_power: st.effective_baseline_power.clone(),
+        this_epoch_reward: st.this_epoch_reward.clone(),
+        this_epoch_reward_smoothed: st.this_epoch_reward_smoothed.clone(),
+        this_epoch_baseline_power: st.this_epoch_baseline_power.clone(),
+        epoch: st.epoch,
+        total_storage_power_reward: st.total_storage_power_reward.clone(),
+        simple_total: st.simple_total.clone(),
+        baseline_total: st.baseline_total.clone(),
+    };
+    put_state(v, &REWARD_ACTOR_ADDR, &old);
+
+    migrate_from_v12(v, &[REWARD_ACTOR_ADDR]);
+
+    // The migrated state has no governor or treasury, and nothing emitted in the upgrade epoch.
+    let migrated: RewardState = get_state(v, &REWARD_ACTOR_ADDR).unwrap();
+    assert_eq!(st.epoch, migrated.epoch);
+    assert_eq!(st.this_epoch_reward, migrated.this_epoch_reward);
+    assert_eq!(st.total_storage_power_reward, migrated.total_storage_power_reward);
+    assert_eq!(None, migrated.governor);
+    assert_eq!(None, migrated.treasury_split);
+    assert!(migrated.total_treasury_reward.is_zero());
+    assert_eq!(v.epoch(), migrated.emission_epoch);
+    assert!(migrated.epoch_emission.is_zero());
+
+    // Block rewards are paid in full and counted against the epoch's emission.
+    apply_ok(
+        v,
+        &SYSTEM_ACTOR_ADDR,
+        &REWARD_ACTOR_ADDR,
+        &TokenAmount::zero(),
+        RewardMethod::AwardBlockReward as u64,
+        Some(AwardBlockRewardParams {
+            miner,
+            penalty: TokenAmount::zero(),
+            gas_reward: TokenAmount::zero(),
+            win_count: 1,
+        }),
+    );
+    let block_reward = st.this_epoch_reward.div_floor(EXPECTED_LEADERS_PER_EPOCH);
+    let awarded: RewardState = get_state(v, &REWARD_ACTOR_ADDR).unwrap();
+    assert_eq!(block_reward, awarded.epoch_emission);
+    assert_eq!(&st.total_storage_power_reward + &block_reward, awarded.total_storage_power_reward);
+    assert_invariants(v, &Policy::default(), None);
+}
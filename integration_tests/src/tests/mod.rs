@@ -1,3 +1,5 @@
+mod adversarial_state_test;
+pub use adversarial_state_test::*;
 mod authenticate_message_test;
 pub use authenticate_message_test::*;
 mod batch_onboarding;
@@ -10,6 +12,8 @@ mod change_owner_test;
 pub use change_owner_test::*;
 mod commit_post_test;
 pub use commit_post_test::*;
+mod corpus_test;
+pub use corpus_test::*;
 mod datacap_tests;
 pub use datacap_tests::*;
 mod evm_test;
@@ -18,18 +22,28 @@ mod extend_sectors_test;
 pub use extend_sectors_test::*;
 mod market_miner_withdrawal_test;
 pub use market_miner_withdrawal_test::*;
+mod migration_test;
+pub use migration_test::*;
 mod multisig_test;
 pub use multisig_test::*;
 mod init_test;
 pub use init_test::*;
+mod paych_test;
+pub use paych_test::*;
 mod power_scenario_tests;
 pub use power_scenario_tests::*;
 mod publish_deals_test;
 pub use publish_deals_test::*;
+mod reachability_test;
+pub use reachability_test::*;
 mod replica_update_test;
 pub use replica_update_test::*;
+mod reward_treasury_test;
+pub use reward_treasury_test::*;
 mod terminate_test;
 pub use terminate_test::*;
+mod verified_aggregate_onboarding_test;
+pub use verified_aggregate_onboarding_test::*;
 mod verified_claim_test;
 pub use verified_claim_test::*;
 mod verifreg_remove_datacap_test;
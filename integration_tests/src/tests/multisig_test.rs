@@ -40,6 +40,7 @@ pub fn proposal_hash_test(v: &dyn VM) {
         value: fil_delta.clone(),
         method: METHOD_SEND,
         params: RawBytes::default(),
+        expiration_epoch: None,
     };
     apply_ok(
         v,
@@ -56,6 +57,8 @@ pub fn proposal_hash_test(v: &dyn VM) {
         method: METHOD_SEND,
         approved: vec![alice],
         params: RawBytes::default(),
+        expiration_epoch: None,
+        executable_epoch: None,
     };
 
     let wrong_hash = compute_proposal_hash(&wrong_tx, v.primitives()).unwrap();
@@ -77,6 +80,8 @@ pub fn proposal_hash_test(v: &dyn VM) {
         method: METHOD_SEND,
         approved: vec![alice],
         params: RawBytes::default(),
+        expiration_epoch: None,
+        executable_epoch: None,
     };
 
     let correct_hash = compute_proposal_hash(&correct_tx, v.primitives()).unwrap();
@@ -119,6 +124,7 @@ pub fn test_delete_self_inner_test(v: &dyn VM, signers: u64, threshold: usize, r
         value: TokenAmount::zero(),
         method: MsigMethod::RemoveSigner as u64,
         params: remove_param_ser,
+        expiration_epoch: None,
     };
 
     // first proposal goes ok and should have txnid = 0
@@ -181,6 +187,7 @@ pub fn swap_self_1_of_2_test(v: &dyn VM) {
         value: TokenAmount::zero(),
         method: MsigMethod::SwapSigner as u64,
         params: serialize(&swap_params, "swap params").unwrap(),
+        expiration_epoch: None,
     };
     // alice succeeds when trying to execute the tx swapping alice for chuck
     apply_ok(
@@ -210,6 +217,7 @@ pub fn swap_self_2_of_3_test(v: &dyn VM) {
         value: TokenAmount::zero(),
         method: MsigMethod::SwapSigner as u64,
         params: serialize(&swap_params, "swap params").unwrap(),
+        expiration_epoch: None,
     };
 
     // proposal from swapped addr goes ok with txnid 0
@@ -242,6 +250,7 @@ pub fn swap_self_2_of_3_test(v: &dyn VM) {
         value: TokenAmount::zero(),
         method: MsigMethod::SwapSigner as u64,
         params: serialize(&swap_params, "swap params").unwrap(),
+        expiration_epoch: None,
     };
 
     // proposal from non swapped goes ok, txnid = 1
@@ -268,7 +277,7 @@ pub fn swap_self_2_of_3_test(v: &dyn VM) {
     assert_invariants(v, &Policy::default(), None)
 }
 
-fn create_msig(v: &dyn VM, signers: &[Address], threshold: u64) -> Address {
+pub(crate) fn create_msig(v: &dyn VM, signers: &[Address], threshold: u64) -> Address {
     assert!(!signers.is_empty());
     let msig_ctor_params = serialize(
         &fil_actor_multisig::ConstructorParams {
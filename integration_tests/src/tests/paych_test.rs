@@ -0,0 +1,138 @@
+use export_macro::vm_test;
+use fil_actor_account::types::AuthenticateMessageParams;
+use fil_actor_eam::{CreateExternalParams, CreateExternalReturn, Method as EamMethod};
+use fil_actor_evm::{Method as EvmMethod, EVM_CONTRACT_REVERTED};
+use fil_actor_init::{ExecParams, ExecReturn, Method as InitMethod};
+use fil_actor_paych::{
+    ConstructorParams as PaychConstructorParams, Method as PaychMethod, SignedVoucher,
+    State as PaychState, UpdateChannelStateParams, SETTLE_DELAY,
+};
+use fil_actors_runtime::cbor::serialize;
+use fil_actors_runtime::runtime::Policy;
+use fil_actors_runtime::test_utils::PAYCH_ACTOR_CODE_ID;
+use fil_actors_runtime::{EAM_ACTOR_ADDR, EAM_ACTOR_ID, INIT_ACTOR_ADDR};
+use fvm_ipld_encoding::RawBytes;
+use fvm_shared::address::Address;
+use fvm_shared::crypto::hash::SupportedHashes;
+use fvm_shared::crypto::signature::{Signature, SignatureType};
+use fvm_shared::econ::TokenAmount;
+use num_traits::Zero;
+use vm_api::util::{apply_code, apply_ok, get_state};
+use vm_api::VM;
+
+use super::evm_test::ContractParams;
+use crate::util::{assert_invariants, create_accounts};
+
+// A smart wallet contract is the payer of a channel created to its delegated address. The
+// payee redeems vouchers the wallet authenticates through AuthenticateMessage, and collects
+// them once the channel settles.
+#[vm_test]
+pub fn paych_evm_wallet_payer_test(v: &dyn VM) {
+    let addrs = create_accounts(v, 2, &TokenAmount::from_whole(10_000));
+    let (owner, payee) = (addrs[0], addrs[1]);
+
+    let bytecode =
+        hex::decode(include_str!("../../../actors/evm/tests/contracts/PaychWallet.hex")).unwrap();
+    let create_return: CreateExternalReturn = apply_ok(
+        v,
+        &owner,
+        &EAM_ACTOR_ADDR,
+        &TokenAmount::zero(),
+        EamMethod::CreateExternal as u64,
+        Some(CreateExternalParams(bytecode)),
+    )
+    .deserialize()
+    .unwrap();
+    let wallet = Address::new_id(create_return.actor_id);
+    let wallet_f410 = Address::new_delegated(EAM_ACTOR_ID, &create_return.eth_address.0).unwrap();
+
+    // The wallet's owner funds a channel from the wallet's delegated address to the payee.
+    let channel_funds = TokenAmount::from_whole(100);
+    let ctor_params =
+        serialize(&PaychConstructorParams { from: wallet_f410, to: payee }, "paych ctor params")
+            .unwrap();
+    let ret: ExecReturn = apply_ok(
+        v,
+        &owner,
+        &INIT_ACTOR_ADDR,
+        &channel_funds,
+        InitMethod::Exec as u64,
+        Some(ExecParams { code_cid: *PAYCH_ACTOR_CODE_ID, constructor_params: ctor_params }),
+    )
+    .deserialize()
+    .unwrap();
+    let paych = ret.id_address;
+    let st: PaychState = get_state(v, &paych).unwrap();
+    assert_eq!((wallet, payee), (st.from, st.to));
+
+    let amount = TokenAmount::from_whole(10);
+    let voucher = SignedVoucher {
+        channel_addr: paych,
+        time_lock_min: 0,
+        time_lock_max: 0,
+        secret_pre_image: vec![],
+        extra: None,
+        lane: 0,
+        nonce: 1,
+        amount: amount.clone(),
+        min_settle_height: 0,
+        merges: vec![],
+        signature: Some(Signature { sig_type: SignatureType::Secp256k1, bytes: vec![] }),
+    };
+    let update = || Some(UpdateChannelStateParams { sv: voucher.clone(), secret: vec![] });
+
+    // The wallet doesn't authenticate a voucher its owner hasn't approved.
+    apply_code(
+        v,
+        &payee,
+        &paych,
+        &TokenAmount::zero(),
+        PaychMethod::UpdateChannelState as u64,
+        update(),
+        EVM_CONTRACT_REVERTED,
+    );
+
+    // The owner approves the authentication the channel will request for the voucher.
+    let auth_params =
+        AuthenticateMessageParams { signature: vec![], message: voucher.signing_bytes().unwrap() };
+    let hash = v
+        .primitives()
+        .hash(SupportedHashes::Keccak256, &RawBytes::serialize(&auth_params).unwrap());
+    apply_ok(
+        v,
+        &owner,
+        &wallet,
+        &TokenAmount::zero(),
+        EvmMethod::InvokeContract as u64,
+        Some(ContractParams(hash)),
+    );
+
+    apply_ok(
+        v,
+        &payee,
+        &paych,
+        &TokenAmount::zero(),
+        PaychMethod::UpdateChannelState as u64,
+        update(),
+    );
+    let st: PaychState = get_state(v, &paych).unwrap();
+    assert_eq!(amount, st.to_send);
+
+    // Once settled, the payee collects the voucher's amount and the rest returns to the wallet.
+    apply_ok(v, &payee, &paych, &TokenAmount::zero(), PaychMethod::Settle as u64, None::<RawBytes>);
+    v.set_epoch(v.epoch() + SETTLE_DELAY);
+    let payee_balance = v.balance(&payee);
+    apply_ok(
+        v,
+        &payee,
+        &paych,
+        &TokenAmount::zero(),
+        PaychMethod::Collect as u64,
+        None::<RawBytes>,
+    );
+    assert_eq!(&payee_balance + &amount, v.balance(&payee));
+    assert_eq!(&channel_funds - &amount, v.balance(&wallet));
+    assert!(v.actor(&paych).is_none());
+
+    assert_invariants(v, &Policy::default(), None);
+}
@@ -1,9 +1,12 @@
 use export_macro::vm_test;
+use fil_actor_account::{Method as AccountMethod, State as AccountState};
 use fil_actor_init::Method as InitMethod;
 use fil_actor_miner::{
     max_prove_commit_duration, Method as MinerMethod, MinerConstructorParams, MIN_SECTOR_EXPIRATION,
 };
-use fil_actor_power::{CreateMinerParams, Method as PowerMethod};
+use fil_actor_power::{
+    CreateMinerDelegatedParams, CreateMinerParams, Method as PowerMethod, MinerChanges,
+};
 use fil_actors_runtime::runtime::Policy;
 
 use fil_actors_runtime::{
@@ -15,31 +18,26 @@ use fvm_ipld_encoding::BytesDe;
 use fvm_ipld_encoding::RawBytes;
 use fvm_shared::address::Address;
 use fvm_shared::econ::TokenAmount;
-use fvm_shared::sector::{RegisteredPoStProof, RegisteredSealProof};
+use fvm_shared::error::ExitCode;
+use fvm_shared::sector::{RegisteredPoStProof, RegisteredSealProof, StoragePower};
 use fvm_shared::METHOD_SEND;
 use num_traits::Zero;
 use vm_api::trace::ExpectInvocation;
-use vm_api::util::{apply_ok, serialize_ok};
+use vm_api::util::{apply_ok, get_state, serialize_ok};
 use vm_api::VM;
 
 use crate::expects::Expect;
 use crate::util::{
-    assert_invariants, create_accounts, create_miner, expect_invariants,
-    invariant_failure_patterns, miner_dline_info, miner_precommit_one_sector_v2, PrecommitMetadata,
+    assert_invariants, create_accounts, create_miner, create_miner_delegated, expect_invariants,
+    faucet, invariant_failure_patterns, miner_dline_info, miner_precommit_one_sector_v2,
+    PrecommitMetadata,
 };
-use crate::{FIRST_TEST_USER_ADDR, TEST_FAUCET_ADDR};
 
 #[vm_test]
 pub fn power_create_miner_test(v: &dyn VM) {
     let owner = Address::new_bls(&[1; fvm_shared::address::BLS_PUB_LEN]).unwrap();
-    v.execute_message(
-        &TEST_FAUCET_ADDR,
-        &owner,
-        &TokenAmount::from_atto(10_000u32),
-        METHOD_SEND,
-        None,
-    )
-    .unwrap();
+    v.execute_message(&faucet(v), &owner, &TokenAmount::from_atto(10_000u32), METHOD_SEND, None)
+        .unwrap();
     let multiaddrs = vec![BytesDe("multiaddr".as_bytes().to_vec())];
     let peer_id = "miner".as_bytes().to_vec();
     let post_proof = RegisteredPoStProof::StackedDRGWindow32GiBV1P1;
@@ -78,7 +76,7 @@ pub fn power_create_miner_test(v: &dyn VM) {
                 subinvocs: Some(vec![ExpectInvocation {
                     // init then calls miner constructor
                     from: INIT_ACTOR_ID,
-                    to: Address::new_id(FIRST_TEST_USER_ADDR + 1),
+                    to: Address::new_id(owner_id + 1),
                     method: MinerMethod::Constructor as u64,
                     params: Some(
                         IpldBlock::serialize_cbor(&MinerConstructorParams {
@@ -103,6 +101,86 @@ pub fn power_create_miner_test(v: &dyn VM) {
     assert_invariants(v, &Policy::default(), None);
 }
 
+#[vm_test]
+pub fn power_create_miner_delegated_test(v: &dyn VM) {
+    let addrs = create_accounts(v, 2, &TokenAmount::from_whole(10_000));
+    let (owner, other) = (addrs[0], addrs[1]);
+    let post_proof = RegisteredPoStProof::StackedDRGWindow32GiBV1P1;
+
+    let salt = b"salt".to_vec();
+    let ret =
+        create_miner_delegated(v, &owner, &owner, post_proof, &TokenAmount::zero(), salt.clone());
+    let owner_id = v.resolve_id_address(&owner).unwrap().id().unwrap();
+    let params = CreateMinerDelegatedParams {
+        miner: CreateMinerParams {
+            owner,
+            worker: owner,
+            window_post_proof_type: post_proof,
+            peer: "miner".as_bytes().to_vec(),
+            multiaddrs: vec![BytesDe("multiaddr".as_bytes().to_vec())],
+        },
+        salt: salt.clone(),
+    };
+    ExpectInvocation {
+        from: owner_id,
+        to: STORAGE_POWER_ACTOR_ADDR,
+        method: PowerMethod::CreateMinerDelegatedExported as u64,
+        params: Some(IpldBlock::serialize_cbor(&params).unwrap()),
+        subinvocs: Some(vec![
+            ExpectInvocation {
+                from: STORAGE_POWER_ACTOR_ID,
+                to: Address::new_id(owner_id),
+                method: AccountMethod::PubkeyAddress as u64,
+                ..Default::default()
+            },
+            ExpectInvocation {
+                from: STORAGE_POWER_ACTOR_ID,
+                to: INIT_ACTOR_ADDR,
+                method: InitMethod::ExecDelegated as u64,
+                subinvocs: Some(vec![ExpectInvocation {
+                    from: INIT_ACTOR_ID,
+                    to: ret.id_address,
+                    method: MinerMethod::Constructor as u64,
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            },
+        ]),
+        ..Default::default()
+    }
+    .matches(v.take_invocations().last().unwrap());
+
+    // The f4 address is in the power actor's namespace, and resolves to the miner.
+    // It's derived from the owner's public key address, which a re-org can't change.
+    let owner_key = get_state::<AccountState>(v, &owner).unwrap().address;
+    let mut preimage = owner_key.to_bytes();
+    preimage.extend_from_slice(&salt);
+    let expected =
+        Address::new_delegated(STORAGE_POWER_ACTOR_ID, &v.primitives().hash_blake2b(&preimage))
+            .unwrap();
+    assert_eq!(expected, ret.delegated_address);
+    assert_eq!(Some(ret.id_address), v.resolve_id_address(&ret.delegated_address));
+    assert_eq!(Some(expected), v.actor(&ret.id_address).unwrap().delegated_address);
+
+    // The same salt gives another creator a different address.
+    let other_ret =
+        create_miner_delegated(v, &other, &other, post_proof, &TokenAmount::zero(), salt.clone());
+    assert_ne!(ret.delegated_address, other_ret.delegated_address);
+
+    // A creator can't reuse a salt.
+    let res = v
+        .execute_message(
+            &owner,
+            &STORAGE_POWER_ACTOR_ADDR,
+            &TokenAmount::zero(),
+            PowerMethod::CreateMinerDelegatedExported as u64,
+            Some(serialize_ok(&params)),
+        )
+        .unwrap();
+    assert_eq!(ExitCode::USR_FORBIDDEN, res.code);
+    assert_invariants(v, &Policy::default(), None);
+}
+
 #[vm_test]
 pub fn cron_tick_test(v: &dyn VM) {
     let addrs = create_accounts(v, 1, &TokenAmount::from_whole(10_000));
@@ -161,6 +239,12 @@ pub fn cron_tick_test(v: &dyn VM) {
             // expect miner call to be missing
             Expect::reward_update_kpi(),
         ]),
+        // The miner was created since genesis, and has no power.
+        events: vec![Expect::build_power_summary_event(
+            &StoragePower::zero(),
+            &StoragePower::zero(),
+            &MinerChanges { created: 1, ..Default::default() },
+        )],
         ..Default::default()
     }
     .matches(v.take_invocations().first().unwrap());
@@ -201,6 +285,11 @@ pub fn cron_tick_test(v: &dyn VM) {
         to: STORAGE_POWER_ACTOR_ADDR,
         method: PowerMethod::OnEpochTickEnd as u64,
         subinvocs: Some(sub_invocs),
+        events: vec![Expect::build_power_summary_event(
+            &StoragePower::zero(),
+            &StoragePower::zero(),
+            &MinerChanges::default(),
+        )],
         ..Default::default()
     }
     .matches(v.take_invocations().first().unwrap());
@@ -1,7 +1,9 @@
 use fil_actor_account::types::AuthenticateMessageParams;
 use fil_actor_account::Method as AccountMethod;
 use fil_actor_market::{
-    ClientDealProposal, DealProposal, Label, Method as MarketMethod, PublishStorageDealsParams,
+    deal_proposal_typed_data_hash, ClientDealProposal, DealProposal, Label, Method as MarketMethod,
+    ProposalAuthScheme, PublishStorageDealsParams, PublishStorageDealsReturn,
+    MARKET_NOTIFY_DEAL_METHOD,
 };
 use fil_actor_miner::max_prove_commit_duration;
 use fil_actor_verifreg::{AddVerifiedClientParams, Method as VerifregMethod};
@@ -9,11 +11,13 @@ use fil_actors_runtime::cbor::serialize;
 use fil_actors_runtime::network::EPOCHS_IN_DAY;
 use fil_actors_runtime::runtime::Policy;
 use fil_actors_runtime::{
-    test_utils::*, STORAGE_MARKET_ACTOR_ADDR, STORAGE_MARKET_ACTOR_ID, VERIFIED_REGISTRY_ACTOR_ADDR,
+    test_utils::*, EAM_ACTOR_ADDR, STORAGE_MARKET_ACTOR_ADDR, STORAGE_MARKET_ACTOR_ID,
+    VERIFIED_REGISTRY_ACTOR_ADDR,
 };
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_shared::address::Address;
 use fvm_shared::bigint::Zero;
+use fvm_shared::chainid::ChainID;
 use fvm_shared::clock::ChainEpoch;
 use fvm_shared::crypto::signature::{Signature, SignatureType};
 use fvm_shared::econ::TokenAmount;
@@ -24,14 +28,14 @@ use vm_api::trace::ExpectInvocation;
 use vm_api::util::{apply_ok, serialize_ok};
 use vm_api::VM;
 
+use super::evm_test::ContractParams;
 use crate::deals::{DealBatcher, DealOptions};
 use crate::expects::Expect;
 
 use crate::util::{
-    assert_invariants, bf_all, create_accounts, create_accounts_seeded, create_miner,
+    assert_invariants, bf_all, create_accounts, create_accounts_seeded, create_miner, faucet,
     verifreg_add_verifier,
 };
-use crate::TEST_FAUCET_ADDR;
 use export_macro::vm_test;
 
 struct Addrs {
@@ -249,7 +253,7 @@ pub fn psd_not_enough_provider_lockup_for_batch_test(v: &dyn VM) {
     let (a, deal_start) = setup(v);
     // note different seed, different address
     let cheap_worker =
-        create_accounts_seeded(v, 1, &TokenAmount::from_whole(10_000), 444, &TEST_FAUCET_ADDR)[0];
+        create_accounts_seeded(v, 1, &TokenAmount::from_whole(10_000), 444, &faucet(v))[0];
     let cheap_maddr = create_miner(
         v,
         &cheap_worker,
@@ -375,8 +379,7 @@ pub fn psd_random_assortment_of_failures_test(v: &dyn VM) {
         MarketMethod::AddBalance as u64,
         Some(a.cheap_client),
     );
-    let broke_client =
-        create_accounts_seeded(v, 1, &TokenAmount::zero(), 555, &TEST_FAUCET_ADDR)[0];
+    let broke_client = create_accounts_seeded(v, 1, &TokenAmount::zero(), 555, &faucet(v))[0];
 
     batcher.stage_with_opts_label(
         a.verified_client,
@@ -474,6 +477,7 @@ pub fn psd_bad_sig_test(v: &dyn VM) {
                 sig_type: SignatureType::BLS,
                 bytes: invalid_sig_bytes.clone(),
             },
+            auth_scheme: ProposalAuthScheme::Cbor,
         }],
     };
     let ret = v
@@ -520,6 +524,131 @@ pub fn psd_bad_sig_test(v: &dyn VM) {
     assert_invariants(v, &Policy::default(), None)
 }
 
+// A contract client authorizes a deal by its EIP-712 typed-data hash, which the contract
+// approves in advance, without any signature.
+#[vm_test]
+pub fn psd_contract_client_typed_data_test(v: &dyn VM) {
+    let (a, deal_start) = setup(v);
+    let creator = a.not_miner;
+
+    let bytecode =
+        hex::decode(include_str!("../../../actors/evm/tests/contracts/DealClient.hex")).unwrap();
+    let create_return: fil_actor_eam::CreateExternalReturn = apply_ok(
+        v,
+        &creator,
+        &EAM_ACTOR_ADDR,
+        &TokenAmount::zero(),
+        fil_actor_eam::Method::CreateExternal as u64,
+        Some(fil_actor_eam::CreateExternalParams(bytecode)),
+    )
+    .deserialize()
+    .unwrap();
+    let client = Address::new_id(create_return.actor_id);
+    apply_ok(
+        v,
+        &creator,
+        &STORAGE_MARKET_ACTOR_ADDR,
+        &TokenAmount::from_whole(100),
+        MarketMethod::AddBalance as u64,
+        Some(client),
+    );
+
+    let DealOptions { price_per_epoch, provider_collateral, client_collateral, .. } =
+        DealOptions::default();
+    let deal_label = "deal0".to_string();
+    let proposal = DealProposal {
+        piece_cid: make_piece_cid(deal_label.as_bytes()),
+        piece_size: PaddedPieceSize(1 << 30),
+        verified_deal: false,
+        client,
+        provider: a.maddr,
+        label: Label::String(deal_label),
+        start_epoch: deal_start,
+        end_epoch: deal_start + DEAL_LIFETIME,
+        storage_price_per_epoch: price_per_epoch,
+        provider_collateral,
+        client_collateral,
+    };
+    let publish = |auth_scheme| {
+        let params = PublishStorageDealsParams {
+            deals: vec![ClientDealProposal {
+                proposal: proposal.clone(),
+                client_signature: Signature { sig_type: SignatureType::BLS, bytes: vec![] },
+                auth_scheme,
+            }],
+        };
+        v.execute_message(
+            &a.worker,
+            &STORAGE_MARKET_ACTOR_ADDR,
+            &TokenAmount::zero(),
+            MarketMethod::PublishStorageDeals as u64,
+            Some(serialize_ok(&params)),
+        )
+        .unwrap()
+    };
+
+    // The contract doesn't authenticate a proposal it hasn't approved.
+    assert_eq!(ExitCode::USR_ILLEGAL_ARGUMENT, publish(ProposalAuthScheme::TypedData).code);
+
+    // The test VM's chain ID is zero.
+    let hash = deal_proposal_typed_data_hash(v.primitives(), ChainID::from(0), &proposal).unwrap();
+    apply_ok(
+        v,
+        &creator,
+        &client,
+        &TokenAmount::zero(),
+        fil_actor_evm::Method::InvokeContract as u64,
+        Some(ContractParams(hash.to_vec())),
+    );
+
+    // The contract authenticates the typed-data hash, but not the proposal's CBOR encoding.
+    assert_eq!(ExitCode::USR_ILLEGAL_ARGUMENT, publish(ProposalAuthScheme::Cbor).code);
+    let ret = publish(ProposalAuthScheme::TypedData);
+    assert_eq!(ExitCode::OK, ret.code, "{}", ret.message);
+    let ret: PublishStorageDealsReturn = ret.ret.unwrap().deserialize().unwrap();
+    assert_eq!(vec![0], bf_all(ret.valid_deals.clone()));
+
+    ExpectInvocation {
+        from: a.worker.id().unwrap(),
+        to: STORAGE_MARKET_ACTOR_ADDR,
+        method: MarketMethod::PublishStorageDeals as u64,
+        subinvocs: Some(vec![
+            Expect::miner_is_controlling_address(STORAGE_MARKET_ACTOR_ID, a.maddr, a.worker),
+            Expect::reward_this_epoch(STORAGE_MARKET_ACTOR_ID),
+            Expect::power_current_total(STORAGE_MARKET_ACTOR_ID),
+            ExpectInvocation {
+                from: STORAGE_MARKET_ACTOR_ID,
+                to: client,
+                method: AccountMethod::AuthenticateMessageExported as u64,
+                params: Some(
+                    IpldBlock::serialize_cbor(&AuthenticateMessageParams {
+                        signature: vec![],
+                        message: hash.to_vec(),
+                    })
+                    .unwrap(),
+                ),
+                ..Default::default()
+            },
+            ExpectInvocation {
+                from: STORAGE_MARKET_ACTOR_ID,
+                to: client,
+                method: MARKET_NOTIFY_DEAL_METHOD,
+                ..Default::default()
+            },
+        ]),
+        events: vec![Expect::build_market_event(
+            "deal-published",
+            ret.ids[0],
+            client.id().unwrap(),
+            a.maddr.id().unwrap(),
+        )],
+        ..Default::default()
+    }
+    .matches(v.take_invocations().last().unwrap());
+
+    assert_invariants(v, &Policy::default(), None)
+}
+
 #[vm_test]
 pub fn all_deals_are_good_test(v: &dyn VM) {
     let (a, deal_start) = setup(v);
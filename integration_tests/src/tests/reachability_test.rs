@@ -0,0 +1,54 @@
+use std::collections::BTreeSet;
+
+use cid::multihash::{Code, MultihashDigest};
+use cid::Cid;
+use export_macro::vm_test;
+use fil_actor_miner::State as MinerState;
+use fvm_ipld_encoding::{CborStore, DAG_CBOR};
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::sector::RegisteredSealProof;
+use vm_api::util::{get_state, mutate_state, DynBlockstore};
+use vm_api::VM;
+
+use crate::util::{check_reachability, create_accounts, create_miner};
+
+#[vm_test]
+pub fn state_reachability_test(v: &dyn VM) {
+    let addrs = create_accounts(v, 1, &TokenAmount::from_whole(10_000));
+    let seal_proof = RegisteredSealProof::StackedDRG32GiBV1P1;
+    let (owner, worker) = (addrs[0], addrs[0]);
+    let (miner_id, _) = create_miner(
+        v,
+        &owner,
+        &worker,
+        seal_proof.registered_window_post_proof().unwrap(),
+        &TokenAmount::from_whole(10_000),
+    );
+
+    // Every actor's state is complete.
+    let (report, acc) = check_reachability(v).unwrap();
+    acc.assert_empty();
+    let st: MinerState = get_state(v, &miner_id).unwrap();
+    let miner_blocks = &report.actors[&miner_id];
+    assert!(miner_blocks.reachable.contains(&v.actor(&miner_id).unwrap().state));
+    assert!(miner_blocks.reachable.contains(&st.deadlines));
+    assert!(miner_blocks.missing.is_empty());
+
+    // A deadline flushed without updating the miner's deadlines is orphaned.
+    let store = DynBlockstore::wrap(v.blockstore());
+    let deadlines = st.load_deadlines(&store).unwrap();
+    let mut deadline = deadlines.load_deadline(&store, 0).unwrap();
+    deadline.live_sectors += 1;
+    let orphan = store.put_cbor(&deadline, Code::Blake2b256).unwrap();
+    let (report, acc) = check_reachability(v).unwrap();
+    acc.assert_empty();
+    assert_eq!(BTreeSet::from([orphan]), report.orphans([orphan, st.deadlines]));
+
+    // A link to a block that was never flushed is reported missing.
+    let missing = Cid::new_v1(DAG_CBOR, Code::Blake2b256.digest(b"never flushed"));
+    mutate_state(v, &miner_id, |st: &mut MinerState| st.deadlines = missing);
+    let (report, acc) = check_reachability(v).unwrap();
+    assert_eq!(1, acc.len());
+    assert!(acc.messages()[0].contains(&missing.to_string()));
+    assert_eq!(BTreeSet::from([missing]), report.actors[&miner_id].missing);
+}
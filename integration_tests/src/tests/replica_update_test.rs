@@ -431,6 +431,7 @@ pub fn terminated_sector_failure_test(v: &dyn VM) {
             partition: p_idx,
             sectors: make_bitfield(&[sector_number]),
         }],
+        handoff_manifest: None,
     };
     apply_ok(
         v,
@@ -651,6 +652,7 @@ pub fn terminate_after_upgrade_test(v: &dyn VM) {
             partition: partition_index,
             sectors: make_bitfield(&[sector_number]),
         }],
+        handoff_manifest: None,
     };
 
     apply_ok(
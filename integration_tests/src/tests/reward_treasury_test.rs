@@ -0,0 +1,79 @@
+use export_macro::vm_test;
+use fil_actor_multisig::{Method as MultisigMethod, ProposeParams};
+use fil_actor_reward::{
+    AwardBlockRewardParams, Method as RewardMethod, SetTreasurySplitParams, State as RewardState,
+    TreasurySplit, TREASURY_SHARE_DENOMINATOR,
+};
+use fil_actors_runtime::runtime::Policy;
+use fil_actors_runtime::{EXPECTED_LEADERS_PER_EPOCH, REWARD_ACTOR_ADDR, SYSTEM_ACTOR_ADDR};
+use fvm_ipld_encoding::RawBytes;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::sector::RegisteredSealProof;
+use num_traits::Zero;
+use vm_api::util::{apply_ok, get_state};
+use vm_api::VM;
+
+use crate::util::{assert_invariants, create_accounts, create_miner};
+use crate::{TEST_VERIFREG_ROOT_ADDR, TEST_VERIFREG_ROOT_SIGNER_ADDR};
+
+// The governor configured at genesis sets a treasury split, after which the treasury receives
+// its share of each block reward.
+#[vm_test]
+pub fn reward_treasury_split_test(v: &dyn VM) {
+    let addrs = create_accounts(v, 2, &TokenAmount::from_whole(10_000));
+    let (owner, treasury) = (addrs[0], addrs[1]);
+    let (miner, _) = create_miner(
+        v,
+        &owner,
+        &owner,
+        RegisteredSealProof::StackedDRG32GiBV1P1.registered_window_post_proof().unwrap(),
+        &TokenAmount::from_whole(1_000),
+    );
+
+    let st: RewardState = get_state(v, &REWARD_ACTOR_ADDR).unwrap();
+    assert_eq!(Some(TEST_VERIFREG_ROOT_ADDR), st.governor);
+
+    // The governor is the root multisig, which proposes the split from its signer.
+    let split = TreasurySplit { address: treasury, share_bps: 2_000 };
+    let proposal = ProposeParams {
+        to: REWARD_ACTOR_ADDR,
+        value: TokenAmount::zero(),
+        method: RewardMethod::SetTreasurySplit as u64,
+        params: RawBytes::serialize(SetTreasurySplitParams { split: Some(split.clone()) }).unwrap(),
+        expiration_epoch: None,
+    };
+    apply_ok(
+        v,
+        &TEST_VERIFREG_ROOT_SIGNER_ADDR,
+        &TEST_VERIFREG_ROOT_ADDR,
+        &TokenAmount::zero(),
+        MultisigMethod::Propose as u64,
+        Some(proposal),
+    );
+    let st: RewardState = get_state(v, &REWARD_ACTOR_ADDR).unwrap();
+    assert_eq!(Some(split), st.treasury_split);
+
+    let treasury_balance = v.balance(&treasury);
+    let block_reward = st.this_epoch_reward.div_floor(EXPECTED_LEADERS_PER_EPOCH);
+    apply_ok(
+        v,
+        &SYSTEM_ACTOR_ADDR,
+        &REWARD_ACTOR_ADDR,
+        &TokenAmount::zero(),
+        RewardMethod::AwardBlockReward as u64,
+        Some(AwardBlockRewardParams {
+            miner,
+            penalty: TokenAmount::zero(),
+            gas_reward: TokenAmount::zero(),
+            win_count: 1,
+        }),
+    );
+
+    let treasury_reward = (&block_reward * 2_000).div_floor(TREASURY_SHARE_DENOMINATOR);
+    assert_eq!(&treasury_balance + &treasury_reward, v.balance(&treasury));
+    let st: RewardState = get_state(v, &REWARD_ACTOR_ADDR).unwrap();
+    assert_eq!(treasury_reward, st.total_treasury_reward);
+    assert_eq!(block_reward, st.total_storage_power_reward);
+
+    assert_invariants(v, &Policy::default(), None);
+}
@@ -19,7 +19,7 @@ use fil_actor_verifreg::{Method as VerifregMethod, VerifierParams};
 use fil_actors_runtime::network::EPOCHS_IN_DAY;
 use fil_actors_runtime::runtime::Policy;
 use fil_actors_runtime::{
-    STORAGE_MARKET_ACTOR_ADDR, STORAGE_MARKET_ACTOR_ID, STORAGE_POWER_ACTOR_ADDR,
+    PenaltyCategory, STORAGE_MARKET_ACTOR_ADDR, STORAGE_MARKET_ACTOR_ID, STORAGE_POWER_ACTOR_ADDR,
     VERIFIED_REGISTRY_ACTOR_ADDR,
 };
 use fvm_shared::deal::DealID;
@@ -240,8 +240,15 @@ pub fn terminate_sectors_test(v: &dyn VM) {
                 partition: p_idx,
                 sectors: make_bitfield(&[sector_number]),
             }],
+            handoff_manifest: None,
         }),
     );
+    let invocations = v.take_invocations();
+    let termination = invocations.last().unwrap();
+    // The termination fee itself is checked by the miner's own tests.
+    let termination_fee = termination.subinvocations[2].value.clone();
+    // Each of the 3 deals required 2 FIL provider collateral, which is slashed.
+    let deal_collateral = TokenAmount::from_whole(6);
     ExpectInvocation {
         from: worker_id,
         to: miner_id_addr,
@@ -249,20 +256,31 @@ pub fn terminate_sectors_test(v: &dyn VM) {
         subinvocs: Some(vec![
             Expect::reward_this_epoch(miner_id),
             Expect::power_current_total(miner_id),
-            Expect::burn(miner_id, None),
+            Expect::burn(miner_id, Some(termination_fee.clone())),
             Expect::power_update_pledge(miner_id, None),
             Expect::market_sectors_terminate(
                 miner_id,
                 epoch,
                 [sector_number].to_vec(),
                 deal_clients,
+                &deal_collateral,
             ),
             Expect::power_update_claim(miner_id, sector_power.neg()),
         ]),
-        events: vec![expect_event],
+        events: vec![
+            Expect::build_penalty_event(
+                miner_id,
+                miner_id,
+                PenaltyCategory::EarlyTermination,
+                &termination_fee,
+                &termination_fee,
+                &TokenAmount::zero(),
+            ),
+            expect_event,
+        ],
         ..Default::default()
     }
-    .matches(v.take_invocations().last().unwrap());
+    .matches(termination);
 
     let miner_balances = miner_balance(v, &miner_id_addr);
     assert!(miner_balances.initial_pledge.is_zero());
@@ -0,0 +1,230 @@
+use fil_actor_miner::{max_prove_commit_duration, power_for_sector, PowerPair};
+use fil_actor_verifreg::{AllocationID, Claim};
+use fil_actors_runtime::runtime::policy::policy_constants::PRE_COMMIT_CHALLENGE_DELAY;
+use fil_actors_runtime::runtime::Policy;
+use fil_actors_runtime::VERIFIED_REGISTRY_ACTOR_ADDR;
+use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::deal::DealID;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::piece::PaddedPieceSize;
+use fvm_shared::sector::{RegisteredSealProof, StoragePower};
+use num_traits::Zero;
+
+use export_macro::vm_test;
+use vm_api::VM;
+
+use crate::deals::{DealBatcher, DealOptions};
+use crate::util::{
+    advance_to_proving_deadline, assert_invariants, bf_all, create_accounts, create_miner,
+    datacap_balance, datacap_supply, get_deal, get_network_stats, market_add_balance,
+    market_list_deals, market_pending_deal_allocations, miner_balance, miner_dline_info,
+    miner_power, precommit_meta_data_from_deals, precommit_sectors_v2,
+    prove_commit_aggregate_claiming, sector_deadline, sector_info, submit_windowed_post,
+    verifreg_add_client, verifreg_add_verifier, verifreg_list_claims,
+};
+
+const SEAL_PROOF: RegisteredSealProof = RegisteredSealProof::StackedDRG32GiBV1P1;
+const MINERS: usize = 2;
+const CLIENTS: usize = 2;
+const DEALS_PER_CLIENT: usize = 2;
+
+// Onboards sectors of verified deals for several miners and clients, with each miner
+// prove-committing its sectors in one aggregate in the same epoch.
+// Checks the full path from allocation to claim: deal activation, claims, datacap burnt by
+// the verified registry, and the resulting pledge and quality-adjusted power.
+#[vm_test(1)]
+pub fn verified_aggregate_onboarding_test(v: &dyn VM) {
+    let policy = Policy::default();
+    let deal_duration = policy.min_sector_expiration;
+    let sector_size = SEAL_PROOF.sector_size().unwrap() as u64;
+    let piece_size = PaddedPieceSize(sector_size);
+    let deals_per_miner = CLIENTS * DEALS_PER_CLIENT;
+
+    let addrs = create_accounts(v, (1 + MINERS + CLIENTS) as u64, &TokenAmount::from_whole(10_000));
+    let verifier = addrs[0];
+    let owners = &addrs[1..=MINERS];
+    let clients = &addrs[1 + MINERS..];
+
+    let miners: Vec<Address> = owners
+        .iter()
+        .map(|owner| {
+            create_miner(
+                v,
+                owner,
+                owner,
+                SEAL_PROOF.registered_window_post_proof().unwrap(),
+                &TokenAmount::from_whole(1_000),
+            )
+            .0
+        })
+        .collect();
+
+    verifreg_add_verifier(v, &verifier, StoragePower::from((1000_u64 << 30) as u128));
+    for client in clients {
+        verifreg_add_client(v, &verifier, client, StoragePower::from((1000_u64 << 30) as u128));
+        market_add_balance(v, client, client, &TokenAmount::from_whole(1_000));
+    }
+    for (owner, miner) in owners.iter().zip(&miners) {
+        market_add_balance(v, owner, miner, &TokenAmount::from_whole(1_000));
+    }
+
+    // Each miner publishes verified deals from every client, one deal per sector.
+    let deal_start = v.epoch() + max_prove_commit_duration(&policy, SEAL_PROOF).unwrap();
+    let deal_opts = DealOptions {
+        piece_size,
+        verified: true,
+        deal_start,
+        deal_lifetime: deal_duration,
+        ..DealOptions::default()
+    };
+    let miner_deals: Vec<Vec<DealID>> = owners
+        .iter()
+        .zip(&miners)
+        .enumerate()
+        .map(|(m, (owner, miner))| {
+            let mut batcher = DealBatcher::new(v, deal_opts.clone());
+            for (c, client) in clients.iter().enumerate() {
+                for d in 0..DEALS_PER_CLIENT {
+                    batcher.stage_with_label(*client, *miner, format!("m{}-c{}-d{}", m, c, d));
+                }
+            }
+            let ret = batcher.publish_ok(*owner);
+            assert_eq!((0..deals_per_miner as u64).collect::<Vec<_>>(), bf_all(ret.valid_deals));
+            ret.ids
+        })
+        .collect();
+
+    // Publishing moved the clients' datacap to the verified registry to back the allocations.
+    let miner_allocs: Vec<Vec<AllocationID>> =
+        miner_deals.iter().map(|deals| market_pending_deal_allocations(v, deals)).collect();
+    let total_verified = TokenAmount::from_whole(sector_size * (MINERS * deals_per_miner) as u64);
+    let verifreg_id = VERIFIED_REGISTRY_ACTOR_ADDR.id().unwrap();
+    assert_eq!(total_verified, datacap_balance(v, verifreg_id));
+    let supply_before = datacap_supply(v);
+
+    // Pre-commit each miner's sectors in one batch.
+    let sector_expiry = v.epoch() + deal_duration + policy.market_default_allocation_term_buffer;
+    let miner_precommits: Vec<_> = owners
+        .iter()
+        .zip(&miners)
+        .zip(&miner_deals)
+        .map(|((owner, miner), deals)| {
+            let metadata = deals
+                .iter()
+                .map(|id| precommit_meta_data_from_deals(v, &[*id], SEAL_PROOF))
+                .collect();
+            precommit_sectors_v2(
+                v,
+                deals_per_miner,
+                deals_per_miner,
+                metadata,
+                owner,
+                miner,
+                SEAL_PROOF,
+                0,
+                true,
+                Some(sector_expiry),
+            )
+        })
+        .collect();
+
+    // Every miner prove-commits its sectors as an aggregate in the same epoch.
+    let prove_epoch = v.epoch() + PRE_COMMIT_CHALLENGE_DELAY + 1;
+    v.set_epoch(prove_epoch);
+    for (m, precommits) in miner_precommits.iter().enumerate() {
+        let claimed = prove_commit_aggregate_claiming(v, &owners[m], &miners[m], precommits);
+        assert_eq!(miner_allocs[m], claimed);
+    }
+
+    // The registry burnt all the datacap it held for the claimed allocations.
+    assert_eq!(TokenAmount::zero(), datacap_balance(v, verifreg_id));
+    assert_eq!(&supply_before - &total_verified, datacap_supply(v));
+
+    // Each allocation became a claim by its provider for the sector holding the deal.
+    let deal_states = market_list_deals(v);
+    for (m, miner) in miners.iter().enumerate() {
+        let miner_id = miner.id().unwrap();
+        let claims = verifreg_list_claims(v, miner_id);
+        assert_eq!(deals_per_miner, claims.len());
+        for (sector_number, (deal_id, alloc_id)) in
+            miner_deals[m].iter().zip(&miner_allocs[m]).enumerate()
+        {
+            let deal = get_deal(v, *deal_id);
+            let term_min = deal.end_epoch - deal.start_epoch;
+            assert_eq!(
+                &Claim {
+                    provider: miner_id,
+                    client: deal.client.id().unwrap(),
+                    data: deal.piece_cid,
+                    size: deal.piece_size,
+                    term_min,
+                    term_max: (term_min + policy.market_default_allocation_term_buffer)
+                        .min(policy.maximum_verified_allocation_term),
+                    term_start: prove_epoch,
+                    sector: sector_number as u64,
+                },
+                claims.get(alloc_id).unwrap()
+            );
+            let state = deal_states.get(deal_id).unwrap().1.unwrap();
+            assert_eq!(prove_epoch, state.sector_start_epoch);
+            assert_eq!(sector_number as u64, state.sector_number);
+        }
+    }
+
+    // Pledge is locked for every sector and accounted by the power actor.
+    let mut total_pledge = TokenAmount::zero();
+    for miner in &miners {
+        let mut sector_pledge = TokenAmount::zero();
+        for s in 0..deals_per_miner as u64 {
+            sector_pledge += sector_info(v, miner, s).initial_pledge;
+        }
+        assert!(sector_pledge.is_positive());
+        let balances = miner_balance(v, miner);
+        assert_eq!(sector_pledge, balances.initial_pledge);
+        // Pre-commit deposits were released when the sectors were proven.
+        assert!(balances.pre_commit_deposit.is_zero());
+        total_pledge += sector_pledge;
+    }
+    assert_eq!(total_pledge, get_network_stats(v).total_pledge_collateral);
+
+    // Each miner's first Window PoSt activates its power, with verified deals
+    // making the sectors' quality-adjusted power ten times their raw power.
+    // Miners are proven in the order their deadlines open, so none is missed.
+    let mut proving_order: Vec<usize> = (0..MINERS).collect();
+    proving_order.sort_by_key(|m| {
+        let (deadline, _) = sector_deadline(v, &miners[*m], 0);
+        next_deadline_open(v, &miners[*m], deadline)
+    });
+    for m in proving_order {
+        let miner = &miners[m];
+        let mut expect_power = PowerPair::zero();
+        for s in 0..deals_per_miner as u64 {
+            let sector = sector_info(v, miner, s);
+            let power = power_for_sector(sector_size, &sector);
+            assert_eq!(&power.raw * 10, power.qa);
+            expect_power += &power;
+        }
+        let (dline_info, p_idx) = advance_to_proving_deadline(v, miner, 0);
+        submit_windowed_post(v, &owners[m], miner, dline_info, p_idx, Some(expect_power.clone()));
+        assert_eq!(expect_power, miner_power(v, miner));
+    }
+
+    let network_stats = get_network_stats(v);
+    let total_raw = StoragePower::from(sector_size * (MINERS * deals_per_miner) as u64);
+    assert_eq!(total_raw, network_stats.total_bytes_committed);
+    assert_eq!(&total_raw * 10, network_stats.total_qa_bytes_committed);
+    assert_invariants(v, &policy, None);
+}
+
+// Returns the epoch at which a miner's deadline next opens.
+fn next_deadline_open(v: &dyn VM, maddr: &Address, deadline: u64) -> ChainEpoch {
+    let policy = Policy::default();
+    let current = miner_dline_info(v, maddr);
+    let open = current.period_start + deadline as ChainEpoch * policy.wpost_challenge_window;
+    if open < current.open {
+        open + policy.wpost_proving_period
+    } else {
+        open
+    }
+}
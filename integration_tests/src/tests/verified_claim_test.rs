@@ -246,15 +246,16 @@ pub fn verified_claim_scenario_test(v: &dyn VM) {
     let new_max_term = new_claim_expiry_epoch - claim.term_start;
     assert!(new_max_term > original_max_term);
 
-    datacap_extend_claim(
+    let extension_cost = datacap_extend_claim(
         v,
         &verified_client2,
         &miner_id,
         claim_id,
-        deal_size,
         new_max_term,
         verified_client.id().unwrap(),
     );
+    // The client paid for the added term, which is less than the full maximum term.
+    assert!(extension_cost < TokenAmount::from_whole(deal_size));
 
     // The miner extends the sector into the second year.
     let extended_expiration_2 = extended_expiration_1 + 60 * EPOCHS_IN_DAY;
@@ -315,7 +316,7 @@ pub fn verified_claim_scenario_test(v: &dyn VM) {
             .unwrap()
     );
     assert_eq!(
-        TokenAmount::from_whole(datacap.clone()) - TokenAmount::from_whole(deal_size), // Also spent deal size
+        TokenAmount::from_whole(datacap.clone()) - &extension_cost, // Spent on the extension
         datacap_state
             .token
             .get_balance(&DynBlockstore::wrap(v.blockstore()), verified_client2.id().unwrap())
@@ -332,7 +333,7 @@ pub fn verified_claim_scenario_test(v: &dyn VM) {
             .unwrap()
     );
     assert_eq!(
-        TokenAmount::from_whole(datacap) * 2 - TokenAmount::from_whole(deal_size) * 2, // Spent deal size twice
+        TokenAmount::from_whole(datacap) * 2 - TokenAmount::from_whole(deal_size) - extension_cost, // Spent deal size and extension
         datacap_state.token.supply
     );
 
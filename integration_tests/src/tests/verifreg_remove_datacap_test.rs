@@ -1,4 +1,5 @@
 use export_macro::vm_test;
+use fil_actor_account::State as AccountState;
 use fil_actor_datacap::{
     DestroyParams, Method as DataCapMethod, MintParams, State as DataCapState,
 };
@@ -16,6 +17,7 @@ use fil_actors_runtime::{
 };
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_ipld_encoding::{to_vec, RawBytes};
+use fvm_shared::address::Address;
 use fvm_shared::bigint::bigint_ser::BigIntDe;
 use fvm_shared::bigint::{BigInt, Zero};
 use fvm_shared::crypto::signature::{Signature, SignatureType};
@@ -32,7 +34,8 @@ use vm_api::VM;
 use crate::expects::Expect;
 
 use crate::util::{
-    assert_invariants, create_accounts, verifier_balance_event, verifreg_add_verifier,
+    assert_invariants, create_accounts, verifier_balance_event, verifreg_add_client,
+    verifreg_add_verifier,
 };
 use crate::{TEST_VERIFREG_ROOT_ADDR, TEST_VERIFREG_ROOT_ID};
 
@@ -367,6 +370,83 @@ pub fn remove_datacap_fails_on_verifreg_test(v: &dyn VM) {
     assert_invariants(v, &Policy::default(), None)
 }
 
+#[vm_test]
+pub fn remove_datacap_key_address_verifiers_test(v: &dyn VM) {
+    let addrs = create_accounts(v, 3, &TokenAmount::from_whole(10_000));
+    let (verifier1, verifier2, verified_client) = (addrs[0], addrs[1], addrs[2]);
+    let verifier_allowance = StoragePower::from(2 * 1048576u64);
+    let allowance_to_remove: StoragePower = verifier_allowance.clone().div(2);
+
+    verifreg_add_verifier(v, &verifier1, verifier_allowance.clone());
+    verifreg_add_verifier(v, &verifier2, verifier_allowance.clone());
+    verifreg_add_client(v, &verifier1, &verified_client, verifier_allowance);
+
+    // Both verifiers sign their requests with the keys behind their accounts.
+    let key_address = |verifier: &Address| {
+        let st: AccountState = get_state(v, verifier).unwrap();
+        st.address
+    };
+    let proposal = RemoveDataCapProposal {
+        verified_client,
+        data_cap_amount: allowance_to_remove.clone(),
+        removal_proposal_id: RemoveDataCapProposalID { id: 0 },
+    };
+    let payload =
+        [SIGNATURE_DOMAIN_SEPARATION_REMOVE_DATA_CAP, to_vec(&proposal).unwrap().as_slice()]
+            .concat();
+    let remove_datacap_params = RemoveDataCapParams {
+        verified_client_to_remove: verified_client,
+        data_cap_amount_to_remove: allowance_to_remove.clone(),
+        verifier_request_1: RemoveDataCapRequest {
+            verifier: key_address(&verifier1),
+            signature: Signature { sig_type: SignatureType::BLS, bytes: payload.clone() },
+        },
+        verifier_request_2: RemoveDataCapRequest {
+            verifier: key_address(&verifier2),
+            signature: Signature { sig_type: SignatureType::BLS, bytes: payload },
+        },
+    };
+
+    let remove_datacap_ret: RemoveDataCapReturn = apply_ok(
+        v,
+        &TEST_VERIFREG_ROOT_ADDR,
+        &VERIFIED_REGISTRY_ACTOR_ADDR,
+        &TokenAmount::zero(),
+        VerifregMethod::RemoveVerifiedClientDataCap as u64,
+        Some(remove_datacap_params.clone()),
+    )
+    .deserialize()
+    .unwrap();
+    assert_eq!(verified_client, remove_datacap_ret.verified_client);
+    assert_eq!(allowance_to_remove, remove_datacap_ret.data_cap_removed);
+
+    // The signatures are verified directly, without asking the verifiers to authenticate them.
+    ExpectInvocation {
+        from: TEST_VERIFREG_ROOT_ID,
+        to: VERIFIED_REGISTRY_ACTOR_ADDR,
+        method: VerifregMethod::RemoveVerifiedClientDataCap as u64,
+        params: Some(IpldBlock::serialize_cbor(&remove_datacap_params).unwrap()),
+        subinvocs: Some(vec![
+            Expect::frc42_balance(
+                VERIFIED_REGISTRY_ACTOR_ID,
+                DATACAP_TOKEN_ACTOR_ADDR,
+                verified_client,
+            ),
+            ExpectInvocation {
+                from: VERIFIED_REGISTRY_ACTOR_ID,
+                to: DATACAP_TOKEN_ACTOR_ADDR,
+                method: DataCapMethod::DestroyExported as u64,
+                subinvocs: None,
+                ..Default::default()
+            },
+        ]),
+        ..Default::default()
+    }
+    .matches(v.take_invocations().last().unwrap());
+
+    assert_invariants(v, &Policy::default(), None)
+}
+
 fn expect_remove_datacap(
     sender: ActorID,
     params: &RemoveDataCapParams,
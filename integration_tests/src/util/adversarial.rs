@@ -0,0 +1,151 @@
+//! Builders that install pathological miner states directly into a VM's state tree.
+//!
+//! Some worst-case states (deadlines with thousands of partitions, heavily fragmented sector
+//! bitfields, long expiration queues) are legal but take far too many messages to reach through
+//! normal onboarding. These builders write such states straight into the miner actor so that
+//! cron and PoSt paths can be exercised against them.
+
+use fil_actor_miner::{
+    CollisionPolicy, SectorOnChainInfo, SectorOnChainInfoFlags, State as MinerState,
+};
+use fil_actors_runtime::runtime::Policy;
+use fil_actors_runtime::test_utils::make_sealed_cid;
+use fvm_ipld_bitfield::BitField;
+use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::sector::{RegisteredSealProof, SectorNumber};
+use num_traits::Zero;
+use vm_api::util::{mutate_state, DynBlockstore};
+use vm_api::VM;
+
+/// Installs synthetic sectors into a miner's deadlines without going through pre-commit and
+/// prove-commit.
+///
+/// Installed sectors are unproven, exactly as if they had just been prove-committed, so no power
+/// is claimed for them at the power actor and state invariants continue to hold. They carry no
+/// deals and no pledge. The miner should already have its deadline cron enrolled (e.g. by having
+/// pre-committed a sector through the normal flow) for the installed state to be processed by
+/// cron. Callers must not install into a deadline that is currently open or about to open.
+pub struct AdversarialMinerState<'a> {
+    v: &'a dyn VM,
+    miner: Address,
+    seal_proof: RegisteredSealProof,
+    next_sector_number: SectorNumber,
+    sector_number_stride: u64,
+    sectors_per_partition: u64,
+    expiration: ChainEpoch,
+    expiration_spread: u64,
+}
+
+impl<'a> AdversarialMinerState<'a> {
+    /// Creates a builder allocating sector numbers upwards from `first_sector_number`.
+    /// By default partitions are filled to the seal proof's partition size and all sectors
+    /// expire at the policy's minimum sector expiration from the current epoch.
+    pub fn new(
+        v: &'a dyn VM,
+        miner: &Address,
+        seal_proof: RegisteredSealProof,
+        first_sector_number: SectorNumber,
+    ) -> Self {
+        let policy = Policy::default();
+        Self {
+            v,
+            miner: *miner,
+            seal_proof,
+            next_sector_number: first_sector_number,
+            sector_number_stride: 1,
+            sectors_per_partition: seal_proof
+                .registered_window_post_proof()
+                .unwrap()
+                .window_post_partitions_sector()
+                .unwrap(),
+            expiration: v.epoch() + policy.min_sector_expiration,
+            expiration_spread: 1,
+        }
+    }
+
+    /// Leaves `stride - 1` unallocated sector numbers between consecutive sectors, so that every
+    /// sector bitfield built from them consists of runs of length one.
+    pub fn sector_number_stride(mut self, stride: u64) -> Self {
+        assert!(stride > 0, "sector number stride must be positive");
+        self.sector_number_stride = stride;
+        self
+    }
+
+    /// Limits the number of sectors placed in each new partition, so that many partitions can be
+    /// created with few sectors.
+    pub fn sectors_per_partition(mut self, count: u64) -> Self {
+        assert!(count > 0, "sectors per partition must be positive");
+        self.sectors_per_partition = count;
+        self
+    }
+
+    /// Sets the expiration epoch of the first installed sector.
+    pub fn expiration(mut self, epoch: ChainEpoch) -> Self {
+        self.expiration = epoch;
+        self
+    }
+
+    /// Spreads sector expirations over `periods` consecutive proving periods, so that each
+    /// partition's expiration queue holds up to that many distinct entries.
+    pub fn expiration_spread(mut self, periods: u64) -> Self {
+        assert!(periods > 0, "expiration spread must be positive");
+        self.expiration_spread = periods;
+        self
+    }
+
+    /// Installs `partitions` new partitions of synthetic sectors into a deadline, returning the
+    /// numbers of the sectors installed.
+    pub fn install_deadline(&mut self, deadline_idx: u64, partitions: u64) -> Vec<SectorNumber> {
+        let policy = Policy::default();
+        let sector_size = self.seal_proof.sector_size().unwrap();
+        let sectors: Vec<SectorOnChainInfo> =
+            (0..partitions * self.sectors_per_partition).map(|i| self.make_sector(i)).collect();
+        let sector_numbers: Vec<SectorNumber> = sectors.iter().map(|s| s.sector_number).collect();
+        let allocated = BitField::try_from_bits(sector_numbers.iter().copied()).unwrap();
+        let partition_size = self.sectors_per_partition;
+
+        let store = DynBlockstore::wrap(self.v.blockstore());
+        mutate_state(self.v, &self.miner, |st: &mut MinerState| {
+            st.allocate_sector_numbers(&store, &allocated, CollisionPolicy::DenyCollisions)
+                .unwrap();
+            st.put_sectors(&store, sectors.clone()).unwrap();
+
+            let quant = st.quant_spec_for_deadline(&policy, deadline_idx);
+            let mut deadlines = st.load_deadlines(&store).unwrap();
+            let mut deadline = deadlines.load_deadline(&store, deadline_idx).unwrap();
+            deadline
+                .add_sectors(&store, partition_size, false, &sectors, sector_size, quant)
+                .unwrap();
+            deadlines.update_deadline(&policy, &store, deadline_idx, &deadline).unwrap();
+            st.save_deadlines(&store, deadlines).unwrap();
+        });
+        sector_numbers
+    }
+
+    fn make_sector(&mut self, index: u64) -> SectorOnChainInfo {
+        let policy = Policy::default();
+        let sector_number = self.next_sector_number;
+        self.next_sector_number += self.sector_number_stride;
+
+        let period = (index % self.expiration_spread) as ChainEpoch;
+        SectorOnChainInfo {
+            sector_number,
+            seal_proof: self.seal_proof,
+            sealed_cid: make_sealed_cid(format!("adversarial-{}", sector_number).as_bytes()),
+            deprecated_deal_ids: vec![],
+            activation: self.v.epoch(),
+            expiration: self.expiration + period * policy.wpost_proving_period,
+            deal_weight: Zero::zero(),
+            verified_deal_weight: Zero::zero(),
+            initial_pledge: TokenAmount::zero(),
+            expected_day_reward: TokenAmount::zero(),
+            expected_storage_pledge: TokenAmount::zero(),
+            power_base_epoch: self.v.epoch(),
+            replaced_day_reward: TokenAmount::zero(),
+            sector_key_cid: None,
+            flags: SectorOnChainInfoFlags::SIMPLE_QA_POWER,
+        }
+    }
+}
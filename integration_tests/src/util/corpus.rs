@@ -0,0 +1,486 @@
+//! Synthesizes large, internally consistent state trees for migration testing and benchmarking.
+//!
+//! Reaching a state holding many thousands of deals and allocations through messages would take
+//! far longer than the state is useful for. The corpus builder creates accounts and miners with
+//! ordinary messages, which is cheap, and escrows market funds the same way so that token
+//! balances stay conserved. It then writes deals and allocations directly into the market,
+//! verified registry and datacap states, maintaining the bookkeeping those actors would.
+//! Sectors are onboarded with real pre-commit and prove-commit messages, so that activated
+//! deals and claims are backed by sectors exactly as the miner, market and registry record them.
+
+use std::collections::BTreeMap;
+
+use cid::Cid;
+use fil_actor_datacap::State as DatacapState;
+use fil_actor_market::{next_update_epoch, DealProposal, Label, State as MarketState};
+use fil_actor_miner::{
+    max_prove_commit_duration, CompactCommD, DataActivationNotification, Method as MinerMethod,
+    PieceActivationManifest, ProveCommitSectors3Params, SectorActivationManifest,
+    VerifiedAllocationKey,
+};
+use fil_actor_verifreg::{Allocation, AllocationID, ClaimID, State as VerifregState};
+use fil_actors_runtime::cbor::serialize;
+use fil_actors_runtime::runtime::Policy;
+use fil_actors_runtime::test_utils::make_piece_cid;
+use fil_actors_runtime::{
+    DATACAP_TOKEN_ACTOR_ADDR, EPOCHS_IN_DAY, STORAGE_MARKET_ACTOR_ADDR,
+    VERIFIED_REGISTRY_ACTOR_ADDR,
+};
+use fvm_ipld_encoding::RawBytes;
+use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::deal::DealID;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::piece::{PaddedPieceSize, PieceInfo};
+use fvm_shared::sector::{RegisteredSealProof, SectorNumber};
+use fvm_shared::ActorID;
+use num_traits::Zero;
+use vm_api::util::{apply_ok, mutate_state, DynBlockstore};
+use vm_api::VM;
+
+use super::{
+    advance_by_deadline_to_epoch, create_accounts, create_miner, deal_cid_for_testing,
+    market_add_balance, precommit_sectors_v2, PrecommitMetadata,
+};
+
+// Funds given to each miner per sector, covering its pre-commit deposit and initial pledge.
+const SECTOR_FUNDING_WHOLE: u64 = 1_000;
+
+/// Builds a populated state tree of configurable scale.
+///
+/// Deals and allocations are spread round-robin across the miners and clients. Deals are
+/// published and allocations unclaimed, except for the first few of each configured to be
+/// activated or claimed, which each miner onboards one piece per sector. Miners may also
+/// onboard committed-capacity sectors. All other actor state is left as the actors' own
+/// messages made it, so the result passes state invariant checks.
+pub struct StateCorpus {
+    miners: u64,
+    clients: u64,
+    deals: u64,
+    allocations: u64,
+    activated_deals: u64,
+    claimed_allocations: u64,
+    cc_sectors: u64,
+    seal_proof: RegisteredSealProof,
+    piece_size: PaddedPieceSize,
+    deal_start_delay: ChainEpoch,
+    deal_duration: ChainEpoch,
+    storage_price_per_epoch: TokenAmount,
+    provider_collateral: TokenAmount,
+    client_collateral: TokenAmount,
+}
+
+/// The actors and records created by a [`StateCorpus`].
+pub struct CorpusSummary {
+    /// ID addresses of the miner actors, in creation order.
+    pub miners: Vec<Address>,
+    /// ID addresses of the miner owners, which are also their workers.
+    pub owners: Vec<Address>,
+    /// ID addresses of the deal and allocation clients.
+    pub clients: Vec<Address>,
+    pub deals: Vec<DealID>,
+    /// Allocation IDs in the order they were spread across miners and clients.
+    pub allocations: Vec<AllocationID>,
+    /// The leading deals, which were activated in sectors.
+    pub activated_deals: Vec<DealID>,
+    /// Claims of the leading allocations, which share their allocations' IDs.
+    pub claims: Vec<ClaimID>,
+    /// Sector numbers onboarded by each miner, indexed like `miners`.
+    pub sectors: Vec<Vec<SectorNumber>>,
+}
+
+impl StateCorpus {
+    /// Creates a builder for `miners` miners, `deals` market deals and `allocations`
+    /// verified allocations. By default there is one client per miner.
+    pub fn new(miners: u64, deals: u64, allocations: u64) -> Self {
+        Self {
+            miners,
+            clients: miners.max(1),
+            deals,
+            allocations,
+            activated_deals: 0,
+            claimed_allocations: 0,
+            cc_sectors: 0,
+            seal_proof: RegisteredSealProof::StackedDRG32GiBV1P1,
+            piece_size: PaddedPieceSize(1 << 30),
+            deal_start_delay: 10 * EPOCHS_IN_DAY,
+            deal_duration: 180 * EPOCHS_IN_DAY,
+            storage_price_per_epoch: TokenAmount::from_atto(1_000),
+            provider_collateral: TokenAmount::from_whole(1),
+            client_collateral: TokenAmount::from_whole(1),
+        }
+    }
+
+    /// Sets the number of distinct clients making deals and allocations.
+    pub fn clients(mut self, count: u64) -> Self {
+        assert!(count > 0, "corpus requires at least one client");
+        self.clients = count;
+        self
+    }
+
+    /// Sets the seal proof type of the created miners.
+    pub fn seal_proof(mut self, seal_proof: RegisteredSealProof) -> Self {
+        self.seal_proof = seal_proof;
+        self
+    }
+
+    /// Sets the piece size of every deal and allocation.
+    pub fn piece_size(mut self, size: PaddedPieceSize) -> Self {
+        self.piece_size = size;
+        self
+    }
+
+    /// Sets the deal start delay from the current epoch and the deal duration.
+    pub fn deal_term(mut self, start_delay: ChainEpoch, duration: ChainEpoch) -> Self {
+        assert!(start_delay > 0, "deals must start in the future");
+        assert!(duration > 0, "deal duration must be positive");
+        self.deal_start_delay = start_delay;
+        self.deal_duration = duration;
+        self
+    }
+
+    /// Sets how many of the leading deals are activated and allocations claimed, each in a
+    /// sector of its own onboarded by its provider.
+    pub fn activate(mut self, deals: u64, allocations: u64) -> Self {
+        assert!(deals <= self.deals, "cannot activate more deals than the corpus holds");
+        assert!(
+            allocations <= self.allocations,
+            "cannot claim more allocations than the corpus holds"
+        );
+        self.activated_deals = deals;
+        self.claimed_allocations = allocations;
+        self
+    }
+
+    /// Sets the number of committed-capacity sectors onboarded by each miner.
+    pub fn cc_sectors(mut self, count: u64) -> Self {
+        self.cc_sectors = count;
+        self
+    }
+
+    /// Builds the corpus into the VM's state tree at the current epoch.
+    pub fn build(&self, v: &dyn VM) -> CorpusSummary {
+        assert!(
+            self.miners > 0 || (self.deals == 0 && self.allocations == 0),
+            "deals and allocations require at least one miner"
+        );
+        let policy = Policy::default();
+        assert!(
+            self.activated_deals == 0 || self.deal_start_delay > policy.pre_commit_challenge_delay,
+            "activated deals must start after their sectors' pre-commit challenge delay"
+        );
+        let proposals: Vec<DealProposal> = (0..self.deals).map(|i| self.make_deal(v, i)).collect();
+
+        // Escrow required by each client and provider, indexed by their position.
+        let mut client_escrow = vec![TokenAmount::zero(); self.clients as usize];
+        let mut provider_escrow = vec![TokenAmount::zero(); self.miners as usize];
+        for (i, proposal) in proposals.iter().enumerate() {
+            client_escrow[i % self.clients as usize] += proposal.client_balance_requirement();
+            provider_escrow[i % self.miners.max(1) as usize] += &proposal.provider_collateral;
+        }
+        let miner_funding =
+            TokenAmount::from_whole(SECTOR_FUNDING_WHOLE * self.max_sectors_per_miner());
+        let account_balance =
+            client_escrow.iter().chain(provider_escrow.iter()).max().cloned().unwrap_or_default()
+                + &miner_funding
+                + TokenAmount::from_whole(1_000);
+
+        let accounts = create_accounts(v, self.miners + self.clients, &account_balance);
+        let (owners, clients) = accounts.split_at(self.miners as usize);
+        let miners: Vec<Address> = owners
+            .iter()
+            .map(|owner| {
+                create_miner(
+                    v,
+                    owner,
+                    owner,
+                    self.seal_proof.registered_window_post_proof().unwrap(),
+                    &miner_funding,
+                )
+                .0
+            })
+            .collect();
+
+        for (client, amount) in clients.iter().zip(&client_escrow) {
+            if amount.is_positive() {
+                market_add_balance(v, client, client, amount);
+            }
+        }
+        for ((owner, miner), amount) in owners.iter().zip(&miners).zip(&provider_escrow) {
+            if amount.is_positive() {
+                market_add_balance(v, owner, miner, amount);
+            }
+        }
+
+        let deals = self.install_deals(v, &policy, proposals, clients, &miners);
+        let allocations = self.install_allocations(v, &policy, clients, &miners);
+        let sectors =
+            self.onboard_sectors(v, &policy, owners, &miners, clients, &deals, &allocations);
+
+        CorpusSummary {
+            miners,
+            owners: owners.to_vec(),
+            clients: clients.to_vec(),
+            activated_deals: deals[..self.activated_deals as usize].to_vec(),
+            claims: allocations[..self.claimed_allocations as usize].to_vec(),
+            deals,
+            allocations,
+            sectors,
+        }
+    }
+
+    // The most sectors onboarded by any one miner.
+    fn max_sectors_per_miner(&self) -> u64 {
+        if self.miners == 0 {
+            return 0;
+        }
+        self.cc_sectors
+            + self.activated_deals.div_ceil(self.miners)
+            + self.claimed_allocations.div_ceil(self.miners)
+    }
+
+    // Makes the i'th deal proposal, with placeholder client and provider resolved on install.
+    fn make_deal(&self, v: &dyn VM, i: u64) -> DealProposal {
+        let start_epoch = v.epoch() + self.deal_start_delay;
+        DealProposal {
+            piece_cid: deal_piece_cid(i),
+            piece_size: self.piece_size,
+            verified_deal: false,
+            client: Address::new_id(0),
+            provider: Address::new_id(0),
+            label: Label::String(format!("corpus-deal-{}", i)),
+            start_epoch,
+            end_epoch: start_epoch + self.deal_duration,
+            storage_price_per_epoch: self.storage_price_per_epoch.clone(),
+            provider_collateral: self.provider_collateral.clone(),
+            client_collateral: self.client_collateral.clone(),
+        }
+    }
+
+    fn install_deals(
+        &self,
+        v: &dyn VM,
+        policy: &Policy,
+        proposals: Vec<DealProposal>,
+        clients: &[Address],
+        miners: &[Address],
+    ) -> Vec<DealID> {
+        if proposals.is_empty() {
+            return vec![];
+        }
+        let store = DynBlockstore::wrap(v.blockstore());
+        let mut deal_ids = Vec::with_capacity(proposals.len());
+        mutate_state(v, &STORAGE_MARKET_ACTOR_ADDR, |st: &mut MarketState| {
+            let mut pending_deals = Vec::with_capacity(proposals.len());
+            let mut deal_proposals = Vec::with_capacity(proposals.len());
+            let mut deals_by_epoch = Vec::with_capacity(proposals.len());
+            let mut client_deals = Vec::with_capacity(proposals.len());
+            for (i, mut proposal) in proposals.into_iter().enumerate() {
+                proposal.client = clients[i % clients.len()];
+                proposal.provider = miners[i % miners.len()];
+                st.lock_client_and_provider_balances(&store, &proposal).unwrap();
+
+                let deal_id = st.generate_storage_deal_id();
+                pending_deals.push(deal_cid_for_testing(&proposal));
+                deals_by_epoch.push((
+                    next_update_epoch(deal_id, policy.deal_updates_interval, proposal.start_epoch),
+                    deal_id,
+                ));
+                client_deals.push((
+                    proposal.client.id().unwrap(),
+                    proposal.piece_size.0,
+                    proposal.total_storage_fee(),
+                ));
+                deal_proposals.push((deal_id, proposal));
+                deal_ids.push(deal_id);
+            }
+            st.put_pending_deals(&store, &pending_deals).unwrap();
+            st.put_deal_proposals(&store, &deal_proposals).unwrap();
+            st.put_deals_by_epoch(&store, &deals_by_epoch).unwrap();
+            st.record_client_deals(&store, v.epoch(), &client_deals).unwrap();
+        });
+        deal_ids
+    }
+
+    fn install_allocations(
+        &self,
+        v: &dyn VM,
+        policy: &Policy,
+        clients: &[Address],
+        miners: &[Address],
+    ) -> Vec<AllocationID> {
+        if self.allocations == 0 {
+            return vec![];
+        }
+        // Allocations are inserted per client, remembering each one's position in the corpus.
+        let mut by_client: BTreeMap<ActorID, (Vec<usize>, Vec<Allocation>)> = BTreeMap::new();
+        for i in 0..self.allocations {
+            let client = clients[i as usize % clients.len()].id().unwrap();
+            let (indices, allocs) = by_client.entry(client).or_default();
+            indices.push(i as usize);
+            allocs.push(Allocation {
+                client,
+                provider: miners[i as usize % miners.len()].id().unwrap(),
+                data: allocation_piece_cid(i),
+                size: self.piece_size,
+                term_min: policy.minimum_verified_allocation_term,
+                term_max: policy.maximum_verified_allocation_term,
+                expiration: v.epoch() + policy.maximum_verified_allocation_expiration,
+            });
+        }
+
+        let store = DynBlockstore::wrap(v.blockstore());
+        let mut allocation_ids = vec![0; self.allocations as usize];
+        mutate_state(v, &VERIFIED_REGISTRY_ACTOR_ADDR, |st: &mut VerifregState| {
+            for (client, (indices, allocs)) in by_client {
+                let ids = st.insert_allocations(&store, client, allocs).unwrap();
+                for (i, id) in indices.into_iter().zip(ids) {
+                    allocation_ids[i] = id;
+                }
+            }
+        });
+
+        // The registry holds the datacap backing every unclaimed allocation.
+        let datacap = TokenAmount::from_whole(self.piece_size.0 * self.allocations);
+        mutate_state(v, &DATACAP_TOKEN_ACTOR_ADDR, |st: &mut DatacapState| {
+            st.token
+                .change_balance_by(&store, VERIFIED_REGISTRY_ACTOR_ADDR.id().unwrap(), &datacap)
+                .unwrap();
+            st.token.change_supply_by(&datacap).unwrap();
+        });
+        allocation_ids
+    }
+
+    // Onboards each miner's sectors with pre-commit and prove-commit messages. Activated deals
+    // and claimed allocations each fill a sector of their own, numbered before any
+    // committed-capacity sectors, and are activated through the market's notification and the
+    // registry's claim exactly as for any other sector.
+    #[allow(clippy::too_many_arguments)]
+    fn onboard_sectors(
+        &self,
+        v: &dyn VM,
+        policy: &Policy,
+        owners: &[Address],
+        miners: &[Address],
+        clients: &[Address],
+        deals: &[DealID],
+        allocations: &[AllocationID],
+    ) -> Vec<Vec<SectorNumber>> {
+        let mut manifests: Vec<Vec<SectorActivationManifest>> = vec![vec![]; miners.len()];
+        let mut add_sector = |m: usize, pieces: Vec<PieceActivationManifest>| {
+            let sectors = &mut manifests[m];
+            let sector_number = sectors.len() as SectorNumber;
+            sectors.push(SectorActivationManifest { sector_number, pieces });
+        };
+        for (i, deal_id) in deals.iter().take(self.activated_deals as usize).enumerate() {
+            add_sector(
+                i % miners.len(),
+                vec![PieceActivationManifest {
+                    cid: deal_piece_cid(i as u64),
+                    size: self.piece_size,
+                    verified_allocation_key: None,
+                    notify: vec![DataActivationNotification {
+                        address: STORAGE_MARKET_ACTOR_ADDR,
+                        payload: serialize(deal_id, "deal id").unwrap(),
+                    }],
+                }],
+            );
+        }
+        for (i, alloc_id) in allocations.iter().take(self.claimed_allocations as usize).enumerate()
+        {
+            add_sector(
+                i % miners.len(),
+                vec![PieceActivationManifest {
+                    cid: allocation_piece_cid(i as u64),
+                    size: self.piece_size,
+                    verified_allocation_key: Some(VerifiedAllocationKey {
+                        client: clients[i % clients.len()].id().unwrap(),
+                        id: *alloc_id,
+                    }),
+                    notify: vec![],
+                }],
+            );
+        }
+        for m in 0..miners.len() {
+            for _ in 0..self.cc_sectors {
+                add_sector(m, vec![]);
+            }
+        }
+
+        let onboarding: Vec<usize> =
+            (0..miners.len()).filter(|m| !manifests[*m].is_empty()).collect();
+        if onboarding.is_empty() {
+            return vec![vec![]; miners.len()];
+        }
+
+        // Sectors outlive both the minimum sector lifetime and every deal they might hold.
+        let sector_expiry = (v.epoch()
+            + policy.min_sector_expiration
+            + max_prove_commit_duration(policy, self.seal_proof).unwrap())
+        .max(v.epoch() + self.deal_start_delay + self.deal_duration);
+        for m in &onboarding {
+            let metadata = manifests[*m]
+                .iter()
+                .map(|sector| {
+                    if sector.pieces.is_empty() {
+                        return PrecommitMetadata::default();
+                    }
+                    let pieces: Vec<PieceInfo> = sector
+                        .pieces
+                        .iter()
+                        .map(|p| PieceInfo { size: p.size, cid: p.cid })
+                        .collect();
+                    let commd = v
+                        .primitives()
+                        .compute_unsealed_sector_cid(self.seal_proof, &pieces)
+                        .unwrap();
+                    PrecommitMetadata { deals: vec![], commd: CompactCommD::of(commd) }
+                })
+                .collect();
+            precommit_sectors_v2(
+                v,
+                manifests[*m].len(),
+                policy.pre_commit_sector_batch_max_size,
+                metadata,
+                &owners[*m],
+                &miners[*m],
+                self.seal_proof,
+                0,
+                true,
+                Some(sector_expiry),
+            );
+        }
+
+        let prove_epoch = v.epoch() + policy.pre_commit_challenge_delay + 1;
+        advance_by_deadline_to_epoch(v, &miners[onboarding[0]], prove_epoch);
+        for m in &onboarding {
+            for batch in manifests[*m].chunks(policy.pre_commit_sector_batch_max_size) {
+                apply_ok(
+                    v,
+                    &owners[*m],
+                    &miners[*m],
+                    &TokenAmount::zero(),
+                    MinerMethod::ProveCommitSectors3 as u64,
+                    Some(ProveCommitSectors3Params {
+                        sector_activations: batch.to_vec(),
+                        sector_proofs: vec![RawBytes::new(vec![1, 2, 3, 4]); batch.len()],
+                        aggregate_proof: RawBytes::default(),
+                        aggregate_proof_type: None,
+                        require_activation_success: true,
+                        require_notification_success: true,
+                    }),
+                );
+            }
+        }
+
+        manifests.iter().map(|sectors| sectors.iter().map(|s| s.sector_number).collect()).collect()
+    }
+}
+
+fn deal_piece_cid(i: u64) -> Cid {
+    make_piece_cid(format!("corpus-deal-{}", i).as_bytes())
+}
+
+fn allocation_piece_cid(i: u64) -> Cid {
+    make_piece_cid(format!("corpus-allocation-{}", i).as_bytes())
+}
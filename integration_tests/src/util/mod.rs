@@ -1,11 +1,15 @@
+pub use adversarial::*;
 use cid::multihash::{Code, MultihashDigest};
 use cid::Cid;
+pub use corpus::*;
+use fil_actor_datacap::State as DatacapState;
+use fil_actor_init::State as InitState;
 use fil_actor_market::{load_provider_sector_deals, DealProposal, DealState, State as MarketState};
 use fil_actor_miner::ext::verifreg::AllocationID;
 use fil_actor_miner::{
     new_deadline_info_from_offset_and_epoch, CompactCommD, Deadline, DeadlineInfo,
-    GetBeneficiaryReturn, Method as MinerMethod, MinerInfo, PowerPair, SectorOnChainInfo,
-    State as MinerState,
+    GetBeneficiaryReturn, GetWithdrawableBalanceReturn, Method as MinerMethod, MinerInfo,
+    PowerPair, SectorOnChainInfo, State as MinerState,
 };
 use fil_actor_power::State as PowerState;
 use fil_actor_reward::State as RewardState;
@@ -13,10 +17,13 @@ use fil_actor_verifreg::{Claim, ClaimID, State as VerifregState};
 use fil_actors_runtime::cbor::serialize;
 use fil_actors_runtime::test_utils::make_piece_cid;
 use fil_actors_runtime::{
-    parse_uint_key, runtime::Policy, MessageAccumulator, REWARD_ACTOR_ADDR,
-    STORAGE_MARKET_ACTOR_ADDR, STORAGE_POWER_ACTOR_ADDR, VERIFIED_REGISTRY_ACTOR_ADDR,
+    parse_uint_key, runtime::Policy, MessageAccumulator, DATACAP_TOKEN_ACTOR_ADDR, INIT_ACTOR_ADDR,
+    REWARD_ACTOR_ADDR, STORAGE_MARKET_ACTOR_ADDR, STORAGE_POWER_ACTOR_ADDR, SYSTEM_ACTOR_ADDR,
+    VERIFIED_REGISTRY_ACTOR_ADDR,
 };
 use fil_builtin_actors_state::check::check_state_invariants;
+use fil_builtin_actors_state::migration::migrate_state_tree;
+use fil_builtin_actors_state::reachability::{self, ReachabilityReport};
 use fvm_ipld_bitfield::BitField;
 use fvm_ipld_encoding::{CborStore, RawBytes, DAG_CBOR};
 use fvm_shared::address::Address;
@@ -28,22 +35,37 @@ use fvm_shared::{ActorID, METHOD_SEND};
 use integer_encoding::VarInt;
 use num_traits::Zero;
 use regex::Regex;
-use std::collections::HashMap;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use vm_api::builtin::Type;
 use vm_api::{
-    util::{apply_ok, get_state, pk_addrs_from, DynBlockstore},
-    VM,
+    util::{apply_ok, apply_ok_implicit, get_state, pk_addrs_from, DynBlockstore},
+    ActorState, VM,
 };
 pub use workflows::*;
 
-use crate::{MinerBalances, NetworkStats, TEST_FAUCET_ADDR};
+use crate::{MinerBalances, NetworkStats, FAUCET_ROOT_KEY};
 
+mod adversarial;
+mod corpus;
 mod workflows;
 
-const ACCOUNT_SEED: u64 = 93837778;
+/// Returns the ID address of the account funding those created by tests, resolved in the VM
+/// rather than assumed, so tests don't depend on the order in which the VM created it.
+pub fn faucet(v: &dyn VM) -> Address {
+    v.resolve_id_address(&Address::new_bls(FAUCET_ROOT_KEY).unwrap()).expect("VM has no faucet")
+}
 
-/// Returns addresses of created accounts in ID format
+/// Returns the ID the init actor will assign to the next actor created.
+pub fn next_actor_id(v: &dyn VM) -> ActorID {
+    get_state::<InitState>(v, &INIT_ACTOR_ADDR).unwrap().next_id
+}
+
+/// Returns addresses of created accounts in ID format.
+/// The accounts' keys are seeded by the next actor ID, so each call creates new accounts, however
+/// many accounts the VM already holds.
 pub fn create_accounts(v: &dyn VM, count: u64, balance: &TokenAmount) -> Vec<Address> {
-    create_accounts_seeded(v, count, balance, ACCOUNT_SEED, &TEST_FAUCET_ADDR)
+    create_accounts_seeded(v, count, balance, next_actor_id(v), &faucet(v))
 }
 
 /// Returns addresses of created accounts in ID format
@@ -63,6 +85,32 @@ pub fn create_accounts_seeded(
     pk_addrs.iter().map(|pk_addr| v.resolve_id_address(pk_addr).unwrap()).collect()
 }
 
+/// Returns addresses of created accounts in ID format, allocated sequentially from first_id
+pub fn create_accounts_from_id(
+    v: &dyn VM,
+    first_id: ActorID,
+    count: u64,
+    balance: &TokenAmount,
+) -> Vec<Address> {
+    v.set_next_actor_id(first_id);
+    create_accounts(v, count, balance)
+}
+
+/// Creates a placeholder actor with the given ID at a delegated address by sending it funds,
+/// returning its ID address
+pub fn create_placeholder_with_id(
+    v: &dyn VM,
+    id: ActorID,
+    delegated: &Address,
+    balance: &TokenAmount,
+) -> Address {
+    v.set_next_actor_id(id);
+    apply_ok(v, &faucet(v), delegated, balance, METHOD_SEND, None::<RawBytes>);
+    let id_addr = v.resolve_id_address(delegated).unwrap();
+    assert_eq!(Address::new_id(id), id_addr);
+    id_addr
+}
+
 pub fn check_invariants(
     vm: &dyn VM,
     policy: &Policy,
@@ -78,6 +126,31 @@ pub fn check_invariants(
     )
 }
 
+/// Walks the blocks reachable from every actor's state, reporting links to missing blocks.
+pub fn check_reachability(vm: &dyn VM) -> anyhow::Result<(ReachabilityReport, MessageAccumulator)> {
+    reachability::check_reachability(&DynBlockstore::wrap(vm.blockstore()), &vm.actor_states())
+}
+
+/// Replaces an actor's state, which may be in the layout of an older version.
+pub fn put_state<S: Serialize>(v: &dyn VM, addr: &Address, state: &S) {
+    let mut actor = v.actor(addr).unwrap();
+    actor.state = DynBlockstore::wrap(v.blockstore()).put_cbor(state, Code::Blake2b256).unwrap();
+    v.set_actor(addr, actor);
+}
+
+/// Migrates the state of some actors from the actors v12 layouts, keeping their code.
+pub fn migrate_from_v12(v: &dyn VM, addrs: &[Address]) {
+    let manifest = v.actor_manifest();
+    let codes: BTreeMap<Type, Cid> = manifest.iter().map(|(code, t)| (*t, *code)).collect();
+    let mut tree: BTreeMap<Address, ActorState> =
+        v.actor_states().into_iter().filter(|(addr, _)| addrs.contains(addr)).collect();
+    let store = DynBlockstore::wrap(v.blockstore());
+    migrate_state_tree(&store, &manifest, &codes, v.epoch(), &mut tree).unwrap();
+    for (addr, actor) in tree {
+        v.set_actor(&addr, actor);
+    }
+}
+
 pub fn assert_invariants(v: &dyn VM, policy: &Policy, expected_balance_total: Option<TokenAmount>) {
     check_invariants(v, policy, expected_balance_total).unwrap().assert_empty()
 }
@@ -91,13 +164,23 @@ pub fn expect_invariants(
     check_invariants(v, policy, expected_balance_total).unwrap().assert_expected(expected_patterns)
 }
 
+/// Queries the miner's balances as they would be for a withdrawal, with vested funds unlocked.
 pub fn miner_balance(v: &dyn VM, m: &Address) -> MinerBalances {
-    let st: MinerState = get_state(v, m).unwrap();
+    let ret: GetWithdrawableBalanceReturn = apply_ok_implicit(
+        v,
+        &SYSTEM_ACTOR_ADDR,
+        m,
+        &TokenAmount::zero(),
+        MinerMethod::GetWithdrawableBalanceExported as u64,
+        None::<RawBytes>,
+    )
+    .deserialize()
+    .unwrap();
     MinerBalances {
-        available_balance: st.get_available_balance(&v.balance(m)).unwrap(),
-        vesting_balance: st.locked_funds,
-        initial_pledge: st.initial_pledge,
-        pre_commit_deposit: st.pre_commit_deposits,
+        available_balance: ret.available_balance,
+        vesting_balance: ret.locked_funds,
+        initial_pledge: ret.initial_pledge,
+        pre_commit_deposit: ret.pre_commit_deposits,
     }
 }
 
@@ -222,6 +305,18 @@ pub fn verifreg_list_claims(v: &dyn VM, provider: ActorID) -> HashMap<ClaimID, C
     found
 }
 
+/// Returns an actor's datacap balance, read from the token state.
+pub fn datacap_balance(v: &dyn VM, owner: ActorID) -> TokenAmount {
+    let st: DatacapState = get_state(v, &DATACAP_TOKEN_ACTOR_ADDR).unwrap();
+    st.token.get_balance(&DynBlockstore::wrap(v.blockstore()), owner).unwrap()
+}
+
+/// Returns the total supply of datacap tokens.
+pub fn datacap_supply(v: &dyn VM) -> TokenAmount {
+    let st: DatacapState = get_state(v, &DATACAP_TOKEN_ACTOR_ADDR).unwrap();
+    st.token.supply
+}
+
 pub fn make_bitfield(bits: &[u64]) -> BitField {
     BitField::try_from_bits(bits.iter().copied()).unwrap()
 }
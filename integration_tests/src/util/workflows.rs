@@ -34,8 +34,9 @@ use fil_actor_cron::Method as CronMethod;
 use fil_actor_datacap::Method as DataCapMethod;
 use fil_actor_market::ext::verifreg::AllocationsResponse;
 use fil_actor_market::{
-    ClientDealProposal, DealProposal, Label, Method as MarketMethod, PublishStorageDealsParams,
-    PublishStorageDealsReturn, SectorDeals, State as MarketState, MARKET_NOTIFY_DEAL_METHOD,
+    BatchActivateDealsParams, ClientDealProposal, DealProposal, Label, Method as MarketMethod,
+    ProposalAuthScheme, PublishStorageDealsParams, PublishStorageDealsReturn, SectorDeals,
+    State as MarketState, MARKET_NOTIFY_DEAL_METHOD, NO_ALLOCATION_ID,
 };
 use fil_actor_miner::{
     aggregate_pre_commit_network_fee, aggregate_prove_commit_network_fee,
@@ -48,16 +49,19 @@ use fil_actor_miner::{
 };
 use fil_actor_multisig::Method as MultisigMethod;
 use fil_actor_multisig::ProposeParams;
-use fil_actor_power::{CreateMinerParams, CreateMinerReturn, Method as PowerMethod};
+use fil_actor_power::{
+    CreateMinerDelegatedParams, CreateMinerDelegatedReturn, CreateMinerParams,
+    Method as PowerMethod,
+};
 use fil_actor_verifreg::ext::datacap::MintParams;
 use fil_actor_verifreg::AllocationRequests;
 use fil_actor_verifreg::ClaimExtensionRequest;
+use fil_actor_verifreg::{extension_datacap, AllocationRequest, DataCap};
 use fil_actor_verifreg::{
-    AddVerifiedClientParams, AllocationID, ClaimID, ClaimTerm, ExtendClaimTermsParams,
-    Method as VerifregMethod, RemoveExpiredAllocationsParams, State as VerifregState,
-    VerifierParams,
+    AddVerifiedClientParams, AllocationClaim, AllocationID, ClaimAllocationsParams, ClaimID,
+    ClaimTerm, ExtendClaimTermsParams, Method as VerifregMethod, RemoveExpiredAllocationsParams,
+    SectorAllocationClaims, State as VerifregState, VerifierParams,
 };
-use fil_actor_verifreg::{AllocationRequest, DataCap};
 use fil_actors_runtime::cbor::deserialize;
 use fil_actors_runtime::cbor::serialize;
 use fil_actors_runtime::runtime::policy_constants::{
@@ -88,6 +92,7 @@ use crate::*;
 use super::make_bitfield;
 use super::miner_dline_info;
 use super::sector_deadline;
+use super::verifreg_list_claims;
 
 pub fn cron_tick(v: &dyn VM) {
     apply_ok_implicit(
@@ -107,31 +112,47 @@ pub fn create_miner(
     post_proof_type: RegisteredPoStProof,
     balance: &TokenAmount,
 ) -> (Address, Address) {
+    // Salt with the owner's nonce, so an owner may create any number of miners.
+    let salt = v.actor(owner).unwrap().sequence.to_be_bytes().to_vec();
+    let res = create_miner_delegated(v, owner, worker, post_proof_type, balance, salt);
+    (res.id_address, res.robust_address)
+}
+
+// Creates a miner with an f4 address derived from the owner and salt.
+pub fn create_miner_delegated(
+    v: &dyn VM,
+    owner: &Address,
+    worker: &Address,
+    post_proof_type: RegisteredPoStProof,
+    balance: &TokenAmount,
+    salt: Vec<u8>,
+) -> CreateMinerDelegatedReturn {
     let multiaddrs = vec![BytesDe("multiaddr".as_bytes().to_vec())];
     let peer_id = "miner".as_bytes().to_vec();
-    let params = CreateMinerParams {
-        owner: *owner,
-        worker: *worker,
-        window_post_proof_type: post_proof_type,
-        peer: peer_id,
-        multiaddrs,
+    let params = CreateMinerDelegatedParams {
+        miner: CreateMinerParams {
+            owner: *owner,
+            worker: *worker,
+            window_post_proof_type: post_proof_type,
+            peer: peer_id,
+            multiaddrs,
+        },
+        salt,
     };
 
     let params = IpldBlock::serialize_cbor(&params).unwrap().unwrap();
-    let res: CreateMinerReturn = v
-        .execute_message(
-            owner,
-            &STORAGE_POWER_ACTOR_ADDR,
-            balance,
-            PowerMethod::CreateMiner as u64,
-            Some(params),
-        )
-        .unwrap()
-        .ret
-        .unwrap()
-        .deserialize()
-        .unwrap();
-    (res.id_address, res.robust_address)
+    v.execute_message(
+        owner,
+        &STORAGE_POWER_ACTOR_ADDR,
+        balance,
+        PowerMethod::CreateMinerDelegatedExported as u64,
+        Some(params),
+    )
+    .unwrap()
+    .ret
+    .unwrap()
+    .deserialize()
+    .unwrap()
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -444,6 +465,155 @@ pub fn prove_commit_sectors(
     }
 }
 
+/// Prove-commits pre-committed sectors with a single aggregate proof, checking that the
+/// sectors' deals are activated and any verified allocations backing them are claimed,
+/// with the verified registry burning the claimed datacap.
+/// Returns the IDs of the allocations claimed, in sector order.
+pub fn prove_commit_aggregate_claiming(
+    v: &dyn VM,
+    worker: &Address,
+    maddr: &Address,
+    precommits: &[SectorPreCommitOnChainInfo],
+) -> Vec<AllocationID> {
+    let worker_id = v.resolve_id_address(worker).unwrap().id().unwrap();
+    let miner_id = v.resolve_id_address(maddr).unwrap().id().unwrap();
+
+    // Pending allocations are removed by activation, so gather the expected claims first.
+    let mut st: MarketState = get_state(v, &STORAGE_MARKET_ACTOR_ADDR).unwrap();
+    let store = DynBlockstore::wrap(v.blockstore());
+    let pending_allocations = st.load_pending_deal_allocation_ids(&store).unwrap();
+    let mut sector_deals = vec![];
+    let mut sector_claims = vec![];
+    let mut deal_events = vec![];
+    let mut claim_events = vec![];
+    let mut activation_events = vec![];
+    let mut claimed_ids = vec![];
+    let mut claimed_space = 0;
+    for pc in precommits {
+        let mut claims = vec![];
+        let mut pieces: Vec<(Cid, u64)> = vec![];
+        for deal_id in &pc.info.deal_ids {
+            let proposal = st.get_proposal(&store, *deal_id).unwrap();
+            let client = proposal.client.id().unwrap();
+            deal_events.push(Expect::build_market_event(
+                "deal-activated",
+                *deal_id,
+                client,
+                miner_id,
+            ));
+            pieces.push((proposal.piece_cid, proposal.piece_size.0));
+            let allocation_id =
+                pending_allocations.get(deal_id).unwrap().copied().unwrap_or(NO_ALLOCATION_ID);
+            if allocation_id != NO_ALLOCATION_ID {
+                claims.push(AllocationClaim {
+                    client,
+                    allocation_id,
+                    data: proposal.piece_cid,
+                    size: proposal.piece_size,
+                });
+                claim_events.push(Expect::build_verifreg_event(
+                    "claim",
+                    allocation_id,
+                    client,
+                    miner_id,
+                ));
+                claimed_ids.push(allocation_id);
+                claimed_space += proposal.piece_size.0;
+            }
+        }
+        sector_deals.push(SectorDeals {
+            sector_number: pc.info.sector_number,
+            sector_type: pc.info.seal_proof,
+            sector_expiry: pc.info.expiration,
+            deal_ids: pc.info.deal_ids.clone(),
+        });
+        sector_claims.push(SectorAllocationClaims {
+            sector: pc.info.sector_number,
+            expiry: pc.info.expiration,
+            claims,
+        });
+        activation_events.push(Expect::build_sector_activation_event(
+            "sector-activated",
+            miner_id,
+            pc.info.sector_number,
+            pc.info.unsealed_cid.0,
+            &pieces,
+        ));
+    }
+
+    let sector_numbers: Vec<SectorNumber> =
+        precommits.iter().map(|pc| pc.info.sector_number).collect();
+    let params = ProveCommitAggregateParams {
+        sector_numbers: make_bitfield(&sector_numbers),
+        aggregate_proof: vec![].into(),
+    };
+    apply_ok(
+        v,
+        worker,
+        maddr,
+        &TokenAmount::zero(),
+        MinerMethod::ProveCommitAggregate as u64,
+        Some(params.clone()),
+    );
+
+    let mut subinvocs =
+        vec![Expect::reward_this_epoch(miner_id), Expect::power_current_total(miner_id)];
+    if sector_deals.iter().any(|s| !s.deal_ids.is_empty()) {
+        subinvocs.push(ExpectInvocation {
+            from: miner_id,
+            to: STORAGE_MARKET_ACTOR_ADDR,
+            method: MarketMethod::BatchActivateDeals as u64,
+            params: Some(
+                IpldBlock::serialize_cbor(&BatchActivateDealsParams {
+                    sectors: sector_deals,
+                    compute_cid: false,
+                })
+                .unwrap(),
+            ),
+            events: deal_events,
+            ..Default::default()
+        });
+    }
+    if !claimed_ids.is_empty() {
+        subinvocs.push(ExpectInvocation {
+            from: miner_id,
+            to: VERIFIED_REGISTRY_ACTOR_ADDR,
+            method: VerifregMethod::ClaimAllocations as u64,
+            params: Some(
+                IpldBlock::serialize_cbor(&ClaimAllocationsParams {
+                    sectors: sector_claims,
+                    all_or_nothing: true,
+                })
+                .unwrap(),
+            ),
+            subinvocs: Some(vec![Expect::frc46_burn(
+                VERIFIED_REGISTRY_ACTOR_ID,
+                DATACAP_TOKEN_ACTOR_ADDR,
+                TokenAmount::from_whole(claimed_space),
+            )]),
+            events: claim_events,
+            ..Default::default()
+        });
+    }
+    subinvocs.push(Expect::power_update_pledge(miner_id, None));
+    subinvocs.push(Expect::burn(
+        miner_id,
+        Some(aggregate_prove_commit_network_fee(precommits.len(), &TokenAmount::zero())),
+    ));
+
+    ExpectInvocation {
+        from: worker_id,
+        to: *maddr,
+        method: MinerMethod::ProveCommitAggregate as u64,
+        params: Some(IpldBlock::serialize_cbor(&params).unwrap()),
+        subinvocs: Some(subinvocs),
+        events: activation_events,
+        ..Default::default()
+    }
+    .matches(v.take_invocations().last().unwrap());
+    claimed_ids
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn miner_extend_sector_expiration2(
     v: &dyn VM,
@@ -599,7 +769,7 @@ pub fn declare_recovery(
         recoveries: vec![RecoveryDeclaration {
             deadline,
             partition,
-            sectors: BitField::try_from_bits([sector_number].iter().copied()).unwrap(),
+            sectors: BitField::try_from_bits([sector_number].iter().copied()).unwrap().into(),
         }],
     };
 
@@ -632,6 +802,7 @@ pub fn submit_windowed_post(
         }],
         chain_commit_epoch: dline_info.challenge,
         chain_commit_rand: Randomness(TEST_VM_RAND_ARRAY.into()),
+        auto_recover_faults: false,
     };
     apply_ok(
         v,
@@ -748,6 +919,7 @@ pub fn submit_invalid_post(
         }],
         chain_commit_epoch: dline_info.challenge,
         chain_commit_rand: Randomness(TEST_VM_RAND_ARRAY.into()),
+        auto_recover_faults: false,
     };
     apply_ok(
         v,
@@ -771,6 +943,23 @@ pub fn verifier_balance_event(verifier: ActorID, data_cap: DataCap) -> EmittedEv
     }
 }
 
+pub fn expired_allocations_removed_event(
+    client: ActorID,
+    allocations: u64,
+    datacap: DataCap,
+) -> EmittedEvent {
+    EmittedEvent {
+        emitter: VERIFIED_REGISTRY_ACTOR_ID,
+        event: EventBuilder::new()
+            .typ("expired-allocations-removed")
+            .field_indexed("client", &client)
+            .field("allocations", &allocations)
+            .field("datacap", &BigIntSer(&datacap))
+            .build()
+            .unwrap(),
+    }
+}
+
 pub fn verifreg_add_verifier(v: &dyn VM, verifier: &Address, data_cap: StoragePower) {
     let add_verifier_params = VerifierParams { address: *verifier, allowance: data_cap.clone() };
     // root address is msig, send proposal from root key
@@ -779,6 +968,7 @@ pub fn verifreg_add_verifier(v: &dyn VM, verifier: &Address, data_cap: StoragePo
         value: TokenAmount::zero(),
         method: VerifregMethod::AddVerifier as u64,
         params: serialize(&add_verifier_params, "verifreg add verifier params").unwrap(),
+        expiration_epoch: None,
     };
 
     apply_ok(
@@ -904,7 +1094,7 @@ pub fn verifreg_remove_expired_allocations(
     let v_st: VerifregState = get_state(v, &VERIFIED_REGISTRY_ACTOR_ADDR).unwrap();
     let store = DynBlockstore::wrap(v.blockstore());
     let mut allocs = v_st.load_allocs(&store).unwrap();
-    let expected_events: Vec<EmittedEvent> = expected_expirations
+    let mut expected_events: Vec<EmittedEvent> = expected_expirations
         .iter()
         .map(|id| {
             let alloc = allocs.get(client.id().unwrap(), *id).unwrap().unwrap();
@@ -916,6 +1106,13 @@ pub fn verifreg_remove_expired_allocations(
             )
         })
         .collect();
+    if !expected_expirations.is_empty() {
+        expected_events.push(expired_allocations_removed_event(
+            client.id().unwrap(),
+            expected_expirations.len() as u64,
+            DataCap::from(datacap_refund),
+        ));
+    }
 
     let caller_id = v.resolve_id_address(caller).unwrap().id().unwrap();
     let params =
@@ -1021,15 +1218,17 @@ pub fn datacap_create_allocations(
     alloc_response.new_allocations
 }
 
+// Extends a claim's maximum term by transferring datacap to the verified registry.
+// Returns the datacap spent, which is pro-rated by the added term.
 pub fn datacap_extend_claim(
     v: &dyn VM,
     client: &Address,
     provider: &Address,
     claim: ClaimID,
-    size: u64,
     new_term: ChainEpoch,
     claim_client: ActorID,
-) {
+) -> TokenAmount {
+    let existing = verifreg_list_claims(v, provider.id().unwrap()).remove(&claim).unwrap();
     let payload = AllocationRequests {
         allocations: vec![],
         extensions: vec![ClaimExtensionRequest {
@@ -1038,7 +1237,8 @@ pub fn datacap_extend_claim(
             term_max: new_term,
         }],
     };
-    let token_amount = TokenAmount::from_whole(size);
+    let token_amount =
+        TokenAmount::from_whole(extension_datacap(&existing, new_term, &Policy::default()));
     let operator_data = serialize(&payload, "allocation requests").unwrap();
     let transfer_params = TransferParams {
         to: VERIFIED_REGISTRY_ACTOR_ADDR,
@@ -1058,7 +1258,7 @@ pub fn datacap_extend_claim(
     let client_id = v.resolve_id_address(client).unwrap().id().unwrap();
     Expect::datacap_transfer_to_verifreg(
         client_id,
-        token_amount,
+        token_amount.clone(),
         operator_data,
         true, // Burn
         vec![Expect::build_verifreg_event(
@@ -1069,6 +1269,7 @@ pub fn datacap_extend_claim(
         )],
     )
     .matches(v.take_invocations().last().unwrap());
+    token_amount
 }
 
 pub fn market_add_balance(
@@ -1123,6 +1324,7 @@ pub fn market_publish_deal(
         deals: vec![ClientDealProposal {
             proposal: proposal.clone(),
             client_signature: signature.clone(),
+            auth_scheme: ProposalAuthScheme::Cbor,
         }],
     };
     let ret: PublishStorageDealsReturn = apply_ok(
@@ -13,7 +13,10 @@ static NETWORKS: &[(&str, &[&str])] = &[
     ),
     ("butterflynet", &["sector-512m", "sector-32g", "sector-64g", "min-power-2g"]),
     ("calibrationnet", &["sector-32g", "sector-64g", "min-power-32g"]),
-    ("devnet", &["sector-2k", "sector-8m", "small-deals", "short-precommit", "min-power-2k"]),
+    (
+        "devnet",
+        &["sector-2k", "sector-8m", "small-deals", "short-precommit", "min-power-2k", "metrics"],
+    ),
     (
         "testing",
         &[
@@ -26,6 +29,7 @@ static NETWORKS: &[(&str, &[&str])] = &[
             "short-precommit",
             "min-power-2k",
             "no-provider-deal-collateral",
+            "metrics",
         ],
     ),
     (
@@ -41,6 +45,7 @@ static NETWORKS: &[(&str, &[&str])] = &[
             "min-power-2k",
             "no-provider-deal-collateral",
             "fake-proofs",
+            "metrics",
         ],
     ),
 ];
@@ -2,7 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 use fvm_ipld_encoding::tuple::*;
 use fvm_shared::bigint::bigint_ser;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
 use fvm_shared::sector::StoragePower;
+use num_traits::Zero;
+
+use math::PRECISION;
 
 pub mod math;
 pub mod smooth;
@@ -16,3 +21,30 @@ pub struct ThisEpochRewardReturn {
     #[serde(with = "bigint_ser")]
     pub this_epoch_baseline_power: StoragePower,
 }
+
+/// The projected block reward a sector would earn over some period.
+/// Also known as "BR(t)".
+/// BR(t) = ProjectedRewardFraction(t) * SectorQualityAdjustedPower
+/// ProjectedRewardFraction(t) is the sum of estimated reward over estimated total power
+/// over all epochs in the projection period [t t+projectionDuration]
+pub fn expected_reward_for_power(
+    reward_estimate: &FilterEstimate,
+    network_qa_power_estimate: &FilterEstimate,
+    qa_sector_power: &StoragePower,
+    projection_duration: ChainEpoch,
+) -> TokenAmount {
+    let network_qa_power_smoothed = network_qa_power_estimate.estimate();
+
+    if network_qa_power_smoothed.is_zero() {
+        return TokenAmount::from_atto(reward_estimate.estimate());
+    }
+
+    let expected_reward_for_proving_period = smooth::extrapolated_cum_sum_of_ratio(
+        projection_duration,
+        0,
+        reward_estimate,
+        network_qa_power_estimate,
+    );
+    let br128 = qa_sector_power * expected_reward_for_proving_period; // Q.0 * Q.128 => Q.128
+    TokenAmount::from_atto(std::cmp::max(br128 >> PRECISION, Default::default()))
+}
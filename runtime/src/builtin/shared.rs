@@ -4,12 +4,13 @@
 use crate::runtime::builtins::Type;
 use crate::{actor_error, ActorContext, ActorError};
 use fvm_ipld_encoding::ipld_block::IpldBlock;
-use fvm_shared::address::Address;
+use fvm_shared::address::{Address, Protocol};
+use fvm_shared::crypto::signature::{Signature, SignatureType};
 use fvm_shared::METHOD_SEND;
 use fvm_shared::{ActorID, MethodNum};
 use std::fmt::{Display, Formatter};
 
-use crate::runtime::Runtime;
+use crate::runtime::{Runtime, SignatureVerifyInfo};
 
 pub const HAMT_BIT_WIDTH: u32 = 5;
 
@@ -54,6 +55,52 @@ pub fn resolve_to_actor_id(
     Err(actor_error!(illegal_argument, "failed to resolve or initialize address {}", address))
 }
 
+/// Verifies, in a single batch, signatures made by signers with key (BLS or SECP256K1) addresses.
+///
+/// Each item is a signer with the signature bytes and plaintext it would be passed in an account's
+/// `AuthenticateMessage`, the signature type following from the signer's address protocol.
+/// Returns whether each signature is valid, or `None` for signers without a key address,
+/// which must instead be asked to authenticate the message.
+pub fn verify_key_signatures(
+    rt: &impl Runtime,
+    items: impl IntoIterator<Item = (Address, Vec<u8>, Vec<u8>)>,
+) -> Result<Vec<Option<bool>>, ActorError> {
+    let mut batch = Vec::new();
+    let mut batch_indices = Vec::new();
+    for (signer, bytes, plaintext) in items {
+        let sig_type = match signer.protocol() {
+            Protocol::BLS => SignatureType::BLS,
+            Protocol::Secp256k1 => SignatureType::Secp256k1,
+            _ => {
+                batch_indices.push(None);
+                continue;
+            }
+        };
+        batch_indices.push(Some(batch.len()));
+        batch.push(SignatureVerifyInfo {
+            signature: Signature { sig_type, bytes },
+            signer,
+            plaintext,
+        });
+    }
+    if batch.is_empty() {
+        return Ok(vec![None; batch_indices.len()]);
+    }
+
+    let valid = rt
+        .verify_signatures_batch(&batch)
+        .map_err(|e| actor_error!(illegal_argument; "failed to verify signatures: {}", e))?;
+    if valid.len() != batch.len() {
+        return Err(actor_error!(
+            assertion_failed,
+            "expected {} signature verification results, got {}",
+            batch.len(),
+            valid.len()
+        ));
+    }
+    Ok(batch_indices.into_iter().map(|i| i.map(|i| valid[i])).collect())
+}
+
 // The lowest FRC-42 method number.
 pub const FIRST_EXPORTED_METHOD_NUMBER: MethodNum = 1 << 24;
 
@@ -6,6 +6,7 @@ use std::ops::{Deref, DerefMut};
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 use serde::{de::DeserializeOwned, Serialize};
 
+use crate::runtime::{ActorCode, Runtime};
 use crate::ActorError;
 
 pub struct WithCodec<T, const CODEC: u64>(pub T);
@@ -59,6 +60,9 @@ macro_rules! actor_dispatch {
             RT::Blockstore: Clone,
         {
             $crate::builtin::shared::restrict_internal_api(rt, method)?;
+            if method == $crate::runtime::GET_ACTOR_INFO_METHOD {
+                return $crate::dispatch_actor_info::<Self, RT>(rt, args);
+            }
             match <Self::Methods as num_traits::FromPrimitive>::from_u64(method) {
                 $($(#[$m])*
                   $crate::actor_dispatch!(@pattern $($method)|*) =>
@@ -118,6 +122,61 @@ macro_rules! actor_dispatch_unrestricted {
     };
 }
 
+/// Defines an actor's description for `GetActorInfo`, within its `ActorCode` implementation.
+/// The named features are reported if enabled in the actor's crate, following those enabled
+/// in the runtime.
+///
+/// ```ignore
+/// impl ActorCode for Actor {
+///     actor_info! {
+///         interface_revisions: [1],
+///         features: ["storage-rent"],
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! actor_info {
+    (
+        interface_revisions: [$($revision:expr),* $(,)?],
+        features: [$($feature:literal),* $(,)?] $(,)?
+    ) => {
+        fn info() -> $crate::runtime::ActorInfo {
+            #[allow(unused_mut)]
+            let mut features: Vec<String> =
+                $crate::runtime::runtime_features().into_iter().map(String::from).collect();
+            $(
+                if cfg!(feature = $feature) {
+                    features.push($feature.to_string());
+                }
+            )*
+            $crate::runtime::ActorInfo {
+                interface_revisions: vec![$($revision),*],
+                features,
+            }
+        }
+    };
+}
+
+/// Implements `GetActorInfo`, which any caller may invoke, for an actor.
+/// [`actor_dispatch!`](crate::actor_dispatch) routes the method here. Actors dispatching with
+/// [`actor_dispatch_unrestricted!`](crate::actor_dispatch_unrestricted) must route it themselves
+/// if they implement it, as their method numbers may otherwise be handled by user code.
+#[doc(hidden)]
+pub fn dispatch_actor_info<A, RT>(
+    rt: &RT,
+    args: Option<IpldBlock>,
+) -> Result<Option<IpldBlock>, ActorError>
+where
+    A: ActorCode,
+    RT: Runtime,
+{
+    rt.validate_immediate_caller_accept_any()?;
+    if args.is_some() {
+        return Err(ActorError::illegal_argument("method expects no arguments".into()));
+    }
+    maybe_into_block(A::info(), CBOR)
+}
+
 pub trait Dispatch<RT> {
     fn call(
         self,
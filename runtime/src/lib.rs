@@ -12,7 +12,7 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 use unsigned_varint::decode::Error as UVarintError;
 
-pub use dispatch::{dispatch, dispatch_default, WithCodec};
+pub use dispatch::{dispatch, dispatch_actor_info, dispatch_default, WithCodec};
 pub use {fvm_ipld_amt, fvm_ipld_hamt};
 
 #[cfg(feature = "fil-actor")]
@@ -3,15 +3,33 @@
 
 use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_ipld_encoding::tuple::*;
 use fvm_shared::MethodNum;
 
 use crate::{ActorError, Runtime};
 
+/// Method number of `GetActorInfo`, which built-in actors implement through their dispatch.
+/// This is `frc42_dispatch::method_hash!("GetActorInfo")`.
+pub const GET_ACTOR_INFO_METHOD: MethodNum = 1137357315;
+
+/// Describes the capabilities of an actor's code, returned by `GetActorInfo`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ActorInfo {
+    /// Revisions of the actor's exported method interface that it supports.
+    pub interface_revisions: Vec<u64>,
+    /// Compile-time features enabled in the actor's build, including network-wide features
+    /// of the runtime.
+    pub features: Vec<String>,
+}
+
 /// Interface for invoking methods on an Actor
 pub trait ActorCode {
     type Methods;
     /// A name for the actor type, used in debugging.
     fn name() -> &'static str;
+    /// Describes the actor's code, as returned by `GetActorInfo`.
+    /// Implemented with [`actor_info!`](crate::actor_info).
+    fn info() -> ActorInfo;
     /// Invokes method with runtime on the actor's code. Method number will match one
     /// defined by the Actor, and parameters will be serialized and used in execution
     fn invoke_method<RT>(
@@ -26,3 +44,41 @@ pub trait ActorCode {
         RT: Runtime,
         RT::Blockstore: Blockstore + Clone;
 }
+
+/// Names the network-wide features enabled in the runtime.
+pub fn runtime_features() -> Vec<&'static str> {
+    let mut features = Vec::new();
+    macro_rules! push_enabled {
+        ($($feature:literal),*) => {
+            $(
+                if cfg!(feature = $feature) {
+                    features.push($feature);
+                }
+            )*
+        };
+    }
+    push_enabled!(
+        "sector-2k",
+        "sector-8m",
+        "sector-512m",
+        "sector-32g",
+        "sector-64g",
+        "small-deals",
+        "short-precommit",
+        "min-power-2k",
+        "min-power-2g",
+        "no-provider-deal-collateral",
+        "fake-proofs"
+    );
+    features
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn get_actor_info_method_is_frc42() {
+        assert_eq!(frc42_dispatch::method_hash!("GetActorInfo"), GET_ACTOR_INFO_METHOD);
+    }
+}
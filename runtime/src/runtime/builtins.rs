@@ -1 +1,167 @@
+use fvm_shared::version::NetworkVersion;
+
 pub use vm_api::builtin::Type;
+
+/// The type of an actor as resolved from its code CID.
+///
+/// Unlike [`Type`], this also represents code that does not belong to any built-in actor.
+/// Helpers on this type match every variant explicitly, so introducing a new actor type fails
+/// to compile until each of them decides how the new type is handled.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ActorType {
+    System,
+    Init,
+    Cron,
+    Account,
+    Power,
+    Miner,
+    Market,
+    PaymentChannel,
+    Multisig,
+    Reward,
+    VerifiedRegistry,
+    DataCap,
+    Placeholder,
+    EVM,
+    EAM,
+    EthAccount,
+    /// Code not belonging to any built-in actor.
+    Unknown,
+}
+
+impl ActorType {
+    /// Returns the built-in type of the actor, if any.
+    pub fn builtin(&self) -> Option<Type> {
+        match self {
+            ActorType::System => Some(Type::System),
+            ActorType::Init => Some(Type::Init),
+            ActorType::Cron => Some(Type::Cron),
+            ActorType::Account => Some(Type::Account),
+            ActorType::Power => Some(Type::Power),
+            ActorType::Miner => Some(Type::Miner),
+            ActorType::Market => Some(Type::Market),
+            ActorType::PaymentChannel => Some(Type::PaymentChannel),
+            ActorType::Multisig => Some(Type::Multisig),
+            ActorType::Reward => Some(Type::Reward),
+            ActorType::VerifiedRegistry => Some(Type::VerifiedRegistry),
+            ActorType::DataCap => Some(Type::DataCap),
+            ActorType::Placeholder => Some(Type::Placeholder),
+            ActorType::EVM => Some(Type::EVM),
+            ActorType::EAM => Some(Type::EAM),
+            ActorType::EthAccount => Some(Type::EthAccount),
+            ActorType::Unknown => None,
+        }
+    }
+
+    /// Returns the network version in which the actor type was introduced,
+    /// or None if the actor is not a built-in actor.
+    pub fn introduced_in(&self) -> Option<NetworkVersion> {
+        match self {
+            ActorType::System
+            | ActorType::Init
+            | ActorType::Cron
+            | ActorType::Account
+            | ActorType::Power
+            | ActorType::Miner
+            | ActorType::Market
+            | ActorType::PaymentChannel
+            | ActorType::Multisig
+            | ActorType::Reward
+            | ActorType::VerifiedRegistry => Some(NetworkVersion::V0),
+            ActorType::DataCap => Some(NetworkVersion::V17),
+            ActorType::Placeholder | ActorType::EVM | ActorType::EAM | ActorType::EthAccount => {
+                Some(NetworkVersion::V18)
+            }
+            ActorType::Unknown => None,
+        }
+    }
+
+    /// Whether the actor is a storage provider, able to hold deals and sectors.
+    pub fn is_storage_provider(&self) -> bool {
+        match self {
+            ActorType::Miner => true,
+            ActorType::System
+            | ActorType::Init
+            | ActorType::Cron
+            | ActorType::Account
+            | ActorType::Power
+            | ActorType::Market
+            | ActorType::PaymentChannel
+            | ActorType::Multisig
+            | ActorType::Reward
+            | ActorType::VerifiedRegistry
+            | ActorType::DataCap
+            | ActorType::Placeholder
+            | ActorType::EVM
+            | ActorType::EAM
+            | ActorType::EthAccount
+            | ActorType::Unknown => false,
+        }
+    }
+
+    /// Whether the actor is a native account, holding a secp256k1 or BLS key.
+    pub fn is_native_account(&self) -> bool {
+        match self {
+            ActorType::Account => true,
+            ActorType::System
+            | ActorType::Init
+            | ActorType::Cron
+            | ActorType::Power
+            | ActorType::Miner
+            | ActorType::Market
+            | ActorType::PaymentChannel
+            | ActorType::Multisig
+            | ActorType::Reward
+            | ActorType::VerifiedRegistry
+            | ActorType::DataCap
+            | ActorType::Placeholder
+            | ActorType::EVM
+            | ActorType::EAM
+            | ActorType::EthAccount
+            | ActorType::Unknown => false,
+        }
+    }
+}
+
+impl From<Option<Type>> for ActorType {
+    fn from(typ: Option<Type>) -> Self {
+        match typ {
+            Some(Type::System) => ActorType::System,
+            Some(Type::Init) => ActorType::Init,
+            Some(Type::Cron) => ActorType::Cron,
+            Some(Type::Account) => ActorType::Account,
+            Some(Type::Power) => ActorType::Power,
+            Some(Type::Miner) => ActorType::Miner,
+            Some(Type::Market) => ActorType::Market,
+            Some(Type::PaymentChannel) => ActorType::PaymentChannel,
+            Some(Type::Multisig) => ActorType::Multisig,
+            Some(Type::Reward) => ActorType::Reward,
+            Some(Type::VerifiedRegistry) => ActorType::VerifiedRegistry,
+            Some(Type::DataCap) => ActorType::DataCap,
+            Some(Type::Placeholder) => ActorType::Placeholder,
+            Some(Type::EVM) => ActorType::EVM,
+            Some(Type::EAM) => ActorType::EAM,
+            Some(Type::EthAccount) => ActorType::EthAccount,
+            None => ActorType::Unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ActorType, Type};
+    use num_traits::FromPrimitive;
+
+    #[test]
+    fn builtin_types_round_trip() {
+        let mut i = 1;
+        while let Some(typ) = Type::from_i32(i) {
+            let actor_type = ActorType::from(Some(typ));
+            assert_eq!(Some(typ), actor_type.builtin());
+            assert!(actor_type.introduced_in().is_some());
+            i += 1;
+        }
+        assert_eq!(None, ActorType::from(None).builtin());
+        assert_eq!(None, ActorType::Unknown.introduced_in());
+    }
+}
@@ -14,7 +14,7 @@ use fvm_shared::clock::ChainEpoch;
 use fvm_shared::consensus::ConsensusFault;
 use fvm_shared::crypto::hash::SupportedHashes;
 use fvm_shared::crypto::signature::{
-    Signature, SECP_PUB_LEN, SECP_SIG_LEN, SECP_SIG_MESSAGE_HASH_SIZE,
+    Signature, BLS_PUB_LEN, BLS_SIG_LEN, SECP_PUB_LEN, SECP_SIG_LEN, SECP_SIG_MESSAGE_HASH_SIZE,
 };
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::{ErrorNumber, ExitCode};
@@ -323,7 +323,22 @@ where
             return Err(SendError(ErrorNumber::IllegalOperation));
         }
 
-        fvm::send::send(to, method, params, value, gas_limit, flags).map_err(SendError)
+        #[cfg(feature = "send-trace")]
+        let (traced_to, traced_value) = (*to, value.clone());
+        let res = fvm::send::send(to, method, params, value, gas_limit, flags).map_err(SendError);
+        #[cfg(feature = "send-trace")]
+        log::debug!(
+            "{}",
+            crate::runtime::send_trace::SendRecord {
+                from: Address::new_id(fvm::message::receiver()),
+                to: traced_to,
+                method,
+                value: traced_value,
+                depth: 0,
+                result: Some(res.as_ref().map(|r| r.exit_code).map_err(|e| e.0)),
+            }
+        );
+        res
     }
 
     fn new_actor_address(&self) -> Result<Address, ActorError> {
@@ -392,6 +407,14 @@ where
     fn read_only(&self) -> bool {
         fvm::vm::read_only()
     }
+
+    /// The FVM offers actors no metrics sink, so counters aren't accumulated on chain: each
+    /// increment is only written to the debug log, from which node telemetry may total them.
+    #[cfg(feature = "metrics")]
+    fn increment_counter(&self, name: &'static str, by: u64) {
+        // The logger prefixes the actor, and is installed only if debugging is enabled.
+        log::info!("counter {} +{}", name, by);
+    }
 }
 
 impl<B> Primitives for FvmRuntime<B>
@@ -410,6 +433,18 @@ where
         }
     }
 
+    fn verify_bls_aggregate(
+        &self,
+        aggregate_sig: &[u8; BLS_SIG_LEN],
+        pub_keys: &[[u8; BLS_PUB_LEN]],
+        plaintexts: &[&[u8]],
+    ) -> Result<(), Error> {
+        match fvm::crypto::verify_bls_aggregate(aggregate_sig, pub_keys, plaintexts) {
+            Ok(true) => Ok(()),
+            Ok(false) | Err(_) => Err(Error::msg("invalid aggregate signature")),
+        }
+    }
+
     fn hash_blake2b(&self, data: &[u8]) -> [u8; 32] {
         fvm::crypto::hash_blake2b(data)
     }
@@ -0,0 +1,96 @@
+// Copyright 2019-2022 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Named counters of the work done by actors, for comparing workloads across benchmarks and
+//! forks. Enabled by the `metrics` feature, which the devnet and testing bundles build with.
+//!
+//! Actors increment counters through [`Runtime::increment_counter`](super::Runtime), named
+//! `<actor>.<event>` (e.g. `market.deals_published`). Counters aren't part of actor state:
+//! the FVM runtime logs each increment to the debug log, where node telemetry can collect it,
+//! and the test VM and mock runtime accumulate them per actor. Counters whose totals actor state
+//! also records can be derived from any state tree with `fil_builtin_actors_state::counters`.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// The counters incremented by a single actor.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Counters(BTreeMap<String, u64>);
+
+impl Counters {
+    /// Increments a counter, saturating at `u64::MAX`.
+    pub fn increment(&mut self, name: &str, by: u64) {
+        match self.0.get_mut(name) {
+            Some(count) => *count = count.saturating_add(by),
+            None => {
+                self.0.insert(name.to_string(), by);
+            }
+        }
+    }
+
+    /// Returns the value of a counter, which is zero if it was never incremented.
+    pub fn get(&self, name: &str) -> u64 {
+        self.0.get(name).copied().unwrap_or_default()
+    }
+
+    /// Adds every counter in `other` to this one.
+    pub fn merge(&mut self, other: &Counters) {
+        for (name, count) in other.iter() {
+            self.increment(name, count);
+        }
+    }
+
+    /// Iterates the counters in name order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u64)> {
+        self.0.iter().map(|(name, count)| (name.as_str(), *count))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+impl fmt::Display for Counters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, count) in self.iter() {
+            writeln!(f, "{} {}", name, count)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn increments_and_merges() {
+        let mut counters = Counters::default();
+        assert_eq!(0, counters.get("market.deals_published"));
+        counters.increment("market.deals_published", 2);
+        counters.increment("market.deals_published", 1);
+        counters.increment("miner.post_submissions", u64::MAX);
+        counters.increment("miner.post_submissions", 1);
+        assert_eq!(3, counters.get("market.deals_published"));
+        assert_eq!(u64::MAX, counters.get("miner.post_submissions"));
+
+        let mut total = Counters::default();
+        total.increment("market.deals_published", 4);
+        total.merge(&counters);
+        assert_eq!(7, total.get("market.deals_published"));
+        assert_eq!(
+            "market.deals_published 7\nminer.post_submissions 18446744073709551615\n",
+            total.to_string()
+        );
+
+        total.clear();
+        assert!(total.is_empty());
+    }
+}
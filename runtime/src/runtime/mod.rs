@@ -16,13 +16,17 @@ use serde::Serialize;
 pub use self::actor_code::*;
 pub use self::policy::*;
 pub use self::randomness::DomainSeparationTag;
-use crate::runtime::builtins::Type;
+use crate::runtime::builtins::{ActorType, Type};
 use crate::{actor_error, ActorError, SendError};
 
 mod actor_code;
 pub mod builtins;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 pub mod policy;
 mod randomness;
+#[cfg(feature = "send-trace")]
+pub mod send_trace;
 
 #[cfg(feature = "fil-actor")]
 mod actor_blockstore;
@@ -39,7 +43,7 @@ use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_shared::chainid::ChainID;
 use fvm_shared::event::ActorEvent;
 use fvm_shared::sys::SendFlags;
-pub use vm_api::Primitives;
+pub use vm_api::{Primitives, SignatureVerifyInfo};
 
 /// Runtime is the VM's internal runtime object.
 /// this is everything that is accessible to actors, beyond parameters.
@@ -211,6 +215,12 @@ pub trait Runtime: Primitives + RuntimePolicy {
     /// Returns whether the specified CodeCID belongs to a built-in actor.
     fn resolve_builtin_actor_type(&self, code_id: &Cid) -> Option<Type>;
 
+    /// Resolves the actor type for a CodeCID, distinguishing code that does not belong
+    /// to a built-in actor.
+    fn resolve_builtin_actor_type_checked(&self, code_id: &Cid) -> ActorType {
+        ActorType::from(self.resolve_builtin_actor_type(code_id))
+    }
+
     /// Returns the CodeCID for a built-in actor type. The kernel will abort
     /// if the supplied type is invalid.
     fn get_code_cid_for_type(&self, typ: Type) -> Cid;
@@ -248,6 +258,12 @@ pub trait Runtime: Primitives + RuntimePolicy {
     /// Returns true if the call is read_only.
     /// All state updates, including actor creation and balance transfers, are rejected in read_only calls.
     fn read_only(&self) -> bool;
+
+    /// Increments a named workload counter for the executing actor.
+    /// Counters are recorded only by runtimes built with the `metrics` feature, and have no
+    /// effect on execution. The FVM runtime only logs each increment, while the test VM and
+    /// mock runtime accumulate them per actor.
+    fn increment_counter(&self, _name: &'static str, _by: u64) {}
 }
 
 /// Message information available to the actor about executing message.
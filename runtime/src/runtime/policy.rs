@@ -53,6 +53,9 @@ pub struct Policy {
     /// Maximum number of control addresses a miner may register.
     pub max_control_addresses: usize,
 
+    /// Maximum number of provers a miner may authorize to submit Window PoSts.
+    pub max_prover_authorizations: usize,
+
     /// MaxPeerIDLength is the maximum length allowed for any on-chain peer ID.
     pub max_peer_id_length: usize,
 
@@ -93,6 +96,9 @@ pub struct Policy {
     /// Staging period for a miner worker key change.
     pub worker_key_change_delay: ChainEpoch,
 
+    /// Period after which a proposed miner owner change can no longer be confirmed.
+    pub owner_change_expiry: ChainEpoch,
+
     /// Minimum number of epochs past the current epoch a sector may be set to expire.
     pub min_sector_expiration: i64,
 
@@ -101,6 +107,14 @@ pub struct Policy {
     /// and sector.ActivationEpoch+sealProof.SectorMaximumLifetime()
     pub max_sector_expiration_extension: i64,
 
+    /// Whether sectors committed for longer terms earn a QA power multiplier.
+    /// The multiplier is fixed when a sector is committed and recomputed when its term is extended.
+    pub sector_duration_multiplier_enabled: bool,
+
+    /// Maximum age of a committed capacity sector whose termination fee is pro-rated to its age,
+    /// allowing a mistaken commitment to be removed cheaply soon after activation.
+    pub cc_sector_fast_removal_max_age: ChainEpoch,
+
     /// Ratio of sector size to maximum deals per sector.
     /// The maximum number of deals is the sector size divided by this number.
     pub deal_limit_denominator: u64,
@@ -139,6 +153,10 @@ pub struct Policy {
     /// The number of blocks between payouts for deals
     pub deal_updates_interval: i64,
 
+    /// Maximum number of deals the market settles for terminated sectors in a single call
+    /// or cron tick. Further terminations are deferred to later cron ticks.
+    pub max_deal_terminations_per_batch: u64,
+
     /// Numerator of the percentage of normalized cirulating
     /// supply that must be covered by provider collateral
     pub prov_collateral_percent_supply_num: i64,
@@ -151,9 +169,35 @@ pub struct Policy {
     /// allocation's maximum term.
     pub market_default_allocation_term_buffer: i64,
 
+    /// Numerator of the fraction of a deal's total storage fee paid to the provider
+    /// when the client cancels the deal before activation
+    pub deal_cancellation_fee_num: i64,
+
+    /// Denominator of the fraction of a deal's total storage fee paid to the provider
+    /// when the client cancels the deal before activation
+    pub deal_cancellation_fee_denom: i64,
+
+    /// Numerator of the fraction of a deal's provider collateral that is the minimum fee paid
+    /// to the provider when the client cancels the deal before activation
+    pub deal_cancellation_min_fee_num: i64,
+
+    /// Denominator of the fraction of a deal's provider collateral that is the minimum fee paid
+    /// to the provider when the client cancels the deal before activation
+    pub deal_cancellation_min_fee_denom: i64,
+
+    /// Maximum total delay to a deal's start epoch that a provider may make without the
+    /// client's approval
+    pub deal_activation_extension_slack: ChainEpoch,
+
     // --- power ---
     /// Minimum miner consensus power
     pub minimum_consensus_power: StoragePower,
+
+    // --- reward ---
+    /// Ceiling on the block reward paid out in a single epoch, in parts per billion of the
+    /// circulating supply. Rewards beyond the ceiling are withheld in the reward actor.
+    /// Zero disables the ceiling.
+    pub max_epoch_reward_emission_ppb: u64,
 }
 
 impl Default for Policy {
@@ -174,6 +218,7 @@ impl Default for Policy {
             sectors_max: policy_constants::SECTORS_MAX,
             max_partitions_per_deadline: policy_constants::MAX_PARTITIONS_PER_DEADLINE,
             max_control_addresses: policy_constants::MAX_CONTROL_ADDRESSES,
+            max_prover_authorizations: policy_constants::MAX_PROVER_AUTHORIZATIONS,
             max_peer_id_length: policy_constants::MAX_PEER_ID_LENGTH,
             max_multiaddr_data: policy_constants::MAX_MULTIADDR_DATA,
             addressed_partitions_max: policy_constants::ADDRESSED_PARTITIONS_MAX,
@@ -187,8 +232,12 @@ impl Default for Policy {
             fault_declaration_cutoff: policy_constants::FAULT_DECLARATION_CUTOFF,
             fault_max_age: policy_constants::FAULT_MAX_AGE,
             worker_key_change_delay: policy_constants::WORKER_KEY_CHANGE_DELAY,
+            owner_change_expiry: policy_constants::OWNER_CHANGE_EXPIRY,
             min_sector_expiration: policy_constants::MIN_SECTOR_EXPIRATION,
             max_sector_expiration_extension: policy_constants::MAX_SECTOR_EXPIRATION_EXTENSION,
+            sector_duration_multiplier_enabled:
+                policy_constants::SECTOR_DURATION_MULTIPLIER_ENABLED,
+            cc_sector_fast_removal_max_age: policy_constants::CC_SECTOR_FAST_REMOVAL_MAX_AGE,
             deal_limit_denominator: policy_constants::DEAL_LIMIT_DENOMINATOR,
             consensus_fault_ineligibility_duration:
                 policy_constants::CONSENSUS_FAULT_INELIGIBILITY_DURATION,
@@ -207,14 +256,22 @@ impl Default for Policy {
                 policy_constants::MAXIMUM_VERIFIED_ALLOCATION_EXPIRATION,
             end_of_life_claim_drop_period: policy_constants::END_OF_LIFE_CLAIM_DROP_PERIOD,
             deal_updates_interval: policy_constants::DEAL_UPDATES_INTERVAL,
+            max_deal_terminations_per_batch: policy_constants::MAX_DEAL_TERMINATIONS_PER_BATCH,
             prov_collateral_percent_supply_num:
                 policy_constants::PROV_COLLATERAL_PERCENT_SUPPLY_NUM,
             prov_collateral_percent_supply_denom:
                 policy_constants::PROV_COLLATERAL_PERCENT_SUPPLY_DENOM,
             market_default_allocation_term_buffer:
                 policy_constants::MARKET_DEFAULT_ALLOCATION_TERM_BUFFER,
+            deal_cancellation_fee_num: policy_constants::DEAL_CANCELLATION_FEE_NUM,
+            deal_cancellation_fee_denom: policy_constants::DEAL_CANCELLATION_FEE_DENOM,
+            deal_cancellation_min_fee_num: policy_constants::DEAL_CANCELLATION_MIN_FEE_NUM,
+            deal_cancellation_min_fee_denom: policy_constants::DEAL_CANCELLATION_MIN_FEE_DENOM,
+            deal_activation_extension_slack: policy_constants::DEAL_ACTIVATION_EXTENSION_SLACK,
 
             minimum_consensus_power: StoragePower::from(policy_constants::MINIMUM_CONSENSUS_POWER),
+
+            max_epoch_reward_emission_ppb: policy_constants::MAX_EPOCH_REWARD_EMISSION_PPB,
         }
     }
 }
@@ -268,6 +325,8 @@ pub mod policy_constants {
 
     pub const MAX_CONTROL_ADDRESSES: usize = 10;
 
+    pub const MAX_PROVER_AUTHORIZATIONS: usize = 10;
+
     // Most Peer IDs are expected to be less than 50 bytes.
     pub const MAX_PEER_ID_LENGTH: usize = 128;
 
@@ -307,10 +366,16 @@ pub mod policy_constants {
     // key or allowing the owner account to submit PoSts while a key change is pending.
     pub const WORKER_KEY_CHANGE_DELAY: ChainEpoch = CHAIN_FINALITY;
 
+    pub const OWNER_CHANGE_EXPIRY: ChainEpoch = 7 * EPOCHS_IN_DAY;
+
     pub const MIN_SECTOR_EXPIRATION: i64 = 180 * EPOCHS_IN_DAY;
 
     pub const MAX_SECTOR_EXPIRATION_EXTENSION: i64 = 1278 * EPOCHS_IN_DAY;
 
+    pub const SECTOR_DURATION_MULTIPLIER_ENABLED: bool = false;
+
+    pub const CC_SECTOR_FAST_REMOVAL_MAX_AGE: ChainEpoch = 30 * EPOCHS_IN_DAY;
+
     /// A value (2^27) limits 32GiB sectors to 256 deals and 64GiB sectors to 512.
     pub const DEAL_LIMIT_DENOMINATOR: u64 = 134217728;
 
@@ -332,6 +397,8 @@ pub mod policy_constants {
 
     pub const DEAL_UPDATES_INTERVAL: i64 = 30 * EPOCHS_IN_DAY;
 
+    pub const MAX_DEAL_TERMINATIONS_PER_BATCH: u64 = 1000;
+
     #[cfg(not(feature = "no-provider-deal-collateral"))]
     pub const PROV_COLLATERAL_PERCENT_SUPPLY_NUM: i64 = 1;
     #[cfg(feature = "no-provider-deal-collateral")]
@@ -341,6 +408,14 @@ pub mod policy_constants {
 
     pub const MARKET_DEFAULT_ALLOCATION_TERM_BUFFER: i64 = 90 * EPOCHS_IN_DAY;
 
+    pub const DEAL_CANCELLATION_FEE_NUM: i64 = 1;
+    pub const DEAL_CANCELLATION_FEE_DENOM: i64 = 100;
+
+    pub const DEAL_CANCELLATION_MIN_FEE_NUM: i64 = 5;
+    pub const DEAL_CANCELLATION_MIN_FEE_DENOM: i64 = 100;
+
+    pub const DEAL_ACTIVATION_EXTENSION_SLACK: ChainEpoch = 12 * EPOCHS_IN_HOUR;
+
     #[cfg(feature = "min-power-2k")]
     pub const MINIMUM_CONSENSUS_POWER: i64 = 2 << 10;
     #[cfg(feature = "min-power-2g")]
@@ -353,6 +428,8 @@ pub mod policy_constants {
         feature = "min-power-32g"
     )))]
     pub const MINIMUM_CONSENSUS_POWER: i64 = 10 << 40;
+
+    pub const MAX_EPOCH_REWARD_EMISSION_PPB: u64 = 0;
 }
 
 /// A set indicating which proofs are considered valid, optimised for lookup of a small number of
@@ -0,0 +1,251 @@
+// Copyright 2019-2022 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Structured traces of the sends made by actors while executing a message, for debugging deep
+//! call trees. Enabled by the `send-trace` feature.
+
+use std::fmt;
+
+use fvm_shared::address::Address;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::{ErrorNumber, ExitCode};
+use fvm_shared::{MethodNum, METHOD_CONSTRUCTOR, METHOD_SEND};
+
+/// A send made by an actor, and its outcome.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SendRecord {
+    pub from: Address,
+    pub to: Address,
+    pub method: MethodNum,
+    pub value: TokenAmount,
+    /// Number of sends enclosing this one within the message.
+    pub depth: usize,
+    /// The receiver's exit code, or the error if the send itself failed.
+    /// None if the send hasn't returned.
+    pub result: Option<Result<ExitCode, ErrorNumber>>,
+}
+
+impl fmt::Display for SendRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:indent$}{} -> {} ", "", self.from, self.to, indent = 2 * self.depth)?;
+        match method_name(self.method) {
+            Some(name) => write!(f, "{}", name)?,
+            None => write!(f, "method {}", self.method)?,
+        }
+        write!(f, " value {}: ", self.value)?;
+        match &self.result {
+            Some(Ok(code)) => write!(f, "exit code {}", code),
+            Some(Err(e)) => write!(f, "send failed: {:?}", e),
+            None => write!(f, "no result"),
+        }
+    }
+}
+
+/// The sends made while executing a message, in the order they were made.
+///
+/// A trace that is dropped while the thread is panicking is printed to stderr, so a failing test
+/// shows the calls leading up to the failure.
+#[derive(Debug, Default)]
+pub struct SendTrace {
+    records: Vec<SendRecord>,
+}
+
+impl SendTrace {
+    /// Records the start of a send, returning its index for `end`.
+    /// Sends that begin before an earlier send ends are nested within it.
+    pub fn begin(
+        &mut self,
+        from: Address,
+        to: Address,
+        method: MethodNum,
+        value: TokenAmount,
+    ) -> usize {
+        let depth = self.records.iter().filter(|r| r.result.is_none()).count();
+        self.records.push(SendRecord { from, to, method, value, depth, result: None });
+        self.records.len() - 1
+    }
+
+    /// Records the outcome of a send.
+    pub fn end(&mut self, index: usize, result: Result<ExitCode, ErrorNumber>) {
+        self.records[index].result = Some(result);
+    }
+
+    /// Discards the trace, at the start of a new message.
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+
+    pub fn records(&self) -> &[SendRecord] {
+        &self.records
+    }
+}
+
+impl fmt::Display for SendTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for record in &self.records {
+            writeln!(f, "{}", record)?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for SendTrace {
+    fn drop(&mut self) {
+        if std::thread::panicking() && !self.records.is_empty() {
+            eprintln!("sends made by the last message:\n{}", self);
+        }
+    }
+}
+
+/// Returns the name of a method number, for the universal methods and the FRC-42 methods
+/// exported or called by the builtin actors.
+/// Other method numbers are specific to the receiving actor, so can't be named here.
+pub fn method_name(method: MethodNum) -> Option<&'static str> {
+    match method {
+        METHOD_SEND => Some("Send"),
+        METHOD_CONSTRUCTOR => Some("Constructor"),
+        _ => FRC42_METHODS.iter().find(|(number, _)| *number == method).map(|(_, name)| *name),
+    }
+}
+
+macro_rules! frc42_methods {
+    ($($name:tt),* $(,)?) => {
+        &[$((frc42_dispatch::method_hash!($name), $name)),*]
+    };
+}
+
+const FRC42_METHODS: &[(MethodNum, &str)] = frc42_methods![
+    "AddBalance",
+    "AddVerifiedClient",
+    "Allowance",
+    "AllowanceTerms",
+    "ApproveActivationExtension",
+    "AuthenticateMessage",
+    "AuthenticateMessages",
+    "AuthorizeProver",
+    "Balance",
+    "Burn",
+    "BurnFrom",
+    "CancelOwnerChange",
+    "CancelPublishedDeal",
+    "ChangeBeneficiary",
+    "ChangeMultiaddrs",
+    "ChangeOwnerAddress",
+    "ChangePeerID",
+    "ChangeWorkerAddress",
+    "Collect",
+    "ConfirmChangeWorkerAddress",
+    "CreateDeterministic",
+    "CreateMiner",
+    "CreateMinerDelegated",
+    "DecreaseAllowance",
+    "Destroy",
+    "EstimateTerminationFee",
+    "Exec",
+    "ExtendClaimTerms",
+    "ExtendClaimTermsBatch",
+    "GetActorInfo",
+    "GetAvailableBalance",
+    "GetBalance",
+    "GetBeneficiary",
+    "GetClaims",
+    "GetClientDealStats",
+    "GetDealActivation",
+    "GetDealClient",
+    "GetDealClientCollateral",
+    "GetDealDataCommitment",
+    "GetDealLabel",
+    "GetDealProvider",
+    "GetDealProviderCollateral",
+    "GetDealSector",
+    "GetDealTerm",
+    "GetDealTotalPrice",
+    "GetDealVerified",
+    "GetMinerEssentials",
+    "GetMultiaddrs",
+    "GetOwner",
+    "GetPeerID",
+    "GetPendingOwnerChange",
+    "GetPledgeRatios",
+    "GetProverAuthorizations",
+    "GetSectorSize",
+    "GetVestingFunds",
+    "GetWithdrawableBalance",
+    "Granularity",
+    "IncreaseAllowance",
+    "IncreaseAllowanceWithTerms",
+    "InvokeEVM",
+    "IsControllingAddress",
+    "ListAllocationsForClient",
+    "ListClaimsForProvider",
+    "ListDeals",
+    "ListPendingTransactions",
+    "ListSectors",
+    "LockedBalance",
+    "MarketNotifyDeal",
+    "MinerConsensusCount",
+    "MinerCount",
+    "MinerRawPower",
+    "Mint",
+    "MintLocked",
+    "Name",
+    "NetworkRawPower",
+    "NetworkStats",
+    "PreviewSkippedFaults",
+    "ProjectedReward",
+    "PruneExpired",
+    "PublishStorageDeals",
+    "Receive",
+    "RemoveExpiredAllocations",
+    "RemoveExpiredAllocationsWithReceipt",
+    "RemoveExpiredClaims",
+    "RepayDebt",
+    "RequestActivationExtension",
+    "RetargetAllocation",
+    "RevokeAllowance",
+    "RevokeProver",
+    "SectorContentChanged",
+    "Settle",
+    "SettleAndCollect",
+    "SettleDealPayments",
+    "SweepExpiredAllocations",
+    "Symbol",
+    "TotalSupply",
+    "Transfer",
+    "TransferFrom",
+    "UpdateChannelState",
+    "UpdateChannelStateBatch",
+    "WithdrawBalance",
+];
+
+#[cfg(test)]
+mod test {
+    use num_traits::Zero;
+
+    use super::*;
+
+    #[test]
+    fn names_methods() {
+        assert_eq!(Some("Send"), method_name(METHOD_SEND));
+        assert_eq!(Some("Constructor"), method_name(METHOD_CONSTRUCTOR));
+        assert_eq!(Some("Receive"), method_name(frc42_dispatch::method_hash!("Receive")));
+        assert_eq!(None, method_name(2));
+    }
+
+    #[test]
+    fn nests_sends() {
+        let mut trace = SendTrace::default();
+        let outer = trace.begin(Address::new_id(100), Address::new_id(4), 2, TokenAmount::zero());
+        let inner = trace.begin(Address::new_id(4), Address::new_id(1), 2, TokenAmount::zero());
+        trace.end(inner, Ok(ExitCode::OK));
+        trace.end(outer, Err(ErrorNumber::InsufficientFunds));
+        let after = trace.begin(Address::new_id(100), Address::new_id(5), 0, TokenAmount::zero());
+        trace.end(after, Ok(ExitCode::USR_FORBIDDEN));
+
+        let depths: Vec<_> = trace.records().iter().map(|r| r.depth).collect();
+        assert_eq!(vec![0, 1, 0], depths);
+        assert!(trace.to_string().lines().nth(1).unwrap().starts_with("  f04 -> f01 method 2"));
+        trace.clear();
+        assert!(trace.records().is_empty());
+    }
+}
@@ -34,6 +34,15 @@ impl MemoryBlockstore {
     pub fn new() -> Self {
         Self { blocks: Default::default(), stats: Default::default() }
     }
+
+    /// Returns a copy of every block in the store, ordered by CID.
+    /// This isn't counted in the stats.
+    pub fn blocks(&self) -> Vec<(Cid, Vec<u8>)> {
+        let mut blocks: Vec<_> =
+            self.blocks.borrow().iter().map(|(k, v)| (*k, v.clone())).collect();
+        blocks.sort_by(|(a, _), (b, _)| a.cmp(b));
+        blocks
+    }
 }
 
 impl Blockstore for MemoryBlockstore {
@@ -19,7 +19,7 @@ use fvm_shared::commcid::{FIL_COMMITMENT_SEALED, FIL_COMMITMENT_UNSEALED};
 use fvm_shared::consensus::ConsensusFault;
 use fvm_shared::crypto::hash::SupportedHashes;
 use fvm_shared::crypto::signature::{
-    Signature, SECP_PUB_LEN, SECP_SIG_LEN, SECP_SIG_MESSAGE_HASH_SIZE,
+    Signature, BLS_PUB_LEN, BLS_SIG_LEN, SECP_PUB_LEN, SECP_SIG_LEN, SECP_SIG_MESSAGE_HASH_SIZE,
 };
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::{ErrorNumber, ExitCode};
@@ -36,9 +36,13 @@ use cid::multihash::MultihashDigest;
 use multihash::derive::Multihash;
 
 use crate::runtime::builtins::Type;
+#[cfg(feature = "metrics")]
+use crate::runtime::metrics::Counters;
+#[cfg(feature = "send-trace")]
+use crate::runtime::send_trace::SendTrace;
 use crate::runtime::{
     ActorCode, DomainSeparationTag, MessageInfo, Policy, Primitives, Runtime, RuntimePolicy,
-    EMPTY_ARR_CID,
+    SignatureVerifyInfo, EMPTY_ARR_CID,
 };
 use crate::{actor_error, ActorError, SendError};
 use libsecp256k1::{recover, Message, RecoveryId, Signature as EcsdaSignature};
@@ -173,6 +177,14 @@ pub struct MockRuntime {
     // Expectations
     pub expectations: RefCell<Expectations>,
 
+    // Sends made by the current call, printed if a test fails.
+    #[cfg(feature = "send-trace")]
+    pub send_trace: RefCell<SendTrace>,
+
+    // Counters incremented by calls that succeeded.
+    #[cfg(feature = "metrics")]
+    pub counters: RefCell<Counters>,
+
     // policy
     pub policy: Policy,
 
@@ -195,6 +207,8 @@ pub struct Expectations {
     pub expect_create_actor: Option<ExpectCreateActor>,
     pub expect_delete_actor: bool,
     pub expect_verify_sigs: VecDeque<ExpectedVerifySig>,
+    pub expect_verify_bls_aggregate: Option<ExpectVerifyBlsAggregate>,
+    pub expect_verify_signatures_batch: Option<ExpectVerifySignaturesBatch>,
     pub expect_verify_post: Option<ExpectVerifyPoSt>,
     pub expect_compute_unsealed_sector_cid: VecDeque<ExpectComputeUnsealedSectorCid>,
     pub expect_verify_consensus_fault: Option<ExpectVerifyConsensusFault>,
@@ -260,6 +274,16 @@ impl Expectations {
             "expect_verify_sigs: {:?}, not received",
             this.expect_verify_sigs
         );
+        assert!(
+            this.expect_verify_bls_aggregate.is_none(),
+            "expect_verify_bls_aggregate {:?}, not received",
+            this.expect_verify_bls_aggregate
+        );
+        assert!(
+            this.expect_verify_signatures_batch.is_none(),
+            "expect_verify_signatures_batch {:?}, not received",
+            this.expect_verify_signatures_batch
+        );
         assert!(
             this.expect_verify_post.is_none(),
             "expect_verify_post {:?}, not received",
@@ -349,6 +373,10 @@ impl MockRuntime {
             store: Rc::new(Default::default()),
             in_transaction: Default::default(),
             expectations: Default::default(),
+            #[cfg(feature = "send-trace")]
+            send_trace: Default::default(),
+            #[cfg(feature = "metrics")]
+            counters: Default::default(),
             policy: Default::default(),
             circulating_supply: Default::default(),
             gas_limit: 10_000_000_000u64,
@@ -390,6 +418,20 @@ pub struct ExpectedVerifySig {
     pub result: Result<(), anyhow::Error>,
 }
 
+#[derive(Debug)]
+pub struct ExpectVerifyBlsAggregate {
+    pub sig: [u8; BLS_SIG_LEN],
+    pub pub_keys: Vec<[u8; BLS_PUB_LEN]>,
+    pub plaintexts: Vec<Vec<u8>>,
+    pub result: Result<(), anyhow::Error>,
+}
+
+#[derive(Debug)]
+pub struct ExpectVerifySignaturesBatch {
+    pub batch: Vec<SignatureVerifyInfo>,
+    pub result: Vec<bool>,
+}
+
 #[derive(Clone, Debug)]
 pub struct ExpectVerifyPoSt {
     post: WindowPoStVerifyInfo,
@@ -550,11 +592,17 @@ impl MockRuntime {
         params: Option<IpldBlock>,
     ) -> Result<Option<IpldBlock>, ActorError> {
         self.in_call.replace(true);
+        #[cfg(feature = "send-trace")]
+        self.send_trace.borrow_mut().clear();
         let prev_state = *self.state.borrow();
+        #[cfg(feature = "metrics")]
+        let prev_counters = self.counters.borrow().clone();
         let res = A::invoke_method(self, method_num, params);
 
         if res.is_err() {
             self.state.replace(prev_state);
+            #[cfg(feature = "metrics")]
+            self.counters.replace(prev_counters);
         }
         self.in_call.replace(false);
         res
@@ -583,6 +631,22 @@ impl MockRuntime {
         self.expectations.borrow_mut().expect_verify_sigs.push_back(exp);
     }
 
+    #[allow(dead_code)]
+    pub fn expect_verify_bls_aggregate(&self, exp: ExpectVerifyBlsAggregate) {
+        self.expectations.borrow_mut().expect_verify_bls_aggregate = Some(exp);
+    }
+
+    #[allow(dead_code)]
+    pub fn expect_verify_signatures_batch(
+        &self,
+        batch: Vec<SignatureVerifyInfo>,
+        result: Vec<bool>,
+    ) {
+        assert_eq!(batch.len(), result.len(), "batch and result lengths must match");
+        self.expectations.borrow_mut().expect_verify_signatures_batch =
+            Some(ExpectVerifySignaturesBatch { batch, result });
+    }
+
     #[allow(dead_code)]
     pub fn expect_verify_consensus_fault(
         &self,
@@ -1126,6 +1190,9 @@ impl Runtime for MockRuntime {
         if *self.in_transaction.borrow() {
             return Ok(Response { exit_code: ExitCode::USR_ASSERTION_FAILED, return_data: None });
         }
+        #[cfg(feature = "send-trace")]
+        let trace_index =
+            self.send_trace.borrow_mut().begin(self.receiver, *to, method, value.clone());
 
         assert!(
             !self.expectations.borrow_mut().expect_sends.is_empty(),
@@ -1165,19 +1232,25 @@ impl Runtime for MockRuntime {
             to, method, expected_msg.send_flags, send_flags
         );
 
-        if let Some(e) = expected_msg.send_error {
-            return Err(SendError(e));
-        }
-
-        {
+        let res = if let Some(e) = expected_msg.send_error {
+            Err(SendError(e))
+        } else {
             let mut balance = self.balance.borrow_mut();
             if value > *balance {
-                return Err(SendError(ErrorNumber::InsufficientFunds));
+                Err(SendError(ErrorNumber::InsufficientFunds))
+            } else {
+                *balance -= value;
+                Ok(Response {
+                    exit_code: expected_msg.exit_code,
+                    return_data: expected_msg.send_return,
+                })
             }
-            *balance -= value;
-        }
-
-        Ok(Response { exit_code: expected_msg.exit_code, return_data: expected_msg.send_return })
+        };
+        #[cfg(feature = "send-trace")]
+        self.send_trace
+            .borrow_mut()
+            .end(trace_index, res.as_ref().map(|r| r.exit_code).map_err(|e| e.0));
+        res
     }
 
     fn new_actor_address(&self) -> Result<Address, ActorError> {
@@ -1299,6 +1372,11 @@ impl Runtime for MockRuntime {
     fn read_only(&self) -> bool {
         false
     }
+
+    #[cfg(feature = "metrics")]
+    fn increment_counter(&self, name: &'static str, by: u64) {
+        self.counters.borrow_mut().increment(name, by);
+    }
 }
 
 impl Primitives for MockRuntime {
@@ -1343,6 +1421,37 @@ impl Primitives for MockRuntime {
         Ok(())
     }
 
+    fn verify_bls_aggregate(
+        &self,
+        aggregate_sig: &[u8; BLS_SIG_LEN],
+        pub_keys: &[[u8; BLS_PUB_LEN]],
+        plaintexts: &[&[u8]],
+    ) -> anyhow::Result<()> {
+        let exp = self
+            .expectations
+            .borrow_mut()
+            .expect_verify_bls_aggregate
+            .take()
+            .expect("Unexpected syscall to verify_bls_aggregate");
+
+        assert_eq!(exp.sig, *aggregate_sig, "Unexpected aggregate signature");
+        assert_eq!(exp.pub_keys, pub_keys, "Unexpected aggregate signature public keys");
+        assert_eq!(exp.plaintexts, plaintexts, "Unexpected aggregate signature plaintexts");
+        exp.result
+    }
+
+    fn verify_signatures_batch(&self, batch: &[SignatureVerifyInfo]) -> anyhow::Result<Vec<bool>> {
+        let exp = self
+            .expectations
+            .borrow_mut()
+            .expect_verify_signatures_batch
+            .take()
+            .expect("Unexpected syscall to verify_signatures_batch");
+
+        assert_eq!(exp.batch, batch, "Unexpected signature batch");
+        Ok(exp.result)
+    }
+
     fn hash_blake2b(&self, data: &[u8]) -> [u8; 32] {
         let (digest, _) = (*self.hash_func)(SupportedHashes::Blake2b256, data);
         let mut ret = [0u8; 32];
@@ -1631,6 +1740,9 @@ pub struct FakePrimitives {
     pub verify_aggregate_seals:
         RefCell<Option<fn(&AggregateSealVerifyProofAndInfos) -> Result<(), Error>>>,
     pub verify_signature: RefCell<Option<fn(&Signature, &Address, &[u8]) -> Result<(), Error>>>,
+    pub verify_bls_aggregate: RefCell<
+        Option<fn(&[u8; BLS_SIG_LEN], &[[u8; BLS_PUB_LEN]], &[&[u8]]) -> Result<(), Error>>,
+    >,
     pub verify_replica_update: RefCell<Option<fn(&ReplicaUpdateInfo) -> Result<(), Error>>>,
 }
 
@@ -1709,6 +1821,19 @@ impl Primitives for FakePrimitives {
         Ok(())
     }
 
+    fn verify_bls_aggregate(
+        &self,
+        aggregate_sig: &[u8; BLS_SIG_LEN],
+        pub_keys: &[[u8; BLS_PUB_LEN]],
+        plaintexts: &[&[u8]],
+    ) -> Result<(), Error> {
+        if let Some(override_fn) = *self.verify_bls_aggregate.borrow() {
+            override_fn(aggregate_sig, pub_keys, plaintexts)
+        } else {
+            Ok(())
+        }
+    }
+
     fn recover_secp_public_key(
         &self,
         hash: &[u8; SECP_SIG_MESSAGE_HASH_SIZE],
@@ -1833,6 +1958,13 @@ impl MockPrimitives for FakePrimitives {
         self.verify_signature.replace(Some(f));
     }
 
+    fn override_verify_bls_aggregate(
+        &self,
+        f: fn(&[u8; BLS_SIG_LEN], &[[u8; BLS_PUB_LEN]], &[&[u8]]) -> std::result::Result<(), Error>,
+    ) {
+        self.verify_bls_aggregate.replace(Some(f));
+    }
+
     fn override_verify_replica_update(
         &self,
         f: fn(&ReplicaUpdateInfo) -> std::result::Result<(), Error>,
@@ -164,21 +164,103 @@ where
                     K::from_bytes(k).context_code(ExitCode::USR_ILLEGAL_STATE, "invalid key")?;
                 f(key, v).map_err(|e| anyhow!(e))
             })
-            .map_err(|hamt_err| match hamt_err {
-                hamt::Error::Dynamic(e) => match e.downcast::<ActorError>() {
-                    Ok(ae) => ae,
-                    Err(e) => ActorError::illegal_state(format!(
-                        "error in callback traversing HAMT {}: {}",
-                        self.name, e
-                    )),
-                },
-                e => {
-                    ActorError::illegal_state(format!("error traversing HAMT {}: {}", self.name, e))
-                }
+            .map_err(|hamt_err| self.traversal_error(hamt_err))
+    }
+
+    /// Iterates over at most `max` key-value pairs in the map, starting at a key
+    /// (or at the beginning of the map if no key is given).
+    /// If the start key is absent (e.g. because the entry at a listing's cursor has since been
+    /// removed), iteration resumes from the entry following the position the key would occupy.
+    /// Returns the number of entries traversed, and the key at which to resume iteration
+    /// if any entries remain.
+    pub fn for_each_ranged<F>(
+        &mut self,
+        start_at: Option<&K>,
+        max: Option<usize>,
+        mut f: F,
+    ) -> Result<(usize, Option<K>), ActorError>
+    where
+        V: Clone + PartialEq,
+        F: FnMut(K, &V) -> Result<(), ActorError>,
+    {
+        let start = match start_at {
+            Some(k) => Some(hamt::BytesKey(
+                k.to_bytes().context_code(ExitCode::USR_ASSERTION_FAILED, "invalid key")?,
+            )),
+            None => None,
+        };
+        let (traversed, next) =
+            for_each_ranged_resuming(&mut self.hamt, start.as_ref(), max, |k, v| {
+                let key =
+                    K::from_bytes(k).context_code(ExitCode::USR_ILLEGAL_STATE, "invalid key")?;
+                f(key, v).map_err(|e| anyhow!(e))
             })
+            .map_err(|hamt_err| self.traversal_error(hamt_err))?;
+        let next = next
+            .map(|k| K::from_bytes(&k))
+            .transpose()
+            .context_code(ExitCode::USR_ILLEGAL_STATE, "invalid key")?;
+        Ok((traversed, next))
+    }
+
+    fn traversal_error(&self, hamt_err: hamt::Error) -> ActorError {
+        match hamt_err {
+            hamt::Error::Dynamic(e) => match e.downcast::<ActorError>() {
+                Ok(ae) => ae,
+                Err(e) => ActorError::illegal_state(format!(
+                    "error in callback traversing HAMT {}: {}",
+                    self.name, e
+                )),
+            },
+            e => ActorError::illegal_state(format!("error traversing HAMT {}: {}", self.name, e)),
+        }
     }
 }
 
+/// Iterates over at most `max` entries of a HAMT from a start key, like `Hamt::for_each_ranged`,
+/// but if the start key is absent (e.g. because the entry at a listing's cursor has since been
+/// removed) resumes from the entry that follows the position the key would occupy.
+/// A placeholder is inserted at the start key for the traversal and removed after it, leaving
+/// the HAMT as it was.
+pub(crate) fn for_each_ranged_resuming<BS, V, F>(
+    hamt: &mut hamt::Hamt<BS, V, hamt::BytesKey, Hasher>,
+    start_at: Option<&hamt::BytesKey>,
+    max: Option<usize>,
+    mut f: F,
+) -> Result<(usize, Option<hamt::BytesKey>), hamt::Error>
+where
+    BS: Blockstore,
+    V: DeserializeOwned + Serialize + Clone + PartialEq,
+    F: FnMut(&hamt::BytesKey, &V) -> anyhow::Result<()>,
+{
+    let start = match start_at {
+        Some(start) if !hamt.contains_key(start)? => start,
+        _ => return hamt.for_each_ranged(start_at, max, f),
+    };
+    // Any value will do as the placeholder, as it isn't visited.
+    let mut placeholder = None;
+    hamt.for_each_ranged(None::<&hamt::BytesKey>, Some(1), |_, v| {
+        placeholder = Some(v.clone());
+        Ok(())
+    })?;
+    let placeholder = match placeholder {
+        Some(v) => v,
+        None => return Ok((0, None)),
+    };
+
+    hamt.set(start.clone(), placeholder)?;
+    let result = hamt.for_each_ranged(Some(start), max.map(|m| m + 1), |k, v| {
+        if k == start {
+            Ok(())
+        } else {
+            f(k, v)
+        }
+    });
+    hamt.delete(start)?;
+    let (traversed, next) = result?;
+    Ok((traversed - 1, next))
+}
+
 impl MapKey for Vec<u8> {
     fn from_bytes(b: &[u8]) -> Result<Self, String> {
         Ok(b.to_vec())
@@ -266,4 +348,69 @@ mod tests {
         assert!(res.is_err());
         assert_eq!(res.unwrap_err(), ActorError::forbidden("test".to_string()));
     }
+
+    #[test]
+    fn for_each_ranged_resumes() {
+        let bs = MemoryBlockstore::new();
+        let mut m = Map2::<_, u64, String>::empty(bs, DEFAULT_HAMT_CONFIG, "empty");
+        for i in 0..10u64 {
+            m.set(&i, i.to_string()).unwrap();
+        }
+
+        let mut seen = Vec::new();
+        let mut cursor = None;
+        loop {
+            let (traversed, next) = m
+                .for_each_ranged(cursor.as_ref(), Some(3), |k, v| {
+                    assert_eq!(&k.to_string(), v);
+                    seen.push(k);
+                    Ok(())
+                })
+                .unwrap();
+            assert!(traversed <= 3);
+            match next {
+                Some(k) => cursor = Some(k),
+                None => break,
+            }
+        }
+        seen.sort();
+        assert_eq!((0..10u64).collect::<Vec<_>>(), seen);
+    }
+
+    #[test]
+    fn for_each_ranged_resumes_after_removed_start() {
+        let bs = MemoryBlockstore::new();
+        let mut m = Map2::<_, u64, String>::empty(bs, DEFAULT_HAMT_CONFIG, "empty");
+        for i in 0..10u64 {
+            m.set(&i, i.to_string()).unwrap();
+        }
+        let root = m.flush().unwrap();
+
+        let mut seen = Vec::new();
+        let (_, cursor) = m
+            .for_each_ranged(None, Some(3), |k, _| {
+                seen.push(k);
+                Ok(())
+            })
+            .unwrap();
+        let cursor = cursor.unwrap();
+
+        // The entry at the cursor is removed before the next page is listed.
+        m.delete(&cursor).unwrap();
+        let (traversed, next) = m
+            .for_each_ranged(Some(&cursor), None, |k, _| {
+                seen.push(k);
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(6, traversed);
+        assert_eq!(None, next);
+        seen.sort();
+        let expected: Vec<u64> = (0..10u64).filter(|k| *k != cursor).collect();
+        assert_eq!(expected, seen);
+
+        // Listing leaves the map unchanged.
+        m.set(&cursor, cursor.to_string()).unwrap();
+        assert_eq!(root, m.flush().unwrap());
+    }
 }
@@ -1,3 +1,4 @@
+use super::map::for_each_ranged_resuming;
 use crate::{make_empty_map, make_map_with_root_and_bitwidth, Keyer, Map};
 use cid::Cid;
 use fvm_ipld_blockstore::Blockstore;
@@ -65,9 +66,13 @@ where
     // 1. ensure inner map is loaded into cache
     // 2. return (inner map is empty, inner map)
     fn load_inner_map(&mut self, k: K1) -> Result<(bool, &mut Map<'a, BS, V>), Error> {
+        self.load_inner_map_by_key(k.key())
+    }
+
+    fn load_inner_map_by_key(&mut self, k: BytesKey) -> Result<(bool, &mut Map<'a, BS, V>), Error> {
         let in_map_thunk = || -> Result<(bool, Map<BS, V>), Error> {
             // lazy to avoid ipld operations in case of cache hit
-            match self.outer.get(&k.key())? {
+            match self.outer.get(&k)? {
                 // flush semantics guarantee all written inner maps are non empty
                 Some(root) => Ok((
                     false,
@@ -80,7 +85,7 @@ where
                 None => Ok((true, make_empty_map(*self.outer.store(), self.inner_bitwidth))),
             }
         };
-        let raw_k = k.key().0;
+        let raw_k = k.0.clone();
         match self.cache.entry(raw_k) {
             Occupied(entry) => {
                 let in_map = entry.into_mut();
@@ -122,6 +127,88 @@ where
         in_map.for_each(f)
     }
 
+    // Runs a function over the values for one outer key, starting at an inner key (or the
+    // beginning) and visiting at most `max` entries.
+    // If the start key isn't present, starts from the entry following where it would be.
+    // Returns the number of entries visited and the inner key at which to resume, if any remain.
+    pub fn for_each_in_ranged<F>(
+        &mut self,
+        outside_k: K1,
+        start_at: Option<K2>,
+        max: Option<usize>,
+        f: F,
+    ) -> Result<(usize, Option<BytesKey>), Error>
+    where
+        F: FnMut(&BytesKey, &V) -> anyhow::Result<()>,
+    {
+        let (is_empty, in_map) = self.load_inner_map(outside_k)?;
+        if is_empty {
+            return Ok((0, None));
+        }
+        let start = start_at.map(|k| k.key());
+        for_each_ranged_resuming(in_map, start.as_ref(), max, f)
+    }
+
+    // Runs a function over the values for every outer key, starting at an outer key and an
+    // inner key within it (or the beginning of either) and visiting at most `max` values.
+    // Outer keys are visited in the order stored, so values cached since the last flush under
+    // an outer key that wasn't stored are skipped.
+    // If the start keys aren't present, starts from the entry following where they would be.
+    // Returns the number of values visited and the outer and inner keys at which to resume,
+    // if any remain.
+    pub fn for_each_ranged<F>(
+        &mut self,
+        start_at: Option<(K1, Option<K2>)>,
+        max: usize,
+        mut f: F,
+    ) -> Result<(usize, Option<(BytesKey, Option<BytesKey>)>), Error>
+    where
+        F: FnMut(&BytesKey, &BytesKey, &V) -> anyhow::Result<()>,
+    {
+        let (mut outer_start, mut inner_start) = match start_at {
+            Some((k1, k2)) => (Some(k1.key()), k2.map(|k| k.key())),
+            None => (None, None),
+        };
+        let mut visited = 0;
+        while visited < max {
+            let mut outer_k = None;
+            let (_, next_outer) = for_each_ranged_resuming(
+                &mut self.outer,
+                outer_start.as_ref(),
+                Some(1),
+                |k, _| {
+                    outer_k = Some(k.clone());
+                    Ok(())
+                },
+            )?;
+            let outer_k = match outer_k {
+                Some(k) => k,
+                None => return Ok((visited, None)),
+            };
+            // The inner start key only applies to the outer key it was given with.
+            if outer_start.as_ref() != Some(&outer_k) {
+                inner_start = None;
+            }
+            let in_map = self.load_inner_map_by_key(outer_k.clone())?.1;
+            let (n, next_inner) = for_each_ranged_resuming(
+                in_map,
+                inner_start.as_ref(),
+                Some(max - visited),
+                |k, v| f(&outer_k, k, v),
+            )?;
+            visited += n;
+            if next_inner.is_some() {
+                return Ok((visited, Some((outer_k, next_inner))));
+            }
+            outer_start = match next_outer {
+                Some(k) => Some(k),
+                None => return Ok((visited, None)),
+            };
+            inner_start = None;
+        }
+        Ok((visited, outer_start.map(|k| (k, None))))
+    }
+
     // Puts a key value pair in the MapMap, overwriting any existing value.
     // Returns the previous value, if any.
     pub fn put(&mut self, outside_k: K1, inside_k: K2, value: V) -> Result<Option<V>, Error> {
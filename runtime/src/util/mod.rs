@@ -8,6 +8,8 @@ pub use self::map::*;
 pub use self::mapmap::MapMap;
 pub use self::message_accumulator::MessageAccumulator;
 pub use self::multimap::*;
+pub use self::penalty::*;
+pub use self::sector_set::*;
 pub use self::set::Set;
 pub use self::set_multimap::SetMultimap;
 pub use self::set_multimap::SetMultimapConfig;
@@ -20,5 +22,8 @@ mod map;
 mod mapmap;
 mod message_accumulator;
 mod multimap;
+mod penalty;
+pub mod query;
+mod sector_set;
 mod set;
 mod set_multimap;
@@ -0,0 +1,73 @@
+// Copyright 2019-2022 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::ActorID;
+
+use crate::runtime::Runtime;
+use crate::{ActorError, EventBuilder};
+
+/// The type of event emitted whenever an actor applies a penalty.
+pub const PENALTY_ASSESSED_EVENT: &str = "penalty-assessed";
+
+/// The reasons for which built-in actors apply penalties.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PenaltyCategory {
+    /// A miner's fee for sectors remaining faulty at a deadline.
+    ContinuedFault,
+    /// A miner's fee for terminating sectors before their expiration.
+    EarlyTermination,
+    /// A miner's penalty, deducted from its block reward, for including invalid messages.
+    BlockReward,
+    /// A miner's penalty for a reported consensus fault.
+    ConsensusFault,
+    /// A miner's penalty for a successfully disputed Window PoSt.
+    InvalidWindowPost,
+    /// A miner's pre-commit deposit forfeited for a sector not proven in time.
+    PreCommitExpiry,
+    /// A provider's collateral slashed for a deal whose sector was terminated.
+    DealTermination,
+    /// A provider's collateral slashed for a deal not activated by its start epoch.
+    DealActivationTimeout,
+}
+
+impl PenaltyCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PenaltyCategory::ContinuedFault => "continued-fault",
+            PenaltyCategory::EarlyTermination => "early-termination",
+            PenaltyCategory::BlockReward => "block-reward",
+            PenaltyCategory::ConsensusFault => "consensus-fault",
+            PenaltyCategory::InvalidWindowPost => "invalid-window-post",
+            PenaltyCategory::PreCommitExpiry => "pre-commit-expiry",
+            PenaltyCategory::DealTermination => "deal-termination",
+            PenaltyCategory::DealActivationTimeout => "deal-activation-timeout",
+        }
+    }
+}
+
+/// Emits a `penalty-assessed` event, the format shared by every actor that applies penalties.
+///
+/// The `amount` is the penalty assessed against the `subject`. Of that, `burned` was paid
+/// immediately to the burnt funds actor and `withheld` was left owing, to be recovered from
+/// the subject's future funds. Any remainder was paid out to a reporter.
+pub fn emit_penalty_assessed(
+    rt: &impl Runtime,
+    subject: ActorID,
+    category: PenaltyCategory,
+    amount: &TokenAmount,
+    burned: &TokenAmount,
+    withheld: &TokenAmount,
+) -> Result<(), ActorError> {
+    rt.emit_event(
+        &EventBuilder::new()
+            .typ(PENALTY_ASSESSED_EVENT)
+            .field("actor", &rt.message().receiver().id().unwrap())
+            .field_indexed("subject", &subject)
+            .field_indexed("category", category.as_str())
+            .field("amount", amount)
+            .field("burned", burned)
+            .field("withheld", withheld)
+            .build()?,
+    )
+}
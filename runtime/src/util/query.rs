@@ -0,0 +1,171 @@
+//! Standard envelopes for exported methods that list a collection a page at a time.
+//!
+//! A listing traverses at most `limit` entries in storage order, starting from a cursor,
+//! and returns the entries matching the request's filter along with the cursor at which to resume.
+//! A cursor whose entry has since been removed resumes from the entry that followed it.
+//! Since the limit bounds the entries traversed rather than those returned, a page may hold fewer
+//! than `limit` entries while more remain to be listed.
+
+use fvm_ipld_encoding::tuple::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+use crate::{actor_error, ActorError};
+
+/// The maximum number of entries a single request may traverse.
+pub const MAX_LIST_LIMIT: u64 = 1000;
+
+/// Comparison of an indexed field against a filter's value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+pub enum FilterOp {
+    Eq = 0,
+    Ne = 1,
+    Lt = 2,
+    Le = 3,
+    Gt = 4,
+    Ge = 5,
+}
+
+/// A condition on one indexed field of the listed entries.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct FieldFilter {
+    pub field: String,
+    pub op: FilterOp,
+    pub value: i64,
+}
+
+impl FieldFilter {
+    pub fn new(field: &str, op: FilterOp, value: i64) -> Self {
+        Self { field: field.to_string(), op, value }
+    }
+}
+
+/// A request for one page of a listing.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ListRequest<C: Serialize + DeserializeOwned> {
+    /// Key at which to start the listing, as returned by a previous page.
+    /// None means start from the beginning.
+    pub cursor: Option<C>,
+    /// Maximum number of entries to traverse.
+    pub limit: u64,
+    /// Conditions that returned entries must all satisfy. Empty matches every entry.
+    pub filter: Vec<FieldFilter>,
+}
+
+impl<C: Serialize + DeserializeOwned> ListRequest<C> {
+    /// A request for the first page of a listing, without a filter.
+    pub fn first(limit: u64) -> Self {
+        Self { cursor: None, limit, filter: vec![] }
+    }
+
+    /// Checks the limit is within bounds and the filter only refers to indexed fields of `T`.
+    pub fn validate<T: Indexed>(&self) -> Result<(), ActorError> {
+        if self.limit == 0 || self.limit > MAX_LIST_LIMIT {
+            return Err(actor_error!(
+                illegal_argument,
+                "page limit {} must be between 1 and {}",
+                self.limit,
+                MAX_LIST_LIMIT
+            ));
+        }
+        for f in &self.filter {
+            if !T::INDEXED_FIELDS.contains(&f.field.as_str()) {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "cannot filter on {}, indexed fields are {:?}",
+                    f.field,
+                    T::INDEXED_FIELDS
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns whether an entry satisfies every condition of the filter.
+    pub fn matches<T: Indexed>(&self, entry: &T) -> bool {
+        self.filter.iter().all(|f| match entry.field(&f.field) {
+            Some(v) => {
+                let target = i128::from(f.value);
+                match f.op {
+                    FilterOp::Eq => v == target,
+                    FilterOp::Ne => v != target,
+                    FilterOp::Lt => v < target,
+                    FilterOp::Le => v <= target,
+                    FilterOp::Gt => v > target,
+                    FilterOp::Ge => v >= target,
+                }
+            }
+            None => false,
+        })
+    }
+}
+
+/// One page of a listing.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ListPage<T: Serialize + DeserializeOwned, C: Serialize + DeserializeOwned> {
+    pub entries: Vec<T>,
+    /// Cursor from which to request the next page, or None if the listing is complete.
+    pub next_cursor: Option<C>,
+}
+
+/// An entry of a listing with fields that may be filtered on.
+pub trait Indexed {
+    /// Names of the fields that may be filtered on.
+    const INDEXED_FIELDS: &'static [&'static str];
+
+    /// Returns the value of an indexed field, or None if the field isn't indexed.
+    /// Boolean fields are 0 or 1.
+    fn field(&self, name: &str) -> Option<i128>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Entry {
+        epoch: i64,
+        verified: bool,
+    }
+
+    impl Indexed for Entry {
+        const INDEXED_FIELDS: &'static [&'static str] = &["epoch", "verified"];
+
+        fn field(&self, name: &str) -> Option<i128> {
+            match name {
+                "epoch" => Some(self.epoch.into()),
+                "verified" => Some(self.verified.into()),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn validates_limit_and_fields() {
+        assert!(ListRequest::<u64>::first(1).validate::<Entry>().is_ok());
+        assert!(ListRequest::<u64>::first(MAX_LIST_LIMIT).validate::<Entry>().is_ok());
+        assert!(ListRequest::<u64>::first(0).validate::<Entry>().is_err());
+        assert!(ListRequest::<u64>::first(MAX_LIST_LIMIT + 1).validate::<Entry>().is_err());
+
+        let mut req = ListRequest::<u64>::first(10);
+        req.filter.push(FieldFilter::new("epoch", FilterOp::Gt, 5));
+        assert!(req.validate::<Entry>().is_ok());
+        req.filter.push(FieldFilter::new("size", FilterOp::Eq, 5));
+        assert!(req.validate::<Entry>().is_err());
+    }
+
+    #[test]
+    fn matches_all_conditions() {
+        let entry = Entry { epoch: 10, verified: true };
+        let mut req = ListRequest::<u64>::first(10);
+        assert!(req.matches(&entry));
+
+        req.filter.push(FieldFilter::new("epoch", FilterOp::Ge, 10));
+        assert!(req.matches(&entry));
+        req.filter.push(FieldFilter::new("verified", FilterOp::Eq, 1));
+        assert!(req.matches(&entry));
+        req.filter.push(FieldFilter::new("epoch", FilterOp::Lt, 10));
+        assert!(!req.matches(&entry));
+    }
+}
@@ -0,0 +1,142 @@
+// Copyright 2019-2022 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::fmt;
+
+use fvm_ipld_bitfield::iter::Ranges;
+use fvm_ipld_bitfield::BitField;
+use fvm_ipld_encoding::tuple::*;
+use serde::de::{self, SeqAccess, Visitor};
+use serde::{Deserializer, Serializer};
+
+/// A contiguous run of `count` numbers beginning at `start`.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SectorRange {
+    pub start: u64,
+    pub count: u64,
+}
+
+/// A set of sector numbers in one of two encodings:
+/// - an RLE+ bitfield, encoded as CBOR bytes, or
+/// - a list of `[start, count]` ranges, encoded as a CBOR array.
+///
+/// The bitfield encoding is the historical one, so params that only ever carried bitfields
+/// decode unchanged. Ranges are much simpler to construct for callers without an RLE+
+/// encoder (e.g. EVM contracts), and are compact when sectors are largely contiguous.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SectorSet {
+    Bitfield(BitField),
+    Ranges(Vec<SectorRange>),
+}
+
+impl SectorSet {
+    /// Returns the set as a bitfield, validating any ranges.
+    pub fn to_bitfield(&self) -> Result<BitField, String> {
+        match self {
+            SectorSet::Bitfield(bf) => Ok(bf.clone()),
+            SectorSet::Ranges(ranges) => bitfield_from_ranges(ranges),
+        }
+    }
+
+    /// Consumes the set, returning it as a bitfield.
+    pub fn into_bitfield(self) -> Result<BitField, String> {
+        match self {
+            SectorSet::Bitfield(bf) => Ok(bf),
+            SectorSet::Ranges(ranges) => bitfield_from_ranges(&ranges),
+        }
+    }
+}
+
+impl From<BitField> for SectorSet {
+    fn from(bf: BitField) -> Self {
+        SectorSet::Bitfield(bf)
+    }
+}
+
+impl From<Vec<SectorRange>> for SectorSet {
+    fn from(ranges: Vec<SectorRange>) -> Self {
+        SectorSet::Ranges(ranges)
+    }
+}
+
+/// Builds a bitfield from a list of ranges.
+/// Ranges may be given in any order and may overlap, but must be non-empty and must not
+/// extend past `u64::MAX`.
+pub fn bitfield_from_ranges(ranges: &[SectorRange]) -> Result<BitField, String> {
+    let mut sorted = Vec::with_capacity(ranges.len());
+    for r in ranges {
+        if r.count == 0 {
+            return Err(format!("empty range at {}", r.start));
+        }
+        let end = r
+            .start
+            .checked_add(r.count)
+            .ok_or_else(|| format!("range {} + {} overflows", r.start, r.count))?;
+        sorted.push(r.start..end);
+    }
+    sorted.sort_by_key(|r| r.start);
+
+    // Coalesce overlapping and adjacent ranges, as the bitfield requires disjoint, ordered ranges.
+    let mut merged: Vec<std::ops::Range<u64>> = Vec::with_capacity(sorted.len());
+    for r in sorted {
+        match merged.last_mut() {
+            Some(last) if r.start <= last.end => last.end = last.end.max(r.end),
+            _ => merged.push(r),
+        }
+    }
+    Ok(BitField::from_ranges(Ranges::new(merged)))
+}
+
+/// Returns the minimal list of ranges covering exactly the bits set in a bitfield.
+pub fn ranges_from_bitfield(bf: &BitField) -> Vec<SectorRange> {
+    bf.ranges().map(|r| SectorRange { start: r.start, count: r.end - r.start }).collect()
+}
+
+impl serde::Serialize for SectorSet {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            SectorSet::Bitfield(bf) => serde::Serialize::serialize(bf, serializer),
+            SectorSet::Ranges(ranges) => serde::Serialize::serialize(ranges, serializer),
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for SectorSet {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct SectorSetVisitor;
+
+        impl<'de> Visitor<'de> for SectorSetVisitor {
+            type Value = SectorSet;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an RLE+ bitfield or a list of [start, count] ranges")
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                BitField::from_bytes(v).map(SectorSet::Bitfield).map_err(de::Error::custom)
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut ranges = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(1024));
+                while let Some(r) = seq.next_element::<SectorRange>()? {
+                    ranges.push(r);
+                }
+                Ok(SectorSet::Ranges(ranges))
+            }
+        }
+
+        deserializer.deserialize_any(SectorSetVisitor)
+    }
+}
@@ -1,6 +1,6 @@
 use cid::Cid;
 use fil_actors_runtime::test_blockstores::MemoryBlockstore;
-use fil_actors_runtime::{make_map_with_root_and_bitwidth, Keyer, Map, MapMap};
+use fil_actors_runtime::{make_map_with_root_and_bitwidth, parse_uint_key, Keyer, Map, MapMap};
 use fvm_shared::HAMT_BIT_WIDTH;
 
 #[test]
@@ -88,3 +88,64 @@ fn mapmap_test() {
         .unwrap();
     assert_eq!(2, count);
 }
+
+#[test]
+fn for_each_ranged_resumes_across_outer_keys() {
+    let store = MemoryBlockstore::new();
+    let mut mm: MapMap<MemoryBlockstore, u64, u64, u64> =
+        MapMap::new(&store, HAMT_BIT_WIDTH, HAMT_BIT_WIDTH);
+    for outer in 1..=3u64 {
+        for inner in 1..=outer {
+            mm.put(outer, inner, outer * 10 + inner).unwrap();
+        }
+    }
+    let root = mm.flush().unwrap();
+    let mut mm: MapMap<MemoryBlockstore, u64, u64, u64> =
+        MapMap::from_root(&store, &root, HAMT_BIT_WIDTH, HAMT_BIT_WIDTH).unwrap();
+
+    // Pages of two values visit every value exactly once.
+    let mut visited = Vec::new();
+    let mut cursor = None;
+    loop {
+        let (n, next) = mm
+            .for_each_ranged(cursor, 2, |_, _, v| {
+                visited.push(*v);
+                Ok(())
+            })
+            .unwrap();
+        assert!(n <= 2);
+        match next {
+            Some((outer, inner)) => {
+                let inner = inner.map(|k| parse_uint_key(&k).unwrap());
+                cursor = Some((parse_uint_key(&outer).unwrap(), inner));
+            }
+            None => break,
+        }
+    }
+    visited.sort();
+    assert_eq!(vec![11, 21, 22, 31, 32, 33], visited);
+
+    // A cursor within an outer key that has since been removed resumes from the following outer
+    // key, from its beginning.
+    let mut first = None;
+    mm.for_each_ranged(None, 1, |outer, _, _| {
+        first = Some(parse_uint_key(outer).unwrap());
+        Ok(())
+    })
+    .unwrap();
+    let first = first.unwrap();
+    for inner in 1..=first {
+        mm.remove(first, inner).unwrap();
+    }
+    mm.flush().unwrap();
+    let mut visited = Vec::new();
+    let (n, next) = mm
+        .for_each_ranged(Some((first, Some(1))), 10, |outer, _, _| {
+            visited.push(parse_uint_key(outer).unwrap());
+            Ok(())
+        })
+        .unwrap();
+    assert!(next.is_none());
+    assert_eq!(n, visited.len());
+    assert!(!visited.contains(&first));
+}
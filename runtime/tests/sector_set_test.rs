@@ -0,0 +1,46 @@
+use fil_actors_runtime::{bitfield_from_ranges, ranges_from_bitfield, SectorRange, SectorSet};
+use fvm_ipld_bitfield::BitField;
+use fvm_ipld_encoding::{from_slice, to_vec};
+
+fn range(start: u64, count: u64) -> SectorRange {
+    SectorRange { start, count }
+}
+
+#[test]
+fn ranges_round_trip_through_bitfield() {
+    let bf = BitField::try_from_bits([1, 2, 3, 7, 10, 11].iter().copied()).unwrap();
+    let ranges = ranges_from_bitfield(&bf);
+    assert_eq!(vec![range(1, 3), range(7, 1), range(10, 2)], ranges);
+    assert_eq!(bf, bitfield_from_ranges(&ranges).unwrap());
+}
+
+#[test]
+fn unordered_and_overlapping_ranges_are_merged() {
+    let bf =
+        bitfield_from_ranges(&[range(10, 5), range(0, 2), range(12, 10), range(2, 1)]).unwrap();
+    let expected = BitField::try_from_bits((0..3).chain(10..22)).unwrap();
+    assert_eq!(expected, bf);
+}
+
+#[test]
+fn invalid_ranges_are_rejected() {
+    assert!(bitfield_from_ranges(&[range(5, 0)]).is_err());
+    assert!(bitfield_from_ranges(&[range(u64::MAX, 2)]).is_err());
+}
+
+#[test]
+fn decodes_either_encoding() {
+    let bf = BitField::try_from_bits([4, 5, 6].iter().copied()).unwrap();
+
+    // A bitfield encodes exactly as a plain BitField, so existing params remain valid.
+    let encoded = to_vec(&SectorSet::Bitfield(bf.clone())).unwrap();
+    assert_eq!(to_vec(&bf).unwrap(), encoded);
+    let decoded: SectorSet = from_slice(&encoded).unwrap();
+    assert_eq!(SectorSet::Bitfield(bf.clone()), decoded);
+
+    let ranges = SectorSet::Ranges(vec![range(4, 3)]);
+    let encoded = to_vec(&ranges).unwrap();
+    let decoded: SectorSet = from_slice(&encoded).unwrap();
+    assert_eq!(ranges, decoded);
+    assert_eq!(bf, decoded.to_bitfield().unwrap());
+}
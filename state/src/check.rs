@@ -191,6 +191,7 @@ pub fn check_state_invariants<BS: Blockstore>(
             check_market_against_verifreg(&acc, &market_summary, &verifreg_summary);
         }
         check_verifreg_against_miners(&acc, &verifreg_summary, &miner_summaries);
+        check_sector_verified_weights_against_claims(&acc, &verifreg_summary, &miner_summaries);
     }
 
     if let Some(expected_balance_total) = expected_balance_total {
@@ -386,10 +387,6 @@ fn check_verifreg_against_miners(
     verifreg_summary: &verifreg::StateSummary,
     miner_summaries: &HashMap<Address, miner::StateSummary>,
 ) {
-    // Accumulates the weight of claims for each sector.
-    let mut sector_claim_verified_weights: BTreeMap<(Address, SectorNumber), DealWeight> =
-        BTreeMap::new();
-
     for (id, claim) in &verifreg_summary.claims {
         // All claims are indexed by valid providers
         let maddr = Address::new_id(claim.provider);
@@ -434,22 +431,62 @@ fn check_verifreg_against_miners(
                         maddr
                     ),
                 );
-                let expected_duration = sector.sector_expiration - claim.term_start;
-                let expected_weight = DealWeight::from(claim.size.0) * expected_duration;
-                *sector_claim_verified_weights.entry((maddr, claim.sector)).or_default() +=
-                    expected_weight;
             }
         }
     }
-    for ((maddr, sector), claim_weight) in &sector_claim_verified_weights {
-        let miner_summary = miner_summaries.get(maddr).unwrap();
-        let sector = miner_summary.live_data_sectors.get(sector).unwrap();
-        acc.require(
-            sector.verified_deal_weight == *claim_weight,
-            format!(
-                "sector verified weight {} does not match claims of {} for miner {}",
-                sector.verified_deal_weight, claim_weight, maddr
-            ),
-        )
+}
+
+fn check_sector_verified_weights_against_claims(
+    acc: &MessageAccumulator,
+    verifreg_summary: &verifreg::StateSummary,
+    miner_summaries: &HashMap<Address, miner::StateSummary>,
+) {
+    // Accumulates the size and expected weight of the claims for each live sector.
+    let mut sector_claims: BTreeMap<(Address, SectorNumber), (u64, DealWeight)> = BTreeMap::new();
+    for claim in verifreg_summary.claims.values() {
+        let maddr = Address::new_id(claim.provider);
+        let sector = match miner_summaries
+            .get(&maddr)
+            .and_then(|summary| summary.live_data_sectors.get(&claim.sector))
+        {
+            None => continue,
+            Some(sector) => sector,
+        };
+        let expected_weight =
+            DealWeight::from(claim.size.0) * (sector.sector_expiration - claim.term_start);
+        let entry = sector_claims.entry((maddr, claim.sector)).or_default();
+        entry.0 += claim.size.0;
+        entry.1 += expected_weight;
+    }
+
+    // Every live sector's verified weight must be accounted for by its claims, else the sector's
+    // QA power is inflated.
+    for (maddr, miner_summary) in miner_summaries {
+        for (sector_number, sector) in &miner_summary.live_data_sectors {
+            let (claimed_size, claimed_weight) =
+                sector_claims.remove(&(*maddr, *sector_number)).unwrap_or_default();
+            if !sector.legacy_qap {
+                // Weight is exactly the claimed space over the remaining sector lifetime.
+                acc.require(
+                    sector.verified_deal_weight == claimed_weight,
+                    format!(
+                        "sector {} verified weight {} does not match claims of {} for miner {}",
+                        sector_number, sector.verified_deal_weight, claimed_weight, maddr
+                    ),
+                );
+            } else if claimed_size > 0 {
+                // Legacy weight was computed from deal terms, which may be shorter than the
+                // sector's lifetime, so it's only bounded by the claimed space over that lifetime.
+                let max_weight = DealWeight::from(claimed_size)
+                    * (sector.sector_expiration - sector.sector_start);
+                acc.require(
+                    sector.verified_deal_weight <= max_weight,
+                    format!(
+                        "legacy sector {} verified weight {} exceeds claimed size {} over its lifetime ({}) for miner {}",
+                        sector_number, sector.verified_deal_weight, claimed_size, max_weight, maddr
+                    ),
+                );
+            }
+        }
     }
 }
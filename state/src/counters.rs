@@ -0,0 +1,41 @@
+use std::collections::BTreeMap;
+
+use anyhow::anyhow;
+use cid::Cid;
+use fil_actor_market::State as MarketState;
+use fil_actors_runtime::runtime::builtins::Type;
+use fil_actors_runtime::runtime::metrics::Counters;
+use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::CborStore;
+use fvm_shared::address::Address;
+use fvm_shared::ActorID;
+use vm_api::ActorState;
+
+/// Derives the workload counters recorded in actor state, by actor ID.
+///
+/// Counters incremented at runtime aren't stored on chain, so only those whose totals are also
+/// kept in actor state can be recovered from a state tree:
+/// - `market.deals_published`, as every published deal is assigned the market's next deal ID.
+///
+/// Actors with none of these counters are omitted.
+pub fn derive_counters<BS: Blockstore>(
+    store: &BS,
+    manifest: &BTreeMap<Cid, Type>,
+    tree: &BTreeMap<Address, ActorState>,
+) -> anyhow::Result<BTreeMap<ActorID, Counters>> {
+    let mut counters = BTreeMap::new();
+    for (address, actor) in tree {
+        let id = address.id().map_err(|_| anyhow!("unexpected address {address} in state tree"))?;
+        let mut actor_counters = Counters::default();
+        if let Some(Type::Market) = manifest.get(&actor.code) {
+            let state: MarketState = store
+                .get_cbor(&actor.state)?
+                .ok_or_else(|| anyhow!("market state {} not found", actor.state))?;
+            actor_counters.increment("market.deals_published", state.next_id);
+        }
+        if !actor_counters.is_empty() {
+            counters.insert(id, actor_counters);
+        }
+    }
+    Ok(counters)
+}
@@ -0,0 +1,141 @@
+use std::collections::BTreeMap;
+
+use anyhow::anyhow;
+use cid::multihash::Code;
+use cid::Cid;
+use fil_actor_account::State as AccountState;
+use fil_actor_cron::{Entry as CronEntry, State as CronState};
+use fil_actor_datacap::State as DataCapState;
+use fil_actor_init::State as InitState;
+use fil_actor_market::{Method as MarketMethod, State as MarketState};
+use fil_actor_power::{Method as PowerMethod, State as PowerState};
+use fil_actor_reward::State as RewardState;
+use fil_actor_system::State as SystemState;
+use fil_actor_verifreg::State as VerifRegState;
+use fil_actors_runtime::runtime::EMPTY_ARR_CID;
+use fil_actors_runtime::{
+    BURNT_FUNDS_ACTOR_ADDR, CRON_ACTOR_ADDR, DATACAP_TOKEN_ACTOR_ADDR, EAM_ACTOR_ADDR,
+    FIRST_NON_SINGLETON_ADDR, INIT_ACTOR_ADDR, REWARD_ACTOR_ADDR, STORAGE_MARKET_ACTOR_ADDR,
+    STORAGE_POWER_ACTOR_ADDR, SYSTEM_ACTOR_ADDR, VERIFIED_REGISTRY_ACTOR_ADDR,
+};
+use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::CborStore;
+use fvm_shared::address::Address;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::sector::StoragePower;
+use num_traits::Zero;
+use serde::Serialize;
+use vm_api::builtin::Type;
+use vm_api::manifest::load_manifest;
+use vm_api::{new_actor, ActorState};
+
+/// Parameters of the singleton actors created at genesis.
+pub struct GenesisConfig {
+    /// Network name recorded by the init actor.
+    pub network_name: String,
+    /// Initial balance of the reward actor, from which block rewards are paid.
+    pub reward_balance: TokenAmount,
+    /// Address permitted to configure the reward actor's treasury split, if any.
+    /// This must be the ID address of an actor, which may be created after genesis.
+    pub reward_governor: Option<Address>,
+    /// Root key of the verified registry.
+    /// This must be the ID address of an actor, which may be created after genesis.
+    pub verifreg_root: Address,
+    /// Entries invoked by the cron actor at the end of every epoch.
+    pub cron_entries: Vec<CronEntry>,
+}
+
+impl Default for GenesisConfig {
+    fn default() -> Self {
+        Self {
+            network_name: "localnet".to_string(),
+            reward_balance: TokenAmount::from_whole(1_100_000_000),
+            reward_governor: None,
+            // The first actor created after genesis is expected to be the root signer, followed
+            // by the root multisig.
+            verifreg_root: Address::new_id(FIRST_NON_SINGLETON_ADDR + 1),
+            cron_entries: vec![
+                CronEntry {
+                    receiver: STORAGE_POWER_ACTOR_ADDR,
+                    method_num: PowerMethod::OnEpochTickEnd as u64,
+                    priority: 0,
+                    gas_limit: None,
+                    disabled: false,
+                },
+                CronEntry {
+                    receiver: STORAGE_MARKET_ACTOR_ADDR,
+                    method_num: MarketMethod::CronTick as u64,
+                    priority: 0,
+                    gas_limit: None,
+                    disabled: false,
+                },
+            ],
+        }
+    }
+}
+
+/// Builds the builtin singleton actors with the code CIDs given by the bundle manifest at
+/// `manifest`, storing their initial states in `store`.
+/// Each actor's state is that which its constructor would create from the configuration,
+/// and the system actor's registry of builtin actors is populated from the manifest.
+/// Returns the actors keyed by address, to be installed in a new state tree.
+pub fn singleton_actors<BS: Blockstore>(
+    store: &BS,
+    manifest: &Cid,
+    config: &GenesisConfig,
+) -> anyhow::Result<BTreeMap<Address, ActorState>> {
+    let codes = load_manifest(store, manifest)?;
+    let mut actors = BTreeMap::new();
+    let mut create = |addr: Address, t: Type, state: Cid, balance: TokenAmount| {
+        let code = codes
+            .get(&t)
+            .ok_or_else(|| anyhow!("no {} actor in manifest {}", t.name(), manifest))?;
+        actors.insert(addr, new_actor(*code, state, 0, balance, None));
+        anyhow::Ok(())
+    };
+
+    let mut system = SystemState::new(store)?;
+    let registry: Vec<(String, Cid)> =
+        codes.iter().map(|(t, code)| (t.name().to_string(), *code)).collect();
+    system.builtin_actors = store.put_cbor(&registry, Code::Blake2b256)?;
+    create(SYSTEM_ACTOR_ADDR, Type::System, put(store, &system)?, TokenAmount::zero())?;
+
+    let init = InitState::new(store, config.network_name.clone())?;
+    create(INIT_ACTOR_ADDR, Type::Init, put(store, &init)?, TokenAmount::zero())?;
+
+    let mut reward = RewardState::new(StoragePower::zero());
+    reward.governor = config.reward_governor;
+    create(REWARD_ACTOR_ADDR, Type::Reward, put(store, &reward)?, config.reward_balance.clone())?;
+
+    let cron = CronState::new(store, config.cron_entries.clone())?;
+    create(CRON_ACTOR_ADDR, Type::Cron, put(store, &cron)?, TokenAmount::zero())?;
+
+    let power = PowerState::new(store)?;
+    create(STORAGE_POWER_ACTOR_ADDR, Type::Power, put(store, &power)?, TokenAmount::zero())?;
+
+    let market = MarketState::new(store)?;
+    create(STORAGE_MARKET_ACTOR_ADDR, Type::Market, put(store, &market)?, TokenAmount::zero())?;
+
+    let verifreg = VerifRegState::new(store, config.verifreg_root)?;
+    create(
+        VERIFIED_REGISTRY_ACTOR_ADDR,
+        Type::VerifiedRegistry,
+        put(store, &verifreg)?,
+        TokenAmount::zero(),
+    )?;
+
+    // The Ethereum Address Manager has no state.
+    create(EAM_ACTOR_ADDR, Type::EAM, EMPTY_ARR_CID, TokenAmount::zero())?;
+
+    let datacap = DataCapState::new(store, VERIFIED_REGISTRY_ACTOR_ADDR)?;
+    create(DATACAP_TOKEN_ACTOR_ADDR, Type::DataCap, put(store, &datacap)?, TokenAmount::zero())?;
+
+    let burnt_funds = AccountState { address: BURNT_FUNDS_ACTOR_ADDR };
+    create(BURNT_FUNDS_ACTOR_ADDR, Type::Account, put(store, &burnt_funds)?, TokenAmount::zero())?;
+
+    Ok(actors)
+}
+
+fn put<BS: Blockstore, S: Serialize>(store: &BS, state: &S) -> anyhow::Result<Cid> {
+    store.put_cbor(state, Code::Blake2b256)
+}
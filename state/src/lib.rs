@@ -1 +1,6 @@
 pub mod check;
+#[cfg(feature = "metrics")]
+pub mod counters;
+pub mod genesis;
+pub mod migration;
+pub mod reachability;
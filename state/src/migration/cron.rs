@@ -0,0 +1,40 @@
+//! Cron entries gained a priority, a gas limit and a flag disabling them, and actors may
+//! register themselves to be called in rotation.
+
+use cid::Cid;
+use fil_actor_cron::{Entry, State};
+use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::tuple::*;
+use fvm_shared::address::Address;
+use fvm_shared::MethodNum;
+
+use super::{load, put};
+
+#[derive(Clone, Debug, Serialize_tuple, Deserialize_tuple)]
+pub struct StateV12 {
+    pub entries: Vec<EntryV12>,
+}
+
+#[derive(Clone, Debug, Serialize_tuple, Deserialize_tuple)]
+pub struct EntryV12 {
+    pub receiver: Address,
+    pub method_num: MethodNum,
+}
+
+/// Migrated entries keep their order, with equal priority and no gas limit, so they're
+/// called exactly as they were before. No actors are registered.
+pub fn migrate_state<BS: Blockstore>(store: &BS, head: &Cid) -> anyhow::Result<Cid> {
+    let old: StateV12 = load(store, head)?;
+    let entries = old
+        .entries
+        .into_iter()
+        .map(|e| Entry {
+            receiver: e.receiver,
+            method_num: e.method_num,
+            priority: 0,
+            gas_limit: None,
+            disabled: false,
+        })
+        .collect();
+    put(store, &State::new(store, entries)?)
+}
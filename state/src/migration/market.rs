@@ -0,0 +1,82 @@
+//! The market state gained client deal publishing statistics, deferred deal terminations,
+//! deal start epoch extensions and the deals verified for pre-committed sectors.
+
+use cid::Cid;
+use fil_actor_market::{
+    ActivationExtensionsMap, ClientDealStatsMap, PendingDealTerminationsMap, PrecommittedDealsMap,
+    State, ACTIVATION_EXTENSIONS_CONFIG, CLIENT_DEAL_STATS_CONFIG,
+    PENDING_DEAL_TERMINATIONS_CONFIG, PRECOMMITTED_DEALS_CONFIG,
+};
+use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::tuple::*;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::deal::DealID;
+use fvm_shared::econ::TokenAmount;
+
+use super::{load, put};
+
+#[derive(Clone, Debug, Serialize_tuple, Deserialize_tuple)]
+pub struct StateV12 {
+    pub proposals: Cid,
+    pub states: Cid,
+    pub pending_proposals: Cid,
+    pub escrow_table: Cid,
+    pub locked_table: Cid,
+    pub next_id: DealID,
+    pub deal_ops_by_epoch: Cid,
+    pub last_cron: ChainEpoch,
+    pub total_client_locked_collateral: TokenAmount,
+    pub total_provider_locked_collateral: TokenAmount,
+    pub total_client_storage_fee: TokenAmount,
+    pub pending_deal_allocation_ids: Cid,
+    pub provider_sectors: Cid,
+}
+
+/// The migrated state records no publishing activity, deferred terminations or extensions.
+/// No deal is recorded as pre-committed, so clients may cancel deals already verified for
+/// sectors pre-committed before the migration until they are activated.
+pub fn migrate_state<BS: Blockstore>(store: &BS, head: &Cid) -> anyhow::Result<Cid> {
+    let old: StateV12 = load(store, head)?;
+    put(
+        store,
+        &State {
+            proposals: old.proposals,
+            states: old.states,
+            pending_proposals: old.pending_proposals,
+            escrow_table: old.escrow_table,
+            locked_table: old.locked_table,
+            next_id: old.next_id,
+            deal_ops_by_epoch: old.deal_ops_by_epoch,
+            last_cron: old.last_cron,
+            total_client_locked_collateral: old.total_client_locked_collateral,
+            total_provider_locked_collateral: old.total_provider_locked_collateral,
+            total_client_storage_fee: old.total_client_storage_fee,
+            pending_deal_allocation_ids: old.pending_deal_allocation_ids,
+            provider_sectors: old.provider_sectors,
+            client_deal_stats: ClientDealStatsMap::empty(
+                store,
+                CLIENT_DEAL_STATS_CONFIG,
+                "client deal stats",
+            )
+            .flush()?,
+            pending_deal_terminations: PendingDealTerminationsMap::empty(
+                store,
+                PENDING_DEAL_TERMINATIONS_CONFIG,
+                "pending deal terminations",
+            )
+            .flush()?,
+            activation_extensions: ActivationExtensionsMap::empty(
+                store,
+                ACTIVATION_EXTENSIONS_CONFIG,
+                "activation extensions",
+            )
+            .flush()?,
+            precommitted_deals: PrecommittedDealsMap::empty(
+                store,
+                PRECOMMITTED_DEALS_CONFIG,
+                "precommitted deals",
+            )
+            .flush()?,
+        },
+    )
+}
@@ -0,0 +1,127 @@
+//! The miner state gained a record of the space of verified claims that expired while backing
+//! sectors, grouped by deadline.
+//! The miner info gained an expiration for the pending owner change, and a list of authorized
+//! provers.
+
+use cid::Cid;
+use fil_actor_miner::{
+    BeneficiaryTerm, ExpiredClaimSpaceMap, MinerInfo, PendingBeneficiaryChange, State,
+    WorkerKeyChange, EXPIRED_CLAIM_SPACE_CONFIG,
+};
+use fil_actors_runtime::runtime::policy_constants::OWNER_CHANGE_EXPIRY;
+use fvm_ipld_bitfield::BitField;
+use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::tuple::*;
+use fvm_ipld_encoding::{strict_bytes, BytesDe};
+use fvm_shared::address::Address;
+use fvm_shared::clock::{ChainEpoch, EPOCH_UNDEFINED};
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::sector::{RegisteredPoStProof, SectorSize};
+
+use super::{load, put};
+
+#[derive(Clone, Debug, Serialize_tuple, Deserialize_tuple)]
+pub struct StateV12 {
+    pub info: Cid,
+    pub pre_commit_deposits: TokenAmount,
+    pub locked_funds: TokenAmount,
+    pub vesting_funds: Cid,
+    pub fee_debt: TokenAmount,
+    pub initial_pledge: TokenAmount,
+    pub pre_committed_sectors: Cid,
+    pub pre_committed_sectors_cleanup: Cid,
+    pub allocated_sectors: Cid,
+    pub sectors: Cid,
+    pub proving_period_start: ChainEpoch,
+    pub current_deadline: u64,
+    pub deadlines: Cid,
+    pub early_terminations: BitField,
+    pub deadline_cron_active: bool,
+}
+
+#[derive(Debug, Serialize_tuple, Deserialize_tuple)]
+pub struct MinerInfoV12 {
+    pub owner: Address,
+    pub worker: Address,
+    pub control_addresses: Vec<Address>,
+    pub pending_worker_key: Option<WorkerKeyChange>,
+    #[serde(with = "strict_bytes")]
+    pub peer_id: Vec<u8>,
+    pub multi_address: Vec<BytesDe>,
+    pub window_post_proof_type: RegisteredPoStProof,
+    pub sector_size: SectorSize,
+    pub window_post_partition_sectors: u64,
+    pub consensus_fault_elapsed: ChainEpoch,
+    pub pending_owner_address: Option<Address>,
+    pub beneficiary: Address,
+    pub beneficiary_term: BeneficiaryTerm,
+    pub pending_beneficiary_term: Option<PendingBeneficiaryChange>,
+}
+
+/// The migrated state records no expired claim space.
+/// An owner change pending at the upgrade epoch may be confirmed for the usual period after it,
+/// and no provers are authorized.
+pub fn migrate_state<BS: Blockstore>(
+    store: &BS,
+    head: &Cid,
+    upgrade_epoch: ChainEpoch,
+) -> anyhow::Result<Cid> {
+    let old: StateV12 = load(store, head)?;
+    put(
+        store,
+        &State {
+            info: migrate_info(store, &old.info, upgrade_epoch)?,
+            pre_commit_deposits: old.pre_commit_deposits,
+            locked_funds: old.locked_funds,
+            vesting_funds: old.vesting_funds,
+            fee_debt: old.fee_debt,
+            initial_pledge: old.initial_pledge,
+            pre_committed_sectors: old.pre_committed_sectors,
+            pre_committed_sectors_cleanup: old.pre_committed_sectors_cleanup,
+            allocated_sectors: old.allocated_sectors,
+            sectors: old.sectors,
+            proving_period_start: old.proving_period_start,
+            current_deadline: old.current_deadline,
+            deadlines: old.deadlines,
+            early_terminations: old.early_terminations,
+            deadline_cron_active: old.deadline_cron_active,
+            expired_claim_space: ExpiredClaimSpaceMap::flush_empty(
+                store,
+                EXPIRED_CLAIM_SPACE_CONFIG,
+            )?,
+        },
+    )
+}
+
+fn migrate_info<BS: Blockstore>(
+    store: &BS,
+    info: &Cid,
+    upgrade_epoch: ChainEpoch,
+) -> anyhow::Result<Cid> {
+    let old: MinerInfoV12 = load(store, info)?;
+    let pending_owner_expiration = match old.pending_owner_address {
+        Some(_) => upgrade_epoch + OWNER_CHANGE_EXPIRY,
+        None => EPOCH_UNDEFINED,
+    };
+    put(
+        store,
+        &MinerInfo {
+            owner: old.owner,
+            worker: old.worker,
+            control_addresses: old.control_addresses,
+            pending_worker_key: old.pending_worker_key,
+            peer_id: old.peer_id,
+            multi_address: old.multi_address,
+            window_post_proof_type: old.window_post_proof_type,
+            sector_size: old.sector_size,
+            window_post_partition_sectors: old.window_post_partition_sectors,
+            consensus_fault_elapsed: old.consensus_fault_elapsed,
+            pending_owner_address: old.pending_owner_address,
+            beneficiary: old.beneficiary,
+            beneficiary_term: old.beneficiary_term,
+            pending_beneficiary_term: old.pending_beneficiary_term,
+            pending_owner_expiration,
+            prover_authorizations: Vec::new(),
+        },
+    )
+}
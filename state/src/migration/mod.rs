@@ -0,0 +1,69 @@
+//! Migration of actors v12 state to the state layouts of this version.
+//!
+//! Each actor whose state layout has changed has a module here declaring its v12 layout, from
+//! which its state is decoded and rewritten in the current layout. New fields are initialised
+//! to the values they would hold had the actor always run this version.
+
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Context};
+use cid::multihash::Code;
+use cid::Cid;
+use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::CborStore;
+use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use vm_api::builtin::Type;
+use vm_api::ActorState;
+
+pub mod cron;
+pub mod market;
+pub mod miner;
+pub mod multisig;
+pub mod paych;
+pub mod power;
+pub mod reward;
+
+/// Migrates the state of every actor in a state tree from actors v12, and updates its code to
+/// the new bundle.
+/// `manifest` maps the code CIDs of the v12 actors to their types, and `codes` gives the
+/// code CID of each type of actor in the new bundle.
+/// `upgrade_epoch` is the epoch at which the new bundle takes effect.
+pub fn migrate_state_tree<BS: Blockstore>(
+    store: &BS,
+    manifest: &BTreeMap<Cid, Type>,
+    codes: &BTreeMap<Type, Cid>,
+    upgrade_epoch: ChainEpoch,
+    tree: &mut BTreeMap<Address, ActorState>,
+) -> anyhow::Result<()> {
+    for (addr, actor) in tree.iter_mut() {
+        let actor_type = *manifest
+            .get(&actor.code)
+            .ok_or_else(|| anyhow!("actor {} has unknown code {}", addr, actor.code))?;
+        actor.state = match actor_type {
+            Type::Cron => cron::migrate_state(store, &actor.state),
+            Type::Market => market::migrate_state(store, &actor.state),
+            Type::Miner => miner::migrate_state(store, &actor.state, upgrade_epoch),
+            Type::Power => power::migrate_state(store, &actor.state),
+            Type::Reward => reward::migrate_state(store, &actor.state, upgrade_epoch),
+            Type::Multisig => multisig::migrate_state(store, &actor.state),
+            Type::PaymentChannel => paych::migrate_state(store, &actor.state),
+            _ => Ok(actor.state),
+        }
+        .with_context(|| format!("failed to migrate {} actor {}", actor_type.name(), addr))?;
+        actor.code = *codes
+            .get(&actor_type)
+            .ok_or_else(|| anyhow!("no {} actor in the new bundle", actor_type.name()))?;
+    }
+    Ok(())
+}
+
+fn load<BS: Blockstore, S: DeserializeOwned>(store: &BS, head: &Cid) -> anyhow::Result<S> {
+    store.get_cbor(head)?.ok_or_else(|| anyhow!("state {} not found", head))
+}
+
+fn put<BS: Blockstore, S: Serialize>(store: &BS, state: &S) -> anyhow::Result<Cid> {
+    store.put_cbor(state, Code::Blake2b256)
+}
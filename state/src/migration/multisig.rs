@@ -0,0 +1,74 @@
+//! Pending transactions gained an expiration and the epoch from which they may be executed,
+//! and the state gained the wallet's execution delays.
+
+use cid::Cid;
+use fil_actor_multisig::{PendingTxnMap, State, Transaction, TxnID, PENDING_TXN_CONFIG};
+use fil_actors_runtime::Map2;
+use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::tuple::*;
+use fvm_ipld_encoding::RawBytes;
+use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::MethodNum;
+
+use super::{load, put};
+
+pub type PendingTxnMapV12<BS> = Map2<BS, TxnID, TransactionV12>;
+
+#[derive(Clone, Debug, Serialize_tuple, Deserialize_tuple)]
+pub struct StateV12 {
+    pub signers: Vec<Address>,
+    pub num_approvals_threshold: u64,
+    pub next_tx_id: TxnID,
+    pub initial_balance: TokenAmount,
+    pub start_epoch: ChainEpoch,
+    pub unlock_duration: ChainEpoch,
+    pub pending_txs: Cid,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct TransactionV12 {
+    pub to: Address,
+    pub value: TokenAmount,
+    pub method: MethodNum,
+    pub params: RawBytes,
+    pub approved: Vec<Address>,
+}
+
+/// Migrated transactions never expire and aren't delayed, and the wallet has no execution
+/// delays, so pending transactions are approved and executed as they were before.
+pub fn migrate_state<BS: Blockstore>(store: &BS, head: &Cid) -> anyhow::Result<Cid> {
+    let old: StateV12 = load(store, head)?;
+    let old_txns =
+        PendingTxnMapV12::load(store, &old.pending_txs, PENDING_TXN_CONFIG, "pending txns")?;
+    let mut txns = PendingTxnMap::empty(store, PENDING_TXN_CONFIG, "pending txns");
+    old_txns.for_each(|id, txn| {
+        txns.set(
+            &id,
+            Transaction {
+                to: txn.to,
+                value: txn.value.clone(),
+                method: txn.method,
+                params: txn.params.clone(),
+                approved: txn.approved.clone(),
+                expiration_epoch: None,
+                executable_epoch: None,
+            },
+        )?;
+        Ok(())
+    })?;
+    put(
+        store,
+        &State {
+            signers: old.signers,
+            num_approvals_threshold: old.num_approvals_threshold,
+            next_tx_id: old.next_tx_id,
+            initial_balance: old.initial_balance,
+            start_epoch: old.start_epoch,
+            unlock_duration: old.unlock_duration,
+            pending_txs: txns.flush()?,
+            execution_delays: Vec::new(),
+        },
+    )
+}
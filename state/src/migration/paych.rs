@@ -0,0 +1,38 @@
+//! The state gained the nonce that cooperative settlements are signed over.
+
+use cid::Cid;
+use fil_actor_paych::State;
+use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::tuple::*;
+use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+
+use super::{load, put};
+
+#[derive(Clone, Debug, Serialize_tuple, Deserialize_tuple)]
+pub struct StateV12 {
+    pub from: Address,
+    pub to: Address,
+    pub to_send: TokenAmount,
+    pub settling_at: ChainEpoch,
+    pub min_settle_height: ChainEpoch,
+    pub lane_states: Cid,
+}
+
+/// Channels start from a zero settlement nonce, as no settlement could be signed before.
+pub fn migrate_state<BS: Blockstore>(store: &BS, head: &Cid) -> anyhow::Result<Cid> {
+    let old: StateV12 = load(store, head)?;
+    put(
+        store,
+        &State {
+            from: old.from,
+            to: old.to,
+            to_send: old.to_send,
+            settling_at: old.settling_at,
+            min_settle_height: old.min_settle_height,
+            lane_states: old.lane_states,
+            settlement_nonce: 0,
+        },
+    )
+}
@@ -0,0 +1,101 @@
+//! The cron event queue became an AMT of the events enrolled for each epoch, replacing a
+//! HAMT of AMTs of events, and the state gained a cursor for recomputing the aggregate power
+//! totals, the pledge flows of the current and last epochs, and the miner changes since the
+//! last cron tick.
+
+use anyhow::anyhow;
+use cid::Cid;
+use fil_actor_power::{CronEvent, CronEventQueue, MinerChanges, State};
+use fil_actors_runtime::builtin::reward::smooth::FilterEstimate;
+use fil_actors_runtime::{Array, Multimap};
+use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::tuple::*;
+use fvm_shared::bigint::bigint_ser;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::sector::StoragePower;
+use integer_encoding::VarInt;
+use num_traits::Zero;
+
+use super::{load, put};
+
+pub const CRON_QUEUE_HAMT_BITWIDTH_V12: u32 = 6;
+pub const CRON_QUEUE_AMT_BITWIDTH_V12: u32 = 6;
+
+#[derive(Clone, Debug, Serialize_tuple, Deserialize_tuple)]
+pub struct StateV12 {
+    #[serde(with = "bigint_ser")]
+    pub total_raw_byte_power: StoragePower,
+    #[serde(with = "bigint_ser")]
+    pub total_bytes_committed: StoragePower,
+    #[serde(with = "bigint_ser")]
+    pub total_quality_adj_power: StoragePower,
+    #[serde(with = "bigint_ser")]
+    pub total_qa_bytes_committed: StoragePower,
+    pub total_pledge_collateral: TokenAmount,
+    #[serde(with = "bigint_ser")]
+    pub this_epoch_raw_byte_power: StoragePower,
+    #[serde(with = "bigint_ser")]
+    pub this_epoch_quality_adj_power: StoragePower,
+    pub this_epoch_pledge_collateral: TokenAmount,
+    pub this_epoch_qa_power_smoothed: FilterEstimate,
+    pub miner_count: i64,
+    pub miner_above_min_power_count: i64,
+    pub cron_event_queue: Cid, // Multimap, (HAMT[ChainEpoch]AMT[CronEvent]
+    pub first_cron_epoch: ChainEpoch,
+    pub claims: Cid,
+    pub proof_validation_batch: Option<Cid>,
+}
+
+/// The migrated state records no pledge flows or miner changes, as if the last cron tick
+/// had just run, and no recomputation of the aggregate totals in flight.
+pub fn migrate_state<BS: Blockstore>(store: &BS, head: &Cid) -> anyhow::Result<Cid> {
+    let old: StateV12 = load(store, head)?;
+    let cron_event_queue = migrate_cron_queue(store, &old.cron_event_queue)?;
+    put(
+        store,
+        &State {
+            total_raw_byte_power: old.total_raw_byte_power,
+            total_bytes_committed: old.total_bytes_committed,
+            total_quality_adj_power: old.total_quality_adj_power,
+            total_qa_bytes_committed: old.total_qa_bytes_committed,
+            total_pledge_collateral: old.total_pledge_collateral,
+            this_epoch_raw_byte_power: old.this_epoch_raw_byte_power,
+            this_epoch_quality_adj_power: old.this_epoch_quality_adj_power,
+            this_epoch_pledge_collateral: old.this_epoch_pledge_collateral,
+            this_epoch_qa_power_smoothed: old.this_epoch_qa_power_smoothed,
+            miner_count: old.miner_count,
+            miner_above_min_power_count: old.miner_above_min_power_count,
+            cron_event_queue,
+            first_cron_epoch: old.first_cron_epoch,
+            claims: old.claims,
+            proof_validation_batch: old.proof_validation_batch,
+            aggregate_recompute: None,
+            pledge_added: TokenAmount::zero(),
+            pledge_released: TokenAmount::zero(),
+            this_epoch_pledge_added: TokenAmount::zero(),
+            this_epoch_pledge_released: TokenAmount::zero(),
+            miner_changes: MinerChanges::default(),
+        },
+    )
+}
+
+/// Moves the events of a v12 cron event queue into a new queue, keeping the order in which
+/// the events for each epoch were enrolled.
+pub fn migrate_cron_queue<BS: Blockstore>(store: &BS, root: &Cid) -> anyhow::Result<Cid> {
+    let legacy = Multimap::from_root(
+        store,
+        root,
+        CRON_QUEUE_HAMT_BITWIDTH_V12,
+        CRON_QUEUE_AMT_BITWIDTH_V12,
+    )?;
+    let empty = CronEventQueue::new_empty(store)?;
+    let mut queue = CronEventQueue::new(store, &empty)?;
+    legacy.for_all(|key, events: &Array<CronEvent, BS>| {
+        let (epoch, _) = ChainEpoch::decode_var(&key.0)
+            .ok_or_else(|| anyhow!("invalid cron event queue key {:?}", key))?;
+        events.for_each(|_, event| queue.enqueue(epoch, event.clone()))?;
+        Ok(())
+    })?;
+    queue.flush()
+}
@@ -0,0 +1,67 @@
+//! The state gained a governed treasury split of block rewards, with the total paid to the
+//! treasury, and the block reward emitted so far in the current epoch, which is counted
+//! against the policy's per-epoch emission ceiling.
+
+use cid::Cid;
+use fil_actor_reward::State;
+use fil_actors_runtime::builtin::reward::smooth::FilterEstimate;
+use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::tuple::*;
+use fvm_shared::bigint::{bigint_ser, BigInt};
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::sector::StoragePower;
+use num_traits::Zero;
+
+use super::{load, put};
+
+#[derive(Clone, Debug, Serialize_tuple, Deserialize_tuple)]
+pub struct StateV12 {
+    #[serde(with = "bigint_ser")]
+    pub cumsum_baseline: BigInt,
+    #[serde(with = "bigint_ser")]
+    pub cumsum_realized: BigInt,
+    pub effective_network_time: ChainEpoch,
+    #[serde(with = "bigint_ser")]
+    pub effective_baseline_power: StoragePower,
+    pub this_epoch_reward: TokenAmount,
+    pub this_epoch_reward_smoothed: FilterEstimate,
+    #[serde(with = "bigint_ser")]
+    pub this_epoch_baseline_power: StoragePower,
+    pub epoch: ChainEpoch,
+    pub total_storage_power_reward: TokenAmount,
+    pub simple_total: TokenAmount,
+    pub baseline_total: TokenAmount,
+}
+
+/// The migrated state has no governor or treasury split, so block rewards are paid in full
+/// to their producers until a later migration configures them. Nothing has been emitted in
+/// the upgrade epoch, whose block rewards are all awarded after the migration.
+pub fn migrate_state<BS: Blockstore>(
+    store: &BS,
+    head: &Cid,
+    upgrade_epoch: ChainEpoch,
+) -> anyhow::Result<Cid> {
+    let old: StateV12 = load(store, head)?;
+    put(
+        store,
+        &State {
+            cumsum_baseline: old.cumsum_baseline,
+            cumsum_realized: old.cumsum_realized,
+            effective_network_time: old.effective_network_time,
+            effective_baseline_power: old.effective_baseline_power,
+            this_epoch_reward: old.this_epoch_reward,
+            this_epoch_reward_smoothed: old.this_epoch_reward_smoothed,
+            this_epoch_baseline_power: old.this_epoch_baseline_power,
+            epoch: old.epoch,
+            total_storage_power_reward: old.total_storage_power_reward,
+            simple_total: old.simple_total,
+            baseline_total: old.baseline_total,
+            governor: None,
+            treasury_split: None,
+            total_treasury_reward: TokenAmount::zero(),
+            emission_epoch: upgrade_epoch,
+            epoch_emission: TokenAmount::zero(),
+        },
+    )
+}
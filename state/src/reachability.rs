@@ -0,0 +1,166 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use anyhow::{anyhow, bail};
+use cid::Cid;
+use fil_actors_runtime::MessageAccumulator;
+use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::DAG_CBOR;
+use fvm_shared::address::Address;
+use vm_api::ActorState;
+
+/// Multihash code of the identity hash, whose CIDs inline their data rather than naming a block.
+const IDENTITY_HASH: u64 = 0x0;
+/// CBOR tag of a CID link in DAG-CBOR.
+const CID_TAG: u64 = 42;
+
+/// The IPLD blocks making up an actor's state.
+#[derive(Clone, Debug, Default)]
+pub struct ActorBlocks {
+    /// Blocks reachable from the actor's state head, including the head itself.
+    pub reachable: BTreeSet<Cid>,
+    /// Blocks linked from reachable blocks that are missing from the blockstore.
+    pub missing: BTreeSet<Cid>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ReachabilityReport {
+    pub actors: BTreeMap<Address, ActorBlocks>,
+}
+
+impl ReachabilityReport {
+    /// Returns those of `blocks` that aren't reachable from the state of any actor.
+    ///
+    /// Given the blocks written while executing some messages, these are the blocks that were
+    /// flushed but never linked into an actor's state, which usually means some head structure
+    /// was saved with a stale link to a child (e.g. a deadline CID from before an update).
+    pub fn orphans(&self, blocks: impl IntoIterator<Item = Cid>) -> BTreeSet<Cid> {
+        blocks
+            .into_iter()
+            .filter(|cid| !self.actors.values().any(|actor| actor.reachable.contains(cid)))
+            .collect()
+    }
+}
+
+/// Walks the IPLD blocks reachable from each actor's state head, reporting the blocks linked from
+/// each state that are missing from the blockstore.
+///
+/// This is much more expensive than checking state invariants, so isn't part of
+/// `check_state_invariants`.
+pub fn check_reachability<BS: Blockstore>(
+    store: &BS,
+    tree: &BTreeMap<Address, ActorState>,
+) -> anyhow::Result<(ReachabilityReport, MessageAccumulator)> {
+    let acc = MessageAccumulator::default();
+    let mut report = ReachabilityReport::default();
+
+    for (address, actor) in tree {
+        let acc = acc.with_prefix(format!("{address} "));
+        let blocks = walk_blocks(store, &actor.state, &acc)?;
+        for cid in &blocks.missing {
+            acc.add(format!("state links to block {cid} missing from the blockstore"));
+        }
+        report.actors.insert(*address, blocks);
+    }
+
+    Ok((report, acc))
+}
+
+fn walk_blocks<BS: Blockstore>(
+    store: &BS,
+    head: &Cid,
+    acc: &MessageAccumulator,
+) -> anyhow::Result<ActorBlocks> {
+    let mut blocks = ActorBlocks::default();
+    let mut queue = VecDeque::from([*head]);
+    while let Some(cid) = queue.pop_front() {
+        if cid.hash().code() == IDENTITY_HASH
+            || blocks.reachable.contains(&cid)
+            || blocks.missing.contains(&cid)
+        {
+            continue;
+        }
+        let data = match store.get(&cid)? {
+            Some(data) => data,
+            None => {
+                blocks.missing.insert(cid);
+                continue;
+            }
+        };
+        blocks.reachable.insert(cid);
+
+        // Only DAG-CBOR blocks can link to other blocks.
+        if cid.codec() != DAG_CBOR {
+            continue;
+        }
+        if let Err(e) = scan_links(&data, &mut queue) {
+            acc.add(format!("failed to scan block {cid} for links: {e}"));
+        }
+    }
+    Ok(blocks)
+}
+
+/// Collects the CIDs linked from a DAG-CBOR encoded block, without decoding anything else.
+fn scan_links(mut data: &[u8], links: &mut impl Extend<Cid>) -> anyhow::Result<()> {
+    // The number of data items still to be read.
+    let mut remaining: u64 = 1;
+    while remaining > 0 {
+        remaining -= 1;
+        let (major, value) = read_header(&mut data)?;
+        let items = match major {
+            // Integers, and simple values and floats, are entirely in the header.
+            0 | 1 | 7 => 0,
+            // Byte and text strings.
+            2 | 3 => {
+                take(&mut data, value)?;
+                0
+            }
+            // Arrays and maps.
+            4 => value,
+            5 => value.checked_mul(2).ok_or_else(|| anyhow!("map length {value} too large"))?,
+            6 if value == CID_TAG => {
+                let (major, len) = read_header(&mut data)?;
+                if major != 2 {
+                    bail!("CID tag must be followed by a byte string");
+                }
+                match take(&mut data, len)? {
+                    [0, cid @ ..] => links.extend([Cid::try_from(cid)?]),
+                    _ => bail!("CID must be prefixed with the identity multibase"),
+                }
+                0
+            }
+            // Other tags are followed by a single data item.
+            _ => 1,
+        };
+        remaining =
+            remaining.checked_add(items).ok_or_else(|| anyhow!("too many items in block"))?;
+    }
+    if !data.is_empty() {
+        bail!("{} trailing bytes", data.len());
+    }
+    Ok(())
+}
+
+/// Reads a CBOR data item header, returning its major type and argument.
+fn read_header(data: &mut &[u8]) -> anyhow::Result<(u8, u64)> {
+    let first = take(data, 1)?[0];
+    let (major, info) = (first >> 5, first & 0x1f);
+    let value = match info {
+        0..=23 => info as u64,
+        24..=27 => {
+            let bytes = take(data, 1 << (info - 24))?;
+            bytes.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64)
+        }
+        _ => bail!("unsupported CBOR additional info {info}"),
+    };
+    Ok((major, value))
+}
+
+fn take<'a>(data: &mut &'a [u8], len: u64) -> anyhow::Result<&'a [u8]> {
+    let len = usize::try_from(len)?;
+    if len > data.len() {
+        bail!("unexpected end of block");
+    }
+    let (head, tail) = data.split_at(len);
+    *data = tail;
+    Ok(head)
+}
@@ -1,5 +1,5 @@
 use fil_actors_runtime::FIRST_NON_SINGLETON_ADDR;
-use fvm_shared::{address::Address, ActorID};
+use fvm_shared::address::Address;
 
 // TODO: Deduplicate these constants which currently exist both here and in the integration_tests crate.
 // https://github.com/filecoin-project/builtin-actors/issues/1348
@@ -11,8 +11,6 @@ pub const TEST_VERIFREG_ROOT_ADDR: Address = Address::new_id(FIRST_NON_SINGLETON
 
 // account actor seeding funds created by new_with_singletons
 pub const FAUCET_ROOT_KEY: &[u8] = &[153; fvm_shared::address::BLS_PUB_LEN];
-pub const TEST_FAUCET_ADDR: Address = Address::new_id(FIRST_NON_SINGLETON_ADDR + 2);
-pub const FIRST_TEST_USER_ADDR: ActorID = FIRST_NON_SINGLETON_ADDR + 3;
 
 // static values for predictable testing
 pub const TEST_VM_RAND_ARRAY: [u8; 32] = [
@@ -0,0 +1,316 @@
+//! Export of a test VM's state and message log, for replaying a scenario against a node.
+//!
+//! The blockstore is written as a CARv1 file rooted at the final state root, and the messages
+//! executed with their receipts are written as a JSON sidecar.
+//! The test VM runs actors under fake code CIDs, so each exported state tree is rewritten to
+//! run the actors of a bundle: actor code CIDs are replaced by the bundle's, as is the system
+//! actor's registry, and the actors HAMT is wrapped in a versioned state root as a node stores it.
+//! Every block the VM wrote is included with the bundle's blocks, so the state tree before and
+//! after each message can be loaded from the CAR at the roots given in the sidecar.
+//! The test VM doesn't charge gas, so receipts record only the exit code and return value.
+//! With the `metrics` feature, the counters incremented by actors are also written as JSON, as
+//! are those derived from the final state.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{BufWriter, Cursor, Read, Write};
+use std::path::Path;
+
+use anyhow::anyhow;
+use cid::multihash::Code;
+use cid::Cid;
+use fil_actor_system::State as SystemState;
+use fil_actors_runtime::runtime::builtins::Type;
+#[cfg(feature = "metrics")]
+use fil_actors_runtime::runtime::metrics::Counters;
+use fil_actors_runtime::test_blockstores::MemoryBlockstore;
+use fil_actors_runtime::test_utils::ACTOR_TYPES;
+use fil_actors_runtime::{DEFAULT_HAMT_CONFIG, SYSTEM_ACTOR_ADDR};
+use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_ipld_encoding::tuple::*;
+use fvm_ipld_encoding::CborStore;
+use fvm_ipld_hamt::{BytesKey, Hamt, Sha256};
+use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
+use fvm_shared::version::NetworkVersion;
+use fvm_shared::MethodNum;
+use integer_encoding::VarInt;
+use serde::{Deserialize, Serialize};
+use vm_api::manifest::load_manifest;
+use vm_api::ActorState;
+
+/// Environment variable naming a directory to which a test VM is exported if it's dropped while
+/// its test is failing.
+pub const EXPORT_DIR_ENV: &str = "TEST_VM_EXPORT_DIR";
+
+/// Environment variable naming the bundle CAR whose actors a test VM is exported to run, when
+/// it's exported because its test is failing.
+pub const EXPORT_BUNDLE_ENV: &str = "TEST_VM_EXPORT_BUNDLE";
+
+/// The version of the state tree layout written by exports.
+pub const STATE_TREE_VERSION: u64 = 5;
+
+/// The root of a state tree as stored by a node, linking the actors HAMT and state tree info.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct StateRoot {
+    pub version: u64,
+    pub actors: Cid,
+    pub info: Cid,
+}
+
+/// State tree info, which is empty in this version.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct StateInfo0 {}
+
+/// A message executed by the test VM, and its outcome.
+#[derive(Clone, Debug)]
+pub struct MessageRecord {
+    pub epoch: ChainEpoch,
+    pub from: Address,
+    pub to: Address,
+    /// The sender's call sequence number for the message.
+    pub sequence: u64,
+    pub value: TokenAmount,
+    pub method: MethodNum,
+    pub params: Option<IpldBlock>,
+    /// Whether the message was executed implicitly, as by the system rather than a sender.
+    pub implicit: bool,
+    /// State root before the message was executed.
+    pub pre_state_root: Cid,
+    pub exit_code: ExitCode,
+    pub ret: Option<IpldBlock>,
+    /// State root after the message was executed.
+    pub post_state_root: Cid,
+}
+
+/// Writes a CARv1 file holding every block in a store, with the given roots.
+/// Blocks are written in CID order, so the same store always produces the same file.
+pub fn write_car<W: Write>(store: &MemoryBlockstore, roots: &[Cid], out: W) -> anyhow::Result<()> {
+    #[derive(Serialize)]
+    struct CarHeader<'a> {
+        roots: &'a [Cid],
+        version: u64,
+    }
+
+    let mut out = BufWriter::new(out);
+    let header = fvm_ipld_encoding::to_vec(&CarHeader { roots, version: 1 })?;
+    out.write_all(&header.len().encode_var_vec())?;
+    out.write_all(&header)?;
+    for (cid, data) in store.blocks() {
+        let cid = cid.to_bytes();
+        out.write_all(&(cid.len() + data.len()).encode_var_vec())?;
+        out.write_all(&cid)?;
+        out.write_all(&data)?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Reads a bundle CAR into a store, returning the code CIDs of the actors in its manifest,
+/// which is the CAR's root.
+pub fn load_bundle(store: &MemoryBlockstore, car: &[u8]) -> anyhow::Result<BTreeMap<Type, Cid>> {
+    #[derive(Deserialize)]
+    struct CarHeader {
+        roots: Vec<Cid>,
+        version: u64,
+    }
+
+    let (header_len, n) =
+        usize::decode_var(car).ok_or_else(|| anyhow!("bundle CAR header is truncated"))?;
+    let header: CarHeader = fvm_ipld_encoding::from_slice(
+        car.get(n..n + header_len).ok_or_else(|| anyhow!("bundle CAR header is truncated"))?,
+    )?;
+    if header.version != 1 {
+        return Err(anyhow!("unsupported CAR version {}", header.version));
+    }
+    let manifest = match header.roots[..] {
+        [root] => root,
+        _ => return Err(anyhow!("bundle CAR has {} roots, expected 1", header.roots.len())),
+    };
+
+    let mut offset = n + header_len;
+    while offset < car.len() {
+        let (len, n) = usize::decode_var(&car[offset..])
+            .ok_or_else(|| anyhow!("bundle CAR block is truncated"))?;
+        let section = car
+            .get(offset + n..offset + n + len)
+            .ok_or_else(|| anyhow!("bundle CAR block is truncated"))?;
+        let mut reader = Cursor::new(section);
+        let cid = Cid::read_bytes(&mut reader)?;
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        store.put_keyed(&cid, &data)?;
+        offset += n + len;
+    }
+    load_manifest(store, &manifest)
+}
+
+/// Rewrites a test VM state tree, given by the root of its actors HAMT, to run the actors of a
+/// bundle, and returns the root of the rewritten tree.
+/// Each actor's code CID is replaced by the bundle's code for the same actor type, the system
+/// actor's registry is replaced by the bundle's, and the actors HAMT is wrapped in a state root.
+pub fn replayable_state_root(
+    store: &MemoryBlockstore,
+    actors: &Cid,
+    bundle: &BTreeMap<Type, Cid>,
+) -> anyhow::Result<Cid> {
+    let registry: Vec<(String, Cid)> =
+        bundle.iter().map(|(t, code)| (t.name().to_string(), *code)).collect();
+    let registry = store.put_cbor(&registry, Code::Blake2b256)?;
+
+    let tree = Hamt::<_, ActorState, BytesKey, Sha256>::load_with_config(
+        actors,
+        store,
+        DEFAULT_HAMT_CONFIG,
+    )?;
+    let mut rewritten =
+        Hamt::<_, ActorState, BytesKey, Sha256>::new_with_config(store, DEFAULT_HAMT_CONFIG);
+    tree.for_each(|key, actor| {
+        let typ = ACTOR_TYPES
+            .get(&actor.code)
+            .ok_or_else(|| anyhow!("actor code {} isn't a builtin actor", actor.code))?;
+        let mut actor = actor.clone();
+        actor.code =
+            *bundle.get(typ).ok_or_else(|| anyhow!("no {} actor in bundle", typ.name()))?;
+        if key.0 == SYSTEM_ACTOR_ADDR.to_bytes() {
+            let mut system: SystemState = store
+                .get_cbor(&actor.state)?
+                .ok_or_else(|| anyhow!("system actor state {} not found", actor.state))?;
+            system.builtin_actors = registry;
+            actor.state = store.put_cbor(&system, Code::Blake2b256)?;
+        }
+        rewritten.set(key.clone(), actor)?;
+        Ok(())
+    })?;
+
+    let info = store.put_cbor(&StateInfo0::default(), Code::Blake2b256)?;
+    let root = StateRoot { version: STATE_TREE_VERSION, actors: rewritten.flush()?, info };
+    Ok(store.put_cbor(&root, Code::Blake2b256)?)
+}
+
+/// Writes `<name>.car` and `<name>.json` to a directory, creating it if necessary.
+/// The state roots given are those of the actors HAMT, which are rewritten with
+/// `replayable_state_root` to run the bundle's actors.
+pub fn write_export(
+    dir: &Path,
+    name: &str,
+    store: &MemoryBlockstore,
+    network_version: NetworkVersion,
+    bundle: &BTreeMap<Type, Cid>,
+    state_root: Cid,
+    messages: &[MessageRecord],
+) -> anyhow::Result<()> {
+    // Consecutive messages share state roots, so each is rewritten once.
+    let mut rewritten = HashMap::new();
+    let mut replayable = |root: &Cid| -> anyhow::Result<Cid> {
+        if let Some(r) = rewritten.get(root) {
+            return Ok(*r);
+        }
+        let r = replayable_state_root(store, root, bundle)?;
+        rewritten.insert(*root, r);
+        Ok(r)
+    };
+    let state_root = replayable(&state_root)?;
+    let messages = messages
+        .iter()
+        .map(|msg| {
+            let pre = replayable(&msg.pre_state_root)?;
+            let post = replayable(&msg.post_state_root)?;
+            Ok(MessageJson::new(msg, pre, post))
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    fs::create_dir_all(dir)?;
+    write_car(store, &[state_root], File::create(dir.join(format!("{}.car", name)))?)?;
+
+    let sidecar = Sidecar {
+        network_version: network_version.into(),
+        state_root: state_root.to_string(),
+        messages,
+    };
+    let mut out = BufWriter::new(File::create(dir.join(format!("{}.json", name)))?);
+    serde_json::to_writer_pretty(&mut out, &sidecar)?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Writes the counters incremented by each actor to `<name>.counters.json` in a directory,
+/// keyed by actor ID.
+#[cfg(feature = "metrics")]
+pub fn write_counters(
+    dir: &Path,
+    name: &str,
+    counters: &std::collections::BTreeMap<fvm_shared::ActorID, Counters>,
+) -> anyhow::Result<()> {
+    fs::create_dir_all(dir)?;
+    let mut out = BufWriter::new(File::create(dir.join(format!("{}.counters.json", name)))?);
+    serde_json::to_writer_pretty(&mut out, counters)?;
+    out.flush()?;
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct Sidecar {
+    network_version: u32,
+    state_root: String,
+    messages: Vec<MessageJson>,
+}
+
+#[derive(Serialize)]
+struct MessageJson {
+    epoch: ChainEpoch,
+    from: String,
+    to: String,
+    sequence: u64,
+    value: String,
+    method: MethodNum,
+    params: Option<BlockJson>,
+    implicit: bool,
+    pre_state_root: String,
+    receipt: ReceiptJson,
+}
+
+#[derive(Serialize)]
+struct ReceiptJson {
+    exit_code: u32,
+    #[serde(rename = "return")]
+    ret: Option<BlockJson>,
+    post_state_root: String,
+}
+
+/// An IPLD block with its data hex-encoded.
+#[derive(Serialize)]
+struct BlockJson {
+    codec: u64,
+    data: String,
+}
+
+impl From<&IpldBlock> for BlockJson {
+    fn from(block: &IpldBlock) -> Self {
+        Self { codec: block.codec, data: hex::encode(&block.data) }
+    }
+}
+
+impl MessageJson {
+    fn new(msg: &MessageRecord, pre_state_root: Cid, post_state_root: Cid) -> Self {
+        Self {
+            epoch: msg.epoch,
+            from: msg.from.to_string(),
+            to: msg.to.to_string(),
+            sequence: msg.sequence,
+            value: msg.value.atto().to_string(),
+            method: msg.method,
+            params: msg.params.as_ref().map(BlockJson::from),
+            implicit: msg.implicit,
+            pre_state_root: pre_state_root.to_string(),
+            receipt: ReceiptJson {
+                exit_code: msg.exit_code.value(),
+                ret: msg.ret.as_ref().map(BlockJson::from),
+                post_state_root: post_state_root.to_string(),
+            },
+        }
+    }
+}
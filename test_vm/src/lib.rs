@@ -1,25 +1,19 @@
 use cid::multihash::Code;
 use cid::Cid;
-use fil_actor_account::State as AccountState;
-use fil_actor_cron::{Entry as CronEntry, State as CronState};
-use fil_actor_datacap::State as DataCapState;
 use fil_actor_init::{ExecReturn, State as InitState};
-use fil_actor_market::{Method as MarketMethod, State as MarketState};
-use fil_actor_power::{Method as MethodPower, State as PowerState};
-use fil_actor_reward::State as RewardState;
-use fil_actor_system::State as SystemState;
-use fil_actor_verifreg::State as VerifRegState;
 use fil_actors_runtime::cbor::serialize;
 use fil_actors_runtime::runtime::builtins::Type;
-use fil_actors_runtime::runtime::{Policy, Primitives, EMPTY_ARR_CID};
+#[cfg(feature = "metrics")]
+use fil_actors_runtime::runtime::metrics::Counters;
+#[cfg(feature = "send-trace")]
+use fil_actors_runtime::runtime::send_trace::SendTrace;
+use fil_actors_runtime::runtime::{Policy, Primitives};
 use fil_actors_runtime::test_blockstores::MemoryBlockstore;
-use fil_actors_runtime::DATACAP_TOKEN_ACTOR_ADDR;
 use fil_actors_runtime::{test_utils::*, Map2, DEFAULT_HAMT_CONFIG};
-use fil_actors_runtime::{
-    BURNT_FUNDS_ACTOR_ADDR, CRON_ACTOR_ADDR, EAM_ACTOR_ADDR, INIT_ACTOR_ADDR, REWARD_ACTOR_ADDR,
-    STORAGE_MARKET_ACTOR_ADDR, STORAGE_POWER_ACTOR_ADDR, SYSTEM_ACTOR_ADDR,
-    VERIFIED_REGISTRY_ACTOR_ADDR,
-};
+use fil_actors_runtime::{INIT_ACTOR_ADDR, SYSTEM_ACTOR_ADDR};
+#[cfg(feature = "metrics")]
+use fil_builtin_actors_state::counters::derive_counters;
+use fil_builtin_actors_state::genesis::{singleton_actors, GenesisConfig};
 use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_ipld_encoding::CborStore;
@@ -29,20 +23,23 @@ use fvm_shared::bigint::Zero;
 use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
-use fvm_shared::sector::StoragePower;
 use fvm_shared::version::NetworkVersion;
-use fvm_shared::{MethodNum, METHOD_SEND};
+use fvm_shared::{ActorID, MethodNum, METHOD_SEND};
 use serde::ser;
 use std::cell::{RefCell, RefMut};
 use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
 use std::rc::Rc;
+use vm_api::manifest::put_manifest;
 use vm_api::trace::InvocationTrace;
-use vm_api::{new_actor, ActorState, MessageResult, MockPrimitives, VMError, VM};
+use vm_api::{ActorState, MessageResult, MockPrimitives, VMError, VM};
 
 use vm_api::util::{get_state, serialize_ok};
 
 mod constants;
 pub use constants::*;
+mod export;
+pub use export::*;
 mod messaging;
 pub use messaging::*;
 
@@ -54,6 +51,14 @@ pub struct TestVM {
     actors_dirty: RefCell<bool>,
     actors_cache: RefCell<HashMap<Address, ActorState>>,
     invocations: RefCell<Vec<InvocationTrace>>,
+    // Every message executed, for export.
+    messages: RefCell<Vec<MessageRecord>>,
+    // Sends made by the last message, printed if a test fails.
+    #[cfg(feature = "send-trace")]
+    send_trace: RefCell<SendTrace>,
+    // Counters incremented by each actor, by ID, in messages that succeeded.
+    #[cfg(feature = "metrics")]
+    counters: RefCell<BTreeMap<ActorID, Counters>>,
     // MachineContext equivalents
     network_version: NetworkVersion,
     curr_epoch: RefCell<ChainEpoch>,
@@ -81,6 +86,11 @@ impl TestVM {
             network_version: NetworkVersion::V16,
             curr_epoch: RefCell::new(ChainEpoch::zero()),
             invocations: RefCell::new(vec![]),
+            messages: RefCell::new(vec![]),
+            #[cfg(feature = "send-trace")]
+            send_trace: RefCell::new(SendTrace::default()),
+            #[cfg(feature = "metrics")]
+            counters: RefCell::new(BTreeMap::new()),
             base_fee: RefCell::new(TokenAmount::zero()),
             timestamp: RefCell::new(0),
         }
@@ -95,63 +105,24 @@ impl TestVM {
         let v = TestVM::new(Rc::clone(&store));
         v.set_circulating_supply(&reward_total + &faucet_total);
 
-        // system
-        let sys_st = SystemState::new(&store).unwrap();
-        let sys_head = v.put_store(&sys_st);
-        let sys_value = faucet_total.clone(); // delegate faucet funds to system so we can construct faucet by sending to bls addr
-        v.set_actor(
-            &SYSTEM_ACTOR_ADDR,
-            new_actor(*SYSTEM_ACTOR_CODE_ID, sys_head, 0, sys_value, None),
-        );
+        // singletons
+        let manifest = put_manifest(store.as_ref(), &ACTOR_CODES).unwrap();
+        let config = GenesisConfig {
+            network_name: "integration-test".to_string(),
+            reward_balance: reward_total,
+            verifreg_root: TEST_VERIFREG_ROOT_ADDR,
+            // The root multisig also governs the reward actor's treasury split.
+            reward_governor: Some(TEST_VERIFREG_ROOT_ADDR),
+            ..Default::default()
+        };
+        for (addr, actor) in singleton_actors(store.as_ref(), &manifest, &config).unwrap() {
+            v.set_actor(&addr, actor);
+        }
+        // delegate faucet funds to system so we can construct faucet by sending to bls addr
+        let mut system = v.actor(&SYSTEM_ACTOR_ADDR).unwrap();
+        system.balance = faucet_total.clone();
+        v.set_actor(&SYSTEM_ACTOR_ADDR, system);
 
-        // init
-        let init_st = InitState::new(&store, "integration-test".to_string()).unwrap();
-        let init_head = v.put_store(&init_st);
-        v.set_actor(
-            &INIT_ACTOR_ADDR,
-            new_actor(*INIT_ACTOR_CODE_ID, init_head, 0, TokenAmount::zero(), None),
-        );
-
-        // reward
-
-        let reward_head = v.put_store(&RewardState::new(StoragePower::zero()));
-        v.set_actor(
-            &REWARD_ACTOR_ADDR,
-            new_actor(*REWARD_ACTOR_CODE_ID, reward_head, 0, reward_total, None),
-        );
-
-        // cron
-        let builtin_entries = vec![
-            CronEntry {
-                receiver: STORAGE_POWER_ACTOR_ADDR,
-                method_num: MethodPower::OnEpochTickEnd as u64,
-            },
-            CronEntry {
-                receiver: STORAGE_MARKET_ACTOR_ADDR,
-                method_num: MarketMethod::CronTick as u64,
-            },
-        ];
-        let cron_head = v.put_store(&CronState { entries: builtin_entries });
-        v.set_actor(
-            &CRON_ACTOR_ADDR,
-            new_actor(*CRON_ACTOR_CODE_ID, cron_head, 0, TokenAmount::zero(), None),
-        );
-
-        // power
-        let power_head = v.put_store(&PowerState::new(&v.store).unwrap());
-        v.set_actor(
-            &STORAGE_POWER_ACTOR_ADDR,
-            new_actor(*POWER_ACTOR_CODE_ID, power_head, 0, TokenAmount::zero(), None),
-        );
-
-        // market
-        let market_head = v.put_store(&MarketState::new(&v.store).unwrap());
-        v.set_actor(
-            &STORAGE_MARKET_ACTOR_ADDR,
-            new_actor(*MARKET_ACTOR_CODE_ID, market_head, 0, TokenAmount::zero(), None),
-        );
-
-        // verifreg
         // initialize verifreg root signer
         v.execute_message(
             &INIT_ACTOR_ADDR,
@@ -193,33 +164,6 @@ impl TestVM {
             .unwrap();
         let root_msig_addr = msig_ctor_ret.id_address;
         assert_eq!(TEST_VERIFREG_ROOT_ADDR, root_msig_addr);
-        // verifreg
-        let verifreg_head = v.put_store(&VerifRegState::new(&v.store, root_msig_addr).unwrap());
-        v.set_actor(
-            &VERIFIED_REGISTRY_ACTOR_ADDR,
-            new_actor(*VERIFREG_ACTOR_CODE_ID, verifreg_head, 0, TokenAmount::zero(), None),
-        );
-
-        // Ethereum Address Manager
-        v.set_actor(
-            &EAM_ACTOR_ADDR,
-            new_actor(*EAM_ACTOR_CODE_ID, EMPTY_ARR_CID, 0, TokenAmount::zero(), None),
-        );
-
-        // datacap
-        let datacap_head =
-            v.put_store(&DataCapState::new(&v.store, VERIFIED_REGISTRY_ACTOR_ADDR).unwrap());
-        v.set_actor(
-            &DATACAP_TOKEN_ACTOR_ADDR,
-            new_actor(*DATACAP_TOKEN_ACTOR_CODE_ID, datacap_head, 0, TokenAmount::zero(), None),
-        );
-
-        // burnt funds
-        let burnt_funds_head = v.put_store(&AccountState { address: BURNT_FUNDS_ACTOR_ADDR });
-        v.set_actor(
-            &BURNT_FUNDS_ACTOR_ADDR,
-            new_actor(*ACCOUNT_ACTOR_CODE_ID, burnt_funds_head, 0, TokenAmount::zero(), None),
-        );
 
         // create a faucet with 1 billion FIL for setting up test accounts
         v.execute_message(
@@ -266,24 +210,80 @@ impl TestVM {
         self.actors_dirty.replace(false);
     }
 
-    fn actor_map(&self) -> Map2<&MemoryBlockstore, Address, ActorState> {
-        Map2::load(self.store.as_ref(), &self.checkpoint(), DEFAULT_HAMT_CONFIG, "actors").unwrap()
+    /// Returns the messages executed so far, with their receipts.
+    pub fn messages(&self) -> Vec<MessageRecord> {
+        self.messages.borrow().clone()
     }
-}
 
-impl VM for TestVM {
-    fn blockstore(&self) -> &dyn Blockstore {
-        self.store.as_ref()
+    /// Exports the blockstore and message log to `<name>.car` and `<name>.json` in a directory,
+    /// for replay against a node implementation.
+    /// The exported state runs the actors of the bundle CAR given, which is added to the store.
+    pub fn export(&self, dir: &Path, name: &str, bundle: &[u8]) -> anyhow::Result<()> {
+        let state_root = self.checkpoint();
+        let bundle = load_bundle(&self.store, bundle)?;
+        write_export(
+            dir,
+            name,
+            &self.store,
+            self.network_version,
+            &bundle,
+            state_root,
+            &self.messages.borrow(),
+        )?;
+        #[cfg(feature = "metrics")]
+        {
+            write_counters(dir, name, &self.counters.borrow())?;
+            write_counters(dir, &format!("{}.state", name), &self.state_counters())?;
+        }
+        Ok(())
     }
 
-    fn execute_message(
+    /// Returns the counters incremented by each actor, by actor ID.
+    /// Increments made by invocations that failed are discarded along with their state changes.
+    #[cfg(feature = "metrics")]
+    pub fn counters(&self) -> BTreeMap<ActorID, Counters> {
+        self.counters.borrow().clone()
+    }
+
+    /// Returns the counters incremented by an actor.
+    #[cfg(feature = "metrics")]
+    pub fn actor_counters(&self, id: ActorID) -> Counters {
+        self.counters.borrow().get(&id).cloned().unwrap_or_default()
+    }
+
+    /// Returns the sum of each counter over every actor.
+    #[cfg(feature = "metrics")]
+    pub fn total_counters(&self) -> Counters {
+        let mut total = Counters::default();
+        for counters in self.counters.borrow().values() {
+            total.merge(counters);
+        }
+        total
+    }
+
+    /// Returns the counters recorded in actor state, by actor ID.
+    /// Unlike those returned by `counters`, these count every message since genesis.
+    #[cfg(feature = "metrics")]
+    pub fn state_counters(&self) -> BTreeMap<ActorID, Counters> {
+        derive_counters(self.store.as_ref(), &ACTOR_TYPES, &self.actor_states()).unwrap()
+    }
+
+    /// Discards all counters, e.g. after setting up a benchmark scenario.
+    #[cfg(feature = "metrics")]
+    pub fn reset_counters(&self) {
+        self.counters.borrow_mut().clear();
+    }
+
+    fn execute(
         &self,
         from: &Address,
         to: &Address,
         value: &TokenAmount,
         method: MethodNum,
         params: Option<IpldBlock>,
+        implicit: bool,
     ) -> Result<MessageResult, VMError> {
+        let pre_state_root = self.checkpoint();
         let from_id = &self.resolve_id_address(from).unwrap();
         // TODO: for non-implicit calls validate that from_id is either the
         // account actor or the ethereum account actor and error otherwise
@@ -297,8 +297,24 @@ impl VM for TestVM {
             a.code = *ETHACCOUNT_ACTOR_CODE_ID;
         }
         self.set_actor(from_id, a);
+        #[cfg(feature = "send-trace")]
+        self.send_trace.borrow_mut().clear();
 
         let prior_root = self.checkpoint();
+        let mut record = MessageRecord {
+            epoch: self.epoch(),
+            from: *from,
+            to: *to,
+            sequence: call_seq,
+            value: value.clone(),
+            method,
+            params: params.clone(),
+            implicit,
+            pre_state_root,
+            exit_code: ExitCode::OK,
+            ret: None,
+            post_state_root: prior_root,
+        };
 
         // big.Mul(big.NewInt(1e9), big.NewInt(1e18))
         // make top level context with internal context
@@ -333,20 +349,45 @@ impl VM for TestVM {
             invocs.push(invoc);
             invocs
         });
-        match res {
+        let result = match res {
             Err(mut ae) => {
                 self.rollback(prior_root);
-                Ok(MessageResult {
+                MessageResult {
                     code: ae.exit_code(),
                     message: ae.msg().to_string(),
                     ret: ae.take_data(),
-                })
+                }
             }
             Ok(ret) => {
-                self.checkpoint();
-                Ok(MessageResult { code: ExitCode::OK, message: "OK".to_string(), ret })
+                record.post_state_root = self.checkpoint();
+                MessageResult { code: ExitCode::OK, message: "OK".to_string(), ret }
             }
-        }
+        };
+        record.exit_code = result.code;
+        record.ret = result.ret.clone();
+        self.messages.borrow_mut().push(record);
+        Ok(result)
+    }
+
+    fn actor_map(&self) -> Map2<&MemoryBlockstore, Address, ActorState> {
+        Map2::load(self.store.as_ref(), &self.checkpoint(), DEFAULT_HAMT_CONFIG, "actors").unwrap()
+    }
+}
+
+impl VM for TestVM {
+    fn blockstore(&self) -> &dyn Blockstore {
+        self.store.as_ref()
+    }
+
+    fn execute_message(
+        &self,
+        from: &Address,
+        to: &Address,
+        value: &TokenAmount,
+        method: MethodNum,
+        params: Option<IpldBlock>,
+    ) -> Result<MessageResult, VMError> {
+        self.execute(from, to, value, method, params, false)
     }
 
     fn execute_message_implicit(
@@ -357,7 +398,7 @@ impl VM for TestVM {
         method: MethodNum,
         params: Option<IpldBlock>,
     ) -> Result<MessageResult, VMError> {
-        self.execute_message(from, to, value, method, params)
+        self.execute(from, to, value, method, params, true)
     }
     fn resolve_id_address(&self, address: &Address) -> Option<Address> {
         let st: InitState = get_state(self, &INIT_ACTOR_ADDR).unwrap();
@@ -447,3 +488,26 @@ impl VM for TestVM {
         &self.primitives
     }
 }
+
+impl Drop for TestVM {
+    fn drop(&mut self) {
+        if !std::thread::panicking() {
+            return;
+        }
+        if let Some(dir) = std::env::var_os(EXPORT_DIR_ENV) {
+            let Some(bundle) = std::env::var_os(EXPORT_BUNDLE_ENV) else {
+                eprintln!("not exporting test VM: {} must name a bundle CAR", EXPORT_BUNDLE_ENV);
+                return;
+            };
+            // Name the export after the failing test, which is the name of its thread.
+            let name = std::thread::current().name().unwrap_or("test_vm").replace("::", "-");
+            let exported = std::fs::read(&bundle)
+                .map_err(anyhow::Error::from)
+                .and_then(|bundle| self.export(Path::new(&dir), &name, &bundle));
+            match exported {
+                Ok(()) => eprintln!("exported test VM to {}/{}.car", dir.to_string_lossy(), name),
+                Err(e) => eprintln!("failed to export test VM: {}", e),
+            }
+        }
+    }
+}
@@ -20,7 +20,7 @@ use fil_actor_verifreg::Actor as VerifregActor;
 use fil_actors_runtime::runtime::builtins::Type;
 use fil_actors_runtime::runtime::{
     ActorCode, DomainSeparationTag, MessageInfo, Policy, Primitives, Runtime, RuntimePolicy,
-    EMPTY_ARR_CID,
+    SignatureVerifyInfo, EMPTY_ARR_CID,
 };
 use fil_actors_runtime::{actor_error, SendError};
 use fil_actors_runtime::{test_utils::*, SYSTEM_ACTOR_ID};
@@ -36,7 +36,7 @@ use fvm_shared::clock::ChainEpoch;
 use fvm_shared::consensus::ConsensusFault;
 use fvm_shared::crypto::hash::SupportedHashes;
 use fvm_shared::crypto::signature::{
-    Signature, SECP_PUB_LEN, SECP_SIG_LEN, SECP_SIG_MESSAGE_HASH_SIZE,
+    Signature, BLS_PUB_LEN, BLS_SIG_LEN, SECP_PUB_LEN, SECP_SIG_LEN, SECP_SIG_MESSAGE_HASH_SIZE,
 };
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
@@ -230,6 +230,8 @@ impl<'invocation> InvocationCtx<'invocation> {
 
     pub fn invoke(&mut self) -> Result<Option<IpldBlock>, ActorError> {
         let prior_root = self.v.checkpoint();
+        #[cfg(feature = "metrics")]
+        let prior_counters = self.v.counters.borrow().clone();
 
         // Transfer funds
         let mut from_actor = self.v.actor(&Address::new_id(self.msg.from)).unwrap();
@@ -296,7 +298,9 @@ impl<'invocation> InvocationCtx<'invocation> {
             res = Err(actor_error!(assertion_failed, "failed to validate caller"));
         }
         if res.is_err() {
-            self.v.rollback(prior_root)
+            self.v.rollback(prior_root);
+            #[cfg(feature = "metrics")]
+            self.v.counters.replace(prior_counters);
         };
 
         res
@@ -505,6 +509,13 @@ impl<'invocation> Runtime for InvocationCtx<'invocation> {
         }
 
         let from_id = self.resolve_address(&self.to()).unwrap();
+        #[cfg(feature = "send-trace")]
+        let trace_index = self.v.send_trace.borrow_mut().begin(
+            Address::new_id(from_id),
+            *to,
+            method,
+            value.clone(),
+        );
 
         let new_actor_msg = InternalMessage { from: from_id, to: *to, value, method, params };
         let mut new_ctx = InvocationCtx {
@@ -525,10 +536,10 @@ impl<'invocation> Runtime for InvocationCtx<'invocation> {
             subinvocs
         });
 
-        Ok(Response {
-            exit_code: res.as_ref().err().map(|e| e.exit_code()).unwrap_or(ExitCode::OK),
-            return_data: res.unwrap_or_else(|mut e| e.take_data()),
-        })
+        let exit_code = res.as_ref().err().map(|e| e.exit_code()).unwrap_or(ExitCode::OK);
+        #[cfg(feature = "send-trace")]
+        self.v.send_trace.borrow_mut().end(trace_index, Ok(exit_code));
+        Ok(Response { exit_code, return_data: res.unwrap_or_else(|mut e| e.take_data()) })
     }
 
     fn get_randomness_from_tickets(
@@ -651,6 +662,12 @@ impl<'invocation> Runtime for InvocationCtx<'invocation> {
     fn read_only(&self) -> bool {
         self.read_only
     }
+
+    #[cfg(feature = "metrics")]
+    fn increment_counter(&self, name: &'static str, by: u64) {
+        let id = self.to().id().unwrap();
+        self.v.counters.borrow_mut().entry(id).or_default().increment(name, by);
+    }
 }
 
 impl Primitives for InvocationCtx<'_> {
@@ -663,6 +680,22 @@ impl Primitives for InvocationCtx<'_> {
         self.v.primitives().verify_signature(signature, signer, plaintext)
     }
 
+    fn verify_bls_aggregate(
+        &self,
+        aggregate_sig: &[u8; BLS_SIG_LEN],
+        pub_keys: &[[u8; BLS_PUB_LEN]],
+        plaintexts: &[&[u8]],
+    ) -> Result<(), anyhow::Error> {
+        self.v.primitives().verify_bls_aggregate(aggregate_sig, pub_keys, plaintexts)
+    }
+
+    fn verify_signatures_batch(
+        &self,
+        batch: &[SignatureVerifyInfo],
+    ) -> Result<Vec<bool>, anyhow::Error> {
+        self.v.primitives().verify_signatures_batch(batch)
+    }
+
     fn hash_blake2b(&self, data: &[u8]) -> [u8; 32] {
         self.v.primitives().hash_blake2b(data)
     }
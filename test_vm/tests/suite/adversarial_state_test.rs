@@ -0,0 +1,10 @@
+use fil_actors_integration_tests::tests::adversarial_deadline_cron_test;
+use fil_actors_runtime::test_blockstores::MemoryBlockstore;
+use test_vm::TestVM;
+
+#[test]
+fn adversarial_deadline_cron() {
+    let store = MemoryBlockstore::new();
+    let v = TestVM::new_with_singletons(store);
+    adversarial_deadline_cron_test(&v);
+}
@@ -0,0 +1,10 @@
+use fil_actors_integration_tests::tests::state_corpus_test;
+use fil_actors_runtime::test_blockstores::MemoryBlockstore;
+use test_vm::TestVM;
+
+#[test]
+fn state_corpus() {
+    let store = MemoryBlockstore::new();
+    let v = TestVM::new_with_singletons(store);
+    state_corpus_test(&v);
+}
@@ -1,7 +1,7 @@
 use fil_actors_integration_tests::tests::{
-    evm_call_test, evm_create_test, evm_delegatecall_test, evm_empty_initcode_test,
-    evm_eth_create_external_test, evm_init_revert_data_test, evm_staticcall_delegatecall_test,
-    evm_staticcall_test,
+    evm_call_test, evm_create_deterministic_test, evm_create_test, evm_delegatecall_test,
+    evm_empty_initcode_test, evm_eth_create_external_test, evm_init_revert_data_test,
+    evm_staticcall_delegatecall_test, evm_staticcall_test,
 };
 use fil_actors_runtime::test_blockstores::MemoryBlockstore;
 use test_vm::TestVM;
@@ -20,6 +20,13 @@ fn evm_create() {
     evm_create_test(&v);
 }
 
+#[test]
+fn evm_create_deterministic() {
+    let store = MemoryBlockstore::new();
+    let v = TestVM::new_with_singletons(store);
+    evm_create_deterministic_test(&v);
+}
+
 #[test]
 fn evm_eth_create_external() {
     let store = MemoryBlockstore::new();
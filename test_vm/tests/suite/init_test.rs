@@ -1,4 +1,6 @@
-use fil_actors_integration_tests::tests::placeholder_deploy_test;
+use fil_actors_integration_tests::tests::{
+    deterministic_id_allocation_test, placeholder_deploy_test,
+};
 use fil_actors_runtime::test_blockstores::MemoryBlockstore;
 use test_vm::TestVM;
 
@@ -9,3 +11,11 @@ fn placeholder_deploy() {
 
     placeholder_deploy_test(&v);
 }
+
+#[test]
+fn deterministic_id_allocation() {
+    let store = MemoryBlockstore::new();
+    let v = TestVM::new_with_singletons(store);
+
+    deterministic_id_allocation_test(&v);
+}
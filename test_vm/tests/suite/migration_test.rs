@@ -0,0 +1,56 @@
+use fil_actors_integration_tests::tests::{
+    migrate_cron_state_test, migrate_market_state_test, migrate_miner_state_test,
+    migrate_multisig_state_test, migrate_paych_state_test, migrate_power_state_test,
+    migrate_reward_state_test,
+};
+use fil_actors_runtime::test_blockstores::MemoryBlockstore;
+use test_vm::TestVM;
+
+#[test]
+fn migrate_cron_state() {
+    let store = MemoryBlockstore::new();
+    let v = TestVM::new_with_singletons(store);
+    migrate_cron_state_test(&v);
+}
+
+#[test]
+fn migrate_power_state() {
+    let store = MemoryBlockstore::new();
+    let v = TestVM::new_with_singletons(store);
+    migrate_power_state_test(&v);
+}
+
+#[test]
+fn migrate_market_state() {
+    let store = MemoryBlockstore::new();
+    let v = TestVM::new_with_singletons(store);
+    migrate_market_state_test(&v);
+}
+
+#[test]
+fn migrate_miner_state() {
+    let store = MemoryBlockstore::new();
+    let v = TestVM::new_with_singletons(store);
+    migrate_miner_state_test(&v);
+}
+
+#[test]
+fn migrate_multisig_state() {
+    let store = MemoryBlockstore::new();
+    let v = TestVM::new_with_singletons(store);
+    migrate_multisig_state_test(&v);
+}
+
+#[test]
+fn migrate_paych_state() {
+    let store = MemoryBlockstore::new();
+    let v = TestVM::new_with_singletons(store);
+    migrate_paych_state_test(&v);
+}
+
+#[test]
+fn migrate_reward_state() {
+    let store = MemoryBlockstore::new();
+    let v = TestVM::new_with_singletons(store);
+    migrate_reward_state_test(&v);
+}
@@ -1,22 +1,29 @@
+mod adversarial_state_test;
 mod authenticate_message_test;
 mod batch_onboarding;
 mod batch_onboarding_deals_test;
 mod change_beneficiary_test;
 mod change_owner_test;
 mod commit_post_test;
+mod corpus_test;
 mod datacap_tests;
 mod evm_test;
 mod extend_sectors_test;
 mod init_test;
 mod market_miner_withdrawal_test;
+mod migration_test;
 mod multisig_test;
+mod paych_test;
 mod power_scenario_tests;
 mod prove_commit3_test;
 mod publish_deals_test;
+mod reachability_test;
 mod replica_update3_test;
 mod replica_update_test;
+mod reward_treasury_test;
 mod terminate_test;
 mod test_vm_test;
+mod verified_aggregate_onboarding_test;
 mod verified_claim_test;
 mod verifreg_remove_datacap_test;
 mod withdraw_balance_test;
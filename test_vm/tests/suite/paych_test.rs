@@ -0,0 +1,10 @@
+use fil_actors_integration_tests::tests::paych_evm_wallet_payer_test;
+use fil_actors_runtime::test_blockstores::MemoryBlockstore;
+use test_vm::TestVM;
+
+#[test]
+fn paych_evm_wallet_payer() {
+    let store = MemoryBlockstore::new();
+    let v = TestVM::new_with_singletons(store);
+    paych_evm_wallet_payer_test(&v);
+}
@@ -1,8 +1,8 @@
 use fil_actors_integration_tests::tests::{
     all_deals_are_good_test, psd_all_deals_are_bad_test, psd_bad_piece_size_test, psd_bad_sig_test,
-    psd_client_address_cannot_be_resolved_test, psd_deal_duration_too_long_test,
-    psd_duplicate_deal_in_batch_test, psd_duplicate_deal_in_state_test,
-    psd_mismatched_provider_test, psd_no_client_lockup_test,
+    psd_client_address_cannot_be_resolved_test, psd_contract_client_typed_data_test,
+    psd_deal_duration_too_long_test, psd_duplicate_deal_in_batch_test,
+    psd_duplicate_deal_in_state_test, psd_mismatched_provider_test, psd_no_client_lockup_test,
     psd_not_enough_client_lockup_for_batch_test, psd_not_enough_provider_lockup_for_batch_test,
     psd_random_assortment_of_failures_test, psd_start_time_in_past_test,
     psd_valid_deals_with_ones_longer_than_540_test, psd_verified_deal_fails_getting_datacap_test,
@@ -101,6 +101,13 @@ fn psd_bad_sig() {
     psd_bad_sig_test(&v);
 }
 
+#[test]
+fn psd_contract_client_typed_data() {
+    let store = MemoryBlockstore::new();
+    let v = TestVM::new_with_singletons(store);
+    psd_contract_client_typed_data_test(&v);
+}
+
 #[test]
 fn psd_all_deals_are_good() {
     let store = MemoryBlockstore::new();
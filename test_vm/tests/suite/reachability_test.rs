@@ -0,0 +1,10 @@
+use fil_actors_integration_tests::tests::state_reachability_test;
+use fil_actors_runtime::test_blockstores::MemoryBlockstore;
+use test_vm::TestVM;
+
+#[test]
+fn state_reachability() {
+    let store = MemoryBlockstore::new();
+    let v = TestVM::new_with_singletons(store);
+    state_reachability_test(&v);
+}
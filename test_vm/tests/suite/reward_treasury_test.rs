@@ -0,0 +1,10 @@
+use fil_actors_integration_tests::tests::reward_treasury_split_test;
+use fil_actors_runtime::test_blockstores::MemoryBlockstore;
+use test_vm::TestVM;
+
+#[test]
+fn reward_treasury_split() {
+    let store = MemoryBlockstore::new();
+    let v = TestVM::new_with_singletons(store);
+    reward_treasury_split_test(&v);
+}
@@ -1,18 +1,31 @@
+use std::collections::BTreeMap;
+
+use cid::Cid;
 use fil_actor_account::State as AccountState;
-use fil_actors_integration_tests::util::{assert_invariants, check_invariants};
+use fil_actor_system::State as SystemState;
+use fil_actors_integration_tests::util::{
+    assert_invariants, check_invariants, faucet, next_actor_id,
+};
+use fil_actors_runtime::runtime::builtins::Type;
 use fil_actors_runtime::runtime::Policy;
 use fil_actors_runtime::test_blockstores::MemoryBlockstore;
 use fil_actors_runtime::test_utils::{
-    make_identity_cid, ACCOUNT_ACTOR_CODE_ID, PAYCH_ACTOR_CODE_ID,
+    make_identity_cid, ACCOUNT_ACTOR_CODE_ID, ACTOR_CODES, PAYCH_ACTOR_CODE_ID,
 };
+use fil_actors_runtime::{DEFAULT_HAMT_CONFIG, SYSTEM_ACTOR_ADDR};
+use fvm_ipld_encoding::CborStore;
+use fvm_ipld_hamt::{BytesKey, Hamt, Sha256};
 use fvm_shared::address::Address;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
 use fvm_shared::METHOD_SEND;
+use integer_encoding::VarInt;
 use num_traits::Zero;
-use test_vm::{TestVM, FIRST_TEST_USER_ADDR, TEST_FAUCET_ADDR};
+use serde::Deserialize;
+use test_vm::{write_car, StateInfo0, StateRoot, TestVM, STATE_TREE_VERSION};
+use vm_api::manifest::put_manifest;
 use vm_api::util::{get_state, pk_addrs_from};
-use vm_api::{new_actor, VM};
+use vm_api::{new_actor, ActorState, VM};
 
 #[test]
 fn state_control() {
@@ -76,16 +89,17 @@ fn test_sent() {
     let v = TestVM::new_with_singletons(store);
 
     // send to uninitialized account actor
+    let first_id = next_actor_id(&v);
     let addr1 = Address::new_bls(&[1; fvm_shared::address::BLS_PUB_LEN]).unwrap();
-    v.execute_message(&TEST_FAUCET_ADDR, &addr1, &TokenAmount::from_atto(42u8), METHOD_SEND, None)
+    v.execute_message(&faucet(&v), &addr1, &TokenAmount::from_atto(42u8), METHOD_SEND, None)
         .unwrap();
-    let expect_id_addr1 = Address::new_id(FIRST_TEST_USER_ADDR);
+    let expect_id_addr1 = Address::new_id(first_id);
     assert_account_actor(0, TokenAmount::from_atto(42u8), addr1, &v, expect_id_addr1);
 
     // send from this account actor to another uninit account actor
     let addr2 = Address::new_bls(&[2; fvm_shared::address::BLS_PUB_LEN]).unwrap();
     v.execute_message(&addr1, &addr2, &TokenAmount::from_atto(41u8), METHOD_SEND, None).unwrap();
-    let expect_id_addr2 = Address::new_id(FIRST_TEST_USER_ADDR + 1);
+    let expect_id_addr2 = Address::new_id(first_id + 1);
     assert_account_actor(0, TokenAmount::from_atto(41u8), addr2, &v, expect_id_addr2);
 
     // send between two initialized account actors
@@ -129,3 +143,143 @@ fn test_pk_gen() {
     let first_addr_seeded_six = addrs[0];
     assert_ne!(second_addr_seeded_five, first_addr_seeded_six);
 }
+
+#[test]
+fn export_car_and_sidecar() {
+    let store = MemoryBlockstore::new();
+    let v = TestVM::new_with_singletons(store);
+    let addr = pk_addrs_from(7, 1)[0];
+    v.execute_message(&faucet(&v), &addr, &TokenAmount::from_atto(42u8), METHOD_SEND, None)
+        .unwrap();
+
+    // A bundle with its own code CIDs, which the exported state runs.
+    let bundle_store = MemoryBlockstore::new();
+    let bundle_codes: BTreeMap<Type, Cid> = ACTOR_CODES
+        .keys()
+        .map(|t| (*t, make_identity_cid(format!("bundle/{}", t.name()).as_bytes())))
+        .collect();
+    let manifest = put_manifest(&bundle_store, &bundle_codes).unwrap();
+    let mut bundle = Vec::new();
+    write_car(&bundle_store, &[manifest], &mut bundle).unwrap();
+
+    let dir = std::env::temp_dir().join(format!("test_vm_export_{}", std::process::id()));
+    v.export(&dir, "send", &bundle).unwrap();
+
+    // The CAR holds every block in the store, rooted at the final state root.
+    #[derive(Deserialize)]
+    struct CarHeader {
+        roots: Vec<Cid>,
+        version: u64,
+    }
+    let car = std::fs::read(dir.join("send.car")).unwrap();
+    let (header_len, n) = usize::decode_var(&car).unwrap();
+    let header: CarHeader = fvm_ipld_encoding::from_slice(&car[n..n + header_len]).unwrap();
+    assert_eq!(1, header.roots.len());
+    assert_eq!(1, header.version);
+    let mut offset = n + header_len;
+    let mut blocks = 0;
+    while offset < car.len() {
+        let (len, n) = usize::decode_var(&car[offset..]).unwrap();
+        offset += n + len;
+        blocks += 1;
+    }
+    assert_eq!(v.store.blocks().len(), blocks);
+
+    // The root is a versioned state root whose actors run the bundle's code.
+    let root: StateRoot = v.store.get_cbor(&header.roots[0]).unwrap().unwrap();
+    assert_eq!(STATE_TREE_VERSION, root.version);
+    assert_eq!(Some(StateInfo0::default()), v.store.get_cbor(&root.info).unwrap());
+    let actors = Hamt::<_, ActorState, BytesKey, Sha256>::load_with_config(
+        &root.actors,
+        &*v.store,
+        DEFAULT_HAMT_CONFIG,
+    )
+    .unwrap();
+    let mut count = 0;
+    actors
+        .for_each(|_, actor| {
+            assert!(bundle_codes.values().any(|code| *code == actor.code));
+            count += 1;
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(v.actor_states().len(), count);
+    let system = actors.get(&BytesKey::from(SYSTEM_ACTOR_ADDR.to_bytes())).unwrap().unwrap();
+    assert_eq!(bundle_codes[&Type::System], system.code);
+    let system: SystemState = v.store.get_cbor(&system.state).unwrap().unwrap();
+    let registry: Vec<(String, Cid)> = v.store.get_cbor(&system.builtin_actors).unwrap().unwrap();
+    assert_eq!(
+        bundle_codes.iter().map(|(t, code)| (t.name().to_string(), *code)).collect::<Vec<_>>(),
+        registry
+    );
+
+    // The sidecar logs every message, including those made setting up the singletons.
+    let sidecar: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(dir.join("send.json")).unwrap()).unwrap();
+    let messages = sidecar["messages"].as_array().unwrap();
+    assert_eq!(v.messages().len(), messages.len());
+    let send = messages.last().unwrap();
+    assert_eq!(faucet(&v).to_string(), send["from"]);
+    assert_eq!(addr.to_string(), send["to"]);
+    assert_eq!("42", send["value"]);
+    assert_eq!(0, send["receipt"]["exit_code"]);
+    assert_eq!(header.roots[0].to_string(), send["receipt"]["post_state_root"]);
+    assert_eq!(header.roots[0].to_string(), sidecar["state_root"]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn counts_actor_workload() {
+    use fil_actors_integration_tests::util::{
+        create_accounts, create_miner, market_add_balance, market_publish_deal,
+    };
+    use fil_actors_runtime::{EPOCHS_IN_DAY, STORAGE_MARKET_ACTOR_ID};
+    use fvm_shared::piece::PaddedPieceSize;
+    use fvm_shared::sector::RegisteredPoStProof;
+
+    let store = MemoryBlockstore::new();
+    let v = TestVM::new_with_singletons(store);
+    let addrs = create_accounts(&v, 2, &TokenAmount::from_whole(10_000));
+    let (owner, client) = (addrs[0], addrs[1]);
+    let (miner, _) = create_miner(
+        &v,
+        &owner,
+        &owner,
+        RegisteredPoStProof::StackedDRGWindow32GiBV1P1,
+        &TokenAmount::zero(),
+    );
+    market_add_balance(&v, &client, &client, &TokenAmount::from_whole(100));
+    market_add_balance(&v, &owner, &miner, &TokenAmount::from_whole(100));
+    assert_eq!(0, v.total_counters().get("market.deals_published"));
+
+    let deal_start = v.epoch() + 10 * EPOCHS_IN_DAY;
+    for label in ["deal1", "deal2"] {
+        market_publish_deal(
+            &v,
+            &owner,
+            &client,
+            &miner,
+            label.to_string(),
+            PaddedPieceSize(1 << 30),
+            false,
+            deal_start,
+            180 * EPOCHS_IN_DAY,
+        );
+    }
+    assert_eq!(2, v.actor_counters(STORAGE_MARKET_ACTOR_ID).get("market.deals_published"));
+    assert_eq!(2, v.total_counters().get("market.deals_published"));
+
+    // Counters are dumped as JSON keyed by actor ID.
+    let dump = serde_json::to_value(v.counters()).unwrap();
+    assert_eq!(2, dump[STORAGE_MARKET_ACTOR_ID.to_string()]["market.deals_published"]);
+
+    // The published deals are also recorded in market state, from which they can be derived.
+    let state_counters = v.state_counters();
+    assert_eq!(2, state_counters[&STORAGE_MARKET_ACTOR_ID].get("market.deals_published"));
+
+    v.reset_counters();
+    assert!(v.counters().is_empty());
+    assert_eq!(state_counters, v.state_counters());
+}
@@ -0,0 +1,10 @@
+use fil_actors_integration_tests::tests::verified_aggregate_onboarding_test;
+use fil_actors_runtime::test_blockstores::MemoryBlockstore;
+use test_vm::TestVM;
+
+#[test]
+fn verified_aggregate_onboarding() {
+    let store = MemoryBlockstore::new();
+    let v = TestVM::new_with_singletons(store);
+    verified_aggregate_onboarding_test(&v);
+}
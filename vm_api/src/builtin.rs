@@ -47,4 +47,27 @@ impl Type {
             Type::EthAccount => "ethaccount",
         }
     }
+    /// Returns the type with the given manifest name, if any.
+    pub fn from_name(name: &str) -> Option<Type> {
+        let t = match name {
+            "system" => Type::System,
+            "init" => Type::Init,
+            "cron" => Type::Cron,
+            "account" => Type::Account,
+            "storagepower" => Type::Power,
+            "storageminer" => Type::Miner,
+            "storagemarket" => Type::Market,
+            "paymentchannel" => Type::PaymentChannel,
+            "multisig" => Type::Multisig,
+            "reward" => Type::Reward,
+            "verifiedregistry" => Type::VerifiedRegistry,
+            "datacap" => Type::DataCap,
+            "placeholder" => Type::Placeholder,
+            "evm" => Type::EVM,
+            "eam" => Type::EAM,
+            "ethaccount" => Type::EthAccount,
+            _ => return None,
+        };
+        Some(t)
+    }
 }
@@ -1,11 +1,13 @@
 use std::collections::BTreeMap;
 
 use anyhow::Error;
+use cid::multihash::{Code, MultihashDigest};
 use cid::Cid;
 use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::{
     ipld_block::IpldBlock,
     tuple::{serde_tuple, Deserialize_tuple, Serialize_tuple},
+    DAG_CBOR,
 };
 use fvm_shared::{
     address::Address,
@@ -13,7 +15,10 @@ use fvm_shared::{
     consensus::ConsensusFault,
     crypto::{
         hash::SupportedHashes,
-        signature::{Signature, SECP_PUB_LEN, SECP_SIG_LEN, SECP_SIG_MESSAGE_HASH_SIZE},
+        signature::{
+            Signature, BLS_PUB_LEN, BLS_SIG_LEN, SECP_PUB_LEN, SECP_SIG_LEN,
+            SECP_SIG_MESSAGE_HASH_SIZE,
+        },
     },
     econ::TokenAmount,
     error::ExitCode,
@@ -22,7 +27,7 @@ use fvm_shared::{
         AggregateSealVerifyProofAndInfos, RegisteredSealProof, ReplicaUpdateInfo, SealVerifyInfo,
         WindowPoStVerifyInfo,
     },
-    MethodNum,
+    ActorID, MethodNum,
 };
 
 use builtin::*;
@@ -31,6 +36,7 @@ use trace::*;
 
 pub mod builtin;
 mod error;
+pub mod manifest;
 pub mod trace;
 #[cfg(feature = "testing")]
 pub mod util;
@@ -53,6 +59,26 @@ pub trait VM {
     /// Get the ID for the specified address
     fn resolve_id_address(&self, address: &Address) -> Option<Address>;
 
+    /// Set the ID allocated to the next actor created, with later actors allocated sequential IDs
+    /// from there. This keeps the addresses of actors created by a test stable when setup changes.
+    /// The ID must not be below any ID already allocated.
+    ///
+    /// The default implementation rewrites the init actor's state through the blockstore.
+    fn set_next_actor_id(&self, id: ActorID) {
+        // The init actor (ID 1) has state (address_map, next_id, network_name).
+        let init = Address::new_id(1);
+        let mut actor = self.actor(&init).expect("init actor not found");
+        let block = self.blockstore().get(&actor.state).unwrap().expect("init state not found");
+        let (address_map, next_id, network_name): (Cid, ActorID, String) =
+            fvm_ipld_encoding::from_slice(&block).unwrap();
+        assert!(id >= next_id, "actor ID {} is already allocated, next is {}", id, next_id);
+
+        let block = fvm_ipld_encoding::to_vec(&(address_map, id, network_name)).unwrap();
+        actor.state = Cid::new_v1(DAG_CBOR, Code::Blake2b256.digest(&block));
+        self.blockstore().put_keyed(&actor.state, &block).unwrap();
+        self.set_actor(&init, actor);
+    }
+
     /// Send a message between the two specified actors
     fn execute_message(
         &self,
@@ -149,6 +175,14 @@ pub fn new_actor(
     ActorState { code, state, sequence, balance, delegated_address }
 }
 
+/// A signature to be verified, with the address expected to have signed the plaintext.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SignatureVerifyInfo {
+    pub signature: Signature,
+    pub signer: Address,
+    pub plaintext: Vec<u8>,
+}
+
 /// Pure functions implemented as primitives by the runtime.
 pub trait Primitives {
     /// Hashes input data using blake2b with 256 bit output.
@@ -175,6 +209,29 @@ pub trait Primitives {
         plaintext: &[u8],
     ) -> Result<(), Error>;
 
+    /// Verifies an aggregate BLS signature over distinct plaintexts, each signed by the key at the
+    /// same index in `pub_keys`.
+    fn verify_bls_aggregate(
+        &self,
+        aggregate_sig: &[u8; BLS_SIG_LEN],
+        pub_keys: &[[u8; BLS_PUB_LEN]],
+        plaintexts: &[&[u8]],
+    ) -> Result<(), Error>;
+
+    /// Verifies a batch of signatures, returning whether each is valid for its signer and
+    /// plaintext.
+    ///
+    /// The default implementation verifies each signature in turn. Implementations may override
+    /// it with a cheaper batched verification where one is available.
+    fn verify_signatures_batch(&self, batch: &[SignatureVerifyInfo]) -> Result<Vec<bool>, Error> {
+        Ok(batch
+            .iter()
+            .map(|info| {
+                self.verify_signature(&info.signature, &info.signer, &info.plaintext).is_ok()
+            })
+            .collect())
+    }
+
     fn recover_secp_public_key(
         &self,
         hash: &[u8; SECP_SIG_MESSAGE_HASH_SIZE],
@@ -257,6 +314,12 @@ pub trait MockPrimitives: Primitives {
     /// Override the primitive verify_signature function
     fn override_verify_signature(&self, f: fn(&Signature, &Address, &[u8]) -> Result<(), Error>);
 
+    /// Override the primitive verify_bls_aggregate function
+    fn override_verify_bls_aggregate(
+        &self,
+        f: fn(&[u8; BLS_SIG_LEN], &[[u8; BLS_PUB_LEN]], &[&[u8]]) -> Result<(), Error>,
+    );
+
     /// Override the primitive verify_replica_update function
     fn override_verify_replica_update(&self, f: fn(&ReplicaUpdateInfo) -> Result<(), Error>);
 
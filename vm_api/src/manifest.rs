@@ -0,0 +1,40 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Context};
+use cid::multihash::Code;
+use cid::Cid;
+use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::CborStore;
+
+use crate::builtin::Type;
+
+/// The only bundle manifest version understood by this crate.
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// Loads the code CIDs of the builtin actors from a bundle manifest.
+/// The manifest root is a `(version, data)` pair, where `data` links to the list of
+/// `(name, code CID)` pairs of the actors in the bundle.
+/// Entries with names that aren't builtin actor types are ignored.
+pub fn load_manifest(store: &impl Blockstore, root: &Cid) -> anyhow::Result<BTreeMap<Type, Cid>> {
+    let (version, data): (u32, Cid) =
+        store.get_cbor(root)?.ok_or_else(|| anyhow!("manifest {} not found", root))?;
+    if version != MANIFEST_VERSION {
+        return Err(anyhow!("unsupported manifest version {}", version));
+    }
+    let entries: Vec<(String, Cid)> =
+        store.get_cbor(&data)?.ok_or_else(|| anyhow!("manifest data {} not found", data))?;
+    Ok(entries
+        .into_iter()
+        .filter_map(|(name, code)| Type::from_name(&name).map(|t| (t, code)))
+        .collect())
+}
+
+/// Stores a bundle manifest with the given actor code CIDs and returns its root.
+/// This is the inverse of `load_manifest`, for VMs whose actor code isn't loaded from a bundle.
+pub fn put_manifest(store: &impl Blockstore, codes: &BTreeMap<Type, Cid>) -> anyhow::Result<Cid> {
+    let entries: Vec<(String, Cid)> =
+        codes.iter().map(|(t, code)| (t.name().to_string(), *code)).collect();
+    let data =
+        store.put_cbor(&entries, Code::Blake2b256).context("failed to store manifest data")?;
+    store.put_cbor(&(MANIFEST_VERSION, data), Code::Blake2b256).context("failed to store manifest")
+}